@@ -9,9 +9,9 @@ use clap::{value_parser, Parser, Subcommand};
 use reth_chainspec::ChainSpec;
 use reth_cli::chainspec::ChainSpecParser;
 use reth_cli_commands::{
-    config_cmd, db, dump_genesis, import, init_cmd, init_state,
+    config_cmd, db, dump_genesis, dump_state, export, import, init_cmd, init_state,
     node::{self, NoArgs},
-    p2p, prune, recover, stage,
+    p2p, prune, recover, stage, static_file,
 };
 use reth_cli_runner::CliRunner;
 use reth_db::DatabaseEnv;
@@ -164,7 +164,13 @@ impl<C: ChainSpecParser<ChainSpec = ChainSpec>, Ext: clap::Args + fmt::Debug> Cl
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(
                 command.execute::<EthereumNode, _, _>(EthExecutorProvider::ethereum),
             ),
+            Commands::Export(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
+            }
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::DumpState(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
+            }
             Commands::Db(command) => {
                 runner.run_blocking_until_ctrl_c(command.execute::<EthereumNode>())
             }
@@ -182,6 +188,9 @@ impl<C: ChainSpecParser<ChainSpec = ChainSpec>, Ext: clap::Args + fmt::Debug> Cl
                 runner.run_command_until_exit(|ctx| command.execute::<EthereumNode>(ctx))
             }
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute::<EthereumNode>()),
+            Commands::StaticFile(command) => {
+                runner.run_until_ctrl_c(command.execute::<EthereumNode>())
+            }
         }
     }
 
@@ -210,8 +219,14 @@ pub enum Commands<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> {
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand<C>),
+    /// Exports blocks to RLP or ERA1 files.
+    #[command(name = "export")]
+    Export(export::ExportCommand<C>),
     /// Dumps genesis block JSON configuration to stdout.
     DumpGenesis(dump_genesis::DumpGenesisCommand<C>),
+    /// Dumps the state at a block into a state dump file.
+    #[command(name = "dump-state")]
+    DumpState(dump_state::DumpStateCommand<C>),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command<C>),
@@ -237,6 +252,9 @@ pub enum Commands<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> {
     /// Prune according to the configuration without any limits
     #[command(name = "prune")]
     Prune(prune::PruneCommand<C>),
+    /// Static file utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command<C>),
 }
 
 #[cfg(test)]
@@ -918,10 +918,13 @@ mod tests {
     use reth_errors::ProviderResult;
     use reth_primitives::{Account, Bytecode, Receipt};
     use reth_storage_api::{
-        AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
-        StorageRootProvider,
+        AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRangeProvider,
+        StateRootProvider, StorageRootProvider,
+    };
+    use reth_trie::{
+        range::{HashedAccountRange, HashedStorageRange},
+        AccountProof, HashedStorage, MultiProof, StorageProof, TrieInput,
     };
-    use reth_trie::{AccountProof, HashedStorage, MultiProof, StorageProof, TrieInput};
 
     fn create_mock_state(
         test_block_builder: &mut TestBlockBuilder,
@@ -1033,6 +1036,47 @@ mod tests {
         }
     }
 
+    impl StateRangeProvider for MockStateProvider {
+        fn account_range(
+            &self,
+            _hashed_state: HashedPostState,
+            _start: B256,
+            _limit: usize,
+            _with_storage: bool,
+        ) -> ProviderResult<HashedAccountRange> {
+            Ok(HashedAccountRange::default())
+        }
+
+        fn storage_range(
+            &self,
+            _address: Address,
+            _hashed_storage: HashedStorage,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<HashedStorageRange> {
+            Ok(HashedStorageRange::default())
+        }
+
+        fn hashed_storage_range(
+            &self,
+            _hashed_address: B256,
+            _hashed_storage: HashedStorage,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<HashedStorageRange> {
+            Ok(HashedStorageRange::default())
+        }
+
+        fn hashed_account_range(
+            &self,
+            _hashed_state: HashedPostState,
+            _start: B256,
+            _limit: usize,
+        ) -> ProviderResult<HashedAccountRange> {
+            Ok(HashedAccountRange::default())
+        }
+    }
+
     impl StateProofProvider for MockStateProvider {
         fn proof(
             &self,
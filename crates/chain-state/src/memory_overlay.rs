@@ -7,11 +7,13 @@ use alloy_primitives::{
 use reth_errors::ProviderResult;
 use reth_primitives::{Account, Bytecode};
 use reth_storage_api::{
-    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
-    StorageRootProvider,
+    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRangeProvider,
+    StateRootProvider, StorageRootProvider,
 };
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
+    range::{HashedAccountRange, HashedStorageRange},
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
 };
 use std::sync::OnceLock;
 
@@ -202,6 +204,59 @@ macro_rules! impl_state_provider {
             }
         }
 
+        impl $($tokens)* StateRangeProvider for $type {
+            fn account_range(
+                &self,
+                hashed_state: HashedPostState,
+                start: B256,
+                limit: usize,
+                with_storage: bool,
+            ) -> ProviderResult<HashedAccountRange> {
+                let mut state = self.trie_state().state.clone();
+                state.extend(hashed_state);
+                self.historical.account_range(state, start, limit, with_storage)
+            }
+
+            fn storage_range(
+                &self,
+                address: Address,
+                storage: HashedStorage,
+                start: B256,
+                limit: usize,
+            ) -> ProviderResult<HashedStorageRange> {
+                let state = &self.trie_state().state;
+                let mut hashed_storage =
+                    state.storages.get(&keccak256(address)).cloned().unwrap_or_default();
+                hashed_storage.extend(&storage);
+                self.historical.storage_range(address, hashed_storage, start, limit)
+            }
+
+            fn hashed_storage_range(
+                &self,
+                hashed_address: B256,
+                storage: HashedStorage,
+                start: B256,
+                limit: usize,
+            ) -> ProviderResult<HashedStorageRange> {
+                let state = &self.trie_state().state;
+                let mut hashed_storage =
+                    state.storages.get(&hashed_address).cloned().unwrap_or_default();
+                hashed_storage.extend(&storage);
+                self.historical.hashed_storage_range(hashed_address, hashed_storage, start, limit)
+            }
+
+            fn hashed_account_range(
+                &self,
+                hashed_state: HashedPostState,
+                start: B256,
+                limit: usize,
+            ) -> ProviderResult<HashedAccountRange> {
+                let mut state = self.trie_state().state.clone();
+                state.extend(hashed_state);
+                self.historical.hashed_account_range(state, start, limit)
+            }
+        }
+
         impl $($tokens)* StateProvider for $type {
             fn storage(
                 &self,
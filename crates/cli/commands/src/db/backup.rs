@@ -0,0 +1,31 @@
+use clap::Parser;
+use reth_db_common::backup::backup;
+use reth_provider::{providers::ProviderNodeTypes, ProviderFactory};
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db backup` command
+pub struct Command {
+    /// The directory to write the backup to. Must be empty or not exist.
+    dest: PathBuf,
+}
+
+impl Command {
+    /// Execute `db backup` command
+    pub fn execute<N: ProviderNodeTypes>(
+        self,
+        provider_factory: ProviderFactory<N>,
+    ) -> eyre::Result<()> {
+        let manifest = backup(&provider_factory, &self.dest)?;
+        info!(
+            target: "reth::cli",
+            block_number = manifest.block_number,
+            block_hash = %manifest.block_hash,
+            files = manifest.files.len(),
+            path = ?self.dest,
+            "Backup written"
+        );
+        Ok(())
+    }
+}
@@ -1,10 +1,16 @@
 use alloy_primitives::{hex, BlockHash};
 use clap::Parser;
 use reth_db::{
-    static_file::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask},
+    static_file::{
+        AccountChangeSetMask, ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask,
+        StorageChangeSetMask, TransactionMask,
+    },
     tables, RawKey, RawTable, Receipts, TableViewer, Transactions,
 };
-use reth_db_api::table::{Decompress, DupSort, Table};
+use reth_db_api::{
+    models::{AccountChangeSet, StorageChangeSet},
+    table::{Decompress, DupSort, Table},
+};
 use reth_db_common::DbTool;
 use reth_node_builder::NodeTypesWithDB;
 use reth_primitives::Header;
@@ -71,6 +77,14 @@ impl Command {
                         table_key::<tables::Receipts>(&key)?,
                         <ReceiptMask<<Receipts as Table>::Value>>::MASK,
                     ),
+                    StaticFileSegment::AccountChangeSets => (
+                        table_key::<tables::AccountChangeSets>(&key)?,
+                        <AccountChangeSetMask<AccountChangeSet>>::MASK,
+                    ),
+                    StaticFileSegment::StorageChangeSets => (
+                        table_key::<tables::AccountChangeSets>(&key)?,
+                        <StorageChangeSetMask<StorageChangeSet>>::MASK,
+                    ),
                 };
 
                 let content = tool.provider_factory.static_file_provider().find_static_file(
@@ -112,6 +126,16 @@ impl Command {
                                     )?;
                                     println!("{}", serde_json::to_string_pretty(&receipt)?);
                                 }
+                                StaticFileSegment::AccountChangeSets => {
+                                    let changeset =
+                                        AccountChangeSet::decompress(content[0].as_slice())?;
+                                    println!("{}", serde_json::to_string_pretty(&changeset)?);
+                                }
+                                StaticFileSegment::StorageChangeSets => {
+                                    let changeset =
+                                        StorageChangeSet::decompress(content[0].as_slice())?;
+                                    println!("{}", serde_json::to_string_pretty(&changeset)?);
+                                }
                             }
                         }
                     }
@@ -7,14 +7,17 @@ use reth_db_common::DbTool;
 use reth_node_builder::NodeTypesWithEngine;
 use std::io::{self, Write};
 
+mod backup;
 mod checksum;
 mod clear;
 mod diff;
 mod get;
 mod list;
+mod restore;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -47,6 +50,13 @@ pub enum Subcommands {
     },
     /// Deletes all table entries
     Clear(clear::Command),
+    /// Writes a consistent backup of the database and static files, with a manifest of checksums
+    Backup(backup::Command),
+    /// Restores a backup into the database and static files directories after validating its
+    /// manifest
+    Restore(restore::Command),
+    /// Cross-checks the consistency of the database and static files, and writes a JSON report
+    Verify(verify::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -79,8 +89,10 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             data_dir.data_dir()
         );
 
-        // ensure the provided database exist
-        eyre::ensure!(db_path.is_dir(), "Database does not exist: {:?}", db_path);
+        // ensure the provided database exist, unless it's restored from a backup
+        if !matches!(self.command, Subcommands::Restore(_)) {
+            eyre::ensure!(db_path.is_dir(), "Database does not exist: {:?}", db_path);
+        }
 
         match self.command {
             // TODO: We'll need to add this on the DB trait.
@@ -133,6 +145,18 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
                 let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RW)?;
                 command.execute(provider_factory)?;
             }
+            Subcommands::Backup(command) => {
+                let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RO)?;
+                command.execute(provider_factory)?;
+            }
+            Subcommands::Restore(command) => {
+                command.execute(&db_path, &static_files_path)?;
+            }
+            Subcommands::Verify(command) => {
+                let Environment { provider_factory, .. } =
+                    self.env.init::<N>(command.access_rights())?;
+                command.execute(provider_factory)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
@@ -0,0 +1,26 @@
+use clap::Parser;
+use reth_db_common::backup::restore;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db restore` command
+pub struct Command {
+    /// The directory of the backup, as written by `reth db backup`.
+    backup: PathBuf,
+}
+
+impl Command {
+    /// Execute `db restore` command
+    pub fn execute(self, db_path: &Path, static_files_path: &Path) -> eyre::Result<()> {
+        let manifest = restore(&self.backup, db_path, static_files_path)?;
+        info!(
+            target: "reth::cli",
+            block_number = manifest.block_number,
+            block_hash = %manifest.block_hash,
+            path = ?self.backup,
+            "Backup restored"
+        );
+        Ok(())
+    }
+}
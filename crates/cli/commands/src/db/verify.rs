@@ -0,0 +1,56 @@
+use crate::common::AccessRights;
+use clap::Parser;
+use reth_db_common::verify::{verify, VerifyCheck};
+use reth_provider::{providers::ProviderNodeTypes, ProviderFactory};
+use std::path::PathBuf;
+use tracing::info;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db verify` command
+pub struct Command {
+    /// Checks to run, all of them by default.
+    ///
+    /// Possible values: block-bodies, senders, hashed-state, state-root, history-indices
+    #[arg(long = "check", value_name = "CHECK", value_delimiter = ',')]
+    checks: Vec<VerifyCheck>,
+
+    /// Rebuilds the derived tables of the checks that found issues: transaction senders, hashed
+    /// state, trie tables and history indices.
+    #[arg(long)]
+    repair: bool,
+
+    /// Writes the JSON report to the file, instead of printing it.
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+impl Command {
+    /// Returns the access rights that the database needs to be opened with.
+    pub(crate) const fn access_rights(&self) -> AccessRights {
+        if self.repair {
+            AccessRights::RW
+        } else {
+            AccessRights::RO
+        }
+    }
+
+    /// Execute `db verify` command
+    pub fn execute<N: ProviderNodeTypes>(
+        self,
+        provider_factory: ProviderFactory<N>,
+    ) -> eyre::Result<()> {
+        let report = verify(&provider_factory, &self.checks, self.repair)?;
+
+        let json = serde_json::to_string_pretty(&report)?;
+        match self.output {
+            Some(path) => {
+                reth_fs_util::write(&path, json)?;
+                info!(target: "reth::cli", ?path, "Report written");
+            }
+            None => println!("{json}"),
+        }
+
+        eyre::ensure!(report.is_consistent(), "Database verification found issues");
+        Ok(())
+    }
+}
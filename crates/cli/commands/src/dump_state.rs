@@ -0,0 +1,84 @@
+//! Command that dumps the state at a block into a state dump file.
+
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use alloy_primitives::BlockNumber;
+use clap::Parser;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_db_common::dump::export_state_dump;
+use reth_node_builder::NodeTypesWithEngine;
+use reth_provider::BlockNumReader;
+use std::{io::BufWriter, path::PathBuf};
+use tracing::{info, warn};
+
+/// Dumps the state at a block into a JSONL state dump file, as read by `init-state`.
+#[derive(Debug, Parser)]
+pub struct DumpStateCommand<C: ChainSpecParser> {
+    #[command(flatten)]
+    pub env: EnvironmentArgs<C>,
+
+    /// The block to dump the state at.
+    ///
+    /// Defaults to the latest block. Historical state is reconstructed from changesets, so the
+    /// account and storage history of all later blocks must not be pruned.
+    #[arg(long, value_name = "BLOCK_NUMBER", verbatim_doc_comment)]
+    pub block: Option<BlockNumber>,
+
+    /// Path of the JSONL file to write the state dump to.
+    ///
+    /// The first line contains { "root": \<state-root\> } of the block, followed by one line per
+    /// account, in the format read by `init-state`.
+    #[arg(value_name = "STATE_DUMP_FILE", verbatim_doc_comment)]
+    pub output: PathBuf,
+
+    /// Compresses the state dump with zstd.
+    ///
+    /// `init-state` decompresses state dump files with a `.zst` extension.
+    #[arg(long, default_value = "false")]
+    pub zstd: bool,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> DumpStateCommand<C> {
+    /// Execute the `dump-state` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        info!(target: "reth::cli", "Reth dump-state starting");
+
+        let Environment { config, provider_factory, .. } = self.env.init::<N>(AccessRights::RO)?;
+        let provider = provider_factory.provider()?;
+
+        let block = match self.block {
+            Some(block) => block,
+            None => provider.best_block_number()?,
+        };
+
+        info!(target: "reth::cli", block, output = ?self.output, "Dumping state");
+
+        let file = reth_fs_util::create_file(&self.output)?;
+        let result = if self.zstd {
+            let mut encoder = zstd::Encoder::new(file, 0)?;
+            export_state_dump(&provider, block, BufWriter::new(&mut encoder), config.stages.etl)
+                .and_then(|dumped| {
+                    encoder.finish()?;
+                    Ok(dumped)
+                })
+        } else {
+            export_state_dump(&provider, block, BufWriter::new(file), config.stages.etl)
+        };
+
+        match result {
+            Ok(dumped) => {
+                info!(target: "reth::cli", block, dumped, "State dump written");
+                Ok(())
+            }
+            Err(err) => {
+                // don't leave an incomplete or invalid state dump behind
+                if let Err(remove_err) = reth_fs_util::remove_file(&self.output) {
+                    warn!(target: "reth::cli", %remove_err, "Failed to remove state dump file");
+                }
+                Err(err)
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Command that exports blocks to RLP or ERA1 files.
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use alloy_primitives::BlockNumber;
+use alloy_rlp::Encodable;
+use clap::{Parser, ValueEnum};
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_era::{era1_file_name, Era1Block, Era1File, Era1Writer, MAX_BLOCKS_PER_ERA1};
+use reth_node_builder::NodeTypesWithEngine;
+use reth_node_core::version::SHORT_VERSION;
+use reth_provider::{
+    BlockNumReader, BlockReader, ChainSpecProvider, HeaderProvider, ProviderError, ReceiptProvider,
+};
+use std::{
+    io::{BufWriter, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Interval of exported blocks after which progress is logged.
+const LOG_INTERVAL: u64 = 100_000;
+
+/// Exports blocks to RLP or ERA1 files.
+#[derive(Debug, Parser)]
+pub struct ExportCommand<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK_NUMBER", default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export. Defaults to the latest block.
+    #[arg(long, value_name = "BLOCK_NUMBER")]
+    to: Option<BlockNumber>,
+
+    /// The format to export blocks in.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Rlp)]
+    format: ExportFormat,
+
+    /// The path to export to.
+    ///
+    /// For RLP, this is a file of concatenated RLP encoded blocks, as read by the 'import'
+    /// command. For ERA1, this is a directory that one archive is written to per epoch of 8192
+    /// blocks.
+    #[arg(value_name = "EXPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+/// The format of exported blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Concatenated RLP encoded blocks.
+    Rlp,
+    /// ERA1 archives of pre-merge blocks with their receipts and total difficulties.
+    Era1,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ExportCommand<C> {
+    /// Execute `export` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RO)?;
+        let provider = provider_factory.provider()?;
+
+        let to = match self.to {
+            Some(to) => to,
+            None => provider.best_block_number()?,
+        };
+        if self.from > to {
+            eyre::bail!("invalid block range {}..={to}", self.from)
+        }
+
+        match self.format {
+            ExportFormat::Rlp => export_rlp(&provider, self.from..=to, &self.path)?,
+            ExportFormat::Era1 => {
+                let chain_spec = provider_factory.chain_spec();
+                if chain_spec.is_paris_active_at_block(to) == Some(true) {
+                    eyre::bail!(
+                        "ERA1 archives can only hold pre-merge blocks, block {to} is post-merge"
+                    )
+                }
+                export_era1(&provider, &chain_spec.chain().to_string(), self.from..=to, &self.path)?
+            }
+        }
+
+        info!(target: "reth::cli", from = self.from, to, path = ?self.path, "Blocks exported");
+
+        Ok(())
+    }
+}
+
+/// Writes the blocks in the range into a single file of concatenated RLP encoded blocks.
+fn export_rlp<Provider: BlockReader>(
+    provider: &Provider,
+    range: RangeInclusive<BlockNumber>,
+    path: &Path,
+) -> eyre::Result<()> {
+    let mut writer = BufWriter::new(reth_fs_util::create_file(path)?);
+    let mut buf = Vec::new();
+    for number in range {
+        let block = provider
+            .block_by_number(number)?
+            .ok_or(ProviderError::HeaderNotFound(number.into()))?;
+        buf.clear();
+        block.encode(&mut buf);
+        writer.write_all(&buf)?;
+
+        if number % LOG_INTERVAL == 0 {
+            info!(target: "reth::cli", number, "Exported blocks");
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the blocks in the range into ERA1 archives in the directory, one per epoch.
+///
+/// Each archive is written to a temporary file first, read back and verified, and renamed once
+/// its accumulator is known.
+fn export_era1<Provider>(
+    provider: &Provider,
+    network: &str,
+    range: RangeInclusive<BlockNumber>,
+    dir: &Path,
+) -> eyre::Result<()>
+where
+    Provider: BlockReader + HeaderProvider + ReceiptProvider,
+{
+    reth_fs_util::create_dir_all(dir)?;
+
+    let epoch_len = MAX_BLOCKS_PER_ERA1 as u64;
+    let (mut start, end) = range.into_inner();
+    while start <= end {
+        let epoch = start / epoch_len;
+        let epoch_end = end.min((epoch + 1) * epoch_len - 1);
+
+        let tmp_path = dir.join(format!("{network}-{epoch:05}.era1.tmp"));
+        let mut writer = Era1Writer::new(BufWriter::new(reth_fs_util::create_file(&tmp_path)?))?;
+        for number in start..=epoch_end {
+            let block = provider
+                .block_by_number(number)?
+                .ok_or(ProviderError::HeaderNotFound(number.into()))?;
+            let receipts = provider
+                .receipts_by_block(number.into())?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(number))?;
+            let total_difficulty = provider
+                .header_td_by_number(number)?
+                .ok_or(ProviderError::TotalDifficultyNotFound(number))?;
+            writer.append(&Era1Block {
+                header: block.header,
+                body: block.body,
+                receipts: receipts.into_iter().map(|receipt| receipt.with_bloom()).collect(),
+                total_difficulty,
+            })?;
+        }
+        let accumulator = writer.finish()?;
+        Era1File::open(&tmp_path)?.verify()?;
+
+        let path = dir.join(era1_file_name(network, epoch, accumulator));
+        reth_fs_util::rename(&tmp_path, &path)?;
+        info!(target: "reth::cli",
+            blocks = ?start..=epoch_end,
+            path = ?path,
+            %accumulator,
+            "Exported ERA1 archive"
+        );
+
+        start = epoch_end + 1;
+    }
+
+    Ok(())
+}
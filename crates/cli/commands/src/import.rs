@@ -15,6 +15,7 @@ use reth_downloaders::{
     file_client::{ChunkedFileReader, FileClient, DEFAULT_BYTE_LEN_CHUNK_CHAIN_FILE},
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
+use reth_era::{Era1Block, Era1File};
 use reth_evm::execute::BlockExecutorProvider;
 use reth_network_p2p::{
     bodies::downloader::BodyDownloader,
@@ -30,11 +31,14 @@ use reth_provider::{
 use reth_prune::PruneModes;
 use reth_stages::{prelude::*, Pipeline, StageId, StageSet};
 use reth_static_file::StaticFileProducer;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
 use tracing::{debug, error, info};
 
-/// Syncs RLP encoded blocks from a file.
+/// Syncs RLP encoded blocks or ERA1 archives from files.
 #[derive(Debug, Parser)]
 pub struct ImportCommand<C: ChainSpecParser> {
     #[command(flatten)]
@@ -52,6 +56,9 @@ pub struct ImportCommand<C: ChainSpecParser> {
     ///
     /// The online stages (headers and bodies) are replaced by a file import, after which the
     /// remaining stages are executed.
+    ///
+    /// Files with an `.era1` extension and directories are imported as ERA1 archives, in the
+    /// order of their file names. Archives are verified against their accumulator before import.
     #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
     path: PathBuf,
 }
@@ -82,12 +89,12 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ImportComm
         info!(target: "reth::cli", "Consensus engine initialized");
 
         // open file
-        let mut reader = ChunkedFileReader::new(&self.path, self.chunk_len).await?;
+        let mut source = ImportSource::new(&self.path, self.chunk_len).await?;
 
         let mut total_decoded_blocks = 0;
         let mut total_decoded_txns = 0;
 
-        while let Some(file_client) = reader.next_chunk::<FileClient>().await? {
+        while let Some(file_client) = source.next_chunk().await? {
             // create a new FileClient from chunk read from file
             info!(target: "reth::cli",
                 "Importing chain file chunk"
@@ -154,6 +161,56 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ImportComm
     }
 }
 
+/// The files blocks are imported from.
+#[derive(Debug)]
+enum ImportSource {
+    /// A file of concatenated RLP encoded blocks, read in chunks.
+    Rlp(ChunkedFileReader),
+    /// ERA1 archives, each read as one chunk.
+    Era1(std::vec::IntoIter<PathBuf>),
+}
+
+impl ImportSource {
+    /// Opens the file or the ERA1 archives at the given path.
+    async fn new(path: &Path, chunk_len: Option<u64>) -> eyre::Result<Self> {
+        if path.is_dir() {
+            let mut archives = reth_fs_util::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .filter(|path| path.as_ref().map_or(true, |path| is_era1(path)))
+                .collect::<Result<Vec<_>, _>>()?;
+            archives.sort_unstable();
+            info!(target: "reth::cli", archives = archives.len(), "Importing ERA1 archives");
+            Ok(Self::Era1(archives.into_iter()))
+        } else if is_era1(path) {
+            Ok(Self::Era1(vec![path.to_path_buf()].into_iter()))
+        } else {
+            Ok(Self::Rlp(ChunkedFileReader::new(path, chunk_len).await?))
+        }
+    }
+
+    /// Returns a [`FileClient`] with the blocks of the next chunk.
+    async fn next_chunk(&mut self) -> eyre::Result<Option<FileClient>> {
+        match self {
+            Self::Rlp(reader) => Ok(reader.next_chunk::<FileClient>().await?),
+            Self::Era1(archives) => {
+                let Some(path) = archives.next() else { return Ok(None) };
+                info!(target: "reth::cli", path = ?path, "Reading ERA1 archive");
+
+                let archive = Era1File::open(&path)?;
+                archive.verify()?;
+                Ok(Some(FileClient::from_blocks(
+                    archive.blocks.into_iter().map(Era1Block::into_block),
+                )))
+            }
+        }
+    }
+}
+
+/// Returns `true` if the path has an `.era1` extension.
+fn is_era1(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "era1")
+}
+
 /// Builds import pipeline.
 ///
 /// If configured to execute, all stages will run. Otherwise, only stages that don't require state
@@ -38,6 +38,8 @@ pub struct InitStateCommand<C: ChainSpecParser> {
     ///     "address": "\<address\>",
     /// }
     ///
+    /// Files with a `.zst` extension are decompressed with zstd, see 'dump-state' command.
+    ///
     /// Allows init at a non-genesis block. Caution! Blocks must be manually imported up until
     /// and including the non-genesis block to init chain at. See 'import' command.
     #[arg(value_name = "STATE_DUMP_FILE", verbatim_doc_comment)]
@@ -119,10 +121,14 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> InitStateC
 
         info!(target: "reth::cli", "Initiating state dump");
 
-        let file = File::open(self.state)?;
-        let reader = BufReader::new(file);
-
-        let hash = init_from_state_dump(reader, &provider_rw, config.stages.etl)?;
+        let file = File::open(&self.state)?;
+        let hash = if self.state.extension().is_some_and(|extension| extension == "zst") {
+            let reader = BufReader::new(zstd::Decoder::new(file)?);
+            init_from_state_dump(reader, &provider_rw, config.stages.etl)?
+        } else {
+            let reader = BufReader::new(file);
+            init_from_state_dump(reader, &provider_rw, config.stages.etl)?
+        };
 
         provider_rw.commit()?;
 
@@ -12,6 +12,8 @@ pub mod common;
 pub mod config_cmd;
 pub mod db;
 pub mod dump_genesis;
+pub mod dump_state;
+pub mod export;
 pub mod import;
 pub mod init_cmd;
 pub mod init_state;
@@ -20,6 +22,7 @@ pub mod p2p;
 pub mod prune;
 pub mod recover;
 pub mod stage;
+pub mod static_file;
 #[cfg(feature = "arbitrary")]
 pub mod test_vectors;
 
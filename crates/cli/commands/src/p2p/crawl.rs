@@ -0,0 +1,256 @@
+//! Crawl subcommand of P2P Debugging tool.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::Parser;
+use enr::Enr;
+use futures::{stream::SelectAll, StreamExt};
+use reth_chainspec::{EthChainSpec, ForkId, Hardforks, Head};
+use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config};
+use reth_discv5::{discv5, Discv5, NetworkStackId};
+use reth_network_peers::{NodeRecord, PeerId};
+use reth_node_core::args::DiscoveryArgs;
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{info, trace};
+
+/// Minimum time between two rounds of random lookups.
+const MIN_LOOKUP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A node found by the crawler.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrawledNode {
+    /// The enode record of the node.
+    pub record: NodeRecord,
+    /// The [`ForkId`] the node advertised, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_id: Option<ForkId>,
+    /// The ENR of the node, if it was discovered via discv5 or advertised a fork id via EIP-868.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enr: Option<Enr<SecretKey>>,
+}
+
+impl CrawledNode {
+    /// Reads the nodes written by `reth p2p crawl` from the given file.
+    pub fn read_from_file(path: &Path) -> eyre::Result<Vec<Self>> {
+        Ok(reth_fs_util::read_json_file(path)?)
+    }
+}
+
+/// `reth p2p crawl` command
+///
+/// Walks the discv4 and, if enabled, the discv5 network of the chain and writes all nodes with a
+/// [`ForkId`] compatible with the chain to a JSON file.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// How long to crawl for, in seconds.
+    #[arg(long, default_value_t = 60)]
+    duration: u64,
+
+    /// The file to write the crawled nodes to.
+    #[arg(long, short, value_name = "FILE", default_value = "nodes.json")]
+    output: PathBuf,
+
+    /// Also keep nodes that did not advertise a fork id.
+    ///
+    /// By default only nodes whose fork id is compatible with the chain are kept.
+    #[arg(long)]
+    include_unknown_fork: bool,
+}
+
+/// An update from one of the discovery services.
+#[derive(Debug)]
+enum CrawlUpdate {
+    Discv4(DiscoveryUpdate),
+    Discv5(discv5::Event),
+}
+
+impl Command {
+    /// Execute `p2p crawl` command
+    pub async fn execute(
+        &self,
+        chain: impl EthChainSpec + Hardforks,
+        secret_key: SecretKey,
+        discovery: &DiscoveryArgs,
+        rlpx_socket: SocketAddr,
+        boot_nodes: Vec<NodeRecord>,
+    ) -> eyre::Result<()> {
+        if discovery.disable_discovery ||
+            (discovery.disable_discv4_discovery && !discovery.enable_discv5_discovery)
+        {
+            eyre::bail!("Discovery is disabled, nothing to crawl")
+        }
+
+        // same head the network uses when it is started without a database
+        let head = Head {
+            hash: chain.genesis_hash(),
+            number: 0,
+            timestamp: chain.genesis().timestamp,
+            difficulty: chain.genesis().difficulty,
+            total_difficulty: chain.genesis().difficulty,
+        };
+        let fork_filter = chain.fork_filter(head);
+
+        let mut updates = SelectAll::new();
+
+        let mut discv4 = None;
+        if !discovery.disable_discv4_discovery {
+            let discovery_addr = SocketAddr::new(discovery.addr, discovery.port);
+            let local_enr = NodeRecord::from_secret_key(discovery_addr, &secret_key)
+                .with_tcp_port(rlpx_socket.port());
+            let config = Discv4Config::builder()
+                .add_boot_nodes(boot_nodes.clone())
+                .enable_eip868(true)
+                .build();
+            let (handle, mut service) =
+                Discv4::bind(discovery_addr, local_enr, secret_key, config).await?;
+            updates.push(service.update_stream().map(CrawlUpdate::Discv4).boxed());
+            service.spawn();
+            discv4 = Some(handle);
+        }
+
+        let mut discv5 = None;
+        if discovery.enable_discv5_discovery {
+            let mut builder = discovery.discovery_v5_builder(rlpx_socket, boot_nodes);
+            if let Some(network_stack_id) = NetworkStackId::id(&chain) {
+                builder = builder.fork(network_stack_id, chain.latest_fork_id());
+            }
+            let (handle, events, _) = Discv5::start(&secret_key, builder.build()).await?;
+            updates.push(ReceiverStream::new(events).map(CrawlUpdate::Discv5).boxed());
+            discv5 = Some(handle);
+        }
+
+        info!(target: "reth::cli", duration = self.duration, "Crawling");
+
+        let mut nodes = HashMap::<PeerId, CrawledNode>::new();
+        let crawl = async {
+            while let Some(update) = updates.next().await {
+                match update {
+                    CrawlUpdate::Discv4(update) => on_discv4_update(&mut nodes, update),
+                    CrawlUpdate::Discv5(event) => {
+                        if let Some(discv5) = &discv5 {
+                            on_discv5_event(&mut nodes, discv5, event)
+                        }
+                    }
+                }
+            }
+        };
+        // the services only look up random targets every few seconds, keep looking up new ones
+        // to walk as much of the network as possible
+        let lookups = async {
+            loop {
+                let discv4_lookup = async {
+                    if let Some(discv4) = &discv4 {
+                        if let Err(err) = discv4.lookup_random().await {
+                            trace!(target: "reth::cli", %err, "discv4 lookup failed");
+                        }
+                    }
+                };
+                let discv5_lookup = async {
+                    if let Some(discv5) = &discv5 {
+                        let lookup = discv5.with_discv5(|service| {
+                            service.find_node(discv5::enr::NodeId::random())
+                        });
+                        if let Err(err) = lookup.await {
+                            trace!(target: "reth::cli", %err, "discv5 lookup failed");
+                        }
+                    }
+                };
+                futures::join!(
+                    discv4_lookup,
+                    discv5_lookup,
+                    tokio::time::sleep(MIN_LOOKUP_INTERVAL)
+                );
+            }
+        };
+        let _ = tokio::time::timeout(Duration::from_secs(self.duration), async {
+            tokio::select! {
+                _ = crawl => {}
+                _ = lookups => {}
+            }
+        })
+        .await;
+
+        let discovered = nodes.len();
+        let mut nodes = nodes
+            .into_values()
+            .filter(|node| match node.fork_id {
+                Some(fork_id) => fork_filter.validate(fork_id).is_ok(),
+                None => self.include_unknown_fork,
+            })
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|node| node.record.id);
+
+        reth_fs_util::write_json_file(&self.output, &nodes)?;
+
+        println!(
+            "Discovered {discovered} nodes, wrote {} compatible nodes ({} with ENR) to {}",
+            nodes.len(),
+            nodes.iter().filter(|node| node.enr.is_some()).count(),
+            self.output.display()
+        );
+
+        Ok(())
+    }
+}
+
+/// Records the nodes of a discv4 update.
+fn on_discv4_update(nodes: &mut HashMap<PeerId, CrawledNode>, update: DiscoveryUpdate) {
+    match update {
+        DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
+            nodes.entry(record.id).or_insert(CrawledNode { record, fork_id: None, enr: None });
+        }
+        DiscoveryUpdate::EnrForkId(record, fork_id, enr) => {
+            let node =
+                nodes.entry(record.id).or_insert(CrawledNode { record, fork_id: None, enr: None });
+            node.fork_id = Some(fork_id);
+            node.enr = Some(enr);
+        }
+        // nodes that left the table were still seen
+        DiscoveryUpdate::Removed(_) => {}
+        DiscoveryUpdate::Batch(updates) => {
+            for update in updates {
+                on_discv4_update(nodes, update)
+            }
+        }
+    }
+}
+
+/// Records the node of a discv5 event, together with its ENR.
+fn on_discv5_event(
+    nodes: &mut HashMap<PeerId, CrawledNode>,
+    discv5: &Discv5,
+    event: discv5::Event,
+) {
+    let (enr, socket) = match event {
+        discv5::Event::SessionEstablished(enr, socket) |
+        discv5::Event::UnverifiableEnr { enr, socket, .. } => (enr, socket),
+        _ => return,
+    };
+
+    let Some(peer) = discv5.on_discovered_peer(&enr, socket) else { return };
+    let fork_id = peer.fork_id.or_else(|| discv5.get_fork_id(&enr).ok());
+    // discv5 ENRs use the same secp256k1 identity scheme, so they can be re-parsed
+    let enr = match enr.to_base64().parse::<Enr<SecretKey>>() {
+        Ok(enr) => Some(enr),
+        Err(err) => {
+            trace!(target: "reth::cli", %err, "Failed to convert discv5 ENR");
+            None
+        }
+    };
+
+    let node = nodes.entry(peer.node_record.id).or_insert(CrawledNode {
+        record: peer.node_record,
+        fork_id: None,
+        enr: None,
+    });
+    node.fork_id = fork_id.or(node.fork_id);
+    node.enr = enr.or(node.enr.take());
+}
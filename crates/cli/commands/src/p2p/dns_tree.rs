@@ -0,0 +1,89 @@
+//! DNS tree subcommand of P2P Debugging tool.
+
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::Parser;
+use reth_cli_util::get_secret_key;
+use reth_dns_discovery::{tree::LinkEntry, DnsTree};
+use secp256k1::SECP256K1;
+
+use super::crawl::CrawledNode;
+
+/// `reth p2p dns-tree` command
+///
+/// Signs the nodes found by `reth p2p crawl` as an EIP-1459 tree and writes its TXT records as a
+/// zone file.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The JSON file with the nodes written by `reth p2p crawl`.
+    #[arg(long, value_name = "FILE", default_value = "nodes.json")]
+    nodes: PathBuf,
+
+    /// The domain the tree is published under, e.g. `nodes.example.org`.
+    #[arg(long)]
+    domain: String,
+
+    /// Secret key to sign the tree with.
+    ///
+    /// If the file does not exist, a new key is generated and written to it.
+    #[arg(long, value_name = "PATH")]
+    signing_key: PathBuf,
+
+    /// Sequence number of the tree, must increase with every update of the published tree.
+    ///
+    /// Defaults to the current unix timestamp.
+    #[arg(long)]
+    seq: Option<u64>,
+
+    /// Link to another tree to include, as `enrtree://<public key>@<domain>`.
+    ///
+    /// Can be passed multiple times.
+    #[arg(long = "link", value_name = "ENRTREE")]
+    links: Vec<LinkEntry>,
+
+    /// TTL of the records, in seconds.
+    #[arg(long, default_value_t = 3600)]
+    ttl: u32,
+
+    /// The file to write the zone file to.
+    #[arg(long, short, value_name = "FILE", default_value = "nodes.zone")]
+    output: PathBuf,
+}
+
+impl Command {
+    /// Execute `p2p dns-tree` command
+    pub fn execute(&self) -> eyre::Result<()> {
+        let key = get_secret_key(&self.signing_key)?;
+        let seq = match self.seq {
+            Some(seq) => seq,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        };
+
+        // only nodes with an ENR signed by the node itself can be published
+        let nodes = CrawledNode::read_from_file(&self.nodes)?;
+        let total = nodes.len();
+        let enrs = nodes.into_iter().filter_map(|node| node.enr).collect::<Vec<_>>();
+        if enrs.len() < total {
+            println!("Skipping {} nodes without ENR", total - enrs.len());
+        }
+
+        let tree = DnsTree::new(seq, enrs, self.links.clone(), &key)?;
+        reth_fs_util::write(&self.output, tree.to_zone_file(&self.domain, self.ttl))?;
+
+        let link = LinkEntry {
+            domain: self.domain.trim_end_matches('.').to_string(),
+            pubkey: key.public_key(SECP256K1),
+        };
+        println!(
+            "Wrote {} records with sequence number {seq} to {}",
+            tree.entries().len() + 1,
+            self.output.display()
+        );
+        println!("Tree link: {link}");
+
+        Ok(())
+    }
+}
@@ -16,6 +16,8 @@ use reth_node_core::{
     utils::get_single_header,
 };
 
+mod crawl;
+mod dns_tree;
 mod rlpx;
 
 /// `reth p2p` command
@@ -71,11 +73,19 @@ pub enum Subcommands {
     },
     // RLPx utilities
     Rlpx(rlpx::Command),
+    /// Crawl the discovery network for nodes of the chain
+    Crawl(crawl::Command),
+    /// Sign crawled nodes as an EIP-1459 DNS tree and write it as a zone file
+    DnsTree(dns_tree::Command),
 }
 
 impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
     /// Execute `p2p` command
     pub async fn execute(self) -> eyre::Result<()> {
+        if let Subcommands::DnsTree(command) = &self.command {
+            return command.execute()
+        }
+
         let data_dir = self.datadir.clone().resolve_datadir(self.chain.chain());
         let config_path = self.config.clone().unwrap_or_else(|| data_dir.config());
 
@@ -97,6 +107,19 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
         let rlpx_socket = (self.network.addr, self.network.port).into();
         let boot_nodes = self.chain.bootnodes().unwrap_or_default();
 
+        // the crawler runs its own discovery services instead of a full network
+        if let Subcommands::Crawl(command) = &self.command {
+            return command
+                .execute(
+                    &self.chain,
+                    p2p_secret_key,
+                    &self.network.discovery,
+                    rlpx_socket,
+                    boot_nodes,
+                )
+                .await
+        }
+
         let net = NetworkConfigBuilder::new(p2p_secret_key)
             .peer_config(config.peers_config_with_basic_nodes_from_file(None))
             .external_ip_resolver(self.network.nat)
@@ -158,6 +181,7 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             Subcommands::Rlpx(command) => {
                 command.execute().await?;
             }
+            Subcommands::Crawl(_) | Subcommands::DnsTree(_) => unreachable!("handled above"),
         }
 
         Ok(())
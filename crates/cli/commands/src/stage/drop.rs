@@ -37,17 +37,20 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
         let tool = DbTool::new(provider_factory)?;
 
-        let static_file_segment = match self.stage {
-            StageEnum::Headers => Some(StaticFileSegment::Headers),
-            StageEnum::Bodies => Some(StaticFileSegment::Transactions),
-            StageEnum::Execution => Some(StaticFileSegment::Receipts),
-            _ => None,
+        let static_file_segments: &[StaticFileSegment] = match self.stage {
+            StageEnum::Headers => &[StaticFileSegment::Headers],
+            StageEnum::Bodies => &[StaticFileSegment::Transactions],
+            StageEnum::Execution => &[
+                StaticFileSegment::Receipts,
+                StaticFileSegment::AccountChangeSets,
+                StaticFileSegment::StorageChangeSets,
+            ],
+            _ => &[],
         };
 
         // Delete static file segment data before inserting the genesis header below
-        if let Some(static_file_segment) = static_file_segment {
-            let static_file_provider = tool.provider_factory.static_file_provider();
-            let static_files = iter_static_files(static_file_provider.directory())?;
+        let static_files = iter_static_files(static_file_provider.directory())?;
+        for &static_file_segment in static_file_segments {
             if let Some(segment_static_files) = static_files.get(&static_file_segment) {
                 // Delete static files from the highest to the lowest block range
                 for (block_range, _) in segment_static_files
@@ -101,6 +104,8 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
 
                 reset_prune_checkpoint(tx, PruneSegment::Receipts)?;
                 reset_prune_checkpoint(tx, PruneSegment::ContractLogs)?;
+                reset_prune_checkpoint(tx, PruneSegment::AccountChangeSets)?;
+                reset_prune_checkpoint(tx, PruneSegment::StorageChangeSets)?;
                 reset_stage_checkpoint(tx, StageId::Execution)?;
 
                 let alloc = &self.env.chain.genesis().alloc;
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use super::setup;
+use super::{import_account_changesets, setup};
 use alloy_primitives::BlockNumber;
 use eyre::Result;
 use reth_db::{tables, DatabaseEnv};
@@ -25,13 +25,7 @@ pub(crate) async fn dump_hashing_account_stage<N: ProviderNodeTypes>(
     let (output_db, tip_block_number) = setup(from, to, &output_datadir.db(), db_tool)?;
 
     // Import relevant AccountChangeSets
-    output_db.update(|tx| {
-        tx.import_table_with_range::<tables::AccountChangeSets, _>(
-            &db_tool.provider_factory.db_ref().tx()?,
-            Some(from),
-            to,
-        )
-    })??;
+    import_account_changesets(db_tool, from..=to, &output_db)?;
 
     unwind_and_copy(db_tool, from, tip_block_number, &output_db)?;
 
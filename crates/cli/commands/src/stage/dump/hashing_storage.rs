@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use super::setup;
+use super::{import_storage_changesets, setup};
 use eyre::Result;
 use reth_db::{tables, DatabaseEnv};
 use reth_db_api::{database::Database, table::TableImporter};
@@ -23,6 +23,7 @@ pub(crate) async fn dump_hashing_storage_stage<N: ProviderNodeTypes>(
 ) -> Result<()> {
     let (output_db, tip_block_number) = setup(from, to, &output_datadir.db(), db_tool)?;
 
+    import_storage_changesets(db_tool, from..=to, &output_db)?;
     unwind_and_copy(db_tool, from, tip_block_number, &output_db)?;
 
     if should_run {
@@ -61,11 +62,9 @@ fn unwind_and_copy<N: ProviderNodeTypes>(
     )?;
     let unwind_inner_tx = provider.into_tx();
 
-    // TODO optimize we can actually just get the entries we need for both these tables
+    // TODO optimize we can actually just get the entries we need
     output_db
         .update(|tx| tx.import_dupsort::<tables::PlainStorageState, _>(&unwind_inner_tx))??;
-    output_db
-        .update(|tx| tx.import_dupsort::<tables::StorageChangeSets, _>(&unwind_inner_tx))??;
 
     Ok(())
 }
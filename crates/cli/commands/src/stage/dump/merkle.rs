@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use super::setup;
+use super::{import_account_changesets, import_storage_changesets, setup};
 use alloy_primitives::BlockNumber;
 use eyre::Result;
 use reth_config::config::EtlConfig;
@@ -42,13 +42,8 @@ pub(crate) async fn dump_merkle_stage<N: ProviderNodeTypes>(
         )
     })??;
 
-    output_db.update(|tx| {
-        tx.import_table_with_range::<tables::AccountChangeSets, _>(
-            &db_tool.provider_factory.db_ref().tx()?,
-            Some(from),
-            to,
-        )
-    })??;
+    import_account_changesets(db_tool, from..=to, &output_db)?;
+    import_storage_changesets(db_tool, from..=to, &output_db)?;
 
     unwind_and_copy(db_tool, (from, to), tip_block_number, &output_db)?;
 
@@ -133,10 +128,6 @@ fn unwind_and_copy<N: ProviderNodeTypes>(
 
     let unwind_inner_tx = provider.into_tx();
 
-    // TODO optimize we can actually just get the entries we need
-    output_db
-        .update(|tx| tx.import_dupsort::<tables::StorageChangeSets, _>(&unwind_inner_tx))??;
-
     output_db.update(|tx| tx.import_table::<tables::HashedAccounts, _>(&unwind_inner_tx))??;
     output_db.update(|tx| tx.import_dupsort::<tables::HashedStorages, _>(&unwind_inner_tx))??;
     output_db.update(|tx| tx.import_table::<tables::AccountsTrie, _>(&unwind_inner_tx))??;
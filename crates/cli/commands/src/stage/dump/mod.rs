@@ -5,8 +5,11 @@ use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_cli::chainspec::ChainSpecParser;
 use reth_db::{init_db, mdbx::DatabaseArguments, tables, DatabaseEnv};
 use reth_db_api::{
-    cursor::DbCursorRO, database::Database, models::ClientVersion, table::TableImporter,
-    transaction::DbTx,
+    cursor::DbCursorRO,
+    database::Database,
+    models::ClientVersion,
+    table::TableImporter,
+    transaction::{DbTx, DbTxMut},
 };
 use reth_db_common::DbTool;
 use reth_evm::execute::BlockExecutorProvider;
@@ -15,7 +18,8 @@ use reth_node_core::{
     args::DatadirArgs,
     dirs::{DataDirPath, PlatformPath},
 };
-use std::{path::PathBuf, sync::Arc};
+use reth_provider::{providers::ProviderNodeTypes, ChangeSetReader, StorageChangeSetReader};
+use std::{ops::RangeInclusive, path::PathBuf, sync::Arc};
 use tracing::info;
 
 mod hashing_storage;
@@ -143,3 +147,47 @@ pub(crate) fn setup<N: NodeTypesWithDB>(
 
     Ok((output_datadir, tip_block_number))
 }
+
+/// Copies the account changesets of the block range to the output database.
+///
+/// Changesets are read through the provider, so the ones of blocks that were moved to static files
+/// are copied as well.
+pub(crate) fn import_account_changesets<N: ProviderNodeTypes>(
+    db_tool: &DbTool<N>,
+    range: RangeInclusive<u64>,
+    output_db: &DatabaseEnv,
+) -> eyre::Result<()> {
+    let provider = db_tool.provider_factory.provider()?;
+    output_db.update(|tx| {
+        for block_number in range {
+            for account in provider.account_block_changeset(block_number)? {
+                tx.put::<tables::AccountChangeSets>(block_number, account)?;
+            }
+        }
+        Ok::<_, eyre::Report>(())
+    })??;
+
+    Ok(())
+}
+
+/// Copies the storage changesets of the block range to the output database.
+///
+/// Changesets are read through the provider, so the ones of blocks that were moved to static files
+/// are copied as well.
+pub(crate) fn import_storage_changesets<N: ProviderNodeTypes>(
+    db_tool: &DbTool<N>,
+    range: RangeInclusive<u64>,
+    output_db: &DatabaseEnv,
+) -> eyre::Result<()> {
+    let provider = db_tool.provider_factory.provider()?;
+    output_db.update(|tx| {
+        for block_number in range {
+            for (key, entry) in provider.storage_changeset(block_number)? {
+                tx.put::<tables::StorageChangeSets>(key, entry)?;
+            }
+        }
+        Ok::<_, eyre::Report>(())
+    })??;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use human_bytes::human_bytes;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_cli::chainspec::ChainSpecParser;
+use reth_db::static_file::iter_static_files;
+use reth_node_builder::NodeTypesWithEngine;
+use reth_provider::{providers::DEFAULT_MAX_DICTIONARY_SIZE, StaticFileProviderFactory};
+use reth_static_file_types::{Compression, StaticFileSegment};
+use tracing::info;
+
+/// `reth static-file recompress` command
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// The segment to recompress.
+    #[arg(long, value_enum)]
+    segment: StaticFileSegment,
+
+    /// The compression to rewrite the static files with.
+    ///
+    /// To keep using it for static files that are created afterwards, set it for the segment in
+    /// the `[static_files]` section of the configuration file.
+    #[arg(long, value_enum, verbatim_doc_comment)]
+    compression: Compression,
+
+    /// Maximum size in bytes of the zstd dictionary trained per column, with `zstd-dict`.
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_DICTIONARY_SIZE)]
+    max_dict_size: usize,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C> {
+    /// Execute `static-file recompress` command
+    pub async fn execute<N: NodeTypesWithEngine<ChainSpec = C::ChainSpec>>(
+        self,
+    ) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init::<N>(AccessRights::RW)?;
+        let static_file_provider = provider_factory.static_file_provider();
+
+        let ranges = iter_static_files(static_file_provider.directory())?
+            .remove(&self.segment)
+            .unwrap_or_default();
+
+        let (mut total_before, mut total_after) = (0, 0);
+        for (block_range, _) in ranges {
+            let recompressed = static_file_provider.recompress_jar(
+                self.segment,
+                block_range.start(),
+                self.compression,
+                self.max_dict_size,
+            )?;
+            info!(target: "reth::cli",
+                segment = %self.segment,
+                blocks = %block_range,
+                rows = recompressed.rows,
+                before = human_bytes(recompressed.size_before as f64),
+                after = human_bytes(recompressed.size_after as f64),
+                "Recompressed static file"
+            );
+
+            total_before += recompressed.size_before;
+            total_after += recompressed.size_after;
+        }
+
+        info!(target: "reth::cli",
+            segment = %self.segment,
+            compression = ?self.compression,
+            before = human_bytes(total_before as f64),
+            after = human_bytes(total_after as f64),
+            difference = %format_difference(total_before, total_after),
+            "Recompressed segment"
+        );
+
+        Ok(())
+    }
+}
+
+/// Formats the size difference between `before` and `after` as a signed percentage of `before`.
+fn format_difference(before: u64, after: u64) -> String {
+    if before == 0 {
+        return "0%".to_string()
+    }
+    format!("{:+.2}%", (after as f64 - before as f64) / before as f64 * 100.0)
+}
@@ -1,7 +1,9 @@
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::B256;
+use serde::de::DeserializeOwned;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs},
+    path::Path,
     str::FromStr,
     time::Duration,
 };
@@ -37,6 +39,13 @@ pub fn hash_or_num_value_parser(value: &str) -> eyre::Result<BlockHashOrNumber,
     }
 }
 
+/// Reads the JSON file at the given path, for use as a clap value parser.
+pub fn read_json_from_file<T: DeserializeOwned>(
+    path: &str,
+) -> Result<T, reth_fs_util::FsPathError> {
+    reth_fs_util::read_json_file(Path::new(path))
+}
+
 /// Error thrown while parsing a socket address.
 #[derive(thiserror::Error, Debug)]
 pub enum SocketAddressParsingError {
@@ -4,6 +4,7 @@ use eyre::eyre;
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_prune_types::PruneModes;
 use reth_stages_types::ExecutionStageThresholds;
+use reth_static_file_types::{SegmentConfig, StaticFileSegment};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
     ffi::OsStr,
@@ -27,6 +28,8 @@ pub struct Config {
     /// Configuration for pruning.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prune: Option<PruneConfig>,
+    /// Configuration for static files.
+    pub static_files: StaticFilesConfig,
     /// Configuration for the discovery service.
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
@@ -120,6 +123,8 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Snap Sync stage configuration.
+    pub snap_sync: SnapSyncConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -373,6 +378,78 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Snap sync stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct SnapSyncConfig {
+    /// The maximum number of accounts, storage slots and bytecodes to download before committing
+    /// progress to the database.
+    pub commit_threshold: u64,
+    /// The number of account ranges to download concurrently.
+    pub concurrency: usize,
+}
+
+impl Default for SnapSyncConfig {
+    fn default() -> Self {
+        Self { commit_threshold: 1_000_000, concurrency: 16 }
+    }
+}
+
+/// Static files configuration.
+///
+/// Only applies to static files that are created afterwards. Existing ones can be recompressed
+/// with `reth static-file recompress`.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct StaticFilesConfig {
+    /// Configuration of the headers segment.
+    pub headers: SegmentConfig,
+    /// Configuration of the transactions segment.
+    pub transactions: SegmentConfig,
+    /// Configuration of the receipts segment.
+    pub receipts: SegmentConfig,
+    /// Configuration of the account changesets segment.
+    pub account_changesets: SegmentConfig,
+    /// Configuration of the storage changesets segment.
+    pub storage_changesets: SegmentConfig,
+}
+
+impl Default for StaticFilesConfig {
+    fn default() -> Self {
+        Self {
+            headers: StaticFileSegment::Headers.config(),
+            transactions: StaticFileSegment::Transactions.config(),
+            receipts: StaticFileSegment::Receipts.config(),
+            account_changesets: StaticFileSegment::AccountChangeSets.config(),
+            storage_changesets: StaticFileSegment::StorageChangeSets.config(),
+        }
+    }
+}
+
+impl StaticFilesConfig {
+    /// Returns the configuration of the segment.
+    pub const fn segment(&self, segment: StaticFileSegment) -> SegmentConfig {
+        match segment {
+            StaticFileSegment::Headers => self.headers,
+            StaticFileSegment::Transactions => self.transactions,
+            StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::AccountChangeSets => self.account_changesets,
+            StaticFileSegment::StorageChangeSets => self.storage_changesets,
+        }
+    }
+
+    /// Returns the configuration of every segment.
+    pub const fn segments(&self) -> [(StaticFileSegment, SegmentConfig); 5] {
+        [
+            (StaticFileSegment::Headers, self.headers),
+            (StaticFileSegment::Transactions, self.transactions),
+            (StaticFileSegment::Receipts, self.receipts),
+            (StaticFileSegment::AccountChangeSets, self.account_changesets),
+            (StaticFileSegment::StorageChangeSets, self.storage_changesets),
+        ]
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -393,7 +470,7 @@ impl Default for PruneConfig {
 impl PruneConfig {
     /// Returns whether there is any kind of receipt pruning configuration.
     pub fn has_receipts_pruning(&self) -> bool {
-        self.segments.receipts.is_some() || !self.segments.receipts_log_filter.is_empty()
+        self.segments.has_receipts_pruning()
     }
 
     /// Merges another `PruneConfig` into this one, taking values from the other config if and only
@@ -410,6 +487,7 @@ impl PruneConfig {
                     account_history,
                     storage_history,
                     receipts_log_filter,
+                    receipts_retention,
                 },
         } = other;
 
@@ -428,6 +506,10 @@ impl PruneConfig {
         if self.segments.receipts_log_filter.0.is_empty() && !receipts_log_filter.0.is_empty() {
             self.segments.receipts_log_filter = receipts_log_filter;
         }
+
+        if self.segments.receipts_retention.is_empty() && !receipts_retention.is_empty() {
+            self.segments.receipts_retention = receipts_retention;
+        }
     }
 }
 
@@ -455,7 +537,11 @@ mod tests {
     use crate::PruneConfig;
     use alloy_primitives::Address;
     use reth_network_peers::TrustedPeer;
-    use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig};
+    use reth_prune_types::{
+        PruneMode, PruneModes, ReceiptsLogPruneConfig, ReceiptsRetentionConfig,
+        ReceiptsRetentionRule,
+    };
+    use reth_static_file_types::{Compression, StaticFileSegment};
     use std::{collections::BTreeMap, path::Path, str::FromStr, time::Duration};
 
     fn with_tempdir(filename: &str, proc: fn(&std::path::Path)) {
@@ -947,6 +1033,7 @@ receipts = 'full'
                     Address::random(),
                     PruneMode::Full,
                 )])),
+                receipts_retention: Default::default(),
             },
         };
 
@@ -962,10 +1049,15 @@ receipts = 'full'
                     (Address::random(), PruneMode::Distance(1000)),
                     (Address::random(), PruneMode::Before(2000)),
                 ])),
+                receipts_retention: ReceiptsRetentionConfig(vec![ReceiptsRetentionRule::address(
+                    Address::random(),
+                    PruneMode::Distance(1000),
+                )]),
             },
         };
 
         let original_filter = config1.segments.receipts_log_filter.clone();
+        let retention = config2.segments.receipts_retention.clone();
         config1.merge(Some(config2));
 
         // Check that the configuration has been merged. Any configuration present in config1
@@ -977,6 +1069,52 @@ receipts = 'full'
         assert_eq!(config1.segments.account_history, Some(PruneMode::Distance(2000)));
         assert_eq!(config1.segments.storage_history, Some(PruneMode::Before(5000)));
         assert_eq!(config1.segments.receipts_log_filter, original_filter);
+        assert_eq!(config1.segments.receipts_retention, retention);
+    }
+
+    #[test]
+    fn test_receipts_retention_config() {
+        let s = r#"
+[prune.segments]
+receipts_log_filter = { "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48" = { before = 17000000 } }
+
+[[prune.segments.receipts_retention]]
+address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"
+topics = ["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"]
+mode = { distance = 100000 }
+
+[[prune.segments.receipts_retention]]
+to = "0xdac17f958d2ee523a2206206994597c13d831ec7"
+mode = { before = 18000000 }
+"#;
+        let config: Config = toml::from_str(s).unwrap();
+        let segments = config.prune.unwrap().segments;
+        assert_eq!(segments.receipts_retention.0.len(), 2);
+        assert_eq!(segments.receipts_retention.0[0].topics.len(), 1);
+        assert_eq!(
+            segments.receipts_retention.0[1].to,
+            Some(Address::from_str("0xdac17f958d2ee523a2206206994597c13d831ec7").unwrap())
+        );
+
+        // Log filter addresses are retained as rules too
+        assert_eq!(segments.receipts_retention_rules().0.len(), 3);
+    }
+
+    #[test]
+    fn test_static_files_config() {
+        let s = r"#
+[static_files.receipts]
+compression = 'zstd-dict'
+#";
+        let config: Config = toml::from_str(s).unwrap();
+        assert_eq!(
+            config.static_files.segment(StaticFileSegment::Receipts).compression,
+            Compression::ZstdWithDictionary
+        );
+        assert_eq!(
+            config.static_files.segment(StaticFileSegment::Headers),
+            StaticFileSegment::Headers.config()
+        );
     }
 
     #[test]
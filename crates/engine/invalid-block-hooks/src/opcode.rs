@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use alloy_primitives::B256;
+use alloy_rpc_types_trace::geth::GethDefaultTracingOptions;
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_engine_primitives::InvalidBlockHook;
+use reth_evm::ConfigureEvm;
+use reth_primitives::{Header, Receipt, SealedBlockWithSenders, SealedHeader};
+use reth_provider::{BlockExecutionOutput, ChainSpecProvider, StateProviderFactory};
+use reth_tracing::tracing::warn;
+use reth_trie::updates::TrieUpdates;
+
+use crate::trace::{BlockTracer, InvalidBlockTracer};
+
+/// Generates per-opcode struct logs for the transactions of the given block and saves them to a
+/// file.
+#[derive(Debug)]
+pub struct InvalidBlockOpcodeHook<P, EvmConfig> {
+    inner: InvalidBlockTracer<P, EvmConfig>,
+}
+
+impl<P, EvmConfig> InvalidBlockOpcodeHook<P, EvmConfig> {
+    /// Creates a new opcode hook.
+    pub fn new(
+        provider: P,
+        evm_config: EvmConfig,
+        output_directory: PathBuf,
+        healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+    ) -> Self {
+        Self {
+            inner: InvalidBlockTracer::new(
+                provider,
+                evm_config,
+                BlockTracer::StructLog(GethDefaultTracingOptions::default()),
+                output_directory,
+                healthy_node_client,
+            ),
+        }
+    }
+}
+
+impl<P, EvmConfig> InvalidBlockHook for InvalidBlockOpcodeHook<P, EvmConfig>
+where
+    P: StateProviderFactory
+        + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>
+        + Send
+        + Sync
+        + 'static,
+    EvmConfig: ConfigureEvm<Header = Header>,
+{
+    fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader,
+        block: &SealedBlockWithSenders,
+        _output: &BlockExecutionOutput<Receipt>,
+        _trie_updates: Option<(&TrieUpdates, B256)>,
+    ) {
+        if let Err(err) = self.inner.on_invalid_block(parent_header, block) {
+            warn!(target: "engine::invalid_block_hooks::opcode", %err, "Failed to invoke hook");
+        }
+    }
+}
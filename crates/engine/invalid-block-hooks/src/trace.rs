@@ -0,0 +1,382 @@
+use std::path::PathBuf;
+
+use alloy_primitives::U256;
+use alloy_rpc_types_trace::geth::{
+    GethDebugBuiltInTracerType, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace,
+    PreStateConfig, TraceResult,
+};
+use reth_chainspec::{EthChainSpec, EthereumHardforks};
+use reth_evm::{system_calls::SystemCaller, ConfigureEvm};
+use reth_primitives::{Header, SealedBlockWithSenders, SealedHeader};
+use reth_provider::{ChainSpecProvider, StateProviderFactory};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::CacheDB,
+    primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg},
+    DatabaseCommit,
+};
+use reth_rpc_api::DebugApiClient;
+use reth_tracing::tracing::warn;
+use revm_inspectors::tracing::{TracingInspector, TracingInspectorConfig};
+
+use crate::witness::{save_diff, save_file};
+
+/// The geth tracer used to trace the transactions of an invalid block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BlockTracer {
+    /// The built-in `prestateTracer`.
+    PreState(PreStateConfig),
+    /// The default struct log tracer, producing one entry per executed opcode.
+    StructLog(GethDefaultTracingOptions),
+}
+
+impl BlockTracer {
+    /// Returns the name of the tracer, used in the names of the output files.
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::PreState(_) => "prestate",
+            Self::StructLog(_) => "opcode",
+        }
+    }
+
+    /// Returns the `debug_traceBlock*` options that produce the same traces.
+    fn tracing_options(&self) -> GethDebugTracingOptions {
+        match self {
+            Self::PreState(config) => GethDebugTracingOptions::default()
+                .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into())
+                .with_prestate_config(*config),
+            Self::StructLog(config) => {
+                GethDebugTracingOptions { config: *config, ..Default::default() }
+            }
+        }
+    }
+
+    /// Returns the inspector config for the tracer.
+    fn inspector_config(&self) -> TracingInspectorConfig {
+        match self {
+            Self::PreState(config) => TracingInspectorConfig::from_geth_prestate_config(config),
+            Self::StructLog(config) => TracingInspectorConfig::from_geth_config(config),
+        }
+    }
+}
+
+/// Re-executes an invalid block with a geth tracer, saves the traces to a file and compares
+/// them against the traces of a healthy node.
+#[derive(Debug)]
+pub(crate) struct InvalidBlockTracer<P, EvmConfig> {
+    /// The provider to read the historical state and do the EVM execution.
+    provider: P,
+    /// The EVM configuration to use for the execution.
+    evm_config: EvmConfig,
+    /// The tracer to trace the transactions with.
+    tracer: BlockTracer,
+    /// The directory to write the traces to. Additionally, diff files will be written to this
+    /// directory in case of a mismatch against the healthy node.
+    output_directory: PathBuf,
+    /// The healthy node client to compare the traces against.
+    healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+}
+
+impl<P, EvmConfig> InvalidBlockTracer<P, EvmConfig> {
+    /// Creates a new tracer.
+    pub(crate) const fn new(
+        provider: P,
+        evm_config: EvmConfig,
+        tracer: BlockTracer,
+        output_directory: PathBuf,
+        healthy_node_client: Option<jsonrpsee::http_client::HttpClient>,
+    ) -> Self {
+        Self { provider, evm_config, tracer, output_directory, healthy_node_client }
+    }
+}
+
+impl<P, EvmConfig> InvalidBlockTracer<P, EvmConfig>
+where
+    P: StateProviderFactory
+        + ChainSpecProvider<ChainSpec: EthChainSpec + EthereumHardforks>
+        + Send
+        + Sync
+        + 'static,
+    EvmConfig: ConfigureEvm<Header = Header>,
+{
+    /// Traces the block, saves the traces to the output directory and, if a healthy node is
+    /// configured, diffs them against the traces returned by it.
+    pub(crate) fn on_invalid_block(
+        &self,
+        parent_header: &SealedHeader,
+        block: &SealedBlockWithSenders,
+    ) -> eyre::Result<()> {
+        let name = self.tracer.name();
+        let traces = self.trace_block(parent_header, block)?;
+        let re_executed_path = save_file(
+            &self.output_directory,
+            format!("{}_{}.{name}.re_executed.json", block.number, block.hash()),
+            &traces,
+        )?;
+
+        if let Some(healthy_node_client) = &self.healthy_node_client {
+            // Compare the traces against the healthy node.
+            let healthy_node_traces = futures::executor::block_on(async move {
+                DebugApiClient::debug_trace_block_by_number(
+                    healthy_node_client,
+                    block.number.into(),
+                    Some(self.tracer.tracing_options()),
+                )
+                .await
+            })?;
+
+            let healthy_path = save_file(
+                &self.output_directory,
+                format!("{}_{}.{name}.healthy.json", block.number, block.hash()),
+                &healthy_node_traces,
+            )?;
+
+            // If the traces are different, write the diff to the output directory.
+            if traces != healthy_node_traces {
+                let filename = format!("{}_{}.{name}.diff", block.number, block.hash());
+                let diff_path =
+                    save_diff(&self.output_directory, filename, &traces, &healthy_node_traces)?;
+                warn!(
+                    target: "engine::invalid_block_hooks::trace",
+                    tracer = name,
+                    diff_path = %diff_path.display(),
+                    re_executed_path = %re_executed_path.display(),
+                    healthy_path = %healthy_path.display(),
+                    "Trace mismatch against healthy node"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Re-executes all transactions of the block on top of the parent state and traces each of
+    /// them with the configured tracer.
+    fn trace_block(
+        &self,
+        parent_header: &SealedHeader,
+        block: &SealedBlockWithSenders,
+    ) -> eyre::Result<Vec<TraceResult>> {
+        // Setup database.
+        let mut db = CacheDB::new(StateProviderDatabase::new(
+            self.provider.state_by_block_hash(parent_header.hash())?,
+        ));
+
+        // Setup environment for the execution.
+        let mut cfg = CfgEnvWithHandlerCfg::new(Default::default(), Default::default());
+        let mut block_env = BlockEnv::default();
+        self.evm_config.fill_cfg_and_block_env(&mut cfg, &mut block_env, block.header(), U256::MAX);
+
+        // Apply pre-block system contract calls.
+        {
+            let mut evm = self.evm_config.evm_with_env(
+                &mut db,
+                EnvWithHandlerCfg::new_with_cfg_env(
+                    cfg.clone(),
+                    block_env.clone(),
+                    Default::default(),
+                ),
+            );
+            let mut system_caller =
+                SystemCaller::new(self.evm_config.clone(), self.provider.chain_spec());
+            system_caller.apply_pre_execution_changes(&block.clone().unseal(), &mut evm)?;
+        }
+
+        let mut traces = Vec::with_capacity(block.body.transactions.len());
+        for (sender, tx) in block.transactions_with_sender() {
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                cfg.clone(),
+                block_env.clone(),
+                self.evm_config.tx_env(tx, *sender),
+            );
+            let mut inspector = TracingInspector::new(self.tracer.inspector_config());
+            let (res, gas_limit) = {
+                let mut evm =
+                    self.evm_config.evm_with_env_and_inspector(&mut db, env, &mut inspector);
+                let res = evm.transact()?;
+                (res, evm.tx().gas_limit)
+            };
+            inspector.set_transaction_gas_limit(gas_limit);
+
+            let trace: GethTrace = match &self.tracer {
+                BlockTracer::PreState(config) => {
+                    inspector.geth_builder().geth_prestate_traces(&res, config, &db)?.into()
+                }
+                BlockTracer::StructLog(config) => inspector
+                    .geth_builder()
+                    .geth_traces(
+                        res.result.gas_used(),
+                        res.result.output().cloned().unwrap_or_default(),
+                        *config,
+                    )
+                    .into(),
+            };
+            db.commit(res.state);
+
+            traces.push(TraceResult::Success { result: trace, tx_hash: Some(tx.hash()) });
+        }
+
+        Ok(traces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{InvalidBlockOpcodeHook, InvalidBlockPreStateHook};
+    use alloy_primitives::{address, bytes, Address, Signature, TxKind, B256, U256};
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_engine_primitives::InvalidBlockHook;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        Block, BlockBody, Header, Receipt, SealedBlockWithSenders, SealedHeader, Transaction,
+        TransactionSigned, TxLegacy,
+    };
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        BlockExecutionOutput,
+    };
+    use serde_json::Value;
+    use std::{path::Path, sync::Arc};
+
+    const SENDER: Address = address!("00000000000000000000000000000000000000aa");
+    const CONTRACT: Address = address!("00000000000000000000000000000000000000bb");
+
+    /// Returns a provider with a funded sender and a contract that stores `1` at slot `0`.
+    fn provider() -> MockEthProvider {
+        let provider = MockEthProvider {
+            chain_spec: Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build()),
+            ..Default::default()
+        };
+        provider.add_account(SENDER, ExtendedAccount::new(0, U256::from(1_000_000_000_000u64)));
+        // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+        provider.add_account(
+            CONTRACT,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(bytes!("600160005500")),
+        );
+        provider
+    }
+
+    /// Returns a parent header and a block with a single call to the contract on top of it.
+    fn block() -> (SealedHeader, SealedBlockWithSenders) {
+        let parent =
+            Header { gas_limit: 30_000_000, base_fee_per_gas: Some(1), ..Default::default() };
+        let parent = SealedHeader::new(parent.clone(), parent.hash_slow());
+
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                gas_price: 10,
+                gas_limit: 100_000,
+                to: TxKind::Call(CONTRACT),
+                ..Default::default()
+            }),
+            Signature::test_signature(),
+        );
+        let header = Header {
+            parent_hash: parent.hash(),
+            number: 1,
+            timestamp: 12,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1),
+            ..Default::default()
+        };
+        let block = Block {
+            header,
+            body: BlockBody { transactions: vec![transaction], ..Default::default() },
+        }
+        .seal_slow()
+        .with_senders_unchecked(vec![SENDER]);
+
+        (parent, block)
+    }
+
+    /// Invokes the hook for the block and returns the re-executed traces it wrote.
+    fn invoke_hook(
+        hook: &dyn InvalidBlockHook,
+        output_directory: &Path,
+        name: &str,
+        parent: &SealedHeader,
+        block: &SealedBlockWithSenders,
+    ) -> Value {
+        let output = BlockExecutionOutput::<Receipt> {
+            state: Default::default(),
+            receipts: Vec::new(),
+            requests: Default::default(),
+            gas_used: 0,
+        };
+        hook.on_invalid_block(parent, block, &output, None);
+
+        // Nothing is compared without a healthy node, so no other files are written.
+        let filename = format!("{}_{}.{name}.re_executed.json", block.number, block.hash());
+        let files = std::fs::read_dir(output_directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(files, vec![filename.clone()]);
+
+        serde_json::from_str(&std::fs::read_to_string(output_directory.join(filename)).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn prestate_hook_writes_diff_traces() {
+        let provider = provider();
+        let evm_config = EthEvmConfig::new(provider.chain_spec.clone());
+        let output_directory = tempfile::tempdir().unwrap();
+        let hook = InvalidBlockPreStateHook::new(
+            provider,
+            evm_config,
+            output_directory.path().to_path_buf(),
+            None,
+        );
+
+        let (parent, block) = block();
+        let traces = invoke_hook(&hook, output_directory.path(), "prestate", &parent, &block);
+
+        let traces = traces.as_array().unwrap();
+        assert_eq!(traces.len(), 1);
+        assert_eq!(
+            traces[0]["txHash"],
+            serde_json::to_value(block.body.transactions[0].hash()).unwrap()
+        );
+
+        let contract = serde_json::to_value(CONTRACT).unwrap();
+        let slot = serde_json::to_value(B256::ZERO).unwrap();
+        let post = &traces[0]["result"]["post"][contract.as_str().unwrap()];
+        assert_eq!(
+            post["storage"][slot.as_str().unwrap()],
+            serde_json::to_value(B256::with_last_byte(1)).unwrap()
+        );
+        assert!(traces[0]["result"]["pre"]
+            [serde_json::to_value(SENDER).unwrap().as_str().unwrap()]
+        .is_object());
+    }
+
+    #[test]
+    fn opcode_hook_writes_struct_logs() {
+        let provider = provider();
+        let evm_config = EthEvmConfig::new(provider.chain_spec.clone());
+        let output_directory = tempfile::tempdir().unwrap();
+        let hook = InvalidBlockOpcodeHook::new(
+            provider,
+            evm_config,
+            output_directory.path().to_path_buf(),
+            None,
+        );
+
+        let (parent, block) = block();
+        let traces = invoke_hook(&hook, output_directory.path(), "opcode", &parent, &block);
+
+        let traces = traces.as_array().unwrap();
+        assert_eq!(traces.len(), 1);
+        let result = &traces[0]["result"];
+        assert_eq!(result["failed"], false);
+        let ops = result["structLogs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|log| log["op"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(ops, vec!["PUSH1", "PUSH1", "SSTORE", "STOP"]);
+    }
+}
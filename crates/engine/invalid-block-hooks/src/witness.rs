@@ -1,4 +1,10 @@
-use std::{collections::HashMap, fmt::Debug, fs::File, io::Write, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use alloy_primitives::{keccak256, B256, U256};
 use alloy_rpc_types_debug::ExecutionWitness;
@@ -165,7 +171,8 @@ where
             codes: Default::default(),
             keys: state_preimages,
         };
-        let re_executed_witness_path = self.save_file(
+        let re_executed_witness_path = save_file(
+            &self.output_directory,
             format!("{}_{}.witness.re_executed.json", block.number, block.hash()),
             &response,
         )?;
@@ -176,7 +183,8 @@ where
                     .await
             })?;
 
-            let healthy_path = self.save_file(
+            let healthy_path = save_file(
+                &self.output_directory,
                 format!("{}_{}.witness.healthy.json", block.number, block.hash()),
                 &healthy_node_witness,
             )?;
@@ -184,7 +192,8 @@ where
             // If the witnesses are different, write the diff to the output directory.
             if response != healthy_node_witness {
                 let filename = format!("{}_{}.witness.diff", block.number, block.hash());
-                let diff_path = self.save_diff(filename, &response, &healthy_node_witness)?;
+                let diff_path =
+                    save_diff(&self.output_directory, filename, &response, &healthy_node_witness)?;
                 warn!(
                     target: "engine::invalid_block_hooks::witness",
                     diff_path = %diff_path.display(),
@@ -212,17 +221,20 @@ where
         }
 
         if bundle_state != output.state {
-            let original_path = self.save_file(
+            let original_path = save_file(
+                &self.output_directory,
                 format!("{}_{}.bundle_state.original.json", block.number, block.hash()),
                 &output.state,
             )?;
-            let re_executed_path = self.save_file(
+            let re_executed_path = save_file(
+                &self.output_directory,
                 format!("{}_{}.bundle_state.re_executed.json", block.number, block.hash()),
                 &bundle_state,
             )?;
 
             let filename = format!("{}_{}.bundle_state.diff", block.number, block.hash());
-            let diff_path = self.save_diff(filename, &bundle_state, &output.state)?;
+            let diff_path =
+                save_diff(&self.output_directory, filename, &bundle_state, &output.state)?;
 
             warn!(
                 target: "engine::invalid_block_hooks::witness",
@@ -240,24 +252,32 @@ where
         if let Some((original_updates, original_root)) = trie_updates {
             if re_executed_root != original_root {
                 let filename = format!("{}_{}.state_root.diff", block.number, block.hash());
-                let diff_path = self.save_diff(filename, &re_executed_root, &original_root)?;
+                let diff_path =
+                    save_diff(&self.output_directory, filename, &re_executed_root, &original_root)?;
                 warn!(target: "engine::invalid_block_hooks::witness", ?original_root, ?re_executed_root, diff_path = %diff_path.display(), "State root mismatch after re-execution");
             }
 
             // If the re-executed state root does not match the _header_ state root, also log that.
             if re_executed_root != block.state_root {
                 let filename = format!("{}_{}.header_state_root.diff", block.number, block.hash());
-                let diff_path = self.save_diff(filename, &re_executed_root, &block.state_root)?;
+                let diff_path = save_diff(
+                    &self.output_directory,
+                    filename,
+                    &re_executed_root,
+                    &block.state_root,
+                )?;
                 warn!(target: "engine::invalid_block_hooks::witness", header_state_root=?block.state_root, ?re_executed_root, diff_path = %diff_path.display(), "Re-executed state root does not match block state root");
             }
 
             if &trie_output != original_updates {
                 // Trie updates are too big to diff, so we just save the original and re-executed
-                let original_path = self.save_file(
+                let original_path = save_file(
+                    &self.output_directory,
                     format!("{}_{}.trie_updates.original.json", block.number, block.hash()),
                     original_updates,
                 )?;
-                let re_executed_path = self.save_file(
+                let re_executed_path = save_file(
+                    &self.output_directory,
                     format!("{}_{}.trie_updates.re_executed.json", block.number, block.hash()),
                     &trie_output,
                 )?;
@@ -272,27 +292,6 @@ where
 
         Ok(())
     }
-
-    /// Saves the diff of two values into a file with the given name in the output directory.
-    fn save_diff<T: PartialEq + Debug>(
-        &self,
-        filename: String,
-        original: &T,
-        new: &T,
-    ) -> eyre::Result<PathBuf> {
-        let path = self.output_directory.join(filename);
-        let diff = Comparison::new(original, new);
-        File::create(&path)?.write_all(diff.to_string().as_bytes())?;
-
-        Ok(path)
-    }
-
-    fn save_file<T: Serialize>(&self, filename: String, value: &T) -> eyre::Result<PathBuf> {
-        let path = self.output_directory.join(filename);
-        File::create(&path)?.write_all(serde_json::to_string(value)?.as_bytes())?;
-
-        Ok(path)
-    }
 }
 
 impl<P, EvmConfig> InvalidBlockHook for InvalidBlockWitnessHook<P, EvmConfig>
@@ -316,3 +315,29 @@ where
         }
     }
 }
+
+/// Saves the diff of two values into a file with the given name in the output directory.
+pub(crate) fn save_diff<T: PartialEq + Debug>(
+    output_directory: &Path,
+    filename: String,
+    original: &T,
+    new: &T,
+) -> eyre::Result<PathBuf> {
+    let path = output_directory.join(filename);
+    let diff = Comparison::new(original, new);
+    File::create(&path)?.write_all(diff.to_string().as_bytes())?;
+
+    Ok(path)
+}
+
+/// Saves the value as JSON into a file with the given name in the output directory.
+pub(crate) fn save_file<T: Serialize>(
+    output_directory: &Path,
+    filename: String,
+    value: &T,
+) -> eyre::Result<PathBuf> {
+    let path = output_directory.join(filename);
+    File::create(&path)?.write_all(serde_json::to_string(value)?.as_bytes())?;
+
+    Ok(path)
+}
@@ -14,4 +14,5 @@ pub mod service;
 
 pub use miner::MiningMode;
 pub use payload::LocalPayloadAttributesBuilder;
+pub use reth_engine_primitives::LocalMinerHandle;
 pub use service::LocalEngineService;
@@ -1,27 +1,39 @@
 //! Contains the implementation of the mining mode for the local engine.
 
-use alloy_primitives::{TxHash, B256};
+use alloy_primitives::{map::HashMap, Address, TxHash, B256, U256};
 use alloy_rpc_types_engine::{CancunPayloadFields, ExecutionPayloadSidecar, ForkchoiceState};
 use eyre::OptionExt;
-use futures_util::{stream::Fuse, StreamExt};
+use futures_util::{stream::Fuse, FutureExt, StreamExt};
 use reth_beacon_consensus::BeaconEngineMessage;
 use reth_chainspec::EthereumHardforks;
-use reth_engine_primitives::{EngineApiMessageVersion, EngineTypes};
+use reth_db::tables;
+use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
+use reth_engine_primitives::{
+    DevStateDump, EngineApiMessageVersion, EngineTypes, LocalMinerHandle, MinerCommand,
+};
+use reth_evm::dev_state::{AccountOverride, DevStateOverrides};
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_payload_primitives::{
     BuiltPayload, PayloadAttributesBuilder, PayloadBuilder, PayloadKind, PayloadTypes,
 };
-use reth_provider::{BlockReader, ChainSpecProvider};
+use reth_provider::{
+    BlockNumReader, BlockReader, ChainSpecProvider, DBProvider, DatabaseProviderFactory,
+    StateReader,
+};
 use reth_rpc_types_compat::engine::payload::block_to_payload;
 use reth_transaction_pool::TransactionPool;
 use std::{
+    collections::BTreeMap,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
     time::{Duration, UNIX_EPOCH},
 };
 use tokio::{
-    sync::{mpsc::UnboundedSender, oneshot},
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
     time::Interval,
 };
 use tokio_stream::wrappers::ReceiverStream;
@@ -35,6 +47,8 @@ pub enum MiningMode {
     Instant(Fuse<ReceiverStream<TxHash>>),
     /// In this mode a block is built at a fixed interval.
     Interval(Interval),
+    /// In this mode blocks are only built when requested through the [`LocalMinerHandle`].
+    Manual,
 }
 
 impl MiningMode {
@@ -49,6 +63,11 @@ impl MiningMode {
         let start = tokio::time::Instant::now() + duration;
         Self::Interval(tokio::time::interval_at(start, duration))
     }
+
+    /// Returns true if a block is built for every new pending transaction.
+    pub const fn is_instant(&self) -> bool {
+        matches!(self, Self::Instant(_))
+    }
 }
 
 impl Future for MiningMode {
@@ -70,10 +89,22 @@ impl Future for MiningMode {
                 }
                 Poll::Pending
             }
+            Self::Manual => Poll::Pending,
         }
     }
 }
 
+/// The head of the chain recorded by [`MinerCommand::Snapshot`].
+#[derive(Debug)]
+struct Snapshot {
+    /// The number of the head block.
+    number: u64,
+    /// The latest mined blocks at the time of the snapshot.
+    last_block_hashes: Vec<B256>,
+    /// The timestamp of the head block.
+    last_timestamp: u64,
+}
+
 /// Local miner advancing the chain/
 #[derive(Debug)]
 pub struct LocalMiner<EngineT: EngineTypes, Provider, B> {
@@ -91,24 +122,51 @@ pub struct LocalMiner<EngineT: EngineTypes, Provider, B> {
     last_timestamp: u64,
     /// Stores latest mined blocks.
     last_block_hashes: Vec<B256>,
+    /// Receiver for commands from [`LocalMinerHandle`]s.
+    commands: UnboundedReceiver<MinerCommand>,
+    /// The instant mining mode while it is replaced by another mode.
+    paused_instant: Option<MiningMode>,
+    /// The timestamp of the next block, if set.
+    next_timestamp: Option<u64>,
+    /// Offset of the clock of the miner to the system time, in seconds.
+    time_offset: i64,
+    /// The number of seconds between the timestamps of consecutive blocks, if set.
+    block_timestamp_interval: Option<u64>,
+    /// The fee recipient of the mined blocks, random if unset.
+    fee_recipient: Option<Address>,
+    /// The `prev_randao` of the next block, random if unset.
+    prev_randao: Option<B256>,
+    /// Snapshots of the chain by id.
+    snapshots: BTreeMap<U256, Snapshot>,
+    /// The id of the next snapshot.
+    next_snapshot_id: U256,
+    /// State changes applied by the payload builder and the block executor.
+    dev_state_overrides: DevStateOverrides,
 }
 
 impl<EngineT, Provider, B> LocalMiner<EngineT, Provider, B>
 where
     EngineT: EngineTypes,
-    Provider: BlockReader + ChainSpecProvider<ChainSpec: EthereumHardforks> + 'static,
+    Provider: BlockReader
+        + ChainSpecProvider<ChainSpec: EthereumHardforks>
+        + DatabaseProviderFactory<Provider: BlockNumReader>
+        + StateReader
+        + Clone
+        + 'static,
     B: PayloadAttributesBuilder<<EngineT as PayloadTypes>::PayloadAttributes>,
 {
-    /// Spawns a new [`LocalMiner`] with the given parameters.
+    /// Spawns a new [`LocalMiner`] with the given parameters and returns a handle to it.
     pub fn spawn_new(
         provider: Provider,
         payload_attributes_builder: B,
         to_engine: UnboundedSender<BeaconEngineMessage<EngineT>>,
         mode: MiningMode,
         payload_builder: PayloadBuilderHandle<EngineT>,
-    ) {
+        dev_state_overrides: DevStateOverrides,
+    ) -> LocalMinerHandle {
         let latest_header =
             provider.sealed_header(provider.best_block_number().unwrap()).unwrap().unwrap();
+        let (to_miner, commands) = unbounded_channel();
 
         let miner = Self {
             provider,
@@ -118,10 +176,22 @@ where
             payload_builder,
             last_timestamp: latest_header.timestamp,
             last_block_hashes: vec![latest_header.hash()],
+            commands,
+            paused_instant: None,
+            next_timestamp: None,
+            time_offset: 0,
+            block_timestamp_interval: None,
+            fee_recipient: None,
+            prev_randao: None,
+            snapshots: BTreeMap::new(),
+            next_snapshot_id: U256::ZERO,
+            dev_state_overrides,
         };
 
         // Spawn the miner
         tokio::spawn(miner.run());
+
+        LocalMinerHandle::new(to_miner)
     }
 
     /// Runs the [`LocalMiner`] in a loop, polling the miner and building payloads.
@@ -141,10 +211,226 @@ where
                         error!(target: "engine::local", "Error updating fork choice: {:?}", e);
                     }
                 }
+                // handle requests from the miner handles
+                Some(command) = self.commands.recv() => self.on_command(command).await,
+            }
+        }
+    }
+
+    /// Handles a command sent by a [`LocalMinerHandle`].
+    async fn on_command(&mut self, command: MinerCommand) {
+        match command {
+            MinerCommand::Mine { blocks, interval, tx } => {
+                let _ = tx.send(self.mine(blocks, interval).await);
+            }
+            MinerCommand::SetAutomine(enabled, tx) => {
+                let _ = tx.send(self.set_automine(enabled));
+            }
+            MinerCommand::GetAutomine(tx) => {
+                let _ = tx.send(self.mode.is_instant());
+            }
+            MinerCommand::SetIntervalMining(interval, tx) => {
+                self.set_mode(interval.map_or(MiningMode::Manual, MiningMode::interval));
+                let _ = tx.send(());
+            }
+            MinerCommand::SetNextBlockTimestamp(timestamp, tx) => {
+                let res = if timestamp <= self.last_timestamp {
+                    Err(eyre::eyre!(
+                        "timestamp {timestamp} is not greater than the latest block timestamp {}",
+                        self.last_timestamp
+                    ))
+                } else {
+                    self.next_timestamp = Some(timestamp);
+                    Ok(())
+                };
+                let _ = tx.send(res);
+            }
+            MinerCommand::IncreaseTime(seconds, tx) => {
+                self.time_offset = self.time_offset.saturating_add_unsigned(seconds);
+                let _ = tx.send(self.time_offset);
+            }
+            MinerCommand::SetTime(timestamp, tx) => {
+                self.time_offset = (timestamp as i64).saturating_sub(Self::now() as i64);
+                let _ = tx.send(self.time_offset);
+            }
+            MinerCommand::SetBlockTimestampInterval(interval, tx) => {
+                let previous = std::mem::replace(&mut self.block_timestamp_interval, interval);
+                let _ = tx.send(previous.is_some());
+            }
+            MinerCommand::SetFeeRecipient(fee_recipient, tx) => {
+                self.fee_recipient = Some(fee_recipient);
+                let _ = tx.send(());
+            }
+            MinerCommand::SetPrevRandao(prev_randao, tx) => {
+                self.prev_randao = Some(prev_randao);
+                let _ = tx.send(());
+            }
+            MinerCommand::Snapshot(tx) => {
+                let _ = tx.send(self.snapshot());
+            }
+            MinerCommand::Revert(id, tx) => {
+                let _ = tx.send(self.revert(id).await);
+            }
+            MinerCommand::Snapshots(tx) => {
+                let snapshots = self
+                    .snapshots
+                    .iter()
+                    .map(|(id, snapshot)| {
+                        let hash = *snapshot.last_block_hashes.last().expect("at least 1 block");
+                        (*id, (snapshot.number, hash))
+                    })
+                    .collect();
+                let _ = tx.send(snapshots);
+            }
+            MinerCommand::OverrideState(accounts, tx) => {
+                let _ = tx.send(self.override_state(accounts).await);
+            }
+            MinerCommand::Reset(tx) => {
+                let _ = tx.send(self.reset().await);
+            }
+            MinerCommand::DumpState(tx) => {
+                let provider = self.provider.clone();
+                let res = tokio::task::spawn_blocking(move || dump_state(&provider))
+                    .await
+                    .unwrap_or_else(|err| Err(err.into()));
+                let _ = tx.send(res);
             }
         }
     }
 
+    /// Replaces the mining mode, keeping the instant mode around so it can be restored later.
+    fn set_mode(&mut self, mode: MiningMode) {
+        let previous = std::mem::replace(&mut self.mode, mode);
+        if previous.is_instant() {
+            self.paused_instant = Some(previous);
+        }
+    }
+
+    /// Switches between the instant mode and manual mining.
+    fn set_automine(&mut self, enabled: bool) -> eyre::Result<()> {
+        if enabled == self.mode.is_instant() {
+            return Ok(())
+        }
+        if !enabled {
+            self.set_mode(MiningMode::Manual);
+            return Ok(())
+        }
+
+        let Some(mut instant) = self.paused_instant.take() else {
+            eyre::bail!("the miner was not started in instant mining mode")
+        };
+        // skip the transactions that arrived while the instant mode was paused
+        if let MiningMode::Instant(rx) = &mut instant {
+            while let Some(Some(_)) = rx.next().now_or_never() {}
+        }
+        self.mode = instant;
+        Ok(())
+    }
+
+    /// Mines the given number of blocks and returns their hashes.
+    async fn mine(&mut self, blocks: u64, interval: Option<u64>) -> eyre::Result<Vec<B256>> {
+        let mut hashes = Vec::new();
+        for i in 0..blocks {
+            if let Some(interval) = interval.filter(|_| i > 0) {
+                self.next_timestamp = Some(self.last_timestamp + interval);
+            }
+            self.advance().await?;
+            hashes.push(*self.last_block_hashes.last().expect("at least 1 block exists"));
+        }
+        Ok(hashes)
+    }
+
+    /// Records the current head and returns the id of the snapshot.
+    fn snapshot(&mut self) -> U256 {
+        let number = self.provider.best_block_number().unwrap_or_default();
+        let id = self.next_snapshot_id;
+        self.next_snapshot_id += U256::from(1);
+        self.snapshots.insert(
+            id,
+            Snapshot {
+                number,
+                last_block_hashes: self.last_block_hashes.clone(),
+                last_timestamp: self.last_timestamp,
+            },
+        );
+        id
+    }
+
+    /// Reverts the chain to the snapshot with the given id by making its head the canonical head.
+    async fn revert(&mut self, id: U256) -> eyre::Result<bool> {
+        let Some(snapshot) = self.snapshots.remove(&id) else { return Ok(false) };
+        // snapshots taken after this one are invalidated by the revert
+        self.snapshots.retain(|snapshot_id, _| *snapshot_id < id);
+
+        self.set_head(snapshot.number, snapshot.last_block_hashes, snapshot.last_timestamp).await?;
+        Ok(true)
+    }
+
+    /// Reverts the chain to the genesis block and restores the initial settings of the miner.
+    async fn reset(&mut self) -> eyre::Result<()> {
+        let genesis = self.provider.sealed_header(0)?.ok_or_eyre("missing genesis header")?;
+
+        self.snapshots.clear();
+        self.next_timestamp = None;
+        self.time_offset = 0;
+        self.block_timestamp_interval = None;
+        self.fee_recipient = None;
+        self.prev_randao = None;
+        self.dev_state_overrides.clear();
+
+        self.set_head(0, vec![genesis.hash()], genesis.timestamp).await
+    }
+
+    /// Makes the given earlier block the canonical head, removing all blocks after it.
+    async fn set_head(
+        &mut self,
+        number: u64,
+        last_block_hashes: Vec<B256>,
+        last_timestamp: u64,
+    ) -> eyre::Result<()> {
+        self.last_block_hashes = last_block_hashes;
+        self.last_timestamp = last_timestamp;
+        self.update_forkchoice_state().await?;
+
+        // the engine removes the reverted blocks that are already persisted in the background
+        while self.provider.block_hash(number + 1)?.is_some() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        Ok(())
+    }
+
+    /// Queues the given state changes and mines a block that applies them.
+    async fn override_state(
+        &mut self,
+        accounts: HashMap<Address, AccountOverride>,
+    ) -> eyre::Result<B256> {
+        for (address, account) in accounts {
+            self.dev_state_overrides.queue(address, account);
+        }
+        self.advance().await?;
+        Ok(*self.last_block_hashes.last().expect("at least 1 block exists"))
+    }
+
+    /// Returns the current system time in seconds.
+    fn now() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("cannot be earlier than UNIX_EPOCH")
+            .as_secs()
+    }
+
+    /// Returns the timestamp of the next block.
+    fn next_block_timestamp(&mut self) -> u64 {
+        if let Some(timestamp) = self.next_timestamp.take() {
+            return timestamp
+        }
+        if let Some(interval) = self.block_timestamp_interval {
+            return self.last_timestamp + interval
+        }
+        std::cmp::max(self.last_timestamp + 1, Self::now().saturating_add_signed(self.time_offset))
+    }
+
     /// Returns current forkchoice state.
     fn forkchoice_state(&self) -> ForkchoiceState {
         ForkchoiceState {
@@ -178,21 +464,24 @@ where
         Ok(())
     }
 
-    /// Generates payload attributes for a new block, passes them to FCU and inserts built payload
-    /// through newPayload.
+    /// Generates payload attributes for a new block, passes them to FCU, inserts built payload
+    /// through newPayload and makes it canonical.
     async fn advance(&mut self) -> eyre::Result<()> {
-        let timestamp = std::cmp::max(
-            self.last_timestamp + 1,
-            std::time::SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("cannot be earlier than UNIX_EPOCH")
-                .as_secs(),
+        // the queued state changes are applied on top of the current head
+        self.dev_state_overrides
+            .seal(*self.last_block_hashes.last().expect("at least 1 block exists"));
+
+        let timestamp = self.next_block_timestamp();
+        let payload_attrs = self.payload_attributes_builder.build_with(
+            timestamp,
+            self.fee_recipient,
+            self.prev_randao.take(),
         );
 
         let (tx, rx) = oneshot::channel();
         self.to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
             state: self.forkchoice_state(),
-            payload_attrs: Some(self.payload_attributes_builder.build(timestamp)),
+            payload_attrs: Some(payload_attrs),
             tx,
             version: EngineApiMessageVersion::default(),
         })?;
@@ -244,6 +533,84 @@ where
                 self.last_block_hashes.split_off(self.last_block_hashes.len() - 64);
         }
 
-        Ok(())
+        self.update_forkchoice_state().await
+    }
+}
+
+/// Reads the state of the chain at the best block.
+///
+/// The state of the latest persisted block is read from the database and the changes of the blocks
+/// that are only in memory are applied on top of it.
+fn dump_state<Provider>(provider: &Provider) -> eyre::Result<DevStateDump>
+where
+    Provider: BlockNumReader + DatabaseProviderFactory<Provider: BlockNumReader> + StateReader,
+{
+    let db = provider.database_provider_ro()?;
+    let persisted_block_number = db.best_block_number()?;
+    let block_number = provider.best_block_number()?;
+    let tx = db.tx_ref();
+
+    let mut accounts = BTreeMap::<Address, AccountOverride>::new();
+    for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+        let (address, account) = entry?;
+        let code = match account.bytecode_hash {
+            Some(code_hash) => {
+                tx.get::<tables::Bytecodes>(code_hash)?.map(|code| code.original_bytes())
+            }
+            None => None,
+        };
+        accounts.insert(
+            address,
+            AccountOverride {
+                balance: Some(account.balance),
+                nonce: Some(account.nonce),
+                code: Some(code.unwrap_or_default()),
+                storage: HashMap::default(),
+            },
+        );
     }
+    for entry in tx.cursor_dup_read::<tables::PlainStorageState>()?.walk(None)? {
+        let (address, slot) = entry?;
+        if let Some(account) = accounts.get_mut(&address) {
+            account.storage.insert(slot.key, slot.value);
+        }
+    }
+
+    for number in persisted_block_number + 1..=block_number {
+        let outcome = provider
+            .get_state(number)?
+            .ok_or_else(|| eyre::eyre!("missing state of block {number}"))?;
+        let contracts = outcome.bundle.contracts;
+        for (address, bundle_account) in outcome.bundle.state {
+            let destroyed = bundle_account.was_destroyed();
+            let Some(info) = bundle_account.info else {
+                accounts.remove(&address);
+                continue
+            };
+            let code = match info.code.or_else(|| contracts.get(&info.code_hash).cloned()) {
+                Some(code) => code.original_bytes(),
+                None => tx
+                    .get::<tables::Bytecodes>(info.code_hash)?
+                    .map(|code| code.original_bytes())
+                    .unwrap_or_default(),
+            };
+
+            let account = accounts.entry(address).or_default();
+            if destroyed {
+                account.storage.clear();
+            }
+            account.balance = Some(info.balance);
+            account.nonce = Some(info.nonce);
+            account.code = Some(code);
+            for (slot, value) in bundle_account.storage {
+                account.storage.insert(slot.into(), value.present_value);
+            }
+        }
+    }
+
+    for account in accounts.values_mut() {
+        account.storage.retain(|_, value| !value.is_zero());
+    }
+
+    Ok(DevStateDump { block_number, accounts })
 }
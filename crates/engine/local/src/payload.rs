@@ -26,11 +26,16 @@ impl<ChainSpec> PayloadAttributesBuilder<EthPayloadAttributes>
 where
     ChainSpec: Send + Sync + EthereumHardforks + 'static,
 {
-    fn build(&self, timestamp: u64) -> EthPayloadAttributes {
+    fn build_with(
+        &self,
+        timestamp: u64,
+        suggested_fee_recipient: Option<Address>,
+        prev_randao: Option<B256>,
+    ) -> EthPayloadAttributes {
         EthPayloadAttributes {
             timestamp,
-            prev_randao: B256::random(),
-            suggested_fee_recipient: Address::random(),
+            prev_randao: prev_randao.unwrap_or_else(B256::random),
+            suggested_fee_recipient: suggested_fee_recipient.unwrap_or_else(Address::random),
             withdrawals: self
                 .chain_spec
                 .is_shanghai_active_at_timestamp(timestamp)
@@ -49,9 +54,14 @@ impl<ChainSpec> PayloadAttributesBuilder<op_alloy_rpc_types_engine::OpPayloadAtt
 where
     ChainSpec: Send + Sync + EthereumHardforks + 'static,
 {
-    fn build(&self, timestamp: u64) -> op_alloy_rpc_types_engine::OpPayloadAttributes {
+    fn build_with(
+        &self,
+        timestamp: u64,
+        suggested_fee_recipient: Option<Address>,
+        prev_randao: Option<B256>,
+    ) -> op_alloy_rpc_types_engine::OpPayloadAttributes {
         op_alloy_rpc_types_engine::OpPayloadAttributes {
-            payload_attributes: self.build(timestamp),
+            payload_attributes: self.build_with(timestamp, suggested_fee_recipient, prev_randao),
             transactions: None,
             no_tx_pool: None,
             gas_limit: None,
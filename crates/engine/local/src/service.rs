@@ -14,7 +14,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use crate::miner::{LocalMiner, MiningMode};
+use crate::miner::{LocalMiner, LocalMinerHandle, MiningMode};
 use futures_util::{Stream, StreamExt};
 use reth_beacon_consensus::{BeaconConsensusEngineEvent, BeaconEngineMessage, EngineNodeTypes};
 use reth_chainspec::EthChainSpec;
@@ -27,9 +27,9 @@ use reth_engine_tree::{
         RequestHandlerEvent,
     },
     persistence::PersistenceHandle,
-    tree::{EngineApiTreeHandler, InvalidBlockHook, TreeConfig},
+    tree::{BadBlockStore, EngineApiTreeHandler, InvalidBlockHook, TreeConfig},
 };
-use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::{dev_state::DevStateOverrides, execute::BlockExecutorProvider};
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_payload_primitives::{PayloadAttributesBuilder, PayloadTypes};
 use reth_payload_validator::ExecutionPayloadValidator;
@@ -54,6 +54,8 @@ where
     handler: EngineApiRequestHandler<EngineApiRequest<N::Engine>>,
     /// Receiver for incoming requests (from the engine API endpoint) that need to be processed.
     incoming_requests: EngineMessageStream<N::Engine>,
+    /// Handle to the miner that drives the chain forward.
+    miner: LocalMinerHandle,
 }
 
 impl<N> LocalEngineService<N>
@@ -71,11 +73,13 @@ where
         payload_builder: PayloadBuilderHandle<N::Engine>,
         tree_config: TreeConfig,
         invalid_block_hook: Box<dyn InvalidBlockHook>,
+        bad_blocks: BadBlockStore,
         sync_metrics_tx: MetricEventsSender,
         to_engine: UnboundedSender<BeaconEngineMessage<N::Engine>>,
         from_engine: EngineMessageStream<N::Engine>,
         mode: MiningMode,
         payload_attributes_builder: B,
+        dev_state_overrides: DevStateOverrides,
     ) -> Self
     where
         B: PayloadAttributesBuilder<<N::Engine as PayloadTypes>::PayloadAttributes>,
@@ -88,6 +92,9 @@ where
             PersistenceHandle::spawn_service(provider, pruner, sync_metrics_tx);
         let payload_validator = ExecutionPayloadValidator::new(chain_spec);
 
+        // the miner reverts the chain by moving the head back to an earlier block
+        let tree_config = tree_config.with_unwind_canonical_header(true);
+
         let canonical_in_memory_state = blockchain_db.canonical_in_memory_state();
 
         let (to_tree_tx, from_tree) = EngineApiTreeHandler::spawn_new(
@@ -100,20 +107,28 @@ where
             canonical_in_memory_state,
             tree_config,
             invalid_block_hook,
+            bad_blocks,
             engine_kind,
         );
 
         let handler = EngineApiRequestHandler::new(to_tree_tx, from_tree);
 
-        LocalMiner::spawn_new(
+        let miner = LocalMiner::spawn_new(
             blockchain_db,
             payload_attributes_builder,
             to_engine,
             mode,
             payload_builder,
+            dev_state_overrides,
         );
 
-        Self { handler, incoming_requests: from_engine }
+        Self { handler, incoming_requests: from_engine, miner }
+    }
+
+    /// Returns a handle to the miner of the service, which can be used to control block
+    /// production.
+    pub fn miner(&self) -> LocalMinerHandle {
+        self.miner.clone()
     }
 }
 
@@ -0,0 +1,247 @@
+use alloy_primitives::B256;
+use parking_lot::RwLock;
+use reth_fs_util::{self as fs, FsPathError};
+use reth_primitives::SealedBlock;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread::JoinHandle,
+};
+use tracing::warn;
+
+/// The default number of bad blocks kept by the [`BadBlockStore`].
+pub const DEFAULT_BAD_BLOCK_LIMIT: usize = 10;
+
+/// A block that was rejected by the engine.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BadBlock {
+    /// The rejected block, including its body.
+    pub block: SealedBlock,
+    /// The reason the block was rejected.
+    pub reason: String,
+}
+
+/// A bounded store of the most recent blocks rejected by the engine.
+///
+/// If the store is backed by a file, the bad blocks are written to it on a background thread after
+/// every insertion, so that they survive restarts without blocking the caller on I/O.
+///
+/// This type is cheap to clone, all clones share the same underlying store.
+#[derive(Debug, Clone, Default)]
+pub struct BadBlockStore {
+    inner: Arc<RwLock<BadBlockStoreInner>>,
+    /// Writes the bad blocks to the file backing the store, if any.
+    writer: Option<Arc<BadBlockWriter>>,
+}
+
+impl BadBlockStore {
+    /// Creates a new in-memory store that keeps at most `limit` bad blocks.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(BadBlockStoreInner {
+                blocks: VecDeque::with_capacity(limit),
+                limit,
+            })),
+            writer: None,
+        }
+    }
+
+    /// Opens a store that keeps at most `limit` bad blocks and persists them to the given file.
+    ///
+    /// Bad blocks that were previously written to the file are loaded.
+    pub fn open(path: impl Into<PathBuf>, limit: usize) -> Result<Self, FsPathError> {
+        let path = path.into();
+        let mut blocks = if path.exists() {
+            fs::read_json_file::<VecDeque<BadBlock>>(&path)?
+        } else {
+            VecDeque::new()
+        };
+        while blocks.len() > limit {
+            blocks.pop_front();
+        }
+
+        Ok(Self {
+            inner: Arc::new(RwLock::new(BadBlockStoreInner { blocks, limit })),
+            writer: Some(Arc::new(BadBlockWriter::spawn(path))),
+        })
+    }
+
+    /// Returns the file the bad blocks are persisted to, if any.
+    pub fn path(&self) -> Option<PathBuf> {
+        self.writer.as_ref().map(|writer| writer.path.clone())
+    }
+
+    /// Records a rejected block, evicting the oldest one if the store is full.
+    ///
+    /// Blocks that are already in the store are ignored.
+    pub fn insert(&self, block: SealedBlock, reason: String) {
+        let mut inner = self.inner.write();
+        if inner.limit == 0 || inner.blocks.iter().any(|bad| bad.block.hash() == block.hash()) {
+            return
+        }
+
+        while inner.blocks.len() >= inner.limit {
+            inner.blocks.pop_front();
+        }
+        inner.blocks.push_back(BadBlock { block, reason });
+
+        // Snapshots are queued while holding the lock, so the writer receives them in order.
+        if let Some(writer) = &self.writer {
+            writer.write(inner.blocks.clone());
+        }
+    }
+
+    /// Returns the bad block with the given hash, if it is in the store.
+    pub fn get(&self, hash: &B256) -> Option<BadBlock> {
+        self.inner.read().blocks.iter().find(|bad| bad.block.hash() == *hash).cloned()
+    }
+
+    /// Returns all bad blocks in the store, most recently rejected first.
+    pub fn blocks(&self) -> Vec<BadBlock> {
+        self.inner.read().blocks.iter().rev().cloned().collect()
+    }
+
+    /// Returns the number of bad blocks in the store.
+    pub fn len(&self) -> usize {
+        self.inner.read().blocks.len()
+    }
+
+    /// Returns `true` if the store contains no bad blocks.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().blocks.is_empty()
+    }
+}
+
+#[derive(Debug)]
+struct BadBlockStoreInner {
+    /// The bad blocks, oldest first.
+    blocks: VecDeque<BadBlock>,
+    /// The maximum number of bad blocks to keep.
+    limit: usize,
+}
+
+impl Default for BadBlockStoreInner {
+    fn default() -> Self {
+        Self { blocks: VecDeque::new(), limit: DEFAULT_BAD_BLOCK_LIMIT }
+    }
+}
+
+/// Writes snapshots of the bad blocks to a file on a dedicated thread.
+///
+/// Only the most recent snapshot is written if several are queued. Dropping the writer waits for
+/// the queued snapshots to be written.
+#[derive(Debug)]
+struct BadBlockWriter {
+    /// The file to persist the bad blocks to.
+    path: PathBuf,
+    /// Sends snapshots to the writer thread.
+    sender: Option<mpsc::Sender<VecDeque<BadBlock>>>,
+    /// Handle of the writer thread.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl BadBlockWriter {
+    /// Spawns the writer thread for the given file.
+    fn spawn(path: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel::<VecDeque<BadBlock>>();
+        let thread_path = path.clone();
+        let handle = std::thread::Builder::new()
+            .name("bad-blocks-writer".to_string())
+            .spawn(move || {
+                while let Ok(mut blocks) = receiver.recv() {
+                    // skip outdated snapshots
+                    while let Ok(next) = receiver.try_recv() {
+                        blocks = next;
+                    }
+                    if let Err(err) = write_bad_blocks(&thread_path, &blocks) {
+                        warn!(target: "engine::bad_blocks", %err, "Failed to persist bad blocks");
+                    }
+                }
+            })
+            .unwrap();
+
+        Self { path, sender: Some(sender), handle: Some(handle) }
+    }
+
+    /// Queues a snapshot of the bad blocks to be written.
+    fn write(&self, blocks: VecDeque<BadBlock>) {
+        if let Some(sender) = &self.sender {
+            // the writer thread only exits once the sender is dropped
+            let _ = sender.send(blocks);
+        }
+    }
+}
+
+impl Drop for BadBlockWriter {
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Atomically writes the bad blocks to the given file.
+fn write_bad_blocks(path: &Path, blocks: &VecDeque<BadBlock>) -> Result<(), FsPathError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::atomic_write_file(path, |file| serde_json::to_writer(file, blocks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Block, Header};
+
+    fn block(number: u64) -> SealedBlock {
+        Block { header: Header { number, ..Default::default() }, ..Default::default() }.seal_slow()
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let store = BadBlockStore::new(2);
+        for number in 0..3 {
+            store.insert(block(number), format!("bad {number}"));
+        }
+
+        let blocks = store.blocks();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].block.number, 2);
+        assert_eq!(blocks[1].block.number, 1);
+        assert!(store.get(&block(0).hash()).is_none());
+        assert_eq!(store.get(&block(1).hash()).unwrap().reason, "bad 1");
+    }
+
+    #[test]
+    fn ignores_duplicates() {
+        let store = BadBlockStore::new(2);
+        store.insert(block(1), "first".to_string());
+        store.insert(block(1), "second".to_string());
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get(&block(1).hash()).unwrap().reason, "first");
+    }
+
+    #[test]
+    fn persists_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad_blocks.json");
+
+        let store = BadBlockStore::open(&path, 3).unwrap();
+        for number in 0..3 {
+            store.insert(block(number), format!("bad {number}"));
+        }
+        drop(store);
+
+        // reopening with a smaller limit only keeps the most recent blocks
+        let store = BadBlockStore::open(&path, 2).unwrap();
+        let blocks = store.blocks();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].block.number, 2);
+        assert_eq!(blocks[0].reason, "bad 2");
+        assert_eq!(blocks[1].block.number, 1);
+    }
+}
@@ -8,9 +8,15 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod bad_blocks;
+pub use bad_blocks::{BadBlock, BadBlockStore, DEFAULT_BAD_BLOCK_LIMIT};
+
 mod invalid_block_hook;
 pub use invalid_block_hook::InvalidBlockHook;
 
+mod local_miner;
+pub use local_miner::{DevStateDump, LocalMinerHandle, MinerCommand};
+
 pub use reth_payload_primitives::{
     BuiltPayload, EngineApiMessageVersion, EngineObjectValidationError, PayloadOrAttributes,
     PayloadTypes,
@@ -0,0 +1,188 @@
+//! Handle to the local miner of a dev chain.
+
+use alloy_primitives::{map::HashMap, Address, B256, U256};
+use reth_evm::dev_state::AccountOverride;
+use std::{collections::BTreeMap, time::Duration};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// A command sent to the local miner of a dev chain by a [`LocalMinerHandle`].
+#[derive(Debug)]
+pub enum MinerCommand {
+    /// Mines the given number of blocks, with timestamps `interval` seconds apart if set.
+    Mine {
+        /// The number of blocks to mine.
+        blocks: u64,
+        /// The number of seconds between the timestamps of the mined blocks.
+        interval: Option<u64>,
+        /// Returns the hashes of the mined blocks.
+        tx: oneshot::Sender<eyre::Result<Vec<B256>>>,
+    },
+    /// Enables or disables mining a block for every new pending transaction.
+    SetAutomine(bool, oneshot::Sender<eyre::Result<()>>),
+    /// Returns whether a block is mined for every new pending transaction.
+    GetAutomine(oneshot::Sender<bool>),
+    /// Mines blocks at the given interval, or disables interval mining if `None`.
+    SetIntervalMining(Option<Duration>, oneshot::Sender<()>),
+    /// Sets the timestamp of the next block.
+    SetNextBlockTimestamp(u64, oneshot::Sender<eyre::Result<()>>),
+    /// Moves the clock of the miner forward by the given number of seconds and returns the total
+    /// offset of the clock.
+    IncreaseTime(u64, oneshot::Sender<i64>),
+    /// Sets the clock of the miner to the given timestamp and returns the total offset of the
+    /// clock.
+    SetTime(u64, oneshot::Sender<i64>),
+    /// Sets the number of seconds between the timestamps of consecutive blocks, or restores the
+    /// wall clock timestamps if `None`. Returns whether an interval was set before.
+    SetBlockTimestampInterval(Option<u64>, oneshot::Sender<bool>),
+    /// Sets the fee recipient of all following blocks.
+    SetFeeRecipient(Address, oneshot::Sender<()>),
+    /// Sets the `prev_randao` of the next block.
+    SetPrevRandao(B256, oneshot::Sender<()>),
+    /// Records the current head and returns the id of the snapshot.
+    Snapshot(oneshot::Sender<U256>),
+    /// Reverts the chain to the snapshot with the given id, returns false if there is no such
+    /// snapshot.
+    Revert(U256, oneshot::Sender<eyre::Result<bool>>),
+    /// Returns the block number and hash of all snapshots.
+    Snapshots(oneshot::Sender<BTreeMap<U256, (u64, B256)>>),
+    /// Applies the given changes to the state of the accounts in a new block and returns its hash.
+    OverrideState(HashMap<Address, AccountOverride>, oneshot::Sender<eyre::Result<B256>>),
+    /// Reverts the chain to the genesis block and discards all snapshots and settings of the
+    /// miner.
+    Reset(oneshot::Sender<eyre::Result<()>>),
+    /// Returns the state of the chain at the best block.
+    DumpState(oneshot::Sender<eyre::Result<DevStateDump>>),
+}
+
+/// The state of a dev chain at a block, returned by [`LocalMinerHandle::dump_state`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DevStateDump {
+    /// The number of the block.
+    pub block_number: u64,
+    /// All accounts of the state, with all of their fields and non-zero storage slots set.
+    pub accounts: BTreeMap<Address, AccountOverride>,
+}
+
+/// A handle to the running local miner of a dev chain, used to control block production.
+#[derive(Debug, Clone)]
+pub struct LocalMinerHandle {
+    to_miner: UnboundedSender<MinerCommand>,
+}
+
+impl LocalMinerHandle {
+    /// Creates a new handle that sends commands to the miner over the given channel.
+    pub const fn new(to_miner: UnboundedSender<MinerCommand>) -> Self {
+        Self { to_miner }
+    }
+
+    /// Sends a command to the miner and waits for the response.
+    async fn request<T>(
+        &self,
+        command: impl FnOnce(oneshot::Sender<T>) -> MinerCommand,
+    ) -> eyre::Result<T> {
+        let (tx, rx) = oneshot::channel();
+        self.to_miner.send(command(tx)).map_err(|_| eyre::eyre!("local miner is not running"))?;
+        rx.await.map_err(|_| eyre::eyre!("local miner is not running"))
+    }
+
+    /// Mines the given number of blocks, regardless of the mining mode, and returns their hashes.
+    ///
+    /// If `interval` is set, the timestamps of the blocks are `interval` seconds apart.
+    pub async fn mine(&self, blocks: u64, interval: Option<u64>) -> eyre::Result<Vec<B256>> {
+        self.request(|tx| MinerCommand::Mine { blocks, interval, tx }).await?
+    }
+
+    /// Enables or disables mining a block for every new pending transaction.
+    pub async fn set_automine(&self, enabled: bool) -> eyre::Result<()> {
+        self.request(|tx| MinerCommand::SetAutomine(enabled, tx)).await?
+    }
+
+    /// Returns whether a block is mined for every new pending transaction.
+    pub async fn automine(&self) -> eyre::Result<bool> {
+        self.request(MinerCommand::GetAutomine).await
+    }
+
+    /// Mines blocks at the given interval, or disables interval mining if `None`.
+    pub async fn set_interval_mining(&self, interval: Option<Duration>) -> eyre::Result<()> {
+        self.request(|tx| MinerCommand::SetIntervalMining(interval, tx)).await
+    }
+
+    /// Sets the timestamp of the next block, which must be greater than the timestamp of the
+    /// latest block.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> eyre::Result<()> {
+        self.request(|tx| MinerCommand::SetNextBlockTimestamp(timestamp, tx)).await?
+    }
+
+    /// Moves the clock forward by the given number of seconds and returns the total offset of the
+    /// clock.
+    pub async fn increase_time(&self, seconds: u64) -> eyre::Result<i64> {
+        self.request(|tx| MinerCommand::IncreaseTime(seconds, tx)).await
+    }
+
+    /// Sets the clock to the given timestamp and returns the total offset of the clock.
+    pub async fn set_time(&self, timestamp: u64) -> eyre::Result<i64> {
+        self.request(|tx| MinerCommand::SetTime(timestamp, tx)).await
+    }
+
+    /// Sets the number of seconds between the timestamps of consecutive blocks, or restores the
+    /// wall clock timestamps if `None`. Returns whether an interval was set before.
+    pub async fn set_block_timestamp_interval(&self, interval: Option<u64>) -> eyre::Result<bool> {
+        self.request(|tx| MinerCommand::SetBlockTimestampInterval(interval, tx)).await
+    }
+
+    /// Sets the fee recipient of all following blocks.
+    pub async fn set_fee_recipient(&self, fee_recipient: Address) -> eyre::Result<()> {
+        self.request(|tx| MinerCommand::SetFeeRecipient(fee_recipient, tx)).await
+    }
+
+    /// Sets the `prev_randao` of the next block.
+    pub async fn set_prev_randao(&self, prev_randao: B256) -> eyre::Result<()> {
+        self.request(|tx| MinerCommand::SetPrevRandao(prev_randao, tx)).await
+    }
+
+    /// Records the current head and returns the id of the snapshot.
+    pub async fn snapshot(&self) -> eyre::Result<U256> {
+        self.request(MinerCommand::Snapshot).await
+    }
+
+    /// Reverts the chain to the snapshot with the given id, discarding it and all snapshots taken
+    /// after it.
+    ///
+    /// This makes the snapshot head the canonical head without mining a block, all blocks mined
+    /// after the snapshot are removed.
+    ///
+    /// Returns false if there is no snapshot with the given id.
+    pub async fn revert(&self, id: U256) -> eyre::Result<bool> {
+        self.request(|tx| MinerCommand::Revert(id, tx)).await?
+    }
+
+    /// Returns the block number and hash of all snapshots, by id.
+    pub async fn snapshots(&self) -> eyre::Result<BTreeMap<U256, (u64, B256)>> {
+        self.request(MinerCommand::Snapshots).await
+    }
+
+    /// Applies the given changes to the state of the accounts and returns the hash of the block
+    /// that contains them.
+    ///
+    /// The state of a dev chain only changes through blocks, so this mines a new block, regardless
+    /// of the mining mode, that applies the changes before its transactions.
+    pub async fn override_state(
+        &self,
+        accounts: HashMap<Address, AccountOverride>,
+    ) -> eyre::Result<B256> {
+        self.request(|tx| MinerCommand::OverrideState(accounts, tx)).await?
+    }
+
+    /// Reverts the chain to the genesis block, removing all mined blocks.
+    ///
+    /// This also discards all snapshots and pending state changes, and restores the default
+    /// timestamps, fee recipient and `prev_randao` of new blocks. The mining mode is kept.
+    pub async fn reset(&self) -> eyre::Result<()> {
+        self.request(MinerCommand::Reset).await?
+    }
+
+    /// Returns the state of the chain at the best block.
+    pub async fn dump_state(&self) -> eyre::Result<DevStateDump> {
+        self.request(MinerCommand::DumpState).await?
+    }
+}
@@ -8,7 +8,7 @@ use reth_engine_tree::{
     download::BasicBlockDownloader,
     engine::{EngineApiKind, EngineApiRequest, EngineApiRequestHandler, EngineHandler},
     persistence::PersistenceHandle,
-    tree::{EngineApiTreeHandler, InvalidBlockHook, TreeConfig},
+    tree::{BadBlockStore, EngineApiTreeHandler, InvalidBlockHook, TreeConfig},
 };
 pub use reth_engine_tree::{
     chain::{ChainEvent, ChainOrchestrator},
@@ -78,6 +78,7 @@ where
         payload_builder: PayloadBuilderHandle<N::Engine>,
         tree_config: TreeConfig,
         invalid_block_hook: Box<dyn InvalidBlockHook>,
+        bad_blocks: BadBlockStore,
         sync_metrics_tx: MetricEventsSender,
     ) -> Self {
         let engine_kind =
@@ -101,6 +102,7 @@ where
             canonical_in_memory_state,
             tree_config,
             invalid_block_hook,
+            bad_blocks,
             engine_kind,
         );
 
@@ -204,6 +206,7 @@ mod tests {
             PayloadBuilderHandle::new(tx),
             TreeConfig::default(),
             Box::new(NoopInvalidBlockHook::default()),
+            BadBlockStore::default(),
             sync_metrics_tx,
         );
     }
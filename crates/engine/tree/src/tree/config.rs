@@ -32,6 +32,11 @@ pub struct TreeConfig {
     /// This is used as a cutoff to prevent long-running sequential block execution when we receive
     /// a batch of downloaded blocks.
     max_execute_block_batch_size: usize,
+    /// Whether a forkchoice update whose head is an ancestor of the canonical head makes it the
+    /// new canonical head, reverting all blocks above it.
+    ///
+    /// This is used by dev chains to revert to a snapshot.
+    unwind_canonical_header: bool,
 }
 
 impl Default for TreeConfig {
@@ -42,6 +47,7 @@ impl Default for TreeConfig {
             block_buffer_limit: DEFAULT_BLOCK_BUFFER_LIMIT,
             max_invalid_header_cache_length: DEFAULT_MAX_INVALID_HEADER_CACHE_LENGTH,
             max_execute_block_batch_size: DEFAULT_MAX_EXECUTE_BLOCK_BATCH_SIZE,
+            unwind_canonical_header: false,
         }
     }
 }
@@ -61,6 +67,7 @@ impl TreeConfig {
             block_buffer_limit,
             max_invalid_header_cache_length,
             max_execute_block_batch_size,
+            unwind_canonical_header: false,
         }
     }
 
@@ -89,6 +96,11 @@ impl TreeConfig {
         self.max_execute_block_batch_size
     }
 
+    /// Return whether a forkchoice update to an ancestor of the canonical head unwinds the head.
+    pub const fn unwind_canonical_header(&self) -> bool {
+        self.unwind_canonical_header
+    }
+
     /// Setter for persistence threshold.
     pub const fn with_persistence_threshold(mut self, persistence_threshold: u64) -> Self {
         self.persistence_threshold = persistence_threshold;
@@ -127,4 +139,11 @@ impl TreeConfig {
         self.max_execute_block_batch_size = max_execute_block_batch_size;
         self
     }
+
+    /// Setter for whether a forkchoice update to an ancestor of the canonical head unwinds the
+    /// head.
+    pub const fn with_unwind_canonical_header(mut self, unwind_canonical_header: bool) -> Self {
+        self.unwind_canonical_header = unwind_canonical_header;
+        self
+    }
 }
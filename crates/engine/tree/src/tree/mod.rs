@@ -26,7 +26,7 @@ use reth_chain_state::{
 };
 use reth_chainspec::EthereumHardforks;
 use reth_consensus::{Consensus, PostExecutionInput};
-use reth_engine_primitives::{EngineApiMessageVersion, EngineTypes};
+use reth_engine_primitives::{BadBlockStore, EngineApiMessageVersion, EngineTypes};
 use reth_errors::{ConsensusError, ProviderResult};
 use reth_evm::execute::BlockExecutorProvider;
 use reth_payload_builder::PayloadBuilderHandle;
@@ -36,9 +36,9 @@ use reth_primitives::{
     Block, GotExpected, Header, SealedBlock, SealedBlockWithSenders, SealedHeader,
 };
 use reth_provider::{
-    providers::ConsistentDbView, BlockReader, DatabaseProviderFactory, ExecutionOutcome,
-    ProviderError, StateProviderBox, StateProviderFactory, StateReader, StateRootProvider,
-    TransactionVariant,
+    providers::ConsistentDbView, BlockReader, ChangeSetReader, DatabaseProviderFactory,
+    ExecutionOutcome, ProviderError, StateProviderBox, StateProviderFactory, StateReader,
+    StateRootProvider, StorageChangeSetReader, TransactionVariant,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_stages_api::ControlFlow;
@@ -73,7 +73,7 @@ use crate::{
 pub use config::TreeConfig;
 pub use invalid_block_hook::{InvalidBlockHooks, NoopInvalidBlockHook};
 pub use persistence_state::PersistenceState;
-pub use reth_engine_primitives::InvalidBlockHook;
+pub use reth_engine_primitives::{BadBlockStore, InvalidBlockHook};
 
 /// Keeps track of the state of the tree.
 ///
@@ -502,6 +502,8 @@ pub struct EngineApiTreeHandler<P, E, T: EngineTypes, Spec> {
     metrics: EngineApiMetrics,
     /// An invalid block hook.
     invalid_block_hook: Box<dyn InvalidBlockHook>,
+    /// Store of the most recent blocks that were rejected as invalid.
+    bad_blocks: BadBlockStore,
     /// The engine API variant of this handler
     engine_kind: EngineApiKind,
 }
@@ -525,6 +527,7 @@ impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug> std::fmt::Debug
             .field("config", &self.config)
             .field("metrics", &self.metrics)
             .field("invalid_block_hook", &format!("{:p}", self.invalid_block_hook))
+            .field("bad_blocks", &self.bad_blocks)
             .field("engine_kind", &self.engine_kind)
             .finish()
     }
@@ -533,7 +536,8 @@ impl<P: Debug, E: Debug, T: EngineTypes + Debug, Spec: Debug> std::fmt::Debug
 impl<P, E, T, Spec> EngineApiTreeHandler<P, E, T, Spec>
 where
     P: DatabaseProviderFactory + BlockReader + StateProviderFactory + StateReader + Clone + 'static,
-    <P as DatabaseProviderFactory>::Provider: BlockReader,
+    <P as DatabaseProviderFactory>::Provider:
+        BlockReader + ChangeSetReader + StorageChangeSetReader,
     E: BlockExecutorProvider,
     T: EngineTypes,
     Spec: Send + Sync + EthereumHardforks + 'static,
@@ -573,6 +577,7 @@ where
             metrics: Default::default(),
             incoming_tx,
             invalid_block_hook: Box::new(NoopInvalidBlockHook),
+            bad_blocks: BadBlockStore::default(),
             engine_kind,
         }
     }
@@ -582,6 +587,11 @@ where
         self.invalid_block_hook = invalid_block_hook;
     }
 
+    /// Sets the store that invalid blocks are recorded in.
+    fn set_bad_blocks(&mut self, bad_blocks: BadBlockStore) {
+        self.bad_blocks = bad_blocks;
+    }
+
     /// Creates a new [`EngineApiTreeHandler`] instance and spawns it in its
     /// own thread.
     ///
@@ -598,6 +608,7 @@ where
         canonical_in_memory_state: CanonicalInMemoryState,
         config: TreeConfig,
         invalid_block_hook: Box<dyn InvalidBlockHook>,
+        bad_blocks: BadBlockStore,
         kind: EngineApiKind,
     ) -> (Sender<FromEngine<EngineApiRequest<T>>>, UnboundedReceiver<EngineApiEvent>) {
         let best_block_number = provider.best_block_number().unwrap_or(0);
@@ -631,6 +642,7 @@ where
             kind,
         );
         task.set_invalid_block_hook(invalid_block_hook);
+        task.set_bad_blocks(bad_blocks);
         let incoming = task.incoming_tx.clone();
         std::thread::Builder::new().name("Tree Task".to_string()).spawn(|| task.run()).unwrap();
         (incoming, outgoing)
@@ -1050,6 +1062,27 @@ where
         if let Ok(Some(canonical_header)) = self.find_canonical_header(state.head_block_hash) {
             debug!(target: "engine::tree", head = canonical_header.number, "fcu head block is already canonical");
 
+            // Dev chains move the head back to an ancestor to revert to a snapshot.
+            if self.config.unwind_canonical_header() &&
+                canonical_header.number < self.state.tree_state.canonical_block_number()
+            {
+                self.unwind_canonical_head(canonical_header.clone())?;
+
+                // update the safe and finalized blocks and ensure their values are valid
+                if let Err(outcome) = self.ensure_consistent_forkchoice_state(state) {
+                    // safe or finalized hashes are invalid
+                    return Ok(TreeOutcome::new(outcome))
+                }
+
+                if let Some(attr) = attrs {
+                    let updated =
+                        self.process_payload_attributes(attr, &canonical_header, state, version);
+                    return Ok(TreeOutcome::new(updated))
+                }
+
+                return Ok(valid_outcome(state.head_block_hash))
+            }
+
             // For OpStack the proposers are allowed to reorg their own chain at will, so we need to
             // always trigger a new payload job if requested.
             if self.engine_kind.is_opstack() {
@@ -1990,6 +2023,81 @@ where
         ));
     }
 
+    /// Makes the given ancestor of the canonical head the new canonical head, reverting all blocks
+    /// above it.
+    ///
+    /// The reverted blocks that are already persisted are removed from disk by the persistence
+    /// task.
+    fn unwind_canonical_head(&mut self, new_head: SealedHeader) -> ProviderResult<()> {
+        let start = Instant::now();
+        debug!(target: "engine::tree", from = self.state.tree_state.canonical_block_number(), to = new_head.number, "unwinding canonical head");
+
+        // collect the reverted blocks, from the oldest to the current head
+        let mut old = Vec::new();
+        let mut current = *self.state.tree_state.canonical_head();
+        while current.number > new_head.number {
+            let block = self.canonical_executed_block(current.hash)?;
+            current = BlockNumHash::new(block.block.number - 1, block.block.parent_hash);
+            old.push(block);
+        }
+        old.reverse();
+        let head = self.canonical_executed_block(new_head.hash())?;
+
+        // remove the reverted blocks from disk if they are persisted
+        self.persistence_state.schedule_removal(new_head.number);
+
+        // update the tracked canonical head
+        self.state.tree_state.set_canonical_head(new_head.num_hash());
+        self.update_reorg_metrics(old.len());
+
+        let notification =
+            NewCanonicalChain::Reorg { new: vec![head], old: old.clone() }.to_chain_notification();
+
+        // update the tracked in-memory state, the new head is either in memory already or persisted
+        self.canonical_in_memory_state
+            .update_chain(NewCanonicalChain::Reorg { new: Vec::new(), old });
+        self.canonical_in_memory_state.set_canonical_head(new_head.clone());
+
+        // Update metrics based on new tip
+        self.metrics.tree.canonical_chain_height.set(new_head.number as f64);
+
+        // sends an event to all active listeners about the new canonical chain
+        self.canonical_in_memory_state.notify_canon_state(notification);
+
+        // emit event
+        self.emit_event(BeaconConsensusEngineEvent::CanonicalChainCommitted(
+            Box::new(new_head),
+            start.elapsed(),
+        ));
+
+        Ok(())
+    }
+
+    /// Return the canonical [`ExecutedBlock`] with the given hash.
+    ///
+    /// Unlike [`Self::executed_block_by_hash`] this also reads blocks from the database whose trie
+    /// updates are no longer available, in which case the returned trie updates are empty.
+    fn canonical_executed_block(&self, hash: B256) -> ProviderResult<ExecutedBlock> {
+        if let Some(block) = self.executed_block_by_hash(hash)? {
+            return Ok(block)
+        }
+
+        let SealedBlockWithSenders { block, senders } = self
+            .provider
+            .sealed_block_with_senders(hash.into(), TransactionVariant::WithHash)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(hash.into()))?;
+        let execution_output = self.provider.get_state(block.number)?.unwrap_or_default();
+        let hashed_state = execution_output.hash_state_slow();
+
+        Ok(ExecutedBlock {
+            block: Arc::new(block),
+            senders: Arc::new(senders),
+            trie: Arc::new(TrieUpdates::default()),
+            execution_output: Arc::new(execution_output),
+            hashed_state: Arc::new(hashed_state),
+        })
+    }
+
     /// This updates metrics based on the given reorg length.
     fn update_reorg_metrics(&self, old_chain_length: usize) {
         self.metrics.tree.reorgs.increment(1);
@@ -2347,6 +2455,9 @@ where
             self.latest_valid_hash_for_invalid_payload(block.parent_hash)?
         };
 
+        // keep track of the invalid block, so that it can be inspected later
+        self.bad_blocks.insert(block.clone(), validation_err.to_string());
+
         // keep track of the invalid header
         self.state.invalid_headers.insert(block.header);
         Ok(PayloadStatus::new(
@@ -0,0 +1,92 @@
+//! The epoch accumulator of an ERA1 archive.
+//!
+//! The accumulator is the SSZ hash tree root of `List[HeaderRecord, 8192]`, where each header
+//! record is a container of the block hash and the total difficulty at that block.
+
+use crate::MAX_BLOCKS_PER_ERA1;
+use alloy_primitives::{B256, U256};
+use sha2::{Digest, Sha256};
+
+/// Depth of the merkle tree over the header records, `log2(MAX_BLOCKS_PER_ERA1)`.
+const DEPTH: usize = MAX_BLOCKS_PER_ERA1.trailing_zeros() as usize;
+
+/// The accumulator entry of a single block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// Total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ hash tree root of the record.
+    pub fn tree_hash_root(&self) -> B256 {
+        hash_pair(self.block_hash, B256::from(self.total_difficulty.to_le_bytes::<32>()))
+    }
+}
+
+/// Computes the accumulator root of the given header records.
+///
+/// # Panics
+///
+/// If there are more than [`MAX_BLOCKS_PER_ERA1`] records.
+pub fn accumulator_root(records: &[HeaderRecord]) -> B256 {
+    assert!(records.len() <= MAX_BLOCKS_PER_ERA1, "too many header records");
+
+    let mut zero_hashes = [B256::ZERO; DEPTH + 1];
+    for depth in 0..DEPTH {
+        zero_hashes[depth + 1] = hash_pair(zero_hashes[depth], zero_hashes[depth]);
+    }
+
+    let mut layer = records.iter().map(HeaderRecord::tree_hash_root).collect::<Vec<_>>();
+    for zero_hash in &zero_hashes[..DEPTH] {
+        if layer.len() % 2 == 1 {
+            layer.push(*zero_hash);
+        }
+        layer = layer.chunks_exact(2).map(|pair| hash_pair(pair[0], pair[1])).collect();
+    }
+    let root = layer.first().copied().unwrap_or(zero_hashes[DEPTH]);
+
+    // mix in the length of the list
+    hash_pair(root, B256::from(U256::from(records.len()).to_le_bytes::<32>()))
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_accumulator() {
+        // the root of an empty list is the zero subtree root mixed with a zero length
+        let mut root = B256::ZERO;
+        for _ in 0..DEPTH {
+            root = hash_pair(root, root);
+        }
+        assert_eq!(accumulator_root(&[]), hash_pair(root, B256::ZERO));
+    }
+
+    #[test]
+    fn single_record_accumulator() {
+        let record =
+            HeaderRecord { block_hash: B256::repeat_byte(1), total_difficulty: U256::from(2) };
+
+        let mut root = record.tree_hash_root();
+        let mut zero = B256::ZERO;
+        for _ in 0..DEPTH {
+            root = hash_pair(root, zero);
+            zero = hash_pair(zero, zero);
+        }
+        assert_eq!(
+            accumulator_root(&[record]),
+            hash_pair(root, B256::from(U256::from(1).to_le_bytes::<32>()))
+        );
+    }
+}
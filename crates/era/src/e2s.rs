@@ -0,0 +1,112 @@
+//! The e2store format, a sequence of type-length-value entries.
+//!
+//! Each entry starts with an 8 byte header: the entry type as `u16`, the data length as `u32` and
+//! two reserved zero bytes, all little endian, followed by the entry data.
+
+use crate::Era1Error;
+use std::io::Write;
+
+/// Length of an entry header.
+pub const HEADER_LEN: usize = 8;
+
+/// A single e2store entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The type of the entry.
+    pub entry_type: u16,
+    /// The entry data.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Creates a new entry.
+    pub const fn new(entry_type: u16, data: Vec<u8>) -> Self {
+        Self { entry_type, data }
+    }
+
+    /// Returns the encoded length of the entry, including the header.
+    pub fn encoded_len(&self) -> usize {
+        HEADER_LEN + self.data.len()
+    }
+}
+
+/// Writes e2store entries, keeping track of the position in the output.
+#[derive(Debug)]
+pub struct E2sWriter<W> {
+    writer: W,
+    position: u64,
+}
+
+impl<W: Write> E2sWriter<W> {
+    /// Creates a new writer that starts at position zero.
+    pub const fn new(writer: W) -> Self {
+        Self { writer, position: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Writes an entry and returns the position it was written at.
+    pub fn write_entry(&mut self, entry: &Entry) -> Result<u64, Era1Error> {
+        let length = u32::try_from(entry.data.len()).map_err(|_| Era1Error::EntryTooLarge)?;
+        let mut header = [0u8; HEADER_LEN];
+        header[..2].copy_from_slice(&entry.entry_type.to_le_bytes());
+        header[2..6].copy_from_slice(&length.to_le_bytes());
+        self.writer.write_all(&header)?;
+        self.writer.write_all(&entry.data)?;
+
+        let position = self.position;
+        self.position += entry.encoded_len() as u64;
+        Ok(position)
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn into_inner(mut self) -> Result<W, Era1Error> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads e2store entries from a buffer.
+#[derive(Debug)]
+pub struct E2sReader<'a> {
+    buf: &'a [u8],
+    position: usize,
+}
+
+impl<'a> E2sReader<'a> {
+    /// Creates a new reader over the buffer.
+    pub const fn new(buf: &'a [u8]) -> Self {
+        Self { buf, position: 0 }
+    }
+
+    /// Returns the position of the next entry in the buffer.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Reads the next entry, or returns `None` if the buffer is exhausted.
+    pub fn read_entry(&mut self) -> Result<Option<Entry>, Era1Error> {
+        let remaining = &self.buf[self.position..];
+        if remaining.is_empty() {
+            return Ok(None)
+        }
+        if remaining.len() < HEADER_LEN {
+            return Err(Era1Error::TruncatedEntry)
+        }
+
+        let entry_type = u16::from_le_bytes([remaining[0], remaining[1]]);
+        let length =
+            u32::from_le_bytes([remaining[2], remaining[3], remaining[4], remaining[5]]) as usize;
+        if remaining[6..HEADER_LEN] != [0, 0] {
+            return Err(Era1Error::InvalidEntryHeader)
+        }
+        let data =
+            remaining.get(HEADER_LEN..HEADER_LEN + length).ok_or(Era1Error::TruncatedEntry)?;
+
+        self.position += HEADER_LEN + length;
+        Ok(Some(Entry::new(entry_type, data.to_vec())))
+    }
+}
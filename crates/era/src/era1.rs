@@ -0,0 +1,376 @@
+//! Reading and writing of ERA1 archives.
+//!
+//! An ERA1 archive is an e2store file with the following layout:
+//!
+//! ```text
+//! era1        := Version | block-tuple* | Accumulator | BlockIndex
+//! block-tuple := CompressedHeader | CompressedBody | CompressedReceipts | TotalDifficulty
+//! ```
+//!
+//! Headers, bodies and receipts are snappy framed RLP, the total difficulty is a little endian
+//! `u256`. The block index holds the number of the first block, the offset of each block tuple
+//! relative to the start of the index entry and the number of blocks.
+
+use crate::{
+    accumulator::{accumulator_root, HeaderRecord},
+    e2s::{E2sReader, E2sWriter, Entry},
+    Era1Error,
+};
+use alloy_primitives::{BlockNumber, B256, U256};
+use alloy_rlp::{Decodable, Encodable};
+use reth_primitives::{
+    proofs::calculate_receipt_root, Block, BlockBody, GotExpected, Header, ReceiptWithBloom,
+};
+use std::{
+    io::{Read, Write},
+    path::Path,
+};
+
+/// The maximum number of blocks in an ERA1 archive.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// Entry type of the version entry.
+pub const VERSION: u16 = 0x3265;
+/// Entry type of a snappy compressed header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+/// Entry type of a snappy compressed body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+/// Entry type of snappy compressed receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// Entry type of a total difficulty.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+/// Entry type of the accumulator.
+pub const ACCUMULATOR: u16 = 0x07;
+/// Entry type of the block index.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// Returns the name of the ERA1 archive of the given epoch, e.g.
+/// `mainnet-00000-5ec1ffb8.era1`.
+pub fn era1_file_name(network: &str, epoch: u64, accumulator: B256) -> String {
+    format!("{network}-{epoch:05}-{}.era1", alloy_primitives::hex::encode(&accumulator[..4]))
+}
+
+/// A block of an ERA1 archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The block header.
+    pub header: Header,
+    /// The block body.
+    pub body: BlockBody,
+    /// The receipts of the block's transactions.
+    pub receipts: Vec<ReceiptWithBloom>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    /// Returns the block without receipts and total difficulty.
+    pub fn into_block(self) -> Block {
+        self.body.into_block(self.header)
+    }
+}
+
+/// Writes blocks into an ERA1 archive.
+#[derive(Debug)]
+pub struct Era1Writer<W> {
+    writer: E2sWriter<W>,
+    offsets: Vec<u64>,
+    records: Vec<HeaderRecord>,
+    start: Option<BlockNumber>,
+}
+
+impl<W: Write> Era1Writer<W> {
+    /// Creates a new writer and writes the version entry.
+    pub fn new(writer: W) -> Result<Self, Era1Error> {
+        let mut writer = E2sWriter::new(writer);
+        writer.write_entry(&Entry::new(VERSION, Vec::new()))?;
+        Ok(Self { writer, offsets: Vec::new(), records: Vec::new(), start: None })
+    }
+
+    /// Returns the number of blocks written so far.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if no blocks have been written.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Appends the next block to the archive.
+    ///
+    /// Blocks must be appended in ascending order without gaps.
+    pub fn append(&mut self, block: &Era1Block) -> Result<(), Era1Error> {
+        if self.records.len() == MAX_BLOCKS_PER_ERA1 {
+            return Err(Era1Error::Full)
+        }
+        let expected = self.start.map_or(block.header.number, |start| start + self.len() as u64);
+        if block.header.number != expected {
+            return Err(Era1Error::NonContiguous { expected, got: block.header.number })
+        }
+        self.start.get_or_insert(block.header.number);
+
+        let offset =
+            self.writer.write_entry(&Entry::new(COMPRESSED_HEADER, compress(&block.header)?))?;
+        self.writer.write_entry(&Entry::new(COMPRESSED_BODY, compress(&block.body)?))?;
+        self.writer.write_entry(&Entry::new(COMPRESSED_RECEIPTS, compress(&block.receipts)?))?;
+        self.writer.write_entry(&Entry::new(
+            TOTAL_DIFFICULTY,
+            block.total_difficulty.to_le_bytes::<32>().to_vec(),
+        ))?;
+
+        self.offsets.push(offset);
+        self.records.push(HeaderRecord {
+            block_hash: block.header.hash_slow(),
+            total_difficulty: block.total_difficulty,
+        });
+        Ok(())
+    }
+
+    /// Writes the accumulator and the block index, and returns the accumulator root.
+    pub fn finish(self) -> Result<B256, Era1Error> {
+        let Self { mut writer, offsets, records, start } = self;
+
+        let accumulator = accumulator_root(&records);
+        writer.write_entry(&Entry::new(ACCUMULATOR, accumulator.to_vec()))?;
+
+        let index_position = writer.position();
+        let mut index = Vec::with_capacity(16 + offsets.len() * 8);
+        index.extend_from_slice(&start.unwrap_or_default().to_le_bytes());
+        for offset in &offsets {
+            let relative = *offset as i64 - index_position as i64;
+            index.extend_from_slice(&relative.to_le_bytes());
+        }
+        index.extend_from_slice(&(offsets.len() as u64).to_le_bytes());
+        writer.write_entry(&Entry::new(BLOCK_INDEX, index))?;
+
+        writer.into_inner()?;
+        Ok(accumulator)
+    }
+}
+
+/// A decoded ERA1 archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1File {
+    /// The blocks of the archive, in ascending order.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root stored in the archive.
+    pub accumulator: B256,
+}
+
+impl Era1File {
+    /// Reads and decodes the ERA1 archive at the given path.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Era1Error> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    /// Decodes an ERA1 archive.
+    ///
+    /// This checks the structure of the archive and its block index, see [`Self::verify`] for
+    /// checking its contents.
+    pub fn decode(buf: &[u8]) -> Result<Self, Era1Error> {
+        let mut reader = E2sReader::new(buf);
+        match reader.read_entry()? {
+            Some(entry) if entry.entry_type == VERSION => {}
+            Some(entry) => return Err(Era1Error::UnexpectedEntry(entry.entry_type)),
+            None => return Err(Era1Error::MissingEntry("version")),
+        }
+
+        let mut blocks = Vec::new();
+        let mut offsets = Vec::new();
+        let accumulator = loop {
+            let position = reader.position();
+            let entry = reader.read_entry()?.ok_or(Era1Error::MissingEntry("accumulator"))?;
+            match entry.entry_type {
+                COMPRESSED_HEADER => {
+                    let header = decompress(&entry.data)?;
+                    let body = decompress(&expect_entry(&mut reader, COMPRESSED_BODY)?)?;
+                    let receipts = decompress(&expect_entry(&mut reader, COMPRESSED_RECEIPTS)?)?;
+                    let total_difficulty = expect_entry(&mut reader, TOTAL_DIFFICULTY)?;
+                    let total_difficulty = U256::try_from_le_slice(&total_difficulty)
+                        .ok_or(Era1Error::InvalidEntry("total difficulty"))?;
+                    blocks.push(Era1Block { header, body, receipts, total_difficulty });
+                    offsets.push(position as u64);
+                }
+                ACCUMULATOR => {
+                    if entry.data.len() != 32 {
+                        return Err(Era1Error::InvalidEntry("accumulator"))
+                    }
+                    break B256::from_slice(&entry.data)
+                }
+                // other entries are allowed between the block tuples and the accumulator
+                _ => {}
+            }
+        };
+
+        let index_position = reader.position() as i64;
+        let index = expect_entry(&mut reader, BLOCK_INDEX)?;
+        if index.len() != 16 + offsets.len() * 8 {
+            return Err(Era1Error::InvalidEntry("block index"))
+        }
+        let word = |i: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&index[i * 8..(i + 1) * 8]);
+            bytes
+        };
+        let start = u64::from_le_bytes(word(0));
+        let count = u64::from_le_bytes(word(offsets.len() + 1));
+        let offsets_match = offsets
+            .iter()
+            .enumerate()
+            .all(|(i, offset)| i64::from_le_bytes(word(i + 1)) + index_position == *offset as i64);
+        let numbers_match =
+            blocks.iter().enumerate().all(|(i, block)| block.header.number == start + i as u64);
+        if count != blocks.len() as u64 || !offsets_match || !numbers_match {
+            return Err(Era1Error::InvalidEntry("block index"))
+        }
+
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Verifies the receipts of each block against its header and the accumulator against the
+    /// block hashes and total difficulties.
+    pub fn verify(&self) -> Result<(), Era1Error> {
+        for block in &self.blocks {
+            let receipts_root = calculate_receipt_root(&block.receipts);
+            if receipts_root != block.header.receipts_root {
+                return Err(Era1Error::ReceiptsRootMismatch {
+                    number: block.header.number,
+                    mismatch: GotExpected {
+                        got: receipts_root,
+                        expected: block.header.receipts_root,
+                    },
+                })
+            }
+        }
+
+        let records = self
+            .blocks
+            .iter()
+            .map(|block| HeaderRecord {
+                block_hash: block.header.hash_slow(),
+                total_difficulty: block.total_difficulty,
+            })
+            .collect::<Vec<_>>();
+        let accumulator = accumulator_root(&records);
+        if accumulator != self.accumulator {
+            return Err(Era1Error::AccumulatorMismatch(GotExpected {
+                got: accumulator,
+                expected: self.accumulator,
+            }))
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the next entry, which must be of the given type, and returns its data.
+fn expect_entry(reader: &mut E2sReader<'_>, entry_type: u16) -> Result<Vec<u8>, Era1Error> {
+    match reader.read_entry()? {
+        Some(entry) if entry.entry_type == entry_type => Ok(entry.data),
+        Some(entry) => Err(Era1Error::UnexpectedEntry(entry.entry_type)),
+        None => Err(Era1Error::MissingEntry("block tuple")),
+    }
+}
+
+/// RLP encodes and snappy compresses the value.
+fn compress<T: Encodable>(value: &T) -> Result<Vec<u8>, Era1Error> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(&alloy_rlp::encode(value))?;
+    encoder.into_inner().map_err(|err| err.into_error().into())
+}
+
+/// Decompresses and RLP decodes the value.
+fn decompress<T: Decodable>(data: &[u8]) -> Result<T, Era1Error> {
+    let mut rlp = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut rlp)?;
+    Ok(T::decode(&mut rlp.as_slice())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Receipt;
+    use reth_testing_utils::generators::{
+        self, random_block_range, random_receipt, BlockRangeParams,
+    };
+
+    fn random_era1_blocks(range: std::ops::RangeInclusive<u64>) -> Vec<Era1Block> {
+        let mut rng = generators::rng();
+        let blocks = random_block_range(
+            &mut rng,
+            range,
+            BlockRangeParams { tx_count: 0..3, ..Default::default() },
+        );
+        let mut total_difficulty = U256::ZERO;
+        blocks
+            .into_iter()
+            .map(|block| {
+                let receipts = block
+                    .body
+                    .transactions
+                    .iter()
+                    .map(|tx| random_receipt(&mut rng, tx, Some(1)))
+                    .map(Receipt::with_bloom)
+                    .collect::<Vec<_>>();
+                let block = block.unseal();
+                let mut header = block.header;
+                header.receipts_root = calculate_receipt_root(&receipts);
+                total_difficulty += header.difficulty;
+                Era1Block { header, body: block.body, receipts, total_difficulty }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn era1_roundtrip() {
+        let blocks = random_era1_blocks(8192..=8200);
+
+        let mut buf = Vec::new();
+        let mut writer = Era1Writer::new(&mut buf).unwrap();
+        for block in &blocks {
+            writer.append(block).unwrap();
+        }
+        let accumulator = writer.finish().unwrap();
+
+        let file = Era1File::decode(&buf).unwrap();
+        assert_eq!(file.accumulator, accumulator);
+        assert_eq!(file.blocks, blocks);
+        file.verify().unwrap();
+    }
+
+    #[test]
+    fn era1_rejects_gaps() {
+        let blocks = random_era1_blocks(0..=2);
+
+        let mut writer = Era1Writer::new(Vec::new()).unwrap();
+        writer.append(&blocks[0]).unwrap();
+        assert!(matches!(
+            writer.append(&blocks[2]),
+            Err(Era1Error::NonContiguous { expected: 1, got: 2 })
+        ));
+    }
+
+    #[test]
+    fn era1_detects_tampering() {
+        let mut blocks = random_era1_blocks(0..=2);
+
+        let mut buf = Vec::new();
+        let mut writer = Era1Writer::new(&mut buf).unwrap();
+        for block in &blocks {
+            writer.append(block).unwrap();
+        }
+        writer.finish().unwrap();
+
+        // an archive with a different total difficulty but the original accumulator
+        let mut file = Era1File::decode(&buf).unwrap();
+        file.blocks[1].total_difficulty += U256::from(1);
+        assert!(matches!(file.verify(), Err(Era1Error::AccumulatorMismatch(_))));
+
+        // an archive with receipts that don't match the header
+        let mut file = Era1File::decode(&buf).unwrap();
+        blocks[1].header.receipts_root = B256::repeat_byte(1);
+        file.blocks[1].header = blocks[1].header.clone();
+        assert!(matches!(file.verify(), Err(Era1Error::ReceiptsRootMismatch { number: 1, .. })));
+    }
+}
@@ -0,0 +1,53 @@
+use alloy_primitives::{BlockNumber, B256};
+use reth_primitives::GotExpected;
+
+/// Errors when reading or writing ERA1 archives.
+#[derive(Debug, thiserror::Error)]
+pub enum Era1Error {
+    /// An I/O error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// An RLP decoding error.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The data of an entry does not fit into the length field of its header.
+    #[error("e2store entry too large")]
+    EntryTooLarge,
+    /// The buffer ends in the middle of an entry.
+    #[error("truncated e2store entry")]
+    TruncatedEntry,
+    /// The reserved bytes of an entry header are not zero.
+    #[error("invalid e2store entry header")]
+    InvalidEntryHeader,
+    /// An entry of an unexpected type was found.
+    #[error("unexpected e2store entry type {0:#06x}")]
+    UnexpectedEntry(u16),
+    /// A required entry is missing.
+    #[error("missing {0} entry")]
+    MissingEntry(&'static str),
+    /// An entry has invalid contents.
+    #[error("invalid {0} entry")]
+    InvalidEntry(&'static str),
+    /// The archive already holds the maximum number of blocks.
+    #[error("ERA1 archive is full")]
+    Full,
+    /// Blocks were not appended in ascending order without gaps.
+    #[error("non-contiguous block: expected {expected}, got {got}")]
+    NonContiguous {
+        /// The expected block number.
+        expected: BlockNumber,
+        /// The number of the appended block.
+        got: BlockNumber,
+    },
+    /// The receipts of a block don't match its header.
+    #[error("receipts root mismatch at block {number}: {mismatch}")]
+    ReceiptsRootMismatch {
+        /// The block number.
+        number: BlockNumber,
+        /// The computed and the header's receipts root.
+        mismatch: GotExpected<B256>,
+    },
+    /// The accumulator doesn't match the blocks of the archive.
+    #[error("accumulator mismatch: {0}")]
+    AccumulatorMismatch(GotExpected<B256>),
+}
@@ -0,0 +1,24 @@
+//! ERA1 archives of pre-merge blocks.
+//!
+//! ERA1 is an archival format for pre-merge execution history. An archive stores up to 8192
+//! blocks with their receipts and total difficulties, together with an accumulator over the block
+//! hashes and total difficulties that allows verifying the archive against known epoch roots.
+//!
+//! See also <https://github.com/eth-clients/e2store-format-specs>.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+pub mod accumulator;
+pub mod e2s;
+
+mod era1;
+pub use era1::*;
+
+mod error;
+pub use error::Era1Error;
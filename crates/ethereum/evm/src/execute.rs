@@ -12,6 +12,7 @@ use reth_chainspec::{ChainSpec, EthereumHardfork, EthereumHardforks, MAINNET};
 use reth_consensus::ConsensusError;
 use reth_ethereum_consensus::validate_block_post_execution;
 use reth_evm::{
+    dev_state::DevStateOverrides,
     execute::{
         BasicBlockExecutorProvider, BlockExecutionError, BlockExecutionStrategy,
         BlockExecutionStrategyFactory, BlockValidationError, ExecuteOutput, ProviderError,
@@ -34,6 +35,8 @@ pub struct EthExecutionStrategyFactory<EvmConfig = EthEvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// State changes of a dev chain that are applied before the pre-execution changes.
+    dev_state_overrides: Option<DevStateOverrides>,
 }
 
 impl EthExecutionStrategyFactory {
@@ -51,7 +54,13 @@ impl EthExecutionStrategyFactory {
 impl<EvmConfig> EthExecutionStrategyFactory<EvmConfig> {
     /// Creates a new executor strategy factory.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self { chain_spec, evm_config, dev_state_overrides: None }
+    }
+
+    /// Applies the given state changes of a dev chain to the executed blocks.
+    pub fn with_dev_state_overrides(mut self, dev_state_overrides: DevStateOverrides) -> Self {
+        self.dev_state_overrides = Some(dev_state_overrides);
+        self
     }
 }
 
@@ -70,6 +79,7 @@ where
         let state =
             State::builder().with_database(db).with_bundle_update().without_state_clear().build();
         EthExecutionStrategy::new(state, self.chain_spec.clone(), self.evm_config.clone())
+            .with_dev_state_overrides(self.dev_state_overrides.clone())
     }
 }
 
@@ -87,6 +97,8 @@ where
     state: State<DB>,
     /// Utility to call system smart contracts.
     system_caller: SystemCaller<EvmConfig, ChainSpec>,
+    /// State changes of a dev chain that are applied before the pre-execution changes.
+    dev_state_overrides: Option<DevStateOverrides>,
 }
 
 impl<DB, EvmConfig> EthExecutionStrategy<DB, EvmConfig>
@@ -96,7 +108,16 @@ where
     /// Creates a new [`EthExecutionStrategy`]
     pub fn new(state: State<DB>, chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
         let system_caller = SystemCaller::new(evm_config.clone(), (*chain_spec).clone());
-        Self { state, chain_spec, evm_config, system_caller }
+        Self { state, chain_spec, evm_config, system_caller, dev_state_overrides: None }
+    }
+
+    /// Applies the given state changes of a dev chain before the pre-execution changes.
+    pub fn with_dev_state_overrides(
+        mut self,
+        dev_state_overrides: Option<DevStateOverrides>,
+    ) -> Self {
+        self.dev_state_overrides = dev_state_overrides;
+        self
     }
 }
 
@@ -140,6 +161,12 @@ where
             (*self.chain_spec).is_spurious_dragon_active_at_block(block.header.number);
         self.state.set_state_clear_flag(state_clear_flag);
 
+        if let Some(dev_state_overrides) = &self.dev_state_overrides {
+            dev_state_overrides
+                .apply(block.parent_hash, &mut self.state)
+                .map_err(Into::<ProviderError>::into)?;
+        }
+
         let env = self.evm_env_for_block(&block.header, total_difficulty);
         let mut evm = self.evm_config.evm_with_env(&mut self.state, env);
 
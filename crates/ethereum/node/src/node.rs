@@ -147,7 +147,11 @@ where
     ) -> eyre::Result<(Self::EVM, Self::Executor)> {
         let chain_spec = ctx.chain_spec();
         let evm_config = EthEvmConfig::new(ctx.chain_spec());
-        let strategy_factory = EthExecutionStrategyFactory::new(chain_spec, evm_config.clone());
+        let mut strategy_factory = EthExecutionStrategyFactory::new(chain_spec, evm_config.clone());
+        if ctx.is_dev() {
+            strategy_factory =
+                strategy_factory.with_dev_state_overrides(ctx.dev_state_overrides().clone());
+        }
         let executor = BasicBlockExecutorProvider::new(strategy_factory);
 
         Ok((evm_config, executor))
@@ -218,7 +222,12 @@ where
                     pool,
                     chain_events,
                     ctx.task_executor().clone(),
-                    Default::default(),
+                    reth_transaction_pool::maintain::MaintainPoolConfig {
+                        // the only reorgs of a dev chain revert it to a snapshot, which drops the
+                        // reverted transactions
+                        reinject_reorged_transactions: !ctx.is_dev(),
+                        ..Default::default()
+                    },
                 ),
             );
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
@@ -252,8 +261,12 @@ impl EthereumPayloadBuilder {
             PayloadBuilderAttributes = EthPayloadBuilderAttributes,
         >,
     {
-        let payload_builder =
+        let mut payload_builder =
             reth_ethereum_payload_builder::EthereumPayloadBuilder::new(evm_config);
+        if ctx.is_dev() {
+            payload_builder =
+                payload_builder.with_dev_state_overrides(ctx.dev_state_overrides().clone());
+        }
         let conf = ctx.payload_builder_config();
 
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
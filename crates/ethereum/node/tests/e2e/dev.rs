@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
 use crate::utils::eth_payload_attributes;
+use alloy_eips::BlockNumberOrTag;
 use alloy_genesis::Genesis;
-use alloy_primitives::{b256, hex};
+use alloy_primitives::{address, b256, bytes, hex, Bytes, B256, U256};
+use alloy_provider::{Provider, ProviderBuilder};
 use futures::StreamExt;
-use reth::{args::DevArgs, rpc::api::eth::helpers::EthTransactions};
+use reth::{
+    args::{DevArgs, RpcServerArgs},
+    rpc::api::eth::helpers::EthTransactions,
+};
 use reth_chainspec::ChainSpec;
 use reth_e2e_test_utils::setup;
 use reth_node_api::FullNodeComponents;
@@ -54,6 +59,148 @@ async fn can_run_dev_node_new_engine() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn can_control_dev_node_with_anvil_api() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut rpc = RpcServerArgs::default().with_unused_ports().with_http();
+    rpc.http_api = Some("eth,anvil".parse()?);
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(rpc);
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider2<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let url = node.rpc_server_handle().http_url().unwrap();
+    let provider = ProviderBuilder::new().on_http(url.parse()?);
+
+    let snapshot: U256 = provider.raw_request("anvil_snapshot".into(), ()).await?;
+
+    // mine blocks with timestamps 100 seconds apart
+    let timestamp = provider.get_block_by_number(0.into(), false).await?.unwrap().header.timestamp;
+    let () =
+        provider.raw_request("anvil_setNextBlockTimestamp".into(), (timestamp + 1_000,)).await?;
+    let () = provider.raw_request("anvil_mine".into(), (U256::from(3), U256::from(100))).await?;
+    assert_eq!(provider.get_block_number().await?, 3);
+    let head = provider.get_block_by_number(3.into(), false).await?.unwrap();
+    assert_eq!(head.header.timestamp, timestamp + 1_200);
+
+    // reverting makes the snapshot block the head and removes all later blocks
+    let reverted: bool = provider.raw_request("anvil_revert".into(), (snapshot,)).await?;
+    assert!(reverted);
+    assert_eq!(provider.get_block_number().await?, 0);
+    let genesis_hash = provider.get_block_by_number(0.into(), false).await?.unwrap().header.hash;
+    let head = provider.get_block_by_number(BlockNumberOrTag::Latest, false).await?.unwrap();
+    assert_eq!(head.header.hash, genesis_hash);
+    for number in 1..=3 {
+        assert!(provider.get_block_by_number(number.into(), false).await?.is_none());
+    }
+
+    // the next block is mined on top of the snapshot block
+    let () = provider.raw_request("anvil_mine".into(), (U256::from(1),)).await?;
+    assert_eq!(provider.get_block_number().await?, 1);
+    let head = provider.get_block_by_number(1.into(), false).await?.unwrap();
+    assert_eq!(head.header.parent_hash, genesis_hash);
+
+    // the snapshot is consumed by the revert
+    let reverted: bool = provider.raw_request("anvil_revert".into(), (snapshot,)).await?;
+    assert!(!reverted);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_modify_dev_state_with_anvil_api() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let mut rpc = RpcServerArgs::default().with_unused_ports().with_http();
+    rpc.http_api = Some("eth,anvil".parse()?);
+    let node_config = NodeConfig::test()
+        .with_chain(custom_chain())
+        .with_dev(DevArgs { dev: true, ..Default::default() })
+        .with_rpc(rpc);
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider2<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let url = node.rpc_server_handle().http_url().unwrap();
+    let provider = ProviderBuilder::new().on_http(url.parse()?);
+
+    // every change is applied in a new block that is executed by the engine
+    let account = address!("0000000000000000000000000000000000000abc");
+    let code = bytes!("600160005260206000f3");
+    let () = provider.raw_request("anvil_setBalance".into(), (account, U256::from(1_000))).await?;
+    let () = provider.raw_request("anvil_setNonce".into(), (account, U256::from(5))).await?;
+    let () = provider.raw_request("anvil_setCode".into(), (account, code.clone())).await?;
+    let set: bool = provider
+        .raw_request("anvil_setStorageAt".into(), (account, U256::from(1), B256::with_last_byte(7)))
+        .await?;
+    assert!(set);
+    assert_eq!(provider.get_block_number().await?, 4);
+    assert_eq!(provider.get_balance(account).await?, U256::from(1_000));
+    assert_eq!(provider.get_transaction_count(account).await?, 5);
+    assert_eq!(provider.get_code_at(account).await?, code);
+    assert_eq!(provider.get_storage_at(account, U256::from(1)).await?, U256::from(7));
+
+    // loading a dumped state restores the dumped accounts
+    let state: Bytes = provider.raw_request("anvil_dumpState".into(), ()).await?;
+    let () = provider.raw_request("anvil_setBalance".into(), (account, U256::from(1))).await?;
+    let () = provider.raw_request("anvil_setCode".into(), (account, Bytes::new())).await?;
+    let set: bool = provider
+        .raw_request("anvil_setStorageAt".into(), (account, U256::from(1), B256::ZERO))
+        .await?;
+    assert!(set);
+    assert_eq!(provider.get_balance(account).await?, U256::from(1));
+    assert_eq!(provider.get_code_at(account).await?, Bytes::new());
+
+    let loaded: bool = provider.raw_request("anvil_loadState".into(), (state,)).await?;
+    assert!(loaded);
+    assert_eq!(provider.get_balance(account).await?, U256::from(1_000));
+    assert_eq!(provider.get_transaction_count(account).await?, 5);
+    assert_eq!(provider.get_code_at(account).await?, code);
+    assert_eq!(provider.get_storage_at(account, U256::from(1)).await?, U256::from(7));
+
+    // resetting removes all blocks and their changes
+    let () = provider.raw_request("anvil_reset".into(), ()).await?;
+    assert_eq!(provider.get_block_number().await?, 0);
+    assert_eq!(provider.get_balance(account).await?, U256::ZERO);
+    assert_eq!(provider.get_code_at(account).await?, Bytes::new());
+
+    // forking is rejected
+    let fork = serde_json::json!({ "forking": { "jsonRpcUrl": "http://localhost:8545" } });
+    assert!(provider.raw_request::<_, ()>("anvil_reset".into(), (fork,)).await.is_err());
+
+    Ok(())
+}
+
 async fn assert_chain_advances<N, AddOns>(node: FullNode<N, AddOns>)
 where
     N: FullNodeComponents<Provider: CanonStateSubscriptions>,
@@ -19,7 +19,9 @@ use reth_basic_payload_builder::{
 use reth_chain_state::ExecutedBlock;
 use reth_chainspec::ChainSpec;
 use reth_errors::RethError;
-use reth_evm::{system_calls::SystemCaller, ConfigureEvm, NextBlockEnvAttributes};
+use reth_evm::{
+    dev_state::DevStateOverrides, system_calls::SystemCaller, ConfigureEvm, NextBlockEnvAttributes,
+};
 use reth_evm_ethereum::{eip6110::parse_deposits_from_receipts, EthEvmConfig};
 use reth_execution_types::ExecutionOutcome;
 use reth_payload_builder::{EthBuiltPayload, EthPayloadBuilderAttributes};
@@ -50,16 +52,24 @@ type BestTransactionsIter<Pool> = Box<
 >;
 
 /// Ethereum payload builder
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// State changes of a dev chain that are applied before the pre-execution changes.
+    dev_state_overrides: Option<DevStateOverrides>,
 }
 
 impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
     /// `EthereumPayloadBuilder` constructor.
     pub const fn new(evm_config: EvmConfig) -> Self {
-        Self { evm_config }
+        Self { evm_config, dev_state_overrides: None }
+    }
+
+    /// Applies the given state changes of a dev chain to the built payloads.
+    pub fn with_dev_state_overrides(mut self, dev_state_overrides: DevStateOverrides) -> Self {
+        self.dev_state_overrides = Some(dev_state_overrides);
+        self
     }
 }
 
@@ -102,9 +112,14 @@ where
             .map_err(PayloadBuilderError::other)?;
 
         let pool = args.pool.clone();
-        default_ethereum_payload(self.evm_config.clone(), args, cfg_env, block_env, |attributes| {
-            pool.best_transactions_with_attributes(attributes)
-        })
+        default_ethereum_payload(
+            self.evm_config.clone(),
+            self.dev_state_overrides.as_ref(),
+            args,
+            cfg_env,
+            block_env,
+            |attributes| pool.best_transactions_with_attributes(attributes),
+        )
     }
 
     fn build_empty_payload(
@@ -128,9 +143,14 @@ where
 
         let pool = args.pool.clone();
 
-        default_ethereum_payload(self.evm_config.clone(), args, cfg_env, block_env, |attributes| {
-            pool.best_transactions_with_attributes(attributes)
-        })?
+        default_ethereum_payload(
+            self.evm_config.clone(),
+            self.dev_state_overrides.as_ref(),
+            args,
+            cfg_env,
+            block_env,
+            |attributes| pool.best_transactions_with_attributes(attributes),
+        )?
         .into_payload()
         .ok_or_else(|| PayloadBuilderError::MissingPayload)
     }
@@ -141,9 +161,12 @@ where
 /// Given build arguments including an Ethereum client, transaction pool,
 /// and configuration, this function creates a transaction payload. Returns
 /// a result indicating success with the payload or an error in case of failure.
+///
+/// The state changes of a dev chain are applied before the pre-execution changes, if set.
 #[inline]
 pub fn default_ethereum_payload<EvmConfig, Pool, Client, F>(
     evm_config: EvmConfig,
+    dev_state_overrides: Option<&DevStateOverrides>,
     args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     initialized_cfg: CfgEnvWithHandlerCfg,
     initialized_block_env: BlockEnv,
@@ -181,6 +204,14 @@ where
 
     let block_number = initialized_block_env.number.to::<u64>();
 
+    // apply the state changes of a dev chain
+    if let Some(dev_state_overrides) = dev_state_overrides {
+        dev_state_overrides.apply(parent_header.hash(), &mut db).map_err(|err| {
+            warn!(target: "payload_builder", parent_hash=%parent_header.hash(), %err, "failed to apply dev state overrides for payload");
+            PayloadBuilderError::Internal(err.into())
+        })?;
+    }
+
     let mut system_caller = SystemCaller::new(evm_config.clone(), chain_spec.clone());
 
     // apply eip-4788 pre block contract call
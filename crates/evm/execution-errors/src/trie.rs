@@ -13,6 +13,8 @@ pub enum StateRootError {
     Database(DatabaseError),
     /// Storage root error.
     StorageRootError(StorageRootError),
+    /// Provider error, e.g. when reading changesets.
+    Provider(ProviderError),
 }
 
 impl core::error::Error for StateRootError {
@@ -20,6 +22,7 @@ impl core::error::Error for StateRootError {
         match self {
             Self::Database(source) => core::error::Error::source(source),
             Self::StorageRootError(source) => core::error::Error::source(source),
+            Self::Provider(source) => core::error::Error::source(source),
         }
     }
 }
@@ -28,7 +31,9 @@ impl From<StateRootError> for DatabaseError {
     fn from(err: StateRootError) -> Self {
         match err {
             StateRootError::Database(err) |
-            StateRootError::StorageRootError(StorageRootError::Database(err)) => err,
+            StateRootError::StorageRootError(StorageRootError::Database(err)) |
+            StateRootError::Provider(ProviderError::Database(err)) => err,
+            StateRootError::Provider(err) => Self::Other(err.to_string()),
         }
     }
 }
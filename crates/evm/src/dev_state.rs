@@ -0,0 +1,138 @@
+//! State overrides of dev chains.
+
+use alloc::sync::Arc;
+use alloy_primitives::{map::HashMap, Address, Bytes, B256, U256};
+use parking_lot::RwLock;
+use revm::{
+    db::State,
+    primitives::{Account, AccountStatus, Bytecode, EvmStorageSlot, KECCAK_EMPTY},
+    Database, DatabaseCommit,
+};
+
+/// Changes to the state of an account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountOverride {
+    /// The new balance of the account.
+    pub balance: Option<U256>,
+    /// The new nonce of the account.
+    pub nonce: Option<u64>,
+    /// The new code of the account, empty code removes the code.
+    pub code: Option<Bytes>,
+    /// The new values of storage slots of the account.
+    pub storage: HashMap<B256, U256>,
+}
+
+impl AccountOverride {
+    /// Merges the given changes into these changes, overwriting the values that are set in both.
+    pub fn merge(&mut self, other: Self) {
+        let Self { balance, nonce, code, storage } = other;
+        if balance.is_some() {
+            self.balance = balance;
+        }
+        if nonce.is_some() {
+            self.nonce = nonce;
+        }
+        if code.is_some() {
+            self.code = code;
+        }
+        self.storage.extend(storage);
+    }
+}
+
+/// State changes of a dev chain, like the ones made by the `anvil_` cheatcodes.
+///
+/// Every block of a dev chain is built by the payload builder and then re-executed by the engine,
+/// so the state can not be modified directly. Instead, the changes are queued with
+/// [`Self::queue`] and assigned to the next block with [`Self::seal`] by the local miner before it
+/// is built. The payload builder and the block executor both apply the changes assigned to the
+/// parent of a block with [`Self::apply`] before any other pre-execution changes, which makes them
+/// part of the state transition of the block.
+///
+/// The changes are only kept in memory and are not applied when a block is re-executed after a
+/// restart.
+#[derive(Debug, Clone, Default)]
+pub struct DevStateOverrides {
+    inner: Arc<RwLock<DevStateOverridesInner>>,
+}
+
+#[derive(Debug, Default)]
+struct DevStateOverridesInner {
+    /// Changes that are applied to the next block.
+    pending: HashMap<Address, AccountOverride>,
+    /// Changes applied to the child blocks of a block, by the hash of the parent block.
+    sealed: HashMap<B256, HashMap<Address, AccountOverride>>,
+}
+
+impl DevStateOverrides {
+    /// Queues changes to the given account, which are applied to the next block.
+    pub fn queue(&self, address: Address, account: AccountOverride) {
+        self.inner.write().pending.entry(address).or_default().merge(account);
+    }
+
+    /// Assigns the queued changes to the block that is built on top of the given parent block,
+    /// replacing any changes that were assigned to it before.
+    pub fn seal(&self, parent_hash: B256) {
+        let mut inner = self.inner.write();
+        let pending = core::mem::take(&mut inner.pending);
+        if pending.is_empty() {
+            inner.sealed.remove(&parent_hash);
+        } else {
+            inner.sealed.insert(parent_hash, pending);
+        }
+    }
+
+    /// Discards all queued and assigned changes.
+    pub fn clear(&self) {
+        let mut inner = self.inner.write();
+        inner.pending.clear();
+        inner.sealed.clear();
+    }
+
+    /// Applies the changes assigned to the child blocks of the given parent block to the state.
+    pub fn apply<DB: Database>(
+        &self,
+        parent_hash: B256,
+        state: &mut State<DB>,
+    ) -> Result<(), DB::Error> {
+        let Some(overrides) = self.inner.read().sealed.get(&parent_hash).cloned() else {
+            return Ok(())
+        };
+
+        let mut changes = HashMap::default();
+        for (address, account) in overrides {
+            let AccountOverride { balance, nonce, code, storage } = account;
+            let mut info = state.basic(address)?.unwrap_or_default();
+            if let Some(balance) = balance {
+                info.balance = balance;
+            }
+            if let Some(nonce) = nonce {
+                info.nonce = nonce;
+            }
+            if let Some(code) = code {
+                if code.is_empty() {
+                    info.code_hash = KECCAK_EMPTY;
+                    info.code = None;
+                } else {
+                    let code = Bytecode::new_raw(code);
+                    info.code_hash = code.hash_slow();
+                    info.code = Some(code);
+                }
+            }
+
+            let mut changed_storage = HashMap::default();
+            for (slot, value) in storage {
+                let slot = U256::from_be_bytes(slot.0);
+                let original = state.storage(address, slot)?;
+                changed_storage.insert(slot, EvmStorageSlot::new_changed(original, value));
+            }
+
+            changes.insert(
+                address,
+                Account { info, storage: changed_storage, status: AccountStatus::Touched },
+            );
+        }
+        state.commit(changes);
+
+        Ok(())
+    }
+}
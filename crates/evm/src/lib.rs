@@ -25,6 +25,8 @@ use revm::{Database, Evm, GetInspector};
 use revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg, SpecId, TxEnv};
 
 pub mod builder;
+#[cfg(feature = "std")]
+pub mod dev_state;
 pub mod either;
 pub mod execute;
 #[cfg(feature = "std")]
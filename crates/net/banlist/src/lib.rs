@@ -10,7 +10,14 @@
 
 type PeerId = alloy_primitives::B512;
 
-use std::{collections::HashMap, net::IpAddr, time::Instant};
+pub use ipnet::IpNet;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 /// Determines whether or not the IP is globally routable.
 /// Should be replaced with [`IpAddr::is_global`](std::net::IpAddr::is_global) once it is stable.
@@ -33,6 +40,8 @@ pub struct BanList {
     banned_ips: HashMap<IpAddr, Option<Instant>>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
+    /// A set of subnets whose IPs are treated as banned.
+    banned_subnets: Vec<(IpNet, Option<Instant>)>,
 }
 
 impl BanList {
@@ -52,7 +61,7 @@ impl BanList {
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_peers, banned_subnets: Vec::new() }
     }
 
     /// Removes all peers that are no longer banned.
@@ -85,12 +94,29 @@ impl BanList {
         evicted
     }
 
+    /// Removes all subnets that are no longer banned.
+    pub fn evict_subnets(&mut self, now: Instant) -> Vec<IpNet> {
+        let mut evicted = Vec::new();
+        self.banned_subnets.retain(|(subnet, until)| {
+            if let Some(until) = until {
+                if now > *until {
+                    evicted.push(*subnet);
+                    return false
+                }
+            }
+            true
+        });
+        evicted
+    }
+
     /// Removes all entries that should no longer be banned.
     ///
-    /// Returns the evicted entries.
+    /// Returns the evicted entries. Expired subnets are removed as well, see
+    /// [`Self::evict_subnets`].
     pub fn evict(&mut self, now: Instant) -> (Vec<IpAddr>, Vec<PeerId>) {
         let ips = self.evict_ips(now);
         let peers = self.evict_peers(now);
+        self.evict_subnets(now);
         (ips, peers)
     }
 
@@ -100,10 +126,17 @@ impl BanList {
         self.is_banned_peer(peer_id) || self.is_banned_ip(ip)
     }
 
-    /// checks the ban list to see if it contains the given ip
+    /// checks the ban list to see if it contains the given ip, or a subnet that contains it
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) || self.is_banned_subnet_ip(ip)
+    }
+
+    /// checks the ban list to see if it contains a subnet that contains the given ip
+    pub fn is_banned_subnet_ip(&self, ip: &IpAddr) -> bool {
+        // IPv4-mapped IPv6 addresses are matched against IPv4 subnets
+        let ip = ip.to_canonical();
+        self.banned_subnets.iter().any(|(subnet, _)| subnet.contains(&ip))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -122,6 +155,14 @@ impl BanList {
         self.banned_peers.remove(peer_id);
     }
 
+    /// Unbans the subnet
+    ///
+    /// This only removes the ban of the exact subnet, bans of overlapping subnets remain.
+    pub fn unban_subnet(&mut self, subnet: &IpNet) {
+        let subnet = subnet.trunc();
+        self.banned_subnets.retain(|(banned, _)| *banned != subnet);
+    }
+
     /// Bans the IP until the timestamp.
     ///
     /// This does not ban non-global IPs.
@@ -159,6 +200,114 @@ impl BanList {
             self.banned_ips.insert(ip, until);
         }
     }
+
+    /// Bans the subnet until the timestamp.
+    pub fn ban_subnet_until(&mut self, subnet: IpNet, until: Instant) {
+        self.ban_subnet_with(subnet, Some(until));
+    }
+
+    /// Bans the subnet indefinitely.
+    pub fn ban_subnet(&mut self, subnet: IpNet) {
+        self.ban_subnet_with(subnet, None);
+    }
+
+    /// Bans the subnet indefinitely or until the given timeout.
+    ///
+    /// Unlike single IPs, subnets are banned even if they're not global, since they're only banned
+    /// on purpose.
+    pub fn ban_subnet_with(&mut self, subnet: IpNet, until: Option<Instant>) {
+        let subnet = subnet.trunc();
+        match self.banned_subnets.iter_mut().find(|(banned, _)| *banned == subnet) {
+            Some((_, banned_until)) => *banned_until = until,
+            None => self.banned_subnets.push((subnet, until)),
+        }
+    }
+
+    /// Returns all bans, with their expiry converted to UNIX timestamps.
+    ///
+    /// Bans that expire past the range of the system clock are returned as indefinite.
+    pub fn entries(&self) -> Vec<BanEntry> {
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        let expires_at = |until: &Option<Instant>| {
+            until.and_then(|until| {
+                let expires_at = system_now.checked_add(until.saturating_duration_since(now))?;
+                Some(expires_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+            })
+        };
+
+        let peers = self
+            .banned_peers
+            .iter()
+            .map(|(peer_id, until)| BanEntry::new(BanTarget::Peer(*peer_id), expires_at(until)));
+        let ips = self
+            .banned_ips
+            .iter()
+            .map(|(ip, until)| BanEntry::new(BanTarget::Ip(*ip), expires_at(until)));
+        let subnets = self
+            .banned_subnets
+            .iter()
+            .map(|(subnet, until)| BanEntry::new(BanTarget::Subnet(*subnet), expires_at(until)));
+        peers.chain(ips).chain(subnets).collect()
+    }
+}
+
+impl Extend<BanEntry> for BanList {
+    /// Adds the bans, skipping the ones that have already expired.
+    ///
+    /// Bans that expire past the range of the clock are added as indefinite.
+    fn extend<T: IntoIterator<Item = BanEntry>>(&mut self, entries: T) {
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        for BanEntry { target, expires_at } in entries {
+            let until = match expires_at {
+                Some(expires_at) => match UNIX_EPOCH.checked_add(Duration::from_secs(expires_at)) {
+                    Some(expires_at) => match expires_at.duration_since(system_now) {
+                        Ok(remaining) => now.checked_add(remaining),
+                        Err(_) => continue,
+                    },
+                    None => None,
+                },
+                None => None,
+            };
+            match target {
+                BanTarget::Peer(peer_id) => self.ban_peer_with(peer_id, until),
+                BanTarget::Ip(ip) => self.ban_ip_with(ip, until),
+                BanTarget::Subnet(subnet) => self.ban_subnet_with(subnet, until),
+            }
+        }
+    }
+}
+
+/// A ban on the [`BanList`], as persisted to disk and exposed over RPC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BanEntry {
+    /// What is banned.
+    #[serde(flatten)]
+    pub target: BanTarget,
+    /// The UNIX timestamp in seconds at which the ban expires, or `None` if the ban is
+    /// indefinite.
+    pub expires_at: Option<u64>,
+}
+
+impl BanEntry {
+    /// Creates a new ban entry.
+    pub const fn new(target: BanTarget, expires_at: Option<u64>) -> Self {
+        Self { target, expires_at }
+    }
+}
+
+/// What a [`BanEntry`] bans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BanTarget {
+    /// A peer by its id.
+    Peer(PeerId),
+    /// A single IP address.
+    Ip(IpAddr),
+    /// All IP addresses of a subnet.
+    Subnet(IpNet),
 }
 
 #[cfg(test)]
@@ -208,4 +357,68 @@ mod tests {
         banlist.ban_ip(ip);
         assert!(!banlist.is_banned_ip(&ip));
     }
+
+    #[test]
+    fn can_ban_unban_subnet() {
+        let subnet: IpNet = "1.1.0.0/16".parse().unwrap();
+        let mut banlist = BanList::default();
+        banlist.ban_subnet(subnet);
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 1, 255, 255])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 2, 0, 0])));
+
+        // IPv4-mapped IPv6 addresses are banned as well
+        let mapped: IpAddr = "::ffff:1.1.1.1".parse().unwrap();
+        assert!(banlist.is_banned_ip(&mapped));
+
+        // subnets are normalized to their network address
+        banlist.unban_subnet(&"1.1.1.1/16".parse().unwrap());
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+    }
+
+    #[test]
+    fn evict_expired_subnets() {
+        let subnet: IpNet = "2001:db8::/32".parse().unwrap();
+        let now = Instant::now();
+        let mut banlist = BanList::default();
+        banlist.ban_subnet_until(subnet, now + Duration::from_secs(60));
+        assert!(banlist.is_banned_ip(&"2001:db8::1".parse().unwrap()));
+
+        banlist.evict(now);
+        assert!(banlist.is_banned_ip(&"2001:db8::1".parse().unwrap()));
+        banlist.evict(now + Duration::from_secs(61));
+        assert!(!banlist.is_banned_ip(&"2001:db8::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn entries_roundtrip() {
+        let peer = PeerId::random();
+        let ip = IpAddr::from([1, 1, 1, 1]);
+        let subnet: IpNet = "2.2.0.0/16".parse().unwrap();
+        let mut banlist = BanList::default();
+        banlist.ban_peer(peer);
+        banlist.ban_ip_until(ip, Instant::now() + Duration::from_secs(60 * 60));
+        banlist.ban_subnet(subnet);
+
+        let entries = banlist.entries();
+        assert_eq!(entries.len(), 3);
+        let json = serde_json::to_string(&entries).unwrap();
+        let entries: Vec<BanEntry> = serde_json::from_str(&json).unwrap();
+
+        let mut restored = BanList::default();
+        restored.extend(entries);
+        assert!(restored.is_banned_peer(&peer));
+        assert!(restored.is_banned_ip(&ip));
+        assert!(restored.is_banned_ip(&IpAddr::from([2, 2, 2, 2])));
+
+        // expired bans are skipped
+        let mut restored = BanList::default();
+        restored.extend([BanEntry::new(BanTarget::Ip(ip), Some(1))]);
+        assert!(!restored.is_banned_ip(&ip));
+
+        // bans past the range of the clock are indefinite
+        let mut restored = BanList::default();
+        restored.extend([BanEntry::new(BanTarget::Peer(peer), Some(u64::MAX))]);
+        assert_eq!(restored.entries(), vec![BanEntry::new(BanTarget::Peer(peer), None)]);
+    }
 }
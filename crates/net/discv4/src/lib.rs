@@ -1333,10 +1333,12 @@ impl Discv4Service {
                 match (fork_id, old_fork_id) {
                     (Some(new), Some(old)) => {
                         if new != old {
-                            self.notify(DiscoveryUpdate::EnrForkId(record, new))
+                            self.notify(DiscoveryUpdate::EnrForkId(record, new, msg.enr))
                         }
                     }
-                    (Some(new), None) => self.notify(DiscoveryUpdate::EnrForkId(record, new)),
+                    (Some(new), None) => {
+                        self.notify(DiscoveryUpdate::EnrForkId(record, new, msg.enr))
+                    }
                     _ => {}
                 }
             }
@@ -2376,8 +2378,9 @@ pub enum DiscoveryUpdate {
     Added(NodeRecord),
     /// A new node was discovered but _not_ added to the table because it is currently full.
     DiscoveredAtCapacity(NodeRecord),
-    /// Received a [`ForkId`] via EIP-868 for the given [`NodeRecord`].
-    EnrForkId(NodeRecord, ForkId),
+    /// Received a [`ForkId`] via EIP-868 for the given [`NodeRecord`], together with the [`Enr`]
+    /// that advertised it.
+    EnrForkId(NodeRecord, ForkId, Enr<SecretKey>),
     /// Node that was removed from the table
     Removed(PeerId),
     /// A series of updates
@@ -2507,7 +2510,7 @@ mod tests {
         let mut table = HashMap::new();
         while let Some(update) = updates.next().await {
             match update {
-                DiscoveryUpdate::EnrForkId(record, fork_id) => {
+                DiscoveryUpdate::EnrForkId(record, fork_id, _) => {
                     println!("{record:?}, {fork_id:?}");
                 }
                 DiscoveryUpdate::Added(record) => {
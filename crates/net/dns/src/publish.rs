@@ -0,0 +1,314 @@
+//! Support for publishing node lists as [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) trees.
+//!
+//! This is the counterpart of the [`DnsDiscoveryService`](crate::DnsDiscoveryService): a
+//! [`DnsTree`] arranges a set of ENRs and links into a merkle tree of TXT records and signs its
+//! root, so the records can be served from any DNS zone.
+//!
+//! Every entry but the root is published under the subdomain
+//!
+//! ```text
+//! base32(keccak256(entry)[..16]).<domain>
+//! ```
+//!
+//! while the signed root entry is published at the apex of the domain.
+
+use crate::tree::{BranchEntry, DnsEntry, LinkEntry, NodeEntry, TreeRootEntry};
+use alloy_primitives::{keccak256, Bytes};
+use data_encoding::BASE32_NOPAD;
+use enr::{Enr, EnrKey, EnrKeyUnambiguous, Error as EnrError};
+use secp256k1::SecretKey;
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Maximum number of children of a branch entry.
+///
+/// This keeps branch entries small enough to fit into a single UDP DNS response.
+const MAX_CHILDREN: usize = 13;
+
+/// Maximum length of a single character-string of a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// An [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) tree of node records and links, ready
+/// to be published.
+#[derive(Debug)]
+pub struct DnsTree<K: EnrKeyUnambiguous = SecretKey> {
+    /// The signed root entry of the tree.
+    root: TreeRootEntry,
+    /// All entries of the tree except for the root, keyed by their subdomain hash.
+    entries: BTreeMap<String, DnsEntry<K>>,
+}
+
+// === impl DnsTree ===
+
+impl<K: EnrKeyUnambiguous> DnsTree<K> {
+    /// Creates a new tree for the given nodes and links and signs its root with the given key.
+    ///
+    /// Nodes are sorted by node id and deduplicated, so the same set of nodes always results in
+    /// the same tree.
+    pub fn new<S: EnrKey>(
+        sequence_number: u64,
+        nodes: impl IntoIterator<Item = Enr<K>>,
+        links: impl IntoIterator<Item = LinkEntry<K>>,
+        key: &S,
+    ) -> Result<Self, EnrError> {
+        let mut nodes = nodes.into_iter().collect::<Vec<_>>();
+        nodes.sort_by_key(|enr| enr.node_id().raw());
+        nodes.dedup_by_key(|enr| enr.node_id().raw());
+
+        let mut links = links.into_iter().map(|link| (link.to_string(), link)).collect::<Vec<_>>();
+        links.sort_by(|(a, _), (b, _)| a.cmp(b));
+        links.dedup_by(|(a, _), (b, _)| a == b);
+
+        let mut entries = BTreeMap::new();
+
+        let enr_root = build_subtree(
+            &mut entries,
+            nodes.into_iter().map(|enr| DnsEntry::Node(NodeEntry { enr })).collect(),
+        );
+        let enr_root = insert_entry(&mut entries, enr_root);
+
+        let link_root = build_subtree(
+            &mut entries,
+            links.into_iter().map(|(_, link)| DnsEntry::Link(link)).collect(),
+        );
+        let link_root = insert_entry(&mut entries, link_root);
+
+        let mut root =
+            TreeRootEntry { enr_root, link_root, sequence_number, signature: Bytes::new() };
+        root.sign(key)?;
+
+        Ok(Self { root, entries })
+    }
+
+    /// Returns the signed root entry of the tree.
+    pub const fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns all entries of the tree except for the root, keyed by their subdomain hash.
+    pub const fn entries(&self) -> &BTreeMap<String, DnsEntry<K>> {
+        &self.entries
+    }
+
+    /// Returns the TXT records of the tree as `(name, content)` pairs, for the given domain.
+    ///
+    /// The first record is the root entry, published at the domain itself.
+    pub fn txt_records(&self, domain: &str) -> Vec<(String, String)> {
+        std::iter::once((domain.to_string(), self.root.to_string()))
+            .chain(
+                self.entries
+                    .iter()
+                    .map(|(hash, entry)| (format!("{hash}.{domain}"), entry.to_string())),
+            )
+            .collect()
+    }
+
+    /// Returns the tree as a DNS zone file for the given domain.
+    ///
+    /// Contents longer than 255 bytes are split into multiple character-strings, as required for
+    /// TXT records.
+    pub fn to_zone_file(&self, domain: &str, ttl: u32) -> String {
+        let domain = domain.trim_end_matches('.');
+        let mut zone = String::new();
+
+        let _ =
+            writeln!(zone, "; EIP-1459 node list, sequence number {}", self.root.sequence_number);
+        let _ = writeln!(zone, "$ORIGIN {domain}.");
+        let _ = writeln!(zone, "$TTL {ttl}");
+
+        let records = std::iter::once(("@".to_string(), self.root.to_string()))
+            .chain(self.entries.iter().map(|(hash, entry)| (hash.clone(), entry.to_string())));
+        for (name, content) in records {
+            let _ = writeln!(zone, "{name}\tIN\tTXT\t{}", txt_strings(&content));
+        }
+
+        zone
+    }
+}
+
+/// Returns the subdomain hash of the given entry text.
+pub fn entry_hash(entry: &str) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry.as_bytes())[..16])
+}
+
+/// Builds the subtree for the given entries and returns its root entry.
+///
+/// All other entries of the subtree are inserted into `entries`. An empty list of entries results
+/// in an empty branch.
+fn build_subtree<K: EnrKeyUnambiguous>(
+    entries: &mut BTreeMap<String, DnsEntry<K>>,
+    mut subtree: Vec<DnsEntry<K>>,
+) -> DnsEntry<K> {
+    if subtree.len() == 1 {
+        return subtree.pop().expect("exists")
+    }
+
+    if subtree.len() <= MAX_CHILDREN {
+        let children = subtree.into_iter().map(|entry| insert_entry(entries, entry)).collect();
+        return DnsEntry::Branch(BranchEntry { children })
+    }
+
+    let mut subtrees = Vec::with_capacity(subtree.len().div_ceil(MAX_CHILDREN));
+    let mut remaining = subtree.into_iter();
+    loop {
+        let chunk = remaining.by_ref().take(MAX_CHILDREN).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            break
+        }
+        let root = build_subtree(entries, chunk);
+        // the root of each chunk is referenced by the branch built on top of the chunks, which
+        // inserts it
+        subtrees.push(root);
+    }
+
+    build_subtree(entries, subtrees)
+}
+
+/// Inserts the entry under its subdomain hash and returns the hash.
+fn insert_entry<K: EnrKeyUnambiguous>(
+    entries: &mut BTreeMap<String, DnsEntry<K>>,
+    entry: DnsEntry<K>,
+) -> String {
+    let hash = entry_hash(&entry.to_string());
+    entries.insert(hash.clone(), entry);
+    hash
+}
+
+/// Splits the content into the character-strings of a TXT record.
+fn txt_character_strings(content: &str) -> Vec<String> {
+    // entry texts are plain ascii, so splitting at byte offsets is safe
+    content
+        .as_bytes()
+        .chunks(MAX_TXT_STRING_LEN)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect()
+}
+
+/// Formats the content as the quoted character-strings of a TXT record.
+fn txt_strings(content: &str) -> String {
+    txt_character_strings(content)
+        .into_iter()
+        .map(|string| format!("\"{string}\""))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::txt_record_text;
+    use secp256k1::{rand::thread_rng, SECP256K1};
+    use std::{collections::HashSet, net::Ipv4Addr};
+    use trust_dns_resolver::proto::rr::rdata::TXT;
+
+    fn rng_enr(secret_key: &SecretKey) -> Enr<SecretKey> {
+        Enr::builder().ip4(Ipv4Addr::LOCALHOST).tcp4(30303).udp4(30303).build(secret_key).unwrap()
+    }
+
+    #[test]
+    fn build_and_resolve_tree() {
+        let mut rng = thread_rng();
+        let key = SecretKey::new(&mut rng);
+        let nodes =
+            (0..50).map(|_| rng_enr(&SecretKey::new(&mut rng))).collect::<Vec<Enr<SecretKey>>>();
+        let link = LinkEntry::<SecretKey> {
+            domain: "nodes.example.org".to_string(),
+            pubkey: SecretKey::new(&mut rng).public_key(SECP256K1),
+        };
+
+        let tree = DnsTree::new(7, nodes.clone(), vec![link.clone()], &key).unwrap();
+
+        let root: TreeRootEntry = tree.root().to_string().parse().unwrap();
+        assert_eq!(root.sequence_number, 7);
+        assert!(root.verify::<SecretKey>(&key.public_key(SECP256K1)));
+
+        // resolve the tree the way a client does, starting at the root
+        let mut resolved_nodes = HashSet::new();
+        let mut resolved_links = Vec::new();
+        let mut unresolved = vec![root.enr_root.clone(), root.link_root.clone()];
+        while let Some(hash) = unresolved.pop() {
+            let text = tree.entries()[&hash].to_string();
+            assert_eq!(entry_hash(&text), hash);
+
+            match text.parse::<DnsEntry<SecretKey>>().unwrap() {
+                DnsEntry::Branch(branch) => {
+                    assert!(branch.children.len() <= MAX_CHILDREN);
+                    unresolved.extend(branch.children);
+                }
+                DnsEntry::Node(node) => {
+                    resolved_nodes.insert(node.enr.node_id());
+                }
+                DnsEntry::Link(link) => resolved_links.push(link),
+                DnsEntry::Root(_) => unreachable!(),
+            }
+        }
+
+        assert_eq!(resolved_nodes, nodes.iter().map(|enr| enr.node_id()).collect());
+        assert_eq!(resolved_links, vec![link]);
+    }
+
+    #[test]
+    fn build_empty_tree() {
+        let key = SecretKey::new(&mut thread_rng());
+        let tree = DnsTree::<SecretKey>::new(1, vec![], vec![], &key).unwrap();
+
+        // both subtrees are the same empty branch
+        assert_eq!(tree.root().enr_root, tree.root().link_root);
+        assert_eq!(tree.entries().len(), 1);
+        let text = tree.entries()[&tree.root().enr_root].to_string();
+        assert_eq!(text, "enrtree-branch:");
+        assert!(text.parse::<BranchEntry>().unwrap().children.is_empty());
+    }
+
+    #[test]
+    fn zone_file_contains_all_records() {
+        let mut rng = thread_rng();
+        let key = SecretKey::new(&mut rng);
+        let nodes = (0..20).map(|_| rng_enr(&SecretKey::new(&mut rng))).collect::<Vec<_>>();
+        let tree = DnsTree::new(1, nodes, vec![], &key).unwrap();
+
+        let zone = tree.to_zone_file("nodes.example.org.", 3600);
+        assert!(zone.contains("$ORIGIN nodes.example.org.\n"));
+        assert!(zone.contains("$TTL 3600\n"));
+        assert!(zone.contains(&format!("@\tIN\tTXT\t\"{}\"\n", tree.root())));
+
+        let records = tree.txt_records("nodes.example.org");
+        assert_eq!(records.len(), tree.entries().len() + 1);
+        for (name, content) in records.into_iter().skip(1) {
+            let hash = name.strip_suffix(".nodes.example.org").unwrap();
+            let line = format!("{hash}\tIN\tTXT\t{}", txt_strings(&content));
+            assert!(zone.lines().any(|l| l == line));
+        }
+    }
+
+    #[test]
+    fn split_long_txt_strings() {
+        let content = "a".repeat(600);
+        let strings = txt_strings(&content);
+        assert_eq!(
+            strings,
+            format!("\"{}\" \"{}\" \"{}\"", "a".repeat(255), "a".repeat(255), "a".repeat(90))
+        );
+
+        assert_eq!(txt_strings("enrtree-branch:"), "\"enrtree-branch:\"");
+    }
+
+    #[test]
+    fn resolve_long_txt_records() {
+        let mut rng = thread_rng();
+        let key = SecretKey::new(&mut rng);
+        let nodes = (0..50).map(|_| rng_enr(&SecretKey::new(&mut rng))).collect::<Vec<_>>();
+        let tree = DnsTree::new(1, nodes, vec![], &key).unwrap();
+
+        let mut long_entries = 0;
+        for (_, content) in tree.txt_records("nodes.example.org") {
+            if content.len() > MAX_TXT_STRING_LEN {
+                long_entries += 1;
+            }
+
+            // the record as it is served by a name server and read by a resolver
+            let txt = TXT::new(txt_character_strings(&content));
+            assert_eq!(txt_record_text(&txt).unwrap(), content);
+        }
+        assert!(long_entries > 0);
+    }
+}
@@ -4,7 +4,7 @@ use parking_lot::RwLock;
 use std::{collections::HashMap, future::Future};
 use tracing::trace;
 pub use trust_dns_resolver::{error::ResolveError, TokioAsyncResolver};
-use trust_dns_resolver::{name_server::ConnectionProvider, AsyncResolver};
+use trust_dns_resolver::{name_server::ConnectionProvider, proto::rr::rdata::TXT, AsyncResolver};
 
 /// A type that can lookup DNS entries
 pub trait Resolver: Send + Sync + Unpin + 'static {
@@ -24,13 +24,20 @@ impl<P: ConnectionProvider> Resolver for AsyncResolver<P> {
             }
             Ok(lookup) => {
                 let txt = lookup.into_iter().next()?;
-                let entry = txt.iter().next()?;
-                String::from_utf8(entry.to_vec()).ok()
+                txt_record_text(&txt)
             }
         }
     }
 }
 
+/// Returns the text of a TXT record.
+///
+/// Texts longer than 255 bytes are split into multiple character-strings, which are joined
+/// without a separator.
+pub(crate) fn txt_record_text(txt: &TXT) -> Option<String> {
+    String::from_utf8(txt.iter().flat_map(|string| string.iter().copied()).collect()).ok()
+}
+
 /// An asynchronous DNS resolver
 ///
 /// See also [`TokioAsyncResolver`]
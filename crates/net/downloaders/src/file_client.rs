@@ -12,7 +12,7 @@ use reth_network_p2p::{
     priority::Priority,
 };
 use reth_network_peers::PeerId;
-use reth_primitives::{BlockBody, Header, SealedHeader};
+use reth_primitives::{Block, BlockBody, Header, SealedHeader};
 use thiserror::Error;
 use tokio::{fs::File, io::AsyncReadExt};
 use tokio_stream::StreamExt;
@@ -92,6 +92,20 @@ impl FileClient {
         Ok(Self::from_reader(&reader[..], file_len).await?.file_client)
     }
 
+    /// Create a new file client from blocks that have already been decoded, e.g. from an archive.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = Block>) -> Self {
+        let mut headers = HashMap::default();
+        let mut hash_to_number = HashMap::default();
+        let mut bodies = HashMap::default();
+        for block in blocks {
+            let block_hash = block.header.hash_slow();
+            hash_to_number.insert(block_hash, block.header.number);
+            headers.insert(block.header.number, block.header.clone());
+            bodies.insert(block_hash, block.into());
+        }
+        Self { headers, hash_to_number, bodies }
+    }
+
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
         self.headers.get(&self.max_block()?).map(|h| h.hash_slow())
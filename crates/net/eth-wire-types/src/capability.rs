@@ -48,6 +48,17 @@ impl Capability {
         Self::eth(EthVersion::Eth68)
     }
 
+    /// Returns the `snap/1` capability.
+    pub const fn snap() -> Self {
+        Self::new_static("snap", crate::SNAP_VERSION)
+    }
+
+    /// Whether this is snap v1.
+    #[inline]
+    pub fn is_snap_v1(&self) -> bool {
+        self.name == "snap" && self.version == crate::SNAP_VERSION
+    }
+
     /// Whether this is eth v66 protocol.
     #[inline]
     pub fn is_eth_v66(&self) -> bool {
@@ -40,3 +40,6 @@ pub use disconnect_reason::*;
 
 pub mod capability;
 pub use capability::*;
+
+pub mod snap;
+pub use snap::*;
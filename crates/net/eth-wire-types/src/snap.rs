@@ -0,0 +1,511 @@
+//! Implements the `snap/1` protocol messages:
+//! <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+//!
+//! Unlike `eth`, every `snap` message carries its request id as the first field of the message
+//! itself, so the types of this module are not wrapped in a
+//! [`RequestPair`](crate::message::RequestPair).
+
+use alloy_consensus::constants::{EMPTY_ROOT_HASH, KECCAK_EMPTY};
+use alloy_primitives::{
+    bytes::{Buf, BufMut},
+    Bytes, B256, U256,
+};
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use reth_codecs_derive::add_arbitrary_tests;
+
+/// The current version of the `snap` protocol.
+pub const SNAP_VERSION: usize = 1;
+
+/// The number of message ids reserved by the `snap/1` protocol.
+pub const SNAP_MESSAGE_COUNT: u8 = 8;
+
+/// Requests an unknown number of accounts from the state trie with the given root, starting at
+/// the given account hash, along with the proofs of the range boundaries.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetAccountRange {
+    /// Request id to match up responses with.
+    pub request_id: u64,
+    /// Root hash of the account trie to serve.
+    pub root_hash: B256,
+    /// Account hash of the first account to retrieve.
+    pub starting_hash: B256,
+    /// Account hash after which to stop serving data.
+    pub limit_hash: B256,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetAccountRange`], containing consecutive accounts of the requested trie,
+/// and the proofs of the first and last account of the range.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp, 10)]
+pub struct AccountRange {
+    /// Id of the request this is a response for.
+    pub request_id: u64,
+    /// List of consecutive accounts from the trie.
+    pub accounts: Vec<AccountData>,
+    /// List of trie nodes proving the account range.
+    pub proof: Vec<Bytes>,
+}
+
+/// An account of an [`AccountRange`] response.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct AccountData {
+    /// Hash of the account address.
+    pub hash: B256,
+    /// The account, in the slim format.
+    pub account: SnapAccount,
+}
+
+/// An account as transferred by the `snap` protocol.
+///
+/// The account is encoded in the "slim" format, where the storage root and the code hash are
+/// encoded as empty strings if the account has no storage or no code respectively.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct SnapAccount {
+    /// Account nonce.
+    pub nonce: u64,
+    /// Account balance.
+    pub balance: U256,
+    /// Root hash of the account storage trie.
+    pub storage_root: B256,
+    /// Hash of the account bytecode.
+    pub code_hash: B256,
+}
+
+impl Default for SnapAccount {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        }
+    }
+}
+
+impl SnapAccount {
+    /// Returns the slim encoding of the storage root, which is empty for the empty root.
+    fn slim_storage_root(&self) -> &[u8] {
+        if self.storage_root == EMPTY_ROOT_HASH {
+            &[]
+        } else {
+            self.storage_root.as_slice()
+        }
+    }
+
+    /// Returns the slim encoding of the code hash, which is empty for accounts without code.
+    fn slim_code_hash(&self) -> &[u8] {
+        if self.code_hash == KECCAK_EMPTY {
+            &[]
+        } else {
+            self.code_hash.as_slice()
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        self.nonce.length() +
+            self.balance.length() +
+            self.slim_storage_root().length() +
+            self.slim_code_hash().length()
+    }
+
+    /// Decodes a slim hash, mapping the empty string to the given default.
+    fn decode_slim_hash(buf: &mut &[u8], default: B256) -> alloy_rlp::Result<B256> {
+        let bytes = Header::decode_bytes(buf, false)?;
+        match bytes.len() {
+            0 => Ok(default),
+            32 => Ok(B256::from_slice(bytes)),
+            _ => Err(alloy_rlp::Error::UnexpectedLength),
+        }
+    }
+}
+
+impl Encodable for SnapAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        self.slim_storage_root().encode(out);
+        self.slim_code_hash().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for SnapAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+
+        let initial_length = buf.len();
+        let account = Self {
+            nonce: u64::decode(buf)?,
+            balance: U256::decode(buf)?,
+            storage_root: Self::decode_slim_hash(buf, EMPTY_ROOT_HASH)?,
+            code_hash: Self::decode_slim_hash(buf, KECCAK_EMPTY)?,
+        };
+
+        if initial_length - buf.len() != header.payload_length {
+            return Err(alloy_rlp::Error::UnexpectedLength)
+        }
+        Ok(account)
+    }
+}
+
+/// Requests the storage slots of multiple accounts' storage tries.
+///
+/// The starting and limit hashes only apply to the first account of the request, and are empty
+/// if the full storage of the account is requested. All the other accounts are requested in full.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetStorageRanges {
+    /// Request id to match up responses with.
+    pub request_id: u64,
+    /// Root hash of the account trie to serve.
+    pub root_hash: B256,
+    /// Account hashes of the storage tries to serve.
+    pub account_hashes: Vec<B256>,
+    /// Storage slot hash of the first slot to retrieve.
+    pub starting_hash: Bytes,
+    /// Storage slot hash after which to stop serving data.
+    pub limit_hash: Bytes,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetStorageRanges`], containing the storage slots of the requested accounts.
+///
+/// The proof is only present if the last account's storage range is incomplete, or if it doesn't
+/// start at the first slot.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp, 10)]
+pub struct StorageRanges {
+    /// Id of the request this is a response for.
+    pub request_id: u64,
+    /// List of consecutive slots from the trie, one list per account.
+    pub slots: Vec<Vec<StorageData>>,
+    /// List of trie nodes proving the last slot range.
+    pub proof: Vec<Bytes>,
+}
+
+/// A storage slot of a [`StorageRanges`] response.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct StorageData {
+    /// Hash of the storage slot key.
+    pub hash: B256,
+    /// RLP encoded value of the storage slot.
+    pub data: Bytes,
+}
+
+/// Requests a number of contract bytecodes by hash.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetByteCodes {
+    /// Request id to match up responses with.
+    pub request_id: u64,
+    /// Code hashes to retrieve the code for.
+    pub hashes: Vec<B256>,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`], containing the requested bytecodes in request order.
+///
+/// Unavailable bytecodes are skipped.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct ByteCodes {
+    /// Id of the request this is a response for.
+    pub request_id: u64,
+    /// The requested bytecodes.
+    pub codes: Vec<Bytes>,
+}
+
+/// Requests a number of state (either account or storage) trie nodes by path.
+///
+/// Each path set starts with the compact encoded path of an account trie node. A path set with
+/// more elements requests the storage trie nodes at the following compact encoded paths of the
+/// storage trie of the account whose hash is the first element.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct GetTrieNodes {
+    /// Request id to match up responses with.
+    pub request_id: u64,
+    /// Root hash of the account trie to serve.
+    pub root_hash: B256,
+    /// Trie paths to retrieve the nodes for, grouped by account.
+    pub paths: Vec<Vec<Bytes>>,
+    /// Soft limit at which to stop returning data.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`], containing the requested trie nodes in request order.
+///
+/// The response stops at the first unavailable node.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
+#[add_arbitrary_tests(rlp)]
+pub struct TrieNodes {
+    /// Id of the request this is a response for.
+    pub request_id: u64,
+    /// The requested trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents message IDs for `snap` protocol messages.
+///
+/// The ids are relative to the offset of the `snap` capability in the multiplexed connection.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMessageId {
+    /// Requests an account range.
+    GetAccountRange = 0x00,
+    /// Represents an account range.
+    AccountRange = 0x01,
+    /// Requests storage ranges.
+    GetStorageRanges = 0x02,
+    /// Represents storage ranges.
+    StorageRanges = 0x03,
+    /// Requests bytecodes.
+    GetByteCodes = 0x04,
+    /// Represents bytecodes.
+    ByteCodes = 0x05,
+    /// Requests trie nodes.
+    GetTrieNodes = 0x06,
+    /// Represents trie nodes.
+    TrieNodes = 0x07,
+}
+
+impl Encodable for SnapMessageId {
+    fn encode(&self, out: &mut dyn BufMut) {
+        out.put_u8(*self as u8);
+    }
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Decodable for SnapMessageId {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let id = match buf.first().ok_or(alloy_rlp::Error::InputTooShort)? {
+            0x00 => Self::GetAccountRange,
+            0x01 => Self::AccountRange,
+            0x02 => Self::GetStorageRanges,
+            0x03 => Self::StorageRanges,
+            0x04 => Self::GetByteCodes,
+            0x05 => Self::ByteCodes,
+            0x06 => Self::GetTrieNodes,
+            0x07 => Self::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid snap message ID")),
+        };
+        buf.advance(1);
+        Ok(id)
+    }
+}
+
+/// Represents a message in the `snap/1` protocol.
+///
+/// All `snap` messages are request-response pairs, correlated by their request id.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMessage {
+    /// Represents a `GetAccountRange` request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an `AccountRange` response.
+    AccountRange(AccountRange),
+    /// Represents a `GetStorageRanges` request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a `StorageRanges` response.
+    StorageRanges(StorageRanges),
+    /// Represents a `GetByteCodes` request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a `ByteCodes` response.
+    ByteCodes(ByteCodes),
+    /// Represents a `GetTrieNodes` request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a `TrieNodes` response.
+    TrieNodes(TrieNodes),
+}
+
+impl SnapMessage {
+    /// Decodes a message from its message id followed by its rlp bytes.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let message = match SnapMessageId::decode(buf)? {
+            SnapMessageId::GetAccountRange => Self::GetAccountRange(GetAccountRange::decode(buf)?),
+            SnapMessageId::AccountRange => Self::AccountRange(AccountRange::decode(buf)?),
+            SnapMessageId::GetStorageRanges => {
+                Self::GetStorageRanges(GetStorageRanges::decode(buf)?)
+            }
+            SnapMessageId::StorageRanges => Self::StorageRanges(StorageRanges::decode(buf)?),
+            SnapMessageId::GetByteCodes => Self::GetByteCodes(GetByteCodes::decode(buf)?),
+            SnapMessageId::ByteCodes => Self::ByteCodes(ByteCodes::decode(buf)?),
+            SnapMessageId::GetTrieNodes => Self::GetTrieNodes(GetTrieNodes::decode(buf)?),
+            SnapMessageId::TrieNodes => Self::TrieNodes(TrieNodes::decode(buf)?),
+        };
+        Ok(message)
+    }
+
+    /// Returns the message's ID.
+    pub const fn message_id(&self) -> SnapMessageId {
+        match self {
+            Self::GetAccountRange(_) => SnapMessageId::GetAccountRange,
+            Self::AccountRange(_) => SnapMessageId::AccountRange,
+            Self::GetStorageRanges(_) => SnapMessageId::GetStorageRanges,
+            Self::StorageRanges(_) => SnapMessageId::StorageRanges,
+            Self::GetByteCodes(_) => SnapMessageId::GetByteCodes,
+            Self::ByteCodes(_) => SnapMessageId::ByteCodes,
+            Self::GetTrieNodes(_) => SnapMessageId::GetTrieNodes,
+            Self::TrieNodes(_) => SnapMessageId::TrieNodes,
+        }
+    }
+
+    /// Returns the request id of the message.
+    pub const fn request_id(&self) -> u64 {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id,
+            Self::AccountRange(msg) => msg.request_id,
+            Self::GetStorageRanges(msg) => msg.request_id,
+            Self::StorageRanges(msg) => msg.request_id,
+            Self::GetByteCodes(msg) => msg.request_id,
+            Self::ByteCodes(msg) => msg.request_id,
+            Self::GetTrieNodes(msg) => msg.request_id,
+            Self::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Returns `true` if the message is a request.
+    pub const fn is_request(&self) -> bool {
+        matches!(
+            self,
+            Self::GetAccountRange(_) |
+                Self::GetStorageRanges(_) |
+                Self::GetByteCodes(_) |
+                Self::GetTrieNodes(_)
+        )
+    }
+}
+
+impl Encodable for SnapMessage {
+    /// Encodes the message id as a single byte, followed by the message.
+    fn encode(&self, out: &mut dyn BufMut) {
+        self.message_id().encode(out);
+        match self {
+            Self::GetAccountRange(msg) => msg.encode(out),
+            Self::AccountRange(msg) => msg.encode(out),
+            Self::GetStorageRanges(msg) => msg.encode(out),
+            Self::StorageRanges(msg) => msg.encode(out),
+            Self::GetByteCodes(msg) => msg.encode(out),
+            Self::ByteCodes(msg) => msg.encode(out),
+            Self::GetTrieNodes(msg) => msg.encode(out),
+            Self::TrieNodes(msg) => msg.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = match self {
+            Self::GetAccountRange(msg) => msg.length(),
+            Self::AccountRange(msg) => msg.length(),
+            Self::GetStorageRanges(msg) => msg.length(),
+            Self::StorageRanges(msg) => msg.length(),
+            Self::GetByteCodes(msg) => msg.length(),
+            Self::ByteCodes(msg) => msg.length(),
+            Self::GetTrieNodes(msg) => msg.length(),
+            Self::TrieNodes(msg) => msg.length(),
+        };
+        self.message_id().length() + payload_length
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, hex};
+
+    #[test]
+    fn encode_slim_account() {
+        let account = SnapAccount { nonce: 1, balance: U256::from(2), ..Default::default() };
+        let mut data = vec![];
+        account.encode(&mut data);
+        assert_eq!(data, hex!("c401028080"));
+        assert_eq!(data.len(), account.length());
+        assert_eq!(SnapAccount::decode(&mut &data[..]).unwrap(), account);
+
+        let account = SnapAccount {
+            storage_root: b256!("0101010101010101010101010101010101010101010101010101010101010101"),
+            ..account
+        };
+        let mut data = vec![];
+        account.encode(&mut data);
+        assert_eq!(data.len(), account.length());
+        assert_eq!(SnapAccount::decode(&mut &data[..]).unwrap(), account);
+
+        assert_eq!(
+            SnapAccount::decode(&mut &hex!("c6010282010180")[..]),
+            Err(alloy_rlp::Error::UnexpectedLength)
+        );
+    }
+
+    #[test]
+    fn roundtrip_snap_message() {
+        let messages = [
+            SnapMessage::GetAccountRange(GetAccountRange {
+                request_id: 1,
+                root_hash: B256::repeat_byte(1),
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 512 * 1024,
+            }),
+            SnapMessage::StorageRanges(StorageRanges {
+                request_id: 2,
+                slots: vec![vec![StorageData {
+                    hash: B256::repeat_byte(2),
+                    data: hex!("8203e8").into(),
+                }]],
+                proof: vec![],
+            }),
+            SnapMessage::TrieNodes(TrieNodes { request_id: 3, nodes: vec![hex!("c0").into()] }),
+        ];
+
+        for message in messages {
+            let mut data = vec![];
+            message.encode(&mut data);
+            assert_eq!(data[0], message.message_id() as u8);
+            assert_eq!(data.len(), message.length());
+            assert_eq!(SnapMessage::decode_message(&mut &data[..]).unwrap(), message);
+        }
+
+        assert!(SnapMessage::decode_message(&mut &hex!("08c0")[..]).is_err());
+    }
+}
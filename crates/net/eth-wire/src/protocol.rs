@@ -1,6 +1,6 @@
 //! A Protocol defines a P2P subprotocol in a `RLPx` connection
 
-use crate::{Capability, EthMessageID, EthVersion};
+use crate::{Capability, EthMessageID, EthVersion, SNAP_MESSAGE_COUNT};
 
 /// Type that represents a [Capability] and the number of messages it uses.
 ///
@@ -45,6 +45,11 @@ impl Protocol {
         Self::eth(EthVersion::Eth68)
     }
 
+    /// Returns the `snap/1` protocol.
+    pub const fn snap() -> Self {
+        Self::new(Capability::snap(), SNAP_MESSAGE_COUNT)
+    }
+
     /// Consumes the type and returns a tuple of the [Capability] and number of messages.
     #[inline]
     pub(crate) fn split(self) -> (Capability, u8) {
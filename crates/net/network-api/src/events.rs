@@ -24,6 +24,10 @@ pub trait NetworkEventListenerProvider: Send + Sync {
     ///
     /// This stream yields [`DiscoveryEvent`]s for each peer that is discovered.
     fn discovery_listener(&self) -> UnboundedReceiverStream<DiscoveryEvent>;
+    /// Creates a new [`PeerMessageEvent`] listener channel.
+    ///
+    /// This stream yields a [`PeerMessageEvent`] for every `eth` message exchanged with a peer.
+    fn peer_message_listener(&self) -> EventStream<PeerMessageEvent>;
 }
 
 /// (Non-exhaustive) Events emitted by the network that are of interest for subscribers.
@@ -62,6 +66,25 @@ pub enum NetworkEvent {
     PeerRemoved(PeerId),
 }
 
+/// Events emitted for the `eth` messages exchanged with a peer over an active session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerMessageEvent {
+    /// A message was sent to the peer.
+    Sent {
+        /// The identifier of the peer the message was sent to.
+        peer_id: PeerId,
+        /// The id of the message.
+        message_id: EthMessageID,
+    },
+    /// A message was received from the peer.
+    Received {
+        /// The identifier of the peer the message was received from.
+        peer_id: PeerId,
+        /// The id of the message.
+        message_id: EthMessageID,
+    },
+}
+
 /// Events produced by the `Discovery` manager.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DiscoveryEvent {
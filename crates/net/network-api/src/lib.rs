@@ -22,18 +22,24 @@ pub mod noop;
 pub mod test_utils;
 
 pub use alloy_rpc_types_admin::EthProtocolInfo;
+pub use reth_net_banlist::{BanEntry, BanTarget, IpNet};
 use reth_network_p2p::sync::NetworkSyncUpdater;
 pub use reth_network_p2p::BlockClient;
-pub use reth_network_types::{PeerKind, Reputation, ReputationChangeKind};
+pub use reth_network_types::{PeerKind, Reputation, ReputationChangeKind, ReputationChangeRecord};
 
 pub use downloaders::BlockDownloaderProvider;
 pub use error::NetworkError;
 pub use events::{
-    DiscoveredEvent, DiscoveryEvent, NetworkEvent, NetworkEventListenerProvider, PeerRequest,
-    PeerRequestSender,
+    DiscoveredEvent, DiscoveryEvent, NetworkEvent, NetworkEventListenerProvider, PeerMessageEvent,
+    PeerRequest, PeerRequestSender,
 };
 
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use reth_eth_wire_types::{capability::Capabilities, DisconnectReason, EthVersion, Status};
 use reth_network_peers::NodeRecord;
@@ -207,6 +213,22 @@ pub trait Peers: PeersInfo {
         &self,
         peer_id: PeerId,
     ) -> impl Future<Output = Result<Option<Reputation>, NetworkError>> + Send;
+
+    /// Bans the peer indefinitely or for the given duration, disconnecting it if connected.
+    fn ban_peer(&self, peer: PeerId, duration: Option<Duration>);
+
+    /// Lifts the ban of the peer.
+    fn unban_peer(&self, peer: PeerId);
+
+    /// Bans all IPs of the subnet indefinitely or for the given duration, disconnecting connected
+    /// peers in the subnet.
+    fn ban_subnet(&self, subnet: IpNet, duration: Option<Duration>);
+
+    /// Lifts the ban of the subnet.
+    fn unban_subnet(&self, subnet: IpNet);
+
+    /// Returns all active bans.
+    fn get_bans(&self) -> impl Future<Output = Result<Vec<BanEntry>, NetworkError>> + Send;
 }
 
 /// Info about an active peer session.
@@ -236,6 +258,10 @@ pub struct PeerInfo {
     pub session_established: Instant,
     /// The peer's connection kind
     pub kind: PeerKind,
+    /// The peer's current reputation
+    pub reputation: Reputation,
+    /// The most recent reputation changes applied to the peer, oldest first
+    pub reputation_history: Vec<ReputationChangeRecord>,
 }
 
 /// The direction of the connection.
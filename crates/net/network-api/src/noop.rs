@@ -3,15 +3,24 @@
 //! This is useful for wiring components together that don't require network but still need to be
 //! generic over it.
 
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
 use alloy_rpc_types_admin::EthProtocolInfo;
 use enr::{secp256k1::SecretKey, Enr};
 use reth_eth_wire_types::{DisconnectReason, ProtocolVersion};
 use reth_network_peers::NodeRecord;
 use reth_network_types::{PeerKind, Reputation, ReputationChangeKind};
+use reth_tokio_util::{EventSender, EventStream};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use crate::{NetworkError, NetworkInfo, NetworkStatus, PeerId, PeerInfo, Peers, PeersInfo};
+use crate::{
+    BanEntry, DiscoveryEvent, IpNet, NetworkError, NetworkEvent, NetworkEventListenerProvider,
+    NetworkInfo, NetworkStatus, PeerId, PeerInfo, PeerMessageEvent, Peers, PeersInfo,
+};
 
 /// A type that implements all network trait that does nothing.
 ///
@@ -116,4 +125,30 @@ impl Peers for NoopNetwork {
     async fn reputation_by_id(&self, _peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         Ok(None)
     }
+
+    fn ban_peer(&self, _peer: PeerId, _duration: Option<Duration>) {}
+
+    fn unban_peer(&self, _peer: PeerId) {}
+
+    fn ban_subnet(&self, _subnet: IpNet, _duration: Option<Duration>) {}
+
+    fn unban_subnet(&self, _subnet: IpNet) {}
+
+    async fn get_bans(&self) -> Result<Vec<BanEntry>, NetworkError> {
+        Ok(vec![])
+    }
+}
+
+impl NetworkEventListenerProvider for NoopNetwork {
+    fn event_listener(&self) -> EventStream<NetworkEvent> {
+        EventSender::new(1).new_listener()
+    }
+
+    fn discovery_listener(&self) -> UnboundedReceiverStream<DiscoveryEvent> {
+        UnboundedReceiverStream::new(mpsc::unbounded_channel().1)
+    }
+
+    fn peer_message_listener(&self) -> EventStream<PeerMessageEvent> {
+        EventSender::new(1).new_listener()
+    }
 }
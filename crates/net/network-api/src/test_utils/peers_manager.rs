@@ -1,9 +1,10 @@
 //! Interaction with `reth_network::PeersManager`, for integration testing. Otherwise
 //! `reth_network::NetworkManager` manages `reth_network::PeersManager`.
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
 use derive_more::Constructor;
+use reth_net_banlist::{BanEntry, IpNet};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::{Peer, ReputationChangeKind};
 use tokio::sync::{mpsc, oneshot};
@@ -61,6 +62,38 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Bans the peer indefinitely or for the given duration.
+    ///
+    /// If currently connected this will disconnect the session.
+    pub fn ban_peer(&self, peer_id: PeerId, duration: Option<Duration>) {
+        self.send(PeerCommand::BanPeer(peer_id, duration));
+    }
+
+    /// Lifts the ban of the peer.
+    pub fn unban_peer(&self, peer_id: PeerId) {
+        self.send(PeerCommand::UnbanPeer(peer_id));
+    }
+
+    /// Bans all IPs of the subnet indefinitely or for the given duration.
+    ///
+    /// Connected peers in the subnet are disconnected.
+    pub fn ban_subnet(&self, subnet: IpNet, duration: Option<Duration>) {
+        self.send(PeerCommand::BanSubnet(subnet, duration));
+    }
+
+    /// Lifts the ban of the subnet.
+    pub fn unban_subnet(&self, subnet: IpNet) {
+        self.send(PeerCommand::UnbanSubnet(subnet));
+    }
+
+    /// Returns all active bans.
+    pub async fn bans(&self) -> Vec<BanEntry> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetBans(tx));
+
+        rx.await.unwrap_or_default()
+    }
 }
 
 /// Commands the `PeersManager` listens for.
@@ -78,4 +111,16 @@ pub enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Ban a peer indefinitely or for the given duration
+    ///
+    /// If currently connected this will disconnect the session
+    BanPeer(PeerId, Option<Duration>),
+    /// Lift the ban of a peer
+    UnbanPeer(PeerId),
+    /// Ban all IPs of a subnet indefinitely or for the given duration
+    BanSubnet(IpNet, Option<Duration>),
+    /// Lift the ban of a subnet
+    UnbanSubnet(IpNet),
+    /// Get all active bans
+    GetBans(oneshot::Sender<Vec<BanEntry>>),
 }
@@ -19,7 +19,10 @@ pub mod session;
 /// [`BackoffKind`] definition.
 mod backoff;
 
-pub use peers::reputation::{Reputation, ReputationChangeKind, ReputationChangeWeights};
+pub use peers::reputation::{
+    Reputation, ReputationChangeKind, ReputationChangeRecord, ReputationChangeWeights,
+    ReputationPolicy,
+};
 
 pub use backoff::BackoffKind;
 pub use peers::{
@@ -7,7 +7,7 @@ use std::{
     time::Duration,
 };
 
-use reth_net_banlist::BanList;
+use reth_net_banlist::{BanEntry, BanList};
 use reth_network_peers::{NodeRecord, TrustedPeer};
 use tracing::info;
 
@@ -291,6 +291,23 @@ impl PeersConfig {
         Ok(self.with_basic_nodes(nodes))
     }
 
+    /// Read from file bans to restore at launch, skipping expired ones. Ignored if None.
+    pub fn with_bans_from_file(
+        mut self,
+        optional_file: Option<impl AsRef<Path>>,
+    ) -> Result<Self, io::Error> {
+        let Some(file_path) = optional_file else { return Ok(self) };
+        let reader = match std::fs::File::open(file_path.as_ref()) {
+            Ok(file) => io::BufReader::new(file),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(self),
+            Err(e) => Err(e)?,
+        };
+        info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved bans");
+        let bans: Vec<BanEntry> = serde_json::from_reader(reader)?;
+        self.ban_list.extend(bans);
+        Ok(self)
+    }
+
     /// Returns settings for testing
     #[cfg(any(test, feature = "test-utils"))]
     pub fn test() -> Self {
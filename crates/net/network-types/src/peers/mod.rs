@@ -5,7 +5,12 @@ pub mod reputation;
 pub mod state;
 
 pub use config::{ConnectionsConfig, PeersConfig};
-pub use reputation::{Reputation, ReputationChange, ReputationChangeKind, ReputationChangeWeights};
+pub use reputation::{
+    Reputation, ReputationChange, ReputationChangeKind, ReputationChangeRecord,
+    ReputationChangeWeights, ReputationPolicy,
+};
+
+use std::collections::VecDeque;
 
 use reth_ethereum_forks::ForkId;
 use tracing::trace;
@@ -14,6 +19,7 @@ use crate::{
     is_banned_reputation, PeerAddr, PeerConnectionState, PeerKind, ReputationChangeOutcome,
     DEFAULT_REPUTATION,
 };
+use reputation::MAX_REPUTATION_HISTORY;
 
 /// Tracks info about a single peer.
 #[derive(Debug, Clone)]
@@ -35,6 +41,8 @@ pub struct Peer {
     /// Counts number of times the peer was backed off due to a severe
     /// [`BackoffKind`](crate::BackoffKind).
     pub severe_backoff_counter: u8,
+    /// The most recent reputation changes that were applied to the peer, oldest first.
+    pub reputation_history: VecDeque<ReputationChangeRecord>,
 }
 
 // === impl Peer ===
@@ -66,6 +74,7 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            reputation_history: VecDeque::new(),
         }
     }
 
@@ -106,6 +115,26 @@ impl Peer {
         ReputationChangeOutcome::None
     }
 
+    /// Remembers a reputation change that was applied to the peer at the given UNIX timestamp.
+    ///
+    /// Only the most recent [`MAX_REPUTATION_HISTORY`] changes are kept.
+    pub fn record_reputation_change(
+        &mut self,
+        kind: ReputationChangeKind,
+        change: Reputation,
+        timestamp: u64,
+    ) {
+        if self.reputation_history.len() == MAX_REPUTATION_HISTORY {
+            self.reputation_history.pop_front();
+        }
+        self.reputation_history.push_back(ReputationChangeRecord {
+            kind,
+            change,
+            reputation: self.reputation,
+            timestamp,
+        });
+    }
+
     /// Returns true if the peer's reputation is below the banned threshold.
     #[inline]
     pub const fn is_banned(&self) -> bool {
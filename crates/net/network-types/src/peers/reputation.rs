@@ -1,5 +1,9 @@
 //! Peer reputation management
 
+use std::fmt;
+
+use crate::PeerKind;
+
 /// The default reputation of a peer
 pub const DEFAULT_REPUTATION: Reputation = 0;
 
@@ -51,8 +55,14 @@ pub const fn is_banned_reputation(reputation: i32) -> bool {
 /// The type that tracks the reputation score.
 pub type Reputation = i32;
 
+/// The maximum number of reputation changes that are remembered per peer, see
+/// [`ReputationChangeRecord`].
+pub const MAX_REPUTATION_HISTORY: usize = 32;
+
 /// Various kinds of reputation changes.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum ReputationChangeKind {
     /// Received an unspecific bad message from the peer
     BadMessage,
@@ -179,6 +189,73 @@ impl Default for ReputationChangeWeights {
     }
 }
 
+/// Decides how much a [`ReputationChangeKind`] changes the reputation of a peer.
+///
+/// The default policy is [`ReputationChangeWeights`].
+pub trait ReputationPolicy: fmt::Debug + Send + Sync + 'static {
+    /// Returns the change to apply to the reputation of a peer of the given [`PeerKind`], or
+    /// `None` if the peer is exempt from the change.
+    ///
+    /// This is never called for [`ReputationChangeKind::Reset`], which always restores the
+    /// [`DEFAULT_REPUTATION`].
+    fn reputation_change(
+        &self,
+        kind: ReputationChangeKind,
+        peer_kind: PeerKind,
+    ) -> Option<ReputationChange>;
+
+    /// Returns the change to apply to the reputation of a peer of the given [`PeerKind`] after a
+    /// failed connection attempt or a dropped session that is not backed off, or `None` if the
+    /// peer is exempt from the change.
+    ///
+    /// Defaults to [`Self::reputation_change`].
+    fn connection_failure_change(
+        &self,
+        kind: ReputationChangeKind,
+        peer_kind: PeerKind,
+    ) -> Option<ReputationChange> {
+        self.reputation_change(kind, peer_kind)
+    }
+}
+
+impl ReputationPolicy for ReputationChangeWeights {
+    /// Weighs the change with the configured weights.
+    ///
+    /// Trusted and static peers are exempt from changes that can be attributed to network
+    /// conditions, and are penalized at most by [`MAX_TRUSTED_PEER_REPUTATION_CHANGE`].
+    fn reputation_change(
+        &self,
+        kind: ReputationChangeKind,
+        peer_kind: PeerKind,
+    ) -> Option<ReputationChange> {
+        let change = self.change(kind).as_i32();
+        if peer_kind.is_trusted() || peer_kind.is_static() {
+            if matches!(
+                kind,
+                ReputationChangeKind::Dropped |
+                    ReputationChangeKind::BadAnnouncement |
+                    ReputationChangeKind::Timeout |
+                    ReputationChangeKind::AlreadySeenTransaction
+            ) {
+                return None
+            }
+
+            // this caps the reputation change to the maximum allowed for trusted peers
+            return Some(change.max(MAX_TRUSTED_PEER_REPUTATION_CHANGE).into())
+        }
+        Some(change.into())
+    }
+
+    /// Applies the configured weight to all peers, including trusted and static peers.
+    fn connection_failure_change(
+        &self,
+        kind: ReputationChangeKind,
+        _peer_kind: PeerKind,
+    ) -> Option<ReputationChange> {
+        Some(self.change(kind))
+    }
+}
+
 /// Represents a change in a peer's reputation.
 #[derive(Debug, Copy, Clone, Default)]
 pub struct ReputationChange(Reputation);
@@ -205,6 +282,21 @@ impl From<Reputation> for ReputationChange {
     }
 }
 
+/// A reputation change that was applied to a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ReputationChangeRecord {
+    /// The kind of the change.
+    pub kind: ReputationChangeKind,
+    /// By how much the reputation changed.
+    pub change: Reputation,
+    /// The reputation of the peer after the change.
+    pub reputation: Reputation,
+    /// The UNIX timestamp in seconds at which the change was applied.
+    pub timestamp: u64,
+}
+
 /// Outcomes when a reputation change is applied to a peer
 #[derive(Debug, Clone, Copy)]
 pub enum ReputationChangeOutcome {
@@ -6,6 +6,7 @@ use tokio::sync::mpsc;
 
 use crate::{
     eth_requests::EthRequestHandler,
+    snap::{SnapProtocolHandler, SnapRequestHandler},
     transactions::{TransactionsManager, TransactionsManagerConfig},
     NetworkHandle, NetworkManager,
 };
@@ -14,6 +15,9 @@ use crate::{
 /// 256 requests with malicious 10MB body requests is 2.6GB which can be absorbed by the node.
 pub(crate) const ETH_REQUEST_CHANNEL_CAPACITY: usize = 256;
 
+/// The max channel capacity of the `SnapRequestHandler`, responses are capped at 2MB.
+pub(crate) const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
 /// A builder that can configure all components of the network.
 #[allow(missing_debug_implementations)]
 pub struct NetworkBuilder<Tx, Eth> {
@@ -79,4 +83,17 @@ impl<Tx, Eth> NetworkBuilder<Tx, Eth> {
         let request_handler = EthRequestHandler::new(client, peers, rx);
         NetworkBuilder { network, request_handler, transactions }
     }
+
+    /// Installs the `snap` sub-protocol and creates a new [`SnapRequestHandler`] serving it.
+    ///
+    /// The client requesting state from the `snap` peers is available through
+    /// [`NetworkHandle::snap_client`].
+    pub fn snap_request_handler<Client>(&mut self, client: Client) -> SnapRequestHandler<Client> {
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        let peers = self.network.handle().peers_handle().clone();
+        let protocol = SnapProtocolHandler::new(peers, tx);
+        self.network.handle().set_snap_client(protocol.client());
+        self.network.add_rlpx_sub_protocol(protocol);
+        SnapRequestHandler::new(client, rx)
+    }
 }
@@ -8,7 +8,7 @@ use reth_discv5::NetworkStackId;
 use reth_dns_discovery::DnsDiscoveryConfig;
 use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
 use reth_network_peers::{mainnet_nodes, pk2id, sepolia_nodes, PeerId, TrustedPeer};
-use reth_network_types::{PeersConfig, SessionsConfig};
+use reth_network_types::{PeersConfig, ReputationPolicy, SessionsConfig};
 use reth_primitives::{ForkFilter, Head};
 use reth_storage_api::{noop::NoopBlockReader, BlockNumReader, BlockReader, HeaderProvider};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -83,6 +83,10 @@ pub struct NetworkConfig<C> {
     pub transactions_manager_config: TransactionsManagerConfig,
     /// The NAT resolver for external IP
     pub nat: Option<NatResolver>,
+    /// The policy that decides how reputation changes affect peers.
+    ///
+    /// The [`PeersConfig::reputation_weights`] are used if unset.
+    pub reputation_policy: Option<Box<dyn ReputationPolicy>>,
 }
 
 // === impl NetworkConfig ===
@@ -201,6 +205,8 @@ pub struct NetworkConfigBuilder {
     transactions_manager_config: TransactionsManagerConfig,
     /// The NAT resolver for external IP
     nat: Option<NatResolver>,
+    /// The policy that decides how reputation changes affect peers
+    reputation_policy: Option<Box<dyn ReputationPolicy>>,
 }
 
 // === impl NetworkConfigBuilder ===
@@ -233,6 +239,7 @@ impl NetworkConfigBuilder {
             block_import: None,
             transactions_manager_config: Default::default(),
             nat: None,
+            reputation_policy: None,
         }
     }
 
@@ -485,6 +492,14 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the policy that decides how reputation changes affect peers.
+    ///
+    /// This replaces the [`PeersConfig::reputation_weights`].
+    pub fn reputation_policy(mut self, reputation_policy: Box<dyn ReputationPolicy>) -> Self {
+        self.reputation_policy = Some(reputation_policy);
+        self
+    }
+
     /// Convenience function for creating a [`NetworkConfig`] with a noop provider that does
     /// nothing.
     pub fn build_with_noop_provider<ChainSpec>(
@@ -534,6 +549,7 @@ impl NetworkConfigBuilder {
             block_import,
             transactions_manager_config,
             nat,
+            reputation_policy,
         } = self;
 
         discovery_v5_builder = discovery_v5_builder.map(|mut builder| {
@@ -601,6 +617,7 @@ impl NetworkConfigBuilder {
             tx_gossip_disabled,
             transactions_manager_config,
             nat,
+            reputation_policy,
         }
     }
 }
@@ -235,7 +235,7 @@ impl Discovery {
             DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
                 self.on_node_record_update(record, None);
             }
-            DiscoveryUpdate::EnrForkId(node, fork_id) => {
+            DiscoveryUpdate::EnrForkId(node, fork_id, _) => {
                 self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
             }
             DiscoveryUpdate::Removed(peer_id) => {
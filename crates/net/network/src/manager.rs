@@ -189,9 +189,13 @@ impl NetworkManager {
             tx_gossip_disabled,
             transactions_manager_config: _,
             nat,
+            reputation_policy,
         } = config;
 
-        let peers_manager = PeersManager::new(peers_config);
+        let mut peers_manager = PeersManager::new(peers_config);
+        if let Some(reputation_policy) = reputation_policy {
+            peers_manager.set_reputation_policy(reputation_policy);
+        }
         let peers_handle = peers_manager.handle();
 
         let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
@@ -242,6 +246,8 @@ impl NetworkManager {
             extra_protocols,
         );
 
+        let message_event_sender = sessions.message_event_sender().clone();
+
         let state = NetworkState::new(
             crate::state::BlockNumReader::new(client),
             discovery,
@@ -268,6 +274,7 @@ impl NetworkManager {
             discv4,
             discv5,
             event_sender.clone(),
+            message_event_sender,
             nat,
         );
 
@@ -366,6 +373,15 @@ impl NetworkManager {
         Ok(())
     }
 
+    /// Collect the active bans from the [`NetworkManager`] and write them to the given
+    /// `persistent_bans_file`.
+    pub fn write_bans_to_file(&self, persistent_bans_file: &Path) -> Result<(), FsPathError> {
+        let bans = self.swarm.state().peers().ban_list().entries();
+        persistent_bans_file.parent().map(fs::create_dir_all).transpose()?;
+        reth_fs_util::write_json_file(persistent_bans_file, &bans)?;
+        Ok(())
+    }
+
     /// Returns a new [`FetchClient`] that can be cloned and shared.
     ///
     /// The [`FetchClient`] is the entrypoint for sending requests to the network.
@@ -895,7 +911,7 @@ impl NetworkManager {
                     .state()
                     .peers()
                     .peer_by_id(peer_id)
-                    .map(|(record, kind)| session.peer_info(&record, kind))
+                    .map(|(record, peer)| session.peer_info(&record, peer))
             })
             .collect()
     }
@@ -909,7 +925,7 @@ impl NetworkManager {
                 .state()
                 .peers()
                 .peer_by_id(peer_id)
-                .map(|(record, kind)| session.peer_info(&record, kind))
+                .map(|(record, peer)| session.peer_info(&record, peer))
         })
     }
 
@@ -4,6 +4,7 @@ use reth_metrics::{
     metrics::{Counter, Gauge},
     Metrics,
 };
+use reth_network_types::ReputationChangeKind;
 use reth_primitives::TxType;
 
 /// Scope for monitoring transactions sent from the manager to the tx manager
@@ -308,6 +309,65 @@ impl DisconnectMetrics {
     }
 }
 
+/// Metrics for reputation changes applied to peers, by [`ReputationChangeKind`]
+#[derive(Metrics)]
+#[metrics(scope = "network.reputation_changes")]
+pub struct ReputationChangeMetrics {
+    /// Number of applied `BadMessage` reputation changes
+    pub(crate) bad_message: Counter,
+
+    /// Number of applied `BadBlock` reputation changes
+    pub(crate) bad_block: Counter,
+
+    /// Number of applied `BadTransactions` reputation changes
+    pub(crate) bad_transactions: Counter,
+
+    /// Number of applied `BadAnnouncement` reputation changes
+    pub(crate) bad_announcement: Counter,
+
+    /// Number of applied `AlreadySeenTransaction` reputation changes
+    pub(crate) already_seen_transaction: Counter,
+
+    /// Number of applied `Timeout` reputation changes
+    pub(crate) timeout: Counter,
+
+    /// Number of applied `BadProtocol` reputation changes
+    pub(crate) bad_protocol: Counter,
+
+    /// Number of applied `FailedToConnect` reputation changes
+    pub(crate) failed_to_connect: Counter,
+
+    /// Number of applied `Dropped` reputation changes
+    pub(crate) dropped: Counter,
+
+    /// Number of applied `Reset` reputation changes
+    pub(crate) reset: Counter,
+
+    /// Number of applied `Other` reputation changes
+    pub(crate) other: Counter,
+}
+
+impl ReputationChangeMetrics {
+    /// Increments the proper counter for the given reputation change kind
+    pub(crate) fn increment(&self, kind: ReputationChangeKind) {
+        match kind {
+            ReputationChangeKind::BadMessage => self.bad_message.increment(1),
+            ReputationChangeKind::BadBlock => self.bad_block.increment(1),
+            ReputationChangeKind::BadTransactions => self.bad_transactions.increment(1),
+            ReputationChangeKind::BadAnnouncement => self.bad_announcement.increment(1),
+            ReputationChangeKind::AlreadySeenTransaction => {
+                self.already_seen_transaction.increment(1)
+            }
+            ReputationChangeKind::Timeout => self.timeout.increment(1),
+            ReputationChangeKind::BadProtocol => self.bad_protocol.increment(1),
+            ReputationChangeKind::FailedToConnect => self.failed_to_connect.increment(1),
+            ReputationChangeKind::Dropped => self.dropped.increment(1),
+            ReputationChangeKind::Reset => self.reset.increment(1),
+            ReputationChangeKind::Other(_) => self.other.increment(1),
+        }
+    }
+}
+
 /// Metrics for the `EthRequestHandler`
 #[derive(Metrics)]
 #[metrics(scope = "network")]
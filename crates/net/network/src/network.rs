@@ -2,8 +2,9 @@ use std::{
     net::SocketAddr,
     sync::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
-        Arc,
+        Arc, OnceLock,
     },
+    time::Duration,
 };
 
 use alloy_primitives::B256;
@@ -14,9 +15,9 @@ use reth_discv5::Discv5;
 use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, SharedTransactions};
 use reth_network_api::{
     test_utils::{PeersHandle, PeersHandleProvider},
-    BlockDownloaderProvider, DiscoveryEvent, NetworkError, NetworkEvent,
-    NetworkEventListenerProvider, NetworkInfo, NetworkStatus, PeerInfo, PeerRequest, Peers,
-    PeersInfo,
+    BanEntry, BlockDownloaderProvider, DiscoveryEvent, IpNet, NetworkError, NetworkEvent,
+    NetworkEventListenerProvider, NetworkInfo, NetworkStatus, PeerInfo, PeerMessageEvent,
+    PeerRequest, Peers, PeersInfo,
 };
 use reth_network_p2p::{
     sync::{NetworkSyncUpdater, SyncState, SyncStateProvider},
@@ -34,8 +35,8 @@ use tokio::sync::{
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 use crate::{
-    config::NetworkMode, protocol::RlpxSubProtocol, swarm::NetworkConnectionState,
-    transactions::TransactionsHandle, FetchClient,
+    config::NetworkMode, protocol::RlpxSubProtocol, snap::SnapClientHandle,
+    swarm::NetworkConnectionState, transactions::TransactionsHandle, FetchClient,
 };
 
 /// A _shareable_ network frontend. Used to interact with the network.
@@ -65,6 +66,7 @@ impl NetworkHandle {
         discv4: Option<Discv4>,
         discv5: Option<Discv5>,
         event_sender: EventSender<NetworkEvent>,
+        message_event_sender: EventSender<PeerMessageEvent>,
         nat: Option<NatResolver>,
     ) -> Self {
         let inner = NetworkInner {
@@ -82,7 +84,9 @@ impl NetworkHandle {
             discv4,
             discv5,
             event_sender,
+            message_event_sender,
             nat,
+            snap_client: OnceLock::new(),
         };
         Self { inner: Arc::new(inner) }
     }
@@ -96,6 +100,19 @@ impl NetworkHandle {
         &self.inner.to_manager_tx
     }
 
+    /// Returns the client requesting state from the connected peers over the `snap` protocol, if
+    /// the protocol is installed.
+    ///
+    /// See also [`NetworkBuilder::snap_request_handler`](crate::NetworkBuilder::snap_request_handler).
+    pub fn snap_client(&self) -> Option<SnapClientHandle> {
+        self.inner.snap_client.get().cloned()
+    }
+
+    /// Sets the client of the `snap` protocol, unless it's already set.
+    pub(crate) fn set_snap_client(&self, client: SnapClientHandle) {
+        let _ = self.inner.snap_client.set(client);
+    }
+
     /// Returns the mode of the network, either pow, or pos
     pub fn mode(&self) -> &NetworkMode {
         &self.inner.network_mode
@@ -199,6 +216,10 @@ impl NetworkEventListenerProvider for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::DiscoveryListener(tx));
         UnboundedReceiverStream::new(rx)
     }
+
+    fn peer_message_listener(&self) -> EventStream<PeerMessageEvent> {
+        self.inner.message_event_sender.new_listener()
+    }
 }
 
 impl NetworkProtocols for NetworkHandle {
@@ -338,6 +359,26 @@ impl Peers for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    fn ban_peer(&self, peer: PeerId, duration: Option<Duration>) {
+        self.inner.peers.ban_peer(peer, duration)
+    }
+
+    fn unban_peer(&self, peer: PeerId) {
+        self.inner.peers.unban_peer(peer)
+    }
+
+    fn ban_subnet(&self, subnet: IpNet, duration: Option<Duration>) {
+        self.inner.peers.ban_subnet(subnet, duration)
+    }
+
+    fn unban_subnet(&self, subnet: IpNet) {
+        self.inner.peers.unban_subnet(subnet)
+    }
+
+    async fn get_bans(&self) -> Result<Vec<BanEntry>, NetworkError> {
+        Ok(self.inner.peers.bans().await)
+    }
 }
 
 impl PeersHandleProvider for NetworkHandle {
@@ -437,8 +478,12 @@ struct NetworkInner {
     discv5: Option<Discv5>,
     /// Sender for high level network events.
     event_sender: EventSender<NetworkEvent>,
+    /// Sender for the messages exchanged with peers.
+    message_event_sender: EventSender<PeerMessageEvent>,
     /// The NAT resolver
     nat: Option<NatResolver>,
+    /// The client of the `snap` protocol, if it's installed.
+    snap_client: OnceLock<SnapClientHandle>,
 }
 
 /// Provides access to modify the network's additional protocol handlers.
@@ -6,21 +6,18 @@ use std::{
     io::{self},
     net::{IpAddr, SocketAddr},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use futures::StreamExt;
 use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
-use reth_net_banlist::BanList;
+use reth_net_banlist::{BanList, IpNet};
 use reth_network_api::test_utils::{PeerCommand, PeersHandle};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::{
-    peers::{
-        config::PeerBackoffDurations,
-        reputation::{DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE},
-    },
+    peers::{config::PeerBackoffDurations, reputation::DEFAULT_REPUTATION},
     ConnectionsConfig, Peer, PeerAddr, PeerConnectionState, PeerKind, PeersConfig,
-    ReputationChangeKind, ReputationChangeOutcome, ReputationChangeWeights,
+    ReputationChangeKind, ReputationChangeOutcome, ReputationPolicy,
 };
 use reth_primitives::ForkId;
 use thiserror::Error;
@@ -33,6 +30,7 @@ use tracing::{trace, warn};
 
 use crate::{
     error::SessionError,
+    metrics::ReputationChangeMetrics,
     session::{Direction, PendingSessionHandshakeError},
     swarm::NetworkConnectionState,
 };
@@ -61,7 +59,9 @@ pub struct PeersManager {
     /// Interval for triggering connections if there are free slots.
     refill_slots_interval: Interval,
     /// How to weigh reputation changes
-    reputation_weights: ReputationChangeWeights,
+    reputation_policy: Box<dyn ReputationPolicy>,
+    /// Counts the applied reputation changes by kind.
+    reputation_metrics: ReputationChangeMetrics,
     /// Tracks current slot stats.
     connection_info: ConnectionInfo,
     /// Tracks unwanted ips/peer ids.
@@ -139,7 +139,8 @@ impl PeersManager {
             manager_tx,
             handle_rx: UnboundedReceiverStream::new(handle_rx),
             queued_actions: Default::default(),
-            reputation_weights,
+            reputation_policy: Box::new(reputation_weights),
+            reputation_metrics: Default::default(),
             refill_slots_interval: tokio::time::interval(refill_slots_interval),
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info: ConnectionInfo::new(connection_info),
@@ -155,6 +156,11 @@ impl PeersManager {
         }
     }
 
+    /// Replaces the policy that decides how reputation changes affect peers.
+    pub(crate) fn set_reputation_policy(&mut self, reputation_policy: Box<dyn ReputationPolicy>) {
+        self.reputation_policy = reputation_policy;
+    }
+
     /// Returns a new [`PeersHandle`] that can send commands to this type.
     pub(crate) fn handle(&self) -> PeersHandle {
         PeersHandle::new(self.manager_tx.clone())
@@ -178,8 +184,8 @@ impl PeersManager {
         })
     }
 
-    /// Returns the `NodeRecord` and `PeerKind` for the given peer id
-    pub(crate) fn peer_by_id(&self, peer_id: PeerId) -> Option<(NodeRecord, PeerKind)> {
+    /// Returns the `NodeRecord` and the tracked [`Peer`] for the given peer id
+    pub(crate) fn peer_by_id(&self, peer_id: PeerId) -> Option<(NodeRecord, &Peer)> {
         self.peers.get(&peer_id).map(|v| {
             (
                 NodeRecord::new_with_ports(
@@ -188,7 +194,7 @@ impl PeersManager {
                     v.addr.udp().map(|addr| addr.port()),
                     peer_id,
                 ),
-                v.kind,
+                v,
             )
         })
     }
@@ -412,6 +418,50 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
     }
 
+    /// Bans the peer on request, indefinitely or for the given duration.
+    ///
+    /// The peer is removed from the set, which disconnects it if currently connected. Trusted peers
+    /// are banned but remain in the set. A duration that overflows the clock bans the peer
+    /// indefinitely.
+    fn ban_peer_manually(&mut self, peer_id: PeerId, duration: Option<Duration>) {
+        let until = duration.and_then(|duration| std::time::Instant::now().checked_add(duration));
+        self.ban_list.ban_peer_with(peer_id, until);
+        self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+        self.remove_peer(peer_id);
+    }
+
+    /// Lifts a ban that was requested manually, see [`Self::ban_peer_manually`].
+    fn unban_peer_manually(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.unban();
+        }
+        self.unban_peer(peer_id);
+    }
+
+    /// Bans all IPs of the subnet, indefinitely or for the given duration.
+    ///
+    /// All non-trusted peers in the subnet are removed from the set, which disconnects them if
+    /// currently connected. A duration that overflows the clock bans the subnet indefinitely.
+    fn ban_subnet(&mut self, subnet: IpNet, duration: Option<Duration>) {
+        let until = duration.and_then(|duration| std::time::Instant::now().checked_add(duration));
+        self.ban_list.ban_subnet_with(subnet, until);
+
+        let banned = self
+            .peers
+            .iter()
+            .filter(|(_, peer)| self.ban_list.is_banned_subnet_ip(&peer.addr.tcp().ip()))
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in banned {
+            self.remove_peer(peer_id);
+        }
+    }
+
+    /// Returns the ban list of the peer set.
+    pub(crate) const fn ban_list(&self) -> &BanList {
+        &self.ban_list
+    }
+
     /// Tick function to update reputation of all connected peers.
     /// Peers are rewarded with reputation increases for the time they are connected since the last
     /// tick. This is to prevent peers from being disconnected eventually due to slashed
@@ -442,36 +492,30 @@ impl PeersManager {
 
     /// Apply the corresponding reputation change to the given peer.
     ///
-    /// If the peer is a trusted peer, it will be exempt from reputation slashing for certain
-    /// reputation changes that can be attributed to network conditions. If the peer is a
-    /// trusted peer, it will also be less strict with the reputation slashing.
+    /// How much the reputation changes is decided by the [`ReputationPolicy`], which by default
+    /// exempts trusted and static peers from reputation slashing for certain reputation changes
+    /// that can be attributed to network conditions, and is less strict with them otherwise.
     pub(crate) fn apply_reputation_change(&mut self, peer_id: &PeerId, rep: ReputationChangeKind) {
         let outcome = if let Some(peer) = self.peers.get_mut(peer_id) {
+            let previous = peer.reputation;
             // First check if we should reset the reputation
-            if rep.is_reset() {
+            let outcome = if rep.is_reset() {
                 peer.reset_reputation()
             } else {
-                let mut reputation_change = self.reputation_weights.change(rep).as_i32();
-                if peer.is_trusted() || peer.is_static() {
-                    // exempt trusted and static peers from reputation slashing for
-                    if matches!(
-                        rep,
-                        ReputationChangeKind::Dropped |
-                            ReputationChangeKind::BadAnnouncement |
-                            ReputationChangeKind::Timeout |
-                            ReputationChangeKind::AlreadySeenTransaction
-                    ) {
-                        return
-                    }
-
-                    // also be less strict with the reputation slashing for trusted peers
-                    if reputation_change < MAX_TRUSTED_PEER_REPUTATION_CHANGE {
-                        // this caps the reputation change to the maximum allowed for trusted peers
-                        reputation_change = MAX_TRUSTED_PEER_REPUTATION_CHANGE;
-                    }
-                }
-                peer.apply_reputation(reputation_change)
-            }
+                let Some(reputation_change) =
+                    self.reputation_policy.reputation_change(rep, peer.kind)
+                else {
+                    return
+                };
+                peer.apply_reputation(reputation_change.as_i32())
+            };
+            peer.record_reputation_change(
+                rep,
+                peer.reputation.saturating_sub(previous),
+                unix_timestamp(),
+            );
+            self.reputation_metrics.increment(rep);
+            outcome
         } else {
             return
         };
@@ -630,10 +674,18 @@ impl PeersManager {
                     // connections, so we will hold off on attempting any new connections for a
                     // while
                     backoff_until = Some(backoff_time);
-                } else {
+                } else if let Some(change) =
+                    self.reputation_policy.connection_failure_change(reputation_change, peer.kind)
+                {
                     // If the error was not a backoff error, we reduce the peer's reputation
-                    let reputation_change = self.reputation_weights.change(reputation_change);
-                    peer.reputation = peer.reputation.saturating_add(reputation_change.as_i32());
+                    let previous = peer.reputation;
+                    peer.reputation = peer.reputation.saturating_add(change.as_i32());
+                    peer.record_reputation_change(
+                        reputation_change,
+                        peer.reputation.saturating_sub(previous),
+                        unix_timestamp(),
+                    );
+                    self.reputation_metrics.increment(reputation_change);
                 };
 
                 self.connection_info.decr_state(peer.state);
@@ -944,6 +996,15 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::BanPeer(peer_id, duration) => {
+                        self.ban_peer_manually(peer_id, duration)
+                    }
+                    PeerCommand::UnbanPeer(peer_id) => self.unban_peer_manually(peer_id),
+                    PeerCommand::BanSubnet(subnet, duration) => self.ban_subnet(subnet, duration),
+                    PeerCommand::UnbanSubnet(subnet) => self.ban_list.unban_subnet(&subnet),
+                    PeerCommand::GetBans(tx) => {
+                        let _ = tx.send(self.ban_list.entries());
+                    }
                 }
             }
 
@@ -1140,6 +1201,11 @@ impl Display for InboundConnectionError {
     }
 }
 
+/// Returns the current UNIX timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::B512;
@@ -1147,11 +1213,14 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
         DisconnectReason,
     };
-    use reth_net_banlist::BanList;
+    use reth_net_banlist::{BanEntry, BanList, BanTarget, IpNet};
     use reth_network_api::Direction;
     use reth_network_peers::{PeerId, TrustedPeer};
     use reth_network_types::{
-        peers::reputation::DEFAULT_REPUTATION, BackoffKind, ReputationChangeKind,
+        peers::reputation::{
+            ReputationChange, DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE,
+        },
+        BackoffKind, PeerKind, ReputationChangeKind, ReputationChangeWeights, ReputationPolicy,
     };
     use std::{
         future::{poll_fn, Future},
@@ -1696,11 +1765,12 @@ mod tests {
         // unbanned after timeout
         tokio::time::sleep(ban_duration).await;
 
-        poll_fn(|cx| {
-            let _ = peers.poll(cx);
-            Poll::Ready(())
-        })
-        .await;
+        match event!(peers) {
+            PeerAction::BanPeer { peer_id } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
 
         assert!(!peers.ban_list.is_banned_ip(&socket_addr.ip()));
         assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_ok());
@@ -1916,6 +1986,89 @@ mod tests {
         assert_eq!(peers.get_reputation(&peer), Some(0));
     }
 
+    #[tokio::test]
+    async fn test_reputation_history() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, PeerAddr::from_tcp(socket_addr), None);
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Other(1024));
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Reset);
+
+        let history = &peers.peers.get(&peer).unwrap().reputation_history;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].kind, ReputationChangeKind::Other(1024));
+        assert_eq!(history[0].change, 1024);
+        assert_eq!(history[0].reputation, 1024);
+        assert_eq!(history[1].kind, ReputationChangeKind::Reset);
+        assert_eq!(history[1].change, -1024);
+        assert_eq!(history[1].reputation, DEFAULT_REPUTATION);
+    }
+
+    #[tokio::test]
+    async fn test_custom_reputation_policy() {
+        /// Only penalizes peers for bad protocol messages.
+        #[derive(Debug)]
+        struct BadProtocolOnly;
+
+        impl ReputationPolicy for BadProtocolOnly {
+            fn reputation_change(
+                &self,
+                kind: ReputationChangeKind,
+                _peer_kind: PeerKind,
+            ) -> Option<ReputationChange> {
+                matches!(kind, ReputationChangeKind::BadProtocol).then_some(i32::MIN.into())
+            }
+        }
+
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.set_reputation_policy(Box::new(BadProtocolOnly));
+        peers.add_peer(peer, PeerAddr::from_tcp(socket_addr), None);
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::BadMessage);
+        assert_eq!(peers.get_reputation(&peer), Some(DEFAULT_REPUTATION));
+        assert!(peers.peers.get(&peer).unwrap().reputation_history.is_empty());
+
+        peers.apply_reputation_change(&peer, ReputationChangeKind::BadProtocol);
+        assert!(peers.peers.get(&peer).unwrap().is_banned());
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure_reputation_of_trusted_and_static_peers() {
+        let weights = ReputationChangeWeights::default();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+
+        // the default policy applies the full weights to trusted and static peers when a
+        // connection fails without a backoff
+        for kind in [PeerKind::Trusted, PeerKind::Static] {
+            let dropped = PeerId::random();
+            peers.add_peer_kind(dropped, kind, PeerAddr::from_tcp(socket_addr), None);
+            peers.on_active_session_dropped(&socket_addr, &dropped, &EthStreamError::StreamTimeout);
+            assert_eq!(peers.get_reputation(&dropped), Some(weights.dropped));
+
+            let failed = PeerId::random();
+            peers.add_peer_kind(failed, kind, PeerAddr::from_tcp(socket_addr), None);
+            peers.on_outgoing_pending_session_dropped(
+                &socket_addr,
+                &failed,
+                &PendingSessionHandshakeError::Eth(EthStreamError::StreamTimeout),
+            );
+            assert_eq!(peers.get_reputation(&failed), Some(weights.failed_to_connect));
+        }
+
+        // other reputation changes of the same kind are still exempted or capped
+        let peer = PeerId::random();
+        peers.add_peer_kind(peer, PeerKind::Trusted, PeerAddr::from_tcp(socket_addr), None);
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Dropped);
+        assert_eq!(peers.get_reputation(&peer), Some(DEFAULT_REPUTATION));
+        peers.apply_reputation_change(&peer, ReputationChangeKind::FailedToConnect);
+        assert_eq!(peers.get_reputation(&peer), Some(MAX_TRUSTED_PEER_REPUTATION_CHANGE));
+    }
+
     #[tokio::test]
     async fn test_remove_discovered_active() {
         let peer = PeerId::random();
@@ -2149,6 +2302,73 @@ mod tests {
         assert_eq!(peer_id, given_peer_id)
     }
 
+    #[tokio::test]
+    async fn test_ban_subnet_removes_peers() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, PeerAddr::from_tcp(socket_addr), None);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        let handle = peers.handle();
+        handle.ban_subnet("1.1.0.0/16".parse().unwrap(), Some(Duration::from_secs(60)));
+
+        match event!(peers) {
+            PeerAction::PeerRemoved(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        assert!(peers.ban_list.is_banned_ip(&socket_addr.ip()));
+        assert_eq!(peers.ban_list.entries().len(), 1);
+        assert!(peers.on_incoming_pending_session(socket_addr.ip()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ban_overflowing_duration() {
+        let peer = PeerId::random();
+        let subnet: IpNet = "1.1.0.0/16".parse().unwrap();
+        let mut peers = PeersManager::default();
+
+        let handle = peers.handle();
+        handle.ban_peer(peer, Some(Duration::MAX));
+        handle.ban_subnet(subnet, Some(Duration::MAX));
+        match event!(peers) {
+            PeerAction::BanPeer { peer_id } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        // the bans are indefinite
+        assert_eq!(
+            peers.ban_list.entries(),
+            vec![
+                BanEntry::new(BanTarget::Peer(peer), None),
+                BanEntry::new(BanTarget::Subnet(subnet), None)
+            ]
+        );
+    }
+
     #[test]
     fn test_connection_limits() {
         let mut info = ConnectionInfo::default();
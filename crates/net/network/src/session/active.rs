@@ -18,10 +18,11 @@ use reth_eth_wire::{
     Capabilities, DisconnectP2P, DisconnectReason, EthMessage,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
-use reth_network_api::PeerRequest;
+use reth_network_api::{PeerMessageEvent, PeerRequest};
 use reth_network_p2p::error::RequestError;
 use reth_network_peers::PeerId;
 use reth_network_types::session::config::INITIAL_REQUEST_TIMEOUT;
+use reth_tokio_util::EventSender;
 use rustc_hash::FxHashMap;
 use tokio::{
     sync::{mpsc::error::TrySendError, oneshot},
@@ -97,6 +98,8 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// Sender for the messages exchanged with the remote peer.
+    pub(crate) message_event_sender: EventSender<PeerMessageEvent>,
 }
 
 impl ActiveSession {
@@ -112,6 +115,13 @@ impl ActiveSession {
         id
     }
 
+    /// Notifies the listeners of the messages exchanged with the remote peer, if there are any.
+    fn notify_message_event(&self, event: impl FnOnce(PeerId) -> PeerMessageEvent) {
+        if self.message_event_sender.has_listeners() {
+            self.message_event_sender.notify(event(self.remote_peer_id));
+        }
+    }
+
     /// Shrinks the capacity of the internal buffers.
     pub fn shrink_to_fit(&mut self) {
         self.received_requests_from_remote.shrink_to_fit();
@@ -553,6 +563,10 @@ impl Future for ActiveSession {
             while this.conn.poll_ready_unpin(cx).is_ready() {
                 if let Some(msg) = this.queued_outgoing.pop_front() {
                     progress = true;
+                    let message_id = match &msg {
+                        OutgoingMessage::Eth(msg) => msg.message_id(),
+                        OutgoingMessage::Broadcast(msg) => msg.message_id(),
+                    };
                     let res = match msg {
                         OutgoingMessage::Eth(msg) => this.conn.start_send_unpin(msg),
                         OutgoingMessage::Broadcast(msg) => this.conn.start_send_broadcast(msg),
@@ -562,6 +576,10 @@ impl Future for ActiveSession {
                         // notify the manager
                         return this.close_on_error(err, cx)
                     }
+                    this.notify_message_event(|peer_id| PeerMessageEvent::Sent {
+                        peer_id,
+                        message_id,
+                    });
                 } else {
                     // no more messages to send over the wire
                     break
@@ -607,6 +625,11 @@ impl Future for ActiveSession {
                         match res {
                             Ok(msg) => {
                                 trace!(target: "net::session", msg_id=?msg.message_id(), remote_peer_id=?this.remote_peer_id, "received eth message");
+                                let message_id = msg.message_id();
+                                this.notify_message_event(|peer_id| PeerMessageEvent::Received {
+                                    peer_id,
+                                    message_id,
+                                });
                                 // decode and handle message
                                 match this.on_incoming_message(msg) {
                                     OnIncomingMessageOutcome::Ok => {
@@ -892,6 +915,7 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        message_event_sender: Default::default(),
                     }
                 }
                 ev => {
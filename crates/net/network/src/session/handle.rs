@@ -9,7 +9,7 @@ use reth_eth_wire::{
 };
 use reth_network_api::PeerInfo;
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_network_types::PeerKind;
+use reth_network_types::Peer;
 use tokio::sync::{
     mpsc::{self, error::SendError},
     oneshot,
@@ -137,8 +137,8 @@ impl ActiveSessionHandle {
         self.remote_addr
     }
 
-    /// Extracts the [`PeerInfo`] from the session handle.
-    pub(crate) fn peer_info(&self, record: &NodeRecord, kind: PeerKind) -> PeerInfo {
+    /// Extracts the [`PeerInfo`] from the session handle and the tracked [`Peer`].
+    pub(crate) fn peer_info(&self, record: &NodeRecord, peer: &Peer) -> PeerInfo {
         PeerInfo {
             remote_id: self.remote_id,
             direction: self.direction,
@@ -151,7 +151,9 @@ impl ActiveSessionHandle {
             eth_version: self.version,
             status: self.status.clone(),
             session_established: self.established,
-            kind,
+            kind: peer.kind,
+            reputation: peer.reputation,
+            reputation_history: peer.reputation_history.iter().copied().collect(),
         }
     }
 }
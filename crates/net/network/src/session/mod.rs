@@ -31,11 +31,12 @@ use reth_eth_wire::{
     UnauthedEthStream, UnauthedP2PStream,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
-use reth_network_api::PeerRequestSender;
+use reth_network_api::{PeerMessageEvent, PeerRequestSender};
 use reth_network_peers::PeerId;
 use reth_network_types::SessionsConfig;
 use reth_primitives::{ForkFilter, ForkId, ForkTransition, Head};
 use reth_tasks::TaskSpawner;
+use reth_tokio_util::EventSender;
 use rustc_hash::FxHashMap;
 use secp256k1::SecretKey;
 use tokio::{
@@ -109,6 +110,8 @@ pub struct SessionManager {
     active_session_rx: ReceiverStream<ActiveSessionMessage>,
     /// Additional `RLPx` sub-protocols to be used by the session manager.
     extra_protocols: RlpxSubProtocols,
+    /// Sender for the messages exchanged over active sessions.
+    message_event_sender: EventSender<PeerMessageEvent>,
     /// Metrics for the session manager.
     metrics: SessionManagerMetrics,
 }
@@ -150,10 +153,16 @@ impl SessionManager {
             active_session_tx: MeteredPollSender::new(active_session_tx, "network_active_session"),
             active_session_rx: ReceiverStream::new(active_session_rx),
             extra_protocols,
+            message_event_sender: Default::default(),
             metrics: Default::default(),
         }
     }
 
+    /// Returns the sender for the messages exchanged over active sessions.
+    pub(crate) const fn message_event_sender(&self) -> &EventSender<PeerMessageEvent> {
+        &self.message_event_sender
+    }
+
     /// Check whether the provided [`ForkId`] is compatible based on the validation rules in
     /// `EIP-2124`.
     pub fn is_valid_fork_id(&self, fork_id: ForkId) -> bool {
@@ -503,6 +512,7 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    message_event_sender: self.message_event_sender.clone(),
                 };
 
                 self.spawn(session);
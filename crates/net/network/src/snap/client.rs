@@ -0,0 +1,150 @@
+use super::{SnapCommand, SnapState};
+use reth_eth_wire::{
+    AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes,
+    SnapMessage, StorageRanges, TrieNodes,
+};
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::RequestError,
+    snap::client::{SnapClient, SnapFut},
+};
+use reth_network_peers::{PeerId, WithPeerId};
+use reth_network_types::ReputationChangeKind;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::oneshot;
+
+/// Time to wait for the response to a `snap` request.
+const SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A client that sends `snap` requests to the connected peers that support the protocol.
+///
+/// Every request is sent to the peer with the fewest requests in flight.
+#[derive(Debug, Clone)]
+pub struct SnapClientHandle {
+    state: SnapState,
+}
+
+impl SnapClientHandle {
+    pub(crate) const fn new(state: SnapState) -> Self {
+        Self { state }
+    }
+
+    /// Sends the request to the least busy peer and resolves the response with `f`.
+    fn request<T>(
+        &self,
+        request: impl FnOnce(u64) -> SnapMessage,
+        f: fn(SnapMessage) -> Option<T>,
+    ) -> SnapFut<T>
+    where
+        T: Send + 'static,
+    {
+        let request_id = self.state.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+
+        let sent = {
+            let peers = self.state.peers.read();
+            peers.iter().min_by_key(|(_, peer)| peer.inflight.load(Ordering::Relaxed)).map(
+                |(peer_id, peer)| {
+                    let command = SnapCommand { request: request(request_id), response: tx };
+                    let guard = InflightGuard::new(peer.inflight.clone());
+                    (*peer_id, peer.to_connection.send(command).is_ok(), guard)
+                },
+            )
+        };
+
+        Box::pin(async move {
+            let Some((peer_id, sent, _guard)) = sent else {
+                return Err(RequestError::UnsupportedCapability)
+            };
+            if !sent {
+                return Err(RequestError::ConnectionDropped)
+            }
+
+            let response = tokio::time::timeout(SNAP_REQUEST_TIMEOUT, rx)
+                .await
+                .map_err(|_| RequestError::Timeout)???;
+            f(response)
+                .map(|response| WithPeerId::new(peer_id, response))
+                .ok_or(RequestError::BadResponse)
+        })
+    }
+}
+
+impl DownloadClient for SnapClientHandle {
+    fn report_bad_message(&self, peer_id: PeerId) {
+        self.state.peers_handle.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.state.peers.read().len()
+    }
+}
+
+impl SnapClient for SnapClientHandle {
+    fn num_snap_peers(&self) -> usize {
+        self.state.peers.read().len()
+    }
+
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange> {
+        self.request(
+            |request_id| SnapMessage::GetAccountRange(GetAccountRange { request_id, ..request }),
+            |response| match response {
+                SnapMessage::AccountRange(response) => Some(response),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges> {
+        self.request(
+            |request_id| SnapMessage::GetStorageRanges(GetStorageRanges { request_id, ..request }),
+            |response| match response {
+                SnapMessage::StorageRanges(response) => Some(response),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes> {
+        self.request(
+            |request_id| SnapMessage::GetByteCodes(GetByteCodes { request_id, ..request }),
+            |response| match response {
+                SnapMessage::ByteCodes(response) => Some(response),
+                _ => None,
+            },
+        )
+    }
+
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes> {
+        self.request(
+            |request_id| SnapMessage::GetTrieNodes(GetTrieNodes { request_id, ..request }),
+            |response| match response {
+                SnapMessage::TrieNodes(response) => Some(response),
+                _ => None,
+            },
+        )
+    }
+}
+
+/// Tracks a request in flight to a peer until it is dropped.
+#[derive(Debug)]
+struct InflightGuard(Arc<AtomicUsize>);
+
+impl InflightGuard {
+    fn new(inflight: Arc<AtomicUsize>) -> Self {
+        inflight.fetch_add(1, Ordering::Relaxed);
+        Self(inflight)
+    }
+}
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
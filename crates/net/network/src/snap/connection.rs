@@ -0,0 +1,177 @@
+use super::{IncomingSnapRequest, SnapCommand, SnapState};
+use alloy_primitives::{bytes::BytesMut, map::HashMap};
+use alloy_rlp::Encodable;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+use reth_eth_wire::{multiplex::ProtocolConnection, SnapMessage};
+use reth_network_p2p::error::{RequestError, RequestResult};
+use reth_network_peers::PeerId;
+use reth_network_types::ReputationChangeKind;
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::sync::{mpsc::error::TrySendError, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+/// A `snap/1` connection to a remote peer.
+///
+/// Yields the messages to send to the remote: the outgoing requests of the
+/// [`SnapClientHandle`](super::SnapClientHandle), and the responses to the requests of the remote.
+/// The connection is closed if the remote sends a malformed message.
+#[must_use = "Streams do nothing unless polled"]
+pub struct SnapConnection {
+    /// The remote peer.
+    peer_id: PeerId,
+    /// The multiplexed connection to the remote.
+    conn: ProtocolConnection,
+    /// Requests to send to the remote.
+    commands: UnboundedReceiverStream<SnapCommand>,
+    /// Requests sent to the remote that are awaiting a response, by request id.
+    inflight_requests: HashMap<u64, oneshot::Sender<RequestResult<SnapMessage>>>,
+    /// Responses to the requests of the remote that are being served.
+    pending_responses: FuturesUnordered<BoxFuture<'static, Option<SnapMessage>>>,
+    /// State shared with the protocol handler.
+    state: SnapState,
+}
+
+impl SnapConnection {
+    pub(crate) fn new(
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+        commands: UnboundedReceiverStream<SnapCommand>,
+        state: SnapState,
+    ) -> Self {
+        Self {
+            peer_id,
+            conn,
+            commands,
+            inflight_requests: Default::default(),
+            pending_responses: Default::default(),
+            state,
+        }
+    }
+
+    /// Forwards a request of the remote to the request handler.
+    fn on_request(&mut self, request: SnapMessage) {
+        let peer_id = self.peer_id;
+        let (incoming, response) = match request {
+            SnapMessage::GetAccountRange(request) => {
+                let (tx, rx) = oneshot::channel();
+                let incoming =
+                    IncomingSnapRequest::GetAccountRange { peer_id, request, response: tx };
+                (incoming, rx.map(|res| res.ok().map(SnapMessage::AccountRange)).boxed())
+            }
+            SnapMessage::GetStorageRanges(request) => {
+                let (tx, rx) = oneshot::channel();
+                let incoming =
+                    IncomingSnapRequest::GetStorageRanges { peer_id, request, response: tx };
+                (incoming, rx.map(|res| res.ok().map(SnapMessage::StorageRanges)).boxed())
+            }
+            SnapMessage::GetByteCodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                let incoming = IncomingSnapRequest::GetByteCodes { peer_id, request, response: tx };
+                (incoming, rx.map(|res| res.ok().map(SnapMessage::ByteCodes)).boxed())
+            }
+            SnapMessage::GetTrieNodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                let incoming = IncomingSnapRequest::GetTrieNodes { peer_id, request, response: tx };
+                (incoming, rx.map(|res| res.ok().map(SnapMessage::TrieNodes)).boxed())
+            }
+            _ => return,
+        };
+
+        match self.state.to_request_handler.try_send(incoming) {
+            Ok(()) => self.pending_responses.push(response),
+            Err(TrySendError::Full(_)) => {
+                trace!(target: "net::snap", peer_id=%self.peer_id, "Dropping snap request, request handler is busy");
+            }
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+
+    /// Resolves the inflight request the response belongs to.
+    fn on_response(&mut self, response: SnapMessage) {
+        if let Some(tx) = self.inflight_requests.remove(&response.request_id()) {
+            let _ = tx.send(Ok(response));
+        } else {
+            // the request might have timed out already
+            trace!(target: "net::snap", peer_id=%self.peer_id, request_id=response.request_id(), "Received unknown snap response");
+        }
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(SnapCommand { request, response })) =
+                this.commands.poll_next_unpin(cx)
+            {
+                // drop the requests whose caller is gone, e.g. because they timed out
+                this.inflight_requests.retain(|_, tx| !tx.is_closed());
+                this.inflight_requests.insert(request.request_id(), response);
+                return Poll::Ready(Some(encode(&request)))
+            }
+
+            if let Poll::Ready(Some(response)) = this.pending_responses.poll_next_unpin(cx) {
+                if let Some(response) = response {
+                    return Poll::Ready(Some(encode(&response)))
+                }
+                continue
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+
+            let msg = match SnapMessage::decode_message(&mut &msg[..]) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    trace!(target: "net::snap", peer_id=%this.peer_id, %err, "Failed to decode snap message");
+                    this.state
+                        .peers_handle
+                        .reputation_change(this.peer_id, ReputationChangeKind::BadProtocol);
+                    return Poll::Ready(None)
+                }
+            };
+
+            if msg.is_request() {
+                this.on_request(msg)
+            } else {
+                this.on_response(msg)
+            }
+        }
+    }
+}
+
+impl Drop for SnapConnection {
+    fn drop(&mut self) {
+        // the peer might have reconnected already, so only its closed channel is removed
+        self.commands.close();
+        self.state
+            .peers
+            .write()
+            .retain(|peer_id, peer| *peer_id != self.peer_id || !peer.to_connection.is_closed());
+        for (_, tx) in self.inflight_requests.drain() {
+            let _ = tx.send(Err(RequestError::ConnectionDropped));
+        }
+    }
+}
+
+impl std::fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("inflight_requests", &self.inflight_requests.len())
+            .field("pending_responses", &self.pending_responses.len())
+            .finish_non_exhaustive()
+    }
+}
+
+fn encode(msg: &SnapMessage) -> BytesMut {
+    let mut buf = BytesMut::with_capacity(msg.length());
+    msg.encode(&mut buf);
+    buf
+}
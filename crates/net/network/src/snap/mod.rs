@@ -0,0 +1,151 @@
+//! Support for the `snap/1` protocol: <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+//!
+//! The protocol runs as an additional `RLPx` sub-protocol next to `eth`, see
+//! [`SnapProtocolHandler`]. Requests of remote peers are served by the [`SnapRequestHandler`],
+//! and requests to remote peers are sent through the [`SnapClientHandle`].
+
+mod client;
+mod connection;
+mod requests;
+
+pub use client::SnapClientHandle;
+pub use connection::SnapConnection;
+pub use requests::{IncomingSnapRequest, SnapRequestHandler};
+
+use crate::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use alloy_primitives::map::HashMap;
+use parking_lot::RwLock;
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol, SnapMessage,
+};
+use reth_network_api::{test_utils::PeersHandle, Direction};
+use reth_network_p2p::error::RequestResult;
+use reth_network_peers::PeerId;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize},
+        Arc,
+    },
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// The `RLPx` sub-protocol handler for `snap/1`.
+///
+/// Every connection that shares the protocol is registered, so it can be used by the
+/// [`SnapClientHandle`], and forwards the requests of the remote to the [`SnapRequestHandler`].
+#[derive(Debug)]
+pub struct SnapProtocolHandler {
+    state: SnapState,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new handler that forwards incoming requests to the given channel.
+    pub fn new(
+        peers_handle: PeersHandle,
+        to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    ) -> Self {
+        Self {
+            state: SnapState {
+                peers: Default::default(),
+                next_request_id: Default::default(),
+                peers_handle,
+                to_request_handler,
+            },
+        }
+    }
+
+    /// Returns a new [`SnapClientHandle`] sending requests to the connected `snap` peers.
+    pub fn client(&self) -> SnapClientHandle {
+        SnapClientHandle::new(self.state.clone())
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler { state: self.state.clone() }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// The connection handler of the `snap/1` protocol.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    state: SnapState,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        Protocol::snap()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.state
+            .peers
+            .write()
+            .insert(peer_id, SnapPeer { to_connection: tx, inflight: Default::default() });
+        SnapConnection::new(peer_id, conn, UnboundedReceiverStream::new(rx), self.state)
+    }
+}
+
+/// State shared by the protocol handler, the connections and the clients.
+#[derive(Debug, Clone)]
+pub(crate) struct SnapState {
+    /// All connected peers that share the `snap` protocol.
+    pub(crate) peers: Arc<RwLock<HashMap<PeerId, SnapPeer>>>,
+    /// The request id of the next outgoing request.
+    pub(crate) next_request_id: Arc<AtomicU64>,
+    /// Used for reporting peers.
+    pub(crate) peers_handle: PeersHandle,
+    /// Sender half of the channel to the [`SnapRequestHandler`].
+    pub(crate) to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+}
+
+/// A connected `snap` peer.
+#[derive(Debug)]
+pub(crate) struct SnapPeer {
+    /// Sender half of the command channel to the connection.
+    pub(crate) to_connection: mpsc::UnboundedSender<SnapCommand>,
+    /// Number of requests to the peer that are awaiting a response.
+    pub(crate) inflight: Arc<AtomicUsize>,
+}
+
+/// A request to send to the remote peer of a [`SnapConnection`].
+#[derive(Debug)]
+pub(crate) struct SnapCommand {
+    /// The request, with its request id already assigned.
+    pub(crate) request: SnapMessage,
+    /// The channel the response is sent to.
+    pub(crate) response: oneshot::Sender<RequestResult<SnapMessage>>,
+}
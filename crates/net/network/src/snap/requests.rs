@@ -0,0 +1,662 @@
+//! State serving for the `snap/1` protocol.
+
+use alloy_consensus::constants::KECCAK_EMPTY;
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Bytes, B256,
+};
+use alloy_rlp::Encodable;
+use futures::StreamExt;
+use reth_eth_wire::{
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SnapAccount, StorageData, StorageRanges, TrieNodes,
+};
+use reth_network_peers::PeerId;
+use reth_storage_api::{
+    BlockReader, StateProofProvider, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRangeProvider,
+};
+use reth_trie::{HashedPostState, HashedStorage, Nibbles, TrieInput};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::sync::{mpsc::Receiver, oneshot};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::trace;
+
+use crate::budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS;
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/v1.14.11/eth/protocols/snap/handler.go#L34-L58>
+
+/// Maximum number of bytecodes to serve.
+///
+/// Used to limit lookups.
+const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to serve.
+///
+/// Used to limit lookups.
+const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Number of recent blocks whose state is served.
+const RECENT_STATES: u64 = 128;
+
+/// Lower bound of the encoded size of an account or a storage slot of a range response.
+///
+/// Used to limit the number of entries read from the state for a single response.
+const MIN_RANGE_ENTRY_SIZE: usize = 34;
+
+/// Serves the `snap` requests of remote peers from the state of the recent blocks.
+///
+/// Requests for the state of an unknown or older block are answered with empty responses.
+/// The responses are read from the state on blocking tasks.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Incoming requests from the [`SnapConnection`](super::SnapConnection)s.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// The state roots of the recent blocks, mapped to their block hash, and the hash of the tip
+    /// they were collected at.
+    recent_roots: Option<(B256, HashMap<B256, B256>)>,
+}
+
+// === impl SnapRequestHandler ===
+impl<C> SnapRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(client: C, incoming: Receiver<IncomingSnapRequest>) -> Self {
+        Self { client, incoming_requests: ReceiverStream::new(incoming), recent_roots: None }
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: BlockReader + StateProviderFactory + Clone + 'static,
+{
+    /// Returns the hash of the recent block with the given state root.
+    fn block_hash_by_root(&mut self, root: B256) -> Option<B256> {
+        let best = self.client.best_block_number().ok()?;
+        let tip = self.client.block_hash(best).ok()??;
+
+        if self.recent_roots.as_ref().map_or(true, |(hash, _)| *hash != tip) {
+            let headers = self
+                .client
+                .sealed_headers_range(best.saturating_sub(RECENT_STATES - 1)..=best)
+                .ok()?;
+            let roots = headers.into_iter().map(|header| (header.state_root, header.hash()));
+            self.recent_roots = Some((tip, roots.collect()));
+        }
+
+        self.recent_roots.as_ref()?.1.get(&root).copied()
+    }
+
+    /// Computes the response from the state of the recent block with the given state root on a
+    /// blocking task.
+    fn spawn_response<T: Send + 'static>(
+        &mut self,
+        root: B256,
+        response: oneshot::Sender<T>,
+        f: impl FnOnce(Option<StateProviderBox>) -> T + Send + 'static,
+    ) {
+        let block_hash = self.block_hash_by_root(root);
+        let client = self.client.clone();
+        tokio::task::spawn_blocking(move || {
+            let state = block_hash.and_then(|hash| client.state_by_block_hash(hash).ok());
+            let _ = response.send(f(state));
+        });
+    }
+
+    fn on_account_range_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetAccountRange,
+        response: oneshot::Sender<AccountRange>,
+    ) {
+        trace!(target: "net::snap", %peer_id, ?request, "Received account range request");
+        self.spawn_response(request.root_hash, response, |state| get_account_range(state, request));
+    }
+
+    fn on_storage_ranges_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetStorageRanges,
+        response: oneshot::Sender<StorageRanges>,
+    ) {
+        trace!(target: "net::snap", %peer_id, accounts=request.account_hashes.len(), "Received storage ranges request");
+        self.spawn_response(request.root_hash, response, |state| {
+            get_storage_ranges(state, request)
+        });
+    }
+
+    fn on_byte_codes_request(
+        &self,
+        peer_id: PeerId,
+        request: GetByteCodes,
+        response: oneshot::Sender<ByteCodes>,
+    ) {
+        trace!(target: "net::snap", %peer_id, codes=request.hashes.len(), "Received bytecodes request");
+        let client = self.client.clone();
+        tokio::task::spawn_blocking(move || {
+            let _ = response.send(get_byte_codes(client.latest().ok(), request));
+        });
+    }
+
+    fn on_trie_nodes_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetTrieNodes,
+        response: oneshot::Sender<TrieNodes>,
+    ) {
+        trace!(target: "net::snap", %peer_id, paths=request.paths.len(), "Received trie nodes request");
+        self.spawn_response(request.root_hash, response, |state| get_trie_nodes(state, request));
+    }
+}
+
+/// Returns the accounts of the requested range, along with the proofs of its boundaries.
+fn get_account_range(state: Option<StateProviderBox>, request: GetAccountRange) -> AccountRange {
+    let GetAccountRange { request_id, root_hash: _, starting_hash, limit_hash, response_bytes } =
+        request;
+    let empty = AccountRange { request_id, ..Default::default() };
+    if starting_hash > limit_hash {
+        return empty
+    }
+    let Some(state) = state else { return empty };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let Ok(range) = state.hashed_account_range(
+        HashedPostState::default(),
+        starting_hash,
+        soft_limit / MIN_RANGE_ENTRY_SIZE + 1,
+    ) else {
+        return empty
+    };
+
+    // the last account may be past the limit hash, to prove there are no accounts in between
+    let mut accounts = Vec::new();
+    let mut total_bytes = 0;
+    for entry in range.accounts {
+        let account = AccountData {
+            hash: entry.hashed_address,
+            account: SnapAccount {
+                nonce: entry.account.nonce,
+                balance: entry.account.balance,
+                storage_root: entry.storage_root,
+                code_hash: entry.account.get_bytecode_hash(),
+            },
+        };
+        total_bytes += account.length();
+        accounts.push(account);
+
+        if entry.hashed_address >= limit_hash || total_bytes >= soft_limit {
+            break
+        }
+    }
+
+    let mut targets = HashMap::from_iter([(starting_hash, HashSet::default())]);
+    if let Some(last) = accounts.last() {
+        targets.insert(last.hash, HashSet::default());
+    }
+    let Ok(proof) = state.multiproof(TrieInput::default(), targets) else { return empty };
+
+    AccountRange {
+        request_id,
+        accounts,
+        proof: proof
+            .account_subtree
+            .into_nodes_sorted()
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect(),
+    }
+}
+
+/// Returns the storage slots of the requested accounts.
+///
+/// The starting and limit hashes only apply to the first account. If its range doesn't start
+/// at the first slot, or if the response is cut off, the range of the last account is
+/// proven and no further accounts are served.
+fn get_storage_ranges(state: Option<StateProviderBox>, request: GetStorageRanges) -> StorageRanges {
+    let GetStorageRanges {
+        request_id,
+        root_hash: _,
+        account_hashes,
+        starting_hash,
+        limit_hash,
+        response_bytes,
+    } = request;
+    let empty = StorageRanges { request_id, ..Default::default() };
+    let (Some(mut origin), Some(mut limit)) =
+        (hash_from_bytes(&starting_hash), hash_from_bytes(&limit_hash))
+    else {
+        return empty
+    };
+    let Some(state) = state else { return empty };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut slots = Vec::new();
+    let mut proof = Vec::new();
+    let mut total_bytes = 0;
+
+    for hashed_address in account_hashes {
+        if total_bytes >= soft_limit {
+            break
+        }
+        let start = origin.take().unwrap_or_default();
+        let end = limit.take().unwrap_or(B256::repeat_byte(0xff));
+
+        let Ok(range) = state.hashed_storage_range(
+            hashed_address,
+            HashedStorage::default(),
+            start,
+            (soft_limit - total_bytes) / MIN_RANGE_ENTRY_SIZE + 1,
+        ) else {
+            return empty
+        };
+
+        // the range is cut off if there are more slots than returned before the end
+        let mut aborted = range.next_key.is_some();
+        let mut storage = Vec::new();
+        for entry in range.slots {
+            if total_bytes >= soft_limit {
+                aborted = true;
+                break
+            }
+
+            let slot =
+                StorageData { hash: entry.hashed_key, data: alloy_rlp::encode(entry.value).into() };
+            total_bytes += slot.length();
+            storage.push(slot);
+
+            if entry.hashed_key >= end {
+                aborted = false;
+                break
+            }
+        }
+
+        let last = storage.last().map(|slot| slot.hash);
+        if !storage.is_empty() {
+            slots.push(storage);
+        }
+
+        if !start.is_zero() || (aborted && last.is_some()) {
+            let targets = HashMap::from_iter([(
+                hashed_address,
+                HashSet::from_iter(std::iter::once(start).chain(last)),
+            )]);
+            let Ok(mut multiproof) = state.multiproof(TrieInput::default(), targets) else {
+                return empty
+            };
+            if let Some(storage_proof) = multiproof.storages.remove(&hashed_address) {
+                proof = storage_proof
+                    .subtree
+                    .into_nodes_sorted()
+                    .into_iter()
+                    .map(|(_, node)| node)
+                    .collect();
+            }
+            break
+        }
+    }
+
+    StorageRanges { request_id, slots, proof }
+}
+
+/// Returns the requested bytecodes, skipping the unknown ones.
+fn get_byte_codes(state: Option<StateProviderBox>, request: GetByteCodes) -> ByteCodes {
+    let GetByteCodes { request_id, hashes, response_bytes } = request;
+    let mut codes = Vec::new();
+    let Some(state) = state else { return ByteCodes { request_id, codes } };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+
+    for hash in hashes.into_iter().take(MAX_CODE_LOOKUPS) {
+        let code = if hash == KECCAK_EMPTY {
+            Bytes::new()
+        } else if let Ok(Some(code)) = state.bytecode_by_hash(hash) {
+            code.original_bytes()
+        } else {
+            continue
+        };
+
+        total_bytes += code.len();
+        codes.push(code);
+
+        if total_bytes >= soft_limit {
+            break
+        }
+    }
+
+    ByteCodes { request_id, codes }
+}
+
+/// Returns the requested trie nodes, up to the first unknown one.
+fn get_trie_nodes(state: Option<StateProviderBox>, request: GetTrieNodes) -> TrieNodes {
+    let GetTrieNodes { request_id, root_hash: _, paths, response_bytes } = request;
+    let mut nodes = Vec::new();
+    let Some(state) = state else { return TrieNodes { request_id, nodes } };
+
+    // the requested nodes in request order, with the hashed address of their storage trie
+    let mut requested = Vec::new();
+    'paths: for path_set in paths {
+        match path_set.as_slice() {
+            [] => break,
+            [path] => {
+                let Some(path) = decode_compact_path(path) else { break };
+                requested.push((None, path));
+            }
+            [account, storage_paths @ ..] => {
+                let Some(hashed_address) =
+                    (account.len() == B256::len_bytes()).then(|| B256::from_slice(account))
+                else {
+                    break
+                };
+                for path in storage_paths {
+                    let Some(path) = decode_compact_path(path) else { break 'paths };
+                    requested.push((Some(hashed_address), path));
+                }
+            }
+        }
+    }
+    requested.truncate(MAX_TRIE_NODE_LOOKUPS);
+
+    // every node is retained by the proof of any key it is a prefix of
+    let mut targets = HashMap::<B256, HashSet<B256>>::default();
+    for (hashed_address, path) in &requested {
+        match hashed_address {
+            Some(hashed_address) => {
+                targets.entry(*hashed_address).or_default().insert(padded_key(path));
+            }
+            None => {
+                targets.entry(padded_key(path)).or_default();
+            }
+        }
+    }
+    let Ok(multiproof) = state.multiproof(TrieInput::default(), targets) else {
+        return TrieNodes { request_id, nodes }
+    };
+
+    let soft_limit = (response_bytes as usize).min(SOFT_RESPONSE_LIMIT);
+    let mut total_bytes = 0;
+    for (hashed_address, path) in requested {
+        let subtree = match hashed_address {
+            Some(hashed_address) => match multiproof.storages.get(&hashed_address) {
+                Some(storage) => &storage.subtree,
+                None => break,
+            },
+            None => &multiproof.account_subtree,
+        };
+        let Some(node) = subtree.get(&path) else { break };
+
+        total_bytes += node.len();
+        nodes.push(node.clone());
+
+        if total_bytes >= soft_limit {
+            break
+        }
+    }
+
+    TrieNodes { request_id, nodes }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: BlockReader + StateProviderFactory + Clone + Unpin + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let maybe_more_incoming_requests = crate::poll_nested_stream_with_budget!(
+            "net::snap",
+            "Incoming snap requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| match incoming {
+                IncomingSnapRequest::GetAccountRange { peer_id, request, response } => {
+                    this.on_account_range_request(peer_id, request, response)
+                }
+                IncomingSnapRequest::GetStorageRanges { peer_id, request, response } => {
+                    this.on_storage_ranges_request(peer_id, request, response)
+                }
+                IncomingSnapRequest::GetByteCodes { peer_id, request, response } => {
+                    this.on_byte_codes_request(peer_id, request, response)
+                }
+                IncomingSnapRequest::GetTrieNodes { peer_id, request, response } => {
+                    this.on_trie_nodes_request(peer_id, request, response)
+                }
+            },
+        );
+
+        if maybe_more_incoming_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+        }
+
+        Poll::Pending
+    }
+}
+
+/// All `snap` requests of remote peers delegated by the [`SnapConnection`](super::SnapConnection)s.
+#[derive(Debug)]
+pub enum IncomingSnapRequest {
+    /// Request an account range from the local node.
+    ///
+    /// The response should be sent through the channel.
+    GetAccountRange {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested account range.
+        request: GetAccountRange,
+        /// The channel sender for the response containing the account range.
+        response: oneshot::Sender<AccountRange>,
+    },
+    /// Request storage ranges from the local node.
+    ///
+    /// The response should be sent through the channel.
+    GetStorageRanges {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested storage ranges.
+        request: GetStorageRanges,
+        /// The channel sender for the response containing the storage ranges.
+        response: oneshot::Sender<StorageRanges>,
+    },
+    /// Request bytecodes from the local node.
+    ///
+    /// The response should be sent through the channel.
+    GetByteCodes {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested bytecodes.
+        request: GetByteCodes,
+        /// The channel sender for the response containing the bytecodes.
+        response: oneshot::Sender<ByteCodes>,
+    },
+    /// Request trie nodes from the local node.
+    ///
+    /// The response should be sent through the channel.
+    GetTrieNodes {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested trie nodes.
+        request: GetTrieNodes,
+        /// The channel sender for the response containing the trie nodes.
+        response: oneshot::Sender<TrieNodes>,
+    },
+}
+
+/// Converts an optional hash of a request to a [`B256`], returning `None` if it's malformed.
+///
+/// An empty hash is converted to `Some(None)`.
+fn hash_from_bytes(bytes: &[u8]) -> Option<Option<B256>> {
+    match bytes.len() {
+        0 => Some(None),
+        len if len <= B256::len_bytes() => Some(Some(B256::left_padding_from(bytes))),
+        _ => None,
+    }
+}
+
+/// Decodes a compact (hex-prefix) encoded trie path into its nibbles.
+fn decode_compact_path(path: &[u8]) -> Option<Nibbles> {
+    let (first, rest) = path.split_first()?;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    match first >> 4 {
+        // even length, as extension or leaf path
+        0 | 2 => {}
+        // odd length, as extension or leaf path
+        1 | 3 => nibbles.push(first & 0x0f),
+        _ => return None,
+    }
+    nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+
+    (nibbles.len() <= 2 * B256::len_bytes()).then(|| Nibbles::from_nibbles_unchecked(nibbles))
+}
+
+/// Returns the key of the given trie path, padded with zeros.
+fn padded_key(path: &Nibbles) -> B256 {
+    let mut key = B256::ZERO;
+    let packed = path.pack();
+    key[..packed.len()].copy_from_slice(&packed);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_genesis::{Genesis, GenesisAccount};
+    use alloy_primitives::{hex, keccak256, Address, U256};
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_db_common::init::init_genesis;
+    use reth_provider::{
+        providers::BlockchainProvider2, test_utils::create_test_provider_factory_with_chain_spec,
+    };
+    use reth_trie::{verify_range_proof, TrieAccount};
+    use std::{collections::BTreeMap, sync::Arc};
+    use tokio::sync::mpsc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_serve_account_range() {
+        let alloc = (0..100u8).map(|i| {
+            let storage = (i % 10 == 0)
+                .then(|| BTreeMap::from([(B256::with_last_byte(i), B256::with_last_byte(1))]));
+            let account = GenesisAccount {
+                balance: U256::from(i) + U256::from(1),
+                storage,
+                ..Default::default()
+            };
+            (Address::repeat_byte(i), account)
+        });
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::mainnet()
+                .genesis(Genesis { alloc: alloc.collect(), ..Default::default() })
+                .build(),
+        );
+        let state_root = chain_spec.genesis_header().state_root;
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec);
+        init_genesis(&factory).unwrap();
+
+        let (_tx, rx) = mpsc::channel(1);
+        let mut handler = SnapRequestHandler::new(BlockchainProvider2::new(factory).unwrap(), rx);
+
+        let mut starting_hash = B256::ZERO;
+        let mut served = Vec::new();
+        loop {
+            let (response_tx, response_rx) = oneshot::channel();
+            let request = GetAccountRange {
+                request_id: 0,
+                root_hash: state_root,
+                starting_hash,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 1000,
+            };
+            handler.on_account_range_request(PeerId::random(), request, response_tx);
+            let response = response_rx.await.unwrap();
+            assert!(!response.accounts.is_empty());
+
+            let leaves = response
+                .accounts
+                .iter()
+                .map(|AccountData { hash, account }| {
+                    let SnapAccount { nonce, balance, storage_root, code_hash } = *account;
+                    (
+                        *hash,
+                        alloy_rlp::encode(TrieAccount { nonce, balance, storage_root, code_hash }),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let has_more =
+                verify_range_proof(state_root, starting_hash, &leaves, &response.proof).unwrap();
+
+            served.extend(response.accounts.iter().map(|account| account.hash));
+            if !has_more {
+                break
+            }
+            // the response is cut off at the byte limit
+            assert!(response.accounts.len() < 100);
+            starting_hash =
+                B256::from(U256::from_be_bytes(served.last().unwrap().0) + U256::from(1));
+        }
+
+        let mut expected =
+            (0..100u8).map(|i| keccak256(Address::repeat_byte(i))).collect::<Vec<_>>();
+        expected.sort_unstable();
+        assert_eq!(served, expected);
+
+        // unknown roots are answered with empty responses
+        let (response_tx, response_rx) = oneshot::channel();
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: B256::ZERO,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 1000,
+        };
+        handler.on_account_range_request(PeerId::random(), request, response_tx);
+        assert_eq!(
+            response_rx.await.unwrap(),
+            AccountRange { request_id: 1, ..Default::default() }
+        );
+    }
+
+    #[test]
+    fn test_decode_compact_path() {
+        assert_eq!(decode_compact_path(&hex!("00")), Some(Nibbles::default()));
+        assert_eq!(decode_compact_path(&hex!("1a")), Some(Nibbles::from_nibbles_unchecked([0x0a])));
+        assert_eq!(
+            decode_compact_path(&hex!("00abcd")),
+            Some(Nibbles::from_nibbles_unchecked([0x0a, 0x0b, 0x0c, 0x0d]))
+        );
+        assert_eq!(
+            decode_compact_path(&hex!("31bc")),
+            Some(Nibbles::from_nibbles_unchecked([0x01, 0x0b, 0x0c]))
+        );
+        assert_eq!(decode_compact_path(&hex!("40")), None);
+        assert_eq!(decode_compact_path(&[]), None);
+        assert_eq!(decode_compact_path(&[0; 34]), None);
+
+        let path = decode_compact_path(&hex!("1abc")).unwrap();
+        let mut key = B256::ZERO;
+        key[..2].copy_from_slice(&hex!("abc0"));
+        assert_eq!(padded_key(&path), key);
+    }
+
+    #[test]
+    fn test_hash_from_bytes() {
+        assert_eq!(hash_from_bytes(&[]), Some(None));
+        assert_eq!(hash_from_bytes(&[1]), Some(Some(B256::with_last_byte(1))));
+        assert_eq!(hash_from_bytes(&[0; 33]), None);
+    }
+}
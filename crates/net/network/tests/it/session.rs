@@ -1,12 +1,13 @@
 //! Session tests
 
 use futures::StreamExt;
-use reth_eth_wire::EthVersion;
+use reth_eth_wire::{EthMessageID, EthVersion, HeadersDirection};
 use reth_network::{
-    test_utils::{PeerConfig, Testnet},
-    NetworkEvent, NetworkEventListenerProvider,
+    test_utils::{NetworkEventStream, PeerConfig, Testnet},
+    BlockDownloaderProvider, NetworkEvent, NetworkEventListenerProvider, PeerMessageEvent,
 };
 use reth_network_api::{NetworkInfo, Peers};
+use reth_network_p2p::headers::client::{HeadersClient, HeadersRequest};
 use reth_provider::test_utils::NoopProvider;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -81,3 +82,40 @@ async fn test_session_established_with_different_capability() {
 
     handle.terminate().await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_peer_message_events() {
+    reth_tracing::init_test_tracing();
+
+    let mut net = Testnet::create(2).await;
+    net.for_each_mut(|peer| peer.install_request_handler());
+
+    let handle0 = net.peers()[0].handle();
+    let handle1 = net.peers()[1].handle();
+    let mut events0 = NetworkEventStream::new(handle0.event_listener());
+    let mut messages0 = handle0.peer_message_listener();
+    let mut messages1 = handle1.peer_message_listener();
+
+    let _handle = net.spawn();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let connected = events0.next_session_established().await.unwrap();
+    assert_eq!(connected, *handle1.peer_id());
+
+    let fetch0 = handle0.fetch_client().await.unwrap();
+    let request =
+        HeadersRequest { start: 0u64.into(), limit: 1, direction: HeadersDirection::Rising };
+    let _ = fetch0.get_headers(request).await;
+
+    let sent = PeerMessageEvent::Sent {
+        peer_id: *handle1.peer_id(),
+        message_id: EthMessageID::GetBlockHeaders,
+    };
+    while messages0.next().await.unwrap() != sent {}
+
+    let received = PeerMessageEvent::Received {
+        peer_id: *handle0.peer_id(),
+        message_id: EthMessageID::GetBlockHeaders,
+    };
+    while messages1.next().await.unwrap() != received {}
+}
@@ -0,0 +1,32 @@
+use crate::{download::DownloadClient, error::PeerRequestResult};
+use futures::Future;
+use reth_eth_wire_types::{
+    AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes,
+    StorageRanges, TrieNodes,
+};
+use std::pin::Pin;
+
+/// The future type of `snap` requests.
+pub type SnapFut<T> = Pin<Box<dyn Future<Output = PeerRequestResult<T>> + Send + Sync>>;
+
+/// A client capable of downloading state over the `snap/1` protocol.
+///
+/// The request id of the requests is assigned by the client, so the one of the given request is
+/// ignored.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait SnapClient: DownloadClient {
+    /// Returns how many connected peers support the `snap` protocol.
+    fn num_snap_peers(&self) -> usize;
+
+    /// Fetches a range of accounts, along with the proofs of its boundaries.
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange>;
+
+    /// Fetches ranges of storage slots of multiple accounts.
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges>;
+
+    /// Fetches contract bytecodes by hash.
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes>;
+
+    /// Fetches trie nodes by path.
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes>;
+}
@@ -5,13 +5,14 @@ use std::{future::Future, marker::PhantomData};
 use alloy_rpc_types_engine::JwtSecret;
 use reth_beacon_consensus::BeaconConsensusEngineHandle;
 use reth_consensus::Consensus;
+use reth_engine_primitives::{BadBlockStore, LocalMinerHandle};
 use reth_evm::execute::BlockExecutorProvider;
 use reth_network_api::FullNetwork;
 use reth_node_core::node_config::NodeConfig;
 use reth_node_types::{NodeTypes, NodeTypesWithDB, NodeTypesWithEngine};
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_primitives::Header;
-use reth_provider::FullProvider;
+use reth_provider::{FullProvider, ProviderFactory};
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::TransactionPool;
 
@@ -102,6 +103,12 @@ pub struct AddOnsContext<'a, N: FullNodeComponents> {
         &'a BeaconConsensusEngineHandle<<N::Types as NodeTypesWithEngine>::Engine>,
     /// JWT secret for the node.
     pub jwt_secret: &'a JwtSecret,
+    /// Store of the blocks rejected by the engine.
+    pub bad_blocks: &'a BadBlockStore,
+    /// Handle to the miner of the local engine, if the node runs in dev mode.
+    pub local_miner: Option<&'a LocalMinerHandle>,
+    /// Factory of the node's database and static files providers.
+    pub provider_factory: &'a ProviderFactory<N::Types>,
 }
 
 /// Customizable node add-on types.
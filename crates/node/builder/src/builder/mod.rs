@@ -23,6 +23,7 @@ use reth_db_api::{
     database::Database,
     database_metrics::{DatabaseMetadata, DatabaseMetrics},
 };
+use reth_evm::dev_state::DevStateOverrides;
 use reth_exex::ExExContext;
 use reth_network::{
     transactions::TransactionsManagerConfig, NetworkBuilder, NetworkConfig, NetworkConfigBuilder,
@@ -575,17 +576,25 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
+    /// State changes of the dev chain, queued by the local miner.
+    pub(crate) dev_state_overrides: DevStateOverrides,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
     /// Create a new instance of [`BuilderContext`]
-    pub const fn new(
+    pub fn new(
         head: Head,
         provider: Node::Provider,
         executor: TaskExecutor,
         config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self {
+            head,
+            provider,
+            executor,
+            config_container,
+            dev_state_overrides: DevStateOverrides::default(),
+        }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -625,6 +634,14 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         self.config().dev.dev
     }
 
+    /// Returns the state changes of the dev chain.
+    ///
+    /// These are queued by the local miner of a `--dev` node and must be applied by the block
+    /// executor and the payload builder of the node, see [`DevStateOverrides`].
+    pub const fn dev_state_overrides(&self) -> &DevStateOverrides {
+        &self.dev_state_overrides
+    }
+
     /// Returns the transaction pool config of the node.
     pub fn pool_config(&self) -> PoolConfig {
         self.config().txpool.pool_config()
@@ -666,15 +683,18 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     where
         Pool: TransactionPool + Unpin + 'static,
     {
-        let (handle, network, txpool, eth) = builder
-            .transactions(pool, tx_config)
-            .request_handler(self.provider().clone())
-            .split_with_handle();
+        let mut builder =
+            builder.transactions(pool, tx_config).request_handler(self.provider().clone());
+        let snap = builder.snap_request_handler(self.provider().clone());
+        let (handle, network, txpool, eth) = builder.split_with_handle();
 
         self.executor.spawn_critical("p2p txpool", txpool);
         self.executor.spawn_critical("p2p eth request handler", eth);
+        self.executor.spawn_critical("p2p snap request handler", snap);
 
         let default_peers_path = self.config().datadir().known_peers();
+        let known_bans_file =
+            self.config().network.persistent_bans_file(default_peers_path.clone());
         let known_peers_file = self.config().network.persistent_peers_file(default_peers_path);
         self.executor.spawn_critical_with_graceful_shutdown_signal(
             "p2p network task",
@@ -692,6 +712,16 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
                             }
                         }
                     }
+                    if let Some(bans_file) = known_bans_file {
+                        match network.write_bans_to_file(bans_file.as_path()) {
+                            Ok(_) => {
+                                info!(target: "reth::cli", bans_file=?bans_file, "Wrote network bans to file");
+                            }
+                            Err(err) => {
+                                warn!(target: "reth::cli", %err, "Failed to write network bans to file");
+                            }
+                        }
+                    }
                 })
             },
         );
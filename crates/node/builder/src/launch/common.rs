@@ -17,11 +17,15 @@ use reth_db_api::database::Database;
 use reth_db_common::init::{init_genesis, InitDatabaseError};
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
 use reth_engine_tree::tree::{InvalidBlockHook, InvalidBlockHooks, NoopInvalidBlockHook};
-use reth_evm::noop::NoopBlockExecutorProvider;
+use reth_evm::{dev_state::DevStateOverrides, noop::NoopBlockExecutorProvider};
 use reth_fs_util as fs;
-use reth_invalid_block_hooks::InvalidBlockWitnessHook;
+use reth_invalid_block_hooks::{
+    InvalidBlockOpcodeHook, InvalidBlockPreStateHook, InvalidBlockWitnessHook,
+};
 use reth_network_p2p::headers::client::HeadersClient;
-use reth_node_api::{FullNodeTypes, NodeTypes, NodeTypesWithDB};
+use reth_node_api::{
+    BadBlockStore, FullNodeTypes, NodeTypes, NodeTypesWithDB, DEFAULT_BAD_BLOCK_LIMIT,
+};
 use reth_node_core::{
     args::InvalidBlockHookType,
     dirs::{ChainPath, DataDirPath},
@@ -411,7 +415,8 @@ where
         let factory = ProviderFactory::new(
             self.right().clone(),
             self.chain_spec(),
-            StaticFileProvider::read_write(self.data_dir().static_files())?,
+            StaticFileProvider::read_write(self.data_dir().static_files())?
+                .with_segment_configs(self.toml_config().static_files.segments()),
         )
         .with_prune_modes(self.prune_modes())
         .with_static_files_metrics();
@@ -730,6 +735,7 @@ where
             node_adapter,
             head,
             consensus,
+            dev_state_overrides: builder_ctx.dev_state_overrides().clone(),
         };
 
         let ctx = LaunchContextWith {
@@ -866,6 +872,11 @@ where
         self.right().consensus.clone()
     }
 
+    /// Returns the state changes of the dev chain.
+    pub const fn dev_state_overrides(&self) -> &DevStateOverrides {
+        &self.right().dev_state_overrides
+    }
+
     /// Returns the metrics sender.
     pub fn sync_metrics_tx(&self) -> UnboundedSender<MetricEvent> {
         self.right().db_provider_container.metrics_sender.clone()
@@ -915,9 +926,18 @@ where
                         output_directory,
                         healthy_node_rpc_client.clone(),
                     )),
-                    InvalidBlockHookType::PreState | InvalidBlockHookType::Opcode => {
-                        eyre::bail!("invalid block hook {hook:?} is not implemented yet")
-                    }
+                    InvalidBlockHookType::PreState => Box::new(InvalidBlockPreStateHook::new(
+                        self.blockchain_db().clone(),
+                        self.components().evm_config().clone(),
+                        output_directory,
+                        healthy_node_rpc_client.clone(),
+                    )),
+                    InvalidBlockHookType::Opcode => Box::new(InvalidBlockOpcodeHook::new(
+                        self.blockchain_db().clone(),
+                        self.components().evm_config().clone(),
+                        output_directory,
+                        healthy_node_rpc_client.clone(),
+                    )),
                 } as Box<dyn InvalidBlockHook>)
             })
             .collect::<Result<_, _>>()?;
@@ -925,6 +945,13 @@ where
         Ok(Box::new(InvalidBlockHooks(hooks)))
     }
 
+    /// Returns the [`BadBlockStore`] to record the blocks rejected by the engine in.
+    ///
+    /// The store is persisted to the data directory, so the bad blocks survive restarts.
+    pub fn bad_block_store(&self) -> eyre::Result<BadBlockStore> {
+        Ok(BadBlockStore::open(self.data_dir().bad_blocks(), DEFAULT_BAD_BLOCK_LIMIT)?)
+    }
+
     /// Returns an RPC client for the healthy node, if configured in the node config.
     fn get_healthy_node_client(&self) -> eyre::Result<Option<jsonrpsee::http_client::HttpClient>> {
         self.node_config()
@@ -1048,6 +1075,7 @@ where
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
     consensus: Arc<dyn Consensus>,
+    dev_state_overrides: DevStateOverrides,
 }
 
 #[cfg(test)]
@@ -207,6 +207,9 @@ where
         let pruner_events = pruner.events();
         info!(target: "reth::cli", prune_config=?ctx.prune_config().unwrap_or_default(), "Pruner initialized");
 
+        let bad_blocks = ctx.bad_block_store()?;
+
+        let mut local_miner = None;
         let mut engine_service = if ctx.is_dev() {
             let mining_mode = if let Some(block_time) = ctx.node_config().dev.block_time {
                 MiningMode::interval(block_time)
@@ -222,12 +225,15 @@ where
                 ctx.components().payload_builder().clone(),
                 engine_tree_config,
                 ctx.invalid_block_hook()?,
+                bad_blocks.clone(),
                 ctx.sync_metrics_tx(),
                 consensus_engine_tx.clone(),
                 Box::pin(consensus_engine_stream),
                 mining_mode,
                 LocalPayloadAttributesBuilder::new(ctx.chain_spec()),
+                ctx.dev_state_overrides().clone(),
             );
+            local_miner = Some(eth_service.miner());
 
             Either::Left(eth_service)
         } else {
@@ -245,6 +251,7 @@ where
                 ctx.components().payload_builder().clone(),
                 engine_tree_config,
                 ctx.invalid_block_hook()?,
+                bad_blocks.clone(),
                 ctx.sync_metrics_tx(),
             );
 
@@ -290,6 +297,9 @@ where
             config: ctx.node_config(),
             beacon_engine_handle: &beacon_engine_handle,
             jwt_secret: &jwt_secret,
+            bad_blocks: &bad_blocks,
+            local_miner: local_miner.as_ref(),
+            provider_factory: ctx.provider_factory(),
         };
 
         let RpcHandle { rpc_server_handles, rpc_registry } =
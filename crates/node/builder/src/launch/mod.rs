@@ -328,11 +328,17 @@ where
         // extract the jwt secret from the args if possible
         let jwt_secret = ctx.auth_jwt_secret()?;
 
+        // the legacy engine does not record bad blocks, but we still serve the persisted ones
+        let bad_blocks = ctx.bad_block_store()?;
+
         let add_ons_ctx = AddOnsContext {
             node: ctx.node_adapter(),
             config: ctx.node_config(),
             beacon_engine_handle: &beacon_engine_handle,
             jwt_secret: &jwt_secret,
+            bad_blocks: &bad_blocks,
+            local_miner: None,
+            provider_factory: ctx.provider_factory(),
         };
 
         let RpcHandle { rpc_server_handles, rpc_registry } =
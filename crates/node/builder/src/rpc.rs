@@ -5,6 +5,7 @@ use std::{
     future::Future,
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 use alloy_rpc_types::engine::ClientVersionV1;
@@ -407,7 +408,15 @@ where
     type Handle = RpcHandle<N, EthApi>;
 
     async fn launch_add_ons(self, ctx: AddOnsContext<'_, N>) -> eyre::Result<Self::Handle> {
-        let AddOnsContext { node, config, beacon_engine_handle, jwt_secret } = ctx;
+        let AddOnsContext {
+            node,
+            config,
+            beacon_engine_handle,
+            jwt_secret,
+            bad_blocks,
+            local_miner,
+            provider_factory,
+        } = ctx;
         let Self { eth_api_builder, engine_validator_builder, hooks, _pd: _ } = self;
 
         let client = ClientVersionV1 {
@@ -434,7 +443,7 @@ where
         let module_config = config.rpc.transport_rpc_module_config();
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
-        let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
+        let mut registry = RpcModuleBuilder::default()
             .with_provider(node.provider().clone())
             .with_pool(node.pool().clone())
             .with_network(node.network().clone())
@@ -442,7 +451,23 @@ where
             .with_executor(node.task_executor().clone())
             .with_evm_config(node.evm_config().clone())
             .with_block_executor(node.block_executor().clone())
-            .build_with_auth_server(module_config, engine_api, eth_api_builder);
+            .into_registry(module_config.config().cloned().unwrap_or_default(), eth_api_builder);
+
+        // serve the blocks rejected by the engine over the `debug_` namespace
+        registry.set_bad_blocks(bad_blocks.clone());
+
+        // the `anvil_` and `hardhat_` namespaces control the miner of the dev chain
+        if let Some(local_miner) = local_miner {
+            registry.set_local_miner(local_miner.clone());
+        }
+
+        // `admin_backup` writes backups of the node's database and static files
+        registry.set_database_backup(Arc::new(provider_factory.clone()));
+        // `reth_db_` reads the node's database tables
+        registry.set_database_explorer(Arc::new(provider_factory.clone()));
+
+        let mut modules = registry.create_transport_rpc_modules(module_config);
+        let mut auth_module = registry.create_auth_module(engine_api);
 
         // in dev mode we generate 20 random dev-signer accounts
         if config.dev.dev {
@@ -34,10 +34,13 @@ use reth_network::{
 };
 use reth_network_peers::{mainnet_nodes, TrustedPeer};
 use secp256k1::SecretKey;
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::version::P2P_CLIENT_VERSION;
 
+/// The name of the file active bans are persisted to, next to the known peers file.
+const BANS_FILE_NAME: &str = "bans.json";
+
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "Networking")]
@@ -216,6 +219,16 @@ impl NetworkArgs {
             .with_max_inbound_opt(self.max_inbound_peers)
             .with_max_outbound_opt(self.max_outbound_peers);
 
+        // Restore the bans persisted next to the peers file
+        let bans_file = self.persistent_bans_file(peers_file.clone());
+        let peers_config = match peers_config.clone().with_bans_from_file(bans_file.as_deref()) {
+            Ok(peers_config) => peers_config,
+            Err(err) => {
+                warn!(target: "reth::cli", %err, ?bans_file, "Failed to load persisted bans");
+                peers_config
+            }
+        };
+
         // Configure transactions manager
         let transactions_manager_config = TransactionsManagerConfig {
             transaction_fetcher_config: TransactionFetcherConfig::new(
@@ -271,6 +284,15 @@ impl NetworkArgs {
         self.no_persist_peers.not().then_some(peers_file)
     }
 
+    /// If `no_persist_peers` is false then this returns the path to the persistent bans file,
+    /// which is stored next to the persistent peers file.
+    ///
+    /// The `default_peers_file` is used if no `peers_file` is provided.
+    pub fn persistent_bans_file(&self, default_peers_file: PathBuf) -> Option<PathBuf> {
+        let peers_file = self.peers_file.clone().unwrap_or(default_peers_file);
+        self.persistent_peers_file(peers_file.with_file_name(BANS_FILE_NAME))
+    }
+
     /// Sets the p2p port to zero, to allow the OS to assign a random unused port when
     /// the network components bind to a socket.
     pub const fn with_unused_p2p_port(mut self) -> Self {
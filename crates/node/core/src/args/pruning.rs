@@ -118,6 +118,7 @@ impl PruningArgs {
                             .into_iter()
                             .collect(),
                     ),
+                    receipts_retention: Default::default(),
                 },
             }
         }
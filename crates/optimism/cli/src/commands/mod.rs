@@ -4,9 +4,9 @@ use import::ImportOpCommand;
 use import_receipts::ImportReceiptsOpCommand;
 use reth_cli::chainspec::ChainSpecParser;
 use reth_cli_commands::{
-    config_cmd, db, dump_genesis, init_cmd,
+    config_cmd, db, dump_genesis, dump_state, init_cmd,
     node::{self, NoArgs},
-    p2p, prune, recover, stage,
+    p2p, prune, recover, stage, static_file,
 };
 use std::fmt;
 
@@ -40,6 +40,9 @@ pub enum Commands<Spec: ChainSpecParser = OpChainSpecParser, Ext: clap::Args + f
     ImportReceiptsOp(ImportReceiptsOpCommand<Spec>),
     /// Dumps genesis block JSON configuration to stdout.
     DumpGenesis(dump_genesis::DumpGenesisCommand<Spec>),
+    /// Dumps the state at a block into a state dump file.
+    #[command(name = "dump-state")]
+    DumpState(dump_state::DumpStateCommand<Spec>),
     /// Database debugging utilities
     #[command(name = "db")]
     Db(db::Command<Spec>),
@@ -58,6 +61,9 @@ pub enum Commands<Spec: ChainSpecParser = OpChainSpecParser, Ext: clap::Args + f
     /// Prune according to the configuration without any limits
     #[command(name = "prune")]
     Prune(prune::PruneCommand<Spec>),
+    /// Static file utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command<Spec>),
     /// Generate Test Vectors
     #[cfg(feature = "dev")]
     #[command(name = "test-vectors")]
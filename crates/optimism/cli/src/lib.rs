@@ -157,6 +157,9 @@ where
                 runner.run_blocking_until_ctrl_c(command.execute::<OptimismNode>())
             }
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::DumpState(command) => {
+                runner.run_blocking_until_ctrl_c(command.execute::<OptimismNode>())
+            }
             Commands::Db(command) => {
                 runner.run_blocking_until_ctrl_c(command.execute::<OptimismNode>())
             }
@@ -169,6 +172,9 @@ where
                 runner.run_command_until_exit(|ctx| command.execute::<OptimismNode>(ctx))
             }
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute::<OptimismNode>()),
+            Commands::StaticFile(command) => {
+                runner.run_until_ctrl_c(command.execute::<OptimismNode>())
+            }
             #[cfg(feature = "dev")]
             Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
         }
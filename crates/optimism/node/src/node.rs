@@ -276,7 +276,12 @@ where
                     pool,
                     chain_events,
                     ctx.task_executor().clone(),
-                    Default::default(),
+                    reth_transaction_pool::maintain::MaintainPoolConfig {
+                        // the only reorgs of a dev chain revert it to a snapshot, which drops the
+                        // reverted transactions
+                        reinject_reorged_transactions: !ctx.is_dev(),
+                        ..Default::default()
+                    },
                 ),
             );
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
@@ -163,5 +163,16 @@ impl PayloadAttributes for OpPayloadAttributes {
 /// A builder that can return the current payload attribute.
 pub trait PayloadAttributesBuilder<Attributes>: Send + Sync + 'static {
     /// Return a new payload attribute from the builder.
-    fn build(&self, timestamp: u64) -> Attributes;
+    fn build(&self, timestamp: u64) -> Attributes {
+        self.build_with(timestamp, None, None)
+    }
+
+    /// Return a new payload attribute from the builder, using the given fee recipient and
+    /// `prev_randao` instead of the ones chosen by the builder, if set.
+    fn build_with(
+        &self,
+        timestamp: u64,
+        suggested_fee_recipient: Option<Address>,
+        prev_randao: Option<B256>,
+    ) -> Attributes;
 }
@@ -9,7 +9,9 @@ use reth_exex_types::FinishedExExHeight;
 use reth_provider::{
     DBProvider, DatabaseProviderFactory, PruneCheckpointReader, PruneCheckpointWriter,
 };
-use reth_prune_types::{PruneLimiter, PruneProgress, PruneSegment, PrunerOutput};
+use reth_prune_types::{
+    PruneLimiter, PruneProgress, PruneSegment, PruneSegmentError, PrunerOutput,
+};
 use reth_tokio_util::{EventSender, EventStream};
 use std::time::{Duration, Instant};
 use tokio::sync::watch;
@@ -212,6 +214,15 @@ where
 
                 let segment_start = Instant::now();
                 let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
+                // Data of the archive window can't be restored if it was already pruned.
+                if let Some(window) = prune_mode.archive_window() {
+                    if previous_checkpoint.is_some_and(|checkpoint| {
+                        checkpoint.block_number.is_some_and(|block| block >= window.start) &&
+                            checkpoint.prune_mode.archive_window() != Some(window)
+                    }) {
+                        return Err(PruneSegmentError::ArchiveWindowPruned(segment.segment()).into())
+                    }
+                }
                 let segment_output = segment.prune(
                     provider,
                     PruneInput { previous_checkpoint, to_block, limiter: limiter.clone() },
@@ -5,13 +5,16 @@ mod user;
 
 use crate::PrunerError;
 use alloy_primitives::{BlockNumber, TxNumber};
-use reth_provider::{errors::provider::ProviderResult, BlockReader, PruneCheckpointWriter};
+use reth_provider::{
+    errors::provider::ProviderResult, BlockReader, ProviderError, PruneCheckpointWriter,
+};
 use reth_prune_types::{
     PruneCheckpoint, PruneLimiter, PruneMode, PrunePurpose, PruneSegment, SegmentOutput,
 };
 pub use set::SegmentSet;
 pub use static_file::{
-    Headers as StaticFileHeaders, Receipts as StaticFileReceipts,
+    AccountChangeSets as StaticFileAccountChangeSets, Headers as StaticFileHeaders,
+    Receipts as StaticFileReceipts, StorageChangeSets as StaticFileStorageChangeSets,
     Transactions as StaticFileTransactions,
 };
 use std::{fmt::Debug, ops::RangeInclusive};
@@ -130,6 +133,61 @@ impl PruneInput {
         Some(range)
     }
 
+    /// Returns the input with the next block range not overlapping the archive window of
+    /// [`PruneMode::Window`], or [`None`] if there's nothing to prune outside of the window.
+    ///
+    /// If the next block range starts before the window, it ends right before the window. If it
+    /// starts inside of the window, it starts right after the window instead, as if the window was
+    /// already pruned.
+    pub(crate) fn exclude_archive_window(mut self, mode: PruneMode) -> Option<Self> {
+        let Some(window) = mode.archive_window() else { return Some(self) };
+
+        let from_block = self.get_start_next_block_range();
+        if from_block < window.start {
+            self.to_block = self.to_block.min(window.start - 1);
+        } else if from_block <= window.end {
+            if self.to_block <= window.end {
+                return None
+            }
+            self.previous_checkpoint = Some(PruneCheckpoint {
+                block_number: Some(window.end),
+                tx_number: None,
+                prune_mode: mode,
+            });
+        }
+
+        Some(self)
+    }
+
+    /// Same as [`Self::exclude_archive_window`], but for segments that prune by transaction
+    /// number, so the transactions of the window are also skipped.
+    pub(crate) fn exclude_archive_window_transactions<Provider: BlockReader>(
+        self,
+        provider: &Provider,
+        mode: PruneMode,
+    ) -> ProviderResult<Option<Self>> {
+        let Some(window) = mode.archive_window() else { return Ok(Some(self)) };
+        let starts_in_window = window.contains(self.get_start_next_block_range());
+
+        let Some(mut input) = self.exclude_archive_window(mode) else { return Ok(None) };
+        if starts_in_window {
+            let body = provider
+                .block_body_indices(window.end)?
+                .ok_or(ProviderError::BlockBodyIndicesNotFound(window.end))?;
+            match body.next_tx_num().checked_sub(1) {
+                Some(tx_number) => {
+                    if let Some(checkpoint) = input.previous_checkpoint.as_mut() {
+                        checkpoint.tx_number = Some(tx_number);
+                    }
+                }
+                // There are no transactions up to the end of the window
+                None => input.previous_checkpoint = None,
+            }
+        }
+
+        Ok(Some(input))
+    }
+
     /// Returns the start of the next block range.
     ///
     /// 1. If checkpoint exists, use next block.
@@ -9,7 +9,10 @@ use reth_provider::{
 };
 use reth_prune_types::PruneModes;
 
-use super::{StaticFileHeaders, StaticFileReceipts, StaticFileTransactions};
+use super::{
+    StaticFileAccountChangeSets, StaticFileHeaders, StaticFileReceipts,
+    StaticFileStorageChangeSets, StaticFileTransactions,
+};
 
 /// Collection of [`Segment`]. Thread-safe, allocated on the heap.
 #[derive(Debug)]
@@ -53,13 +56,16 @@ where
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
+        let receipts_retention =
+            prune_modes.has_receipts_retention().then(|| prune_modes.receipts_retention_rules());
         let PruneModes {
             sender_recovery,
             transaction_lookup,
             receipts,
             account_history,
             storage_history,
-            receipts_log_filter,
+            receipts_log_filter: _,
+            receipts_retention: _,
         } = prune_modes;
 
         Self::default()
@@ -68,20 +74,27 @@ where
             // Static file transactions
             .segment(StaticFileTransactions::new(static_file_provider.clone()))
             // Static file receipts
-            .segment(StaticFileReceipts::new(static_file_provider))
+            .segment(StaticFileReceipts::new(static_file_provider.clone()))
+            // Static file account changesets
+            .segment(StaticFileAccountChangeSets::new(static_file_provider.clone()))
+            // Static file storage changesets
+            .segment(StaticFileStorageChangeSets::new(static_file_provider.clone()))
             // Account history
-            .segment_opt(account_history.map(AccountHistory::new))
+            .segment_opt(
+                account_history.map(|mode| AccountHistory::new(mode, static_file_provider.clone())),
+            )
             // Storage history
-            .segment_opt(storage_history.map(StorageHistory::new))
+            .segment_opt(
+                storage_history.map(|mode| StorageHistory::new(mode, static_file_provider.clone())),
+            )
             // User receipts
             .segment_opt(receipts.map(UserReceipts::new))
             // Receipts by logs
+            .segment_opt(receipts_retention.map(ReceiptsByLogs::new))
+            // Transaction lookup
             .segment_opt(
-                (!receipts_log_filter.is_empty())
-                    .then(|| ReceiptsByLogs::new(receipts_log_filter.clone())),
+                transaction_lookup.map(|mode| TransactionLookup::new(mode, static_file_provider)),
             )
-            // Transaction lookup
-            .segment_opt(transaction_lookup.map(TransactionLookup::new))
             // Sender recovery
             .segment_opt(sender_recovery.map(SenderRecovery::new))
     }
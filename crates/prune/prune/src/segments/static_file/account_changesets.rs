@@ -0,0 +1,79 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use reth_db::{tables, transaction::DbTxMut};
+use reth_provider::{providers::StaticFileProvider, DBProvider};
+use reth_prune_types::{
+    PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use reth_static_file_types::StaticFileSegment;
+use tracing::trace;
+
+#[derive(Debug)]
+pub struct AccountChangeSets {
+    static_file_provider: StaticFileProvider,
+}
+
+impl AccountChangeSets {
+    pub const fn new(static_file_provider: StaticFileProvider) -> Self {
+        Self { static_file_provider }
+    }
+}
+
+impl<Provider: DBProvider<Tx: DbTxMut>> Segment<Provider> for AccountChangeSets {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::AccountChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        self.static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::AccountChangeSets)
+            .map(PruneMode::before_inclusive)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::StaticFile
+    }
+
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No account changesets to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+
+        let mut last_pruned_block = None;
+        let (pruned, done) =
+            provider.tx_ref().prune_table_with_range::<tables::AccountChangeSets>(
+                range,
+                &mut limiter,
+                |_| false,
+                |(block_number, _)| last_pruned_block = Some(block_number),
+            )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned account changesets");
+
+        let last_pruned_block = last_pruned_block
+            // If there's more account changesets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its account changesets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
@@ -1,16 +1,27 @@
 use crate::{
     db_ext::DbTxPruneExt,
-    segments::{user::history::prune_history_indices, PruneInput, Segment},
+    segments::{
+        user::history::{
+            lowest_history_block, prune_history_indices, static_file_changesets_range,
+            RetainedBlocks,
+        },
+        PruneInput, Segment,
+    },
     PrunerError,
 };
+use alloy_primitives::{Address, BlockNumber};
 use itertools::Itertools;
-use reth_db::{tables, transaction::DbTxMut};
-use reth_db_api::models::ShardedKey;
-use reth_provider::DBProvider;
+use reth_db::{
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_db_api::{models::ShardedKey, DatabaseError};
+use reth_provider::{providers::StaticFileProvider, ChangeSetReader, DBProvider};
 use reth_prune_types::{
     PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput,
     SegmentOutputCheckpoint,
 };
+use reth_static_file_types::StaticFileSegment;
 use rustc_hash::FxHashMap;
 use tracing::{instrument, trace};
 
@@ -23,11 +34,12 @@ const ACCOUNT_HISTORY_TABLES_TO_PRUNE: usize = 2;
 #[derive(Debug)]
 pub struct AccountHistory {
     mode: PruneMode,
+    static_file_provider: StaticFileProvider,
 }
 
 impl AccountHistory {
-    pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+    pub const fn new(mode: PruneMode, static_file_provider: StaticFileProvider) -> Self {
+        Self { mode, static_file_provider }
     }
 }
 
@@ -49,6 +61,11 @@ where
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
     fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let Some(input) = input.exclude_archive_window(self.mode) else {
+            trace!(target: "pruner", "No account history to prune outside of the archive window");
+            return Ok(SegmentOutput::done())
+        };
+
         let range = match input.get_next_block_range() {
             Some(range) => range,
             None => {
@@ -80,19 +97,97 @@ where
         // size should be up to 0.5MB + some hashmap overhead. `blocks_since_last_run` is
         // additionally limited by the `max_reorg_depth`, so no OOM is expected here.
         let mut highest_deleted_accounts = FxHashMap::default();
-        let (pruned_changesets, done) =
+
+        // Changesets after the archive window are pruned, except for the lowest one of each
+        // account, which holds the account at the end of the window.
+        let window = self.mode.archive_window();
+        let mut history_cursor = provider.tx_ref().cursor_read::<tables::AccountsHistory>()?;
+        let mut boundaries = FxHashMap::default();
+        let mut last_changeset_retained_block = None;
+        let mut is_retained =
+            |block_number: BlockNumber, address: Address| -> Result<bool, DatabaseError> {
+                let Some(window) = window.filter(|window| block_number > window.end) else {
+                    return Ok(false)
+                };
+                let boundary = match boundaries.get(&address) {
+                    Some(boundary) => *boundary,
+                    None => {
+                        let boundary = lowest_history_block::<tables::AccountsHistory, _>(
+                            &mut history_cursor,
+                            ShardedKey::new(address, window.end + 1),
+                            |a, b| a.key == b.key,
+                        )?;
+                        boundaries.insert(address, boundary);
+                        boundary
+                    }
+                };
+
+                // Changesets missing from the history index are retained as well, since their
+                // account can't be looked up without them.
+                let retained = boundary.map_or(true, |boundary| boundary >= block_number);
+                if retained {
+                    last_changeset_retained_block = Some(block_number);
+                }
+                Ok(retained)
+            };
+
+        // Changesets of blocks in static files are only read to prune the history indices, the
+        // static files themselves are kept.
+        let mut range = range;
+        let mut last_static_file_block = None;
+        let mut static_file_done = true;
+        if let Some(static_file_range) = static_file_changesets_range(
+            &self.static_file_provider,
+            StaticFileSegment::AccountChangeSets,
+            &range,
+        ) {
+            range = static_file_range.end() + 1..=range_end;
+            for block_number in static_file_range {
+                if limiter.is_limit_reached() {
+                    static_file_done = false;
+                    break
+                }
+
+                let changeset = self.static_file_provider.account_block_changeset(block_number)?;
+                limiter.increment_deleted_entries_count_by(changeset.len());
+                for account in changeset {
+                    if !is_retained(block_number, account.address)? {
+                        highest_deleted_accounts.insert(account.address, block_number);
+                        last_changeset_pruned_block = Some(block_number);
+                    }
+                }
+                last_static_file_block = Some(block_number);
+            }
+        }
+
+        let mut boundary_error = None;
+        let (pruned_changesets, done) = if static_file_done {
             provider.tx_ref().prune_table_with_range::<tables::AccountChangeSets>(
                 range,
                 &mut limiter,
-                |_| false,
+                |(block_number, account)| match is_retained(*block_number, account.address) {
+                    Ok(retained) => retained,
+                    Err(err) => {
+                        boundary_error = Some(err);
+                        true
+                    }
+                },
                 |(block_number, account)| {
                     highest_deleted_accounts.insert(account.address, block_number);
                     last_changeset_pruned_block = Some(block_number);
                 },
-            )?;
+            )?
+        } else {
+            (0, false)
+        };
+        if let Some(err) = boundary_error {
+            return Err(err.into())
+        }
         trace!(target: "pruner", pruned = %pruned_changesets, %done, "Pruned account history (changesets)");
 
         let last_changeset_pruned_block = last_changeset_pruned_block
+            .max(last_changeset_retained_block)
+            .max(last_static_file_block)
             // If there's more account changesets to prune, set the checkpoint block number to
             // previous, so we could finish pruning its account changesets on the next run.
             .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
@@ -110,6 +205,12 @@ where
             provider,
             highest_sharded_keys,
             |a, b| a.key == b.key,
+            |key| {
+                window.map(|window| RetainedBlocks {
+                    window,
+                    boundary: boundaries.get(&key.key).copied().flatten(),
+                })
+            },
         )?;
         trace!(target: "pruner", ?outcomes, %done, "Pruned account history (indices)");
 
@@ -132,14 +233,20 @@ mod tests {
         user::account_history::ACCOUNT_HISTORY_TABLES_TO_PRUNE, AccountHistory, PruneInput,
         Segment, SegmentOutput,
     };
-    use alloy_primitives::{BlockNumber, B256};
+    use alloy_primitives::{Address, BlockNumber, B256};
     use assert_matches::assert_matches;
-    use reth_db::{tables, BlockNumberList};
-    use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader};
+    use reth_db::{tables, transaction::DbTxMut, BlockNumberList};
+    use reth_db_api::models::{AccountBeforeTx, AccountChangeSet};
+    use reth_provider::{
+        providers::StaticFileWriter, DatabaseProviderFactory, PruneCheckpointReader,
+        StaticFileProviderFactory,
+    };
     use reth_prune_types::{
-        PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+        ArchiveWindow, PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode,
+        PruneProgress, PruneSegment,
     };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_static_file_types::StaticFileSegment;
     use reth_testing_utils::generators::{
         self, random_block_range, random_changeset_range, random_eoa_accounts, BlockRangeParams,
     };
@@ -201,7 +308,7 @@ mod tests {
                     to_block,
                     limiter: limiter.clone(),
                 };
-                let segment = AccountHistory::new(prune_mode);
+                let segment = AccountHistory::new(prune_mode, db.factory.static_file_provider());
 
                 let provider = db.factory.database_provider_rw().unwrap();
                 let result = segment.prune(&provider, input).unwrap();
@@ -305,4 +412,207 @@ mod tests {
         test_prune(998, 2, (PruneProgress::Finished, 998));
         test_prune(1400, 3, (PruneProgress::Finished, 804));
     }
+
+    #[test]
+    fn prune_archive_window() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=100,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..1, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let accounts = random_eoa_accounts(&mut rng, 2).into_iter().collect::<BTreeMap<_, _>>();
+
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            0..0,
+            0..0,
+        );
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets.clone(), None).expect("insert history");
+
+        let window = ArchiveWindow { start: 30, end: 50, distance: 0 };
+        let prune_mode = PruneMode::Window(window);
+        let to_block = 80;
+        let segment = AccountHistory::new(prune_mode, db.factory.static_file_provider());
+
+        // Pruning stops at the window first, and then continues right after it.
+        for _ in 0..3 {
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::AccountHistory)
+                    .unwrap(),
+                to_block,
+                limiter: PruneLimiter::default(),
+            };
+            let provider = db.factory.database_provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            assert!(result.progress.is_finished());
+
+            if let Some(checkpoint) = result.checkpoint {
+                segment
+                    .save_checkpoint(&provider, checkpoint.as_prune_checkpoint(prune_mode))
+                    .unwrap();
+            }
+            provider.commit().expect("commit");
+        }
+
+        let checkpoint = db
+            .factory
+            .provider()
+            .unwrap()
+            .get_prune_checkpoint(PruneSegment::AccountHistory)
+            .unwrap()
+            .unwrap();
+        assert!(checkpoint.block_number.is_some_and(|block| block > window.end));
+        assert_eq!(checkpoint.prune_mode, prune_mode);
+
+        // The lowest changeset after the window is needed to read the state at its end.
+        let mut boundaries = BTreeMap::new();
+        for (block, changeset) in changesets.iter().enumerate().skip(window.end as usize + 1) {
+            for (address, _, _) in changeset {
+                boundaries.entry(*address).or_insert(block as BlockNumber);
+            }
+        }
+        let is_retained = |block: BlockNumber, address: &Address| {
+            window.contains(block) || block > to_block || boundaries.get(address) == Some(&block)
+        };
+
+        let mut expected_changesets = changesets
+            .iter()
+            .enumerate()
+            .flat_map(|(block, changeset)| {
+                changeset.iter().map(move |(address, _, _)| (block as BlockNumber, *address))
+            })
+            .filter(|(block, address)| is_retained(*block, address))
+            .collect::<Vec<_>>();
+        expected_changesets.sort_unstable();
+        expected_changesets.dedup();
+
+        let mut actual_changesets = db
+            .table::<tables::AccountChangeSets>()
+            .unwrap()
+            .into_iter()
+            .map(|(block, account)| (block, account.address))
+            .collect::<Vec<_>>();
+        actual_changesets.sort_unstable();
+
+        assert_eq!(actual_changesets, expected_changesets);
+
+        let mut actual_indices = db
+            .table::<tables::AccountsHistory>()
+            .unwrap()
+            .into_iter()
+            .flat_map(|(key, blocks)| {
+                blocks.iter().map(move |block| (block, key.key)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        actual_indices.sort_unstable();
+
+        assert_eq!(actual_indices, expected_changesets);
+    }
+
+    #[test]
+    fn prune_static_file_changesets() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=100,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..1, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let accounts = random_eoa_accounts(&mut rng, 2).into_iter().collect::<BTreeMap<_, _>>();
+
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            0..0,
+            0..0,
+        );
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets.clone(), None).expect("insert history");
+
+        // Move the changesets of the first blocks to static files, the way the static file
+        // producer and the static file changeset segment do.
+        let static_file_end = 60;
+        let static_file_provider = db.factory.static_file_provider();
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        for (block, changeset) in changesets.iter().enumerate().take(static_file_end + 1) {
+            let mut changeset = changeset
+                .iter()
+                .map(|(address, account, _)| AccountBeforeTx {
+                    address: *address,
+                    info: Some(*account),
+                })
+                .collect::<Vec<_>>();
+            changeset.sort_unstable_by_key(|account| account.address);
+            writer
+                .append_account_changeset(block as BlockNumber, &AccountChangeSet(changeset))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        db.commit(|tx| {
+            for block in 0..=static_file_end as BlockNumber {
+                tx.delete::<tables::AccountChangeSets>(block, None)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let original_shards = db.table::<tables::AccountsHistory>().unwrap();
+
+        let prune_mode = PruneMode::Before(80);
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 80,
+            limiter: PruneLimiter::default(),
+        };
+        let segment = AccountHistory::new(prune_mode, static_file_provider.clone());
+        let provider = db.factory.database_provider_rw().unwrap();
+        let result = segment.prune(&provider, input).unwrap();
+        assert!(result.progress.is_finished());
+        assert!(result
+            .checkpoint
+            .and_then(|checkpoint| checkpoint.block_number)
+            .is_some_and(|block| block > static_file_end as BlockNumber && block <= 80));
+        provider.commit().expect("commit");
+
+        // Static files are kept, changesets in the database are pruned.
+        assert_eq!(
+            static_file_provider
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            Some(static_file_end as BlockNumber)
+        );
+        assert!(db
+            .table::<tables::AccountChangeSets>()
+            .unwrap()
+            .into_iter()
+            .all(|(block, _)| block > 80));
+
+        // History indices of the changesets in static files are pruned as well.
+        let expected_shards = original_shards
+            .iter()
+            .filter(|(key, _)| key.highest_block_number > 80)
+            .map(|(key, blocks)| {
+                let new_blocks = blocks.iter().skip_while(|block| *block <= 80);
+                (key.clone(), BlockNumberList::new_pre_sorted(new_blocks))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(db.table::<tables::AccountsHistory>().unwrap(), expected_shards);
+    }
 }
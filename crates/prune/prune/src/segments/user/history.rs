@@ -7,7 +7,10 @@ use reth_db_api::{
     transaction::DbTxMut,
     DatabaseError,
 };
-use reth_provider::DBProvider;
+use reth_provider::{providers::StaticFileProvider, DBProvider};
+use reth_prune_types::ArchiveWindow;
+use reth_static_file_types::StaticFileSegment;
+use std::ops::RangeInclusive;
 
 enum PruneShardOutcome {
     Deleted,
@@ -22,13 +25,67 @@ pub(crate) struct PrunedIndices {
     pub(crate) unchanged: usize,
 }
 
+/// Block numbers that are kept in the history shards of a key, even if they're not higher than the
+/// target block number for pruning.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetainedBlocks {
+    /// Archive window of [`reth_prune_types::PruneMode::Window`].
+    pub(crate) window: ArchiveWindow,
+    /// Lowest block number after the window, which changeset holds the value of the key at the
+    /// end of the window.
+    pub(crate) boundary: Option<BlockNumber>,
+}
+
+impl RetainedBlocks {
+    fn contains(&self, block: BlockNumber) -> bool {
+        self.window.contains(block) || self.boundary == Some(block)
+    }
+}
+
+/// Returns the part of the block range whose changesets were moved to the given static file
+/// segment.
+///
+/// The database rows of these changesets are deleted by the static file changeset segments, so
+/// they have to be read from static files to find the history shards to prune.
+pub(crate) fn static_file_changesets_range(
+    static_file_provider: &StaticFileProvider,
+    segment: StaticFileSegment,
+    range: &RangeInclusive<BlockNumber>,
+) -> Option<RangeInclusive<BlockNumber>> {
+    static_file_provider
+        .get_highest_static_file_block(segment)
+        .filter(|highest_block| highest_block >= range.start())
+        .map(|highest_block| *range.start()..=highest_block.min(*range.end()))
+}
+
+/// Returns the lowest block number in the history shards of a key that is higher than or equal to
+/// the block number of the provided sharded key.
+pub(crate) fn lowest_history_block<T, SK>(
+    cursor: &mut impl DbCursorRO<T>,
+    sharded_key: T::Key,
+    key_matches: impl Fn(&T::Key, &T::Key) -> bool,
+) -> Result<Option<BlockNumber>, DatabaseError>
+where
+    T: Table<Value = BlockNumberList>,
+    T::Key: AsRef<ShardedKey<SK>>,
+{
+    let block_number = sharded_key.as_ref().highest_block_number;
+    Ok(cursor
+        .seek(sharded_key.clone())?
+        .filter(|(key, _)| key_matches(key, &sharded_key))
+        .and_then(|(_, blocks)| blocks.iter().find(|block| *block >= block_number)))
+}
+
 /// Prune history indices according to the provided list of highest sharded keys.
 ///
+/// Block numbers returned by `retained_blocks` for a sharded key are kept in its shards.
+///
 /// Returns total number of deleted, updated and unchanged entities.
 pub(crate) fn prune_history_indices<Provider, T, SK>(
     provider: &Provider,
     highest_sharded_keys: impl IntoIterator<Item = T::Key>,
     key_matches: impl Fn(&T::Key, &T::Key) -> bool,
+    retained_blocks: impl Fn(&T::Key) -> Option<RetainedBlocks>,
 ) -> Result<PrunedIndices, DatabaseError>
 where
     Provider: DBProvider<Tx: DbTxMut>,
@@ -45,6 +102,7 @@ where
 
         // Get the highest block number that needs to be deleted for this sharded key
         let to_block = sharded_key.as_ref().highest_block_number;
+        let retained = retained_blocks(&sharded_key);
 
         'shard: loop {
             let Some((key, block_nums)) =
@@ -54,7 +112,7 @@ where
             };
 
             if key_matches(&key, &sharded_key) {
-                match prune_shard(&mut cursor, key, block_nums, to_block, &key_matches)? {
+                match prune_shard(&mut cursor, key, block_nums, to_block, retained, &key_matches)? {
                     PruneShardOutcome::Deleted => outcomes.deleted += 1,
                     PruneShardOutcome::Updated => outcomes.updated += 1,
                     PruneShardOutcome::Unchanged => outcomes.unchanged += 1,
@@ -78,11 +136,15 @@ where
 /// 2. If the shard has `highest_block_number` greater than the target block number for pruning,
 ///    filter block numbers inside the shard which are less than the target block number for
 ///    pruning.
+///
+/// Block numbers of `retained` are never pruned. A shard that only keeps some of them keeps its
+/// key, even though the highest block number of the key may not be in the shard anymore.
 fn prune_shard<C, T, SK>(
     cursor: &mut C,
     key: T::Key,
     raw_blocks: RawValue<T::Value>,
     to_block: BlockNumber,
+    retained: Option<RetainedBlocks>,
     key_matches: impl Fn(&T::Key, &T::Key) -> bool,
 ) -> Result<PruneShardOutcome, DatabaseError>
 where
@@ -91,18 +153,41 @@ where
     T::Key: AsRef<ShardedKey<SK>>,
 {
     // If shard consists only of block numbers less than the target one, delete shard
-    // completely.
+    // completely, unless some of them are retained.
     if key.as_ref().highest_block_number <= to_block {
-        cursor.delete_current()?;
-        Ok(PruneShardOutcome::Deleted)
+        let Some(retained) = retained else {
+            cursor.delete_current()?;
+            return Ok(PruneShardOutcome::Deleted)
+        };
+
+        let blocks = raw_blocks.value()?;
+        let retained_blocks =
+            blocks.iter().filter(|block| retained.contains(*block)).collect::<Vec<_>>();
+
+        if retained_blocks.is_empty() {
+            cursor.delete_current()?;
+            Ok(PruneShardOutcome::Deleted)
+        } else if blocks.len() as usize == retained_blocks.len() {
+            Ok(PruneShardOutcome::Unchanged)
+        } else {
+            cursor.upsert(
+                RawKey::new(key),
+                RawValue::new(BlockNumberList::new_pre_sorted(retained_blocks)),
+            )?;
+            Ok(PruneShardOutcome::Updated)
+        }
     }
     // Shard contains block numbers that are higher than the target one, so we need to
     // filter it. It is guaranteed that further shards for this sharded key will not
     // contain the target block number, as it's in this shard.
     else {
         let blocks = raw_blocks.value()?;
-        let higher_blocks =
-            blocks.iter().skip_while(|block| *block <= to_block).collect::<Vec<_>>();
+        let higher_blocks = blocks
+            .iter()
+            .filter(|block| {
+                *block > to_block || retained.is_some_and(|retained| retained.contains(*block))
+            })
+            .collect::<Vec<_>>();
 
         // If there were blocks less than or equal to the target one
         // (so the shard has changed), update the shard.
@@ -8,7 +8,7 @@ use reth_provider::{
     TransactionsProvider,
 };
 use reth_prune_types::{PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment, SegmentOutput};
-use tracing::instrument;
+use tracing::{instrument, trace};
 
 #[derive(Debug)]
 pub struct Receipts {
@@ -39,6 +39,11 @@ where
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
     fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let Some(input) = input.exclude_archive_window_transactions(provider, self.mode)? else {
+            trace!(target: "pruner", "No receipts to prune outside of the archive window");
+            return Ok(SegmentOutput::done())
+        };
+
         crate::segments::receipts::prune(provider, input)
     }
 
@@ -3,21 +3,22 @@ use crate::{
     segments::{PruneInput, Segment},
     PrunerError,
 };
+use alloy_primitives::{Address, TxNumber};
 use reth_db::{tables, transaction::DbTxMut};
 use reth_provider::{BlockReader, DBProvider, PruneCheckpointWriter, TransactionsProvider};
 use reth_prune_types::{
-    PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogPruneConfig,
+    PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsRetentionConfig,
     SegmentOutput, MINIMUM_PRUNING_DISTANCE,
 };
 use tracing::{instrument, trace};
 
 #[derive(Debug)]
 pub struct ReceiptsByLogs {
-    config: ReceiptsLogPruneConfig,
+    config: ReceiptsRetentionConfig,
 }
 
 impl ReceiptsByLogs {
-    pub const fn new(config: ReceiptsLogPruneConfig) -> Self {
+    pub const fn new(config: ReceiptsRetentionConfig) -> Self {
         Self { config }
     }
 }
@@ -40,7 +41,7 @@ where
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
     fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
-        // Contract log filtering removes every receipt possible except the ones in the list. So,
+        // Receipts retention removes every receipt possible except the ones matching a rule. So,
         // for the other receipts it's as if they had a `PruneMode::Distance()` of
         // `MINIMUM_PRUNING_DISTANCE`.
         let to_block = PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)
@@ -63,35 +64,35 @@ where
         };
 
         // Figure out what receipts have already been pruned, so we can have an accurate
-        // `address_filter`
-        let address_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
+        // `rule_filter`
+        let rule_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
 
         // Splits all transactions in different block ranges. Each block range will have its own
-        // filter address list and will check it while going through the table
+        // filter rule list and will check it while going through the table
         //
         // Example:
-        // For an `address_filter` such as:
-        // { block9: [a1, a2], block20: [a3, a4, a5] }
+        // For a `rule_filter` such as:
+        // { block9: [r1, r2], block20: [r3, r4, r5] }
         //
         // The following structures will be created in the exact order as showed:
         // `block_ranges`: [
-        //    (block0, block8, 0 addresses),
-        //    (block9, block19, 2 addresses),
-        //    (block20, to_block, 5 addresses)
+        //    (block0, block8, 0 rules),
+        //    (block9, block19, 2 rules),
+        //    (block20, to_block, 5 rules)
         //  ]
-        // `filtered_addresses`: [a1, a2, a3, a4, a5]
+        // `filtered_rules`: [r1, r2, r3, r4, r5]
         //
         // The first range will delete all receipts between block0 - block8
-        // The second range will delete all receipts between block9 - 19, except the ones with
-        //     emitter logs from these addresses: [a1, a2].
-        // The third range will delete all receipts between block20 - to_block, except the ones with
-        //     emitter logs from these addresses: [a1, a2, a3, a4, a5]
+        // The second range will delete all receipts between block9 - 19, except the ones matching
+        //     these rules: [r1, r2].
+        // The third range will delete all receipts between block20 - to_block, except the ones
+        //     matching these rules: [r1, r2, r3, r4, r5]
         let mut block_ranges = vec![];
-        let mut blocks_iter = address_filter.iter().peekable();
-        let mut filtered_addresses = vec![];
+        let mut blocks_iter = rule_filter.iter().peekable();
+        let mut filtered_rules = vec![];
 
-        while let Some((start_block, addresses)) = blocks_iter.next() {
-            filtered_addresses.extend_from_slice(addresses);
+        while let Some((start_block, rules)) = blocks_iter.next() {
+            filtered_rules.extend_from_slice(rules);
 
             // This will clear all receipts before the first  appearance of a contract log or since
             // the block after the last pruned one.
@@ -105,16 +106,16 @@ where
             let end_block =
                 blocks_iter.peek().map(|(next_block, _)| *next_block - 1).unwrap_or(to_block);
 
-            // Addresses in lower block ranges, are still included in the inclusion list for future
+            // Rules in lower block ranges, are still included in the inclusion list for future
             // ranges.
-            block_ranges.push((*start_block, end_block, filtered_addresses.len()));
+            block_ranges.push((*start_block, end_block, filtered_rules.len()));
         }
 
         trace!(
             target: "pruner",
             ?block_ranges,
-            ?filtered_addresses,
-            "Calculated block ranges and filtered addresses",
+            ?filtered_rules,
+            "Calculated block ranges and filtered rules",
         );
 
         let mut limiter = input.limiter;
@@ -122,8 +123,10 @@ where
         let mut done = true;
         let mut pruned = 0;
         let mut last_pruned_transaction = None;
-        for (start_block, end_block, num_addresses) in block_ranges {
+        for (start_block, end_block, num_rules) in block_ranges {
             let block_range = start_block..=end_block;
+            let rules = &filtered_rules[..num_rules];
+            let matches_transactions = rules.iter().any(|rule| rule.matches_transactions());
 
             // Calculate the transaction range from this block range
             let tx_range_end = match provider.block_body_indices(end_block)? {
@@ -141,15 +144,32 @@ where
 
             // Delete receipts, except the ones in the inclusion list
             let mut last_skipped_transaction = 0;
+            let mut transaction_error = None;
             let deleted;
             (deleted, done) = provider.tx_ref().prune_table_with_range::<tables::Receipts>(
                 tx_range,
                 &mut limiter,
                 |(tx_num, receipt)| {
-                    let skip = num_addresses > 0 &&
-                        receipt.logs.iter().any(|log| {
-                            filtered_addresses[..num_addresses].contains(&&log.address)
-                        });
+                    // Rules that only match logs are checked first, to avoid reading the
+                    // transaction.
+                    let mut skip = rules.iter().any(|rule| {
+                        !rule.matches_transactions() && rule.matches(&receipt.logs, None, None)
+                    });
+
+                    if !skip && matches_transactions {
+                        match transaction_parties(provider, *tx_num) {
+                            Ok((sender, recipient)) => {
+                                skip = rules
+                                    .iter()
+                                    .any(|rule| rule.matches(&receipt.logs, sender, recipient))
+                            }
+                            // Keeps the receipt, and fails once the table walk is over.
+                            Err(err) => {
+                                transaction_error.get_or_insert(err);
+                                skip = true
+                            }
+                        }
+                    }
 
                     if skip {
                         last_skipped_transaction = *tx_num;
@@ -158,6 +178,9 @@ where
                 },
                 |row| last_pruned_transaction = Some(row.0),
             )?;
+            if let Some(err) = transaction_error {
+                return Err(err)
+            }
 
             trace!(target: "pruner", %deleted, %done, ?block_range, "Pruned receipts");
 
@@ -187,9 +210,9 @@ where
             from_tx_number = last_pruned_transaction + 1;
         }
 
-        // If there are contracts using `PruneMode::Distance(_)` there will be receipts before
+        // If there are rules using `PruneMode::Distance(_)` there will be receipts before
         // `to_block` that become eligible to be pruned in future runs. Therefore, our checkpoint is
-        // not actually `to_block`, but the `lowest_block_with_distance` from any contract.
+        // not actually `to_block`, but the `lowest_block_with_distance` from any rule.
         // This ensures that in future pruner runs we can prune all these receipts between the
         // previous `lowest_block_with_distance` and the new one using
         // `get_next_tx_num_range_from_checkpoint`.
@@ -216,6 +239,24 @@ where
     }
 }
 
+/// Returns the sender and the recipient of the transaction, which is `None` for contract
+/// creations.
+///
+/// Senders are recovered from the transaction signature if they're pruned.
+fn transaction_parties<Provider: TransactionsProvider>(
+    provider: &Provider,
+    tx_num: TxNumber,
+) -> Result<(Option<Address>, Option<Address>), PrunerError> {
+    let transaction = provider
+        .transaction_by_id_no_hash(tx_num)?
+        .ok_or(PrunerError::InconsistentData("Transaction for receipt is not found"))?;
+    let sender = match provider.transaction_sender(tx_num)? {
+        Some(sender) => Some(sender),
+        None => transaction.recover_signer(),
+    };
+    Ok((sender, transaction.to()))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::segments::{PruneInput, ReceiptsByLogs, Segment};
@@ -224,7 +265,10 @@ mod tests {
     use reth_db::tables;
     use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
     use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader, TransactionsProvider};
-    use reth_prune_types::{PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig};
+    use reth_prune_types::{
+        PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig, ReceiptsRetentionConfig,
+        ReceiptsRetentionRule, MINIMUM_PRUNING_DISTANCE,
+    };
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::generators::{
         self, random_block_range, random_eoa_account, random_log, random_receipt, BlockRangeParams,
@@ -295,7 +339,7 @@ mod tests {
 
             let limiter = PruneLimiter::default().set_deleted_entries_limit(10);
 
-            let result = ReceiptsByLogs::new(receipts_log_filter).prune(
+            let result = ReceiptsByLogs::new((&receipts_log_filter).into()).prune(
                 &provider,
                 PruneInput {
                     previous_checkpoint: db
@@ -350,4 +394,79 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn prune_receipts_by_transaction_recipient() {
+        reth_tracing::init_test_tracing();
+
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let tip = 20000;
+        let blocks = random_block_range(
+            &mut rng,
+            0..=tip,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..2, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let transactions =
+            blocks.iter().flat_map(|block| &block.body.transactions).collect::<Vec<_>>();
+        db.insert_receipts(transactions.iter().enumerate().map(|(tx_num, transaction)| {
+            (tx_num as u64, random_receipt(&mut rng, transaction, Some(1)))
+        }))
+        .expect("insert receipts");
+
+        let recipient = transactions[transactions.len() / 2..]
+            .iter()
+            .find_map(|transaction| transaction.to())
+            .expect("transaction recipient");
+        let config = ReceiptsRetentionConfig(vec![ReceiptsRetentionRule {
+            address: None,
+            topics: Default::default(),
+            from: None,
+            to: Some(recipient),
+            mode: PruneMode::Before(10),
+        }]);
+
+        loop {
+            let provider = db.factory.database_provider_rw().unwrap();
+            let output = ReceiptsByLogs::new(config.clone())
+                .prune(
+                    &provider,
+                    PruneInput {
+                        previous_checkpoint: db
+                            .factory
+                            .provider()
+                            .unwrap()
+                            .get_prune_checkpoint(PruneSegment::ContractLogs)
+                            .unwrap(),
+                        to_block: tip,
+                        limiter: PruneLimiter::default().set_deleted_entries_limit(100),
+                    },
+                )
+                .unwrap();
+            provider.commit().expect("commit");
+
+            if output.progress.is_finished() {
+                break
+            }
+        }
+
+        let provider = db.factory.provider().unwrap();
+        assert!(db.table::<tables::Receipts>().unwrap().len() < transactions.len());
+
+        let mut cursor = provider.tx_ref().cursor_read::<tables::Receipts>().unwrap();
+        for receipt in cursor.walk(None).unwrap() {
+            let (tx_num, _) = receipt.unwrap();
+
+            // Either the transaction is sent to our recipient, or the receipt is part of the
+            // unprunable receipts
+            assert!(
+                transactions[tx_num as usize].to() == Some(recipient) ||
+                    provider.transaction_block(tx_num).unwrap().unwrap() >
+                        tip - MINIMUM_PRUNING_DISTANCE
+            );
+        }
+    }
 }
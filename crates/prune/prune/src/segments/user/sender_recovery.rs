@@ -39,6 +39,11 @@ where
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
     fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let Some(input) = input.exclude_archive_window_transactions(provider, self.mode)? else {
+            trace!(target: "pruner", "No transaction senders to prune outside of the archive window");
+            return Ok(SegmentOutput::done())
+        };
+
         let tx_range = match input.get_next_tx_num_range(provider)? {
             Some(range) => range,
             None => {
@@ -1,16 +1,30 @@
 use crate::{
     db_ext::DbTxPruneExt,
-    segments::{user::history::prune_history_indices, PruneInput, Segment, SegmentOutput},
+    segments::{
+        user::history::{
+            lowest_history_block, prune_history_indices, static_file_changesets_range,
+            RetainedBlocks,
+        },
+        PruneInput, Segment, SegmentOutput,
+    },
     PrunerError,
 };
+use alloy_primitives::{Address, BlockNumber, B256};
 use itertools::Itertools;
-use reth_db::{tables, transaction::DbTxMut};
-use reth_db_api::models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress};
-use reth_provider::DBProvider;
+use reth_db::{
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_db_api::{
+    models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress},
+    DatabaseError,
+};
+use reth_provider::{providers::StaticFileProvider, DBProvider, StorageChangeSetReader};
 use reth_prune_types::{
     PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
     SegmentOutputCheckpoint,
 };
+use reth_static_file_types::StaticFileSegment;
 use rustc_hash::FxHashMap;
 use tracing::{instrument, trace};
 
@@ -23,11 +37,12 @@ const STORAGE_HISTORY_TABLES_TO_PRUNE: usize = 2;
 #[derive(Debug)]
 pub struct StorageHistory {
     mode: PruneMode,
+    static_file_provider: StaticFileProvider,
 }
 
 impl StorageHistory {
-    pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+    pub const fn new(mode: PruneMode, static_file_provider: StaticFileProvider) -> Self {
+        Self { mode, static_file_provider }
     }
 }
 
@@ -49,6 +64,11 @@ where
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
     fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let Some(input) = input.exclude_archive_window(self.mode) else {
+            trace!(target: "pruner", "No storage history to prune outside of the archive window");
+            return Ok(SegmentOutput::done())
+        };
+
         let range = match input.get_next_block_range() {
             Some(range) => range,
             None => {
@@ -80,19 +100,103 @@ where
         // size should be up to 0.5MB + some hashmap overhead. `blocks_since_last_run` is
         // additionally limited by the `max_reorg_depth`, so no OOM is expected here.
         let mut highest_deleted_storages = FxHashMap::default();
-        let (pruned_changesets, done) =
+
+        // Changesets after the archive window are pruned, except for the lowest one of each
+        // storage slot, which holds the slot at the end of the window.
+        let window = self.mode.archive_window();
+        let mut history_cursor = provider.tx_ref().cursor_read::<tables::StoragesHistory>()?;
+        let mut boundaries = FxHashMap::default();
+        let mut last_changeset_retained_block = None;
+        let mut is_retained = |block_number: BlockNumber,
+                               address: Address,
+                               key: B256|
+         -> Result<bool, DatabaseError> {
+            let Some(window) = window.filter(|window| block_number > window.end) else {
+                return Ok(false)
+            };
+            let boundary = match boundaries.get(&(address, key)) {
+                Some(boundary) => *boundary,
+                None => {
+                    let boundary = lowest_history_block::<tables::StoragesHistory, _>(
+                        &mut history_cursor,
+                        StorageShardedKey::new(address, key, window.end + 1),
+                        |a, b| a.address == b.address && a.sharded_key.key == b.sharded_key.key,
+                    )?;
+                    boundaries.insert((address, key), boundary);
+                    boundary
+                }
+            };
+
+            // Changesets missing from the history index are retained as well, since their
+            // storage slot can't be looked up without them.
+            let retained = boundary.map_or(true, |boundary| boundary >= block_number);
+            if retained {
+                last_changeset_retained_block = Some(block_number);
+            }
+            Ok(retained)
+        };
+
+        // Changesets of blocks in static files are only read to prune the history indices, the
+        // static files themselves are kept.
+        let mut range = range;
+        let mut last_static_file_block = None;
+        let mut static_file_done = true;
+        if let Some(static_file_range) = static_file_changesets_range(
+            &self.static_file_provider,
+            StaticFileSegment::StorageChangeSets,
+            &range,
+        ) {
+            range = static_file_range.end() + 1..=range_end;
+            for block_number in static_file_range {
+                if limiter.is_limit_reached() {
+                    static_file_done = false;
+                    break
+                }
+
+                let changeset = self.static_file_provider.storage_changeset(block_number)?;
+                limiter.increment_deleted_entries_count_by(changeset.len());
+                for (BlockNumberAddress((_, address)), entry) in changeset {
+                    if !is_retained(block_number, address, entry.key)? {
+                        highest_deleted_storages.insert((address, entry.key), block_number);
+                        last_changeset_pruned_block = Some(block_number);
+                    }
+                }
+                last_static_file_block = Some(block_number);
+            }
+        }
+
+        let mut boundary_error = None;
+        let (pruned_changesets, done) = if static_file_done {
             provider.tx_ref().prune_table_with_range::<tables::StorageChangeSets>(
                 BlockNumberAddress::range(range),
                 &mut limiter,
-                |_| false,
+                |(BlockNumberAddress((block_number, address)), entry)| match is_retained(
+                    *block_number,
+                    *address,
+                    entry.key,
+                ) {
+                    Ok(retained) => retained,
+                    Err(err) => {
+                        boundary_error = Some(err);
+                        true
+                    }
+                },
                 |(BlockNumberAddress((block_number, address)), entry)| {
                     highest_deleted_storages.insert((address, entry.key), block_number);
                     last_changeset_pruned_block = Some(block_number);
                 },
-            )?;
+            )?
+        } else {
+            (0, false)
+        };
+        if let Some(err) = boundary_error {
+            return Err(err.into())
+        }
         trace!(target: "pruner", deleted = %pruned_changesets, %done, "Pruned storage history (changesets)");
 
         let last_changeset_pruned_block = last_changeset_pruned_block
+            .max(last_changeset_retained_block)
+            .max(last_static_file_block)
             // If there's more storage changesets to prune, set the checkpoint block number to
             // previous, so we could finish pruning its storage changesets on the next run.
             .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
@@ -115,6 +219,15 @@ where
             provider,
             highest_sharded_keys,
             |a, b| a.address == b.address && a.sharded_key.key == b.sharded_key.key,
+            |key| {
+                window.map(|window| RetainedBlocks {
+                    window,
+                    boundary: boundaries
+                        .get(&(key.address, key.sharded_key.key))
+                        .copied()
+                        .flatten(),
+                })
+            },
         )?;
         trace!(target: "pruner", ?outcomes, %done, "Pruned storage history (indices)");
 
@@ -140,7 +253,9 @@ mod tests {
     use alloy_primitives::{BlockNumber, B256};
     use assert_matches::assert_matches;
     use reth_db::{tables, BlockNumberList};
-    use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader};
+    use reth_provider::{
+        DatabaseProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
+    };
     use reth_prune_types::{PruneCheckpoint, PruneLimiter, PruneMode, PruneProgress, PruneSegment};
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_testing_utils::generators::{
@@ -205,7 +320,7 @@ mod tests {
                 to_block,
                 limiter: limiter.clone(),
             };
-            let segment = StorageHistory::new(prune_mode);
+            let segment = StorageHistory::new(prune_mode, db.factory.static_file_provider());
 
             let provider = db.factory.database_provider_rw().unwrap();
             let result = segment.prune(&provider, input).unwrap();
@@ -5,20 +5,22 @@ use crate::{
 };
 use rayon::prelude::*;
 use reth_db::{tables, transaction::DbTxMut};
-use reth_provider::{BlockReader, DBProvider, TransactionsProvider};
+use reth_provider::{providers::StaticFileProvider, BlockReader, DBProvider, TransactionsProvider};
 use reth_prune_types::{
     PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutputCheckpoint,
 };
+use reth_static_file_types::StaticFileSegment;
 use tracing::{instrument, trace};
 
 #[derive(Debug)]
 pub struct TransactionLookup {
     mode: PruneMode,
+    static_file_provider: StaticFileProvider,
 }
 
 impl TransactionLookup {
-    pub const fn new(mode: PruneMode) -> Self {
-        Self { mode }
+    pub const fn new(mode: PruneMode, static_file_provider: StaticFileProvider) -> Self {
+        Self { mode, static_file_provider }
     }
 }
 
@@ -39,7 +41,30 @@ where
     }
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
-    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+    fn prune(
+        &self,
+        provider: &Provider,
+        mut input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        // Transactions in static files can only be found by hash without their lookup entries
+        // once the static file has a transaction hash index, so lookup entries are only pruned up
+        // to the highest indexed block. Missing indices are built one static file per run.
+        if self
+            .static_file_provider
+            .get_highest_static_file_tx(StaticFileSegment::Transactions)
+            .is_some()
+        {
+            self.static_file_provider.build_missing_transaction_hash_indices(Some(1))?;
+
+            match self.static_file_provider.transaction_hash_indexed_block()? {
+                Some(indexed_block) => input.to_block = input.to_block.min(indexed_block),
+                None => {
+                    trace!(target: "pruner", "No transaction hash indices of static files yet");
+                    return Ok(SegmentOutput::done())
+                }
+            }
+        }
+
         let (start, end) = match input.get_next_tx_num_range(provider)? {
             Some(range) => range,
             None => {
@@ -118,7 +143,9 @@ mod tests {
         Itertools,
     };
     use reth_db::tables;
-    use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader};
+    use reth_provider::{
+        DatabaseProviderFactory, PruneCheckpointReader, StaticFileProviderFactory,
+    };
     use reth_prune_types::{
         PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
     };
@@ -159,7 +186,7 @@ mod tests {
 
         let test_prune = |to_block: BlockNumber, expected_result: (PruneProgress, usize)| {
             let prune_mode = PruneMode::Before(to_block);
-            let segment = TransactionLookup::new(prune_mode);
+            let segment = TransactionLookup::new(prune_mode, db.factory.static_file_provider());
             let mut limiter = PruneLimiter::default().set_deleted_entries_limit(10);
             let input = PruneInput {
                 previous_checkpoint: db
@@ -15,3 +15,14 @@ pub struct PruneCheckpoint {
     /// Prune mode.
     pub prune_mode: PruneMode,
 }
+
+impl PruneCheckpoint {
+    /// Returns true if the data of the block has been pruned.
+    ///
+    /// Blocks up to the checkpoint block number are pruned, except for the ones in the archive
+    /// window of [`PruneMode::Window`].
+    pub fn is_pruned(&self, block: BlockNumber) -> bool {
+        self.block_number.is_some_and(|pruned| block <= pruned) &&
+            !self.prune_mode.archive_window().is_some_and(|window| window.contains(block))
+    }
+}
@@ -12,15 +12,17 @@ mod checkpoint;
 mod limiter;
 mod mode;
 mod pruner;
+mod retention;
 mod segment;
 mod target;
 
 pub use checkpoint::PruneCheckpoint;
 pub use limiter::PruneLimiter;
-pub use mode::PruneMode;
+pub use mode::{ArchiveWindow, PruneMode};
 pub use pruner::{
     PruneInterruptReason, PruneProgress, PrunerOutput, SegmentOutput, SegmentOutputCheckpoint,
 };
+pub use retention::{ReceiptsRetentionConfig, ReceiptsRetentionRule};
 pub use segment::{PrunePurpose, PruneSegment, PruneSegmentError};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
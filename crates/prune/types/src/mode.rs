@@ -15,6 +15,30 @@ pub enum PruneMode {
     Distance(u64),
     /// Prune blocks before the specified block number. The specified block number is not pruned.
     Before(BlockNumber),
+    /// Prune blocks before the `head-N` block number, except for the blocks of the archive window.
+    Window(ArchiveWindow),
+}
+
+/// Range of blocks that is kept by [`PruneMode::Window`], on top of the last blocks that are
+/// kept according to its distance.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Compact)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(Default, arbitrary::Arbitrary))]
+#[add_arbitrary_tests(compact)]
+pub struct ArchiveWindow {
+    /// First block of the window.
+    pub start: BlockNumber,
+    /// Last block of the window, inclusive.
+    pub end: BlockNumber,
+    /// Prune blocks before the `head-N` block number, outside of the window. In other words, keep
+    /// last N + 1 blocks.
+    pub distance: u64,
+}
+
+impl ArchiveWindow {
+    /// Returns true if the block is inside of the window.
+    pub const fn contains(&self, block: BlockNumber) -> bool {
+        self.start <= block && block <= self.end
+    }
 }
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -51,6 +75,15 @@ impl PruneMode {
             Self::Before(n) if tip - n >= segment.min_blocks(purpose) => {
                 Some(((*n).saturating_sub(1), *self))
             }
+            Self::Window(window)
+                if !segment.supports_archive_window() || window.start > window.end =>
+            {
+                return Err(PruneSegmentError::Configuration(segment))
+            }
+            Self::Window(window) if window.distance > tip => None, // Nothing to prune yet
+            Self::Window(window) if window.distance >= segment.min_blocks(purpose) => {
+                Some((tip - window.distance, *self))
+            }
             _ => return Err(PruneSegmentError::Configuration(segment)),
         };
         Ok(result)
@@ -67,6 +100,12 @@ impl PruneMode {
                 block < tip - *distance
             }
             Self::Before(n) => *n > block,
+            Self::Window(window) => {
+                if window.distance > tip {
+                    return false
+                }
+                block < tip - window.distance && !window.contains(block)
+            }
         }
     }
 
@@ -79,12 +118,21 @@ impl PruneMode {
     pub const fn is_distance(&self) -> bool {
         matches!(self, Self::Distance(_))
     }
+
+    /// Returns the archive window if the prune mode is [`PruneMode::Window`].
+    pub const fn archive_window(&self) -> Option<ArchiveWindow> {
+        match self {
+            Self::Window(window) => Some(*window),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        PruneMode, PrunePurpose, PruneSegment, PruneSegmentError, MINIMUM_PRUNING_DISTANCE,
+        ArchiveWindow, PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment, PruneSegmentError,
+        MINIMUM_PRUNING_DISTANCE,
     };
     use assert_matches::assert_matches;
     use serde::Deserialize;
@@ -158,6 +206,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_archive_window() {
+        let tip = 20000;
+        let window = ArchiveWindow { start: 1000, end: 2000, distance: MINIMUM_PRUNING_DISTANCE };
+        let mode = PruneMode::Window(window);
+
+        assert_eq!(
+            mode.prune_target_block(tip, PruneSegment::AccountHistory, PrunePurpose::User),
+            Ok(Some((tip - MINIMUM_PRUNING_DISTANCE, mode)))
+        );
+        assert_eq!(
+            mode.prune_target_block(tip, PruneSegment::TransactionLookup, PrunePurpose::User),
+            Err(PruneSegmentError::Configuration(PruneSegment::TransactionLookup))
+        );
+        assert_eq!(
+            PruneMode::Window(ArchiveWindow { start: 2000, end: 1000, ..window })
+                .prune_target_block(tip, PruneSegment::AccountHistory, PrunePurpose::User),
+            Err(PruneSegmentError::Configuration(PruneSegment::AccountHistory))
+        );
+        assert_eq!(
+            PruneMode::Window(ArchiveWindow { distance: 1, ..window }).prune_target_block(
+                tip,
+                PruneSegment::AccountHistory,
+                PrunePurpose::User
+            ),
+            Err(PruneSegmentError::Configuration(PruneSegment::AccountHistory))
+        );
+
+        assert!(mode.should_prune(999, tip));
+        assert!(!mode.should_prune(1000, tip));
+        assert!(!mode.should_prune(2000, tip));
+        assert!(mode.should_prune(2001, tip));
+        assert!(!mode.should_prune(tip - MINIMUM_PRUNING_DISTANCE, tip));
+
+        let checkpoint =
+            PruneCheckpoint { block_number: Some(5000), tx_number: None, prune_mode: mode };
+        assert!(checkpoint.is_pruned(999));
+        assert!(!checkpoint.is_pruned(1500));
+        assert!(checkpoint.is_pruned(2001));
+        assert!(!checkpoint.is_pruned(5001));
+    }
+
     #[test]
     fn prune_mode_deserialize() {
         #[derive(Debug, Deserialize)]
@@ -166,12 +256,14 @@ mod tests {
             b: Option<PruneMode>,
             c: Option<PruneMode>,
             d: Option<PruneMode>,
+            e: Option<PruneMode>,
         }
 
         let toml_str = r#"
         a = "full"
         b = { distance = 10 }
         c = { before = 20 }
+        e = { window = { start = 30, end = 40, distance = 50 } }
     "#;
 
         assert_matches!(
@@ -180,7 +272,8 @@ mod tests {
                 a: Some(PruneMode::Full),
                 b: Some(PruneMode::Distance(10)),
                 c: Some(PruneMode::Before(20)),
-                d: None
+                d: None,
+                e: Some(PruneMode::Window(ArchiveWindow { start: 30, end: 40, distance: 50 })),
             })
         );
     }
@@ -0,0 +1,225 @@
+use crate::{PruneMode, PrunePurpose, PruneSegment, PruneSegmentError, ReceiptsLogPruneConfig};
+use alloy_primitives::{Address, BlockNumber, Log, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Rule that retains the receipts matching it, according to its own prune mode.
+///
+/// A receipt matches the rule if it matches all of its criteria, so a rule without any criteria
+/// matches every receipt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptsRetentionRule {
+    /// Matches receipts with a log emitted by the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// Matches receipts with a log whose first topic is in the set. If `address` is set, the log
+    /// also needs to be emitted by it.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub topics: BTreeSet<B256>,
+    /// Matches receipts of transactions sent from the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Address>,
+    /// Matches receipts of transactions sent to the address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Prune mode of the matching receipts.
+    pub mode: PruneMode,
+}
+
+impl ReceiptsRetentionRule {
+    /// Creates a rule that matches receipts with a log emitted by the address.
+    pub const fn address(address: Address, mode: PruneMode) -> Self {
+        Self { address: Some(address), topics: BTreeSet::new(), from: None, to: None, mode }
+    }
+
+    /// Returns `true` if the rule needs the sender or the recipient of the transaction to match
+    /// its receipt.
+    pub const fn matches_transactions(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    /// Returns `true` if the receipt with the logs matches the rule.
+    ///
+    /// `sender` and `recipient` are the ones of the transaction, and are only read if
+    /// [`Self::matches_transactions`] is `true`. The recipient of a contract creation is `None`.
+    pub fn matches(
+        &self,
+        logs: &[Log],
+        sender: Option<Address>,
+        recipient: Option<Address>,
+    ) -> bool {
+        self.matches_logs(logs) &&
+            self.from.map_or(true, |from| sender == Some(from)) &&
+            self.to.map_or(true, |to| recipient == Some(to))
+    }
+
+    fn matches_logs(&self, logs: &[Log]) -> bool {
+        if self.address.is_none() && self.topics.is_empty() {
+            return true
+        }
+
+        logs.iter().any(|log| {
+            self.address.map_or(true, |address| log.address == address) &&
+                (self.topics.is_empty() ||
+                    log.topics().first().is_some_and(|topic| self.topics.contains(topic)))
+        })
+    }
+}
+
+/// Configuration for pruning receipts that don't match any of the retention rules.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReceiptsRetentionConfig(pub Vec<ReceiptsRetentionRule>);
+
+impl ReceiptsRetentionConfig {
+    /// Checks if the configuration is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Given the `tip` block number, consolidates the rules so they can easily be queried for
+    /// filtering across a range of blocks.
+    ///
+    /// The [`BlockNumber`] key of the returned map should be viewed as `PruneMode::Before(block)`
+    /// of its rules, like with [`ReceiptsLogPruneConfig::group_by_block`].
+    pub fn group_by_block(
+        &self,
+        tip: BlockNumber,
+        pruned_block: Option<BlockNumber>,
+    ) -> Result<BTreeMap<BlockNumber, Vec<&ReceiptsRetentionRule>>, PruneSegmentError> {
+        let mut map = BTreeMap::new();
+        let base_block = pruned_block.unwrap_or_default() + 1;
+
+        for rule in &self.0 {
+            let block = base_block.max(
+                rule.mode
+                    .prune_target_block(tip, PruneSegment::ContractLogs, PrunePurpose::User)?
+                    .map(|(block, _)| block)
+                    .unwrap_or_default() +
+                    1,
+            );
+
+            map.entry(block).or_insert_with(Vec::new).push(rule)
+        }
+        Ok(map)
+    }
+
+    /// Returns the lowest block where we start filtering receipts of rules which use
+    /// `PruneMode::Distance(_)`.
+    pub fn lowest_block_with_distance(
+        &self,
+        tip: BlockNumber,
+        pruned_block: Option<BlockNumber>,
+    ) -> Result<Option<BlockNumber>, PruneSegmentError> {
+        let pruned_block = pruned_block.unwrap_or_default();
+        let mut lowest = None;
+
+        for rule in &self.0 {
+            if rule.mode.is_distance() {
+                if let Some((block, _)) = rule.mode.prune_target_block(
+                    tip,
+                    PruneSegment::ContractLogs,
+                    PrunePurpose::User,
+                )? {
+                    lowest = Some(lowest.unwrap_or(u64::MAX).min(block));
+                }
+            }
+        }
+
+        Ok(lowest.map(|lowest| lowest.max(pruned_block)))
+    }
+}
+
+impl From<&ReceiptsLogPruneConfig> for ReceiptsRetentionConfig {
+    fn from(config: &ReceiptsLogPruneConfig) -> Self {
+        Self(
+            config
+                .iter()
+                .map(|(address, mode)| ReceiptsRetentionRule::address(*address, *mode))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Bytes, LogData};
+
+    fn empty_rule(mode: PruneMode) -> ReceiptsRetentionRule {
+        ReceiptsRetentionRule { address: None, topics: BTreeSet::new(), from: None, to: None, mode }
+    }
+
+    fn log(address: Address, topic: B256) -> Log {
+        Log { address, data: LogData::new_unchecked(vec![topic], Bytes::new()) }
+    }
+
+    #[test]
+    fn test_rule_matches() {
+        let contract = Address::new([1; 20]);
+        let account = Address::new([2; 20]);
+        let transfer = B256::new([3; 32]);
+        let approval = B256::new([4; 32]);
+        let mode = PruneMode::Distance(100);
+
+        let rule = ReceiptsRetentionRule {
+            topics: BTreeSet::from([transfer]),
+            ..ReceiptsRetentionRule::address(contract, mode)
+        };
+        assert!(rule.matches(&[log(contract, transfer)], None, None));
+        assert!(!rule.matches(&[log(contract, approval)], None, None));
+        assert!(!rule.matches(&[log(account, transfer)], None, None));
+        assert!(!rule.matches(&[], None, None));
+
+        let rule = ReceiptsRetentionRule { topics: BTreeSet::from([approval]), ..empty_rule(mode) };
+        assert!(rule.matches(&[log(account, transfer), log(contract, approval)], None, None));
+
+        let rule = ReceiptsRetentionRule { from: Some(account), ..empty_rule(mode) };
+        assert!(rule.matches_transactions());
+        assert!(rule.matches(&[], Some(account), None));
+        assert!(!rule.matches(&[], Some(contract), Some(account)));
+
+        let rule = ReceiptsRetentionRule { to: Some(contract), ..empty_rule(mode) };
+        assert!(rule.matches(&[], Some(account), Some(contract)));
+        assert!(!rule.matches(&[], Some(account), None));
+    }
+
+    #[test]
+    fn test_group_by_block() {
+        let address = Address::new([1; 20]);
+        let config = ReceiptsRetentionConfig(vec![
+            ReceiptsRetentionRule::address(address, PruneMode::Before(600)),
+            ReceiptsRetentionRule { from: Some(address), ..empty_rule(PruneMode::Before(300)) },
+            ReceiptsRetentionRule::address(address, PruneMode::Distance(100_000)),
+        ]);
+
+        let result = config.group_by_block(900_000, Some(400)).unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[&401], vec![&config.0[1]]);
+        assert_eq!(result[&600], vec![&config.0[0]]);
+        assert_eq!(result[&800_001], vec![&config.0[2]]);
+
+        assert_eq!(config.lowest_block_with_distance(900_000, Some(400)).unwrap(), Some(800_000));
+    }
+
+    #[test]
+    fn test_deserialize_rules() {
+        let config: ReceiptsRetentionConfig = toml::from_str::<BTreeMap<String, _>>(
+            r#"
+rules = [
+    { address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", topics = ["0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"], mode = { distance = 100000 } },
+    { from = "0xdac17f958d2ee523a2206206994597c13d831ec7", mode = { before = 17000000 } },
+]
+"#,
+        )
+        .unwrap()
+        .remove("rules")
+        .unwrap();
+
+        assert_eq!(config.0.len(), 2);
+        assert_eq!(config.0[0].topics.len(), 1);
+        assert_eq!(config.0[0].mode, PruneMode::Distance(100_000));
+        assert!(config.0[1].matches_transactions());
+        assert_eq!(config.0[1].mode, PruneMode::Before(17_000_000));
+    }
+}
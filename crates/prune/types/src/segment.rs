@@ -39,15 +39,24 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `AccountChangeSets` table rows that were moved to static
+    /// files.
+    AccountChangeSets,
+    /// Prune segment responsible for the `StorageChangeSets` table rows that were moved to static
+    /// files.
+    StorageChangeSets,
 }
 
 impl PruneSegment {
     /// Returns minimum number of blocks to left in the database for this segment.
     pub const fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup | Self::Headers | Self::Transactions => {
-                0
-            }
+            Self::SenderRecovery |
+            Self::TransactionLookup |
+            Self::Headers |
+            Self::Transactions |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets => 0,
             Self::Receipts if purpose.is_static_file() => 0,
             Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
                 MINIMUM_PRUNING_DISTANCE
@@ -55,6 +64,16 @@ impl PruneSegment {
             Self::Receipts => MINIMUM_PRUNING_DISTANCE,
         }
     }
+
+    /// Returns true if the segment can keep the archive window of [`PruneMode::Window`].
+    ///
+    /// [`PruneMode::Window`]: crate::PruneMode::Window
+    pub const fn supports_archive_window(&self) -> bool {
+        matches!(
+            self,
+            Self::SenderRecovery | Self::Receipts | Self::AccountHistory | Self::StorageHistory
+        )
+    }
 }
 
 /// Prune purpose.
@@ -87,6 +106,9 @@ pub enum PruneSegmentError {
     /// Receipts have been pruned
     #[error("receipts have been pruned")]
     ReceiptsPruned,
+    /// Archive window of a prune segment has already been pruned with a different prune mode.
+    #[error("the archive window of {0} has already been pruned")]
+    ArchiveWindowPruned(PruneSegment),
 }
 
 #[cfg(test)]
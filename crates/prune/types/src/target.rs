@@ -1,4 +1,4 @@
-use crate::{PruneMode, ReceiptsLogPruneConfig};
+use crate::{PruneMode, ReceiptsLogPruneConfig, ReceiptsRetentionConfig};
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// Minimum distance from the tip necessary for the node to work correctly:
@@ -43,6 +43,11 @@ pub struct PruneModes {
     /// The [`BlockNumber`](`crate::BlockNumber`) represents the starting block from which point
     /// onwards the receipts are preserved.
     pub receipts_log_filter: ReceiptsLogPruneConfig,
+    /// Receipts pruning configuration by retaining only those receipts that match any of the
+    /// rules, each with its own prune mode, discarding others. Applies alongside
+    /// `receipts_log_filter`, and is overridden by `receipts`.
+    #[serde(skip_serializing_if = "ReceiptsRetentionConfig::is_empty")]
+    pub receipts_retention: ReceiptsRetentionConfig,
 }
 
 impl PruneModes {
@@ -60,12 +65,26 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
+            receipts_retention: Default::default(),
         }
     }
 
     /// Returns whether there is any kind of receipt pruning configuration.
     pub fn has_receipts_pruning(&self) -> bool {
-        self.receipts.is_some() || !self.receipts_log_filter.is_empty()
+        self.receipts.is_some() || self.has_receipts_retention()
+    }
+
+    /// Returns whether receipts are filtered by `receipts_log_filter` or `receipts_retention`.
+    pub fn has_receipts_retention(&self) -> bool {
+        !self.receipts_log_filter.is_empty() || !self.receipts_retention.is_empty()
+    }
+
+    /// Returns the rules of `receipts_retention`, along with the ones equivalent to
+    /// `receipts_log_filter`.
+    pub fn receipts_retention_rules(&self) -> ReceiptsRetentionConfig {
+        let mut rules = ReceiptsRetentionConfig::from(&self.receipts_log_filter);
+        rules.0.extend(self.receipts_retention.0.iter().cloned());
+        rules
     }
 
     /// Returns true if all prune modes are set to [`None`].
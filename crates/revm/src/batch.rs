@@ -2,7 +2,7 @@
 
 use alloc::vec::Vec;
 use alloy_eips::eip7685::Requests;
-use alloy_primitives::{map::HashSet, Address, BlockNumber};
+use alloy_primitives::BlockNumber;
 use reth_execution_errors::{BlockExecutionError, InternalBlockExecutionError};
 use reth_primitives::{Receipt, Receipts};
 use reth_prune_types::{PruneMode, PruneModes, PruneSegmentError, MINIMUM_PRUNING_DISTANCE};
@@ -29,11 +29,6 @@ pub struct BlockBatchRecord {
     /// A transaction may have zero or more requests, so the length of the inner vector is not
     /// guaranteed to be the same as the number of transactions.
     requests: Vec<Requests>,
-    /// Memoized address pruning filter.
-    ///
-    /// Empty implies that there is going to be addresses to include in the filter in a future
-    /// block. None means there isn't any kind of configuration.
-    pruning_address_filter: Option<(u64, HashSet<Address>)>,
     /// First block will be initialized to `None`
     /// and be set to the block number of first block executed.
     first_block: Option<BlockNumber>,
@@ -98,11 +93,11 @@ impl BlockBatchRecord {
             !self
                 .prune_modes
                 .account_history
-                .map_or(false, |mode| mode.should_prune(block_number, tip)) &&
+                .map_or(false, |mode| should_prune_reverts(mode, block_number, tip)) &&
                 !self
                     .prune_modes
                     .storage_history
-                    .map_or(false, |mode| mode.should_prune(block_number, tip))
+                    .map_or(false, |mode| should_prune_reverts(mode, block_number, tip))
         }) {
             BundleRetention::Reverts
         } else {
@@ -146,24 +141,28 @@ impl BlockBatchRecord {
             return Ok(())
         }
 
-        let contract_log_pruner = self.prune_modes.receipts_log_filter.group_by_block(tip, None)?;
+        let retention_rules = self.prune_modes.receipts_retention_rules();
+        if retention_rules.is_empty() {
+            return Ok(())
+        }
+
+        let rules_by_block = retention_rules.group_by_block(tip, None)?;
+        let active_rules = rules_by_block
+            .range(..=block_number)
+            .flat_map(|(_, rules)| rules.iter().copied())
+            .collect::<Vec<_>>();
 
-        if !contract_log_pruner.is_empty() {
-            let (prev_block, filter) =
-                self.pruning_address_filter.get_or_insert_with(|| (0, Default::default()));
-            for (_, addresses) in contract_log_pruner.range(*prev_block..=block_number) {
-                filter.extend(addresses.iter().copied());
-            }
+        // Senders and recipients of transactions are not known here, so receipts that may match
+        // such rules are left to [`PruneSegment::ContractLogs`] of the pruner.
+        if active_rules.iter().any(|rule| rule.matches_transactions()) {
+            return Ok(())
         }
 
-        if let Some((_, filter)) = &self.pruning_address_filter {
-            for receipt in receipts.iter_mut() {
-                // If there is an address_filter, it does not contain any of the
-                // contract addresses, then remove this receipt.
-                let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
-                if !inner_receipt.logs.iter().any(|log| filter.contains(&log.address)) {
-                    receipt.take();
-                }
+        for receipt in receipts.iter_mut() {
+            // If the receipt doesn't match any of the retention rules, then remove it.
+            let inner_receipt = receipt.as_ref().expect("receipts have not been pruned");
+            if !active_rules.iter().any(|rule| rule.matches(&inner_receipt.logs, None, None)) {
+                receipt.take();
             }
         }
 
@@ -176,13 +175,25 @@ impl BlockBatchRecord {
     }
 }
 
+/// Returns true if the reverts of the block don't need to be written according to the history
+/// prune mode.
+///
+/// Reverts after the archive window of [`PruneMode::Window`] are always written, because the
+/// pruner retains the lowest changeset of each account and storage slot after the window.
+fn should_prune_reverts(mode: PruneMode, block_number: BlockNumber, tip: BlockNumber) -> bool {
+    mode.should_prune(block_number, tip) &&
+        mode.archive_window().map_or(true, |window| block_number < window.start)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloc::collections::BTreeMap;
-    use alloy_primitives::Address;
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloy_primitives::{Address, B256};
     use reth_primitives::{Log, Receipt};
-    use reth_prune_types::{PruneMode, ReceiptsLogPruneConfig};
+    use reth_prune_types::{
+        PruneMode, ReceiptsLogPruneConfig, ReceiptsRetentionConfig, ReceiptsRetentionRule,
+    };
 
     #[test]
     fn test_save_receipts_empty() {
@@ -361,4 +372,36 @@ mod tests {
         assert_eq!(recorder.receipts().len(), 4);
         assert_eq!(recorder.receipts()[3][0], Some(receipt3));
     }
+
+    // Test saving receipts with a topic retention rule
+    #[test]
+    fn test_save_receipts_with_topic_rule_pruning() {
+        let transfer = B256::with_last_byte(1);
+        let prune_modes = PruneModes {
+            receipts_retention: ReceiptsRetentionConfig(vec![ReceiptsRetentionRule {
+                topics: BTreeSet::from([transfer]),
+                ..ReceiptsRetentionRule::address(Address::with_last_byte(1), PruneMode::Before(1))
+            }]),
+            ..Default::default()
+        };
+        let mut recorder = BlockBatchRecord::new(prune_modes);
+        recorder.set_first_block(1);
+        recorder.set_tip(1300000);
+
+        // With a receipt of the address, but without the topic
+        let mut receipt = Receipt::default();
+        receipt.logs.push(Log { address: Address::with_last_byte(1), ..Default::default() });
+        assert!(recorder.save_receipts(vec![receipt]).is_ok());
+        assert_eq!(recorder.receipts()[0], vec![None]);
+
+        // With a receipt of the address and the topic
+        let mut receipt = Receipt::default();
+        receipt.logs.push(Log::new_unchecked(
+            Address::with_last_byte(1),
+            vec![transfer],
+            Default::default(),
+        ));
+        assert!(recorder.save_receipts(vec![receipt.clone()]).is_ok());
+        assert_eq!(recorder.receipts()[1], vec![Some(receipt)]);
+    }
 }
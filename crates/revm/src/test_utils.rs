@@ -6,13 +6,14 @@ use alloy_primitives::{
 };
 use reth_primitives::{Account, Bytecode};
 use reth_storage_api::{
-    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
-    StorageRootProvider,
+    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRangeProvider,
+    StateRootProvider, StorageRootProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof,
-    TrieInput,
+    range::{HashedAccountRange, HashedStorageRange},
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof, TrieInput,
 };
 
 /// Mock state for testing
@@ -141,6 +142,47 @@ impl StateProofProvider for StateProviderTest {
     }
 }
 
+impl StateRangeProvider for StateProviderTest {
+    fn account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+        _with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        unimplemented!("state ranges are not supported")
+    }
+
+    fn storage_range(
+        &self,
+        _address: Address,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        unimplemented!("state ranges are not supported")
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        unimplemented!("state ranges are not supported")
+    }
+
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        unimplemented!("state ranges are not supported")
+    }
+}
+
 impl StateProvider for StateProviderTest {
     fn storage(
         &self,
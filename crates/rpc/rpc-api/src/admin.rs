@@ -1,6 +1,25 @@
-use alloy_rpc_types_admin::{NodeInfo, PeerInfo};
+use alloy_eips::BlockNumHash;
+use alloy_rpc_types_admin::{NodeInfo, PeerEvent, PeerInfo};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_net_banlist::{BanEntry, IpNet};
 use reth_network_peers::{AnyNode, NodeRecord};
+use reth_network_types::{Reputation, ReputationChangeRecord};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The [`PeerInfo`] of a connected peer returned by `admin_peers`, extended with the reputation
+/// tracked for the peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfoWithReputation {
+    /// The geth compatible info of the peer.
+    #[serde(flatten)]
+    pub info: PeerInfo,
+    /// The current reputation of the peer.
+    pub reputation: Reputation,
+    /// The most recent reputation changes applied to the peer, oldest first.
+    pub reputation_history: Vec<ReputationChangeRecord>,
+}
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
@@ -27,22 +46,58 @@ pub trait AdminApi {
     #[method(name = "removeTrustedPeer")]
     fn remove_trusted_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
+    /// Bans a remote node, disconnecting it if the connection exists.
+    ///
+    /// The ban is indefinite unless a duration in seconds is given.
+    #[method(name = "banPeer")]
+    fn ban_peer(&self, record: AnyNode, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of a remote node.
+    #[method(name = "unbanPeer")]
+    fn unban_peer(&self, record: AnyNode) -> RpcResult<bool>;
+
+    /// Bans all IP addresses of the given subnet in CIDR notation, disconnecting connected nodes
+    /// in the subnet.
+    ///
+    /// The ban is indefinite unless a duration in seconds is given.
+    #[method(name = "banSubnet")]
+    fn ban_subnet(&self, subnet: IpNet, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of the given subnet in CIDR notation.
+    #[method(name = "unbanSubnet")]
+    fn unban_subnet(&self, subnet: IpNet) -> RpcResult<bool>;
+
+    /// Returns all active bans of nodes, IP addresses and subnets.
+    #[method(name = "listBans")]
+    async fn list_bans(&self) -> RpcResult<Vec<BanEntry>>;
+
     /// The peers administrative property can be queried for all the information known about the
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
-    /// specialized information added by each of the running application protocols
+    /// specialized information added by each of the running application protocols, and the
+    /// reputation the node tracks for them.
     #[method(name = "peers")]
-    async fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
+    async fn peers(&self) -> RpcResult<Vec<PeerInfoWithReputation>>;
 
     /// Creates an RPC subscription which serves events received from the network.
+    ///
+    /// Emits an `add` or `drop` event whenever a peer session is established or closed, and a
+    /// `msgsend` or `msgrecv` event for every `eth` message exchanged with a peer.
     #[subscription(
         name = "peerEvents",
         unsubscribe = "peerEvents_unsubscribe",
-        item = String
+        item = PeerEvent
     )]
     async fn subscribe_peer_events(&self) -> jsonrpsee::core::SubscriptionResult;
 
     /// Returns the ENR of the node.
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
+
+    /// Writes a consistent backup of the database and static files into the given directory on
+    /// the host of the node, which must be empty or not exist.
+    ///
+    /// Returns the block the backup was taken at.
+    #[method(name = "backup")]
+    async fn backup(&self, path: PathBuf) -> RpcResult<BlockNumHash>;
 }
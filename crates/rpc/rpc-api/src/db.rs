@@ -0,0 +1,83 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Statistics of a database table, returned by `reth_db_tables` and `reth_db_stats`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbTableStats {
+    /// Name of the table.
+    pub name: String,
+    /// Whether the table is a `DUPSORT` table, whose keys can have multiple values.
+    pub dupsort: bool,
+    /// Number of entries, counting every value of a `DUPSORT` table.
+    pub entries: usize,
+    /// Size of the table in bytes.
+    pub size: u64,
+}
+
+/// An entry of a database table.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbTableEntry {
+    /// Key of the entry.
+    pub key: Value,
+    /// Value of the entry.
+    pub value: Value,
+}
+
+/// Range of keys of a database table walked by `reth_db_range`, in ascending order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbRangeRequest {
+    /// Key to start at, or the first key of the table if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<Value>,
+    /// Subkey of the `start` key to start at, for `DUPSORT` tables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subkey: Option<Value>,
+    /// Last key of the range, inclusive, or the last key of the table if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<Value>,
+    /// Maximum number of entries to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// The result of `reth_db_range`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbRangePage {
+    /// The entries of the page.
+    pub entries: Vec<DbTableEntry>,
+    /// The request to pass to fetch the next page, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<DbRangeRequest>,
+}
+
+/// Read-only access to the database tables of the node.
+///
+/// Keys and values are the JSON representation of the types of the table.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth_db"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "reth_db"))]
+pub trait DbApi {
+    /// Returns the statistics of all tables.
+    #[method(name = "tables")]
+    async fn tables(&self) -> RpcResult<Vec<DbTableStats>>;
+
+    /// Returns the statistics of a table.
+    #[method(name = "stats")]
+    async fn stats(&self, table: String) -> RpcResult<DbTableStats>;
+
+    /// Returns the value of a key of a table, or `null` if it doesn't exist.
+    ///
+    /// For `DUPSORT` tables, returns the value of the subkey if one is given, or the first value
+    /// of the key otherwise.
+    #[method(name = "get")]
+    async fn get(
+        &self,
+        table: String,
+        key: Value,
+        subkey: Option<Value>,
+    ) -> RpcResult<Option<Value>>;
+
+    /// Returns a page of the entries of a table within the range.
+    #[method(name = "range")]
+    async fn range(&self, table: String, request: DbRangeRequest) -> RpcResult<DbRangePage>;
+}
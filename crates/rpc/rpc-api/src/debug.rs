@@ -3,10 +3,71 @@ use alloy_rpc_types::{Block, Bundle, StateContext};
 use alloy_rpc_types_debug::ExecutionWitness;
 use alloy_rpc_types_eth::transaction::TransactionRequest;
 use alloy_rpc_types_trace::geth::{
-    BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, TraceResult,
+    BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions,
+    GethDefaultTracingOptions, GethTrace, TraceResult,
 };
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{BlockId, BlockNumberOrTag};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The result of `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots in the range, keyed by hashed slot.
+    pub storage: BTreeMap<B256, StorageRangeEntry>,
+    /// The hashed key of the first slot after the range, if any.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot of a [`StorageRangeResult`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageRangeEntry {
+    /// The slot, if its preimage is known.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: B256,
+}
+
+/// The result of `debug_accountRange`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRangeResult {
+    /// The state root of the block.
+    pub root: B256,
+    /// The accounts in the range, keyed by address, or by `pre(<hashed address>)` if the preimage
+    /// of the hashed address is unknown.
+    pub accounts: BTreeMap<String, DumpAccount>,
+    /// The key to pass as `start` to fetch the next page, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<Bytes>,
+}
+
+/// An account of an [`AccountRangeResult`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpAccount {
+    /// The balance of the account, in decimal.
+    pub balance: String,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The storage root of the account.
+    pub root: B256,
+    /// The code hash of the account.
+    pub code_hash: B256,
+    /// The code of the account, unless it was omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The storage of the account, keyed by slot, or by hashed slot if the preimage is unknown.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, B256>,
+    /// The address of the account, if the preimage of the hashed address is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The hashed address of the account.
+    #[serde(rename = "key")]
+    pub address_hash: B256,
+}
 
 /// Debug rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
@@ -45,8 +106,24 @@ pub trait DebugApi {
         &self,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<BlockTraceResult>>;
 
+    /// Creates a subscription that traces all blocks between two blocks (excluding start), like
+    /// `debug_traceChain`, and emits the traces of each block, in order, as soon as they are
+    /// available.
+    #[subscription(
+        name = "subscribeTraceChain" => "traceChain",
+        unsubscribe = "unsubscribeTraceChain",
+        item = BlockTraceResult
+    )]
+    async fn subscribe_trace_chain(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
     ///
@@ -163,7 +240,7 @@ pub trait DebugApi {
         nocode: bool,
         nostorage: bool,
         incompletes: bool,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<AccountRangeResult>;
 
     /// Turns on block profiling for the given duration and writes profile data to disk. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
@@ -234,17 +311,18 @@ pub trait DebugApi {
     async fn debug_get_modified_accounts_by_hash(
         &self,
         start_hash: B256,
-        end_hash: B256,
-    ) -> RpcResult<()>;
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Returns all accounts that have changed between the two blocks specified. A change is defined
-    /// as a difference in nonce, balance, code hash or storage hash.
+    /// as a difference in nonce, balance, code hash or storage hash. With one parameter, returns
+    /// the list of accounts modified in the specified block.
     #[method(name = "getModifiedAccountsByNumber")]
     async fn debug_get_modified_accounts_by_number(
         &self,
         start_number: u64,
-        end_number: u64,
-    ) -> RpcResult<()>;
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Turns on Go runtime tracing for the given duration and writes trace data to disk.
     #[method(name = "goTrace")]
@@ -257,7 +335,7 @@ pub trait DebugApi {
         &self,
         block_hash: B256,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<Vec<B256>>;
 
     /// Returns detailed runtime memory statistics.
     #[method(name = "memStats")]
@@ -310,13 +388,14 @@ pub trait DebugApi {
     #[method(name = "stacks")]
     async fn debug_stacks(&self) -> RpcResult<()>;
 
-    /// Used to obtain info about a block.
+    /// Replays a block which has been rejected as invalid and writes the struct logs of each of
+    /// its transactions to a separate file. Returns the names of the files that were written.
     #[method(name = "standardTraceBadBlockToFile")]
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        block: BlockNumberOrTag,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        block_hash: B256,
+        opts: Option<GethDefaultTracingOptions>,
+    ) -> RpcResult<Vec<String>>;
 
     /// This method is similar to `debug_standardTraceBlockToFile`, but can be used to obtain info
     /// about a block which has been rejected as invalid (for some reason).
@@ -354,7 +433,7 @@ pub trait DebugApi {
         contract_address: Address,
         key_start: B256,
         max_result: u64,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<StorageRangeResult>;
 
     /// Returns the structured logs created during the execution of EVM against a block pulled
     /// from the pool of bad ones and returns them as a JSON object. For the second parameter see
@@ -363,8 +442,8 @@ pub trait DebugApi {
     async fn debug_trace_bad_block(
         &self,
         block_hash: B256,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>>;
 
     /// Sets the logging verbosity ceiling. Log messages with level up to and including the given
     /// level will be printed.
@@ -10,7 +10,7 @@ pub trait HardhatApi {
     /// Removes the given transaction from the mempool, if it exists.
     ///
     /// Returns `true` if successful, otherwise `false`.
-    #[method(name = "hardhat_dropTransaction")]
+    #[method(name = "dropTransaction")]
     async fn hardhat_drop_transaction(&self, tx_hash: B256) -> RpcResult<bool>;
 
     /// Allows Hardhat Network to sign transactions as the given address.
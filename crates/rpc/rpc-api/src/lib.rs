@@ -16,6 +16,7 @@
 
 mod admin;
 mod anvil;
+mod db;
 mod debug;
 mod engine;
 mod ganache;
@@ -30,6 +31,10 @@ mod txpool;
 mod validation;
 mod web3;
 
+pub use admin::PeerInfoWithReputation;
+pub use db::{DbRangePage, DbRangeRequest, DbTableEntry, DbTableStats};
+pub use debug::{AccountRangeResult, DumpAccount, StorageRangeEntry, StorageRangeResult};
+
 /// re-export of all server traits
 pub use servers::*;
 
@@ -37,8 +42,11 @@ pub use servers::*;
 pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
+        anvil::AnvilApiServer,
+        db::DbApiServer,
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
+        hardhat::HardhatApiServer,
         mev::{MevFullApiServer, MevSimApiServer},
         net::NetApiServer,
         otterscan::OtterscanServer,
@@ -65,6 +73,7 @@ pub mod clients {
     pub use crate::{
         admin::AdminApiClient,
         anvil::AnvilApiClient,
+        db::DbApiClient,
         debug::DebugApiClient,
         engine::{EngineApiClient, EngineEthApiClient},
         ganache::GanacheApiClient,
@@ -70,7 +70,7 @@ pub trait Otterscan<T: RpcObject> {
         address: Address,
         block_number: u64,
         page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts>;
+    ) -> RpcResult<TransactionsWithReceipts<T>>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
     #[method(name = "searchTransactionsAfter")]
@@ -79,7 +79,7 @@ pub trait Otterscan<T: RpcObject> {
         address: Address,
         block_number: u64,
         page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts>;
+    ) -> RpcResult<TransactionsWithReceipts<T>>;
 
     /// Gets the transaction hash for a certain sender address, given its nonce.
     #[method(name = "getTransactionBySenderAndNonce")]
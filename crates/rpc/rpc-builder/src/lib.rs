@@ -17,7 +17,7 @@
 //!
 //! ```
 //! use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
-//! use reth_network_api::{NetworkInfo, Peers};
+//! use reth_network_api::{NetworkEventListenerProvider, NetworkInfo, Peers};
 //! use reth_primitives::Header;
 //! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
 //! use reth_rpc::EthApi;
@@ -37,7 +37,7 @@
 //! ) where
 //!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
 //!     Pool: TransactionPool + Unpin + 'static,
-//!     Network: NetworkInfo + Peers + Clone + 'static,
+//!     Network: NetworkInfo + Peers + NetworkEventListenerProvider + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
 //!     EvmConfig: ConfigureEvm<Header = Header>,
 //!     BlockExecutor: BlockExecutorProvider,
@@ -72,7 +72,7 @@
 //! ```
 //! use reth_engine_primitives::EngineTypes;
 //! use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
-//! use reth_network_api::{NetworkInfo, Peers};
+//! use reth_network_api::{NetworkEventListenerProvider, NetworkInfo, Peers};
 //! use reth_primitives::Header;
 //! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
 //! use reth_rpc::EthApi;
@@ -106,7 +106,7 @@
 //! ) where
 //!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
 //!     Pool: TransactionPool + Unpin + 'static,
-//!     Network: NetworkInfo + Peers + Clone + 'static,
+//!     Network: NetworkInfo + Peers + NetworkEventListenerProvider + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
 //!     EngineApi: EngineApiServer<EngineT>,
 //!     EngineT: EngineTypes,
@@ -155,6 +155,7 @@ use std::{
     collections::HashMap,
     fmt::Debug,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
@@ -170,17 +171,19 @@ use jsonrpsee::{
     Methods, RpcModule,
 };
 use reth_chainspec::EthereumHardforks;
-use reth_engine_primitives::EngineTypes;
+use reth_db_common::{backup::DatabaseBackup, explorer::DatabaseExplorer};
+use reth_engine_primitives::{BadBlockStore, EngineTypes, LocalMinerHandle};
 use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
-use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
+use reth_network_api::{noop::NoopNetwork, NetworkEventListenerProvider, NetworkInfo, Peers};
 use reth_primitives::Header;
 use reth_provider::{
     AccountReader, BlockReader, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
     EvmEnvProvider, FullRpcProvider, StateProviderFactory,
 };
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthBundle, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi,
-    TxPoolApi, ValidationApi, Web3Api,
+    AdminApi, AnvilApi, DbApi, DebugApi, EngineEthApi, EthBundle, EthSimBundle, NetApi,
+    OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, ValidationApi, ValidationApiConfig,
+    Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
@@ -194,6 +197,7 @@ use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
 use serde::{Deserialize, Serialize};
 use tower::Layer;
 use tower_http::cors::CorsLayer;
+use tracing::warn;
 
 use crate::{auth::AuthRpcModule, error::WsHttpSamePortError, metrics::RpcRequestMetrics};
 
@@ -247,7 +251,7 @@ pub async fn launch<Provider, Pool, Network, Tasks, Events, EvmConfig, EthApi, B
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
     Pool: TransactionPool + 'static,
-    Network: NetworkInfo + Peers + Clone + 'static,
+    Network: NetworkInfo + Peers + NetworkEventListenerProvider + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm<Header = reth_primitives::Header>,
@@ -477,7 +481,7 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor>
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
     Pool: TransactionPool + 'static,
-    Network: NetworkInfo + Peers + Clone + 'static,
+    Network: NetworkInfo + Peers + NetworkEventListenerProvider + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm<Header = Header>,
@@ -632,6 +636,8 @@ impl Default for RpcModuleBuilder<(), (), (), (), (), (), ()> {
 pub struct RpcModuleConfig {
     /// `eth` namespace settings
     eth: EthConfig,
+    /// `flashbots` namespace settings
+    flashbots: ValidationApiConfig,
 }
 
 // === impl RpcModuleConfig ===
@@ -642,9 +648,9 @@ impl RpcModuleConfig {
         RpcModuleConfigBuilder::default()
     }
 
-    /// Returns a new RPC module config given the eth namespace config
-    pub const fn new(eth: EthConfig) -> Self {
-        Self { eth }
+    /// Returns a new RPC module config given the eth and flashbots namespace configs
+    pub const fn new(eth: EthConfig, flashbots: ValidationApiConfig) -> Self {
+        Self { eth, flashbots }
     }
 
     /// Get a reference to the eth namespace config
@@ -656,12 +662,18 @@ impl RpcModuleConfig {
     pub fn eth_mut(&mut self) -> &mut EthConfig {
         &mut self.eth
     }
+
+    /// Get a reference to the flashbots namespace config
+    pub const fn flashbots(&self) -> &ValidationApiConfig {
+        &self.flashbots
+    }
 }
 
 /// Configures [`RpcModuleConfig`]
 #[derive(Clone, Debug, Default)]
 pub struct RpcModuleConfigBuilder {
     eth: Option<EthConfig>,
+    flashbots: Option<ValidationApiConfig>,
 }
 
 // === impl RpcModuleConfigBuilder ===
@@ -673,10 +685,16 @@ impl RpcModuleConfigBuilder {
         self
     }
 
+    /// Configures a custom flashbots namespace config
+    pub fn flashbots(mut self, flashbots: ValidationApiConfig) -> Self {
+        self.flashbots = Some(flashbots);
+        self
+    }
+
     /// Consumes the type and creates the [`RpcModuleConfig`]
     pub fn build(self) -> RpcModuleConfig {
-        let Self { eth } = self;
-        RpcModuleConfig { eth: eth.unwrap_or_default() }
+        let Self { eth, flashbots } = self;
+        RpcModuleConfig { eth: eth.unwrap_or_default(), flashbots: flashbots.unwrap_or_default() }
     }
 
     /// Get a reference to the eth namespace config, if any
@@ -714,8 +732,18 @@ pub struct RpcRegistryInner<
     block_executor: BlockExecutor,
     /// Holds a all `eth_` namespace handlers
     eth: EthHandlers<Provider, Pool, Network, Events, EthApi>,
+    /// The settings of the `flashbots_` namespace
+    flashbots_config: ValidationApiConfig,
     /// to put trace calls behind semaphore
     blocking_pool_guard: BlockingTaskGuard,
+    /// The blocks rejected by the engine, served by the `debug_` namespace
+    bad_blocks: BadBlockStore,
+    /// The miner of the dev chain, controlled by the `anvil_` and `hardhat_` namespaces
+    local_miner: Option<LocalMinerHandle>,
+    /// Writes the database backups of the `admin_` namespace
+    database_backup: Option<Arc<dyn DatabaseBackup>>,
+    /// Reads the database tables for the `reth_db_` namespace
+    database_explorer: Option<Arc<dyn DatabaseExplorer>>,
     /// Contains the [Methods] of a module
     modules: HashMap<RethRpcModule, Methods>,
 }
@@ -775,9 +803,14 @@ where
             pool,
             network,
             eth,
+            flashbots_config: config.flashbots,
             executor,
             modules: Default::default(),
             blocking_pool_guard,
+            bad_blocks: Default::default(),
+            local_miner: None,
+            database_backup: None,
+            database_explorer: None,
             events,
             block_executor,
         }
@@ -794,6 +827,42 @@ where
         &self.eth.api
     }
 
+    /// Sets the [`BadBlockStore`] that is served by the `debug_` namespace.
+    ///
+    /// Note: This only affects the `debug_` handlers that are created afterwards.
+    pub fn set_bad_blocks(&mut self, bad_blocks: BadBlockStore) -> &mut Self {
+        self.bad_blocks = bad_blocks;
+        self
+    }
+
+    /// Sets the [`LocalMinerHandle`] of the dev chain that is controlled by the `anvil_` and
+    /// `hardhat_` namespaces.
+    ///
+    /// Note: This only affects the `anvil_` handlers that are created afterwards.
+    pub fn set_local_miner(&mut self, local_miner: LocalMinerHandle) -> &mut Self {
+        self.local_miner = Some(local_miner);
+        self
+    }
+
+    /// Sets the [`DatabaseBackup`] that writes the backups of `admin_backup`.
+    ///
+    /// Note: This only affects the `admin_` handlers that are created afterwards.
+    pub fn set_database_backup(&mut self, database_backup: Arc<dyn DatabaseBackup>) -> &mut Self {
+        self.database_backup = Some(database_backup);
+        self
+    }
+
+    /// Sets the [`DatabaseExplorer`] that reads the tables for the `reth_db_` namespace.
+    ///
+    /// Note: This only affects the `reth_db_` handlers that are created afterwards.
+    pub fn set_database_explorer(
+        &mut self,
+        database_explorer: Arc<dyn DatabaseExplorer>,
+    ) -> &mut Self {
+        self.database_explorer = Some(database_explorer);
+        self
+    }
+
     /// Returns a reference to the installed [`EthHandlers`].
     pub const fn eth_handlers(&self) -> &EthHandlers<Provider, Pool, Network, Events, EthApi> {
         &self.eth
@@ -853,9 +922,10 @@ where
     /// Instantiates `AdminApi`
     pub fn admin_api(&self) -> AdminApi<Network, Provider::ChainSpec>
     where
-        Network: Peers,
+        Network: Peers + NetworkEventListenerProvider,
     {
         AdminApi::new(self.network.clone(), self.provider.chain_spec())
+            .with_database_backup(self.database_backup.clone())
     }
 
     /// Instantiates `Web3Api`
@@ -866,7 +936,7 @@ where
     /// Register Admin Namespace
     pub fn register_admin(&mut self) -> &mut Self
     where
-        Network: Peers,
+        Network: Peers + NetworkEventListenerProvider,
     {
         let adminapi = self.admin_api();
         self.modules.insert(RethRpcModule::Admin, adminapi.into_rpc().into());
@@ -988,9 +1058,9 @@ where
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
-    pub fn otterscan_api(&self) -> OtterscanApi<EthApi> {
+    pub fn otterscan_api(&self) -> OtterscanApi<Provider, EthApi> {
         let eth_api = self.eth_api().clone();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api)
     }
 }
 
@@ -1048,6 +1118,7 @@ where
             self.blocking_pool_guard.clone(),
             self.block_executor.clone(),
         )
+        .with_bad_blocks(self.bad_blocks.clone())
     }
 
     /// Instantiates `NetApi`
@@ -1069,8 +1140,16 @@ where
     }
 
     /// Instantiates `ValidationApi`
-    pub fn validation_api(&self) -> ValidationApi<Provider> {
-        ValidationApi::new(self.provider.clone())
+    pub fn validation_api(&self) -> ValidationApi<Provider, BlockExecutor>
+    where
+        BlockExecutor: BlockExecutorProvider,
+    {
+        ValidationApi::new(
+            self.provider.clone(),
+            self.block_executor.clone(),
+            self.flashbots_config.clone(),
+            Box::new(self.executor.clone()),
+        )
     }
 }
 
@@ -1079,7 +1158,7 @@ impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor>
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
     Pool: TransactionPool + 'static,
-    Network: NetworkInfo + Peers + Clone + 'static,
+    Network: NetworkInfo + Peers + NetworkEventListenerProvider + Clone + 'static,
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     EthApi: FullEthApiServer,
@@ -1169,6 +1248,7 @@ where
                     .or_insert_with(|| match namespace {
                         RethRpcModule::Admin => {
                             AdminApi::new(self.network.clone(), self.provider.chain_spec())
+                                .with_database_backup(self.database_backup.clone())
                                 .into_rpc()
                                 .into()
                         }
@@ -1178,6 +1258,7 @@ where
                             self.blocking_pool_guard.clone(),
                             self.block_executor.clone(),
                         )
+                        .with_bad_blocks(self.bad_blocks.clone())
                         .into_rpc()
                         .into(),
                         RethRpcModule::Eth => {
@@ -1222,14 +1303,50 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => {
+                            OtterscanApi::new(self.provider.clone(), eth_api.clone())
+                                .into_rpc()
+                                .into()
+                        }
                         RethRpcModule::Reth => {
                             RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
                                 .into_rpc()
                                 .into()
                         }
-                        RethRpcModule::Flashbots => {
-                            ValidationApi::new(self.provider.clone()).into_rpc().into()
+                        RethRpcModule::Flashbots => ValidationApi::new(
+                            self.provider.clone(),
+                            self.block_executor.clone(),
+                            self.flashbots_config.clone(),
+                            Box::new(self.executor.clone()),
+                        )
+                        .into_rpc()
+                        .into(),
+                        RethRpcModule::Mev => {
+                            EthSimBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
+                                .into_rpc()
+                                .into()
+                        }
+                        RethRpcModule::Anvil => {
+                            let Some(local_miner) = self.local_miner.clone() else {
+                                warn!(target: "rpc", "The anvil module is only available in dev mode");
+                                return Methods::default()
+                            };
+                            AnvilApi::new(
+                                self.provider.clone(),
+                                self.pool.clone(),
+                                self.network.clone(),
+                                eth_api.clone(),
+                                local_miner,
+                            )
+                            .into_rpc_module()
+                            .into()
+                        }
+                        RethRpcModule::RethDb => {
+                            let Some(explorer) = self.database_explorer.clone() else {
+                                warn!(target: "rpc", "The reth_db module requires access to the database");
+                                return Methods::default()
+                            };
+                            DbApi::new(explorer).into_rpc().into()
                         }
                     })
                     .clone()
@@ -2158,6 +2275,9 @@ mod tests {
                 "rpc" => RethRpcModule::Rpc,
                 "ots" => RethRpcModule::Ots,
                 "reth" => RethRpcModule::Reth,
+                "mev" => RethRpcModule::Mev,
+                "anvil" => RethRpcModule::Anvil,
+                "reth_db" => RethRpcModule::RethDb,
             );
     }
 
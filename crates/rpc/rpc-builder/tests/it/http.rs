@@ -1,8 +1,9 @@
 #![allow(unreachable_pub)]
 //! Standalone http tests
 
-use crate::utils::{launch_http, launch_http_ws, launch_ws};
-use alloy_primitives::{hex_literal::hex, Address, Bytes, TxHash, B256, B64, U256, U64};
+use crate::utils::{launch_http, launch_http_with_provider, launch_http_ws, launch_ws};
+use alloy_consensus::TxLegacy;
+use alloy_primitives::{hex_literal::hex, Address, Bytes, TxHash, TxKind, B256, B64, U256, U64};
 use alloy_rpc_types::{
     Block, FeeHistory, Filter, Index, Log, PendingTransactionFilterKind, SyncStatus, Transaction,
     TransactionReceipt,
@@ -19,13 +20,18 @@ use jsonrpsee::{
     types::error::ErrorCode,
 };
 use reth_network_peers::NodeRecord;
-use reth_primitives::{BlockId, BlockNumberOrTag, Receipt};
+use reth_primitives::{
+    Block as PrimitiveBlock, BlockBody, BlockId, BlockNumberOrTag, Header, Receipt,
+    Transaction as PrimitiveTransaction, TxType,
+};
+use reth_provider::test_utils::MockEthProvider;
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
     DebugApiClient, EthFilterApiClient, NetApiClient, OtterscanClient, TraceApiClient,
     Web3ApiClient,
 };
 use reth_rpc_server_types::RethRpcModule;
+use reth_testing_utils::generators::{self, sign_tx_with_random_key_pair};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashSet;
@@ -145,6 +151,12 @@ where
     AdminApiClient::remove_peer(client, node.into()).await.unwrap();
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
+    AdminApiClient::ban_peer(client, node.into(), Some(60)).await.unwrap();
+    AdminApiClient::unban_peer(client, node.into()).await.unwrap();
+    let subnet = "1.1.0.0/16".parse().unwrap();
+    AdminApiClient::ban_subnet(client, subnet, None).await.unwrap();
+    AdminApiClient::unban_subnet(client, subnet).await.unwrap();
+    AdminApiClient::list_bans(client).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
 }
 
@@ -336,7 +348,8 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap_err();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
-    assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    DebugApiClient::bad_blocks(client).await.unwrap();
+    DebugApiClient::debug_get_modified_accounts_by_number(client, 0, None).await.unwrap_err();
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -431,28 +444,22 @@ where
     .err()
     .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::<Transaction>::search_transactions_before(
-            client,
-            address,
-            block_number,
-            page_size,
-        )
-        .await
-        .err()
-        .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::<Transaction>::search_transactions_after(
-            client,
-            address,
-            block_number,
-            page_size,
-        )
-        .await
-        .err()
-        .unwrap()
-    ));
+    OtterscanClient::<Transaction>::search_transactions_before(
+        client,
+        address,
+        block_number,
+        page_size,
+    )
+    .await
+    .unwrap();
+    OtterscanClient::<Transaction>::search_transactions_after(
+        client,
+        address,
+        block_number,
+        page_size,
+    )
+    .await
+    .unwrap();
     assert!(OtterscanClient::<Transaction>::get_transaction_by_sender_and_nonce(
         client, sender, nonce
     )
@@ -663,6 +670,104 @@ async fn test_call_otterscan_functions_http_and_ws() {
     test_basic_otterscan_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_otterscan_search_transactions_of_callee() {
+    reth_tracing::init_test_tracing();
+
+    // The history indices of the mock provider are empty, so the transactions can only be found
+    // by their recipient.
+    let callee = Address::with_last_byte(0xaa);
+    let other = Address::with_last_byte(0xbb);
+    let recipients: [&[Address]; 6] = [&[], &[callee], &[other], &[callee, callee], &[], &[callee]];
+
+    let mut rng = generators::rng();
+    let provider = MockEthProvider::default();
+    let mut parent_hash = B256::ZERO;
+    let mut hashes = Vec::new();
+    for (number, recipients) in recipients.into_iter().enumerate() {
+        let transactions = recipients
+            .iter()
+            .map(|to| {
+                let tx = TxLegacy {
+                    chain_id: Some(1),
+                    gas_limit: 21_000,
+                    to: TxKind::Call(*to),
+                    ..Default::default()
+                };
+                sign_tx_with_random_key_pair(&mut rng, PrimitiveTransaction::Legacy(tx))
+            })
+            .collect::<Vec<_>>();
+        let receipts = (1..=transactions.len() as u64)
+            .map(|i| Receipt {
+                tx_type: TxType::Legacy,
+                success: true,
+                cumulative_gas_used: 21_000 * i,
+                ..Default::default()
+            })
+            .collect();
+        hashes.push(transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>());
+
+        let header = Header {
+            number: number as u64,
+            parent_hash,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        };
+        let hash = header.hash_slow();
+        provider.add_block(
+            hash,
+            PrimitiveBlock { header, body: BlockBody { transactions, ..Default::default() } },
+        );
+        provider.add_receipts(hash, receipts);
+        parent_hash = hash;
+    }
+
+    let handle = launch_http_with_provider(vec![RethRpcModule::Ots], provider).await;
+    let client = handle.http_client().unwrap();
+
+    let search_before = |block_number, page_size| {
+        OtterscanClient::<Transaction>::search_transactions_before(
+            &client,
+            callee,
+            block_number,
+            page_size,
+        )
+    };
+    let search_after = |block_number, page_size| {
+        OtterscanClient::<Transaction>::search_transactions_after(
+            &client,
+            callee,
+            block_number,
+            page_size,
+        )
+    };
+    let found = |txs: &[Transaction]| txs.iter().map(|tx| tx.hash).collect::<Vec<_>>();
+
+    // Blocks are never split across pages, so a page may be larger than requested
+    let page = search_after(0, 2).await.unwrap();
+    assert_eq!(found(&page.txs), vec![hashes[3][1], hashes[3][0], hashes[1][0]]);
+    assert_eq!(page.receipts.len(), 3);
+    assert!(!page.first_page);
+    assert!(page.last_page);
+
+    let page = search_before(0, 2).await.unwrap();
+    assert_eq!(found(&page.txs), vec![hashes[5][0], hashes[3][1], hashes[3][0]]);
+    assert!(page.first_page);
+    assert!(!page.last_page);
+
+    let page = search_before(3, 2).await.unwrap();
+    assert_eq!(found(&page.txs), vec![hashes[1][0]]);
+    assert!(!page.first_page);
+    assert!(page.last_page);
+
+    let page = search_after(3, 10).await.unwrap();
+    assert_eq!(found(&page.txs), vec![hashes[5][0]]);
+    assert!(page.first_page);
+    assert!(!page.last_page);
+
+    search_after(u64::MAX, 10).await.unwrap_err();
+}
+
 // <https://github.com/paradigmxyz/reth/issues/5830>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_eth_logs_args() {
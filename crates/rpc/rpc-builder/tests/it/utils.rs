@@ -8,7 +8,7 @@ use reth_evm::execute::BasicBlockExecutorProvider;
 use reth_evm_ethereum::{execute::EthExecutionStrategyFactory, EthEvmConfig};
 use reth_network_api::noop::NoopNetwork;
 use reth_payload_builder::test_utils::spawn_test_payload_service;
-use reth_provider::test_utils::{NoopProvider, TestCanonStateSubscriptions};
+use reth_provider::test_utils::{MockEthProvider, NoopProvider, TestCanonStateSubscriptions};
 use reth_rpc::EthApi;
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerConfig, AuthServerHandle},
@@ -69,6 +69,22 @@ pub async fn launch_http(modules: impl Into<RpcModuleSelection>) -> RpcServerHan
         .unwrap()
 }
 
+/// Launches a new server with http only with the given modules, serving the data of the given
+/// provider
+pub async fn launch_http_with_provider(
+    modules: impl Into<RpcModuleSelection>,
+    provider: MockEthProvider,
+) -> RpcServerHandle {
+    let builder = test_rpc_builder().with_provider(provider);
+    let server =
+        builder.build(TransportRpcModuleConfig::set_http(modules), Box::new(EthApi::with_spawner));
+    RpcServerConfig::http(Default::default())
+        .with_http_address(test_address())
+        .start(&server)
+        .await
+        .unwrap()
+}
+
 /// Launches a new server with ws only with the given modules
 pub async fn launch_ws(modules: impl Into<RpcModuleSelection>) -> RpcServerHandle {
     let builder = test_rpc_builder();
@@ -97,6 +97,47 @@ impl reth_storage_api::StateProofProvider for StateProviderTraitObjWrapper<'_> {
     }
 }
 
+impl reth_storage_api::StateRangeProvider for StateProviderTraitObjWrapper<'_> {
+    fn account_range(
+        &self,
+        hashed_state: reth_trie::HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<reth_trie::range::HashedAccountRange> {
+        self.0.account_range(hashed_state, start, limit, with_storage)
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<reth_trie::range::HashedStorageRange> {
+        self.0.storage_range(address, hashed_storage, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<reth_trie::range::HashedStorageRange> {
+        self.0.hashed_storage_range(hashed_address, hashed_storage, start, limit)
+    }
+
+    fn hashed_account_range(
+        &self,
+        hashed_state: reth_trie::HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<reth_trie::range::HashedAccountRange> {
+        self.0.hashed_account_range(hashed_state, start, limit)
+    }
+}
+
 impl reth_storage_api::AccountReader for StateProviderTraitObjWrapper<'_> {
     fn basic_account(
         &self,
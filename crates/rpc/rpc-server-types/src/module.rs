@@ -260,6 +260,13 @@ pub enum RethRpcModule {
     Ots,
     /// `flashbots_` module
     Flashbots,
+    /// `mev_` module
+    Mev,
+    /// `anvil_` and `hardhat_` modules, only available in dev mode
+    Anvil,
+    /// `reth_db_` module
+    #[strum(serialize = "reth_db")]
+    RethDb,
 }
 
 // === impl RethRpcModule ===
@@ -309,6 +316,9 @@ impl FromStr for RethRpcModule {
             "reth" => Self::Reth,
             "ots" => Self::Ots,
             "flashbots" => Self::Flashbots,
+            "mev" => Self::Mev,
+            "anvil" => Self::Anvil,
+            "reth_db" => Self::RethDb,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
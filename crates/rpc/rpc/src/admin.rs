@@ -1,19 +1,28 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
+use alloy_eips::BlockNumHash;
 use alloy_genesis::ChainConfig;
 use alloy_rpc_types_admin::{
-    EthInfo, EthPeerInfo, EthProtocolInfo, NodeInfo, PeerInfo, PeerNetworkInfo, PeerProtocolInfo,
-    Ports, ProtocolInfo,
+    EthInfo, EthPeerInfo, EthProtocolInfo, NodeInfo, PeerEvent, PeerEventType, PeerInfo,
+    PeerNetworkInfo, PeerProtocolInfo, Ports, ProtocolInfo,
 };
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_chainspec::{EthChainSpec, EthereumHardforks, ForkCondition};
-use reth_network_api::{NetworkInfo, Peers};
-use reth_network_peers::{id2pk, AnyNode, NodeRecord};
+use reth_db_common::backup::DatabaseBackup;
+use reth_network_api::{
+    BanEntry, IpNet, NetworkEvent, NetworkEventListenerProvider, NetworkInfo, PeerMessageEvent,
+    Peers,
+};
+use reth_network_peers::{id2pk, AnyNode, NodeRecord, PeerId};
 use reth_network_types::PeerKind;
 use reth_primitives::EthereumHardfork;
-use reth_rpc_api::AdminApiServer;
-use reth_rpc_server_types::ToRpcResult;
+use reth_rpc_api::{AdminApiServer, PeerInfoWithReputation};
+use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+
+use crate::eth::pubsub::pipe_from_stream;
 
 /// `admin` API implementation.
 ///
@@ -23,19 +32,33 @@ pub struct AdminApi<N, ChainSpec> {
     network: N,
     /// The specification of the blockchain's configuration.
     chain_spec: Arc<ChainSpec>,
+    /// Writes backups of the database for `admin_backup`, if configured.
+    database_backup: Option<Arc<dyn DatabaseBackup>>,
+    /// Held while a backup is in progress.
+    backup_lock: Mutex<()>,
 }
 
 impl<N, ChainSpec> AdminApi<N, ChainSpec> {
     /// Creates a new instance of `AdminApi`.
-    pub const fn new(network: N, chain_spec: Arc<ChainSpec>) -> Self {
-        Self { network, chain_spec }
+    pub fn new(network: N, chain_spec: Arc<ChainSpec>) -> Self {
+        Self { network, chain_spec, database_backup: None, backup_lock: Mutex::new(()) }
+    }
+
+    /// Configures the database backups written by `admin_backup`, which is unavailable without
+    /// one.
+    pub fn with_database_backup(
+        mut self,
+        database_backup: Option<Arc<dyn DatabaseBackup>>,
+    ) -> Self {
+        self.database_backup = database_backup;
+        self
     }
 }
 
 #[async_trait]
 impl<N, ChainSpec> AdminApiServer for AdminApi<N, ChainSpec>
 where
-    N: NetworkInfo + Peers + 'static,
+    N: NetworkInfo + Peers + NetworkEventListenerProvider + 'static,
     ChainSpec: EthChainSpec + EthereumHardforks + Send + Sync + 'static,
 {
     /// Handler for `admin_addPeer`
@@ -65,14 +88,43 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_banPeer`
+    fn ban_peer(&self, record: AnyNode, duration: Option<u64>) -> RpcResult<bool> {
+        self.network.ban_peer(record.peer_id(), duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanPeer`
+    fn unban_peer(&self, record: AnyNode) -> RpcResult<bool> {
+        self.network.unban_peer(record.peer_id());
+        Ok(true)
+    }
+
+    /// Handler for `admin_banSubnet`
+    fn ban_subnet(&self, subnet: IpNet, duration: Option<u64>) -> RpcResult<bool> {
+        self.network.ban_subnet(subnet, duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanSubnet`
+    fn unban_subnet(&self, subnet: IpNet) -> RpcResult<bool> {
+        self.network.unban_subnet(subnet);
+        Ok(true)
+    }
+
+    /// Handler for `admin_listBans`
+    async fn list_bans(&self) -> RpcResult<Vec<BanEntry>> {
+        self.network.get_bans().await.to_rpc_result()
+    }
+
     /// Handler for `admin_peers`
-    async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
+    async fn peers(&self) -> RpcResult<Vec<PeerInfoWithReputation>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
         let mut infos = Vec::with_capacity(peers.len());
 
         for peer in peers {
             if let Ok(pk) = id2pk(peer.remote_id) {
-                infos.push(PeerInfo {
+                let info = PeerInfo {
                     id: pk.to_string(),
                     name: peer.client_version.to_string(),
                     enode: peer.enode,
@@ -97,6 +149,11 @@ where
                         snap: None,
                         other: Default::default(),
                     },
+                };
+                infos.push(PeerInfoWithReputation {
+                    info,
+                    reputation: peer.reputation,
+                    reputation_history: peer.reputation_history,
                 })
             }
         }
@@ -156,9 +213,7 @@ where
         ]);
 
         Ok(NodeInfo {
-            id: id2pk(enode.id)
-                .map(|pk| pk.to_string())
-                .unwrap_or_else(|_| alloy_primitives::hex::encode(enode.id.as_slice())),
+            id: peer_id_to_string(enode.id),
             name: status.client_version,
             enode: enode.to_string(),
             enr: self.network.local_enr().to_string(),
@@ -181,9 +236,85 @@ where
     /// Handler for `admin_peerEvents`
     async fn subscribe_peer_events(
         &self,
-        _pending: jsonrpsee::PendingSubscriptionSink,
+        pending: jsonrpsee::PendingSubscriptionSink,
     ) -> jsonrpsee::core::SubscriptionResult {
-        Err("admin_peerEvents is not implemented yet".into())
+        let sink = pending.accept().await?;
+        let session_events = self.network.event_listener().filter_map(session_peer_event);
+        let message_events = self.network.peer_message_listener().map(message_peer_event);
+        pipe_from_stream(sink, session_events.merge(message_events)).await?;
+        Ok(())
+    }
+
+    /// Handler for `admin_backup`
+    async fn backup(&self, path: PathBuf) -> RpcResult<BlockNumHash> {
+        let database_backup = self
+            .database_backup
+            .clone()
+            .ok_or_else(|| internal_rpc_err("database backups are not available"))?;
+        let _guard = self
+            .backup_lock
+            .try_lock()
+            .map_err(|_| internal_rpc_err("a database backup is already in progress"))?;
+
+        let manifest = tokio::task::spawn_blocking(move || database_backup.backup(&path))
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))?
+            .map_err(|err| internal_rpc_err(format!("{err:#}")))?;
+        Ok(BlockNumHash::new(manifest.block_number, manifest.block_hash))
+    }
+}
+
+/// Returns the id of the peer, as it is reported by `admin_nodeInfo`.
+fn peer_id_to_string(peer_id: PeerId) -> String {
+    id2pk(peer_id)
+        .map(|pk| pk.to_string())
+        .unwrap_or_else(|_| alloy_primitives::hex::encode(peer_id.as_slice()))
+}
+
+/// Converts a [`NetworkEvent`] to an `add` or `drop` [`PeerEvent`], if it is a session event.
+fn session_peer_event(event: NetworkEvent) -> Option<PeerEvent> {
+    let (kind, peer_id, error, remote_address) = match event {
+        NetworkEvent::SessionEstablished { peer_id, remote_addr, .. } => {
+            (PeerEventType::Add, peer_id, None, Some(remote_addr))
+        }
+        NetworkEvent::SessionClosed { peer_id, reason } => {
+            (PeerEventType::Drop, peer_id, reason.map(|reason| reason.to_string()), None)
+        }
+        NetworkEvent::PeerAdded(_) | NetworkEvent::PeerRemoved(_) => return None,
+    };
+
+    Some(PeerEvent {
+        kind,
+        peer: peer_id_to_string(peer_id),
+        error,
+        protocol: None,
+        msg_code: None,
+        msg_size: None,
+        local_address: None,
+        remote_address,
+    })
+}
+
+/// Converts a [`PeerMessageEvent`] to a `msgsend` or `msgrecv` [`PeerEvent`].
+fn message_peer_event(event: PeerMessageEvent) -> PeerEvent {
+    let (kind, peer_id, message_id) = match event {
+        PeerMessageEvent::Sent { peer_id, message_id } => {
+            (PeerEventType::MsgSend, peer_id, message_id)
+        }
+        PeerMessageEvent::Received { peer_id, message_id } => {
+            (PeerEventType::MsgRecv, peer_id, message_id)
+        }
+    };
+
+    PeerEvent {
+        kind,
+        peer: peer_id_to_string(peer_id),
+        error: None,
+        protocol: Some("eth".to_string()),
+        msg_code: Some(message_id as u64),
+        msg_size: None,
+        local_address: None,
+        remote_address: None,
     }
 }
 
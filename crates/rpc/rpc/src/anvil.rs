@@ -0,0 +1,688 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+    sync::Arc,
+    time::Duration,
+};
+
+use alloy_primitives::{map::HashMap, Address, Bytes, B256, U256};
+use alloy_rpc_types_anvil::{
+    Forking, Metadata, MineOptions, NodeEnvironment, NodeForkConfig, NodeInfo,
+};
+use async_trait::async_trait;
+use jsonrpsee::{core::RpcResult, RpcModule};
+use reth_chainspec::{EthChainSpec, Hardforks, Head};
+use reth_engine_primitives::{DevStateDump, LocalMinerHandle};
+use reth_evm::dev_state::AccountOverride;
+use reth_network_api::NetworkInfo;
+use reth_primitives::BlockNumberOrTag;
+use reth_provider::{BlockReaderIdExt, ChainSpecProvider};
+use reth_rpc_api::{AnvilApiServer, HardhatApiServer};
+use reth_rpc_eth_api::{helpers::EthBlocks, FullEthApiTypes, RpcBlock};
+use reth_rpc_eth_types::EthApiError;
+use reth_rpc_server_types::{
+    result::{internal_rpc_err, invalid_params_rpc_err},
+    ToRpcResult,
+};
+use reth_transaction_pool::TransactionPool;
+use serde::{Deserialize, Serialize};
+
+/// The `anvil_` and `hardhat_` methods that are not served by [`AnvilApi::into_rpc_module`].
+///
+/// Impersonating accounts is not possible because the engine recovers the sender of every
+/// transaction from its signature, the other methods change settings that are fixed when the node
+/// is started. They are removed from the namespace, so calling them fails with "method not found".
+pub const UNSUPPORTED_ANVIL_METHODS: &[&str] = &[
+    "anvil_impersonateAccount",
+    "anvil_stopImpersonatingAccount",
+    "anvil_autoImpersonateAccount",
+    "anvil_setRpcUrl",
+    "anvil_setChainId",
+    "anvil_setLoggingEnabled",
+    "anvil_setMinGasPrice",
+    "anvil_setNextBlockBaseFeePerGas",
+    "anvil_setBlockGasLimit",
+    "anvil_enableTraces",
+    "hardhat_impersonateAccount",
+    "hardhat_stopImpersonatingAccount",
+    "hardhat_setLoggingEnabled",
+    "hardhat_setMinGasPrice",
+    "hardhat_setNextBlockBaseFeePerGas",
+];
+
+/// The state of the dev chain returned by `anvil_dumpState`, in the format used by anvil.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SerializableState {
+    /// The accounts of the state.
+    accounts: BTreeMap<Address, SerializableAccount>,
+    /// The number of the block the state was dumped at.
+    #[serde(default)]
+    best_block_number: Option<u64>,
+}
+
+/// An account of a [`SerializableState`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SerializableAccount {
+    nonce: u64,
+    balance: U256,
+    code: Bytes,
+    storage: BTreeMap<U256, U256>,
+}
+
+impl From<DevStateDump> for SerializableState {
+    fn from(dump: DevStateDump) -> Self {
+        let accounts = dump
+            .accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let account = SerializableAccount {
+                    nonce: account.nonce.unwrap_or_default(),
+                    balance: account.balance.unwrap_or_default(),
+                    code: account.code.unwrap_or_default(),
+                    storage: account
+                        .storage
+                        .into_iter()
+                        .map(|(slot, value)| (slot.into(), value))
+                        .collect(),
+                };
+                (address, account)
+            })
+            .collect();
+        Self { accounts, best_block_number: Some(dump.block_number) }
+    }
+}
+
+impl From<SerializableAccount> for AccountOverride {
+    fn from(account: SerializableAccount) -> Self {
+        Self {
+            balance: Some(account.balance),
+            nonce: Some(account.nonce),
+            code: Some(account.code),
+            storage: account
+                .storage
+                .into_iter()
+                .map(|(slot, value)| (slot.into(), value))
+                .collect(),
+        }
+    }
+}
+
+/// `anvil_` and `hardhat_` API implementation for dev chains.
+///
+/// This controls the block production of the [`LocalMinerHandle`] of the local engine, which
+/// allows running test suites written for anvil or hardhat against a reth dev node.
+pub struct AnvilApi<Provider, Pool, Network, Eth> {
+    inner: Arc<AnvilApiInner<Provider, Pool, Network, Eth>>,
+}
+
+impl<Provider, Pool, Network, Eth> AnvilApi<Provider, Pool, Network, Eth> {
+    /// Creates a new instance of `AnvilApi`.
+    pub fn new(
+        provider: Provider,
+        pool: Pool,
+        network: Network,
+        eth: Eth,
+        miner: LocalMinerHandle,
+    ) -> Self {
+        let inner =
+            AnvilApiInner { provider, pool, network, eth, miner, instance_id: B256::random() };
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+impl<Provider, Pool, Network, Eth> AnvilApi<Provider, Pool, Network, Eth>
+where
+    Provider: BlockReaderIdExt + ChainSpecProvider<ChainSpec: Hardforks> + 'static,
+    Pool: TransactionPool + 'static,
+    Network: NetworkInfo + 'static,
+    Eth: FullEthApiTypes + EthBlocks + 'static,
+{
+    /// Returns the `anvil_` and `hardhat_` namespaces as a single module, without the
+    /// [`UNSUPPORTED_ANVIL_METHODS`].
+    pub fn into_rpc_module(self) -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        module.merge(AnvilApiServer::into_rpc(self.clone())).expect("No conflicts");
+        module.merge(HardhatApiServer::into_rpc(self)).expect("No conflicts");
+        for method in UNSUPPORTED_ANVIL_METHODS {
+            module.remove_method(*method);
+        }
+        module
+    }
+
+    /// Mines the given number of blocks, one by default, with timestamps `interval` seconds
+    /// apart.
+    async fn mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<Vec<B256>> {
+        let blocks = blocks
+            .map_or(Ok(1), u64::try_from)
+            .map_err(|_| invalid_params_rpc_err("the number of blocks does not fit into an u64"))?;
+        let interval = interval
+            .map(u64::try_from)
+            .transpose()
+            .map_err(|_| invalid_params_rpc_err("the interval does not fit into an u64"))?;
+        self.inner
+            .miner
+            .mine(blocks, interval)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Reverts the chain to the snapshot with the given id.
+    ///
+    /// The transactions of the reverted blocks are not re-injected into the pool of a dev node.
+    async fn revert(&self, id: U256) -> RpcResult<bool> {
+        self.inner.miner.revert(id).await.map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Applies the given changes to the state of an account in a new block.
+    async fn override_account(&self, address: Address, account: AccountOverride) -> RpcResult<()> {
+        let accounts = HashMap::from_iter([(address, account)]);
+        self.inner
+            .miner
+            .override_state(accounts)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Reverts the chain to the genesis block, forking is not supported.
+    async fn reset(&self, fork: Option<Forking>) -> RpcResult<()> {
+        if fork.is_some_and(|fork| fork.json_rpc_url.is_some() || fork.block_number.is_some()) {
+            return Err(invalid_params_rpc_err("forking is not supported by the dev chain"))
+        }
+        self.inner.miner.reset().await.map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Returns the state of the dev chain as gzip compressed JSON.
+    async fn dump_state(&self) -> RpcResult<Bytes> {
+        let dump =
+            self.inner.miner.dump_state().await.map_err(|err| internal_rpc_err(err.to_string()))?;
+        let json = serde_json::to_vec(&SerializableState::from(dump))
+            .map_err(|err| internal_rpc_err(err.to_string()))?;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&json).map_err(|err| internal_rpc_err(err.to_string()))?;
+        let compressed = encoder.finish().map_err(|err| internal_rpc_err(err.to_string()))?;
+        Ok(compressed.into())
+    }
+
+    /// Loads a state returned by `anvil_dumpState` into a new block, the state may be compressed
+    /// with gzip or plain JSON.
+    ///
+    /// Accounts that are not part of the loaded state are kept.
+    async fn load_state(&self, state: Bytes) -> RpcResult<bool> {
+        let mut json = Vec::new();
+        let state =
+            if flate2::read::GzDecoder::new(state.as_ref()).read_to_end(&mut json).is_ok() {
+                serde_json::from_slice::<SerializableState>(&json)
+            } else {
+                serde_json::from_slice::<SerializableState>(&state)
+            }
+            .map_err(|err| invalid_params_rpc_err(format!("invalid state: {err}")))?;
+
+        let accounts = state
+            .accounts
+            .into_iter()
+            .map(|(address, account)| (address, AccountOverride::from(account)))
+            .collect();
+        self.inner
+            .miner
+            .override_state(accounts)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))?;
+        Ok(true)
+    }
+
+    /// Removes the given transaction from the pool and returns its hash if it existed.
+    fn drop_transaction(&self, tx_hash: B256) -> Option<B256> {
+        self.inner.pool.remove_transactions(vec![tx_hash]).first().map(|tx| *tx.hash())
+    }
+
+    /// Returns the metadata of the dev node.
+    async fn metadata(&self) -> RpcResult<Metadata> {
+        let status = self.inner.network.network_status().await.to_rpc_result()?;
+        let header = self.latest_header()?;
+        let snapshots =
+            self.inner.miner.snapshots().await.map_err(|err| internal_rpc_err(err.to_string()))?;
+
+        Ok(Metadata {
+            client_version: status.client_version,
+            chain_id: self.inner.provider.chain_spec().chain().id(),
+            instance_id: self.inner.instance_id,
+            latest_block_number: header.number,
+            latest_block_hash: header.hash(),
+            forked_network: None,
+            snapshots,
+        })
+    }
+
+    /// Returns the configuration of the dev node.
+    fn node_info(&self) -> RpcResult<NodeInfo> {
+        let header = self.latest_header()?;
+        let chain_spec = self.inner.provider.chain_spec();
+
+        let head = Head {
+            number: header.number,
+            hash: header.hash(),
+            difficulty: header.difficulty,
+            total_difficulty: U256::ZERO,
+            timestamp: header.timestamp,
+        };
+        let hard_fork = chain_spec
+            .forks_iter()
+            .filter(|(_, condition)| condition.active_at_head(&head))
+            .last()
+            .map(|(fork, _)| fork.name().to_string())
+            .unwrap_or_default();
+        let base_fee = U256::from(header.base_fee_per_gas.unwrap_or_default());
+
+        Ok(NodeInfo {
+            current_block_number: header.number,
+            current_block_timestamp: header.timestamp,
+            current_block_hash: header.hash(),
+            hard_fork,
+            transaction_order: "fees".to_string(),
+            environment: NodeEnvironment {
+                base_fee,
+                chain_id: chain_spec.chain().id(),
+                gas_limit: U256::from(header.gas_limit),
+                gas_price: base_fee,
+            },
+            fork_config: NodeForkConfig::default(),
+        })
+    }
+
+    /// Returns the latest header of the dev chain.
+    fn latest_header(&self) -> RpcResult<reth_primitives::SealedHeader> {
+        Ok(self
+            .inner
+            .provider
+            .sealed_header_by_number_or_tag(BlockNumberOrTag::Latest)
+            .map_err(EthApiError::from)?
+            .ok_or(EthApiError::HeaderNotFound(BlockNumberOrTag::Latest.into()))?)
+    }
+}
+
+#[async_trait]
+impl<Provider, Pool, Network, Eth> AnvilApiServer<RpcBlock<Eth::NetworkTypes>>
+    for AnvilApi<Provider, Pool, Network, Eth>
+where
+    Provider: BlockReaderIdExt + ChainSpecProvider<ChainSpec: Hardforks> + 'static,
+    Pool: TransactionPool + 'static,
+    Network: NetworkInfo + 'static,
+    Eth: FullEthApiTypes + EthBlocks + 'static,
+{
+    /// Handler for `anvil_impersonateAccount`
+    async fn anvil_impersonate_account(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("impersonating accounts is not supported").into())
+    }
+
+    /// Handler for `anvil_stopImpersonatingAccount`
+    async fn anvil_stop_impersonating_account(&self, _address: Address) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("impersonating accounts is not supported").into())
+    }
+
+    /// Handler for `anvil_autoImpersonateAccount`
+    async fn anvil_auto_impersonate_account(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("impersonating accounts is not supported").into())
+    }
+
+    /// Handler for `anvil_getAutomine`
+    async fn anvil_get_automine(&self) -> RpcResult<bool> {
+        self.inner.miner.automine().await.map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        self.mine(blocks, interval).await?;
+        Ok(())
+    }
+
+    /// Handler for `anvil_setAutomine`
+    async fn anvil_set_automine(&self, enabled: bool) -> RpcResult<()> {
+        self.inner
+            .miner
+            .set_automine(enabled)
+            .await
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_setIntervalMining`
+    async fn anvil_set_interval_mining(&self, interval: u64) -> RpcResult<()> {
+        let interval = (interval > 0).then(|| Duration::from_secs(interval));
+        self.inner
+            .miner
+            .set_interval_mining(interval)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_dropTransaction`
+    async fn anvil_drop_transaction(&self, tx_hash: B256) -> RpcResult<Option<B256>> {
+        Ok(self.drop_transaction(tx_hash))
+    }
+
+    /// Handler for `anvil_reset`
+    async fn anvil_reset(&self, fork: Option<Forking>) -> RpcResult<()> {
+        self.reset(fork).await
+    }
+
+    /// Handler for `anvil_setRpcUrl`
+    async fn anvil_set_rpc_url(&self, _url: String) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("forking is not supported by the dev chain").into())
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.override_account(
+            address,
+            AccountOverride { balance: Some(balance), ..Default::default() },
+        )
+        .await
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.override_account(address, AccountOverride { code: Some(code), ..Default::default() })
+            .await
+    }
+
+    /// Handler for `anvil_setNonce`
+    async fn anvil_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        let nonce = u64::try_from(nonce)
+            .map_err(|_| invalid_params_rpc_err("the nonce does not fit into an u64"))?;
+        self.override_account(address, AccountOverride { nonce: Some(nonce), ..Default::default() })
+            .await
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        let storage = HashMap::from_iter([(slot.into(), value.into())]);
+        self.override_account(address, AccountOverride { storage, ..Default::default() }).await?;
+        Ok(true)
+    }
+
+    /// Handler for `anvil_setCoinbase`
+    async fn anvil_set_coinbase(&self, address: Address) -> RpcResult<()> {
+        self.inner
+            .miner
+            .set_fee_recipient(address)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_setChainId`
+    async fn anvil_set_chain_id(&self, _chain_id: u64) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("the chain id is fixed by the chain spec").into())
+    }
+
+    /// Handler for `anvil_setLoggingEnabled`
+    async fn anvil_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("logging is configured on startup").into())
+    }
+
+    /// Handler for `anvil_setMinGasPrice`
+    async fn anvil_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("the minimum gas price is configured on startup").into())
+    }
+
+    /// Handler for `anvil_setNextBlockBaseFeePerGas`
+    async fn anvil_set_next_block_base_fee_per_gas(&self, _base_fee: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("the base fee is determined by the parent block").into())
+    }
+
+    /// Handler for `anvil_setTime`
+    async fn anvil_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        let offset = self
+            .inner
+            .miner
+            .set_time(timestamp)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))?;
+        Ok(offset.max(0) as u64)
+    }
+
+    /// Handler for `anvil_dumpState`
+    async fn anvil_dump_state(&self) -> RpcResult<Bytes> {
+        self.dump_state().await
+    }
+
+    /// Handler for `anvil_loadState`
+    async fn anvil_load_state(&self, state: Bytes) -> RpcResult<bool> {
+        self.load_state(state).await
+    }
+
+    /// Handler for `anvil_nodeInfo`
+    async fn anvil_node_info(&self) -> RpcResult<NodeInfo> {
+        self.node_info()
+    }
+
+    /// Handler for `anvil_metadata`
+    async fn anvil_metadata(&self) -> RpcResult<Metadata> {
+        self.metadata().await
+    }
+
+    /// Handler for `anvil_snapshot`
+    async fn anvil_snapshot(&self) -> RpcResult<U256> {
+        self.inner.miner.snapshot().await.map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_revert`
+    async fn anvil_revert(&self, id: U256) -> RpcResult<bool> {
+        self.revert(id).await
+    }
+
+    /// Handler for `anvil_increaseTime`
+    async fn anvil_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        let seconds = u64::try_from(seconds).map_err(|_| {
+            invalid_params_rpc_err("the number of seconds does not fit into an u64")
+        })?;
+        self.inner
+            .miner
+            .increase_time(seconds)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_setNextBlockTimestamp`
+    async fn anvil_set_next_block_timestamp(&self, seconds: u64) -> RpcResult<()> {
+        self.inner
+            .miner
+            .set_next_block_timestamp(seconds)
+            .await
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_setBlockGasLimit`
+    async fn anvil_set_block_gas_limit(&self, _gas_limit: U256) -> RpcResult<bool> {
+        Err(EthApiError::Unsupported("the block gas limit is configured on startup").into())
+    }
+
+    /// Handler for `anvil_setBlockTimestampInterval`
+    async fn anvil_set_block_timestamp_interval(&self, seconds: u64) -> RpcResult<()> {
+        self.inner
+            .miner
+            .set_block_timestamp_interval(Some(seconds))
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Handler for `anvil_removeBlockTimestampInterval`
+    async fn anvil_remove_block_timestamp_interval(&self) -> RpcResult<bool> {
+        self.inner
+            .miner
+            .set_block_timestamp_interval(None)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `anvil_mine_detailed`
+    async fn anvil_mine_detailed(
+        &self,
+        opts: Option<MineOptions>,
+    ) -> RpcResult<Vec<RpcBlock<Eth::NetworkTypes>>> {
+        let (timestamp, blocks) = match opts.unwrap_or_default() {
+            MineOptions::Options { timestamp, blocks } => (timestamp, blocks),
+            MineOptions::Timestamp(timestamp) => (timestamp, None),
+        };
+        if let Some(timestamp) = timestamp {
+            self.anvil_set_next_block_timestamp(timestamp).await?;
+        }
+
+        let hashes = self.mine(blocks.map(U256::from), None).await?;
+        let mut mined = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let block = self
+                .inner
+                .eth
+                .rpc_block(hash.into(), true)
+                .await
+                .map_err(Into::into)?
+                .ok_or(EthApiError::HeaderNotFound(hash.into()))?;
+            mined.push(block);
+        }
+        Ok(mined)
+    }
+
+    /// Handler for `anvil_enableTraces`
+    async fn anvil_enable_traces(&self) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("call traces are served by the debug_ namespace").into())
+    }
+
+    /// Handler for `anvil_removePoolTransactions`
+    async fn anvil_remove_pool_transactions(&self, address: Address) -> RpcResult<()> {
+        self.inner.pool.remove_transactions_by_sender(address);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Provider, Pool, Network, Eth> HardhatApiServer for AnvilApi<Provider, Pool, Network, Eth>
+where
+    Provider: BlockReaderIdExt + ChainSpecProvider<ChainSpec: Hardforks> + 'static,
+    Pool: TransactionPool + 'static,
+    Network: NetworkInfo + 'static,
+    Eth: FullEthApiTypes + EthBlocks + 'static,
+{
+    /// Handler for `hardhat_dropTransaction`
+    async fn hardhat_drop_transaction(&self, tx_hash: B256) -> RpcResult<bool> {
+        Ok(self.drop_transaction(tx_hash).is_some())
+    }
+
+    /// Handler for `hardhat_impersonateAccount`
+    async fn hardhat_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        self.anvil_impersonate_account(address).await
+    }
+
+    /// Handler for `hardhat_getAutomine`
+    async fn hardhat_get_automine(&self) -> RpcResult<bool> {
+        self.anvil_get_automine().await
+    }
+
+    /// Handler for `hardhat_metadata`
+    async fn hardhat_metadata(&self) -> RpcResult<Metadata> {
+        self.metadata().await
+    }
+
+    /// Handler for `hardhat_mine`
+    async fn hardhat_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        self.mine(blocks, interval).await?;
+        Ok(())
+    }
+
+    /// Handler for `hardhat_reset`
+    async fn hardhat_reset(&self, fork: Option<Forking>) -> RpcResult<()> {
+        self.anvil_reset(fork).await
+    }
+
+    /// Handler for `hardhat_setBalance`
+    async fn hardhat_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.anvil_set_balance(address, balance).await
+    }
+
+    /// Handler for `hardhat_setCode`
+    async fn hardhat_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.anvil_set_code(address, code).await
+    }
+
+    /// Handler for `hardhat_setCoinbase`
+    async fn hardhat_set_coinbase(&self, address: Address) -> RpcResult<()> {
+        self.anvil_set_coinbase(address).await
+    }
+
+    /// Handler for `hardhat_setLoggingEnabled`
+    async fn hardhat_set_logging_enabled(&self, enabled: bool) -> RpcResult<()> {
+        self.anvil_set_logging_enabled(enabled).await
+    }
+
+    /// Handler for `hardhat_setMinGasPrice`
+    async fn hardhat_set_min_gas_price(&self, gas_price: U256) -> RpcResult<()> {
+        self.anvil_set_min_gas_price(gas_price).await
+    }
+
+    /// Handler for `hardhat_setNextBlockBaseFeePerGas`
+    async fn hardhat_set_next_block_base_fee_per_gas(&self, base_fee: U256) -> RpcResult<()> {
+        self.anvil_set_next_block_base_fee_per_gas(base_fee).await
+    }
+
+    /// Handler for `hardhat_setPrevRandao`
+    async fn hardhat_set_prev_randao(&self, prev_randao: B256) -> RpcResult<()> {
+        self.inner
+            .miner
+            .set_prev_randao(prev_randao)
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))
+    }
+
+    /// Handler for `hardhat_setNonce`
+    async fn hardhat_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        self.anvil_set_nonce(address, nonce).await
+    }
+
+    /// Handler for `hardhat_setStorageAt`
+    async fn hardhat_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        self.anvil_set_storage_at(address, slot, value).await
+    }
+
+    /// Handler for `hardhat_stopImpersonatingAccount`
+    async fn hardhat_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        self.anvil_stop_impersonating_account(address).await
+    }
+}
+
+impl<Provider, Pool, Network, Eth> std::fmt::Debug for AnvilApi<Provider, Pool, Network, Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnvilApi").finish_non_exhaustive()
+    }
+}
+
+impl<Provider, Pool, Network, Eth> Clone for AnvilApi<Provider, Pool, Network, Eth> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct AnvilApiInner<Provider, Pool, Network, Eth> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// The transaction pool of the node.
+    pool: Pool,
+    /// The network of the node.
+    network: Network,
+    /// The `eth_` API, used to build the RPC blocks.
+    eth: Eth,
+    /// Handle to the miner of the dev chain.
+    miner: LocalMinerHandle,
+    /// A random id of this instance, reported by the metadata endpoints.
+    instance_id: B256,
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_db_common::explorer::{
+    DatabaseExplorer, ExplorerError, RangePage, RangeRequest, TableEntry, TableStats,
+};
+use reth_rpc_api::{DbApiServer, DbRangePage, DbRangeRequest, DbTableEntry, DbTableStats};
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use serde_json::Value;
+
+/// `reth_db` API implementation.
+///
+/// This type provides read-only access to the database tables of the node.
+#[derive(Debug, Clone)]
+pub struct DbApi {
+    /// Reads the tables of the database.
+    explorer: Arc<dyn DatabaseExplorer>,
+}
+
+impl DbApi {
+    /// Creates a new instance of `DbApi`.
+    pub fn new(explorer: Arc<dyn DatabaseExplorer>) -> Self {
+        Self { explorer }
+    }
+
+    /// Runs the call on the explorer in a blocking task, since it reads from the database.
+    async fn on_blocking_task<R, F>(&self, f: F) -> RpcResult<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&dyn DatabaseExplorer) -> Result<R, ExplorerError> + Send + 'static,
+    {
+        let explorer = self.explorer.clone();
+        tokio::task::spawn_blocking(move || f(explorer.as_ref()))
+            .await
+            .map_err(|err| internal_rpc_err(err.to_string()))?
+            .map_err(|err| match err {
+                ExplorerError::UnknownTable(_) |
+                ExplorerError::InvalidKey { .. } |
+                ExplorerError::NotDupSort(_) => invalid_params_rpc_err(err.to_string()),
+                ExplorerError::Json(_) | ExplorerError::Database(_) => {
+                    internal_rpc_err(err.to_string())
+                }
+            })
+    }
+}
+
+#[async_trait]
+impl DbApiServer for DbApi {
+    /// Handler for `reth_db_tables`
+    async fn tables(&self) -> RpcResult<Vec<DbTableStats>> {
+        let tables = self.on_blocking_task(|explorer| explorer.tables()).await?;
+        Ok(tables.into_iter().map(table_stats).collect())
+    }
+
+    /// Handler for `reth_db_stats`
+    async fn stats(&self, table: String) -> RpcResult<DbTableStats> {
+        self.on_blocking_task(move |explorer| explorer.table_stats(&table)).await.map(table_stats)
+    }
+
+    /// Handler for `reth_db_get`
+    async fn get(
+        &self,
+        table: String,
+        key: Value,
+        subkey: Option<Value>,
+    ) -> RpcResult<Option<Value>> {
+        self.on_blocking_task(move |explorer| explorer.get(&table, key, subkey)).await
+    }
+
+    /// Handler for `reth_db_range`
+    async fn range(&self, table: String, request: DbRangeRequest) -> RpcResult<DbRangePage> {
+        let request = RangeRequest {
+            start: request.start,
+            subkey: request.subkey,
+            end: request.end,
+            limit: request.limit,
+        };
+        let RangePage { entries, next } =
+            self.on_blocking_task(move |explorer| explorer.range(&table, request)).await?;
+        Ok(DbRangePage {
+            entries: entries
+                .into_iter()
+                .map(|TableEntry { key, value }| DbTableEntry { key, value })
+                .collect(),
+            next: next.map(|RangeRequest { start, subkey, end, limit }| DbRangeRequest {
+                start,
+                subkey,
+                end,
+                limit,
+            }),
+        })
+    }
+}
+
+fn table_stats(stats: TableStats) -> DbTableStats {
+    let TableStats { name, dupsort, entries, size } = stats;
+    DbTableStats { name, dupsort, entries, size }
+}
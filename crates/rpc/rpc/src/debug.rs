@@ -1,55 +1,79 @@
+use alloy_consensus::{constants::KECCAK_EMPTY, Transaction as _};
 use alloy_eips::eip2718::Encodable2718;
-use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_primitives::{map::HashSet, Address, BlockNumber, Bytes, B256, U256};
 use alloy_rlp::{Decodable, Encodable};
 use alloy_rpc_types::{
-    state::EvmOverrides, Block as RpcBlock, BlockError, Bundle, StateContext, TransactionInfo,
+    state::EvmOverrides, Block as RpcBlock, BlockError, Bundle, StateContext, Transaction,
+    TransactionInfo,
 };
 use alloy_rpc_types_debug::ExecutionWitness;
 use alloy_rpc_types_eth::transaction::TransactionRequest;
 use alloy_rpc_types_trace::geth::{
-    call::FlatCallFrame, BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType,
-    GethDebugTracerType, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
-    NoopFrame, TraceResult,
+    call::FlatCallFrame, BlockTraceResult, CallConfig, FourByteFrame, GethDebugBuiltInTracerType,
+    GethDebugTracerType, GethDebugTracingCallOptions, GethDebugTracingOptions,
+    GethDefaultTracingOptions, GethTrace, NoopFrame, PreStateConfig, TraceResult,
 };
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::{Stream, StreamExt, TryStreamExt};
+use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
 use reth_chainspec::EthereumHardforks;
+use reth_engine_primitives::BadBlockStore;
 use reth_evm::{
     execute::{BlockExecutorProvider, Executor},
     system_calls::SystemCaller,
     ConfigureEvmEnv,
 };
-use reth_primitives::{Block, BlockId, BlockNumberOrTag, TransactionSignedEcRecovered};
+use reth_primitives::{
+    Block, BlockId, BlockNumberOrTag, BlockWithSenders, SealedBlock, TransactionSignedEcRecovered,
+};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProofProvider, StateProviderFactory,
-    TransactionVariant,
+    BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, HeaderProvider, ProviderError,
+    PruneCheckpointReader, StateProofProvider, StateProvider, StateProviderFactory,
+    StateRangeProvider, StateRootProvider, StorageChangeSetReader, TransactionVariant,
 };
+use reth_prune_types::PruneSegment;
 use reth_revm::database::StateProviderDatabase;
-use reth_rpc_api::DebugApiServer;
+use reth_rpc_api::{
+    AccountRangeResult, DebugApiServer, DumpAccount, StorageRangeEntry, StorageRangeResult,
+};
 use reth_rpc_eth_api::{
     helpers::{EthApiSpec, EthTransactions, TraceExt},
     EthApiTypes, FromEthApiError, RpcNodeCore,
 };
 use reth_rpc_eth_types::{EthApiError, StateCacheDb};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use reth_rpc_types_compat::{block::from_block_with_tx_hashes, TransactionCompat};
 use reth_tasks::pool::BlockingTaskGuard;
-use reth_trie::{HashedPostState, HashedStorage};
+use reth_trie::{updates::TrieUpdates, HashedPostState, HashedStorage, TrieInput};
 use revm::{
-    db::{CacheDB, State},
+    db::{states::bundle_state::BundleRetention, AccountState, CacheDB, State},
     primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
 };
 use revm_inspectors::tracing::{
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig, TransactionContext,
 };
-use revm_primitives::{keccak256, HashMap};
-use std::sync::Arc;
+use revm_primitives::{
+    db::DatabaseRef, keccak256, EvmState, ExecutionResult, HashMap, ResultAndState,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufWriter, Write},
+    ops::RangeInclusive,
+    sync::Arc,
+};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
+use tracing::warn;
+
+use crate::eth::EthTxBuilder;
 
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
 pub struct DebugApi<Provider, Eth, BlockExecutor> {
     inner: Arc<DebugApiInner<Provider, Eth, BlockExecutor>>,
+    /// The blocks rejected by the engine.
+    bad_blocks: BadBlockStore,
 }
 
 // === impl DebugApi ===
@@ -64,7 +88,14 @@ impl<Provider, Eth, BlockExecutor> DebugApi<Provider, Eth, BlockExecutor> {
     ) -> Self {
         let inner =
             Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard, block_executor });
-        Self { inner }
+        Self { inner, bad_blocks: BadBlockStore::default() }
+    }
+
+    /// Configures the store of bad blocks that is served by `debug_getBadBlocks` and replayed by
+    /// `debug_traceBadBlock`.
+    pub fn with_bad_blocks(mut self, bad_blocks: BadBlockStore) -> Self {
+        self.bad_blocks = bad_blocks;
+        self
     }
 
     /// Access the underlying `Eth` API.
@@ -81,6 +112,9 @@ where
         + HeaderProvider
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + PruneCheckpointReader
         + 'static,
     Eth: EthApiTypes + TraceExt + 'static,
     BlockExecutor: BlockExecutorProvider,
@@ -185,6 +219,32 @@ where
             .map_err(BlockError::RlpDecodeRawBlock)
             .map_err(Eth::Error::from_eth_err)?;
 
+        self.replay_block(block, opts).await
+    }
+
+    /// Replays a block that was rejected by the engine and returns the trace of each transaction.
+    pub async fn debug_trace_bad_block(
+        &self,
+        block_hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, Eth::Error> {
+        let bad_block = self
+            .bad_blocks
+            .get(&block_hash)
+            .ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+
+        self.replay_block(bad_block.block.unseal(), opts).await
+    }
+
+    /// Replays the given block on top of its parent block and returns the trace of each
+    /// transaction.
+    ///
+    /// Note, the parent of this block must be present, or it will fail.
+    async fn replay_block(
+        &self,
+        block: Block,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, Eth::Error> {
         let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
         // we trace on top the block's parent block
         let parent = block.parent_hash;
@@ -263,6 +323,506 @@ where
         .await
     }
 
+    /// Traces all blocks in the range `(start_exclusive, end_inclusive]`, each on top of its
+    /// parent block's state.
+    ///
+    /// Returns a stream that yields the traces of each block in order. Up to
+    /// [`TRACE_CHAIN_CONCURRENCY`] blocks are traced in parallel, each of them holding a tracing
+    /// permit while it is being traced. The range may span at most [`TRACE_CHAIN_MAX_BLOCKS`]
+    /// blocks, and must end at or before the latest block.
+    ///
+    /// Only the default struct log tracer and the `callTracer`, `prestateTracer` and `noopTracer`
+    /// are supported.
+    pub fn debug_trace_chain(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: GethDebugTracingOptions,
+    ) -> Result<impl Stream<Item = Result<BlockTraceResult, Eth::Error>> + Send + 'static, Eth::Error>
+    {
+        let start = self.block_number(start_exclusive)?;
+        let end = self.block_number(end_inclusive)?;
+        if start >= end {
+            return Err(EthApiError::InvalidParams(
+                "start block must be lower than end block".to_string(),
+            )
+            .into())
+        }
+        if end - start > TRACE_CHAIN_MAX_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "block range exceeds the maximum of {TRACE_CHAIN_MAX_BLOCKS} blocks"
+            ))
+            .into())
+        }
+        let best_number =
+            self.inner.provider.best_block_number().map_err(Eth::Error::from_eth_err)?;
+        if end > best_number {
+            return Err(EthApiError::HeaderNotFound(end.into()).into())
+        }
+        let tracer = ChainTracer::new(&opts)?;
+
+        let this = self.clone();
+        Ok(futures::stream::iter(start + 1..=end)
+            .map(move |number| {
+                let this = this.clone();
+                let tracer = tracer.clone();
+                async move { this.trace_chain_block(number, tracer).await }
+            })
+            .buffered(TRACE_CHAIN_CONCURRENCY))
+    }
+
+    /// Resolves the number of the given block.
+    fn block_number(&self, block: BlockNumberOrTag) -> Result<u64, Eth::Error> {
+        self.inner
+            .provider
+            .convert_block_number(block)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or_else(|| EthApiError::HeaderNotFound(block.into()).into())
+    }
+
+    /// Traces a single block of a `debug_traceChain` request.
+    async fn trace_chain_block(
+        &self,
+        number: u64,
+        tracer: ChainTracer,
+    ) -> Result<BlockTraceResult, Eth::Error> {
+        let _permit = self.acquire_trace_permit().await;
+
+        let block = self
+            .eth_api()
+            .block_with_senders(number.into())
+            .await?
+            .ok_or(EthApiError::HeaderNotFound(number.into()))?;
+        let hash = block.hash();
+        let inspector_config = tracer.inspector_config();
+
+        let transactions = block.clone();
+        let traces = self
+            .eth_api()
+            .trace_block_with(
+                hash.into(),
+                Some(block),
+                inspector_config,
+                move |tx_info, mut inspector, res, state, db| {
+                    if let Some(tx) = tx_info
+                        .index
+                        .and_then(|idx| transactions.body.transactions.get(idx as usize))
+                    {
+                        inspector.set_transaction_gas_limit(tx.gas_limit());
+                    }
+                    let result = tracer
+                        .trace(inspector, res, state, db)
+                        .map_err(Eth::Error::from_eth_err)?;
+                    Ok(TraceResult::Success { result, tx_hash: tx_info.hash })
+                },
+            )
+            .await?
+            .unwrap_or_default();
+
+        Ok(BlockTraceResult { block: U256::from(number), hash, traces })
+    }
+
+    /// Returns a page of the accounts at the given block, in order of their hashed address.
+    ///
+    /// The addresses of the accounts are looked up in a bounded walk over the plain state, so they
+    /// may be unknown on large states. Accounts without a known address are only returned if
+    /// `incompletes` is set.
+    pub async fn debug_account_range(
+        &self,
+        block: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> Result<AccountRangeResult, Eth::Error> {
+        if start.len() > B256::len_bytes() {
+            return Err(
+                EthApiError::InvalidParams("start key must be at most 32 bytes".to_string()).into()
+            )
+        }
+        let mut start_key = B256::ZERO;
+        start_key[..start.len()].copy_from_slice(&start);
+
+        let limit = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        } as usize;
+
+        let root = self
+            .inner
+            .provider
+            .header_by_number_or_tag(block)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or(EthApiError::HeaderNotFound(block.into()))?
+            .state_root;
+
+        self.eth_api()
+            .spawn_with_state_at_block(block.into(), move |state| {
+                let range = state
+                    .account_range(HashedPostState::default(), start_key, limit, !nostorage)
+                    .map_err(Eth::Error::from_eth_err)?;
+
+                let mut accounts = BTreeMap::new();
+                for entry in range.accounts {
+                    if entry.address.is_none() && !incompletes {
+                        continue
+                    }
+
+                    let code = match entry.account.bytecode_hash {
+                        Some(code_hash) if !nocode && code_hash != KECCAK_EMPTY => state
+                            .bytecode_by_hash(code_hash)
+                            .map_err(Eth::Error::from_eth_err)?
+                            .map(|code| code.original_bytes()),
+                        _ => None,
+                    };
+                    let storage = entry
+                        .storage
+                        .into_iter()
+                        .map(|slot| (slot.key.unwrap_or(slot.hashed_key), slot.value.into()))
+                        .collect();
+                    let key = entry.address.map_or_else(
+                        || format!("pre({})", entry.hashed_address),
+                        |address| address.to_string(),
+                    );
+
+                    accounts.insert(
+                        key,
+                        DumpAccount {
+                            balance: entry.account.balance.to_string(),
+                            nonce: entry.account.nonce,
+                            root: entry.storage_root,
+                            code_hash: entry.account.get_bytecode_hash(),
+                            code,
+                            storage,
+                            address: entry.address,
+                            address_hash: entry.hashed_address,
+                        },
+                    );
+                }
+
+                Ok(AccountRangeResult {
+                    root,
+                    accounts,
+                    next: range.next_key.map(|key| Bytes::copy_from_slice(key.as_slice())),
+                })
+            })
+            .await
+    }
+
+    /// Returns a page of the storage of the given contract, in order of the hashed slots, as it
+    /// was right before the transaction at the given index of the block was executed.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> Result<StorageRangeResult, Eth::Error> {
+        let ((cfg, block_env, _), block) = futures::try_join!(
+            self.eth_api().evm_env_at(block_hash.into()),
+            self.eth_api().block_with_senders(block_hash.into()),
+        )?;
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+        if tx_idx != 0 && tx_idx >= block.body.transactions.len() {
+            return Err(EthApiError::InvalidParams(format!(
+                "transaction index {tx_idx} out of range for block {block_hash}"
+            ))
+            .into())
+        }
+        let limit = if max_result == 0 || max_result > STORAGE_RANGE_MAX_RESULTS {
+            STORAGE_RANGE_MAX_RESULTS
+        } else {
+            max_result
+        } as usize;
+
+        // we need to get the state of the parent block because we're replaying the transactions of
+        // this block that precede the given index
+        let state_at = block.parent_hash;
+        let parent_beacon_block_root = block.parent_beacon_block_root;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(state_at.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+                // apply relevant system calls
+                SystemCaller::new(
+                    this.eth_api().evm_config().clone(),
+                    this.eth_api().provider().chain_spec(),
+                )
+                .pre_block_beacon_root_contract_call(
+                    &mut db,
+                    &cfg,
+                    &block_env,
+                    parent_beacon_block_root,
+                )
+                .map_err(|_| {
+                    EthApiError::EvmCustom(
+                        "failed to apply 4788 beacon root system call".to_string(),
+                    )
+                })?;
+
+                // replay all transactions prior to the given index
+                if let Some(tx) = block.body.transactions.get(tx_idx) {
+                    this.eth_api().replay_transactions_until(
+                        &mut db,
+                        cfg,
+                        block_env,
+                        block.transactions_with_sender(),
+                        tx.hash(),
+                    )?;
+                }
+
+                // the storage of the contract that was touched by the replayed transactions
+                let mut hashed_storage = HashedStorage::new(false);
+                let mut preimages = HashMap::default();
+                if let Some(account) = db.accounts.get(&contract_address) {
+                    hashed_storage.wiped = matches!(
+                        account.account_state,
+                        AccountState::StorageCleared | AccountState::NotExisting
+                    );
+                    for (slot, value) in &account.storage {
+                        let key = B256::from(*slot);
+                        let hashed_key = keccak256(key);
+                        hashed_storage.storage.insert(hashed_key, *value);
+                        preimages.insert(hashed_key, key);
+                    }
+                }
+
+                let range = db
+                    .db
+                    .0
+                    .storage_range(contract_address, hashed_storage, key_start, limit)
+                    .map_err(Eth::Error::from_eth_err)?;
+                let storage = range
+                    .slots
+                    .into_iter()
+                    .map(|slot| {
+                        let key = slot.key.or_else(|| preimages.get(&slot.hashed_key).copied());
+                        (slot.hashed_key, StorageRangeEntry { key, value: slot.value.into() })
+                    })
+                    .collect();
+
+                Ok(StorageRangeResult { storage, next_key: range.next_key })
+            })
+            .await
+    }
+
+    /// Returns the addresses of all accounts that were modified in the given block range.
+    ///
+    /// If `end_number` is set, the blocks `(start_number, end_number]` are considered, otherwise
+    /// only block `start_number` is.
+    pub async fn debug_get_modified_accounts_by_number(
+        &self,
+        start_number: BlockNumber,
+        end_number: Option<BlockNumber>,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        let (start_exclusive, end_inclusive) = match end_number {
+            Some(end_number) => {
+                if start_number >= end_number {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "start block ({start_number}) must be less than end block ({end_number})"
+                    ))
+                    .into())
+                }
+                (start_number, end_number)
+            }
+            None => {
+                let parent = start_number.checked_sub(1).ok_or_else(|| {
+                    EthApiError::InvalidParams("genesis block has no parent".to_string())
+                })?;
+                (parent, start_number)
+            }
+        };
+
+        if end_inclusive - start_exclusive > MODIFIED_ACCOUNTS_MAX_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "block range exceeds the maximum of {MODIFIED_ACCOUNTS_MAX_BLOCKS} blocks"
+            ))
+            .into())
+        }
+
+        let best_number =
+            self.inner.provider.best_block_number().map_err(Eth::Error::from_eth_err)?;
+        if end_inclusive > best_number {
+            return Err(EthApiError::HeaderNotFound(end_inclusive.into()).into())
+        }
+
+        // the changesets are pruned together with the history indices
+        let start_inclusive = start_exclusive + 1;
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            // The checkpoint stores the highest pruned block number.
+            let pruned = self
+                .inner
+                .provider
+                .get_prune_checkpoint(segment)
+                .map_err(Eth::Error::from_eth_err)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if pruned.is_some_and(|pruned| pruned >= start_inclusive) {
+                return Err(
+                    EthApiError::from(ProviderError::StateAtBlockPruned(start_inclusive)).into()
+                )
+            }
+        }
+
+        self.modified_accounts(start_inclusive..=end_inclusive).await
+    }
+
+    /// Returns the addresses of all accounts that were modified in the given block range.
+    ///
+    /// See [`Self::debug_get_modified_accounts_by_number`].
+    pub async fn debug_get_modified_accounts_by_hash(
+        &self,
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        let block_number = |hash: B256| -> Result<BlockNumber, Eth::Error> {
+            Ok(self
+                .inner
+                .provider
+                .block_number(hash)
+                .map_err(Eth::Error::from_eth_err)?
+                .ok_or(EthApiError::HeaderNotFound(hash.into()))?)
+        };
+        let start_number = block_number(start_hash)?;
+        let end_number = end_hash.map(block_number).transpose()?;
+
+        self.debug_get_modified_accounts_by_number(start_number, end_number).await
+    }
+
+    /// Collects the addresses of the accounts modified in the given blocks from their account and
+    /// storage changesets.
+    ///
+    /// Both changesets are needed, because the account changesets do not record accounts of which
+    /// only the storage changed.
+    async fn modified_accounts(
+        &self,
+        blocks: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        let _permit = self.acquire_trace_permit().await;
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking_io(move |_| {
+                let provider = &this.inner.provider;
+                let mut accounts = BTreeSet::new();
+                for block_number in blocks {
+                    let account_changes = provider
+                        .account_block_changeset(block_number)
+                        .map_err(Eth::Error::from_eth_err)?;
+                    accounts.extend(account_changes.into_iter().map(|change| change.address));
+
+                    let storage_changes = provider
+                        .storage_changeset(block_number)
+                        .map_err(Eth::Error::from_eth_err)?;
+                    accounts.extend(storage_changes.into_iter().map(|(key, _)| key.address()));
+                }
+                Ok(accounts.into_iter().collect())
+            })
+            .await
+    }
+
+    /// Executes the given block on top of its parent block and returns the state root after each
+    /// transaction.
+    ///
+    /// Blocks that were rejected by the engine are looked up in the bad block store, so their
+    /// intermediate roots can be compared against those of a valid block.
+    pub async fn debug_intermediate_roots(
+        &self,
+        block_hash: B256,
+    ) -> Result<Vec<B256>, Eth::Error> {
+        let (block, cfg, block_env) = match self.bad_blocks.get(&block_hash) {
+            Some(bad_block) => {
+                let block = bad_block
+                    .block
+                    .seal_with_senders()
+                    .ok_or(EthApiError::InvalidTransactionSignature)?;
+                let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
+                (block, cfg, block_env)
+            }
+            None => {
+                let ((cfg, block_env, _), block) = futures::try_join!(
+                    self.eth_api().evm_env_at(block_hash.into()),
+                    self.eth_api().block_with_senders(block_hash.into()),
+                )?;
+                let block = block.ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+                ((*block).clone(), cfg, block_env)
+            }
+        };
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash.into(), move |state_provider| {
+                let mut db = State::builder()
+                    .with_database(StateProviderDatabase::new(&state_provider))
+                    .with_bundle_update()
+                    .build();
+
+                // apply relevant system calls
+                SystemCaller::new(
+                    this.eth_api().evm_config().clone(),
+                    this.eth_api().provider().chain_spec(),
+                )
+                .pre_block_beacon_root_contract_call(
+                    &mut db,
+                    &cfg,
+                    &block_env,
+                    block.parent_beacon_block_root,
+                )
+                .map_err(|_| {
+                    EthApiError::EvmCustom(
+                        "failed to apply 4788 beacon root system call".to_string(),
+                    )
+                })?;
+
+                // the trie nodes computed for the previous transactions are reused, so only the
+                // paths changed by each transaction are recomputed
+                let mut trie_nodes = TrieUpdates::default();
+                let mut roots = Vec::with_capacity(block.body.transactions.len());
+                for (signer, tx) in block.transactions_with_sender() {
+                    let env = EnvWithHandlerCfg {
+                        env: Env::boxed(
+                            cfg.cfg_env.clone(),
+                            block_env.clone(),
+                            RpcNodeCore::evm_config(this.eth_api()).tx_env(tx, *signer),
+                        ),
+                        handler_cfg: cfg.handler_cfg,
+                    };
+                    let (res, _) = this.eth_api().transact(&mut db, env)?;
+                    let touched = res.state.keys().copied().collect::<HashSet<_>>();
+                    db.commit(res.state);
+
+                    // the bundle accumulates the changes of all transactions so far, so the root
+                    // is computed by overlaying them on top of the parent state
+                    db.merge_transitions(BundleRetention::PlainState);
+                    let hashed_state = HashedPostState::from_bundle_state(&db.bundle_state.state);
+                    let prefix_sets = HashedPostState::from_bundle_state(
+                        db.bundle_state
+                            .state
+                            .iter()
+                            .filter(|(address, _)| touched.contains(*address))
+                            .collect::<Vec<_>>(),
+                    )
+                    .construct_prefix_sets();
+
+                    let (root, updates) = state_provider
+                        .state_root_from_nodes_with_updates(TrieInput::new(
+                            trie_nodes.clone(),
+                            hashed_state,
+                            prefix_sets,
+                        ))
+                        .map_err(Eth::Error::from_eth_err)?;
+                    trie_nodes.extend(updates);
+                    roots.push(root);
+                }
+
+                Ok(roots)
+            })
+            .await
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -899,6 +1459,9 @@ where
         + HeaderProvider
         + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + PruneCheckpointReader
         + 'static,
     Eth: EthApiSpec + EthTransactions + TraceExt + 'static,
     BlockExecutor: BlockExecutorProvider,
@@ -975,16 +1538,72 @@ where
 
     /// Handler for `debug_getBadBlocks`
     async fn bad_blocks(&self) -> RpcResult<Vec<RpcBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+        self.bad_blocks
+            .blocks()
+            .into_iter()
+            .map(|bad_block| -> RpcResult<_> {
+                let parent_td = self
+                    .inner
+                    .provider
+                    .header_td(&bad_block.block.parent_hash)
+                    .to_rpc_result()?
+                    .unwrap_or_default();
+                let total_difficulty = parent_td + bad_block.block.difficulty;
+                Ok(rpc_bad_block(bad_block.block, total_difficulty))
+            })
+            .collect()
     }
 
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        let traces =
+            Self::debug_trace_chain(self, start_exclusive, end_inclusive, opts.unwrap_or_default())
+                .map_err(Into::into)?;
+        Ok(traces.try_collect().await.map_err(Into::into)?)
+    }
+
+    /// Handler for `debug_subscribeTraceChain`
+    async fn subscribe_trace_chain(
+        &self,
+        pending: PendingSubscriptionSink,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let traces = match Self::debug_trace_chain(
+            self,
+            start_exclusive,
+            end_inclusive,
+            opts.unwrap_or_default(),
+        ) {
+            Ok(traces) => traces,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let mut traces = std::pin::pin!(traces);
+        loop {
+            tokio::select! {
+                _ = sink.closed() => break,
+                maybe_trace = traces.next() => {
+                    let Some(trace) = maybe_trace else { break };
+                    let msg = SubscriptionMessage::from_json(&trace?)?;
+                    if sink.send(msg).await.is_err() {
+                        break
+                    }
+                }
+            }
+        }
+
+        Ok(())
     }
 
     /// Handler for `debug_traceBlock`
@@ -1071,16 +1690,28 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_accountRange`
     async fn debug_account_range(
         &self,
-        _block_number: BlockNumberOrTag,
-        _start: Bytes,
-        _max_results: u64,
-        _nocode: bool,
-        _nostorage: bool,
-        _incompletes: bool,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> RpcResult<AccountRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_account_range(
+            self,
+            block_number,
+            start,
+            max_results,
+            nocode,
+            nostorage,
+            incompletes,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
@@ -1135,32 +1766,42 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_getModifiedAccountsByHash`
     async fn debug_get_modified_accounts_by_hash(
         &self,
-        _start_hash: B256,
-        _end_hash: B256,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>> {
+        Self::debug_get_modified_accounts_by_hash(self, start_hash, end_hash)
+            .await
+            .map_err(Into::into)
     }
 
+    /// Handler for `debug_getModifiedAccountsByNumber`
     async fn debug_get_modified_accounts_by_number(
         &self,
-        _start_number: u64,
-        _end_number: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_number: u64,
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>> {
+        Self::debug_get_modified_accounts_by_number(self, start_number, end_number)
+            .await
+            .map_err(Into::into)
     }
 
     async fn debug_go_trace(&self, _file: String, _seconds: u64) -> RpcResult<()> {
         Ok(())
     }
 
+    /// Handler for `debug_intermediateRoots`
+    ///
+    /// The tracing options are accepted for compatibility, but have no effect.
     async fn debug_intermediate_roots(
         &self,
-        _block_hash: B256,
+        block_hash: B256,
         _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+    ) -> RpcResult<Vec<B256>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_intermediate_roots(self, block_hash).await.map_err(Into::into)
     }
 
     async fn debug_mem_stats(&self) -> RpcResult<()> {
@@ -1209,10 +1850,15 @@ where
 
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        _block: BlockNumberOrTag,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<GethDefaultTracingOptions>,
+    ) -> RpcResult<Vec<String>> {
+        let _permit = self.acquire_trace_permit().await;
+        let opts =
+            GethDebugTracingOptions { config: opts.unwrap_or_default(), ..Default::default() };
+        let traces =
+            Self::debug_trace_bad_block(self, block_hash, opts).await.map_err(Into::into)?;
+        write_standard_traces(block_hash, traces).map_err(|err| internal_rpc_err(err.to_string()))
     }
 
     async fn debug_standard_trace_block_to_file(
@@ -1239,23 +1885,37 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            max_result,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_trace_bad_block(
         &self,
-        _block_hash: B256,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_trace_bad_block(self, block_hash, opts.unwrap_or_default())
+            .await
+            .map_err(Into::into)
     }
 
     async fn debug_verbosity(&self, _level: usize) -> RpcResult<()> {
@@ -1287,7 +1947,169 @@ impl<Provider, Eth, BlockExecutor> std::fmt::Debug for DebugApi<Provider, Eth, B
 
 impl<Provider, Eth, BlockExecutor> Clone for DebugApi<Provider, Eth, BlockExecutor> {
     fn clone(&self) -> Self {
-        Self { inner: Arc::clone(&self.inner) }
+        Self { inner: Arc::clone(&self.inner), bad_blocks: self.bad_blocks.clone() }
+    }
+}
+
+/// Converts a bad block into an RPC block with full transactions.
+///
+/// Bad blocks can be rejected because of an invalid transaction signature, in that case only the
+/// transaction hashes are returned.
+fn rpc_bad_block(block: SealedBlock, total_difficulty: U256) -> RpcBlock {
+    let block_hash = block.hash();
+    let block = match block.try_seal_with_senders() {
+        Ok(block) => block,
+        Err(block) => {
+            warn!(target: "rpc::debug", %block_hash, "Failed to recover senders of bad block, returning transaction hashes only");
+            // senders are not needed for transaction hashes
+            let block = BlockWithSenders { block: block.unseal(), senders: Vec::new() };
+            return from_block_with_tx_hashes::<Transaction>(
+                block,
+                total_difficulty,
+                Some(block_hash),
+            )
+        }
+    };
+    let transactions = block
+        .transactions_with_sender()
+        .enumerate()
+        .map(|(idx, (sender, tx))| {
+            let tx_info = TransactionInfo {
+                hash: Some(tx.hash()),
+                block_hash: Some(block_hash),
+                block_number: Some(block.number),
+                base_fee: block.base_fee_per_gas.map(u128::from),
+                index: Some(idx as u64),
+            };
+            EthTxBuilder.fill(tx.clone().with_signer(*sender), tx_info).inner
+        })
+        .collect();
+
+    from_block_with_tx_hashes::<Transaction>(block.unseal(), total_difficulty, Some(block_hash))
+        .into_full_block(transactions)
+}
+
+/// Writes the struct logs of every transaction of a bad block to a separate file in the temp
+/// directory, one log entry per line, and returns the paths of the written files.
+fn write_standard_traces(
+    block_hash: B256,
+    traces: Vec<TraceResult>,
+) -> std::io::Result<Vec<String>> {
+    let mut paths = Vec::with_capacity(traces.len());
+    for (index, trace) in traces.into_iter().enumerate() {
+        let TraceResult::Success { result: GethTrace::Default(frame), tx_hash } = trace else {
+            continue
+        };
+
+        let path = std::env::temp_dir()
+            .join(format!("block_{block_hash}-{index}-{}.jsonl", tx_hash.unwrap_or_default()));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        for log in &frame.struct_logs {
+            serde_json::to_writer(&mut writer, log)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+
+        paths.push(path.display().to_string());
+    }
+
+    Ok(paths)
+}
+
+/// The maximum number of accounts returned by a single `debug_accountRange` call.
+const ACCOUNT_RANGE_MAX_RESULTS: u64 = 256;
+
+/// The maximum number of storage slots returned by a single `debug_storageRangeAt` call.
+const STORAGE_RANGE_MAX_RESULTS: u64 = 1024;
+
+/// The maximum number of blocks that are considered by a single `debug_getModifiedAccountsBy*`
+/// call.
+const MODIFIED_ACCOUNTS_MAX_BLOCKS: u64 = 1024;
+
+/// The maximum number of blocks that are traced by a single `debug_traceChain` call.
+const TRACE_CHAIN_MAX_BLOCKS: u64 = 1024;
+
+/// The maximum number of blocks that are traced in parallel by `debug_traceChain`.
+///
+/// The effective parallelism is further bounded by the available tracing permits.
+const TRACE_CHAIN_CONCURRENCY: usize = 16;
+
+/// The tracers supported by `debug_traceChain`, all of which are based on the
+/// [`TracingInspector`].
+#[derive(Debug, Clone)]
+enum ChainTracer {
+    /// The default struct log tracer.
+    StructLog(GethDefaultTracingOptions),
+    /// The `callTracer`.
+    Call(CallConfig),
+    /// The `prestateTracer`.
+    PreState(PreStateConfig),
+    /// The `noopTracer`.
+    Noop,
+}
+
+impl ChainTracer {
+    /// Returns the tracer for the given options.
+    fn new(opts: &GethDebugTracingOptions) -> Result<Self, EthApiError> {
+        let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
+        let Some(tracer) = tracer else { return Ok(Self::StructLog(*config)) };
+
+        match tracer {
+            GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer) => {
+                let config = tracer_config
+                    .clone()
+                    .into_call_config()
+                    .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                Ok(Self::Call(config))
+            }
+            GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer) => {
+                let config = tracer_config
+                    .clone()
+                    .into_pre_state_config()
+                    .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                Ok(Self::PreState(config))
+            }
+            GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::NoopTracer) => {
+                Ok(Self::Noop)
+            }
+            _ => Err(EthApiError::Unsupported("tracer is not supported by debug_traceChain")),
+        }
+    }
+
+    /// Returns the inspector configuration required by this tracer.
+    fn inspector_config(&self) -> TracingInspectorConfig {
+        match self {
+            Self::StructLog(config) => TracingInspectorConfig::from_geth_config(config),
+            Self::Call(config) => TracingInspectorConfig::from_geth_call_config(config),
+            Self::PreState(config) => TracingInspectorConfig::from_geth_prestate_config(config),
+            Self::Noop => TracingInspectorConfig::none(),
+        }
+    }
+
+    /// Builds the trace of a transaction from the inspector that traced it.
+    fn trace<DB: DatabaseRef>(
+        &self,
+        inspector: TracingInspector,
+        result: ExecutionResult,
+        state: &EvmState,
+        db: DB,
+    ) -> Result<GethTrace, DB::Error> {
+        let trace = match self {
+            Self::StructLog(config) => {
+                let gas_used = result.gas_used();
+                let return_value = result.into_output().unwrap_or_default();
+                inspector.into_geth_builder().geth_traces(gas_used, return_value, *config).into()
+            }
+            Self::Call(config) => {
+                inspector.into_geth_builder().geth_call_traces(*config, result.gas_used()).into()
+            }
+            Self::PreState(config) => inspector
+                .into_geth_builder()
+                .geth_prestate_traces(&ResultAndState { result, state: state.clone() }, config, db)?
+                .into(),
+            Self::Noop => NoopFrame::default().into(),
+        };
+        Ok(trace)
     }
 }
 
@@ -1301,3 +2123,158 @@ struct DebugApiInner<Provider, Eth, BlockExecutor> {
     /// block executor for debug & trace apis
     block_executor: BlockExecutor,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip1559::ETHEREUM_BLOCK_GAS_LIMIT;
+    use reth_evm_ethereum::{execute::EthExecutorProvider, EthEvmConfig};
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::Header;
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_eth_types::{
+        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    };
+    use reth_rpc_server_types::constants::{
+        DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_SIMULATE_BLOCKS, DEFAULT_PROOF_PERMITS,
+    };
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+    type TestDebugApi = DebugApi<
+        MockEthProvider,
+        crate::EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig>,
+        EthExecutorProvider,
+    >;
+
+    /// More blocks than are traced concurrently, so the stream has to buffer.
+    const TIP: u64 = TRACE_CHAIN_CONCURRENCY as u64 + 4;
+
+    /// Returns a debug API over a chain of empty blocks up to [`TIP`], and the block hashes.
+    fn debug_api() -> (TestDebugApi, Vec<B256>) {
+        let provider = MockEthProvider::default();
+        let mut hashes = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..=TIP {
+            let header = Header { parent_hash, number, ..Default::default() };
+            parent_hash = header.hash_slow();
+            provider.add_block(parent_hash, Block { header, body: Default::default() });
+            hashes.push(parent_hash);
+        }
+
+        let evm_config = EthEvmConfig::new(provider.chain_spec());
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config.clone());
+        let eth_api = crate::EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            cache.clone(),
+            GasPriceOracle::new(provider.clone(), Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            DEFAULT_MAX_SIMULATE_BLOCKS,
+            DEFAULT_ETH_PROOF_WINDOW,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            DEFAULT_PROOF_PERMITS,
+        );
+        let executor = EthExecutorProvider::ethereum(provider.chain_spec());
+        let api = DebugApi::new(provider, eth_api, BlockingTaskGuard::new(2), executor);
+
+        (api, hashes)
+    }
+
+    #[tokio::test]
+    async fn trace_chain_yields_blocks_in_order() {
+        let (api, hashes) = debug_api();
+
+        let traces = api
+            .debug_trace_chain(
+                BlockNumberOrTag::Earliest,
+                BlockNumberOrTag::Latest,
+                Default::default(),
+            )
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            traces.iter().map(|trace| trace.block).collect::<Vec<_>>(),
+            (1..=TIP).map(U256::from).collect::<Vec<_>>()
+        );
+        assert_eq!(traces.iter().map(|trace| trace.hash).collect::<Vec<_>>(), hashes[1..]);
+        assert!(traces.iter().all(|trace| trace.traces.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn trace_chain_ends_after_end_block() {
+        let (api, hashes) = debug_api();
+
+        let mut traces = std::pin::pin!(api
+            .debug_trace_chain(
+                BlockNumberOrTag::Number(3),
+                BlockNumberOrTag::Number(5),
+                Default::default(),
+            )
+            .unwrap());
+
+        assert_eq!(traces.next().await.unwrap().unwrap().hash, hashes[4]);
+        assert_eq!(traces.next().await.unwrap().unwrap().hash, hashes[5]);
+        assert!(traces.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn trace_chain_rejects_invalid_ranges() {
+        let (api, _) = debug_api();
+
+        for (start, end) in [(5, 5), (6, 5)] {
+            assert!(matches!(
+                api.debug_trace_chain(
+                    BlockNumberOrTag::Number(start),
+                    BlockNumberOrTag::Number(end),
+                    Default::default(),
+                ),
+                Err(EthApiError::InvalidParams(_))
+            ));
+        }
+
+        // The pending block is not tracked by the provider.
+        assert!(matches!(
+            api.debug_trace_chain(
+                BlockNumberOrTag::Number(1),
+                BlockNumberOrTag::Pending,
+                Default::default(),
+            ),
+            Err(EthApiError::HeaderNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn trace_chain_rejects_blocks_after_tip() {
+        let (api, _) = debug_api();
+
+        assert!(matches!(
+            api.debug_trace_chain(
+                BlockNumberOrTag::Number(TIP - 1),
+                BlockNumberOrTag::Number(TIP + 2),
+                Default::default(),
+            ),
+            Err(EthApiError::HeaderNotFound(id)) if id == BlockId::from(TIP + 2)
+        ));
+    }
+
+    #[tokio::test]
+    async fn trace_chain_rejects_long_ranges() {
+        let (api, _) = debug_api();
+
+        assert!(matches!(
+            api.debug_trace_chain(
+                BlockNumberOrTag::Earliest,
+                BlockNumberOrTag::Number(TRACE_CHAIN_MAX_BLOCKS + 1),
+                Default::default(),
+            ),
+            Err(EthApiError::InvalidParams(_))
+        ));
+    }
+}
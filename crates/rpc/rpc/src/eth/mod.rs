@@ -12,6 +12,7 @@ pub use bundle::EthBundle;
 pub use core::EthApi;
 pub use filter::EthFilter;
 pub use pubsub::EthPubSub;
+pub use sim_bundle::EthSimBundle;
 
 pub use helpers::{signer::DevSigner, types::EthTxBuilder};
 
@@ -228,7 +228,7 @@ impl From<SubscriptionSerializeError> for ErrorObject<'static> {
 }
 
 /// Pipes all stream items to the subscription sink.
-async fn pipe_from_stream<T, St>(
+pub(crate) async fn pipe_from_stream<T, St>(
     sink: SubscriptionSink,
     mut stream: St,
 ) -> Result<(), ErrorObject<'static>>
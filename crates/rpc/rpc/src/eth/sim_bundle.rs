@@ -1,14 +1,55 @@
 //! `Eth` Sim bundle implementation and helpers.
 
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use alloy_rpc_types_mev::{SendBundleRequest, SimBundleOverrides, SimBundleResponse};
+use alloy_primitives::{Bytes, U256};
+use alloy_rpc_types::BlockId;
+use alloy_rpc_types_mev::{
+    BundleItem, Inclusion, RefundConfig, SendBundleRequest, SimBundleLogs, SimBundleOverrides,
+    SimBundleResponse,
+};
 use jsonrpsee::core::RpcResult;
+use reth_chainspec::EthChainSpec;
+use reth_errors::ProviderError;
+use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
+use reth_primitives::{
+    revm_primitives::db::{DatabaseCommit, DatabaseRef},
+    PooledTransactionsElement, TransactionSignedEcRecovered,
+};
+use reth_provider::{ChainSpecProvider, HeaderProvider};
+use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::MevSimApiServer;
-use reth_rpc_eth_api::helpers::{Call, EthTransactions, LoadPendingBlock};
-use reth_rpc_eth_types::EthApiError;
+use reth_rpc_eth_api::{
+    helpers::{Call, EthTransactions, LoadPendingBlock},
+    FromEthApiError, RpcNodeCore,
+};
+use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError};
 use reth_tasks::pool::BlockingTaskGuard;
-use tracing::info;
+use revm::{
+    db::{AccountState, CacheDB},
+    primitives::{EVMError, ExecutionResult, ResultAndState, TxEnv},
+    Evm,
+};
+use revm_primitives::{EnvKzgSettings, EnvWithHandlerCfg, SpecId};
+
+/// Maximum depth of nested bundles.
+const MAX_NESTED_BUNDLE_DEPTH: usize = 5;
+
+/// Maximum number of items in the body of a bundle.
+const MAX_BUNDLE_BODY_SIZE: usize = 50;
+
+/// Default timeout of a bundle simulation.
+const DEFAULT_SIM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum timeout of a bundle simulation that can be requested.
+const MAX_SIM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The gas charged for every refund payout, see also
+/// <https://github.com/flashbots/builder/blob/df9c765067d57ab4b2d0ad39dbb156cbe4965778/core/types/sbundle.go#L12>
+const SBUNDLE_PAYOUT_MAX_COST: u64 = 30_000;
 
 /// `Eth` sim bundle implementation.
 pub struct EthSimBundle<Eth> {
@@ -21,20 +62,135 @@ impl<Eth> EthSimBundle<Eth> {
     pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
         Self { inner: Arc::new(EthSimBundleInner { eth_api, blocking_task_guard }) }
     }
+
+    /// Access the underlying `Eth` API.
+    pub fn eth_api(&self) -> &Eth {
+        &self.inner.eth_api
+    }
 }
 
 impl<Eth> EthSimBundle<Eth>
 where
     Eth: EthTransactions + LoadPendingBlock + Call + 'static,
 {
-    /// Simulates a bundle of transactions.
+    /// Simulates a MEV-Share bundle on top of the given parent block (default: latest).
+    ///
+    /// Nested bundles are executed in order and the refunds of their `validity` are paid out of
+    /// the coinbase, following the semantics of the flashbots builder. A bundle that fails to
+    /// execute is reported with `success: false`, invalid requests are rejected with an error.
     pub async fn sim_bundle(
         &self,
         request: SendBundleRequest,
         overrides: SimBundleOverrides,
-    ) -> RpcResult<SimBundleResponse> {
-        info!("mev_simBundle called, request: {:?}, overrides: {:?}", request, overrides);
-        Err(EthApiError::Unsupported("mev_simBundle is not supported").into())
+    ) -> Result<SimBundleResponse, Eth::Error> {
+        let bundle = SimBundle::parse(request, 0)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        let SimBundleOverrides {
+            parent_block,
+            block_number,
+            coinbase,
+            timestamp,
+            gas_limit,
+            base_fee,
+            timeout,
+        } = overrides;
+        let timeout =
+            timeout.map(Duration::from_secs).unwrap_or(DEFAULT_SIM_TIMEOUT).min(MAX_SIM_TIMEOUT);
+
+        let block_id = parent_block.unwrap_or_else(BlockId::latest);
+        let (cfg, mut block_env, at) = self.eth_api().evm_env_at(block_id).await?;
+        let state_block = block_env.number.saturating_to::<u64>();
+
+        // adjust the block environment for the next block
+        block_env.number = U256::from(block_number.unwrap_or(state_block + 1));
+        if let Some(coinbase) = coinbase {
+            block_env.coinbase = coinbase;
+        }
+        if let Some(timestamp) = timestamp {
+            block_env.timestamp = U256::from(timestamp);
+        } else {
+            block_env.timestamp += U256::from(12);
+        }
+        if let Some(gas_limit) = gas_limit {
+            block_env.gas_limit = U256::from(gas_limit);
+        }
+        if let Some(base_fee) = base_fee {
+            block_env.basefee = U256::from(base_fee);
+        } else if cfg.handler_cfg.spec_id.is_enabled_in(SpecId::LONDON) {
+            let parent = RpcNodeCore::provider(self.eth_api())
+                .header_by_number(state_block)
+                .map_err(Eth::Error::from_eth_err)?
+                .ok_or(EthApiError::HeaderNotFound(state_block.into()))?;
+            if let Some(base_fee) = parent.next_block_base_fee(
+                RpcNodeCore::provider(self.eth_api())
+                    .chain_spec()
+                    .base_fee_params_at_block(state_block),
+            ) {
+                block_env.basefee = U256::from(base_fee);
+            }
+        }
+
+        let eth_api = self.eth_api().clone();
+        let deadline = Instant::now() + timeout;
+
+        self.eth_api()
+            .spawn_with_state_at_block(at, move |state| {
+                let block_number = block_env.number.saturating_to::<u64>();
+                let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+                let db = CacheDB::new(StateProviderDatabase::new(state));
+                let evm_config = RpcNodeCore::evm_config(&eth_api);
+
+                let mut simulator = BundleSimulator {
+                    evm_config,
+                    evm: evm_config.evm_with_env(db, env),
+                    block_number,
+                    deadline,
+                };
+
+                let response = match simulator.simulate(&bundle) {
+                    Ok(result) => SimBundleResponse {
+                        success: true,
+                        error: None,
+                        state_block,
+                        mev_gas_price: result
+                            .profit
+                            .checked_div(U256::from(result.gas_used))
+                            .unwrap_or_default(),
+                        profit: result.profit,
+                        refundable_value: result.refundable_value,
+                        gas_used: result.gas_used,
+                        logs: Some(result.logs),
+                        exec_error: None,
+                        revert: None,
+                    },
+                    Err(SimulationError::Bundle { error, exec_error, revert }) => {
+                        SimBundleResponse {
+                            success: false,
+                            error: Some(error.to_string()),
+                            state_block,
+                            mev_gas_price: U256::ZERO,
+                            profit: U256::ZERO,
+                            refundable_value: U256::ZERO,
+                            gas_used: 0,
+                            logs: None,
+                            exec_error,
+                            revert,
+                        }
+                    }
+                    Err(SimulationError::Timeout) => {
+                        return Err(Eth::Error::from_eth_err(EthApiError::ExecutionTimedOut(
+                            timeout,
+                        )))
+                    }
+                    Err(SimulationError::Provider(err)) => {
+                        return Err(Eth::Error::from_eth_err(err))
+                    }
+                };
+
+                Ok(response)
+            })
+            .await
     }
 }
 
@@ -48,7 +204,8 @@ where
         request: SendBundleRequest,
         overrides: SimBundleOverrides,
     ) -> RpcResult<SimBundleResponse> {
-        Self::sim_bundle(self, request, overrides).await
+        let _permit = self.inner.blocking_task_guard.clone().acquire_owned().await;
+        Self::sim_bundle(self, request, overrides).await.map_err(Into::into)
     }
 }
 
@@ -56,10 +213,8 @@ where
 #[derive(Debug)]
 struct EthSimBundleInner<Eth> {
     /// Access to commonly used code of the `eth` namespace
-    #[allow(dead_code)]
     eth_api: Eth,
     // restrict the number of concurrent tracing calls.
-    #[allow(dead_code)]
     blocking_task_guard: BlockingTaskGuard,
 }
 
@@ -74,3 +229,335 @@ impl<Eth> Clone for EthSimBundle<Eth> {
         Self { inner: Arc::clone(&self.inner) }
     }
 }
+
+/// A validated [`SendBundleRequest`] with recovered transactions.
+#[derive(Debug)]
+struct SimBundle {
+    /// The blocks the bundle can be included in.
+    inclusion: Inclusion,
+    /// The refunds of the body items, as `(body_idx, percent)`.
+    refunds: Vec<(usize, u64)>,
+    /// How the refunds of this bundle are split, if it is refunded by a parent bundle.
+    refund_config: Option<Vec<RefundConfig>>,
+    /// Whether the logs of the transactions of this bundle are returned.
+    share_logs: bool,
+    /// The body of the bundle.
+    body: Vec<SimBundleItem>,
+}
+
+/// An item of the body of a [`SimBundle`].
+#[derive(Debug)]
+enum SimBundleItem {
+    /// A transaction of the bundle.
+    Tx {
+        /// The recovered transaction.
+        tx: TransactionSignedEcRecovered,
+        /// Whether the transaction is allowed to revert.
+        can_revert: bool,
+    },
+    /// A nested bundle.
+    Bundle(SimBundle),
+}
+
+impl SimBundle {
+    /// Validates the request and recovers its transactions.
+    fn parse(request: SendBundleRequest, depth: usize) -> Result<Self, EthSimBundleError> {
+        if depth > MAX_NESTED_BUNDLE_DEPTH {
+            return Err(EthSimBundleError::MaxDepth)
+        }
+
+        let SendBundleRequest { inclusion, bundle_body, validity, privacy, .. } = request;
+        if bundle_body.is_empty() {
+            return Err(EthSimBundleError::InvalidBundle)
+        }
+        if bundle_body.len() > MAX_BUNDLE_BODY_SIZE {
+            return Err(EthSimBundleError::BundleTooLarge)
+        }
+        if inclusion.block == 0 || inclusion.max_block.is_some_and(|max| max < inclusion.block) {
+            return Err(EthSimBundleError::InvalidInclusion)
+        }
+
+        let validity = validity.unwrap_or_default();
+        let refunds = validity
+            .refund
+            .unwrap_or_default()
+            .into_iter()
+            .map(|refund| (refund.body_idx as usize, refund.percent))
+            .collect::<Vec<_>>();
+        if refunds.iter().any(|(body_idx, _)| *body_idx >= bundle_body.len()) ||
+            refunds.iter().map(|(_, percent)| percent).sum::<u64>() > 100
+        {
+            return Err(EthSimBundleError::InvalidValidity)
+        }
+        if let Some(refund_config) = &validity.refund_config {
+            if refund_config.iter().map(|config| config.percent).sum::<u64>() > 100 {
+                return Err(EthSimBundleError::InvalidValidity)
+            }
+        }
+
+        // the sender of the bundle always sees its own logs
+        let share_logs =
+            depth == 0 || privacy.and_then(|privacy| privacy.hints).is_some_and(|hints| hints.logs);
+
+        let body = bundle_body
+            .into_iter()
+            .map(|item| match item {
+                BundleItem::Hash { .. } => Err(EthSimBundleError::UnmatchedBundle),
+                BundleItem::Tx { tx, can_revert } => {
+                    let (tx, signer) = recover_raw_transaction(tx)
+                        .map_err(|err| EthSimBundleError::InvalidTransaction(err.to_string()))?
+                        .into_components();
+                    // Verify that the given blob data, commitments, and proofs are all valid for
+                    // this transaction.
+                    if let PooledTransactionsElement::BlobTransaction(ref tx) = tx {
+                        tx.validate(EnvKzgSettings::Default.get()).map_err(|err| {
+                            EthSimBundleError::InvalidTransaction(err.to_string())
+                        })?;
+                    }
+                    Ok(SimBundleItem::Tx {
+                        tx: tx.into_ecrecovered_transaction(signer),
+                        can_revert,
+                    })
+                }
+                BundleItem::Bundle { bundle } => {
+                    Self::parse(bundle, depth + 1).map(SimBundleItem::Bundle)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { inclusion, refunds, refund_config: validity.refund_config, share_logs, body })
+    }
+
+    /// Returns whether the bundle can be included in the given block.
+    fn is_includable(&self, block_number: u64) -> bool {
+        let Inclusion { block, max_block } = self.inclusion;
+        block <= block_number && block_number <= max_block.unwrap_or(block)
+    }
+
+    /// Returns whether the body item at the given index is refunded.
+    fn is_refunded(&self, body_idx: usize) -> bool {
+        self.refunds.iter().any(|(idx, _)| *idx == body_idx)
+    }
+
+    /// Returns the refund configuration of the bundle, which defaults to the one of its first
+    /// body item.
+    fn refund_config(&self) -> Vec<RefundConfig> {
+        match &self.refund_config {
+            Some(refund_config) if !refund_config.is_empty() => refund_config.clone(),
+            _ => self.body.first().map(SimBundleItem::refund_config).unwrap_or_default(),
+        }
+    }
+}
+
+impl SimBundleItem {
+    /// Returns the recipients of a refund of this item, the signer in case of a transaction.
+    fn refund_config(&self) -> Vec<RefundConfig> {
+        match self {
+            Self::Tx { tx, .. } => vec![RefundConfig { address: tx.signer(), percent: 100 }],
+            Self::Bundle(bundle) => bundle.refund_config(),
+        }
+    }
+}
+
+/// The result of a successful bundle simulation.
+#[derive(Debug, Default)]
+struct SimBundleResult {
+    /// The coinbase profit of the bundle after refunds.
+    profit: U256,
+    /// The coinbase profit of the body items that are not refunded.
+    refundable_value: U256,
+    /// The gas used by the bundle, including refund payouts.
+    gas_used: u64,
+    /// The logs of each body item.
+    logs: Vec<SimBundleLogs>,
+}
+
+/// Errors that abort a bundle simulation.
+#[derive(Debug)]
+enum SimulationError {
+    /// The bundle can not be included, which is reported in the response.
+    Bundle { error: EthSimBundleError, exec_error: Option<String>, revert: Option<Bytes> },
+    /// The simulation exceeded its deadline.
+    Timeout,
+    /// Failed to read the state.
+    Provider(ProviderError),
+}
+
+impl From<EthSimBundleError> for SimulationError {
+    fn from(error: EthSimBundleError) -> Self {
+        Self::Bundle { error, exec_error: None, revert: None }
+    }
+}
+
+impl From<ProviderError> for SimulationError {
+    fn from(err: ProviderError) -> Self {
+        Self::Provider(err)
+    }
+}
+
+/// Executes [`SimBundle`]s on top of a state, committing their changes.
+struct BundleSimulator<'a, EvmConfig, DB> {
+    /// The evm configuration used to fill the transaction environment.
+    evm_config: &'a EvmConfig,
+    /// The evm to execute the transactions with.
+    evm: Evm<'a, (), CacheDB<DB>>,
+    /// The number of the simulated block.
+    block_number: u64,
+    /// The time at which the simulation is aborted.
+    deadline: Instant,
+}
+
+impl<EvmConfig, DB> BundleSimulator<'_, EvmConfig, DB>
+where
+    EvmConfig: ConfigureEvmEnv,
+    DB: DatabaseRef<Error = ProviderError>,
+{
+    /// Executes the bundle and pays out its refunds.
+    fn simulate(&mut self, bundle: &SimBundle) -> Result<SimBundleResult, SimulationError> {
+        if !bundle.is_includable(self.block_number) {
+            return Err(EthSimBundleError::InvalidInclusion.into())
+        }
+
+        let mut result = SimBundleResult::default();
+        for (body_idx, item) in bundle.body.iter().enumerate() {
+            if Instant::now() > self.deadline {
+                return Err(SimulationError::Timeout)
+            }
+
+            let coinbase_balance_before = self.coinbase_balance()?;
+            let logs = match item {
+                SimBundleItem::Tx { tx, can_revert } => {
+                    self.evm_config.fill_tx_env(self.evm.tx_mut(), tx, tx.signer());
+                    let ResultAndState { result: exec_result, state } =
+                        self.evm.transact().map_err(|err| match err {
+                            EVMError::Database(err) => SimulationError::Provider(err),
+                            err => SimulationError::Bundle {
+                                error: EthSimBundleError::BundleTransactionFailed,
+                                exec_error: Some(err.to_string()),
+                                revert: None,
+                            },
+                        })?;
+
+                    match &exec_result {
+                        ExecutionResult::Revert { output, .. } if !can_revert => {
+                            return Err(SimulationError::Bundle {
+                                error: EthSimBundleError::BundleTransactionFailed,
+                                exec_error: Some("execution reverted".to_string()),
+                                revert: Some(output.clone()),
+                            })
+                        }
+                        ExecutionResult::Halt { reason, .. } if !can_revert => {
+                            return Err(SimulationError::Bundle {
+                                error: EthSimBundleError::BundleTransactionFailed,
+                                exec_error: Some(format!("{reason:?}")),
+                                revert: None,
+                            })
+                        }
+                        _ => {}
+                    }
+
+                    result.gas_used += exec_result.gas_used();
+                    self.evm.db_mut().commit(state);
+
+                    SimBundleLogs {
+                        tx_logs: bundle.share_logs.then(|| exec_result.logs().to_vec()),
+                        bundle_logs: None,
+                    }
+                }
+                SimBundleItem::Bundle(inner) => {
+                    let inner_result = self.simulate(inner)?;
+                    result.gas_used += inner_result.gas_used;
+                    SimBundleLogs { tx_logs: None, bundle_logs: Some(inner_result.logs) }
+                }
+            };
+            result.logs.push(logs);
+
+            let coinbase_diff = self.coinbase_balance()?.saturating_sub(coinbase_balance_before);
+            result.profit += coinbase_diff;
+            if !bundle.is_refunded(body_idx) {
+                result.refundable_value += coinbase_diff;
+            }
+        }
+
+        // pay out the refunds of the bundle from the coinbase
+        let basefee = self.evm.block().basefee;
+        for (body_idx, percent) in &bundle.refunds {
+            let refund_config = bundle.body[*body_idx].refund_config();
+            let payout_cost =
+                basefee * U256::from(SBUNDLE_PAYOUT_MAX_COST * refund_config.len() as u64);
+            let allocated = (result.refundable_value * U256::from(*percent) / U256::from(100))
+                .checked_sub(payout_cost)
+                .ok_or(EthSimBundleError::NegativeProfit)?;
+
+            for config in refund_config {
+                let value = allocated * U256::from(config.percent) / U256::from(100);
+                self.transfer_from_coinbase(config, value)?;
+                result.profit = result.profit.saturating_sub(value);
+                result.gas_used += SBUNDLE_PAYOUT_MAX_COST;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the current balance of the coinbase.
+    fn coinbase_balance(&mut self) -> Result<U256, ProviderError> {
+        let coinbase = self.evm.block().coinbase;
+        Ok(self.evm.db_mut().load_account(coinbase)?.info.balance)
+    }
+
+    /// Transfers the given value from the coinbase to the refund recipient.
+    fn transfer_from_coinbase(
+        &mut self,
+        config: RefundConfig,
+        value: U256,
+    ) -> Result<(), ProviderError> {
+        let coinbase = self.evm.block().coinbase;
+        let db = self.evm.db_mut();
+
+        let account = db.load_account(coinbase)?;
+        account.info.balance = account.info.balance.saturating_sub(value);
+
+        let account = db.load_account(config.address)?;
+        account.info.balance = account.info.balance.saturating_add(value);
+        if account.account_state == AccountState::NotExisting {
+            account.account_state = AccountState::Touched;
+        }
+
+        Ok(())
+    }
+}
+
+/// [`EthSimBundle`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum EthSimBundleError {
+    /// Thrown when the bundles are nested deeper than [`MAX_NESTED_BUNDLE_DEPTH`].
+    #[error("max depth reached")]
+    MaxDepth,
+    /// Thrown when the bundle contains transaction hashes that are not matched to
+    /// transactions.
+    #[error("unmatched bundle")]
+    UnmatchedBundle,
+    /// Thrown when the body of a bundle has more than [`MAX_BUNDLE_BODY_SIZE`] items.
+    #[error("bundle too large")]
+    BundleTooLarge,
+    /// Thrown when the refunds of the bundle are invalid.
+    #[error("invalid validity")]
+    InvalidValidity,
+    /// Thrown when the inclusion range of the bundle is invalid or does not contain the
+    /// simulated block.
+    #[error("invalid inclusion")]
+    InvalidInclusion,
+    /// Thrown when the body of the bundle is empty.
+    #[error("invalid bundle")]
+    InvalidBundle,
+    /// Thrown when a transaction of the bundle can not be decoded or validated.
+    #[error("invalid transaction: {0}")]
+    InvalidTransaction(String),
+    /// Thrown when a transaction of the bundle that is not allowed to revert fails.
+    #[error("bundle transaction failed")]
+    BundleTransactionFailed,
+    /// Thrown when the refunds of the bundle exceed its value.
+    #[error("negative profit")]
+    NegativeProfit,
+}
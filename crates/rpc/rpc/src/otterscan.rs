@@ -1,6 +1,6 @@
 use alloy_consensus::Transaction;
 use alloy_network::{ReceiptResponse, TransactionResponse};
-use alloy_primitives::{Address, Bytes, TxHash, B256, U256};
+use alloy_primitives::{Address, BlockNumber, Bytes, TxHash, B256, U256};
 use alloy_rpc_types::{BlockTransactions, Header, TransactionReceipt};
 use alloy_rpc_types_trace::{
     otterscan::{
@@ -12,6 +12,10 @@ use alloy_rpc_types_trace::{
 use async_trait::async_trait;
 use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned};
 use reth_primitives::{BlockId, BlockNumberOrTag};
+use reth_provider::{
+    HistoryIndexReader, ProviderError, PruneCheckpointReader, TransactionsProvider,
+};
+use reth_prune_types::PruneSegment;
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_eth_api::{
     helpers::{EthTransactions, TraceExt},
@@ -27,20 +31,29 @@ use revm_primitives::{ExecutionResult, SignedAuthorization};
 
 const API_LEVEL: u64 = 8;
 
+/// The number of blocks whose history index entries are loaded at once when searching for the
+/// transactions of an address.
+const SEARCH_WINDOW: u64 = 100_000;
+
+/// The number of blocks whose transactions are loaded at once when searching for the transactions
+/// sent to an address.
+const RECIPIENT_SCAN_BLOCKS: u64 = 1_000;
+
 /// Otterscan API.
 #[derive(Debug)]
-pub struct OtterscanApi<Eth> {
+pub struct OtterscanApi<Provider, Eth> {
+    provider: Provider,
     eth: Eth,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub const fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub const fn new(provider: Provider, eth: Eth) -> Self {
+        Self { provider, eth }
     }
 }
 
-impl<Eth> OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
 where
     Eth: FullEthApiTypes,
 {
@@ -58,11 +71,171 @@ where
 
         Ok(BlockDetails::new(block, Default::default(), U256::from(total_fees)))
     }
+
+    /// Constructs an `OtsTransactionReceipt` from a receipt, dropping the logs and the bloom.
+    fn ots_receipt(
+        receipt: RpcReceipt<Eth::NetworkTypes>,
+        tx_ty: u8,
+        timestamp: Option<u64>,
+    ) -> OtsTransactionReceipt {
+        let inner = OtsReceipt {
+            status: receipt.status(),
+            cumulative_gas_used: receipt.cumulative_gas_used() as u64,
+            logs: None,
+            logs_bloom: None,
+            r#type: tx_ty,
+        };
+
+        let receipt = TransactionReceipt {
+            inner,
+            transaction_hash: receipt.transaction_hash(),
+            transaction_index: receipt.transaction_index(),
+            block_hash: receipt.block_hash(),
+            block_number: receipt.block_number(),
+            gas_used: receipt.gas_used(),
+            effective_gas_price: receipt.effective_gas_price(),
+            blob_gas_used: receipt.blob_gas_used(),
+            blob_gas_price: receipt.blob_gas_price(),
+            from: receipt.from(),
+            to: receipt.to(),
+            contract_address: receipt.contract_address(),
+            authorization_list: receipt.authorization_list().map(<[SignedAuthorization]>::to_vec),
+        };
+
+        OtsTransactionReceipt { receipt, timestamp }
+    }
+}
+
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Provider: HistoryIndexReader + PruneCheckpointReader + TransactionsProvider,
+    Eth: EthApiServer<
+            RpcTransaction<Eth::NetworkTypes>,
+            RpcBlock<Eth::NetworkTypes>,
+            RpcReceipt<Eth::NetworkTypes>,
+        > + TraceExt
+        + 'static,
+{
+    /// Returns the lowest block at which both the account and the storage history indices are
+    /// available.
+    fn lowest_available_history_block(&self) -> Result<BlockNumber, EthApiError> {
+        let mut lowest = 0;
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            // The checkpoint stores the highest pruned block number.
+            if let Some(pruned) = self
+                .provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+            {
+                lowest = lowest.max(pruned + 1);
+            }
+        }
+        Ok(lowest)
+    }
+
+    /// Returns the block numbers in the given range that may contain transactions involving the
+    /// address, in ascending order.
+    ///
+    /// These are the blocks at which the state of the address changed according to the history
+    /// indices, and the blocks with a transaction sent to the address, since a call doesn't
+    /// necessarily change the state of the callee, e.g. a view call or a call that reverted.
+    ///
+    /// NOTE: Blocks in which the address was only called by other contracts without changing its
+    /// state are not returned and therefore never traced by the search handlers.
+    fn history_blocks(
+        &self,
+        address: Address,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> Result<Vec<BlockNumber>, EthApiError> {
+        let mut blocks = self.provider.address_history_blocks(address, start..=end)?;
+
+        let mut chunk_start = start;
+        while chunk_start <= end {
+            let chunk_end = chunk_start.saturating_add(RECIPIENT_SCAN_BLOCKS - 1).min(end);
+            let transactions =
+                self.provider.transactions_by_block_range(chunk_start..=chunk_end)?;
+            blocks.extend(
+                (chunk_start..)
+                    .zip(transactions)
+                    .filter(|(_, transactions)| {
+                        transactions.iter().any(|tx| tx.to() == Some(address))
+                    })
+                    .map(|(block, _)| block),
+            );
+            chunk_start = chunk_end + 1;
+        }
+
+        Ok(blocks.into_iter().collect())
+    }
+
+    /// Returns the transactions of the given block that involve the address, together with their
+    /// receipts, in block order.
+    ///
+    /// A transaction involves the address if the address is the caller, the callee or the
+    /// selfdestruct beneficiary of any call frame of the transaction.
+    ///
+    /// Only the blocks returned by [`Self::history_blocks`] are traced, so transactions in which
+    /// the address is only called by other contracts without changing its state are missed.
+    async fn block_transactions_involving(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> RpcResult<Vec<(RpcTransaction<Eth::NetworkTypes>, OtsTransactionReceipt)>> {
+        let matches = self
+            .eth
+            .trace_block_with(
+                block_number.into(),
+                None,
+                TracingInspectorConfig::default_parity(),
+                move |_tx_info, inspector, _, _, _| {
+                    Ok(inspector.traces().nodes().iter().any(|node| {
+                        node.trace.caller == address ||
+                            node.trace.address == address ||
+                            node.trace.selfdestruct_refund_target == Some(address)
+                    }))
+                },
+            )
+            .await
+            .map_err(Into::into)?
+            .unwrap_or_default();
+
+        if !matches.contains(&true) {
+            return Ok(Vec::new())
+        }
+
+        let block_id = block_number.into();
+        let block = self.eth.block_by_number(block_id, true);
+        let block_id = block_id.into();
+        let receipts = self.eth.block_receipts(block_id);
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_id))?;
+        let receipts = receipts.ok_or(EthApiError::ReceiptsNotFound(block_id))?;
+
+        let timestamp = Some(block.header.timestamp);
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .zip(matches)
+            .filter(|(_, matched)| *matched)
+            .map(|((tx, receipt), _)| {
+                let tx_ty = Eth::TransactionCompat::tx_type(&tx);
+                (tx, Self::ots_receipt(receipt, tx_ty, timestamp))
+            })
+            .collect())
+    }
 }
 
 #[async_trait]
-impl<Eth> OtterscanServer<RpcTransaction<Eth::NetworkTypes>> for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer<RpcTransaction<Eth::NetworkTypes>>
+    for OtterscanApi<Provider, Eth>
 where
+    Provider: HistoryIndexReader + PruneCheckpointReader + TransactionsProvider + 'static,
     Eth: EthApiServer<
             RpcTransaction<Eth::NetworkTypes>,
             RpcBlock<Eth::NetworkTypes>,
@@ -240,35 +413,7 @@ where
         let receipts = receipts
             .drain(page_start..page_end)
             .zip(transactions.iter().map(Eth::TransactionCompat::tx_type))
-            .map(|(receipt, tx_ty)| {
-                let inner = OtsReceipt {
-                    status: receipt.status(),
-                    cumulative_gas_used: receipt.cumulative_gas_used() as u64,
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: tx_ty,
-                };
-
-                let receipt = TransactionReceipt {
-                    inner,
-                    transaction_hash: receipt.transaction_hash(),
-                    transaction_index: receipt.transaction_index(),
-                    block_hash: receipt.block_hash(),
-                    block_number: receipt.block_number(),
-                    gas_used: receipt.gas_used(),
-                    effective_gas_price: receipt.effective_gas_price(),
-                    blob_gas_used: receipt.blob_gas_used(),
-                    blob_gas_price: receipt.blob_gas_price(),
-                    from: receipt.from(),
-                    to: receipt.to(),
-                    contract_address: receipt.contract_address(),
-                    authorization_list: receipt
-                        .authorization_list()
-                        .map(<[SignedAuthorization]>::to_vec),
-                };
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|(receipt, tx_ty)| Self::ots_receipt(receipt, tx_ty, timestamp))
             .collect();
 
         // use `transaction_count` to indicate the paginate information
@@ -280,21 +425,94 @@ where
     /// Handler for `searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts<RpcTransaction<Eth::NetworkTypes>>> {
+        // Block number 0 requests the most recent page, which starts at the tip. Otherwise the
+        // given block is excluded from the search.
+        let first_page = block_number == 0;
+        let mut end =
+            if first_page { self.eth.block_number()?.saturating_to() } else { block_number - 1 };
+
+        let lowest = self.lowest_available_history_block()?;
+        if end < lowest {
+            return Err(EthApiError::from(ProviderError::StateAtBlockPruned(end)).into())
+        }
+
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        let mut last_page = true;
+        'search: loop {
+            let start = end.saturating_sub(SEARCH_WINDOW - 1).max(lowest);
+            for block in self.history_blocks(address, start, end)?.into_iter().rev() {
+                let found = self.block_transactions_involving(address, block).await?;
+                for (tx, receipt) in found.into_iter().rev() {
+                    txs.push(tx);
+                    receipts.push(receipt);
+                }
+
+                // Blocks are never split across pages
+                if txs.len() >= page_size {
+                    last_page = false;
+                    break 'search
+                }
+            }
+
+            if start == lowest {
+                break
+            }
+            end = start - 1;
+        }
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page })
     }
 
     /// Handler for `searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts<RpcTransaction<Eth::NetworkTypes>>> {
+        // Block number 0 requests the oldest page. The given block is excluded from the search.
+        let last_page = block_number == 0;
+        let mut start = block_number.checked_add(1).ok_or_else(|| {
+            EthApiError::InvalidParams("block number is past the latest block".to_string())
+        })?;
+        let tip: u64 = self.eth.block_number()?.saturating_to();
+
+        let lowest = self.lowest_available_history_block()?;
+        if start < lowest {
+            return Err(EthApiError::from(ProviderError::StateAtBlockPruned(start)).into())
+        }
+
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        let mut first_page = true;
+        'search: while start <= tip {
+            let end = start.saturating_add(SEARCH_WINDOW - 1).min(tip);
+            for block in self.history_blocks(address, start, end)? {
+                let found = self.block_transactions_involving(address, block).await?;
+                for (tx, receipt) in found {
+                    txs.push(tx);
+                    receipts.push(receipt);
+                }
+
+                // Blocks are never split across pages
+                if txs.len() >= page_size {
+                    first_page = false;
+                    break 'search
+                }
+            }
+            start = end + 1;
+        }
+
+        // Results are always returned with the most recent transaction first
+        txs.reverse();
+        receipts.reverse();
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
@@ -1,32 +1,59 @@
+use alloy_consensus::Transaction as _;
+use alloy_eips::eip2718::Decodable2718;
+use alloy_primitives::{Address, Bloom, B256, U256};
+use alloy_rpc_types::engine::{
+    CancunPayloadFields, ExecutionPayload, ExecutionPayloadSidecar, PayloadError,
+};
 use alloy_rpc_types_beacon::relay::{
-    BuilderBlockValidationRequest, BuilderBlockValidationRequestV2, BuilderBlockValidationRequestV3,
+    BidTrace, BuilderBlockValidationRequest, BuilderBlockValidationRequestV2,
+    BuilderBlockValidationRequestV3,
 };
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_chainspec::ChainSpecProvider;
+use reth_chainspec::{ChainSpecProvider, EthereumHardforks};
+use reth_consensus_common::validation::{
+    validate_against_parent_eip1559_base_fee, validate_against_parent_hash_number,
+    validate_against_parent_timestamp, validate_block_pre_execution, validate_header_base_fee,
+    validate_header_gas,
+};
+use reth_errors::{BlockExecutionError, ConsensusError, ProviderError};
+use reth_evm::execute::{BlockExecutorProvider, Executor};
+use reth_payload_validator::ExecutionPayloadValidator;
+use reth_primitives::{
+    BlockWithSenders, GotExpected, Receipt, SealedBlock, SealedHeader, TransactionSigned,
+};
 use reth_provider::{
-    AccountReader, BlockReaderIdExt, HeaderProvider, StateProviderFactory, WithdrawalsProvider,
+    AccountReader, BlockExecutionOutput, BlockReaderIdExt, HeaderProvider, StateProviderFactory,
+    StateRootProvider, WithdrawalsProvider,
 };
+use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::BlockSubmissionValidationApiServer;
-use reth_rpc_server_types::result::internal_rpc_err;
-use std::sync::Arc;
-use tracing::warn;
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_tasks::TaskSpawner;
+use reth_trie::HashedPostState;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::oneshot;
+
+/// The bound divisor of the gas limit, used in update calculations.
+const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
 
 /// The type that implements the `validation` rpc namespace trait
-pub struct ValidationApi<Provider> {
-    inner: Arc<ValidationApiInner<Provider>>,
+pub struct ValidationApi<Provider: ChainSpecProvider, E> {
+    inner: Arc<ValidationApiInner<Provider, E>>,
 }
 
-impl<Provider> ValidationApi<Provider>
+impl<Provider, E> ValidationApi<Provider, E>
 where
     Provider: BlockReaderIdExt
-        + ChainSpecProvider
+        + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
         + HeaderProvider
         + AccountReader
         + WithdrawalsProvider
         + Clone
         + 'static,
+    E: BlockExecutorProvider,
 {
     /// The provider that can interact with the chain.
     pub fn provider(&self) -> Provider {
@@ -34,36 +61,393 @@ where
     }
 
     /// Create a new instance of the [`ValidationApi`]
-    pub fn new(provider: Provider) -> Self {
-        let inner = Arc::new(ValidationApiInner { provider });
+    pub fn new(
+        provider: Provider,
+        executor_provider: E,
+        config: ValidationApiConfig,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let ValidationApiConfig { disallow } = config;
+        let payload_validator = ExecutionPayloadValidator::new(provider.chain_spec());
+        let inner = Arc::new(ValidationApiInner {
+            provider,
+            payload_validator,
+            executor_provider,
+            disallow,
+            task_spawner,
+        });
         Self { inner }
     }
+
+    /// Validates the given block against the [`BidTrace`] it was submitted with.
+    ///
+    /// The block must be built on top of a known block and is executed on top of its parent's
+    /// state. The gas limit must move towards the gas limit registered by the proposer, and the
+    /// block must pay at least the value of the bid to the proposer's fee recipient. Blocks that
+    /// touch any of the disallowed addresses are rejected.
+    pub fn validate_message_against_block(
+        &self,
+        block: SealedBlock,
+        message: BidTrace,
+        registered_gas_limit: u64,
+    ) -> Result<(), ValidationApiError> {
+        self.validate_message_against_header(&block.header, &message)?;
+
+        let chain_spec = self.inner.provider.chain_spec();
+        validate_header_gas(&block.header)?;
+        validate_header_base_fee(&block.header, &*chain_spec)?;
+        validate_block_pre_execution(&block, &*chain_spec)?;
+
+        let parent = self
+            .inner
+            .provider
+            .sealed_header_by_hash(block.parent_hash)?
+            .ok_or(ValidationApiError::MissingParentBlock(block.parent_hash))?;
+        validate_against_parent_hash_number(&block.header, &parent)?;
+        validate_against_parent_timestamp(&block.header, &parent)?;
+        validate_against_parent_eip1559_base_fee(&block.header, &parent, &*chain_spec)?;
+        self.validate_gas_limit(registered_gas_limit, &parent, &block.header)?;
+
+        let block = block
+            .seal_with_senders()
+            .ok_or(ValidationApiError::InvalidTransactionSignature)?
+            .unseal();
+        self.validate_disallowed(&block, &message)?;
+
+        let state_provider = self.inner.provider.state_by_block_hash(parent.hash())?;
+        let executor =
+            self.inner.executor_provider.executor(StateProviderDatabase::new(&state_provider));
+        let mut accessed_disallowed = None;
+        let output = executor.execute_with_state_closure((&block, U256::MAX).into(), |state| {
+            if !self.inner.disallow.is_empty() {
+                accessed_disallowed = state
+                    .cache
+                    .accounts
+                    .keys()
+                    .find(|address| self.inner.disallow.contains(*address))
+                    .copied();
+            }
+        })?;
+        if let Some(address) = accessed_disallowed {
+            return Err(ValidationApiError::Blacklist(address))
+        }
+
+        Self::validate_receipts(&block, &output.receipts)?;
+        self.ensure_payment(&block, &output, &message)?;
+
+        let state_root =
+            state_provider.state_root(HashedPostState::from_bundle_state(&output.state.state))?;
+        if state_root != block.state_root {
+            return Err(ValidationApiError::StateRootMismatch(GotExpected {
+                got: state_root,
+                expected: block.state_root,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the fields of the [`BidTrace`] match the header of the submitted block.
+    fn validate_message_against_header(
+        &self,
+        header: &SealedHeader,
+        message: &BidTrace,
+    ) -> Result<(), ValidationApiError> {
+        if header.hash() != message.block_hash {
+            Err(ValidationApiError::BlockHashMismatch(GotExpected {
+                got: message.block_hash,
+                expected: header.hash(),
+            }))
+        } else if header.parent_hash != message.parent_hash {
+            Err(ValidationApiError::ParentHashMismatch(GotExpected {
+                got: message.parent_hash,
+                expected: header.parent_hash,
+            }))
+        } else if header.gas_limit != message.gas_limit {
+            Err(ValidationApiError::GasLimitMismatch(GotExpected {
+                got: message.gas_limit,
+                expected: header.gas_limit,
+            }))
+        } else if header.gas_used != message.gas_used {
+            Err(ValidationApiError::GasUsedMismatch(GotExpected {
+                got: message.gas_used,
+                expected: header.gas_used,
+            }))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ensures that neither the fee recipients of the block nor the senders and recipients of its
+    /// transactions are disallowed.
+    fn validate_disallowed(
+        &self,
+        block: &BlockWithSenders,
+        message: &BidTrace,
+    ) -> Result<(), ValidationApiError> {
+        let disallow = &self.inner.disallow;
+        if disallow.is_empty() {
+            return Ok(())
+        }
+
+        let addresses = [block.beneficiary, message.proposer_fee_recipient]
+            .into_iter()
+            .chain(block.senders.iter().copied())
+            .chain(block.body.transactions.iter().filter_map(|tx| tx.to()));
+        for address in addresses {
+            if disallow.contains(&address) {
+                return Err(ValidationApiError::Blacklist(address))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the gas limit of the block moves as far towards the registered gas limit of
+    /// the proposer as the parent's gas limit allows.
+    fn validate_gas_limit(
+        &self,
+        registered_gas_limit: u64,
+        parent: &SealedHeader,
+        header: &SealedHeader,
+    ) -> Result<(), ValidationApiError> {
+        let max_gas_limit =
+            (parent.gas_limit + parent.gas_limit / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+        let min_gas_limit = parent.gas_limit - parent.gas_limit / GAS_LIMIT_BOUND_DIVISOR + 1;
+        let best_gas_limit = registered_gas_limit.min(max_gas_limit).max(min_gas_limit);
+
+        if header.gas_limit != best_gas_limit {
+            return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+                got: header.gas_limit,
+                expected: best_gas_limit,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the gas used, receipts root and logs bloom of the block match the receipts of
+    /// its execution.
+    fn validate_receipts(
+        block: &BlockWithSenders,
+        receipts: &[Receipt],
+    ) -> Result<(), ValidationApiError> {
+        let gas_used = receipts.last().map(|receipt| receipt.cumulative_gas_used).unwrap_or(0);
+        if block.gas_used != gas_used {
+            return Err(ValidationApiError::GasUsedMismatch(GotExpected {
+                got: gas_used,
+                expected: block.gas_used,
+            }))
+        }
+
+        let receipts_with_bloom = receipts.iter().map(Receipt::with_bloom_ref).collect::<Vec<_>>();
+        let receipts_root =
+            reth_primitives::proofs::calculate_receipt_root_ref(&receipts_with_bloom);
+        if receipts_root != block.receipts_root {
+            return Err(ConsensusError::BodyReceiptRootDiff(
+                GotExpected { got: receipts_root, expected: block.receipts_root }.into(),
+            )
+            .into())
+        }
+
+        let logs_bloom = receipts_with_bloom.iter().fold(Bloom::ZERO, |bloom, r| bloom | r.bloom);
+        if logs_bloom != block.logs_bloom {
+            return Err(ConsensusError::BodyBloomLogDiff(
+                GotExpected { got: logs_bloom, expected: block.logs_bloom }.into(),
+            )
+            .into())
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the proposer's fee recipient received at least the value of the bid.
+    ///
+    /// The payment is either the increase of the fee recipient's balance, not counting
+    /// withdrawals, or the last transaction of the block if it transfers the value to the fee
+    /// recipient.
+    fn ensure_payment(
+        &self,
+        block: &BlockWithSenders,
+        output: &BlockExecutionOutput<Receipt>,
+        message: &BidTrace,
+    ) -> Result<(), ValidationApiError> {
+        let (mut balance_before, balance_after) = if let Some(account) =
+            output.state.state.get(&message.proposer_fee_recipient)
+        {
+            let balance_before =
+                account.original_info.as_ref().map(|info| info.balance).unwrap_or_default();
+            let balance_after = account.info.as_ref().map(|info| info.balance).unwrap_or_default();
+            (balance_before, balance_after)
+        } else {
+            (U256::ZERO, U256::ZERO)
+        };
+
+        if let Some(withdrawals) = &block.body.withdrawals {
+            for withdrawal in withdrawals {
+                if withdrawal.address == message.proposer_fee_recipient {
+                    balance_before += withdrawal.amount_wei();
+                }
+            }
+        }
+
+        if balance_after >= balance_before + message.value {
+            return Ok(())
+        }
+
+        let (receipt, tx) = output
+            .receipts
+            .last()
+            .zip(block.body.transactions.last())
+            .ok_or(ValidationApiError::ProposerPayment)?;
+
+        if !receipt.success {
+            return Err(ValidationApiError::ProposerPayment)
+        }
+        if tx.to() != Some(message.proposer_fee_recipient) {
+            return Err(ValidationApiError::ProposerPayment)
+        }
+        if tx.value() != message.value {
+            return Err(ValidationApiError::ProposerPayment)
+        }
+        if !tx.input().is_empty() {
+            return Err(ValidationApiError::ProposerPayment)
+        }
+        if let Some(base_fee) = block.base_fee_per_gas {
+            if tx.effective_tip_per_gas(Some(base_fee)).unwrap_or_default() != 0 {
+                return Err(ValidationApiError::ProposerPayment)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validates a Bellatrix block submission, which must not contain any withdrawals.
+    pub fn validate_builder_submission_v1(
+        &self,
+        request: BuilderBlockValidationRequest,
+    ) -> Result<(), ValidationApiError> {
+        let payload = request.request.execution_payload;
+        if !matches!(payload, ExecutionPayload::V1(_)) {
+            return Err(ValidationApiError::UnexpectedPayloadVersion(1))
+        }
+        let block = self
+            .inner
+            .payload_validator
+            .ensure_well_formed_payload(payload, ExecutionPayloadSidecar::none())?;
+
+        self.validate_message_against_block(
+            block,
+            request.request.message,
+            request.registered_gas_limit,
+        )
+    }
+
+    /// Validates a Capella block submission, which must commit to the given withdrawals root.
+    pub fn validate_builder_submission_v2(
+        &self,
+        request: BuilderBlockValidationRequestV2,
+    ) -> Result<(), ValidationApiError> {
+        let payload = request.request.execution_payload;
+        if !matches!(payload, ExecutionPayload::V2(_)) {
+            return Err(ValidationApiError::UnexpectedPayloadVersion(2))
+        }
+        let block = self
+            .inner
+            .payload_validator
+            .ensure_well_formed_payload(payload, ExecutionPayloadSidecar::none())?;
+
+        let withdrawals_root = block.withdrawals_root.unwrap_or_default();
+        if withdrawals_root != request.withdrawals_root {
+            return Err(ValidationApiError::WithdrawalsRootMismatch(GotExpected {
+                got: request.withdrawals_root,
+                expected: withdrawals_root,
+            }))
+        }
+
+        self.validate_message_against_block(
+            block,
+            request.request.message,
+            request.registered_gas_limit,
+        )
+    }
+
+    /// Runs the given validation on a blocking task, since it executes the block and computes its
+    /// state root.
+    async fn spawn_validation<F>(&self, f: F) -> RpcResult<()>
+    where
+        F: FnOnce(Self) -> Result<(), ValidationApiError> + Send + 'static,
+    {
+        let this = self.clone();
+        let (tx, rx) = oneshot::channel();
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(f(this));
+        }));
+        rx.await.map_err(|_| internal_rpc_err("validation task was dropped"))?.map_err(Into::into)
+    }
+
+    /// Validates a Deneb block submission on top of the given parent beacon block root.
+    pub fn validate_builder_submission_v3(
+        &self,
+        request: BuilderBlockValidationRequestV3,
+    ) -> Result<(), ValidationApiError> {
+        let payload = request.request.execution_payload;
+        if !matches!(payload, ExecutionPayload::V3(_)) {
+            return Err(ValidationApiError::UnexpectedPayloadVersion(3))
+        }
+
+        // the submission does not come with a blobs bundle, so the versioned hashes are taken
+        // from the blob transactions of the payload itself
+        let mut versioned_hashes = Vec::new();
+        for tx in &payload.as_v1().transactions {
+            let tx = TransactionSigned::decode_2718(&mut tx.as_ref())
+                .map_err(|err| PayloadError::Decode(err.into()))?;
+            versioned_hashes.extend(tx.blob_versioned_hashes().into_iter().flatten());
+        }
+
+        let block = self.inner.payload_validator.ensure_well_formed_payload(
+            payload,
+            ExecutionPayloadSidecar::v3(CancunPayloadFields {
+                parent_beacon_block_root: request.parent_beacon_block_root,
+                versioned_hashes,
+            }),
+        )?;
+
+        self.validate_message_against_block(
+            block,
+            request.request.message,
+            request.registered_gas_limit,
+        )
+    }
 }
 
 #[async_trait]
-impl<Provider> BlockSubmissionValidationApiServer for ValidationApi<Provider>
+impl<Provider, E> BlockSubmissionValidationApiServer for ValidationApi<Provider, E>
 where
     Provider: BlockReaderIdExt
-        + ChainSpecProvider
+        + ChainSpecProvider<ChainSpec: EthereumHardforks>
         + StateProviderFactory
         + HeaderProvider
         + AccountReader
         + WithdrawalsProvider
         + Clone
         + 'static,
+    E: BlockExecutorProvider,
 {
+    /// Validates a block submitted to the relay
     async fn validate_builder_submission_v1(
         &self,
-        _request: BuilderBlockValidationRequest,
+        request: BuilderBlockValidationRequest,
     ) -> RpcResult<()> {
-        Err(internal_rpc_err("unimplemented"))
+        self.spawn_validation(move |this| this.validate_builder_submission_v1(request)).await
     }
 
+    /// Validates a block submitted to the relay
     async fn validate_builder_submission_v2(
         &self,
-        _request: BuilderBlockValidationRequestV2,
+        request: BuilderBlockValidationRequestV2,
     ) -> RpcResult<()> {
-        Err(internal_rpc_err("unimplemented"))
+        self.spawn_validation(move |this| this.validate_builder_submission_v2(request)).await
     }
 
     /// Validates a block submitted to the relay
@@ -71,24 +455,341 @@ where
         &self,
         request: BuilderBlockValidationRequestV3,
     ) -> RpcResult<()> {
-        warn!("flashbots_validateBuilderSubmissionV3: blindly accepting request without validation {:?}", request);
-        Ok(())
+        self.spawn_validation(move |this| this.validate_builder_submission_v3(request)).await
     }
 }
 
-impl<Provider> std::fmt::Debug for ValidationApi<Provider> {
+impl<Provider: ChainSpecProvider, E> std::fmt::Debug for ValidationApi<Provider, E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ValidationApi").finish_non_exhaustive()
     }
 }
 
-impl<Provider> Clone for ValidationApi<Provider> {
+impl<Provider: ChainSpecProvider, E> Clone for ValidationApi<Provider, E> {
     fn clone(&self) -> Self {
         Self { inner: Arc::clone(&self.inner) }
     }
 }
 
-struct ValidationApiInner<Provider> {
+struct ValidationApiInner<Provider: ChainSpecProvider, E> {
     /// The provider that can interact with the chain.
     provider: Provider,
+    /// Converts the submitted payloads into blocks.
+    payload_validator: ExecutionPayloadValidator<Provider::ChainSpec>,
+    /// Executes the submitted blocks.
+    executor_provider: E,
+    /// The addresses that submitted blocks must not touch.
+    disallow: HashSet<Address>,
+    /// Spawns the blocking validation tasks.
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+/// Configuration for the [`ValidationApi`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationApiConfig {
+    /// The addresses that submitted blocks must not touch.
+    ///
+    /// Blocks are rejected if one of these addresses is the fee recipient, sends or receives a
+    /// transaction, or is accessed during execution.
+    pub disallow: HashSet<Address>,
+}
+
+/// Errors thrown by the `validation` API.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationApiError {
+    /// Thrown if the payload is not of the version expected by the method.
+    #[error("expected an execution payload of version {0}")]
+    UnexpectedPayloadVersion(u8),
+    /// Thrown if the block hash of the bid does not match the block.
+    #[error("block hash mismatch: {0}")]
+    BlockHashMismatch(GotExpected<B256>),
+    /// Thrown if the parent hash of the bid does not match the block.
+    #[error("parent hash mismatch: {0}")]
+    ParentHashMismatch(GotExpected<B256>),
+    /// Thrown if the gas limit of the block is not the expected one.
+    #[error("gas limit mismatch: {0}")]
+    GasLimitMismatch(GotExpected<u64>),
+    /// Thrown if the gas used by the block is not the expected one.
+    #[error("gas used mismatch: {0}")]
+    GasUsedMismatch(GotExpected<u64>),
+    /// Thrown if the withdrawals root of the request does not match the block.
+    #[error("withdrawals root mismatch: {0}")]
+    WithdrawalsRootMismatch(GotExpected<B256>),
+    /// Thrown if the state root after executing the block does not match the block.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+    /// Thrown if the parent of the block is unknown.
+    #[error("parent block {0} not found")]
+    MissingParentBlock(B256),
+    /// Thrown if the block touches a disallowed address.
+    #[error("block touches blacklisted address {0}")]
+    Blacklist(Address),
+    /// Thrown if the block does not pay the value of the bid to the proposer.
+    #[error("could not verify proposer payment")]
+    ProposerPayment,
+    /// Thrown if the sender of a transaction can not be recovered.
+    #[error("invalid transaction signature")]
+    InvalidTransactionSignature,
+    /// Thrown if the payload is malformed.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+    /// Thrown if the block is invalid.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// Thrown if the block failed to execute.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// Thrown if the state could not be read.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+impl From<ValidationApiError> for jsonrpsee::types::error::ErrorObject<'static> {
+    fn from(error: ValidationApiError) -> Self {
+        invalid_params_rpc_err(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{constants::EMPTY_OMMER_ROOT_HASH, Header, TxEip1559};
+    use alloy_primitives::TxKind;
+    use alloy_rpc_types_beacon::relay::SubmitBlockRequest;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+    use reth_chainspec::{ChainSpec, ChainSpecBuilder};
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{proofs, Block, BlockBody, Transaction, TxType, Withdrawals};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types_compat::engine::payload::block_to_payload;
+    use reth_tasks::TokioTaskExecutor;
+
+    const GAS_LIMIT: u64 = 30_000_000;
+    const BASE_FEE: u64 = 1_000_000_000;
+
+    /// A chain with a single funded sender, on top of which a block paying the proposer is
+    /// submitted.
+    struct TestChain {
+        provider: MockEthProvider,
+        parent_hash: B256,
+        sender: PrivateKeySigner,
+        proposer_fee_recipient: Address,
+    }
+
+    impl TestChain {
+        fn new(chain_spec: ChainSpec) -> Self {
+            let provider =
+                MockEthProvider { chain_spec: Arc::new(chain_spec), ..Default::default() };
+
+            let parent = Header {
+                number: 1,
+                timestamp: 1,
+                gas_limit: GAS_LIMIT,
+                gas_used: GAS_LIMIT / 2,
+                base_fee_per_gas: Some(BASE_FEE),
+                ..Default::default()
+            };
+            let parent_hash = parent.hash_slow();
+            provider.add_header(parent_hash, parent);
+
+            let sender = PrivateKeySigner::random();
+            provider.add_account(
+                sender.address(),
+                ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))),
+            );
+
+            Self { provider, parent_hash, sender, proposer_fee_recipient: Address::random() }
+        }
+
+        /// Returns a block that pays the given value to the proposer, and the bid for it.
+        fn block(
+            &self,
+            value: U256,
+            withdrawals: Option<Withdrawals>,
+            parent_beacon_block_root: Option<B256>,
+        ) -> (SealedBlock, BidTrace) {
+            let tx = Transaction::Eip1559(TxEip1559 {
+                chain_id: self.provider.chain_spec.chain.id(),
+                nonce: 0,
+                gas_limit: 21_000,
+                max_fee_per_gas: BASE_FEE as u128,
+                max_priority_fee_per_gas: 0,
+                to: TxKind::Call(self.proposer_fee_recipient),
+                value,
+                ..Default::default()
+            });
+            let signature = self.sender.sign_hash_sync(&tx.signature_hash()).unwrap();
+            let transactions =
+                vec![TransactionSigned::from_transaction_and_signature(tx, signature)];
+
+            let receipt = Receipt {
+                tx_type: TxType::Eip1559,
+                success: true,
+                cumulative_gas_used: 21_000,
+                ..Default::default()
+            };
+            let cancun = parent_beacon_block_root.is_some();
+            let header = Header {
+                parent_hash: self.parent_hash,
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                beneficiary: Address::random(),
+                state_root: B256::random(),
+                transactions_root: proofs::calculate_transaction_root(&transactions),
+                receipts_root: proofs::calculate_receipt_root_no_memo(&[&receipt]),
+                number: 2,
+                gas_limit: GAS_LIMIT,
+                gas_used: 21_000,
+                timestamp: 13,
+                base_fee_per_gas: Some(BASE_FEE),
+                withdrawals_root: withdrawals
+                    .as_ref()
+                    .map(|w| proofs::calculate_withdrawals_root(w)),
+                blob_gas_used: cancun.then_some(0),
+                excess_blob_gas: cancun.then_some(0),
+                parent_beacon_block_root,
+                ..Default::default()
+            };
+            let block =
+                Block { header, body: BlockBody { transactions, ommers: Vec::new(), withdrawals } }
+                    .seal_slow();
+
+            let message = BidTrace {
+                parent_hash: block.parent_hash,
+                block_hash: block.hash(),
+                proposer_fee_recipient: self.proposer_fee_recipient,
+                gas_limit: block.gas_limit,
+                gas_used: block.gas_used,
+                value,
+                ..Default::default()
+            };
+            (block, message)
+        }
+
+        fn validation_api(
+            &self,
+            disallow: HashSet<Address>,
+        ) -> ValidationApi<MockEthProvider, impl BlockExecutorProvider> {
+            ValidationApi::new(
+                self.provider.clone(),
+                EthExecutorProvider::ethereum(self.provider.chain_spec.clone()),
+                ValidationApiConfig { disallow },
+                Box::new(TokioTaskExecutor::default()),
+            )
+        }
+    }
+
+    fn submit_block_request(block: SealedBlock, message: BidTrace) -> SubmitBlockRequest {
+        SubmitBlockRequest {
+            message,
+            execution_payload: block_to_payload(block),
+            signature: Default::default(),
+        }
+    }
+
+    fn request_v1(block: SealedBlock, message: BidTrace) -> BuilderBlockValidationRequest {
+        BuilderBlockValidationRequest {
+            request: submit_block_request(block, message),
+            registered_gas_limit: GAS_LIMIT,
+        }
+    }
+
+    #[tokio::test]
+    async fn validate_submission_v1() {
+        let chain = TestChain::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let (block, message) = chain.block(U256::from(1_000), None, None);
+        chain.provider.add_state_root(block.state_root);
+
+        let api = chain.validation_api(HashSet::new());
+        BlockSubmissionValidationApiServer::validate_builder_submission_v1(
+            &api,
+            request_v1(block, message),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn validate_submission_v2() {
+        let chain = TestChain::new(ChainSpecBuilder::mainnet().shanghai_activated().build());
+        let (block, message) = chain.block(U256::from(1_000), Some(Withdrawals::default()), None);
+        chain.provider.add_state_root(block.state_root);
+
+        let api = chain.validation_api(HashSet::new());
+        let request = BuilderBlockValidationRequestV2 {
+            withdrawals_root: block.withdrawals_root.unwrap(),
+            request: submit_block_request(block, message),
+            registered_gas_limit: GAS_LIMIT,
+        };
+        BlockSubmissionValidationApiServer::validate_builder_submission_v2(&api, request)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn validate_submission_v3() {
+        let chain = TestChain::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let parent_beacon_block_root = B256::random();
+        let (block, message) = chain.block(
+            U256::from(1_000),
+            Some(Withdrawals::default()),
+            Some(parent_beacon_block_root),
+        );
+        chain.provider.add_state_root(block.state_root);
+
+        let api = chain.validation_api(HashSet::new());
+        let request = BuilderBlockValidationRequestV3 {
+            request: submit_block_request(block, message),
+            registered_gas_limit: GAS_LIMIT,
+            parent_beacon_block_root,
+        };
+        BlockSubmissionValidationApiServer::validate_builder_submission_v3(&api, request)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn reject_state_root_mismatch() {
+        let chain = TestChain::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let (block, message) = chain.block(U256::from(1_000), None, None);
+        let expected = block.state_root;
+        let got = B256::random();
+        chain.provider.add_state_root(got);
+
+        let err = chain
+            .validation_api(HashSet::new())
+            .validate_builder_submission_v1(request_v1(block, message))
+            .unwrap_err();
+        assert!(
+            matches!(err, ValidationApiError::StateRootMismatch(mismatch) if mismatch == GotExpected { got, expected })
+        );
+    }
+
+    #[test]
+    fn reject_insufficient_payment() {
+        let chain = TestChain::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let (block, mut message) = chain.block(U256::from(1_000), None, None);
+        chain.provider.add_state_root(block.state_root);
+        message.value += U256::from(1);
+
+        let err = chain
+            .validation_api(HashSet::new())
+            .validate_builder_submission_v1(request_v1(block, message))
+            .unwrap_err();
+        assert!(matches!(err, ValidationApiError::ProposerPayment));
+    }
+
+    #[test]
+    fn reject_blacklisted_address() {
+        let chain = TestChain::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let (block, message) = chain.block(U256::from(1_000), None, None);
+        chain.provider.add_state_root(block.state_root);
+        let sender = chain.sender.address();
+
+        let err = chain
+            .validation_api(HashSet::from([sender]))
+            .validate_builder_submission_v1(request_v1(block, message))
+            .unwrap_err();
+        assert!(matches!(err, ValidationApiError::Blacklist(address) if address == sender));
+    }
 }
@@ -250,6 +250,10 @@ impl<N: ProviderNodeTypes> Pipeline<N> {
     ///   [`StageId::Execution`]
     /// - [`StaticFileSegment::Transactions`](reth_static_file_types::StaticFileSegment::Transactions)
     ///   -> [`StageId::Bodies`]
+    /// - [`StaticFileSegment::AccountChangeSets`](reth_static_file_types::StaticFileSegment::AccountChangeSets)
+    ///   -> [`StageId::IndexAccountHistory`]
+    /// - [`StaticFileSegment::StorageChangeSets`](reth_static_file_types::StaticFileSegment::StorageChangeSets)
+    ///   -> [`StageId::IndexStorageHistory`]
     ///
     /// CAUTION: This method locks the static file producer Mutex, hence can block the thread if the
     /// lock is occupied.
@@ -1,7 +1,8 @@
 //! Built-in [`StageSet`]s.
 //!
 //! The easiest set to use is [`DefaultStages`], which provides all stages required to run an
-//! instance of reth.
+//! instance of reth. [`SnapSyncStages`] is an alternative to it, which downloads the state at a
+//! recent block instead of executing all blocks from genesis.
 //!
 //! It is also possible to run parts of reth standalone given the required data is present in
 //! the environment, such as [`ExecutionStages`] or [`HashingStages`].
@@ -37,7 +38,8 @@ use crate::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, FinishStage, HeaderStage,
         IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, PruneSenderRecoveryStage,
-        PruneStage, SenderRecoveryStage, StorageHashingStage, TransactionLookupStage,
+        PruneStage, SenderRecoveryStage, SnapSyncStage, StorageHashingStage,
+        TransactionLookupStage,
     },
     StageSet, StageSetBuilder,
 };
@@ -45,7 +47,9 @@ use alloy_primitives::B256;
 use reth_config::config::StageConfig;
 use reth_consensus::Consensus;
 use reth_evm::execute::BlockExecutorProvider;
-use reth_network_p2p::{bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader};
+use reth_network_p2p::{
+    bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader, SnapClient,
+};
 use reth_provider::HeaderSyncGapProvider;
 use reth_prune_types::PruneModes;
 use reth_stages_api::Stage;
@@ -159,6 +163,83 @@ where
     }
 }
 
+/// A set containing all stages to run a snap syncing instance of reth.
+///
+/// Same as [`DefaultStages`], with the [`SnapSyncStage`] after the [`BodyStage`]. The snap sync
+/// stage downloads the state at a recent pivot block once, and moves the checkpoints of the
+/// offline stages to the pivot, so they only process the blocks after it.
+#[derive(Debug)]
+pub struct SnapSyncStages<Provider, H, B, C, EF> {
+    /// Configuration for the online stages
+    online: OnlineStages<Provider, H, B>,
+    /// The client used to download the state
+    snap_client: C,
+    /// Executor factory needs for execution stage
+    executor_factory: EF,
+    /// Configuration for each stage in the pipeline
+    stages_config: StageConfig,
+    /// Prune configuration for every segment that can be pruned
+    prune_modes: PruneModes,
+}
+
+impl<Provider, H, B, C, E> SnapSyncStages<Provider, H, B, C, E> {
+    /// Create a new set of snap sync stages with default values.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        provider: Provider,
+        tip: watch::Receiver<B256>,
+        consensus: Arc<dyn Consensus>,
+        header_downloader: H,
+        body_downloader: B,
+        snap_client: C,
+        executor_factory: E,
+        stages_config: StageConfig,
+        prune_modes: PruneModes,
+    ) -> Self
+    where
+        C: SnapClient,
+        E: BlockExecutorProvider,
+    {
+        Self {
+            online: OnlineStages::new(
+                provider,
+                tip,
+                consensus,
+                header_downloader,
+                body_downloader,
+                stages_config.clone(),
+            ),
+            snap_client,
+            executor_factory,
+            stages_config,
+            prune_modes,
+        }
+    }
+}
+
+impl<P, H, B, C, E, Provider> StageSet<Provider> for SnapSyncStages<P, H, B, C, E>
+where
+    P: HeaderSyncGapProvider + 'static,
+    H: HeaderDownloader + 'static,
+    B: BodyDownloader + 'static,
+    C: SnapClient + 'static,
+    E: BlockExecutorProvider,
+    OnlineStages<P, H, B>: StageSet<Provider>,
+    OfflineStages<E>: StageSet<Provider>,
+    SnapSyncStage<C>: Stage<Provider>,
+{
+    fn builder(self) -> StageSetBuilder<Provider> {
+        DefaultStages::<P, H, B, E>::add_offline_stages(
+            self.online
+                .builder()
+                .add_stage(SnapSyncStage::new(self.snap_client, self.stages_config.snap_sync)),
+            self.executor_factory,
+            self.stages_config.clone(),
+            self.prune_modes,
+        )
+    }
+}
+
 /// A set containing all stages that require network access by default.
 ///
 /// These stages *can* be run without network access if the specified downloaders are
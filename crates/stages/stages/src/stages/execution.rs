@@ -13,7 +13,7 @@ use reth_exex::{ExExManagerHandle, ExExNotification, ExExNotificationSource};
 use reth_primitives::{Header, SealedHeader, StaticFileSegment};
 use reth_primitives_traits::format_gas_throughput;
 use reth_provider::{
-    providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
+    providers::{is_snap_synced, StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
     writer::UnifiedStorageWriter,
     BlockReader, DBProvider, HeaderProvider, LatestStateProviderRef, OriginalValuesKnown,
     ProviderError, StateChangeWriter, StateWriter, StaticFileProviderFactory, StatsReader,
@@ -205,9 +205,7 @@ where
         let static_file_provider = provider.static_file_provider();
 
         // We only use static files for Receipts, if there is no receipt pruning of any kind.
-        let static_file_producer = if self.prune_modes.receipts.is_none() &&
-            self.prune_modes.receipts_log_filter.is_empty()
-        {
+        let static_file_producer = if !self.prune_modes.has_receipts_pruning() {
             debug!(target: "sync::stages::execution", start = start_block, "Preparing static file producer");
             let mut producer =
                 prepare_static_file_producer(provider, &static_file_provider, start_block)?;
@@ -219,10 +217,10 @@ where
             None
         };
 
-        let db = StateProviderDatabase(LatestStateProviderRef::new(
-            provider.tx_ref(),
-            provider.static_file_provider(),
-        ));
+        let db = StateProviderDatabase(
+            LatestStateProviderRef::new(provider.tx_ref(), provider.static_file_provider())
+                .with_snap_synced(is_snap_synced(provider.tx_ref())?),
+        );
         let mut executor = self.executor_provider.batch_executor(db);
         executor.set_tip(max_block);
         executor.set_prune_modes(prune_modes);
@@ -438,7 +436,7 @@ where
         let static_file_provider = provider.static_file_provider();
 
         // Unwind all receipts for transactions in the block range
-        if self.prune_modes.receipts.is_none() && self.prune_modes.receipts_log_filter.is_empty() {
+        if !self.prune_modes.has_receipts_pruning() {
             // We only use static files for Receipts, if there is no receipt pruning of any kind.
 
             // prepare_static_file_producer does a consistency check that will unwind static files
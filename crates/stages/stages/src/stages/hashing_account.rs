@@ -8,7 +8,9 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::Account;
-use reth_provider::{AccountExtReader, DBProvider, HashingWriter, StatsReader};
+use reth_provider::{
+    providers::is_snap_synced, AccountExtReader, DBProvider, HashingWriter, StatsReader,
+};
 use reth_stages_api::{
     AccountHashingCheckpoint, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint,
     StageError, StageId, UnwindInput, UnwindOutput,
@@ -145,8 +147,11 @@ where
         // if there are more blocks then threshold it is faster to go over Plain state and hash all
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
-        // genesis accounts are not in changeset.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        // genesis accounts are not in changeset. The plain state of a snap synced node is
+        // incomplete, so it's always hashed incrementally.
+        if (to_block - from_block > self.clean_threshold || from_block == 1) &&
+            !is_snap_synced(provider.tx_ref())?
+        {
             let tx = provider.tx_ref();
 
             // clear table, load all accounts and hash it
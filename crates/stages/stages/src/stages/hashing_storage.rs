@@ -10,7 +10,9 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::StorageEntry;
-use reth_provider::{DBProvider, HashingWriter, StatsReader, StorageReader};
+use reth_provider::{
+    providers::is_snap_synced, DBProvider, HashingWriter, StatsReader, StorageReader,
+};
 use reth_stages_api::{
     EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
     StorageHashingCheckpoint, UnwindInput, UnwindOutput,
@@ -83,8 +85,10 @@ where
         // if there are more blocks then threshold it is faster to go over Plain state and hash all
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
-        // genesis accounts are not in changeset, along with their storages.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        // genesis accounts are not in changeset, along with their storages. The plain state of a
+        // snap synced node is incomplete, so it's always hashed incrementally.
+        if (to_block - from_block > self.clean_threshold || from_block == 1) && !is_snap_synced(tx)?
+        {
             // clear table, load all accounts and hash it
             tx.clear::<tables::HashedStorages>()?;
 
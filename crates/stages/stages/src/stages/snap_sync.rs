@@ -0,0 +1,1538 @@
+use alloy_consensus::constants::KECCAK_EMPTY;
+use alloy_primitives::{keccak256, map::HashSet, Bytes, B256, U256};
+use alloy_rlp::Decodable;
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use reth_codecs::Compact;
+use reth_config::config::SnapSyncConfig;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+    transaction::{DbTx, DbTxMut},
+};
+use reth_eth_wire_types::{
+    GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes, SnapAccount,
+};
+use reth_network_p2p::{error::RequestError, SnapClient};
+use reth_primitives::{Account, Bytecode, StorageEntry};
+use reth_provider::{
+    BlockReader, DBProvider, ProviderError, PruneCheckpointWriter, StageCheckpointReader,
+    StageCheckpointWriter, TrieWriter,
+};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages_api::{
+    ExecInput, ExecOutput, MerkleCheckpoint, SnapSyncCheckpoint, SnapSyncPhase, Stage,
+    StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use reth_trie::{
+    prefix_set::{PrefixSetMut, TriePrefixSetsMut},
+    proof::Proof,
+    verify_range_proof, IntermediateStateRootState, Nibbles, StateRoot, StateRootProgress,
+    StorageRoot, StoredSubNode, TrieAccount, TrieNode, CHILD_INDEX_RANGE, EMPTY_ROOT_HASH,
+};
+use reth_trie_db::{DatabaseProof, DatabaseStateRoot, DatabaseStorageRoot};
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tracing::*;
+
+/// The distance of the pivot block from the sync target.
+///
+/// Peers only serve the state of the most recent blocks, so the pivot is chosen close to the tip.
+pub const SNAP_SYNC_PIVOT_DISTANCE: u64 = 64;
+
+/// The soft limit of the response size of account and storage range requests.
+const RANGE_RESPONSE_BYTES: u64 = 512 * 1024;
+
+/// The soft limit of the response size of bytecode and trie node requests.
+const BATCH_RESPONSE_BYTES: u64 = 1024 * 1024;
+
+/// The maximum number of accounts whose storage is requested at once.
+const MAX_STORAGE_ACCOUNTS_PER_REQUEST: usize = 128;
+
+/// The maximum number of bytecodes requested at once.
+const MAX_BYTE_CODES_PER_REQUEST: usize = 64;
+
+/// The maximum number of trie nodes requested at once.
+const MAX_TRIE_NODES_PER_REQUEST: usize = 256;
+
+/// The number of failed requests after which the download is given up.
+const MAX_REQUEST_FAILURES: usize = 16;
+
+/// The number of empty responses after which the state of the pivot is considered unavailable.
+const MAX_EMPTY_RESPONSES: usize = 8;
+
+/// The time to wait before retrying a request if no `snap` peers are connected.
+const NO_PEERS_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The snap sync stage downloads the state at a recent pivot block over `snap/1`, instead of
+/// executing all blocks from genesis.
+///
+/// The stage runs after the headers and bodies are downloaded, and goes through the following
+/// phases, see [`SnapSyncPhase`]:
+///
+/// 1. The accounts, along with their storages and bytecodes, are downloaded in parallel ranges of
+///    the hashed key space. Every range is verified with the proofs of its boundaries against the
+///    state root of the pivot, and written to the hashed state tables.
+/// 2. The state trie is computed from the downloaded state, like the [`MerkleStage`] does.
+/// 3. Peers stop serving the state of the pivot after a while, in which case the stage moves to a
+///    newer pivot. Ranges downloaded before that are outdated, so the trie is healed: the trie
+///    nodes of the new pivot are fetched top-down, and the accounts below the nodes that differ
+///    from the local trie are downloaded again.
+///
+/// Once the state trie matches the state root of the pivot, the checkpoints of the execution and
+/// hashing stages are moved to the pivot, so the rest of the pipeline continues from there. The
+/// plain state only contains the changes after the pivot, so the state providers read the rest of
+/// the state from the hashed tables.
+///
+/// The receipts and history before the pivot are never available, so the stage requires receipts
+/// pruning. It's skipped if the node has already executed blocks, or if the target is too close to
+/// genesis.
+///
+/// [`MerkleStage`]: crate::stages::MerkleStage
+#[derive(Debug)]
+pub struct SnapSyncStage<C> {
+    /// The client used to download the state.
+    client: C,
+    /// The number of entries to download before committing progress to the database.
+    commit_threshold: u64,
+    /// The number of requests that are in flight at once.
+    concurrency: usize,
+    /// The progress of the stage, loaded from the database on the first execution.
+    progress: Option<SnapSyncCheckpoint>,
+    /// Requests that are yet to be sent.
+    pending: VecDeque<SnapRequest>,
+    /// Requests that are in flight.
+    inflight: FuturesUnordered<SnapFuture>,
+    /// Downloaded state that is yet to be written to the database.
+    buffer: StateBuffer,
+    /// Fetched trie nodes that are yet to be compared with the local trie.
+    fetched_nodes: Vec<(Nibbles, Bytes)>,
+    /// Whether peers stopped serving the state of the pivot.
+    stale: bool,
+}
+
+impl<C> SnapSyncStage<C> {
+    /// Create new instance of [`SnapSyncStage`].
+    pub fn new(client: C, config: SnapSyncConfig) -> Self {
+        Self {
+            client,
+            commit_threshold: config.commit_threshold,
+            concurrency: config.concurrency.max(1),
+            progress: None,
+            pending: VecDeque::new(),
+            inflight: FuturesUnordered::new(),
+            buffer: StateBuffer::default(),
+            fetched_nodes: Vec::new(),
+            stale: false,
+        }
+    }
+
+    /// Drops all in-memory state, so it's loaded from the database again.
+    fn reset(&mut self) {
+        self.progress = None;
+        self.pending.clear();
+        self.inflight = FuturesUnordered::new();
+        self.buffer = StateBuffer::default();
+        self.fetched_nodes.clear();
+        self.stale = false;
+    }
+
+    /// Sets the progress of the stage, and queues the requests of its phase.
+    fn set_progress(&mut self, progress: SnapSyncCheckpoint) {
+        self.pending.clear();
+        match progress.phase {
+            SnapSyncPhase::Accounts => {
+                self.pending.extend(progress.account_ranges.iter().map(|(next, last)| {
+                    SnapRequest::Accounts { start: *next, limit: *last, heal: false }
+                }));
+            }
+            SnapSyncPhase::Heal => {
+                self.pending.push_back(SnapRequest::TrieNodes(vec![(
+                    Nibbles::default(),
+                    progress.state_root,
+                )]));
+            }
+            SnapSyncPhase::Trie | SnapSyncPhase::Done => {}
+        }
+        self.progress = Some(progress);
+    }
+
+    /// Handles a response to a request.
+    fn on_response(&mut self, root: B256, response: SnapResponse) {
+        let Some(progress) = &mut self.progress else { return };
+        match response {
+            SnapResponse::Accounts(mut chunk) => {
+                if let Some(next) = chunk.next {
+                    self.pending.push_back(SnapRequest::Accounts {
+                        start: next,
+                        limit: chunk.limit,
+                        heal: chunk.heal,
+                    });
+                }
+                if !chunk.heal {
+                    if let Some(index) =
+                        progress.account_ranges.iter().position(|(_, last)| *last == chunk.limit)
+                    {
+                        match chunk.next {
+                            Some(next) => progress.account_ranges[index].0 = next,
+                            None => {
+                                progress.account_ranges.remove(index);
+                            }
+                        }
+                    }
+                }
+                self.buffer.extend_storages(std::mem::take(&mut chunk.storages));
+                self.buffer.extend_codes(std::mem::take(&mut chunk.codes));
+                self.buffer.len += chunk.accounts.len();
+                self.buffer.accounts.push(chunk);
+            }
+            SnapResponse::Storages(storages) => self.buffer.extend_storages(storages),
+            SnapResponse::ByteCodes(codes) => self.buffer.extend_codes(codes),
+            SnapResponse::TrieNodes(nodes) => {
+                // the nodes of an outdated pivot don't match the paths of the new one
+                if root == progress.state_root {
+                    self.fetched_nodes.extend(nodes);
+                }
+            }
+        }
+    }
+}
+
+impl<C> SnapSyncStage<C>
+where
+    C: SnapClient + Clone + 'static,
+{
+    /// Sends pending requests, as long as the buffer has room for their responses.
+    fn send_requests(&mut self) {
+        let Some(progress) = &self.progress else { return };
+        while self.inflight.len() < self.concurrency &&
+            (self.buffer.len as u64) < self.commit_threshold
+        {
+            let Some(request) = self.pending.pop_front() else { break };
+            let fetcher = SnapFetcher { client: self.client.clone(), root: progress.state_root };
+            self.inflight.push(Box::pin(async move {
+                let response = fetcher.fetch(&request).await;
+                (fetcher.root, request, response)
+            }));
+        }
+    }
+
+    /// Loads the progress of the stage, or starts the snap sync if it's not started yet.
+    ///
+    /// Returns `None` if the stage is skipped.
+    fn load_progress<Provider>(
+        &mut self,
+        provider: &Provider,
+        input: ExecInput,
+    ) -> Result<Option<()>, StageError>
+    where
+        Provider: DBProvider + BlockReader + StageCheckpointReader,
+    {
+        if self.progress.is_some() {
+            return Ok(Some(()))
+        }
+
+        let progress =
+            provider.get_stage_checkpoint_progress(StageId::SnapSync)?.unwrap_or_default();
+        if !progress.is_empty() {
+            let (progress, _) = SnapSyncCheckpoint::from_compact(&progress, progress.len());
+            if progress.phase == SnapSyncPhase::Done {
+                return Ok(None)
+            }
+            self.set_progress(progress);
+            return Ok(Some(()))
+        }
+
+        let target = input.target();
+        let executed =
+            provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+        if executed > 0 || target <= SNAP_SYNC_PIVOT_DISTANCE {
+            info!(target: "sync::stages::snap_sync", executed, target, "Skipping snap sync");
+            return Ok(None)
+        }
+        if !provider.prune_modes_ref().has_receipts_pruning() {
+            return Err(StageError::Fatal(Box::new(SnapSyncStageError::ReceiptsNotPruned)))
+        }
+
+        let pivot = target - SNAP_SYNC_PIVOT_DISTANCE;
+        let state_root = pivot_state_root(provider, pivot)?;
+        info!(target: "sync::stages::snap_sync", pivot, %state_root, "Starting snap sync");
+        self.set_progress(SnapSyncCheckpoint {
+            pivot,
+            state_root,
+            phase: SnapSyncPhase::Accounts,
+            account_ranges: account_partitions(self.concurrency),
+            merkle: None,
+        });
+        Ok(Some(()))
+    }
+
+    fn execute_inner<Provider>(
+        &mut self,
+        provider: &Provider,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut>
+            + BlockReader
+            + TrieWriter
+            + StageCheckpointReader
+            + StageCheckpointWriter
+            + PruneCheckpointWriter,
+    {
+        if self.load_progress(provider, input)?.is_none() {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+        let mut progress = self.progress.take().expect("progress is loaded");
+
+        // the downloaded state is valid regardless of the pivot, so it's always written
+        let buffer = std::mem::take(&mut self.buffer);
+        let heal = progress.phase == SnapSyncPhase::Heal;
+        let mut prefix_sets = TriePrefixSetsMut::default();
+        let requests = write_state(provider, buffer, heal.then_some(&mut prefix_sets))?;
+        self.pending.extend(requests);
+        if heal && !prefix_sets.account_prefix_set.is_empty() {
+            let (_, updates) = StateRoot::from_tx(provider.tx_ref())
+                .with_prefix_sets(prefix_sets.freeze())
+                .root_with_updates()
+                .map_err(|err| StageError::Fatal(Box::new(err)))?;
+            provider.write_trie_updates(&updates)?;
+        }
+
+        // move to a newer pivot if the state of the current one is not served anymore, or if the
+        // pivot is not canonical anymore
+        let canonical = provider
+            .header_by_number(progress.pivot)?
+            .is_some_and(|header| header.state_root == progress.state_root);
+        if self.stale || !canonical {
+            let pivot = input.target().saturating_sub(SNAP_SYNC_PIVOT_DISTANCE);
+            if canonical && pivot <= progress.pivot {
+                // wait for the headers stage to download a newer pivot
+                info!(target: "sync::stages::snap_sync", pivot = progress.pivot, "State of the pivot is unavailable, waiting for a newer pivot");
+                self.stale = false;
+                save_progress(provider, &progress)?;
+                self.progress = Some(progress);
+                return Ok(ExecOutput::done(input.checkpoint()))
+            }
+
+            progress.pivot = pivot;
+            progress.state_root = pivot_state_root(provider, pivot)?;
+            info!(target: "sync::stages::snap_sync", pivot, state_root = %progress.state_root, "Moving to a newer pivot");
+            self.stale = false;
+            if progress.phase == SnapSyncPhase::Heal {
+                // the healing starts over from the new state root
+                self.fetched_nodes.clear();
+                self.set_progress(progress);
+                progress = self.progress.take().expect("progress is set");
+            }
+        }
+
+        let done = match progress.phase {
+            SnapSyncPhase::Accounts => {
+                if progress.account_ranges.is_empty() && self.inflight.is_empty() {
+                    info!(target: "sync::stages::snap_sync", pivot = progress.pivot, "Downloaded accounts, computing state trie");
+                    provider.tx_ref().clear::<tables::AccountsTrie>()?;
+                    provider.tx_ref().clear::<tables::StoragesTrie>()?;
+                    progress.phase = SnapSyncPhase::Trie;
+                }
+                false
+            }
+            SnapSyncPhase::Trie => self.compute_trie(provider, &mut progress)?,
+            SnapSyncPhase::Heal => self.heal(provider, &progress)?,
+            SnapSyncPhase::Done => true,
+        };
+
+        if done {
+            finish(provider, &mut progress)?;
+            save_progress(provider, &progress)?;
+            self.reset();
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        save_progress(provider, &progress)?;
+        self.progress = Some(progress);
+        Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+    }
+
+    /// Computes the next chunk of the state trie from the downloaded state.
+    ///
+    /// Returns whether the state trie matches the state root of the pivot.
+    fn compute_trie<Provider>(
+        &mut self,
+        provider: &Provider,
+        progress: &mut SnapSyncCheckpoint,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider + TrieWriter,
+    {
+        let root_progress = StateRoot::from_tx(provider.tx_ref())
+            .with_intermediate_state(progress.merkle.take().map(IntermediateStateRootState::from))
+            .root_with_progress()
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+
+        match root_progress {
+            StateRootProgress::Progress(state, _, updates) => {
+                provider.write_trie_updates(&updates)?;
+                progress.merkle = Some(MerkleCheckpoint::new(
+                    progress.pivot,
+                    state.last_account_key,
+                    state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                    state.hash_builder.into(),
+                ));
+                Ok(false)
+            }
+            StateRootProgress::Complete(root, _, updates) => {
+                provider.write_trie_updates(&updates)?;
+                if root == progress.state_root {
+                    return Ok(true)
+                }
+
+                info!(target: "sync::stages::snap_sync", pivot = progress.pivot, %root, expected = %progress.state_root, "State trie is outdated, healing");
+                progress.phase = SnapSyncPhase::Heal;
+                self.pending.push_back(SnapRequest::TrieNodes(vec![(
+                    Nibbles::default(),
+                    progress.state_root,
+                )]));
+                Ok(false)
+            }
+        }
+    }
+
+    /// Compares the fetched trie nodes with the local trie, and schedules the parts that differ
+    /// for download.
+    ///
+    /// Returns whether the state trie matches the state root of the pivot.
+    fn heal<Provider>(
+        &mut self,
+        provider: &Provider,
+        progress: &SnapSyncCheckpoint,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider,
+    {
+        let fetched_nodes = std::mem::take(&mut self.fetched_nodes);
+        if !fetched_nodes.is_empty() {
+            let targets = fetched_nodes
+                .iter()
+                .map(|(path, _)| (padded_key(path), HashSet::default()))
+                .collect();
+            let local_nodes = Proof::from_tx(provider.tx_ref())
+                .multiproof(targets)
+                .map_err(|err| StageError::Fatal(Box::new(err)))?
+                .account_subtree;
+
+            let mut missing_nodes = Vec::new();
+            for (path, node) in fetched_nodes {
+                let local = local_nodes.get(&path);
+                if local == Some(&node) {
+                    continue
+                }
+                diff_trie_node(&path, &node, local, &mut missing_nodes, &mut self.pending);
+            }
+            self.pending.extend(
+                missing_nodes
+                    .chunks(MAX_TRIE_NODES_PER_REQUEST)
+                    .map(|nodes| SnapRequest::TrieNodes(nodes.to_vec())),
+            );
+        }
+
+        if !self.pending.is_empty() || !self.inflight.is_empty() {
+            return Ok(false)
+        }
+
+        // the healing pass is complete
+        let root = StateRoot::from_tx(provider.tx_ref())
+            .root()
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+        if root == progress.state_root {
+            return Ok(true)
+        }
+
+        debug!(target: "sync::stages::snap_sync", %root, expected = %progress.state_root, "Restarting trie healing");
+        self.pending
+            .push_back(SnapRequest::TrieNodes(vec![(Nibbles::default(), progress.state_root)]));
+        Ok(false)
+    }
+}
+
+impl<C, Provider> Stage<Provider> for SnapSyncStage<C>
+where
+    C: SnapClient + Clone + 'static,
+    Provider: DBProvider<Tx: DbTxMut>
+        + BlockReader
+        + TrieWriter
+        + StageCheckpointReader
+        + StageCheckpointWriter
+        + PruneCheckpointWriter,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::SnapSync
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        if input.target_reached() || input.checkpoint().block_number > 0 {
+            return Poll::Ready(Ok(()))
+        }
+
+        loop {
+            self.send_requests();
+            if self.stale || self.buffer.len as u64 >= self.commit_threshold {
+                return Poll::Ready(Ok(()))
+            }
+
+            let Some((root, request, response)) = ready!(self.inflight.poll_next_unpin(cx)) else {
+                // nothing is in flight, the responses have to be processed first
+                return Poll::Ready(Ok(()))
+            };
+            match response {
+                Ok(response) => self.on_response(root, response),
+                Err(err) => {
+                    self.pending.push_front(request);
+                    if !matches!(err, SnapSyncStageError::StateUnavailable) {
+                        return Poll::Ready(Err(StageError::Recoverable(Box::new(err))))
+                    }
+                    // responses for an outdated pivot are expected to be empty
+                    self.stale |=
+                        self.progress.as_ref().is_some_and(|progress| progress.state_root == root);
+                }
+            }
+        }
+    }
+
+    /// Execute the stage.
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        // the state is downloaded once, later runs only follow the target
+        if input.checkpoint().block_number > 0 {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        let output = self.execute_inner(provider, input);
+        if output.is_err() {
+            // the progress is not committed, so the in-memory state is outdated
+            self.reset();
+        }
+        output
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &Provider,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let progress =
+            provider.get_stage_checkpoint_progress(StageId::SnapSync)?.unwrap_or_default();
+        if !progress.is_empty() {
+            let (progress, _) = SnapSyncCheckpoint::from_compact(&progress, progress.len());
+            if progress.phase == SnapSyncPhase::Done && input.unwind_to < progress.pivot {
+                return Err(StageError::Fatal(Box::new(SnapSyncStageError::UnwindBelowPivot {
+                    pivot: progress.pivot,
+                })))
+            }
+        }
+
+        self.reset();
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// Compares a fetched trie node with the local node at the same path.
+///
+/// Children of matching branch and extension nodes are compared separately, everything else is
+/// downloaded again.
+fn diff_trie_node(
+    path: &Nibbles,
+    node: &Bytes,
+    local: Option<&Bytes>,
+    missing_nodes: &mut Vec<(Nibbles, B256)>,
+    pending: &mut VecDeque<SnapRequest>,
+) {
+    let local = local.and_then(|local| TrieNode::decode(&mut &local[..]).ok());
+    let mut refetch = |path: &Nibbles| {
+        let (start, limit) = prefix_range(path);
+        pending.push_back(SnapRequest::Accounts { start, limit, heal: true });
+    };
+
+    match (TrieNode::decode(&mut &node[..]), local) {
+        (Ok(TrieNode::Branch(remote)), Some(TrieNode::Branch(local)))
+            if remote.state_mask == local.state_mask =>
+        {
+            let children = CHILD_INDEX_RANGE.filter(|index| remote.state_mask.is_bit_set(*index));
+            for ((index, remote), local) in children.zip(&remote.stack).zip(&local.stack) {
+                if remote == local {
+                    continue
+                }
+                let mut child_path = path.clone();
+                child_path.push(index);
+                match (remote.as_hash(), local.as_hash()) {
+                    (Some(hash), Some(_)) => missing_nodes.push((child_path, hash)),
+                    _ => refetch(&child_path),
+                }
+            }
+        }
+        (Ok(TrieNode::Extension(remote)), Some(TrieNode::Extension(local)))
+            if remote.key == local.key =>
+        {
+            match (remote.child.as_hash(), local.child.as_hash()) {
+                (Some(hash), Some(_)) => missing_nodes.push((path.join(&remote.key), hash)),
+                _ => refetch(path),
+            }
+        }
+        _ => refetch(path),
+    }
+}
+
+/// Writes the downloaded state to the hashed state tables.
+///
+/// The downloaded account ranges replace all local accounts in the range. If the prefix sets are
+/// given, the changed accounts are added to them, and the storages and bytecodes of the accounts
+/// that are missing locally are returned as requests.
+fn write_state<Provider>(
+    provider: &Provider,
+    buffer: StateBuffer,
+    mut prefix_sets: Option<&mut TriePrefixSetsMut>,
+) -> Result<Vec<SnapRequest>, StageError>
+where
+    Provider: DBProvider<Tx: DbTxMut>,
+{
+    let tx = provider.tx_ref();
+    let mut accounts_cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+    let mut missing_storages = Vec::new();
+    let mut missing_codes = HashSet::default();
+
+    for chunk in buffer.accounts {
+        let mut removed = Vec::new();
+        let mut walker = accounts_cursor.walk_range(chunk.start..=chunk.end)?;
+        while let Some((hashed_address, _)) = walker.next().transpose()? {
+            walker.delete_current()?;
+            removed.push(hashed_address);
+        }
+
+        let accounts: HashSet<_> = chunk.accounts.iter().map(|(key, _)| *key).collect();
+        for (hashed_address, account) in &chunk.accounts {
+            accounts_cursor.upsert(*hashed_address, snap_account_to_account(account))?;
+        }
+
+        let Some(prefix_sets) = prefix_sets.as_deref_mut() else { continue };
+        for hashed_address in removed {
+            prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            if !accounts.contains(&hashed_address) {
+                prefix_sets.destroyed_accounts.insert(hashed_address);
+                wipe_storage(tx, hashed_address)?;
+            }
+        }
+        for (hashed_address, account) in chunk.accounts {
+            prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+
+            let storage_root = StorageRoot::from_tx_hashed(tx, hashed_address)
+                .root()
+                .map_err(|err| StageError::Fatal(Box::new(err)))?;
+            if storage_root != account.storage_root {
+                wipe_storage(tx, hashed_address)?;
+                if account.storage_root != EMPTY_ROOT_HASH {
+                    missing_storages.push((hashed_address, account.storage_root));
+                }
+            }
+            if account.code_hash != KECCAK_EMPTY &&
+                tx.get::<tables::Bytecodes>(account.code_hash)?.is_none()
+            {
+                missing_codes.insert(account.code_hash);
+            }
+        }
+    }
+
+    let mut storages_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    for (hashed_address, slots) in buffer.storages {
+        // the downloaded storages are always complete
+        wipe_storage(tx, hashed_address)?;
+        for (key, value) in slots {
+            storages_cursor.upsert(hashed_address, StorageEntry { key, value })?;
+        }
+        if let Some(prefix_sets) = prefix_sets.as_deref_mut() {
+            // the storage trie was wiped, so it's computed from scratch
+            prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            prefix_sets.storage_prefix_sets.insert(hashed_address, PrefixSetMut::all());
+        }
+    }
+
+    let mut bytecodes_cursor = tx.cursor_write::<tables::Bytecodes>()?;
+    for (hash, code) in buffer.codes {
+        bytecodes_cursor.upsert(hash, Bytecode::new_raw(code))?;
+    }
+
+    let missing_codes = missing_codes.into_iter().collect::<Vec<_>>();
+    Ok(missing_storages
+        .chunks(MAX_STORAGE_ACCOUNTS_PER_REQUEST)
+        .map(|storages| SnapRequest::Storages(storages.to_vec()))
+        .chain(
+            missing_codes
+                .chunks(MAX_BYTE_CODES_PER_REQUEST)
+                .map(|codes| SnapRequest::ByteCodes(codes.to_vec())),
+        )
+        .collect())
+}
+
+/// Removes the storage of the account, along with its storage trie.
+fn wipe_storage<TX: DbTxMut + DbTx>(tx: &TX, hashed_address: B256) -> Result<(), StageError> {
+    let mut storages_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    if storages_cursor.seek_exact(hashed_address)?.is_some() {
+        storages_cursor.delete_current_duplicates()?;
+    }
+    let mut storage_trie_cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
+    if storage_trie_cursor.seek_exact(hashed_address)?.is_some() {
+        storage_trie_cursor.delete_current_duplicates()?;
+    }
+    Ok(())
+}
+
+/// Moves the checkpoints of the stages that process the state to the pivot, so they continue
+/// from there.
+fn finish<Provider>(
+    provider: &Provider,
+    progress: &mut SnapSyncCheckpoint,
+) -> Result<(), StageError>
+where
+    Provider: DBProvider + BlockReader + StageCheckpointWriter + PruneCheckpointWriter,
+{
+    let pivot = progress.pivot;
+    info!(target: "sync::stages::snap_sync", pivot, "Finished snap sync");
+
+    for stage_id in [
+        StageId::SenderRecovery,
+        StageId::Execution,
+        StageId::PruneSenderRecovery,
+        StageId::MerkleUnwind,
+        StageId::AccountHashing,
+        StageId::StorageHashing,
+        StageId::MerkleExecute,
+        StageId::IndexStorageHistory,
+        StageId::IndexAccountHistory,
+    ] {
+        provider.save_stage_checkpoint(stage_id, StageCheckpoint::new(pivot))?;
+    }
+
+    // everything before the pivot is missing, as if it was pruned
+    let tx_number = provider.block_body_indices(pivot)?.map(|indices| indices.last_tx_num());
+    let prune_modes = provider.prune_modes_ref();
+    for (segment, prune_mode) in [
+        (PruneSegment::Receipts, prune_modes.receipts),
+        (PruneSegment::SenderRecovery, prune_modes.sender_recovery),
+        (PruneSegment::AccountHistory, prune_modes.account_history),
+        (PruneSegment::StorageHistory, prune_modes.storage_history),
+    ] {
+        provider.save_prune_checkpoint(
+            segment,
+            PruneCheckpoint {
+                block_number: Some(pivot),
+                tx_number,
+                prune_mode: prune_mode.unwrap_or(PruneMode::Before(pivot + 1)),
+            },
+        )?;
+    }
+
+    progress.phase = SnapSyncPhase::Done;
+    progress.account_ranges.clear();
+    progress.merkle = None;
+    Ok(())
+}
+
+/// Saves the progress of the stage.
+fn save_progress(
+    provider: &impl StageCheckpointWriter,
+    progress: &SnapSyncCheckpoint,
+) -> Result<(), StageError> {
+    let mut buf = Vec::new();
+    progress.to_compact(&mut buf);
+    Ok(provider.save_stage_checkpoint_progress(StageId::SnapSync, buf)?)
+}
+
+/// Returns the state root of the pivot block.
+fn pivot_state_root(provider: &impl BlockReader, pivot: u64) -> Result<B256, StageError> {
+    Ok(provider
+        .header_by_number(pivot)?
+        .ok_or_else(|| ProviderError::HeaderNotFound(pivot.into()))?
+        .state_root)
+}
+
+/// Splits the hashed key space into the given number of account ranges.
+fn account_partitions(count: usize) -> Vec<(B256, B256)> {
+    let step = U256::MAX / U256::from(count);
+    (0..count)
+        .map(|index| {
+            let start = step * U256::from(index);
+            let last = if index + 1 == count {
+                U256::MAX
+            } else {
+                step * U256::from(index + 1) - U256::from(1)
+            };
+            (B256::from(start), B256::from(last))
+        })
+        .collect()
+}
+
+/// Returns the first and the last key with the given prefix.
+fn prefix_range(path: &Nibbles) -> (B256, B256) {
+    let mut last = path.clone();
+    while last.len() < 64 {
+        last.push(0xf);
+    }
+    (padded_key(path), B256::from_slice(&last.pack()))
+}
+
+/// Returns the first key with the given prefix.
+fn padded_key(path: &Nibbles) -> B256 {
+    let mut key = B256::ZERO;
+    let packed = path.pack();
+    key[..packed.len()].copy_from_slice(&packed);
+    key
+}
+
+/// Returns the key after the given one.
+fn next_key(key: B256) -> Option<B256> {
+    U256::from_be_bytes(key.0).checked_add(U256::from(1)).map(B256::from)
+}
+
+/// Encodes the path of a trie node in compact encoding, as used by `GetTrieNodes` requests.
+fn encode_compact_path(path: &Nibbles) -> Bytes {
+    Bytes::copy_from_slice(&path.encode_path_leaf(false))
+}
+
+fn snap_account_to_account(account: &SnapAccount) -> Account {
+    Account {
+        nonce: account.nonce,
+        balance: account.balance,
+        bytecode_hash: (account.code_hash != KECCAK_EMPTY).then_some(account.code_hash),
+    }
+}
+
+fn snap_account_to_trie_account(account: &SnapAccount) -> TrieAccount {
+    TrieAccount {
+        nonce: account.nonce,
+        balance: account.balance,
+        storage_root: account.storage_root,
+        code_hash: account.code_hash,
+    }
+}
+
+/// Errors of the [`SnapSyncStage`].
+#[derive(Debug, thiserror::Error)]
+enum SnapSyncStageError {
+    /// Peers don't serve the state of the pivot block anymore.
+    #[error("state of the pivot block is unavailable")]
+    StateUnavailable,
+    /// A request failed too many times.
+    #[error("snap request failed: {0}")]
+    Request(RequestError),
+    /// The node doesn't prune receipts.
+    #[error("snap sync requires receipts pruning, since the receipts before the pivot block are never downloaded")]
+    ReceiptsNotPruned,
+    /// The pipeline tried to unwind below the pivot block, whose previous state was never
+    /// downloaded.
+    #[error("cannot unwind below the snap sync pivot block {pivot}")]
+    UnwindBelowPivot {
+        /// The pivot block.
+        pivot: u64,
+    },
+}
+
+/// A request for a part of the state.
+#[derive(Debug, Clone)]
+enum SnapRequest {
+    /// Accounts from the start to the limit key. Outside of healing, the storages and bytecodes
+    /// of the accounts are downloaded as well.
+    Accounts { start: B256, limit: B256, heal: bool },
+    /// The complete storages of accounts, by hashed address and storage root.
+    Storages(Vec<(B256, B256)>),
+    /// Bytecodes by hash.
+    ByteCodes(Vec<B256>),
+    /// Account trie nodes, by path and hash.
+    TrieNodes(Vec<(Nibbles, B256)>),
+}
+
+/// The verified response to a [`SnapRequest`].
+#[derive(Debug)]
+enum SnapResponse {
+    Accounts(AccountChunk),
+    Storages(Vec<(B256, Vec<(B256, U256)>)>),
+    ByteCodes(Vec<(B256, Bytes)>),
+    TrieNodes(Vec<(Nibbles, Bytes)>),
+}
+
+/// A downloaded chunk of an account range.
+#[derive(Debug)]
+struct AccountChunk {
+    /// The first key of the chunk.
+    start: B256,
+    /// The last key of the chunk.
+    end: B256,
+    /// The last key of the range.
+    limit: B256,
+    /// The start of the next chunk of the range, if the range is not complete.
+    next: Option<B256>,
+    /// Whether the chunk heals the trie.
+    heal: bool,
+    /// The accounts of the chunk.
+    accounts: Vec<(B256, SnapAccount)>,
+    /// The storages of the accounts. Empty when healing.
+    storages: Vec<(B256, Vec<(B256, U256)>)>,
+    /// The bytecodes of the accounts. Empty when healing.
+    codes: Vec<(B256, Bytes)>,
+}
+
+/// Downloaded state that is yet to be written to the database.
+#[derive(Debug, Default)]
+struct StateBuffer {
+    /// Account ranges that replace the local accounts in their range.
+    accounts: Vec<AccountChunk>,
+    /// Storages that replace the local storages of the accounts.
+    storages: Vec<(B256, Vec<(B256, U256)>)>,
+    /// Bytecodes by hash.
+    codes: Vec<(B256, Bytes)>,
+    /// The number of buffered entries.
+    len: usize,
+}
+
+impl StateBuffer {
+    fn extend_storages(&mut self, storages: Vec<(B256, Vec<(B256, U256)>)>) {
+        self.len += storages.iter().map(|(_, slots)| slots.len()).sum::<usize>();
+        self.storages.extend(storages);
+    }
+
+    fn extend_codes(&mut self, codes: Vec<(B256, Bytes)>) {
+        self.len += codes.len();
+        self.codes.extend(codes);
+    }
+}
+
+/// A request in flight, resolving to the state root it was sent for, the request and its
+/// response.
+type SnapFuture = Pin<
+    Box<
+        dyn Future<Output = (B256, SnapRequest, Result<SnapResponse, SnapSyncStageError>)>
+            + Send
+            + Sync,
+    >,
+>;
+
+/// Downloads and verifies the state of a state root.
+#[derive(Debug)]
+struct SnapFetcher<C> {
+    client: C,
+    root: B256,
+}
+
+impl<C: SnapClient> SnapFetcher<C> {
+    async fn fetch(&self, request: &SnapRequest) -> Result<SnapResponse, SnapSyncStageError> {
+        match request {
+            SnapRequest::Accounts { start, limit, heal } => {
+                let mut chunk = self.fetch_accounts(*start, *limit).await?;
+                chunk.heal = *heal;
+                if *heal {
+                    return Ok(SnapResponse::Accounts(chunk))
+                }
+
+                // storages and bytecodes are written along with their accounts, so a range is
+                // never persisted incomplete
+                let storages = chunk
+                    .accounts
+                    .iter()
+                    .filter(|(_, account)| account.storage_root != EMPTY_ROOT_HASH)
+                    .map(|(hashed_address, account)| (*hashed_address, account.storage_root))
+                    .collect();
+                let storages = self.fetch_storages(storages).await?;
+                let codes = chunk
+                    .accounts
+                    .iter()
+                    .map(|(_, account)| account.code_hash)
+                    .filter(|code_hash| *code_hash != KECCAK_EMPTY)
+                    .collect::<HashSet<_>>();
+                chunk.codes = self.fetch_byte_codes(codes.into_iter().collect()).await?;
+                chunk.storages = storages;
+                Ok(SnapResponse::Accounts(chunk))
+            }
+            SnapRequest::Storages(accounts) => {
+                self.fetch_storages(accounts.clone()).await.map(SnapResponse::Storages)
+            }
+            SnapRequest::ByteCodes(hashes) => {
+                self.fetch_byte_codes(hashes.clone()).await.map(SnapResponse::ByteCodes)
+            }
+            SnapRequest::TrieNodes(nodes) => {
+                self.fetch_trie_nodes(nodes).await.map(SnapResponse::TrieNodes)
+            }
+        }
+    }
+
+    /// Downloads the next chunk of an account range.
+    async fn fetch_accounts(
+        &self,
+        start: B256,
+        limit: B256,
+    ) -> Result<AccountChunk, SnapSyncStageError> {
+        let mut chunk = AccountChunk {
+            start,
+            end: limit,
+            limit,
+            next: None,
+            heal: false,
+            accounts: Vec::new(),
+            storages: Vec::new(),
+            codes: Vec::new(),
+        };
+        if self.root == EMPTY_ROOT_HASH {
+            return Ok(chunk)
+        }
+
+        let mut attempts = Attempts::default();
+        loop {
+            let request = GetAccountRange {
+                request_id: 0,
+                root_hash: self.root,
+                starting_hash: start,
+                limit_hash: limit,
+                response_bytes: RANGE_RESPONSE_BYTES,
+            };
+            let (peer_id, response) = match self.client.get_account_range(request).await {
+                Ok(response) => response.split(),
+                Err(err) => {
+                    attempts.on_error(err).await?;
+                    continue
+                }
+            };
+            if response.accounts.is_empty() && response.proof.is_empty() {
+                attempts.on_empty()?;
+                continue
+            }
+
+            let leaves = response
+                .accounts
+                .iter()
+                .map(|data| {
+                    (data.hash, alloy_rlp::encode(snap_account_to_trie_account(&data.account)))
+                })
+                .collect::<Vec<_>>();
+            let has_more = match verify_range_proof(self.root, start, &leaves, &response.proof) {
+                Ok(has_more) => has_more,
+                Err(err) => {
+                    debug!(target: "sync::stages::snap_sync", %peer_id, %err, "Invalid account range");
+                    self.client.report_bad_message(peer_id);
+                    attempts.on_bad_response()?;
+                    continue
+                }
+            };
+
+            // the last account may be past the limit, to prove that the range is complete
+            let last = response.accounts.last().map(|data| data.hash);
+            if let Some(last) = last.filter(|last| has_more && *last < limit) {
+                chunk.end = last;
+                chunk.next = next_key(last);
+            }
+            chunk.accounts = response
+                .accounts
+                .into_iter()
+                .filter(|data| data.hash <= limit)
+                .map(|data| (data.hash, data.account))
+                .collect();
+            return Ok(chunk)
+        }
+    }
+
+    /// Downloads the complete storages of the given accounts.
+    async fn fetch_storages(
+        &self,
+        accounts: Vec<(B256, B256)>,
+    ) -> Result<Vec<(B256, Vec<(B256, U256)>)>, SnapSyncStageError> {
+        let mut storages = Vec::with_capacity(accounts.len());
+        let mut remaining = &accounts[..];
+        let mut attempts = Attempts::default();
+        while !remaining.is_empty() {
+            let batch = &remaining[..remaining.len().min(MAX_STORAGE_ACCOUNTS_PER_REQUEST)];
+            let request = GetStorageRanges {
+                request_id: 0,
+                root_hash: self.root,
+                account_hashes: batch.iter().map(|(hashed_address, _)| *hashed_address).collect(),
+                starting_hash: Bytes::new(),
+                limit_hash: Bytes::new(),
+                response_bytes: RANGE_RESPONSE_BYTES,
+            };
+            let (peer_id, response) = match self.client.get_storage_ranges(request).await {
+                Ok(response) => response.split(),
+                Err(err) => {
+                    attempts.on_error(err).await?;
+                    continue
+                }
+            };
+            if response.slots.is_empty() {
+                attempts.on_empty()?;
+                continue
+            }
+
+            if response.slots.len() > batch.len() {
+                self.client.report_bad_message(peer_id);
+                attempts.on_bad_response()?;
+                continue
+            }
+
+            // all storages are complete, except for the last one if there is a proof
+            let count = response.slots.len();
+            let mut verified = Vec::with_capacity(count);
+            let mut partial = None;
+            let mut valid = true;
+            for (index, ((hashed_address, storage_root), slots)) in
+                batch.iter().zip(response.slots).enumerate()
+            {
+                let proof = if index + 1 == count { &response.proof[..] } else { &[] };
+                match verify_storage_range(*storage_root, B256::ZERO, slots, proof) {
+                    Some((slots, true)) => partial = Some((*hashed_address, *storage_root, slots)),
+                    Some((slots, false)) => verified.push((*hashed_address, slots)),
+                    None => {
+                        valid = false;
+                        break
+                    }
+                }
+            }
+            if !valid {
+                debug!(target: "sync::stages::snap_sync", %peer_id, "Invalid storage ranges");
+                self.client.report_bad_message(peer_id);
+                attempts.on_bad_response()?;
+                continue
+            }
+
+            storages.extend(verified);
+            if let Some((hashed_address, storage_root, slots)) = partial {
+                let slots = self.fetch_large_storage(hashed_address, storage_root, slots).await?;
+                storages.push((hashed_address, slots));
+            }
+            remaining = &remaining[count..];
+        }
+        Ok(storages)
+    }
+
+    /// Downloads the rest of a storage that doesn't fit into a single response.
+    async fn fetch_large_storage(
+        &self,
+        hashed_address: B256,
+        storage_root: B256,
+        mut slots: Vec<(B256, U256)>,
+    ) -> Result<Vec<(B256, U256)>, SnapSyncStageError> {
+        let mut attempts = Attempts::default();
+        while let Some(start) = slots.last().and_then(|(key, _)| next_key(*key)) {
+            let request = GetStorageRanges {
+                request_id: 0,
+                root_hash: self.root,
+                account_hashes: vec![hashed_address],
+                starting_hash: Bytes::copy_from_slice(start.as_slice()),
+                limit_hash: Bytes::copy_from_slice(B256::repeat_byte(0xff).as_slice()),
+                response_bytes: RANGE_RESPONSE_BYTES,
+            };
+            let (peer_id, mut response) = match self.client.get_storage_ranges(request).await {
+                Ok(response) => response.split(),
+                Err(err) => {
+                    attempts.on_error(err).await?;
+                    continue
+                }
+            };
+            let Some(chunk) = response.slots.pop().filter(|_| response.slots.is_empty()) else {
+                attempts.on_empty()?;
+                continue
+            };
+
+            match verify_storage_range(storage_root, start, chunk, &response.proof) {
+                Some((chunk, has_more)) => {
+                    slots.extend(chunk);
+                    if !has_more {
+                        break
+                    }
+                }
+                None => {
+                    debug!(target: "sync::stages::snap_sync", %peer_id, "Invalid storage range");
+                    self.client.report_bad_message(peer_id);
+                    attempts.on_bad_response()?;
+                }
+            }
+        }
+        Ok(slots)
+    }
+
+    /// Downloads the bytecodes with the given hashes.
+    async fn fetch_byte_codes(
+        &self,
+        hashes: Vec<B256>,
+    ) -> Result<Vec<(B256, Bytes)>, SnapSyncStageError> {
+        let mut codes = Vec::with_capacity(hashes.len());
+        let mut remaining: HashSet<_> = hashes.into_iter().collect();
+        let mut attempts = Attempts::default();
+        while !remaining.is_empty() {
+            let request = GetByteCodes {
+                request_id: 0,
+                hashes: remaining.iter().take(MAX_BYTE_CODES_PER_REQUEST).copied().collect(),
+                response_bytes: BATCH_RESPONSE_BYTES,
+            };
+            let (peer_id, response) = match self.client.get_byte_codes(request).await {
+                Ok(response) => response.split(),
+                Err(err) => {
+                    attempts.on_error(err).await?;
+                    continue
+                }
+            };
+            if response.codes.is_empty() {
+                attempts.on_empty()?;
+                continue
+            }
+
+            for code in response.codes {
+                let hash = keccak256(&code);
+                if remaining.remove(&hash) {
+                    codes.push((hash, code));
+                } else {
+                    debug!(target: "sync::stages::snap_sync", %peer_id, %hash, "Unrequested bytecode");
+                    self.client.report_bad_message(peer_id);
+                    attempts.on_bad_response()?;
+                    break
+                }
+            }
+        }
+        Ok(codes)
+    }
+
+    /// Downloads the account trie nodes with the given paths and hashes.
+    async fn fetch_trie_nodes(
+        &self,
+        nodes: &[(Nibbles, B256)],
+    ) -> Result<Vec<(Nibbles, Bytes)>, SnapSyncStageError> {
+        let mut fetched = Vec::with_capacity(nodes.len());
+        let mut attempts = Attempts::default();
+        while fetched.len() < nodes.len() {
+            let remaining = &nodes[fetched.len()..];
+            let request = GetTrieNodes {
+                request_id: 0,
+                root_hash: self.root,
+                paths: remaining.iter().map(|(path, _)| vec![encode_compact_path(path)]).collect(),
+                response_bytes: BATCH_RESPONSE_BYTES,
+            };
+            let (peer_id, response) = match self.client.get_trie_nodes(request).await {
+                Ok(response) => response.split(),
+                Err(err) => {
+                    attempts.on_error(err).await?;
+                    continue
+                }
+            };
+            if response.nodes.is_empty() {
+                attempts.on_empty()?;
+                continue
+            }
+
+            // the nodes are returned in the order of the request
+            for ((path, hash), node) in remaining.iter().zip(response.nodes) {
+                if keccak256(&node) != *hash {
+                    debug!(target: "sync::stages::snap_sync", %peer_id, ?path, "Invalid trie node");
+                    self.client.report_bad_message(peer_id);
+                    attempts.on_bad_response()?;
+                    break
+                }
+                fetched.push((path.clone(), node));
+            }
+        }
+        Ok(fetched)
+    }
+}
+
+/// Verifies a downloaded storage range, and decodes its slots.
+///
+/// Returns the slots and whether the storage has more slots, or `None` if the range is invalid.
+fn verify_storage_range(
+    storage_root: B256,
+    start: B256,
+    slots: Vec<reth_eth_wire_types::StorageData>,
+    proof: &[Bytes],
+) -> Option<(Vec<(B256, U256)>, bool)> {
+    let leaves = slots.iter().map(|slot| (slot.hash, slot.data.to_vec())).collect::<Vec<_>>();
+    let has_more = verify_range_proof(storage_root, start, &leaves, proof).ok()?;
+    let slots = slots
+        .into_iter()
+        .map(|slot| Some((slot.hash, U256::decode(&mut &slot.data[..]).ok()?)))
+        .collect::<Option<_>>()?;
+    Some((slots, has_more))
+}
+
+/// Tracks the attempts of a request.
+#[derive(Debug, Default)]
+struct Attempts {
+    failures: usize,
+    empty_responses: usize,
+}
+
+impl Attempts {
+    /// Handles a failed request.
+    async fn on_error(&mut self, err: RequestError) -> Result<(), SnapSyncStageError> {
+        if matches!(err, RequestError::UnsupportedCapability) {
+            // no peer supports `snap` yet, so this is not a failure of the request
+            tokio::time::sleep(NO_PEERS_BACKOFF).await;
+            return Ok(())
+        }
+        self.on_failure(err)
+    }
+
+    /// Handles a response that failed verification.
+    fn on_bad_response(&mut self) -> Result<(), SnapSyncStageError> {
+        self.on_failure(RequestError::BadResponse)
+    }
+
+    fn on_failure(&mut self, err: RequestError) -> Result<(), SnapSyncStageError> {
+        self.failures += 1;
+        if self.failures >= MAX_REQUEST_FAILURES {
+            return Err(SnapSyncStageError::Request(err))
+        }
+        Ok(())
+    }
+
+    /// Handles an empty response, which peers send if they don't have the requested state.
+    fn on_empty(&mut self) -> Result<(), SnapSyncStageError> {
+        self.empty_responses += 1;
+        if self.empty_responses >= MAX_EMPTY_RESPONSES {
+            return Err(SnapSyncStageError::StateUnavailable)
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestSnapClient, TestStageDB};
+    use alloy_primitives::Address;
+    use reth_db::{test_utils::TempDatabase, Database, DatabaseEnv};
+    use reth_primitives::SealedHeader;
+    use reth_provider::{
+        providers::is_snap_synced, test_utils::MockNodeTypesWithDB, DatabaseProvider,
+        PruneCheckpointReader,
+    };
+    use reth_prune_types::PruneModes;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+    use std::future::poll_fn;
+
+    type Provider =
+        DatabaseProvider<<TempDatabase<DatabaseEnv> as Database>::TXMut, MockNodeTypesWithDB>;
+
+    const RECEIPTS_PRUNE_MODE: PruneMode = PruneMode::Distance(64);
+
+    /// Returns a state of accounts, contracts and storages. If `changed` is set, every account is
+    /// changed, some are removed and some are added.
+    fn test_state(changed: bool) -> TestStageDB {
+        let db = TestStageDB::default();
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]));
+        let code_hash = code.hash_slow();
+        db.commit(|tx| Ok(tx.put::<tables::Bytecodes>(code_hash, code)?)).unwrap();
+
+        let offset = changed as u64;
+        let count = if changed { 72 } else { 64 };
+        let numbers = (0..count).filter(|number| !changed || number % 7 != 0);
+        db.insert_accounts_and_storages(numbers.map(|number| {
+            let account = Account {
+                nonce: number,
+                balance: U256::from(number + offset),
+                bytecode_hash: (number % 4 == 0).then_some(code_hash),
+            };
+            let storage = (1..=number % 3 * 4).map(move |slot| StorageEntry {
+                key: B256::with_last_byte(slot as u8),
+                value: U256::from(slot + offset),
+            });
+            (Address::with_last_byte(number as u8), (account, storage))
+        }))
+        .unwrap();
+        db
+    }
+
+    /// Returns a database with blocks up to block 110, whose pivot blocks have the given state
+    /// roots.
+    fn test_db(state_roots: &[(u64, B256)]) -> TestStageDB {
+        let mut db = TestStageDB::default();
+        db.factory = db.factory.with_prune_modes(PruneModes {
+            receipts: Some(RECEIPTS_PRUNE_MODE),
+            ..PruneModes::none()
+        });
+
+        let mut rng = generators::rng();
+        let mut blocks = random_block_range(
+            &mut rng,
+            0..=110,
+            BlockRangeParams { tx_count: 0..3, ..Default::default() },
+        );
+        for (number, state_root) in state_roots {
+            let block = &mut blocks[*number as usize];
+            let mut header = block.header.clone().unseal();
+            header.state_root = *state_root;
+            let (header, seal) = header.seal_slow().into_parts();
+            block.header = SealedHeader::new(header, seal);
+        }
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+        db
+    }
+
+    /// Runs the stage up to the given number of times, and returns whether it's done.
+    async fn run(
+        stage: &mut SnapSyncStage<TestSnapClient>,
+        db: &TestStageDB,
+        target: u64,
+        runs: usize,
+    ) -> bool {
+        let input = ExecInput { target: Some(target), checkpoint: None };
+        for _ in 0..runs {
+            poll_fn(|cx| Stage::<Provider>::poll_execute_ready(stage, cx, input)).await.unwrap();
+            let provider = db.factory.provider_rw().unwrap();
+            let output = Stage::<Provider>::execute(stage, &provider, input).unwrap();
+            provider.commit().unwrap();
+            if output.done {
+                return true
+            }
+        }
+        false
+    }
+
+    /// Asserts that the state matches the expected one, and that the stages processing the
+    /// state continue from the pivot.
+    fn assert_synced(db: &TestStageDB, expected: &TestStageDB, pivot: u64, state_root: B256) {
+        assert_eq!(
+            db.table::<tables::HashedAccounts>().unwrap(),
+            expected.table::<tables::HashedAccounts>().unwrap()
+        );
+        assert_eq!(
+            db.table::<tables::HashedStorages>().unwrap(),
+            expected.table::<tables::HashedStorages>().unwrap()
+        );
+        assert_eq!(
+            db.table::<tables::Bytecodes>().unwrap(),
+            expected.table::<tables::Bytecodes>().unwrap()
+        );
+
+        let provider = db.factory.provider().unwrap();
+        assert_eq!(StateRoot::from_tx(provider.tx_ref()).root().unwrap(), state_root);
+        assert!(is_snap_synced(provider.tx_ref()).unwrap());
+
+        let progress = provider.get_stage_checkpoint_progress(StageId::SnapSync).unwrap().unwrap();
+        let (progress, _) = SnapSyncCheckpoint::from_compact(&progress, progress.len());
+        assert_eq!(progress.phase, SnapSyncPhase::Done);
+        assert_eq!(progress.pivot, pivot);
+
+        for stage_id in [
+            StageId::SenderRecovery,
+            StageId::Execution,
+            StageId::PruneSenderRecovery,
+            StageId::MerkleUnwind,
+            StageId::AccountHashing,
+            StageId::StorageHashing,
+            StageId::MerkleExecute,
+            StageId::IndexStorageHistory,
+            StageId::IndexAccountHistory,
+        ] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage_id).unwrap(),
+                Some(StageCheckpoint::new(pivot)),
+                "{stage_id}"
+            );
+        }
+
+        let tx_number =
+            provider.block_body_indices(pivot).unwrap().map(|indices| indices.last_tx_num());
+        assert!(tx_number.is_some());
+        for (segment, prune_mode) in [
+            (PruneSegment::Receipts, RECEIPTS_PRUNE_MODE),
+            (PruneSegment::SenderRecovery, PruneMode::Before(pivot + 1)),
+            (PruneSegment::AccountHistory, PruneMode::Before(pivot + 1)),
+            (PruneSegment::StorageHistory, PruneMode::Before(pivot + 1)),
+        ] {
+            assert_eq!(
+                provider.get_prune_checkpoint(segment).unwrap(),
+                Some(PruneCheckpoint { block_number: Some(pivot), tx_number, prune_mode }),
+                "{segment}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn sync_to_pivot_state() {
+        let client = TestSnapClient::new(8);
+        let state_root = client.serve(test_state(false));
+        let db = test_db(&[(36, state_root)]);
+
+        let config = SnapSyncConfig { commit_threshold: 16, concurrency: 4 };
+        let mut stage = SnapSyncStage::new(client, config);
+        assert!(run(&mut stage, &db, 100, 1000).await);
+        assert_synced(&db, &test_state(false), 36, state_root);
+
+        // the state is only downloaded once
+        let input = ExecInput { target: Some(110), checkpoint: Some(StageCheckpoint::new(100)) };
+        let provider = db.factory.provider_rw().unwrap();
+        let output = Stage::<Provider>::execute(&mut stage, &provider, input).unwrap();
+        assert_eq!(output, ExecOutput::done(StageCheckpoint::new(110)));
+    }
+
+    #[tokio::test]
+    async fn heal_after_pivot_move() {
+        let client = TestSnapClient::new(2);
+        let old_root = client.serve(test_state(false));
+        let new_root = client.serve(test_state(true));
+        let db = test_db(&[(36, old_root), (46, new_root)]);
+
+        let config = SnapSyncConfig { commit_threshold: 4, concurrency: 2 };
+        let mut stage = SnapSyncStage::new(client.clone(), config);
+        assert!(!run(&mut stage, &db, 100, 3).await);
+        assert!(!db.table_is_empty::<tables::HashedAccounts>().unwrap());
+
+        // the accounts downloaded so far are outdated once the stage moves to the newer pivot
+        client.forget(old_root);
+        assert!(run(&mut stage, &db, 110, 1000).await);
+        assert_synced(&db, &test_state(true), 46, new_root);
+    }
+
+    #[test]
+    fn account_partitions_cover_key_space() {
+        for count in [1, 3, 16] {
+            let partitions = account_partitions(count);
+            assert_eq!(partitions.len(), count);
+            assert_eq!(partitions[0].0, B256::ZERO);
+            assert_eq!(partitions[count - 1].1, B256::repeat_byte(0xff));
+            for window in partitions.windows(2) {
+                assert_eq!(next_key(window[0].1), Some(window[1].0));
+            }
+        }
+    }
+
+    #[test]
+    fn prefix_ranges() {
+        let (start, last) = prefix_range(&Nibbles::from_nibbles([0xa, 0xb, 0xc]));
+        let mut expected = B256::ZERO;
+        expected[..2].copy_from_slice(&[0xab, 0xc0]);
+        assert_eq!(start, expected);
+        let mut expected = B256::repeat_byte(0xff);
+        expected[..2].copy_from_slice(&[0xab, 0xcf]);
+        assert_eq!(last, expected);
+
+        assert_eq!(prefix_range(&Nibbles::default()), (B256::ZERO, B256::repeat_byte(0xff)));
+    }
+
+    #[test]
+    fn compact_paths() {
+        assert_eq!(encode_compact_path(&Nibbles::default()), Bytes::from_static(&[0x00]));
+        assert_eq!(
+            encode_compact_path(&Nibbles::from_nibbles([0x1, 0x2, 0x3])),
+            Bytes::from_static(&[0x11, 0x23])
+        );
+        assert_eq!(
+            encode_compact_path(&Nibbles::from_nibbles([0x1, 0x2])),
+            Bytes::from_static(&[0x00, 0x12])
+        );
+    }
+}
@@ -1,26 +1,86 @@
 //! Utils for `stages`.
 use alloy_primitives::BlockNumber;
 use reth_config::config::EtlConfig;
-use reth_db::BlockNumberList;
+use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
     cursor::{DbCursorRO, DbCursorRW},
-    models::sharded_key::NUM_OF_INDICES_IN_SHARD,
+    models::{sharded_key::NUM_OF_INDICES_IN_SHARD, AccountBeforeTx, BlockNumberAddress},
     table::{Decompress, Table},
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
 use reth_etl::Collector;
-use reth_provider::DBProvider;
+use reth_primitives::{StaticFileSegment, StorageEntry};
+use reth_provider::{
+    providers::StaticFileProvider, ChangeSetReader, DBProvider, ProviderResult,
+    StaticFileProviderFactory, StorageChangeSetReader,
+};
 use reth_stages_api::StageError;
-use std::{collections::HashMap, hash::Hash, ops::RangeBounds};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{RangeBounds, RangeInclusive},
+};
 use tracing::info;
 
 /// Number of blocks before pushing indices from cache to [`Collector`]
 const DEFAULT_CACHE_THRESHOLD: u64 = 100_000;
 
+/// A changeset table whose older blocks can be moved to a static file segment.
+pub(crate) trait ChangeSetTable: Table {
+    /// The static file segment that holds the changesets of the table.
+    const SEGMENT: StaticFileSegment;
+
+    /// Returns the table key range of the changesets in the block range.
+    fn key_range(range: RangeInclusive<BlockNumber>) -> impl RangeBounds<Self::Key>;
+
+    /// Returns the changeset of the block from static files, as table entries.
+    fn static_file_changeset(
+        static_file_provider: &StaticFileProvider,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(Self::Key, Self::Value)>>;
+}
+
+impl ChangeSetTable for tables::AccountChangeSets {
+    const SEGMENT: StaticFileSegment = StaticFileSegment::AccountChangeSets;
+
+    fn key_range(range: RangeInclusive<BlockNumber>) -> impl RangeBounds<Self::Key> {
+        range
+    }
+
+    fn static_file_changeset(
+        static_file_provider: &StaticFileProvider,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumber, AccountBeforeTx)>> {
+        Ok(static_file_provider
+            .account_block_changeset(block_number)?
+            .into_iter()
+            .map(|entry| (block_number, entry))
+            .collect())
+    }
+}
+
+impl ChangeSetTable for tables::StorageChangeSets {
+    const SEGMENT: StaticFileSegment = StaticFileSegment::StorageChangeSets;
+
+    fn key_range(range: RangeInclusive<BlockNumber>) -> impl RangeBounds<Self::Key> {
+        BlockNumberAddress::range(range)
+    }
+
+    fn static_file_changeset(
+        static_file_provider: &StaticFileProvider,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        static_file_provider.storage_changeset(block_number)
+    }
+}
+
 /// Collects all history (`H`) indices for a range of changesets (`CS`) and stores them in a
 /// [`Collector`].
 ///
+/// Changesets of blocks that were already moved to static files are read from there, the rest
+/// from the database.
+///
 /// ## Process
 /// The function utilizes a `HashMap` cache with a structure of `PartialKey` (`P`) (Address or
 /// Address.StorageKey) to `BlockNumberList`. When the cache exceeds its capacity, its contents are
@@ -37,14 +97,14 @@ const DEFAULT_CACHE_THRESHOLD: u64 = 100_000;
 /// `(Address1.300, [100,300])`. The entries may be stored across one or more files.
 pub(crate) fn collect_history_indices<Provider, CS, H, P>(
     provider: &Provider,
-    range: impl RangeBounds<CS::Key>,
+    range: RangeInclusive<BlockNumber>,
     sharded_key_factory: impl Fn(P, BlockNumber) -> H::Key,
     partial_key_factory: impl Fn((CS::Key, CS::Value)) -> (u64, P),
     etl_config: &EtlConfig,
 ) -> Result<Collector<H::Key, H::Value>, StageError>
 where
-    Provider: DBProvider,
-    CS: Table,
+    Provider: DBProvider + StaticFileProviderFactory,
+    CS: ChangeSetTable,
     H: Table<Value = BlockNumberList>,
     P: Copy + Eq + Hash,
 {
@@ -68,9 +128,29 @@ where
     let total_changesets = provider.tx_ref().entries::<CS>()?;
     let interval = (total_changesets / 1000).max(1);
 
+    // Changesets up to the highest static file block are read from static files, even if they
+    // were not pruned from the database yet.
+    let static_file_provider = provider.static_file_provider();
+    let database_start = match static_file_provider.get_highest_static_file_block(CS::SEGMENT) {
+        Some(highest) if highest >= *range.start() => highest + 1,
+        _ => *range.start(),
+    };
+    let static_file_entries = (*range.start()..database_start.min(range.end() + 1))
+        .map(|block_number| CS::static_file_changeset(&static_file_provider, block_number))
+        .flat_map(|changeset| match changeset {
+            Ok(changeset) => changeset.into_iter().map(Ok).collect::<Vec<_>>(),
+            Err(err) => vec![Err(StageError::from(err))],
+        });
+    let database_entries = (database_start <= *range.end())
+        .then(|| changeset_cursor.walk_range(CS::key_range(database_start..=*range.end())))
+        .transpose()?
+        .into_iter()
+        .flatten()
+        .map(|entry| entry.map_err(StageError::from));
+
     let mut flush_counter = 0;
     let mut current_block_number = u64::MAX;
-    for (idx, entry) in changeset_cursor.walk_range(range)?.enumerate() {
+    for (idx, entry) in static_file_entries.chain(database_entries).enumerate() {
         let (block_number, key) = partial_key_factory(entry?);
         cache.entry(key).or_default().push(block_number);
 
@@ -12,6 +12,11 @@ pub(crate) use runner::{
     ExecuteStageTestRunner, StageTestRunner, TestRunnerError, UnwindStageTestRunner,
 };
 
+#[cfg(test)]
+mod snap;
+#[cfg(test)]
+pub(crate) use snap::TestSnapClient;
+
 mod test_db;
 pub use test_db::{StorageKind, TestStageDB};
 
@@ -0,0 +1,196 @@
+use super::TestStageDB;
+use alloy_primitives::{
+    map::{HashMap, HashSet},
+    Bytes, B256,
+};
+use reth_db::{tables, Database, DatabaseEnv};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    transaction::DbTx,
+};
+use reth_eth_wire_types::{
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SnapAccount, StorageData, StorageRanges, TrieNodes,
+};
+use reth_network_p2p::{download::DownloadClient, snap::client::SnapFut, SnapClient};
+use reth_network_peers::{PeerId, WithPeerId};
+use reth_trie::{proof::Proof, Nibbles, StateRoot, StorageRoot};
+use reth_trie_db::{DatabaseProof, DatabaseStateRoot, DatabaseStorageRoot};
+use std::sync::{Arc, Mutex};
+
+type Tx = <DatabaseEnv as Database>::TX;
+
+/// A [`SnapClient`] that serves the hashed state of [`TestStageDB`]s by their state root.
+///
+/// Requests for the state of an unknown root are answered with empty responses, like peers do
+/// for the states they don't serve anymore. Storages are always served complete.
+#[derive(Debug, Clone)]
+pub(crate) struct TestSnapClient {
+    states: Arc<Mutex<HashMap<B256, Arc<TestStageDB>>>>,
+    max_accounts: usize,
+}
+
+impl TestSnapClient {
+    /// Creates a client that serves at most `max_accounts` accounts per account range.
+    pub(crate) fn new(max_accounts: usize) -> Self {
+        Self { states: Default::default(), max_accounts }
+    }
+
+    /// Serves the state of the database, and returns its state root.
+    pub(crate) fn serve(&self, db: TestStageDB) -> B256 {
+        let root = db.query(|tx| Ok(StateRoot::from_tx(tx).root().unwrap())).unwrap();
+        self.states.lock().unwrap().insert(root, Arc::new(db));
+        root
+    }
+
+    /// Stops serving the state with the given root.
+    pub(crate) fn forget(&self, root: B256) {
+        self.states.lock().unwrap().remove(&root);
+    }
+
+    /// Answers a request for the state with the given root.
+    fn respond<T: Default + Send + Sync + 'static>(
+        &self,
+        root: B256,
+        f: impl FnOnce(&Tx) -> T,
+    ) -> SnapFut<T> {
+        let db = self.states.lock().unwrap().get(&root).cloned();
+        let response = db.map(|db| db.query(|tx| Ok(f(tx))).unwrap()).unwrap_or_default();
+        Box::pin(std::future::ready(Ok(WithPeerId::new(PeerId::default(), response))))
+    }
+}
+
+impl DownloadClient for TestSnapClient {
+    fn report_bad_message(&self, _peer_id: PeerId) {
+        panic!("the served state is always valid")
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        1
+    }
+}
+
+impl SnapClient for TestSnapClient {
+    fn num_snap_peers(&self) -> usize {
+        1
+    }
+
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange> {
+        let max_accounts = self.max_accounts;
+        self.respond(request.root_hash, |tx| {
+            let mut accounts = Vec::new();
+            let mut cursor = tx.cursor_read::<tables::HashedAccounts>().unwrap();
+            for entry in cursor.walk(Some(request.starting_hash)).unwrap() {
+                let (hash, account) = entry.unwrap();
+                let storage_root = StorageRoot::from_tx_hashed(tx, hash).root().unwrap();
+                accounts.push(AccountData {
+                    hash,
+                    account: SnapAccount {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage_root,
+                        code_hash: account.get_bytecode_hash(),
+                    },
+                });
+                // the last account may be past the limit, to prove the range is complete
+                if hash >= request.limit_hash || accounts.len() >= max_accounts {
+                    break
+                }
+            }
+
+            let targets = std::iter::once(request.starting_hash)
+                .chain(accounts.last().map(|data| data.hash))
+                .map(|key| (key, HashSet::default()))
+                .collect();
+            let proof = Proof::from_tx(tx).multiproof(targets).unwrap();
+            AccountRange {
+                request_id: request.request_id,
+                accounts,
+                proof: proof
+                    .account_subtree
+                    .into_nodes_sorted()
+                    .into_iter()
+                    .map(|(_, node)| node)
+                    .collect(),
+            }
+        })
+    }
+
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges> {
+        self.respond(request.root_hash, |tx| {
+            let mut cursor = tx.cursor_dup_read::<tables::HashedStorages>().unwrap();
+            let slots = request
+                .account_hashes
+                .iter()
+                .map(|hashed_address| {
+                    cursor
+                        .walk_dup(Some(*hashed_address), None)
+                        .unwrap()
+                        .map(|entry| {
+                            let (_, entry) = entry.unwrap();
+                            StorageData {
+                                hash: entry.key,
+                                data: alloy_rlp::encode(entry.value).into(),
+                            }
+                        })
+                        .collect()
+                })
+                .collect();
+            StorageRanges { request_id: request.request_id, slots, proof: Vec::new() }
+        })
+    }
+
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes> {
+        let states = self.states.lock().unwrap().values().cloned().collect::<Vec<_>>();
+        let codes = request
+            .hashes
+            .iter()
+            .filter_map(|hash| {
+                states.iter().find_map(|db| {
+                    db.query(|tx| tx.get::<tables::Bytecodes>(*hash).map_err(Into::into))
+                        .unwrap()
+                        .map(|code| code.original_bytes())
+                })
+            })
+            .collect();
+        Box::pin(std::future::ready(Ok(WithPeerId::new(
+            PeerId::default(),
+            ByteCodes { request_id: request.request_id, codes },
+        ))))
+    }
+
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes> {
+        self.respond(request.root_hash, |tx| {
+            // only account trie nodes are served
+            let paths = request
+                .paths
+                .iter()
+                .filter_map(|paths| paths.first())
+                .map(decode_compact_path)
+                .collect::<Vec<_>>();
+            let targets = paths.iter().map(|path| (padded_key(path), HashSet::default())).collect();
+            let proof = Proof::from_tx(tx).multiproof(targets).unwrap();
+            let nodes =
+                paths.iter().map_while(|path| proof.account_subtree.get(path).cloned()).collect();
+            TrieNodes { request_id: request.request_id, nodes }
+        })
+    }
+}
+
+/// Decodes a compact encoded trie path.
+fn decode_compact_path(path: &Bytes) -> Nibbles {
+    let mut nibbles = Vec::new();
+    if path[0] & 0x10 != 0 {
+        nibbles.push(path[0] & 0x0f);
+    }
+    nibbles.extend(path[1..].iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+    Nibbles::from_nibbles_unchecked(nibbles)
+}
+
+/// Returns the key of the given trie path, padded with zeros.
+fn padded_key(path: &Nibbles) -> B256 {
+    let mut key = B256::ZERO;
+    let packed = path.pack();
+    key[..packed.len()].copy_from_slice(&packed);
+    key
+}
@@ -74,6 +74,110 @@ impl Compact for MerkleCheckpoint {
     }
 }
 
+/// The phase of the `SnapSync` stage.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapSyncPhase {
+    /// Downloading the accounts, storages and bytecodes at the pivot.
+    #[default]
+    Accounts,
+    /// Computing the state trie of the downloaded state.
+    Trie,
+    /// Repairing the parts of the state trie that changed while downloading.
+    Heal,
+    /// The state at the pivot is complete.
+    Done,
+}
+
+impl SnapSyncPhase {
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Accounts,
+            1 => Self::Trie,
+            2 => Self::Heal,
+            _ => Self::Done,
+        }
+    }
+}
+
+/// Saves the progress of `SnapSync` stage.
+///
+/// The existence of the checkpoint marks the state of the node as downloaded at the pivot, rather
+/// than computed from genesis.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct SnapSyncCheckpoint {
+    /// The block whose state is downloaded.
+    pub pivot: BlockNumber,
+    /// The state root of the pivot block.
+    pub state_root: B256,
+    /// The current phase.
+    pub phase: SnapSyncPhase,
+    /// The account ranges that are left to download, as the next and the last key of each range.
+    pub account_ranges: Vec<(B256, B256)>,
+    /// The progress of the state trie computation.
+    pub merkle: Option<MerkleCheckpoint>,
+}
+
+impl Compact for SnapSyncCheckpoint {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        let mut len = 0;
+
+        buf.put_u64(self.pivot);
+        len += 8;
+
+        buf.put_slice(self.state_root.as_slice());
+        len += self.state_root.len();
+
+        buf.put_u8(self.phase as u8);
+        len += 1;
+
+        buf.put_u16(self.account_ranges.len() as u16);
+        len += 2;
+        for (next, last) in &self.account_ranges {
+            buf.put_slice(next.as_slice());
+            buf.put_slice(last.as_slice());
+            len += next.len() + last.len();
+        }
+
+        buf.put_u8(self.merkle.is_some() as u8);
+        len += 1;
+        if let Some(merkle) = &self.merkle {
+            len += merkle.to_compact(buf);
+        }
+        len
+    }
+
+    fn from_compact(mut buf: &[u8], _len: usize) -> (Self, &[u8]) {
+        let pivot = buf.get_u64();
+
+        let state_root = B256::from_slice(&buf[..32]);
+        buf.advance(32);
+
+        let phase = SnapSyncPhase::from_u8(buf.get_u8());
+
+        let account_ranges_len = buf.get_u16() as usize;
+        let mut account_ranges = Vec::with_capacity(account_ranges_len);
+        for _ in 0..account_ranges_len {
+            let next = B256::from_slice(&buf[..32]);
+            let last = B256::from_slice(&buf[32..64]);
+            account_ranges.push((next, last));
+            buf.advance(64);
+        }
+
+        let merkle = if buf.get_u8() == 1 {
+            let (merkle, rest) = MerkleCheckpoint::from_compact(buf, 0);
+            buf = rest;
+            Some(merkle)
+        } else {
+            None
+        };
+
+        (Self { pivot, state_root, phase, account_ranges, merkle }, buf)
+    }
+}
+
 /// Saves the progress of AccountHashing stage.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Compact)]
 #[cfg_attr(any(test, feature = "test-utils"), derive(arbitrary::Arbitrary))]
@@ -406,4 +510,35 @@ mod tests {
         let (decoded, _) = MerkleCheckpoint::from_compact(&buf, encoded);
         assert_eq!(decoded, checkpoint);
     }
+
+    #[test]
+    fn snap_sync_checkpoint_roundtrip() {
+        let mut rng = rand::thread_rng();
+        let mut checkpoint = SnapSyncCheckpoint {
+            pivot: rng.gen(),
+            state_root: rng.gen(),
+            phase: SnapSyncPhase::Accounts,
+            account_ranges: vec![(rng.gen(), rng.gen()), (rng.gen(), rng.gen())],
+            merkle: None,
+        };
+
+        let mut buf = Vec::new();
+        let encoded = checkpoint.to_compact(&mut buf);
+        let (decoded, _) = SnapSyncCheckpoint::from_compact(&buf, encoded);
+        assert_eq!(decoded, checkpoint);
+
+        checkpoint.phase = SnapSyncPhase::Trie;
+        checkpoint.account_ranges.clear();
+        checkpoint.merkle = Some(MerkleCheckpoint {
+            target_block: rng.gen(),
+            last_account_key: rng.gen(),
+            walker_stack: Vec::new(),
+            state: HashBuilderState::default(),
+        });
+
+        let mut buf = Vec::new();
+        let encoded = checkpoint.to_compact(&mut buf);
+        let (decoded, _) = SnapSyncCheckpoint::from_compact(&buf, encoded);
+        assert_eq!(decoded, checkpoint);
+    }
 }
@@ -22,6 +22,10 @@ pub enum StageId {
     IndexAccountHistory,
     Prune,
     Finish,
+    /// Downloads the state at a pivot block over `snap/1`.
+    ///
+    /// Not part of [`StageId::ALL`], since it only runs in the snap sync pipeline.
+    SnapSync,
     /// Other custom stage with a provided string identifier.
     Other(&'static str),
 }
@@ -77,6 +81,7 @@ impl StageId {
             Self::IndexStorageHistory => "IndexStorageHistory",
             Self::Prune => "Prune",
             Self::Finish => "Finish",
+            Self::SnapSync => "SnapSync",
             Self::Other(s) => s,
         }
     }
@@ -121,6 +126,7 @@ mod tests {
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
+        assert_eq!(StageId::SnapSync.to_string(), "SnapSync");
 
         assert_eq!(StageId::Other("Foo").to_string(), "Foo");
     }
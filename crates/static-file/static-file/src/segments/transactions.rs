@@ -9,11 +9,28 @@ use reth_provider::{
 use reth_static_file_types::StaticFileSegment;
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::ops::RangeInclusive;
+use tracing::debug;
 
 /// Static File segment responsible for [`StaticFileSegment::Transactions`] part of data.
 #[derive(Debug, Default)]
 pub struct Transactions;
 
+impl Transactions {
+    /// Builds the missing transaction hash indices of complete
+    /// [`StaticFileSegment::Transactions`] static files, so transactions can be found by hash
+    /// without the [`tables::TransactionHashNumbers`] table.
+    pub fn build_hash_indices(
+        &self,
+        static_file_provider: &StaticFileProvider,
+    ) -> ProviderResult<()> {
+        let built = static_file_provider.build_missing_transaction_hash_indices(None)?;
+        if built > 0 {
+            debug!(target: "static_file", built, "Built transaction hash indices");
+        }
+        Ok(())
+    }
+}
+
 impl<Provider: DBProvider + BlockReader> Segment<Provider> for Transactions {
     fn segment(&self) -> StaticFileSegment {
         StaticFileSegment::Transactions
@@ -72,12 +72,18 @@ pub struct StaticFileTargets {
     headers: Option<RangeInclusive<BlockNumber>>,
     receipts: Option<RangeInclusive<BlockNumber>>,
     transactions: Option<RangeInclusive<BlockNumber>>,
+    account_changesets: Option<RangeInclusive<BlockNumber>>,
+    storage_changesets: Option<RangeInclusive<BlockNumber>>,
 }
 
 impl StaticFileTargets {
     /// Returns `true` if any of the targets are [Some].
     pub const fn any(&self) -> bool {
-        self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
+        self.headers.is_some() ||
+            self.receipts.is_some() ||
+            self.transactions.is_some() ||
+            self.account_changesets.is_some() ||
+            self.storage_changesets.is_some()
     }
 
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
@@ -87,6 +93,8 @@ impl StaticFileTargets {
             (self.headers.as_ref(), static_files.headers),
             (self.receipts.as_ref(), static_files.receipts),
             (self.transactions.as_ref(), static_files.transactions),
+            (self.account_changesets.as_ref(), static_files.account_changesets),
+            (self.storage_changesets.as_ref(), static_files.storage_changesets),
         ]
         .iter()
         .all(|(target_block_range, highest_static_fileted_block)| {
@@ -162,6 +170,12 @@ where
         if let Some(block_range) = targets.receipts.clone() {
             segments.push((Box::new(segments::Receipts), block_range));
         }
+        if let Some(block_range) = targets.account_changesets.clone() {
+            segments.push((Box::new(segments::AccountChangeSets), block_range));
+        }
+        if let Some(block_range) = targets.storage_changesets.clone() {
+            segments.push((Box::new(segments::StorageChangeSets), block_range));
+        }
 
         segments.par_iter().try_for_each(|(segment, block_range)| -> ProviderResult<()> {
             debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
@@ -200,19 +214,35 @@ where
     /// Returns highest block numbers for all static file segments.
     pub fn copy_to_static_files(&self) -> ProviderResult<HighestStaticFiles> {
         let provider = self.provider.database_provider_ro()?;
-        let stages_checkpoints = [StageId::Headers, StageId::Execution, StageId::Bodies]
-            .into_iter()
-            .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
-            .collect::<Result<Vec<_>, _>>()?;
+        let stages_checkpoints = [
+            StageId::Headers,
+            StageId::Execution,
+            StageId::Bodies,
+            StageId::IndexAccountHistory,
+            StageId::IndexStorageHistory,
+        ]
+        .into_iter()
+        .map(|stage| provider.get_stage_checkpoint(stage).map(|c| c.map(|c| c.block_number)))
+        .collect::<Result<Vec<_>, _>>()?;
 
+        // Changesets are read by the history index stages, so they're only moved to static files
+        // once indexed.
         let highest_static_files = HighestStaticFiles {
             headers: stages_checkpoints[0],
             receipts: stages_checkpoints[1],
             transactions: stages_checkpoints[2],
+            account_changesets: stages_checkpoints[3],
+            storage_changesets: stages_checkpoints[4],
         };
         let targets = self.get_static_file_targets(highest_static_files)?;
         self.run(targets)?;
 
+        // Transaction lookup entries may be pruned, so transactions in static files need to be
+        // found by hash through their index instead.
+        if self.prune_modes.transaction_lookup.is_some() {
+            segments::Transactions.build_hash_indices(&self.provider.static_file_provider())?;
+        }
+
         Ok(highest_static_files)
     }
 
@@ -230,9 +260,7 @@ where
                 self.get_static_file_target(highest_static_files.headers, finalized_block_number)
             }),
             // StaticFile receipts only if they're not pruned according to the user configuration
-            receipts: if self.prune_modes.receipts.is_none() &&
-                self.prune_modes.receipts_log_filter.is_empty()
-            {
+            receipts: if !self.prune_modes.has_receipts_pruning() {
                 finalized_block_numbers.receipts.and_then(|finalized_block_number| {
                     self.get_static_file_target(
                         highest_static_files.receipts,
@@ -248,6 +276,27 @@ where
                     finalized_block_number,
                 )
             }),
+            // StaticFile changesets only if they're not pruned according to the user configuration
+            account_changesets: if self.prune_modes.account_history.is_none() {
+                finalized_block_numbers.account_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.account_changesets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
+            storage_changesets: if self.prune_modes.storage_history.is_none() {
+                finalized_block_numbers.storage_changesets.and_then(|finalized_block_number| {
+                    self.get_static_file_target(
+                        highest_static_files.storage_changesets,
+                        finalized_block_number,
+                    )
+                })
+            } else {
+                None
+            },
         };
 
         trace!(
@@ -279,16 +328,23 @@ mod tests {
     };
     use alloy_primitives::{B256, U256};
     use assert_matches::assert_matches;
-    use reth_db_api::{database::Database, transaction::DbTx};
+    use reth_db::tables;
+    use reth_db_api::{
+        cursor::DbCursorRO,
+        database::Database,
+        models::{AccountBeforeTx, BlockNumberAddress},
+        transaction::{DbTx, DbTxMut},
+    };
     use reth_provider::{
-        providers::StaticFileWriter, test_utils::MockNodeTypesWithDB, ProviderError,
-        ProviderFactory, StaticFileProviderFactory,
+        providers::StaticFileWriter, test_utils::MockNodeTypesWithDB, ChangeSetReader, DBProvider,
+        ProviderError, ProviderFactory, StaticFileProviderFactory, StorageChangeSetReader,
     };
-    use reth_prune_types::PruneModes;
+    use reth_prune_types::{PruneMode, PruneModes};
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use reth_static_file_types::{HighestStaticFiles, StaticFileSegment};
     use reth_testing_utils::generators::{
-        self, random_block_range, random_receipt, BlockRangeParams,
+        self, random_block_range, random_changeset_range, random_eoa_accounts, random_receipt,
+        BlockRangeParams,
     };
     use std::{sync::mpsc::channel, time::Duration};
     use tempfile::TempDir;
@@ -344,6 +400,7 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -351,13 +408,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(0..=1),
                 receipts: Some(0..=1),
-                transactions: Some(0..=1)
+                transactions: Some(0..=1),
+                account_changesets: None,
+                storage_changesets: None
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                ..Default::default()
+            }
         );
 
         let targets = static_file_producer
@@ -365,6 +429,7 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -372,13 +437,20 @@ mod tests {
             StaticFileTargets {
                 headers: Some(2..=3),
                 receipts: Some(2..=3),
-                transactions: Some(2..=3)
+                transactions: Some(2..=3),
+                account_changesets: None,
+                storage_changesets: None
             }
         );
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
         );
 
         let targets = static_file_producer
@@ -386,6 +458,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                ..Default::default()
             })
             .expect("get static file targets");
         assert_eq!(
@@ -393,7 +466,9 @@ mod tests {
             StaticFileTargets {
                 headers: Some(4..=4),
                 receipts: Some(4..=4),
-                transactions: Some(4..=4)
+                transactions: Some(4..=4),
+                account_changesets: None,
+                storage_changesets: None
             }
         );
         assert_matches!(
@@ -402,8 +477,108 @@ mod tests {
         );
         assert_eq!(
             provider_factory.static_file_provider().get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn changesets() {
+        let (provider_factory, _temp_static_files_dir) = setup();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 0..=3, BlockRangeParams::default());
+        let accounts = random_eoa_accounts(&mut rng, 3)
+            .into_iter()
+            .map(|(address, account)| (address, (account, Vec::new())));
+        let (changesets, _) = random_changeset_range(&mut rng, blocks.iter(), accounts, 0..3, 0..8);
+
+        let tx = provider_factory.db_ref().tx_mut().expect("init tx");
+        for (block, changeset) in changesets.into_iter().enumerate() {
+            for (address, old_account, old_storage) in changeset {
+                tx.put::<tables::AccountChangeSets>(
+                    block as u64,
+                    AccountBeforeTx { address, info: Some(old_account) },
+                )
+                .expect("insert account changeset");
+                for entry in old_storage {
+                    tx.put::<tables::StorageChangeSets>((block as u64, address).into(), entry)
+                        .expect("insert storage changeset");
+                }
+            }
+        }
+        tx.commit().expect("commit tx");
+
+        let static_file_producer =
+            StaticFileProducerInner::new(provider_factory.clone(), PruneModes::default());
+
+        let targets = static_file_producer
+            .get_static_file_targets(HighestStaticFiles {
+                account_changesets: Some(3),
+                storage_changesets: Some(3),
+                ..Default::default()
+            })
+            .expect("get static file targets");
+        assert_eq!(
+            targets,
+            StaticFileTargets {
+                headers: None,
+                receipts: None,
+                transactions: None,
+                account_changesets: Some(0..=3),
+                storage_changesets: Some(0..=3),
+            }
         );
+        assert_matches!(static_file_producer.run(targets), Ok(_));
+
+        let static_file_provider = provider_factory.static_file_provider();
+        let provider = provider_factory.provider().expect("provider");
+        for block in 0..=3 {
+            let account_changeset = provider
+                .tx_ref()
+                .cursor_read::<tables::AccountChangeSets>()
+                .unwrap()
+                .walk_range(block..=block)
+                .unwrap()
+                .map(|entry| entry.unwrap().1)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                static_file_provider.account_block_changeset(block).unwrap(),
+                account_changeset
+            );
+
+            let storage_changeset = provider
+                .tx_ref()
+                .cursor_read::<tables::StorageChangeSets>()
+                .unwrap()
+                .walk_range(BlockNumberAddress::range(block..=block))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(static_file_provider.storage_changeset(block).unwrap(), storage_changeset);
+        }
+
+        // Changesets are not moved to static files if they are pruned
+        let static_file_producer = StaticFileProducerInner::new(
+            provider_factory,
+            PruneModes {
+                account_history: Some(PruneMode::Full),
+                storage_history: Some(PruneMode::Full),
+                ..Default::default()
+            },
+        );
+        let targets = static_file_producer
+            .get_static_file_targets(HighestStaticFiles {
+                account_changesets: Some(4),
+                storage_changesets: Some(4),
+                ..Default::default()
+            })
+            .expect("get static file targets");
+        assert!(!targets.any());
     }
 
     /// Tests that a cloneable [`StaticFileProducer`] type is not susceptible to any race condition.
@@ -430,6 +605,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        ..Default::default()
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_));
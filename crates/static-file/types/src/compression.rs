@@ -1,8 +1,10 @@
+use serde::{Deserialize, Serialize};
 use strum::AsRefStr;
 
 /// Static File compression types.
-#[derive(Debug, Copy, Clone, Default, AsRefStr)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, AsRefStr, Serialize, Deserialize)]
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
 pub enum Compression {
     /// LZ4 compression algorithm.
     #[strum(serialize = "lz4")]
@@ -12,6 +14,8 @@ pub enum Compression {
     Zstd,
     /// Zstandard (Zstd) compression algorithm with a dictionary.
     #[strum(serialize = "zstd-dict")]
+    #[serde(rename = "zstd-dict")]
+    #[cfg_attr(feature = "clap", value(name = "zstd-dict"))]
     ZstdWithDictionary,
     /// No compression.
     #[strum(serialize = "uncompressed")]
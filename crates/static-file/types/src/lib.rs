@@ -30,6 +30,12 @@ pub struct HighestStaticFiles {
     /// Highest static file block of transactions, inclusive.
     /// If [`None`], no static file is available.
     pub transactions: Option<BlockNumber>,
+    /// Highest static file block of account changesets, inclusive.
+    /// If [`None`], no static file is available.
+    pub account_changesets: Option<BlockNumber>,
+    /// Highest static file block of storage changesets, inclusive.
+    /// If [`None`], no static file is available.
+    pub storage_changesets: Option<BlockNumber>,
 }
 
 impl HighestStaticFiles {
@@ -39,6 +45,8 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => self.headers,
             StaticFileSegment::Transactions => self.transactions,
             StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::AccountChangeSets => self.account_changesets,
+            StaticFileSegment::StorageChangeSets => self.storage_changesets,
         }
     }
 
@@ -48,17 +56,32 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => &mut self.headers,
             StaticFileSegment::Transactions => &mut self.transactions,
             StaticFileSegment::Receipts => &mut self.receipts,
+            StaticFileSegment::AccountChangeSets => &mut self.account_changesets,
+            StaticFileSegment::StorageChangeSets => &mut self.storage_changesets,
         }
     }
 
     /// Returns the minimum block of all segments.
     pub fn min(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).min()
+        self.iter().min()
     }
 
     /// Returns the maximum block of all segments.
     pub fn max(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).max()
+        self.iter().max()
+    }
+
+    /// Returns an iterator over the highest blocks of all segments that have static files.
+    fn iter(&self) -> impl Iterator<Item = u64> {
+        [
+            self.headers,
+            self.transactions,
+            self.receipts,
+            self.account_changesets,
+            self.storage_changesets,
+        ]
+        .into_iter()
+        .flatten()
     }
 }
 
@@ -78,8 +101,12 @@ mod tests {
 
     #[test]
     fn test_highest_static_files_highest() {
-        let files =
-            HighestStaticFiles { headers: Some(100), receipts: Some(200), transactions: None };
+        let files = HighestStaticFiles {
+            headers: Some(100),
+            receipts: Some(200),
+            transactions: None,
+            ..Default::default()
+        };
 
         // Test for headers segment
         assert_eq!(files.highest(StaticFileSegment::Headers), Some(100));
@@ -110,8 +137,12 @@ mod tests {
 
     #[test]
     fn test_highest_static_files_min() {
-        let files =
-            HighestStaticFiles { headers: Some(300), receipts: Some(100), transactions: None };
+        let files = HighestStaticFiles {
+            headers: Some(300),
+            receipts: Some(100),
+            transactions: None,
+            ..Default::default()
+        };
 
         // Minimum value among the available segments
         assert_eq!(files.min(), Some(100));
@@ -123,8 +154,12 @@ mod tests {
 
     #[test]
     fn test_highest_static_files_max() {
-        let files =
-            HighestStaticFiles { headers: Some(300), receipts: Some(100), transactions: Some(500) };
+        let files = HighestStaticFiles {
+            headers: Some(300),
+            receipts: Some(100),
+            transactions: Some(500),
+            ..Default::default()
+        };
 
         // Maximum value among the available segments
         assert_eq!(files.max(), Some(500));
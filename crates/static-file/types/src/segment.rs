@@ -34,6 +34,14 @@ pub enum StaticFileSegment {
     #[strum(serialize = "receipts")]
     /// Static File segment responsible for the `Receipts` table.
     Receipts,
+    #[strum(serialize = "account-changesets")]
+    #[cfg_attr(feature = "clap", value(name = "account-changesets"))]
+    /// Static File segment responsible for the `AccountChangeSets` table.
+    AccountChangeSets,
+    #[strum(serialize = "storage-changesets")]
+    #[cfg_attr(feature = "clap", value(name = "storage-changesets"))]
+    /// Static File segment responsible for the `StorageChangeSets` table.
+    StorageChangeSets,
 }
 
 impl StaticFileSegment {
@@ -43,19 +51,29 @@ impl StaticFileSegment {
             Self::Headers => "headers",
             Self::Transactions => "transactions",
             Self::Receipts => "receipts",
+            Self::AccountChangeSets => "account-changesets",
+            Self::StorageChangeSets => "storage-changesets",
         }
     }
 
     /// Returns the default configuration of the segment.
+    ///
+    /// Transactions and receipts are already compressed by their encoding, so only block based
+    /// segments are compressed by default.
     pub const fn config(&self) -> SegmentConfig {
-        SegmentConfig { compression: Compression::Lz4 }
+        let compression =
+            if self.is_block_based() { Compression::Lz4 } else { Compression::Uncompressed };
+        SegmentConfig { compression }
     }
 
     /// Returns the number of columns for the segment
     pub const fn columns(&self) -> usize {
         match self {
             Self::Headers => 3,
-            Self::Transactions | Self::Receipts => 1,
+            Self::Transactions |
+            Self::Receipts |
+            Self::AccountChangeSets |
+            Self::StorageChangeSets => 1,
         }
     }
 
@@ -123,11 +141,23 @@ impl StaticFileSegment {
         matches!(self, Self::Receipts)
     }
 
+    /// Returns `true` if the segment is `StaticFileSegment::AccountChangeSets` or
+    /// `StaticFileSegment::StorageChangeSets`.
+    pub const fn is_change_sets(&self) -> bool {
+        matches!(self, Self::AccountChangeSets | Self::StorageChangeSets)
+    }
+
     /// Returns `true` if the segment is `StaticFileSegment::Receipts` or
     /// `StaticFileSegment::Transactions`.
     pub const fn is_tx_based(&self) -> bool {
         matches!(self, Self::Receipts | Self::Transactions)
     }
+
+    /// Returns `true` if the segment has one row per block, which is the case for all segments
+    /// that are not transaction based.
+    pub const fn is_block_based(&self) -> bool {
+        !self.is_tx_based()
+    }
 }
 
 /// A segment header that contains information common to all segments. Used for storage.
@@ -229,7 +259,9 @@ impl SegmentHeader {
     /// Increments tx end range depending on segment
     pub fn increment_tx(&mut self) {
         match self.segment {
-            StaticFileSegment::Headers => (),
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => (),
             StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
                 if let Some(tx_range) = &mut self.tx_range {
                     tx_range.end += 1;
@@ -243,7 +275,9 @@ impl SegmentHeader {
     /// Removes `num` elements from end of tx or block range.
     pub fn prune(&mut self, num: u64) {
         match self.segment {
-            StaticFileSegment::Headers => {
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 if let Some(range) = &mut self.block_range {
                     if num > range.end - range.start {
                         self.block_range = None;
@@ -287,14 +321,16 @@ impl SegmentHeader {
     /// Returns the row offset which depends on whether the segment is block or transaction based.
     pub fn start(&self) -> Option<u64> {
         match self.segment {
-            StaticFileSegment::Headers => self.block_start(),
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => self.block_start(),
             StaticFileSegment::Transactions | StaticFileSegment::Receipts => self.tx_start(),
         }
     }
 }
 
 /// Configuration used on the segment.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SegmentConfig {
     /// Compression used on the segment
     pub compression: Compression,
@@ -359,6 +395,12 @@ mod tests {
         let test_vectors = [
             (StaticFileSegment::Headers, 2..=30, "static_file_headers_2_30", None),
             (StaticFileSegment::Receipts, 30..=300, "static_file_receipts_30_300", None),
+            (
+                StaticFileSegment::AccountChangeSets,
+                0..=499_999,
+                "static_file_account-changesets_0_499999",
+                None,
+            ),
             (
                 StaticFileSegment::Transactions,
                 1_123_233..=11_223_233,
@@ -3,7 +3,7 @@ use crate::{
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, path::Path, sync::Arc};
 
 /// Main Database trait that can open read-only and read-write transactions.
 ///
@@ -22,6 +22,12 @@ pub trait Database: Send + Sync + Debug {
     #[track_caller]
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError>;
 
+    /// Writes a consistent copy of the database into a new file at `path`, taken inside a
+    /// read-only transaction.
+    ///
+    /// If `compact` is set, free pages are omitted from the copy.
+    fn copy_to(&self, path: &Path, compact: bool) -> Result<(), DatabaseError>;
+
     /// Takes a function and passes a read-only transaction into it, making sure it's closed in the
     /// end of the execution.
     fn view<T, F>(&self, f: F) -> Result<T, DatabaseError>
@@ -62,6 +68,10 @@ impl<DB: Database> Database for Arc<DB> {
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
         <DB as Database>::tx_mut(self)
     }
+
+    fn copy_to(&self, path: &Path, compact: bool) -> Result<(), DatabaseError> {
+        <DB as Database>::copy_to(self, path, compact)
+    }
 }
 
 impl<DB: Database> Database for &DB {
@@ -75,4 +85,8 @@ impl<DB: Database> Database for &DB {
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
         <DB as Database>::tx_mut(self)
     }
+
+    fn copy_to(&self, path: &Path, compact: bool) -> Result<(), DatabaseError> {
+        <DB as Database>::copy_to(self, path, compact)
+    }
 }
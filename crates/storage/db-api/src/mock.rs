@@ -12,7 +12,7 @@ use crate::{
     DatabaseError,
 };
 use core::ops::Bound;
-use std::{collections::BTreeMap, ops::RangeBounds};
+use std::{collections::BTreeMap, ops::RangeBounds, path::Path};
 
 /// Mock database used for testing with inner `BTreeMap` structure
 // TODO
@@ -32,6 +32,10 @@ impl Database for DatabaseMock {
     fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
         Ok(TxMock::default())
     }
+
+    fn copy_to(&self, _path: &Path, _compact: bool) -> Result<(), DatabaseError> {
+        Err(DatabaseError::Other("the mock database has no data to copy".to_string()))
+    }
 }
 
 /// Mock read only tx
@@ -67,6 +71,10 @@ impl DbTx for TxMock {
         Ok(self._table.len())
     }
 
+    fn table_size<T: Table>(&self) -> Result<u64, DatabaseError> {
+        Ok(0)
+    }
+
     fn disable_long_read_transaction_safety(&mut self) {}
 }
 
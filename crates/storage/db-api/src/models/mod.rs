@@ -24,7 +24,8 @@ pub mod storage_sharded_key;
 pub use accounts::*;
 pub use blocks::*;
 pub use reth_db_models::{
-    AccountBeforeTx, ClientVersion, StoredBlockBodyIndices, StoredBlockWithdrawals,
+    AccountBeforeTx, AccountChangeSet, ClientVersion, StorageBeforeTx, StorageChangeSet,
+    StoredBlockBodyIndices, StoredBlockWithdrawals,
 };
 pub use sharded_key::ShardedKey;
 
@@ -225,6 +226,8 @@ impl_compression_for_compact!(
     StoredBlockWithdrawals,
     Bytecode,
     AccountBeforeTx,
+    AccountChangeSet,
+    StorageChangeSet,
     TransactionSignedNoHash,
     CompactU256,
     StageCheckpoint,
@@ -314,13 +317,14 @@ mod tests {
         use super::*;
         use reth_codecs::{test_utils::UnusedBits, validate_bitflag_backwards_compat};
         use reth_primitives::{Account, Receipt, ReceiptWithBloom, Withdrawals};
-        use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+        use reth_prune_types::{ArchiveWindow, PruneCheckpoint, PruneMode, PruneSegment};
         use reth_stages_types::{
             AccountHashingCheckpoint, CheckpointBlockRange, EntitiesCheckpoint,
             ExecutionCheckpoint, HeadersCheckpoint, IndexHistoryCheckpoint, StageCheckpoint,
             StageUnitCheckpoint, StorageHashingCheckpoint,
         };
         assert_eq!(Account::bitflag_encoded_bytes(), 2);
+        assert_eq!(ArchiveWindow::bitflag_encoded_bytes(), 2);
         assert_eq!(AccountHashingCheckpoint::bitflag_encoded_bytes(), 1);
         assert_eq!(CheckpointBlockRange::bitflag_encoded_bytes(), 1);
         assert_eq!(CompactClientVersion::bitflag_encoded_bytes(), 0);
@@ -344,6 +348,7 @@ mod tests {
         assert_eq!(Withdrawals::bitflag_encoded_bytes(), 0);
 
         validate_bitflag_backwards_compat!(Account, UnusedBits::NotZero);
+        validate_bitflag_backwards_compat!(ArchiveWindow, UnusedBits::NotZero);
         validate_bitflag_backwards_compat!(AccountHashingCheckpoint, UnusedBits::NotZero);
         validate_bitflag_backwards_compat!(CheckpointBlockRange, UnusedBits::Zero);
         validate_bitflag_backwards_compat!(CompactClientVersion, UnusedBits::Zero);
@@ -24,6 +24,8 @@ pub trait DbTx: Send + Sync {
     fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError>;
     /// Returns number of entries in the table.
     fn entries<T: Table>(&self) -> Result<usize, DatabaseError>;
+    /// Returns the size of the table in bytes.
+    fn table_size<T: Table>(&self) -> Result<u64, DatabaseError>;
     /// Disables long-lived read transaction safety guarantees.
     fn disable_long_read_transaction_safety(&mut self);
 }
@@ -0,0 +1,417 @@
+//! Online backups of the database and static files.
+
+use alloy_primitives::{BlockNumber, B256};
+use eyre::Context;
+use reth_db::{
+    mdbx::DatabaseArguments, open_db_read_only, tables, version::create_db_version_file,
+};
+use reth_db_api::{database::Database, models::ClientVersion, transaction::DbTx};
+use reth_fs_util as fs;
+use reth_primitives::StaticFileSegment;
+use reth_provider::{
+    providers::{ProviderNodeTypes, StaticFileProvider, StaticFileWriter},
+    BlockHashReader, ProviderError, ProviderFactory, StaticFileProviderFactory,
+};
+use reth_stages_types::StageId;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    fs::File,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+use strum::IntoEnumIterator;
+use tracing::info;
+
+/// Name of the manifest file of a backup.
+pub const BACKUP_MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Version of the backup manifest format.
+pub const BACKUP_MANIFEST_VERSION: u64 = 1;
+
+/// Directory of the database in a backup.
+const DB_DIR: &str = "db";
+
+/// Directory of the static files in a backup.
+const STATIC_FILES_DIR: &str = "static_files";
+
+/// Name of the MDBX data file in the database directory.
+const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
+/// Files that are recreated on demand and never part of a backup: the MDBX lock file and the
+/// storage locks of the database and static files directories.
+const LOCK_FILE_NAMES: [&str; 2] = ["mdbx.lck", "lock"];
+
+/// Manifest of a backup, written to [`BACKUP_MANIFEST_FILE_NAME`] in the backup directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Version of the manifest format.
+    pub version: u64,
+    /// Number of the highest block that was fully synced at the time of the backup.
+    pub block_number: BlockNumber,
+    /// Hash of the block at [`Self::block_number`].
+    pub block_hash: B256,
+    /// Files of the backup, sorted by path.
+    pub files: Vec<BackupFile>,
+}
+
+/// A file of a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupFile {
+    /// Path of the file relative to the backup directory, separated by `/`.
+    pub path: String,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// SHA-256 checksum of the file.
+    pub sha256: B256,
+}
+
+impl BackupManifest {
+    /// Reads the manifest of the backup in `dir`.
+    pub fn read(dir: &Path) -> eyre::Result<Self> {
+        let manifest: Self = fs::read_json_file(&dir.join(BACKUP_MANIFEST_FILE_NAME))?;
+        eyre::ensure!(
+            manifest.version == BACKUP_MANIFEST_VERSION,
+            "Unsupported backup manifest version {}, expected {BACKUP_MANIFEST_VERSION}",
+            manifest.version
+        );
+        Ok(manifest)
+    }
+
+    /// Checks that the files of the backup in `dir` match their sizes and checksums in the
+    /// manifest.
+    pub fn verify(&self, dir: &Path) -> eyre::Result<()> {
+        for file in &self.files {
+            let path = dir.join(file.relative_path()?);
+            let (size, sha256) = checksum(&path)?;
+            eyre::ensure!(
+                size == file.size && sha256 == file.sha256,
+                "Backup file {} does not match the manifest",
+                file.path
+            );
+        }
+        Ok(())
+    }
+}
+
+impl BackupFile {
+    /// Returns the path of the file relative to the backup directory.
+    ///
+    /// Fails if the path is absolute or leaves the backup directory.
+    pub fn relative_path(&self) -> eyre::Result<PathBuf> {
+        let path = PathBuf::from_iter(self.path.split('/'));
+        eyre::ensure!(
+            path.components().all(|component| matches!(component, Component::Normal(_))),
+            "Invalid backup file path {}",
+            self.path
+        );
+        Ok(path)
+    }
+}
+
+/// A type that can write backups of the database and static files of a node.
+pub trait DatabaseBackup: Debug + Send + Sync + 'static {
+    /// Writes a backup into `dest` and returns its manifest. See [`backup`].
+    fn backup(&self, dest: &Path) -> eyre::Result<BackupManifest>;
+}
+
+impl<N: ProviderNodeTypes> DatabaseBackup for ProviderFactory<N> {
+    fn backup(&self, dest: &Path) -> eyre::Result<BackupManifest> {
+        backup(self, dest)
+    }
+}
+
+/// Writes a consistent backup of the database and static files of the provider factory into
+/// `dest`, which must be empty or not exist, and returns its manifest.
+///
+/// The database is copied with compaction inside a read-only transaction, so the node can keep
+/// running while the backup is taken. The static files are copied afterwards while their writers
+/// are locked, hard-linking the files that can no longer be modified. Since the node may have
+/// committed more rows in the meantime, the static files of the backup are then truncated to the
+/// stage checkpoints of the copied database.
+pub fn backup<N: ProviderNodeTypes>(
+    provider_factory: &ProviderFactory<N>,
+    dest: &Path,
+) -> eyre::Result<BackupManifest> {
+    ensure_empty_dir(dest)?;
+
+    let db_path = dest.join(DB_DIR);
+    fs::create_dir_all(&db_path)?;
+    info!(target: "reth::cli", path = ?db_path, "Copying database");
+    provider_factory.db_ref().copy_to(&db_path.join(MDBX_DATA_FILE_NAME), true)?;
+    create_db_version_file(&db_path)?;
+
+    // everything below is relative to the state of the copied database
+    let db = open_db_read_only(&db_path, DatabaseArguments::new(ClientVersion::default()))?;
+    let tx = db.tx()?;
+    let checkpoint = |stage_id: StageId| {
+        Ok::<_, eyre::Report>(
+            tx.get::<tables::StageCheckpoints>(stage_id.to_string())?
+                .unwrap_or_default()
+                .block_number,
+        )
+    };
+    let mut targets = BTreeMap::new();
+    for segment in StaticFileSegment::iter() {
+        let stage_id = match segment {
+            StaticFileSegment::Headers => StageId::Headers,
+            StaticFileSegment::Transactions => StageId::Bodies,
+            StaticFileSegment::Receipts |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => StageId::Execution,
+        };
+        targets.insert(segment, checkpoint(stage_id)?);
+    }
+    let block_number = checkpoint(StageId::Finish)?;
+
+    let static_files_path = dest.join(STATIC_FILES_DIR);
+    info!(target: "reth::cli", path = ?static_files_path, "Copying static files");
+    {
+        // a commit during the copy would leave the copied files of a jar at different heights
+        let live_static_file_provider = provider_factory.static_file_provider();
+        let _lock = live_static_file_provider.lock_writers();
+        copy_static_files(live_static_file_provider.directory(), &static_files_path, &targets)?;
+    }
+
+    let static_file_provider = StaticFileProvider::read_write(&static_files_path)?;
+    truncate_static_files(&static_file_provider, &tx, &targets)?;
+
+    let block_hash = if static_file_provider
+        .get_highest_static_file_block(StaticFileSegment::Headers)
+        .is_some_and(|highest| highest >= block_number)
+    {
+        static_file_provider.block_hash(block_number)?
+    } else {
+        tx.get::<tables::CanonicalHeaders>(block_number)?
+    }
+    .ok_or(ProviderError::HeaderNotFound(block_number.into()))?;
+    drop(static_file_provider);
+    drop(tx);
+    drop(db);
+
+    info!(target: "reth::cli", block_number, %block_hash, "Computing checksums");
+    let mut files = Vec::new();
+    for dir in [DB_DIR, STATIC_FILES_DIR] {
+        for entry in fs::read_dir(dest.join(dir))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !entry.file_type()?.is_file() || LOCK_FILE_NAMES.contains(&name.as_str()) {
+                continue
+            }
+            let (size, sha256) = checksum(&entry.path())?;
+            files.push(BackupFile { path: format!("{dir}/{name}"), size, sha256 });
+        }
+    }
+    files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+    let manifest =
+        BackupManifest { version: BACKUP_MANIFEST_VERSION, block_number, block_hash, files };
+    fs::write_json_file(&dest.join(BACKUP_MANIFEST_FILE_NAME), &manifest)?;
+
+    Ok(manifest)
+}
+
+/// Restores the backup in `backup_dir` into the database and static files directories, which
+/// must be empty or not exist, and returns its manifest.
+///
+/// All files are checked against the manifest before anything is written.
+pub fn restore(
+    backup_dir: &Path,
+    db_path: &Path,
+    static_files_path: &Path,
+) -> eyre::Result<BackupManifest> {
+    let manifest = BackupManifest::read(backup_dir)?;
+    ensure_empty_dir(db_path)?;
+    ensure_empty_dir(static_files_path)?;
+
+    info!(target: "reth::cli", path = ?backup_dir, block_number = manifest.block_number, "Verifying backup");
+    manifest.verify(backup_dir)?;
+
+    for file in &manifest.files {
+        let relative_path = file.relative_path()?;
+        let destination = if let Ok(path) = relative_path.strip_prefix(DB_DIR) {
+            db_path.join(path)
+        } else if let Ok(path) = relative_path.strip_prefix(STATIC_FILES_DIR) {
+            static_files_path.join(path)
+        } else {
+            eyre::bail!("Unexpected backup file {}", file.path)
+        };
+
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(backup_dir.join(&relative_path), &destination)
+            .wrap_err_with(|| format!("Failed to restore {}", file.path))?;
+    }
+
+    Ok(manifest)
+}
+
+/// Copies the static files in `from` that are needed to cover the target blocks of their
+/// segments into `to`.
+///
+/// Files of a segment that can still be modified, because they are the latest file of the
+/// segment or will be truncated to the target block, are copied. All others are hard-linked,
+/// falling back to a copy if that is not possible.
+fn copy_static_files(
+    from: &Path,
+    to: &Path,
+    targets: &BTreeMap<StaticFileSegment, BlockNumber>,
+) -> eyre::Result<()> {
+    fs::create_dir_all(to)?;
+
+    // all files of a jar share the name of its data file, up to the extension
+    let mut jars = BTreeMap::<_, Vec<PathBuf>>::new();
+    for entry in fs::read_dir(from)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().map(|name| name.to_string_lossy().into_owned()) else {
+            continue
+        };
+        let jar_name = name.split('.').next().unwrap_or_default();
+        if path.extension().is_some_and(|extension| extension == "tmp") {
+            continue
+        }
+        if let Some((segment, range)) = StaticFileSegment::parse_filename(jar_name) {
+            jars.entry((segment, range.start(), range.end())).or_default().push(path);
+        }
+    }
+
+    let latest_starts = jars.keys().fold(BTreeMap::new(), |mut latest, (segment, start, _)| {
+        latest.insert(*segment, *start);
+        latest
+    });
+
+    for ((segment, start, end), paths) in jars {
+        let target = targets[&segment];
+        if start > target {
+            continue
+        }
+
+        let mutable = latest_starts[&segment] == start || end >= target;
+        for path in paths {
+            let destination = to.join(path.file_name().expect("is a file"));
+            if mutable || std::fs::hard_link(&path, &destination).is_err() {
+                std::fs::copy(&path, &destination)
+                    .wrap_err_with(|| format!("Failed to copy {}", path.display()))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the rows of the static files that are above the target blocks of their segments.
+fn truncate_static_files(
+    static_file_provider: &StaticFileProvider,
+    tx: &impl DbTx,
+    targets: &BTreeMap<StaticFileSegment, BlockNumber>,
+) -> eyre::Result<()> {
+    for (&segment, &target) in targets {
+        let Some(highest_block) = static_file_provider.get_highest_static_file_block(segment)
+        else {
+            continue
+        };
+        if highest_block <= target {
+            continue
+        }
+
+        let mut writer = static_file_provider.latest_writer(segment)?;
+        match segment {
+            StaticFileSegment::Headers => writer.prune_headers(highest_block - target)?,
+            StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                writer.prune_change_sets(highest_block - target)?
+            }
+            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+                let last_tx_num = tx
+                    .get::<tables::BlockBodyIndices>(target)?
+                    .ok_or(ProviderError::BlockBodyIndicesNotFound(target))?
+                    .last_tx_num();
+                let to_delete = static_file_provider
+                    .get_highest_static_file_tx(segment)
+                    .unwrap_or_default()
+                    .saturating_sub(last_tx_num);
+                if segment.is_receipts() {
+                    writer.prune_receipts(to_delete, target)?
+                } else {
+                    writer.prune_transactions(to_delete, target)?
+                }
+            }
+        }
+        writer.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Returns the size and SHA-256 checksum of the file.
+fn checksum(path: &Path) -> eyre::Result<(u64, B256)> {
+    let mut file = File::open(path).map_err(|err| fs::FsPathError::open(err, path))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0; 1 << 20];
+    let mut size = 0;
+    loop {
+        let read = file.read(&mut buf).map_err(|err| fs::FsPathError::read(err, path))?;
+        if read == 0 {
+            break
+        }
+        hasher.update(&buf[..read]);
+        size += read as u64;
+    }
+    Ok((size, B256::from_slice(&hasher.finalize())))
+}
+
+/// Ensures that the directory does not exist or is empty.
+fn ensure_empty_dir(path: &Path) -> eyre::Result<()> {
+    if path.exists() {
+        eyre::ensure!(
+            fs::read_dir(path)?.next().is_none(),
+            "Directory is not empty: {}",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init::init_genesis;
+    use reth_chainspec::MAINNET;
+    use reth_provider::test_utils::create_test_provider_factory_with_chain_spec;
+
+    #[test]
+    fn backup_and_restore() {
+        let factory = create_test_provider_factory_with_chain_spec(MAINNET.clone());
+        let genesis_hash = init_genesis(&factory).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path();
+        let backup_dir = dir.join("backup");
+        let manifest = backup(&factory, &backup_dir).unwrap();
+        assert_eq!(manifest.block_number, 0);
+        assert_eq!(manifest.block_hash, genesis_hash);
+        assert!(manifest.files.iter().any(|file| file.path == "db/mdbx.dat"));
+        assert!(manifest.files.iter().any(|file| file.path.starts_with("static_files/")));
+        assert_eq!(BackupManifest::read(&backup_dir).unwrap(), manifest);
+
+        // the destination has to be empty
+        assert!(backup(&factory, &backup_dir).is_err());
+
+        let (db_path, static_files_path) = (dir.join("db"), dir.join("static_files"));
+        assert_eq!(restore(&backup_dir, &db_path, &static_files_path).unwrap(), manifest);
+        let db =
+            open_db_read_only(&db_path, DatabaseArguments::new(ClientVersion::default())).unwrap();
+        assert_eq!(
+            db.tx().unwrap().get::<tables::StageCheckpoints>(StageId::Finish.to_string()).unwrap(),
+            Some(Default::default())
+        );
+
+        // a modified file fails the validation
+        let (other_db_path, other_static_files_path) = (dir.join("db2"), dir.join("sf2"));
+        std::fs::write(backup_dir.join("db").join("database.version"), "0").unwrap();
+        assert!(restore(&backup_dir, &other_db_path, &other_static_files_path).is_err());
+        assert!(!other_db_path.exists());
+    }
+}
@@ -0,0 +1,409 @@
+//! Reth state dump export utility functions.
+
+use crate::init::{GenesisAccountWithAddress, StateRoot, AVERAGE_COUNT_ACCOUNTS_PER_GB_STATE_DUMP};
+use alloy_genesis::GenesisAccount;
+use alloy_primitives::{keccak256, Address, BlockNumber, Bytes, B256};
+use reth_config::config::EtlConfig;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress, ShardedKey,
+    },
+    table::{Decode, Decompress},
+    transaction::DbTx,
+};
+use reth_etl::Collector;
+use reth_primitives::{Account, GotExpected, StorageEntry};
+use reth_provider::{
+    providers::is_snap_synced, BlockNumReader, ChangeSetReader, DBProvider, HeaderProvider,
+    ProviderError, PruneCheckpointReader, StorageChangeSetReader,
+};
+use reth_prune_types::PruneSegment;
+use reth_trie::{root::storage_root_unhashed, HashBuilder, Nibbles, TrieAccount};
+use std::{collections::BTreeMap, io::Write, iter::Peekable};
+use tracing::info;
+
+/// Writes the state at the given block to `writer`, in the format read by
+/// [`init_from_state_dump`](crate::init::init_from_state_dump).
+///
+/// The first line contains the state root of the block's header, followed by one line per
+/// account, ordered by address. State at historical blocks is reconstructed by reverting the
+/// plain state with the account and storage changesets of all subsequent blocks. The changesets
+/// are read block by block and sorted by address in ETL collectors, so only the storage of a
+/// single account is held in memory at a time.
+///
+/// While accounts are written, the state root is recomputed from the dumped accounts and
+/// checked against the header's state root. Returns the number of written accounts.
+///
+/// The state of a snap synced database can't be exported, since its plain state only holds the
+/// changes after the pivot block, and the addresses of the other accounts are unknown.
+pub fn export_state_dump<Provider>(
+    provider: &Provider,
+    block: BlockNumber,
+    mut writer: impl Write,
+    etl_config: EtlConfig,
+) -> eyre::Result<usize>
+where
+    Provider: DBProvider
+        + BlockNumReader
+        + HeaderProvider
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + PruneCheckpointReader,
+{
+    if is_snap_synced(provider.tx_ref())? {
+        eyre::bail!(
+            "state of a snap synced database can't be exported, the plain state is incomplete"
+        )
+    }
+
+    let header =
+        provider.header_by_number(block)?.ok_or(ProviderError::HeaderNotFound(block.into()))?;
+
+    // changesets after the requested block are needed to revert the plain state
+    let last_block = provider.last_block_number()?;
+    if block < last_block {
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            if provider
+                .get_prune_checkpoint(segment)?
+                .is_some_and(|checkpoint| checkpoint.is_pruned(block + 1))
+            {
+                return Err(ProviderError::StateAtBlockPruned(block).into())
+            }
+        }
+    }
+
+    // the keys order the reverts by address, slot and block number, so the first revert of an
+    // account or slot holds its value at the block
+    let mut account_reverts = Collector::<ShardedKey<Address>, AccountBeforeTx>::new(
+        etl_config.file_size,
+        etl_config.dir.clone(),
+    );
+    let mut storage_reverts = Collector::<StorageShardedKey, StorageEntry>::new(
+        etl_config.file_size,
+        etl_config.dir.clone(),
+    );
+    for number in block + 1..=last_block {
+        for revert in provider.account_block_changeset(number)? {
+            account_reverts.insert(ShardedKey::new(revert.address, number), revert)?;
+        }
+        for (BlockNumberAddress((_, address)), entry) in provider.storage_changeset(number)? {
+            storage_reverts.insert(StorageShardedKey::new(address, entry.key, number), entry)?;
+        }
+    }
+
+    serde_json::to_writer(&mut writer, &StateRoot { root: header.state_root })?;
+    writeln!(writer)?;
+
+    let tx = provider.tx_ref();
+    let mut dumper = StateDumper {
+        tx,
+        writer,
+        storage_cursor: tx.cursor_dup_read::<tables::PlainStorageState>()?,
+        storage_reverts: storage_reverts
+            .iter()?
+            .map(|entry| -> eyre::Result<_> {
+                let (key, entry) = entry?;
+                let key = StorageShardedKey::decode(&key)?;
+                Ok((key.address, StorageEntry::decompress(&entry)?))
+            })
+            .peekable(),
+        trie_accounts: Collector::new(etl_config.file_size, etl_config.dir),
+        dumped: 0,
+    };
+
+    let mut last_reverted = None;
+    let mut reverts = account_reverts
+        .iter()?
+        .map(|entry| -> eyre::Result<_> {
+            let (_, revert) = entry?;
+            Ok(AccountBeforeTx::decompress(&revert)?)
+        })
+        // only the first revert of each account is applied
+        .filter(|revert| match revert {
+            Ok(revert) => last_reverted.replace(revert.address) != Some(revert.address),
+            Err(_) => true,
+        })
+        .peekable();
+
+    // merge the plain state with the accounts that only exist in the reverts
+    for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+        let (address, account) = entry?;
+        while let Some(revert) = reverts
+            .next_if(|revert| revert.as_ref().map_or(true, |revert| revert.address < address))
+        {
+            let AccountBeforeTx { address: reverted, info } = revert?;
+            dumper.dump_account(reverted, info)?;
+        }
+        let account = match reverts
+            .next_if(|revert| revert.as_ref().is_ok_and(|revert| revert.address == address))
+        {
+            Some(revert) => revert?.info,
+            None => Some(account),
+        };
+        dumper.dump_account(address, account)?;
+    }
+    for revert in reverts {
+        let AccountBeforeTx { address, info } = revert?;
+        dumper.dump_account(address, info)?;
+    }
+
+    let StateDumper { mut writer, mut trie_accounts, dumped, .. } = dumper;
+    writer.flush()?;
+
+    info!(target: "reth::cli", dumped, "Computing state root of dumped accounts");
+
+    let mut hash_builder = HashBuilder::default();
+    for entry in trie_accounts.iter()? {
+        let (hashed_address, trie_account) = entry?;
+        hash_builder.add_leaf(
+            Nibbles::unpack(B256::decode(&hashed_address)?),
+            &Bytes::decompress(&trie_account)?,
+        );
+    }
+    let root = hash_builder.root();
+    if root != header.state_root {
+        eyre::bail!(
+            "state root mismatch: {}",
+            GotExpected { got: root, expected: header.state_root }
+        )
+    }
+
+    Ok(dumped)
+}
+
+/// Writes accounts of a state dump and collects their trie leaves.
+struct StateDumper<'a, TX: DbTx, W, R: Iterator> {
+    tx: &'a TX,
+    writer: W,
+    storage_cursor: TX::DupCursor<tables::PlainStorageState>,
+    /// The storage reverts, ordered by address, slot and block number.
+    storage_reverts: Peekable<R>,
+    trie_accounts: Collector<B256, Bytes>,
+    dumped: usize,
+}
+
+impl<TX, W, R> StateDumper<'_, TX, W, R>
+where
+    TX: DbTx,
+    W: Write,
+    R: Iterator<Item = eyre::Result<(Address, StorageEntry)>>,
+{
+    /// Writes the account with its code and storage, skipping it if it does not exist.
+    ///
+    /// Accounts must be dumped in order of their address.
+    fn dump_account(&mut self, address: Address, account: Option<Account>) -> eyre::Result<()> {
+        // the reverts of accounts before this one were consumed by their own calls, or belong to
+        // accounts that did not exist
+        let mut reverted = BTreeMap::new();
+        while let Some(revert) = self
+            .storage_reverts
+            .next_if(|revert| revert.as_ref().map_or(true, |(reverted, _)| *reverted <= address))
+        {
+            let (reverted_address, StorageEntry { key, value }) = revert?;
+            if reverted_address == address {
+                reverted.entry(key).or_insert(value);
+            }
+        }
+
+        let Some(account) = account else { return Ok(()) };
+
+        let mut storage = BTreeMap::new();
+        for entry in self.storage_cursor.walk_dup(Some(address), None)? {
+            let (_, StorageEntry { key, value }) = entry?;
+            storage.insert(key, value);
+        }
+        storage.extend(reverted);
+        storage.retain(|_, value| !value.is_zero());
+
+        let code = account
+            .bytecode_hash
+            .map(|hash| {
+                self.tx
+                    .get::<tables::Bytecodes>(hash)?
+                    .map(|bytecode| bytecode.original_bytes())
+                    .ok_or_else(|| eyre::eyre!("bytecode {hash} of account {address} not found"))
+            })
+            .transpose()?;
+
+        let storage_root = storage_root_unhashed(storage.iter().map(|(&key, &value)| (key, value)));
+        self.trie_accounts.insert(
+            keccak256(address),
+            alloy_rlp::encode(TrieAccount::from((account, storage_root))).into(),
+        )?;
+
+        let genesis_account = GenesisAccount {
+            nonce: Some(account.nonce),
+            balance: account.balance,
+            code,
+            storage: (!storage.is_empty()).then(|| {
+                storage.into_iter().map(|(key, value)| (key, B256::from(value))).collect()
+            }),
+            private_key: None,
+        };
+        serde_json::to_writer(
+            &mut self.writer,
+            &GenesisAccountWithAddress { genesis_account, address },
+        )?;
+        writeln!(self.writer)?;
+
+        self.dumped += 1;
+        if self.dumped % AVERAGE_COUNT_ACCOUNTS_PER_GB_STATE_DUMP == 0 {
+            info!(target: "reth::cli", dumped = self.dumped, "Dumped accounts");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init::init_genesis;
+    use alloy_genesis::Genesis;
+    use alloy_primitives::U256;
+    use reth_chainspec::{Chain, ChainSpec};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, StageCheckpointWriter,
+    };
+    use reth_stages_types::StageId;
+    use std::{io::BufRead, sync::Arc};
+
+    fn chain_spec() -> Arc<ChainSpec> {
+        Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis {
+                alloc: BTreeMap::from([
+                    (
+                        Address::with_last_byte(1),
+                        GenesisAccount { balance: U256::from(1), ..Default::default() },
+                    ),
+                    (
+                        Address::with_last_byte(2),
+                        GenesisAccount {
+                            nonce: Some(1),
+                            code: Some(Bytes::from_static(&[0x60, 0x00])),
+                            storage: Some(BTreeMap::from([(
+                                B256::with_last_byte(1),
+                                B256::with_last_byte(2),
+                            )])),
+                            ..Default::default()
+                        },
+                    ),
+                ]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    fn read_dump(dump: &[u8]) -> (B256, BTreeMap<Address, GenesisAccount>) {
+        let mut lines = dump.lines();
+        let StateRoot { root } = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        let accounts = lines
+            .map(|line| {
+                let GenesisAccountWithAddress { genesis_account, address } =
+                    serde_json::from_str(&line.unwrap()).unwrap();
+                (address, genesis_account)
+            })
+            .collect();
+        (root, accounts)
+    }
+
+    #[test]
+    fn export_genesis_state() {
+        let chain_spec = chain_spec();
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(&factory).unwrap();
+
+        let mut dump = Vec::new();
+        let dumped =
+            export_state_dump(&factory.provider().unwrap(), 0, &mut dump, EtlConfig::default())
+                .unwrap();
+        assert_eq!(dumped, 2);
+
+        let (root, accounts) = read_dump(&dump);
+        assert_eq!(root, chain_spec.genesis_header().state_root);
+        assert_eq!(accounts[&Address::with_last_byte(1)].balance, U256::from(1));
+        assert_eq!(
+            accounts[&Address::with_last_byte(2)],
+            chain_spec.genesis.alloc[&Address::with_last_byte(2)]
+        );
+    }
+
+    #[test]
+    fn export_reverted_state() {
+        let chain_spec = chain_spec();
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(&factory).unwrap();
+        let (_, genesis_accounts) = {
+            let mut dump = Vec::new();
+            export_state_dump(&factory.provider().unwrap(), 0, &mut dump, EtlConfig::default())
+                .unwrap();
+            read_dump(&dump)
+        };
+
+        // apply the changes of a block on top of genesis: the first account is destroyed, the
+        // storage of the second account is changed and a new account is created
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        let destroyed = Address::with_last_byte(1);
+        let changed = Address::with_last_byte(2);
+        let created = Address::with_last_byte(3);
+        let slot = B256::with_last_byte(1);
+        tx.put::<tables::CanonicalHeaders>(1, B256::with_last_byte(1)).unwrap();
+        let genesis_destroyed = tx.get::<tables::PlainAccountState>(destroyed).unwrap();
+        tx.delete::<tables::PlainAccountState>(destroyed, None).unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            1,
+            AccountBeforeTx { address: destroyed, info: genesis_destroyed },
+        )
+        .unwrap();
+        tx.delete::<tables::PlainStorageState>(changed, None).unwrap();
+        tx.put::<tables::PlainStorageState>(
+            changed,
+            StorageEntry { key: slot, value: U256::from(3) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            BlockNumberAddress((1, changed)),
+            StorageEntry { key: slot, value: U256::from(2) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(created, Account { nonce: 1, ..Default::default() })
+            .unwrap();
+        tx.put::<tables::AccountChangeSets>(1, AccountBeforeTx { address: created, info: None })
+            .unwrap();
+
+        // a tiny buffer flushes every revert to its own ETL file
+        for etl_config in [EtlConfig::default(), EtlConfig::new(None, 1)] {
+            let mut dump = Vec::new();
+            export_state_dump(&provider_rw, 0, &mut dump, etl_config).unwrap();
+            let (root, accounts) = read_dump(&dump);
+            assert_eq!(root, chain_spec.genesis_header().state_root);
+            assert_eq!(accounts, genesis_accounts);
+        }
+    }
+
+    #[test]
+    fn export_snap_synced_state() {
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec());
+        init_genesis(&factory).unwrap();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.save_stage_checkpoint_progress(StageId::SnapSync, vec![1]).unwrap();
+        provider_rw.commit().unwrap();
+
+        let mut dump = Vec::new();
+        assert!(export_state_dump(
+            &factory.provider().unwrap(),
+            0,
+            &mut dump,
+            EtlConfig::default()
+        )
+        .is_err());
+        assert!(dump.is_empty());
+    }
+}
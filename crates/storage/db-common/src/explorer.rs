@@ -0,0 +1,428 @@
+//! Read-only exploration of the database tables of a running node.
+//!
+//! Every call opens its own short-lived read transaction, so a client paginating over a large
+//! table never keeps the database from reclaiming pages. Keys and values are exchanged as the JSON
+//! representation of the table types, the same one used by `reth db get` and `reth db list`.
+//!
+//! Tables that were moved to static files (headers, transactions and receipts) can still be
+//! explored, but only hold the data that wasn't moved yet.
+
+use reth_db::{TableViewer, Tables};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    table::{Compress, DupSort, Encode, Table, TableRow},
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_provider::{providers::ProviderNodeTypes, ProviderFactory};
+use serde::{de::Error as _, Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::Debug;
+
+/// Default number of entries returned by [`DatabaseExplorer::range`].
+pub const DEFAULT_RANGE_LIMIT: usize = 100;
+
+/// Maximum number of entries returned by [`DatabaseExplorer::range`].
+pub const MAX_RANGE_LIMIT: usize = 1_000;
+
+/// Errors of the [`DatabaseExplorer`].
+#[derive(Debug, thiserror::Error)]
+pub enum ExplorerError {
+    /// The table doesn't exist.
+    #[error("unknown table: {0}")]
+    UnknownTable(String),
+    /// A key or subkey couldn't be decoded as the type of the table.
+    #[error("invalid {kind} for table {table}: {error}")]
+    InvalidKey {
+        /// Table of the key.
+        table: Tables,
+        /// Whether it's a key or a subkey.
+        kind: &'static str,
+        /// Decoding error.
+        error: serde_json::Error,
+    },
+    /// A subkey was given for a table that isn't `DUPSORT`.
+    #[error("table {0} has no subkeys")]
+    NotDupSort(Tables),
+    /// An entry couldn't be encoded as JSON.
+    #[error(transparent)]
+    Json(serde_json::Error),
+    /// Database error.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+}
+
+/// Statistics of a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableStats {
+    /// Name of the table.
+    pub name: String,
+    /// Whether the table is a `DUPSORT` table.
+    pub dupsort: bool,
+    /// Number of entries, counting every duplicate of a `DUPSORT` table.
+    pub entries: usize,
+    /// Size of the table in bytes, including its branch and overflow pages.
+    pub size: u64,
+}
+
+/// Entry of a table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableEntry {
+    /// Key of the entry.
+    pub key: Value,
+    /// Value of the entry.
+    pub value: Value,
+}
+
+/// Request for a page of a [`DatabaseExplorer::range`] walk over the keys of a table, in
+/// ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangeRequest {
+    /// Key to start at, or the first key of the table if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<Value>,
+    /// Subkey of the `start` key to start at, to resume within the duplicates of a `DUPSORT`
+    /// table.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subkey: Option<Value>,
+    /// Last key of the range, inclusive, or the last key of the table if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<Value>,
+    /// Maximum number of entries to return, [`DEFAULT_RANGE_LIMIT`] if unset. Capped at
+    /// [`MAX_RANGE_LIMIT`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// Page of a [`DatabaseExplorer::range`] walk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangePage {
+    /// Entries of the page.
+    pub entries: Vec<TableEntry>,
+    /// Request for the next page, if the range has more entries.
+    pub next: Option<RangeRequest>,
+}
+
+/// Read-only access to the tables of a database, used by the `reth_db` RPC namespace.
+pub trait DatabaseExplorer: Debug + Send + Sync + 'static {
+    /// Returns the statistics of all tables.
+    fn tables(&self) -> Result<Vec<TableStats>, ExplorerError>;
+
+    /// Returns the statistics of a table.
+    fn table_stats(&self, table: &str) -> Result<TableStats, ExplorerError>;
+
+    /// Returns the value of a key of a table.
+    ///
+    /// For `DUPSORT` tables, returns the value of the subkey if one is given, or the first value
+    /// of the key otherwise.
+    fn get(
+        &self,
+        table: &str,
+        key: Value,
+        subkey: Option<Value>,
+    ) -> Result<Option<Value>, ExplorerError>;
+
+    /// Returns a page of the entries of a table within the requested range.
+    fn range(&self, table: &str, request: RangeRequest) -> Result<RangePage, ExplorerError>;
+}
+
+impl<N: ProviderNodeTypes> DatabaseExplorer for ProviderFactory<N> {
+    fn tables(&self) -> Result<Vec<TableStats>, ExplorerError> {
+        self.db_ref().view(|tx| {
+            Tables::ALL.iter().map(|table| table.view(&StatsViewer { tx, table: *table })).collect()
+        })?
+    }
+
+    fn table_stats(&self, table: &str) -> Result<TableStats, ExplorerError> {
+        let table = parse_table(table)?;
+        self.db_ref().view(|tx| table.view(&StatsViewer { tx, table }))?
+    }
+
+    fn get(
+        &self,
+        table: &str,
+        key: Value,
+        subkey: Option<Value>,
+    ) -> Result<Option<Value>, ExplorerError> {
+        let table = parse_table(table)?;
+        self.db_ref().view(|tx| table.view(&GetViewer { tx, table, key, subkey }))?
+    }
+
+    fn range(&self, table: &str, request: RangeRequest) -> Result<RangePage, ExplorerError> {
+        let table = parse_table(table)?;
+        self.db_ref().view(|tx| table.view(&RangeViewer { tx, table, request }))?
+    }
+}
+
+fn parse_table(table: &str) -> Result<Tables, ExplorerError> {
+    table.parse().map_err(|_| ExplorerError::UnknownTable(table.to_string()))
+}
+
+fn decode_key<K: serde::de::DeserializeOwned>(
+    table: Tables,
+    kind: &'static str,
+    value: Value,
+) -> Result<K, ExplorerError> {
+    serde_json::from_value(value).map_err(|error| ExplorerError::InvalidKey { table, kind, error })
+}
+
+fn encode_json<T: Serialize>(value: &T) -> Result<Value, ExplorerError> {
+    serde_json::to_value(value).map_err(ExplorerError::Json)
+}
+
+struct StatsViewer<'a, TX> {
+    tx: &'a TX,
+    table: Tables,
+}
+
+impl<TX: DbTx> TableViewer<TableStats> for StatsViewer<'_, TX> {
+    type Error = ExplorerError;
+
+    fn view<T: Table>(&self) -> Result<TableStats, Self::Error> {
+        Ok(TableStats {
+            name: self.table.name().to_string(),
+            dupsort: self.table.is_dupsort(),
+            entries: self.tx.entries::<T>()?,
+            size: self.tx.table_size::<T>()?,
+        })
+    }
+}
+
+struct GetViewer<'a, TX> {
+    tx: &'a TX,
+    table: Tables,
+    key: Value,
+    subkey: Option<Value>,
+}
+
+impl<TX: DbTx> TableViewer<Option<Value>> for GetViewer<'_, TX> {
+    type Error = ExplorerError;
+
+    fn view<T: Table>(&self) -> Result<Option<Value>, Self::Error> {
+        if self.subkey.is_some() {
+            return Err(ExplorerError::NotDupSort(self.table))
+        }
+        let key = decode_key::<T::Key>(self.table, "key", self.key.clone())?;
+        self.tx.get::<T>(key)?.as_ref().map(encode_json).transpose()
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<Option<Value>, Self::Error> {
+        let Some(subkey) = self.subkey.clone() else { return self.view::<T>() };
+        let key = decode_key::<T::Key>(self.table, "key", self.key.clone())?;
+        let subkey = decode_key::<T::SubKey>(self.table, "subkey", subkey)?;
+
+        // The cursor returns the first value with a subkey that is not lower, and the subkey of a
+        // value is the prefix of its encoding.
+        let Some(value) =
+            self.tx.cursor_dup_read::<T>()?.seek_by_key_subkey(key, subkey.clone())?
+        else {
+            return Ok(None)
+        };
+        let json = encode_json(&value)?;
+        Ok(value.compress().as_ref().starts_with(subkey.encode().as_ref()).then_some(json))
+    }
+}
+
+struct RangeViewer<'a, TX> {
+    tx: &'a TX,
+    table: Tables,
+    request: RangeRequest,
+}
+
+impl<TX: DbTx> RangeViewer<'_, TX> {
+    /// Returns the page of entries starting at `first`, reading the following entries with
+    /// `next_entry`.
+    ///
+    /// `subkey` returns the subkey of a value, which is used to resume within the duplicates of a
+    /// key on the next page.
+    fn page<T: Table>(
+        &self,
+        first: Option<TableRow<T>>,
+        mut next_entry: impl FnMut() -> Result<Option<TableRow<T>>, DatabaseError>,
+        subkey: impl Fn(T::Value) -> Result<Option<Value>, ExplorerError>,
+    ) -> Result<RangePage, ExplorerError> {
+        let end = self.decode_key::<T>(self.request.end.clone())?;
+        let limit = self.request.limit.unwrap_or(DEFAULT_RANGE_LIMIT).clamp(1, MAX_RANGE_LIMIT);
+
+        let mut entries = Vec::new();
+        let mut next = None;
+        let mut entry = first;
+        while let Some((key, value)) = entry {
+            if end.as_ref().is_some_and(|end| &key > end) {
+                break
+            }
+            if entries.len() == limit {
+                next = Some(RangeRequest {
+                    start: Some(encode_json(&key)?),
+                    subkey: subkey(value)?,
+                    end: self.request.end.clone(),
+                    limit: self.request.limit,
+                });
+                break
+            }
+
+            entries.push(TableEntry { key: encode_json(&key)?, value: encode_json(&value)? });
+            entry = next_entry()?;
+        }
+
+        Ok(RangePage { entries, next })
+    }
+
+    fn decode_key<T: Table>(&self, key: Option<Value>) -> Result<Option<T::Key>, ExplorerError> {
+        key.map(|key| decode_key::<T::Key>(self.table, "key", key)).transpose()
+    }
+}
+
+impl<TX: DbTx> TableViewer<RangePage> for RangeViewer<'_, TX> {
+    type Error = ExplorerError;
+
+    fn view<T: Table>(&self) -> Result<RangePage, Self::Error> {
+        if self.request.subkey.is_some() {
+            return Err(ExplorerError::NotDupSort(self.table))
+        }
+
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        let first = match self.decode_key::<T>(self.request.start.clone())? {
+            Some(start) => cursor.seek(start)?,
+            None => cursor.first()?,
+        };
+        self.page::<T>(first, || cursor.next(), |_| Ok(None))
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<RangePage, Self::Error> {
+        let start = self.decode_key::<T>(self.request.start.clone())?;
+        let subkey = self
+            .request
+            .subkey
+            .clone()
+            .map(|subkey| decode_key::<T::SubKey>(self.table, "subkey", subkey))
+            .transpose()?;
+
+        let mut cursor = self.tx.cursor_dup_read::<T>()?;
+        let first = match (start, subkey) {
+            (Some(start), Some(subkey)) => {
+                match cursor.seek_by_key_subkey(start.clone(), subkey)? {
+                    Some(value) => Some((start, value)),
+                    // no duplicates of the start key are left, continue at the next key
+                    None => match cursor.seek(start.clone())? {
+                        Some((key, _)) if key == start => cursor.next_no_dup()?,
+                        entry => entry,
+                    },
+                }
+            }
+            (None, Some(_)) => {
+                return Err(ExplorerError::InvalidKey {
+                    table: self.table,
+                    kind: "subkey",
+                    error: serde_json::Error::custom("a subkey requires a start key"),
+                })
+            }
+            (Some(start), None) => cursor.seek(start)?,
+            (None, None) => cursor.first()?,
+        };
+        self.page::<T>(first, || cursor.next(), |value| value_subkey::<T>(value).map(Some))
+    }
+}
+
+/// Returns the JSON of the subkey of a value of a `DUPSORT` table.
+///
+/// The subkey is the field of the value whose encoding is the prefix of the encoded value.
+fn value_subkey<T: DupSort>(value: T::Value) -> Result<Value, ExplorerError> {
+    let json = encode_json(&value)?;
+    let compressed = value.compress();
+    json.as_object()
+        .into_iter()
+        .flat_map(|fields| fields.values())
+        .find(|field| {
+            serde_json::from_value::<T::SubKey>((*field).clone())
+                .is_ok_and(|subkey| compressed.as_ref().starts_with(subkey.encode().as_ref()))
+        })
+        .cloned()
+        .ok_or(ExplorerError::Database(DatabaseError::Decode))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init::init_genesis;
+    use alloy_primitives::{Address, B256, U256};
+    use reth_chainspec::MAINNET;
+    use reth_db::tables;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives::StorageEntry;
+    use reth_provider::test_utils::create_test_provider_factory_with_chain_spec;
+    use serde_json::json;
+
+    #[test]
+    fn explore_tables() {
+        let factory = create_test_provider_factory_with_chain_spec(MAINNET.clone());
+        init_genesis(&factory).unwrap();
+
+        let address = Address::with_last_byte(1);
+        let provider_rw = factory.provider_rw().unwrap();
+        for slot in 1..=3 {
+            let entry = StorageEntry { key: B256::with_last_byte(slot), value: U256::from(slot) };
+            provider_rw.tx_ref().put::<tables::PlainStorageState>(address, entry).unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let tables = factory.tables().unwrap();
+        assert_eq!(tables.len(), Tables::COUNT);
+        let stats = factory.table_stats("PlainStorageState").unwrap();
+        assert!(stats.dupsort);
+        assert_eq!(stats.entries, 3);
+        assert!(stats.size > 0);
+        assert!(matches!(factory.table_stats("Unknown"), Err(ExplorerError::UnknownTable(_))));
+
+        let genesis_hash = factory.get("CanonicalHeaders", json!(0), None).unwrap();
+        assert_eq!(genesis_hash, Some(json!(MAINNET.genesis_hash())));
+        assert!(matches!(
+            factory.get("CanonicalHeaders", json!("zero"), None),
+            Err(ExplorerError::InvalidKey { .. })
+        ));
+
+        let value = factory
+            .get("PlainStorageState", json!(address), Some(json!(B256::with_last_byte(2))))
+            .unwrap();
+        assert_eq!(value, Some(json!({ "key": B256::with_last_byte(2), "value": "0x2" })));
+        let value = factory
+            .get("PlainStorageState", json!(address), Some(json!(B256::with_last_byte(4))))
+            .unwrap();
+        assert_eq!(value, None);
+
+        // paginate within the duplicates of the address
+        let page = factory
+            .range("PlainStorageState", RangeRequest { limit: Some(2), ..Default::default() })
+            .unwrap();
+        assert_eq!(page.entries.len(), 2);
+        let next = page.next.unwrap();
+        assert_eq!(next.start, Some(json!(address)));
+        assert_eq!(next.subkey, Some(json!(B256::with_last_byte(3))));
+
+        let page = factory.range("PlainStorageState", next).unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].value["key"], json!(B256::with_last_byte(3)));
+        assert_eq!(page.next, None);
+
+        let page = factory
+            .range(
+                "CanonicalHeaders",
+                RangeRequest { start: Some(json!(0)), end: Some(json!(0)), ..Default::default() },
+            )
+            .unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.next, None);
+        assert!(matches!(
+            factory.range(
+                "CanonicalHeaders",
+                RangeRequest {
+                    start: Some(json!(0)),
+                    subkey: Some(json!(0)),
+                    ..Default::default()
+                },
+            ),
+            Err(ExplorerError::NotDupSort(_))
+        ));
+    }
+}
@@ -563,19 +563,19 @@ where
 
 /// Type to deserialize state root from state dump file.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-struct StateRoot {
-    root: B256,
+pub(crate) struct StateRoot {
+    pub(crate) root: B256,
 }
 
 /// An account as in the state dump file. This contains a [`GenesisAccount`] and the account's
 /// address.
 #[derive(Debug, Serialize, Deserialize)]
-struct GenesisAccountWithAddress {
+pub(crate) struct GenesisAccountWithAddress {
     /// The account's balance, nonce, code, and storage.
     #[serde(flatten)]
-    genesis_account: GenesisAccount,
+    pub(crate) genesis_account: GenesisAccount,
     /// The account's address.
-    address: Address,
+    pub(crate) address: Address,
 }
 
 #[cfg(test)]
@@ -8,7 +8,11 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod backup;
+pub mod dump;
+pub mod explorer;
 pub mod init;
+pub mod verify;
 
 mod db_tool;
 pub use db_tool::*;
@@ -0,0 +1,860 @@
+//! Consistency checks across the database and static files.
+//!
+//! Each [`VerifyCheck`] cross-checks an invariant between tables, or between tables and static
+//! files. Tables that are derived from other data can be rebuilt when their check finds issues.
+
+use alloy_primitives::{keccak256, Address, BlockNumber, TxNumber, B256};
+use reth_db::{static_file::iter_static_files, tables, BlockNumberList};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    models::{storage_sharded_key::StorageShardedKey, ShardedKey, StoredBlockBodyIndices},
+    table::Table,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_primitives::{StaticFileSegment, StorageEntry};
+use reth_provider::{
+    providers::{is_snap_synced, ProviderNodeTypes},
+    AccountExtReader, DatabaseProviderRO, DatabaseProviderRW, HeaderProvider, HistoryWriter,
+    ProviderError, ProviderFactory, PruneCheckpointReader, StageCheckpointReader, StatsReader,
+    StorageReader, TransactionsProvider, TrieWriter,
+};
+use reth_prune_types::{PruneCheckpoint, PruneSegment};
+use reth_stages_types::StageId;
+use reth_trie::{
+    trie_cursor::noop::NoopTrieCursorFactory, IntermediateStateRootState, StateRoot,
+    StateRootProgress,
+};
+use reth_trie_db::{DatabaseHashedCursorFactory, DatabaseStateRoot};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use tracing::{info, warn};
+
+/// Maximum number of issues that are listed in the report of a check. Further issues are only
+/// counted.
+pub const MAX_REPORTED_ISSUES: usize = 100;
+
+/// Number of blocks whose changesets are read at once by [`VerifyCheck::HistoryIndices`].
+const HISTORY_CHUNK_SIZE: u64 = 10_000;
+
+/// Number of recovered senders that are written at once by the repair of
+/// [`VerifyCheck::Senders`].
+const SENDERS_CHUNK_SIZE: usize = 100_000;
+
+/// A consistency check of the database and static files.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter, EnumString, Display,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum VerifyCheck {
+    /// [`tables::BlockBodyIndices`] are contiguous, and match the transaction ranges of the
+    /// transactions static files.
+    BlockBodies,
+    /// [`tables::TransactionSenders`] match the signers recovered from the transactions.
+    Senders,
+    /// [`tables::HashedAccounts`] and [`tables::HashedStorages`] match
+    /// [`tables::PlainAccountState`] and [`tables::PlainStorageState`].
+    ///
+    /// The plain state of a snap synced database only holds the changes after the pivot block, so
+    /// only its entries are checked, and the hashed state is never rebuilt from it.
+    HashedState,
+    /// The state roots computed from the hashed state and from the trie tables match the state
+    /// root of the header of the synced block.
+    StateRoot,
+    /// [`tables::AccountsHistory`] and [`tables::StoragesHistory`] shards hold exactly the blocks
+    /// of the account and storage changesets.
+    HistoryIndices,
+}
+
+/// Report of [`verify`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Number of the highest block that was fully synced.
+    pub block_number: BlockNumber,
+    /// Reports of the checks, in the order they were run.
+    pub checks: Vec<CheckReport>,
+}
+
+impl VerifyReport {
+    /// Returns `true` if no check found issues, or if they were all repaired.
+    pub fn is_consistent(&self) -> bool {
+        self.checks.iter().all(|check| check.issue_count == 0 || check.repaired)
+    }
+}
+
+/// Report of a single [`VerifyCheck`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckReport {
+    /// The check.
+    pub check: VerifyCheck,
+    /// Number of entries that were checked.
+    pub checked: u64,
+    /// Number of issues that were found.
+    pub issue_count: u64,
+    /// The first [`MAX_REPORTED_ISSUES`] issues that were found.
+    pub issues: Vec<VerifyIssue>,
+    /// Reason why the check was skipped, if it was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    /// Whether the derived tables of the check were rebuilt after issues were found.
+    pub repaired: bool,
+}
+
+impl CheckReport {
+    const fn new(check: VerifyCheck) -> Self {
+        Self {
+            check,
+            checked: 0,
+            issue_count: 0,
+            issues: Vec::new(),
+            skipped: None,
+            repaired: false,
+        }
+    }
+
+    /// Records an issue with the entry of `table` at `key`.
+    fn issue(&mut self, table: &str, key: impl Display, message: impl Display) {
+        self.push_issue(VerifyIssue {
+            table: table.to_string(),
+            key: Some(key.to_string()),
+            message: message.to_string(),
+        })
+    }
+
+    /// Records an issue with `table` as a whole.
+    fn table_issue(&mut self, table: &str, message: impl Display) {
+        self.push_issue(VerifyIssue {
+            table: table.to_string(),
+            key: None,
+            message: message.to_string(),
+        })
+    }
+
+    fn push_issue(&mut self, issue: VerifyIssue) {
+        self.issue_count += 1;
+        if self.issues.len() < MAX_REPORTED_ISSUES {
+            self.issues.push(issue);
+        }
+    }
+}
+
+/// An inconsistency found by a [`VerifyCheck`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyIssue {
+    /// Name of the table or static file segment with the inconsistent data.
+    pub table: String,
+    /// Key of the inconsistent entry, if the issue is about a single entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Description of the issue.
+    pub message: String,
+}
+
+/// Rebuild of the derived tables of a check, which fixes all of the issues that it found.
+#[derive(Debug)]
+enum Repair {
+    /// Rebuilds the hashed state from the plain state.
+    HashedState,
+    /// Rebuilds the trie tables from the hashed state, which has the expected state root.
+    Trie { state_root: B256 },
+    /// Rebuilds the history indices from the changesets of the indexed blocks.
+    HistoryIndices {
+        account_to_block: BlockNumber,
+        storage_to_block: BlockNumber,
+        account_pruning: Option<PruneCheckpoint>,
+        storage_pruning: Option<PruneCheckpoint>,
+    },
+}
+
+/// Runs the `checks`, or all of them if it's empty, against the database and static files of the
+/// provider factory.
+///
+/// With `repair`, the derived tables of a check that found issues are rebuilt from their source,
+/// before the next check is run. Checks with issues in their source data, like invalid
+/// signatures or a state root mismatch of the hashed state, are never repaired. The senders are
+/// repaired in chunks while they are checked, so the senders that were written before an invalid
+/// signature is found are kept.
+pub fn verify<N: ProviderNodeTypes>(
+    provider_factory: &ProviderFactory<N>,
+    checks: &[VerifyCheck],
+    repair: bool,
+) -> eyre::Result<VerifyReport> {
+    let block_number = stage_checkpoint(&provider_factory.provider()?, StageId::Finish)?;
+    let mut report = VerifyReport { block_number, checks: Vec::new() };
+
+    for check in VerifyCheck::iter().filter(|check| checks.is_empty() || checks.contains(check)) {
+        info!(target: "reth::cli", %check, "Running check");
+        let provider = provider_factory.provider()?.disable_long_read_transaction_safety();
+        let (mut check_report, fix) = match check {
+            VerifyCheck::BlockBodies => (check_block_bodies(&provider)?, None),
+            VerifyCheck::Senders => (check_senders(provider_factory, &provider, repair)?, None),
+            VerifyCheck::HashedState => check_hashed_state(&provider, block_number)?,
+            VerifyCheck::StateRoot => check_state_root(&provider, block_number)?,
+            VerifyCheck::HistoryIndices => check_history_indices(&provider)?,
+        };
+        drop(provider);
+
+        if check_report.issue_count > 0 {
+            warn!(target: "reth::cli", %check, issues = check_report.issue_count, "Check found issues");
+            if let Some(fix) = fix.filter(|_| repair) {
+                info!(target: "reth::cli", %check, "Repairing");
+                let provider_rw = provider_factory.provider_rw()?;
+                apply_repair(&provider_rw, fix)?;
+                provider_rw.commit()?;
+                check_report.repaired = true;
+            }
+        }
+
+        report.checks.push(check_report);
+    }
+
+    Ok(report)
+}
+
+/// Checks that the block body indices are contiguous, and that the transactions static files hold
+/// the transactions of their blocks.
+fn check_block_bodies<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRO<N::DB, N>,
+) -> eyre::Result<CheckReport> {
+    let mut report = CheckReport::new(VerifyCheck::BlockBodies);
+    let mut bodies = provider.tx_ref().cursor_read::<tables::BlockBodyIndices>()?;
+
+    let mut previous: Option<(BlockNumber, StoredBlockBodyIndices)> = None;
+    for entry in bodies.walk(None)? {
+        let (block, body) = entry?;
+        report.checked += 1;
+
+        if let Some((previous_block, previous_body)) = previous {
+            if block != previous_block + 1 {
+                report.issue(
+                    tables::BlockBodyIndices::NAME,
+                    block,
+                    format!("block body indices are missing after block {previous_block}"),
+                );
+            } else if body.first_tx_num() != previous_body.next_tx_num() {
+                report.issue(
+                    tables::BlockBodyIndices::NAME,
+                    block,
+                    format!(
+                        "first transaction {} doesn't match the next transaction {} of the previous block",
+                        body.first_tx_num(),
+                        previous_body.next_tx_num(),
+                    ),
+                );
+            }
+        }
+        previous = Some((block, body));
+    }
+
+    let static_file_provider = provider.static_file_provider();
+    let segment = StaticFileSegment::Transactions;
+    let highest_static_file_block = static_file_provider.get_highest_static_file_block(segment);
+    let highest_body_block = previous.map(|(block, _)| block);
+    if highest_static_file_block != highest_body_block {
+        report.table_issue(
+            segment.as_str(),
+            format!(
+                "highest block {highest_static_file_block:?} doesn't match the highest block {highest_body_block:?} of the block body indices"
+            ),
+        );
+    }
+
+    let static_files = iter_static_files(static_file_provider.directory())?;
+    for (block_range, tx_range) in static_files.get(&segment).into_iter().flatten() {
+        report.checked += 1;
+        let key = format!("{}..={}", block_range.start(), block_range.end());
+
+        let (Some((_, first)), Some((_, last))) =
+            (bodies.seek_exact(block_range.start())?, bodies.seek_exact(block_range.end())?)
+        else {
+            report.issue(segment.as_str(), key, "block body indices of the blocks are missing");
+            continue
+        };
+
+        let expected = (first.first_tx_num() < last.next_tx_num())
+            .then(|| (first.first_tx_num(), last.last_tx_num()));
+        let actual = tx_range.map(|range| (range.start(), range.end()));
+        if actual != expected {
+            report.issue(
+                segment.as_str(),
+                key,
+                format!(
+                    "transaction range {actual:?} doesn't match the range {expected:?} of the block body indices"
+                ),
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+/// Checks that the stored senders match the signers recovered from the transactions.
+///
+/// With `repair`, the mismatching senders are overwritten with the recovered ones in chunks of
+/// [`SENDERS_CHUNK_SIZE`], until a signer can't be recovered.
+fn check_senders<N: ProviderNodeTypes>(
+    provider_factory: &ProviderFactory<N>,
+    provider: &DatabaseProviderRO<N::DB, N>,
+    repair: bool,
+) -> eyre::Result<CheckReport> {
+    let mut report = CheckReport::new(VerifyCheck::Senders);
+    let to_block = stage_checkpoint(provider, StageId::SenderRecovery)?;
+    let pruning = provider.get_prune_checkpoint(PruneSegment::SenderRecovery)?;
+
+    let mut senders = provider.tx_ref().cursor_read::<tables::TransactionSenders>()?;
+    let mut bodies = provider.tx_ref().cursor_read::<tables::BlockBodyIndices>()?;
+    let mut fixes = Vec::new();
+    let mut repairable = repair;
+    let mut buf = Vec::new();
+
+    for entry in bodies.walk_range(..=to_block)? {
+        let (block, body) = entry?;
+        if is_pruned(pruning.as_ref(), block) {
+            continue
+        }
+
+        let transactions = provider.transactions_by_tx_range(body.tx_num_range())?;
+        for (tx_num, transaction) in body.tx_num_range().zip(transactions) {
+            report.checked += 1;
+            let stored = senders.seek_exact(tx_num)?.map(|(_, sender)| sender);
+            match transaction.encode_and_recover_unchecked(&mut buf) {
+                Some(sender) if stored != Some(sender) => {
+                    report.issue(
+                        tables::TransactionSenders::NAME,
+                        tx_num,
+                        format!("sender {stored:?} doesn't match the recovered sender {sender}"),
+                    );
+                    if repairable {
+                        fixes.push((tx_num, sender));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    report.issue(
+                        tables::TransactionSenders::NAME,
+                        tx_num,
+                        "signer of the transaction can't be recovered",
+                    );
+                    repairable = false;
+                    fixes.clear();
+                }
+            }
+        }
+
+        if fixes.len() >= SENDERS_CHUNK_SIZE {
+            write_senders(provider_factory, &mut fixes)?;
+        }
+
+        if block % 100_000 == 0 {
+            info!(target: "reth::cli", block, to_block, "Checked senders");
+        }
+    }
+
+    if repairable && report.issue_count > 0 {
+        write_senders(provider_factory, &mut fixes)?;
+        report.repaired = true;
+    }
+
+    Ok(report)
+}
+
+/// Writes the recovered senders of the transactions, and clears them.
+fn write_senders<N: ProviderNodeTypes>(
+    provider_factory: &ProviderFactory<N>,
+    senders: &mut Vec<(TxNumber, Address)>,
+) -> eyre::Result<()> {
+    let provider_rw = provider_factory.provider_rw()?;
+    let mut cursor = provider_rw.tx_ref().cursor_write::<tables::TransactionSenders>()?;
+    for (tx_num, sender) in senders.drain(..) {
+        cursor.upsert(tx_num, sender)?;
+    }
+    drop(cursor);
+    provider_rw.commit()?;
+    Ok(())
+}
+
+/// Checks that the hashed state matches the plain state.
+fn check_hashed_state<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRO<N::DB, N>,
+    block_number: BlockNumber,
+) -> eyre::Result<(CheckReport, Option<Repair>)> {
+    let mut report = CheckReport::new(VerifyCheck::HashedState);
+    report.skipped = stages_behind(
+        provider,
+        &[StageId::Execution, StageId::AccountHashing, StageId::StorageHashing],
+        block_number,
+    )?;
+    if report.skipped.is_some() {
+        return Ok((report, None))
+    }
+
+    let tx = provider.tx_ref();
+    // the hashed state of a snap synced database holds accounts and storage that were never
+    // written to the plain state
+    let snap_synced = is_snap_synced(tx)?;
+
+    let mut hashed_accounts = tx.cursor_read::<tables::HashedAccounts>()?;
+    let mut present = 0;
+    for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+        let (address, account) = entry?;
+        report.checked += 1;
+
+        let hashed = hashed_accounts.seek_exact(keccak256(address))?.map(|(_, account)| account);
+        present += usize::from(hashed.is_some());
+        if hashed != Some(account) {
+            report.issue(
+                tables::HashedAccounts::NAME,
+                address,
+                format!("hashed account {hashed:?} doesn't match the plain account {account:?}"),
+            );
+        }
+    }
+    let extra = provider.count_entries::<tables::HashedAccounts>()?.saturating_sub(present);
+    if extra > 0 && !snap_synced {
+        report.table_issue(
+            tables::HashedAccounts::NAME,
+            format!("{extra} hashed accounts have no plain account"),
+        );
+    }
+
+    let mut hashed_storages = tx.cursor_dup_read::<tables::HashedStorages>()?;
+    let mut present = 0;
+    for entry in tx.cursor_read::<tables::PlainStorageState>()?.walk(None)? {
+        let (address, entry) = entry?;
+        report.checked += 1;
+
+        let hashed_key = keccak256(entry.key);
+        let hashed = hashed_storages
+            .seek_by_key_subkey(keccak256(address), hashed_key)?
+            .filter(|hashed| hashed.key == hashed_key)
+            .map(|hashed| hashed.value);
+        present += usize::from(hashed.is_some());
+        if hashed != Some(entry.value) {
+            report.issue(
+                tables::HashedStorages::NAME,
+                format!("{address} {}", entry.key),
+                format!("hashed value {hashed:?} doesn't match the plain value {}", entry.value),
+            );
+        }
+    }
+    let extra = provider.count_entries::<tables::HashedStorages>()?.saturating_sub(present);
+    if extra > 0 && !snap_synced {
+        report.table_issue(
+            tables::HashedStorages::NAME,
+            format!("{extra} hashed storage entries have no plain storage entry"),
+        );
+    }
+
+    if snap_synced {
+        warn!(target: "reth::cli", "Hashed state of a snap synced database can't be repaired");
+        return Ok((report, None))
+    }
+
+    Ok((report, Some(Repair::HashedState)))
+}
+
+/// Checks the state roots of the hashed state and of the trie tables against the state root of
+/// the header of the synced block.
+///
+/// The state root of the hashed state is computed without the trie tables, which requires walking
+/// the whole hashed state.
+fn check_state_root<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRO<N::DB, N>,
+    block_number: BlockNumber,
+) -> eyre::Result<(CheckReport, Option<Repair>)> {
+    let mut report = CheckReport::new(VerifyCheck::StateRoot);
+    report.skipped = stages_behind(
+        provider,
+        &[StageId::AccountHashing, StageId::StorageHashing, StageId::MerkleExecute],
+        block_number,
+    )?;
+    if report.skipped.is_some() {
+        return Ok((report, None))
+    }
+
+    let state_root = provider
+        .header_by_number(block_number)?
+        .ok_or(ProviderError::HeaderNotFound(block_number.into()))?
+        .state_root;
+    let tx = provider.tx_ref();
+    report.checked = (provider.count_entries::<tables::HashedAccounts>()? +
+        provider.count_entries::<tables::HashedStorages>()?) as u64;
+
+    let hashed_state_root =
+        StateRoot::new(NoopTrieCursorFactory::default(), DatabaseHashedCursorFactory::new(tx))
+            .root()?;
+    if hashed_state_root != state_root {
+        report.issue(
+            tables::HashedAccounts::NAME,
+            block_number,
+            format!(
+                "state root {hashed_state_root} of the hashed state doesn't match the state root {state_root} of the header"
+            ),
+        );
+        return Ok((report, None))
+    }
+
+    let trie_root = StateRoot::from_tx(tx).root()?;
+    if trie_root != state_root {
+        report.issue(
+            tables::AccountsTrie::NAME,
+            block_number,
+            format!(
+                "state root {trie_root} of the trie tables doesn't match the state root {state_root} of the header"
+            ),
+        );
+    }
+
+    Ok((report, Some(Repair::Trie { state_root })))
+}
+
+/// Checks that the history indices hold exactly the blocks of the changesets, up to the indexed
+/// blocks and excluding the pruned ones.
+fn check_history_indices<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRO<N::DB, N>,
+) -> eyre::Result<(CheckReport, Option<Repair>)> {
+    let mut report = CheckReport::new(VerifyCheck::HistoryIndices);
+    let account_to_block = stage_checkpoint(provider, StageId::IndexAccountHistory)?;
+    let storage_to_block = stage_checkpoint(provider, StageId::IndexStorageHistory)?;
+    let account_pruning = provider.get_prune_checkpoint(PruneSegment::AccountHistory)?;
+    let storage_pruning = provider.get_prune_checkpoint(PruneSegment::StorageHistory)?;
+    let tx = provider.tx_ref();
+
+    let mut accounts_history = tx.cursor_read::<tables::AccountsHistory>()?;
+    let mut found = 0;
+    for range in history_chunks(account_to_block) {
+        found += check_history_shards(
+            &mut report,
+            &mut accounts_history,
+            provider.changed_accounts_and_blocks_with_range(range)?,
+            account_pruning.as_ref(),
+            |address, block| ShardedKey::new(*address, block),
+            |key, address| key.key == *address,
+        )?;
+    }
+    let indexed =
+        count_history_blocks(&mut accounts_history, account_to_block, account_pruning.as_ref())?;
+    if indexed > found {
+        report.table_issue(
+            tables::AccountsHistory::NAME,
+            format!("{} indexed blocks have no account changeset", indexed - found),
+        );
+    }
+
+    let mut storages_history = tx.cursor_read::<tables::StoragesHistory>()?;
+    let mut found = 0;
+    for range in history_chunks(storage_to_block) {
+        found += check_history_shards(
+            &mut report,
+            &mut storages_history,
+            provider.changed_storages_and_blocks_with_range(range)?,
+            storage_pruning.as_ref(),
+            |(address, slot), block| StorageShardedKey::new(*address, *slot, block),
+            |key, (address, slot)| key.address == *address && key.sharded_key.key == *slot,
+        )?;
+    }
+    let indexed =
+        count_history_blocks(&mut storages_history, storage_to_block, storage_pruning.as_ref())?;
+    if indexed > found {
+        report.table_issue(
+            tables::StoragesHistory::NAME,
+            format!("{} indexed blocks have no storage changeset", indexed - found),
+        );
+    }
+
+    // The pruner retains the changesets right after an archive window, which can't be told apart
+    // from the pruned ones when rebuilding.
+    let has_archive_window = [account_pruning, storage_pruning]
+        .into_iter()
+        .flatten()
+        .any(|pruning| pruning.prune_mode.archive_window().is_some());
+    if has_archive_window {
+        warn!(target: "reth::cli", "History indices with an archive window can't be repaired");
+        return Ok((report, None))
+    }
+
+    Ok((
+        report,
+        Some(Repair::HistoryIndices {
+            account_to_block,
+            storage_to_block,
+            account_pruning,
+            storage_pruning,
+        }),
+    ))
+}
+
+/// Checks that the shards of the history index `T` hold the blocks of the changed keys, and
+/// returns the number of blocks that were found.
+fn check_history_shards<T, K: Debug>(
+    report: &mut CheckReport,
+    cursor: &mut impl DbCursorRO<T>,
+    changes: BTreeMap<K, Vec<BlockNumber>>,
+    pruning: Option<&PruneCheckpoint>,
+    sharded_key: impl Fn(&K, BlockNumber) -> T::Key,
+    key_matches: impl Fn(&T::Key, &K) -> bool,
+) -> Result<u64, DatabaseError>
+where
+    T: Table<Value = BlockNumberList>,
+{
+    let mut found = 0;
+    for (key, blocks) in changes {
+        for block in blocks.into_iter().filter(|block| !is_pruned(pruning, *block)) {
+            report.checked += 1;
+            // Shards are keyed by their highest block, so the first shard that isn't lower
+            // holds the block.
+            let indexed =
+                cursor.seek(sharded_key(&key, block))?.is_some_and(|(shard_key, shard)| {
+                    key_matches(&shard_key, &key) && shard.contains(block)
+                });
+            if indexed {
+                found += 1;
+            } else {
+                report.issue(T::NAME, format!("{key:?} {block}"), "changed block is not indexed");
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Returns the number of blocks in the shards of the history index `T`, up to `to_block` and
+/// excluding the pruned ones.
+fn count_history_blocks<T>(
+    cursor: &mut impl DbCursorRO<T>,
+    to_block: BlockNumber,
+    pruning: Option<&PruneCheckpoint>,
+) -> Result<u64, DatabaseError>
+where
+    T: Table<Value = BlockNumberList>,
+{
+    let mut count = 0;
+    for entry in cursor.walk(None)? {
+        let (_, shard) = entry?;
+        count +=
+            shard.iter().filter(|block| *block <= to_block && !is_pruned(pruning, *block)).count()
+                as u64;
+    }
+    Ok(count)
+}
+
+/// Applies the repair of a check.
+fn apply_repair<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRW<N::DB, N>,
+    repair: Repair,
+) -> eyre::Result<()> {
+    let tx = provider.tx_ref();
+    match repair {
+        Repair::HashedState => {
+            eyre::ensure!(
+                !is_snap_synced(tx)?,
+                "Hashed state of a snap synced database can't be rebuilt from the plain state"
+            );
+            tx.clear::<tables::HashedAccounts>()?;
+            tx.clear::<tables::HashedStorages>()?;
+
+            let mut hashed_accounts = tx.cursor_write::<tables::HashedAccounts>()?;
+            for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+                let (address, account) = entry?;
+                hashed_accounts.upsert(keccak256(address), account)?;
+            }
+
+            let mut hashed_storages = tx.cursor_dup_write::<tables::HashedStorages>()?;
+            for entry in tx.cursor_read::<tables::PlainStorageState>()?.walk(None)? {
+                let (address, entry) = entry?;
+                hashed_storages.upsert(
+                    keccak256(address),
+                    StorageEntry { key: keccak256(entry.key), value: entry.value },
+                )?;
+            }
+        }
+        Repair::Trie { state_root } => {
+            tx.clear::<tables::AccountsTrie>()?;
+            tx.clear::<tables::StoragesTrie>()?;
+
+            let mut state: Option<IntermediateStateRootState> = None;
+            let root = loop {
+                match StateRoot::from_tx(tx).with_intermediate_state(state).root_with_progress()? {
+                    StateRootProgress::Progress(progress, _, updates) => {
+                        provider.write_trie_updates(&updates)?;
+                        state = Some(*progress);
+                    }
+                    StateRootProgress::Complete(root, _, updates) => {
+                        provider.write_trie_updates(&updates)?;
+                        break root
+                    }
+                }
+            };
+            eyre::ensure!(
+                root == state_root,
+                "Rebuilt state root {root} doesn't match the expected state root {state_root}"
+            );
+        }
+        Repair::HistoryIndices {
+            account_to_block,
+            storage_to_block,
+            account_pruning,
+            storage_pruning,
+        } => {
+            tx.clear::<tables::AccountsHistory>()?;
+            tx.clear::<tables::StoragesHistory>()?;
+
+            for range in history_chunks(account_to_block) {
+                let changes = provider.changed_accounts_and_blocks_with_range(range)?;
+                provider.insert_account_history_index(changes.into_iter().map(
+                    |(address, blocks)| {
+                        (address, retained_blocks(blocks, account_pruning.as_ref()))
+                    },
+                ))?;
+            }
+            for range in history_chunks(storage_to_block) {
+                let changes = provider.changed_storages_and_blocks_with_range(range)?;
+                provider.insert_storage_history_index(changes.into_iter().map(
+                    |(key, blocks)| (key, retained_blocks(blocks, storage_pruning.as_ref())),
+                ))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the block number of the stage checkpoint, or zero if there's none.
+fn stage_checkpoint<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRO<N::DB, N>,
+    stage_id: StageId,
+) -> eyre::Result<BlockNumber> {
+    Ok(provider.get_stage_checkpoint(stage_id)?.unwrap_or_default().block_number)
+}
+
+/// Returns the reason why the state at `block_number` can't be checked, if any of the stages
+/// hasn't reached it.
+fn stages_behind<N: ProviderNodeTypes>(
+    provider: &DatabaseProviderRO<N::DB, N>,
+    stages: &[StageId],
+    block_number: BlockNumber,
+) -> eyre::Result<Option<String>> {
+    for stage_id in stages {
+        let checkpoint = stage_checkpoint(provider, *stage_id)?;
+        if checkpoint != block_number {
+            return Ok(Some(format!(
+                "stage {stage_id} is at block {checkpoint}, not at the synced block {block_number}"
+            )))
+        }
+    }
+    Ok(None)
+}
+
+/// Splits the blocks up to `to_block` into ranges of [`HISTORY_CHUNK_SIZE`] blocks.
+fn history_chunks(to_block: BlockNumber) -> impl Iterator<Item = RangeInclusive<BlockNumber>> {
+    (0..=to_block)
+        .step_by(HISTORY_CHUNK_SIZE as usize)
+        .map(move |start| start..=(start + HISTORY_CHUNK_SIZE - 1).min(to_block))
+}
+
+/// Returns the blocks that aren't pruned.
+fn retained_blocks(
+    blocks: Vec<BlockNumber>,
+    pruning: Option<&PruneCheckpoint>,
+) -> Vec<BlockNumber> {
+    blocks.into_iter().filter(|block| !is_pruned(pruning, *block)).collect()
+}
+
+/// Returns `true` if the data of the block was pruned according to the checkpoint.
+fn is_pruned(pruning: Option<&PruneCheckpoint>, block: BlockNumber) -> bool {
+    pruning.is_some_and(|checkpoint| checkpoint.is_pruned(block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init::init_genesis;
+    use reth_chainspec::MAINNET;
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, StageCheckpointWriter,
+    };
+
+    #[test]
+    fn verify_and_repair() {
+        let factory = create_test_provider_factory_with_chain_spec(MAINNET.clone());
+        init_genesis(&factory).unwrap();
+
+        let report = verify(&factory, &[], false).unwrap();
+        assert_eq!(report.checks.len(), VerifyCheck::iter().count());
+        assert!(report.is_consistent(), "{report:?}");
+
+        // corrupt a hashed account and remove a hashed storage entry
+        let provider_rw = factory.provider_rw().unwrap();
+        let (address, mut account) = provider_rw
+            .tx_ref()
+            .cursor_read::<tables::PlainAccountState>()
+            .unwrap()
+            .first()
+            .unwrap()
+            .unwrap();
+        account.nonce += 1;
+        provider_rw.tx_ref().put::<tables::HashedAccounts>(keccak256(address), account).unwrap();
+        provider_rw.commit().unwrap();
+
+        let report = verify(&factory, &[VerifyCheck::HashedState], false).unwrap();
+        assert_eq!(report.checks[0].issue_count, 1);
+        assert_eq!(report.checks[0].issues[0].key, Some(address.to_string()));
+        assert!(!report.is_consistent());
+
+        let report = verify(&factory, &[VerifyCheck::HashedState], true).unwrap();
+        assert!(report.checks[0].repaired);
+        assert!(report.is_consistent());
+
+        let report = verify(&factory, &[], false).unwrap();
+        assert!(report.is_consistent(), "{report:?}");
+    }
+
+    #[test]
+    fn verify_snap_synced_state() {
+        let factory = create_test_provider_factory_with_chain_spec(MAINNET.clone());
+        init_genesis(&factory).unwrap();
+
+        // the plain state of a snap synced database lacks the accounts that didn't change after
+        // the pivot
+        let provider_rw = factory.provider_rw().unwrap();
+        let mut plain_accounts =
+            provider_rw.tx_ref().cursor_read::<tables::PlainAccountState>().unwrap();
+        let (missing, _) = plain_accounts.first().unwrap().unwrap();
+        let (address, mut account) = plain_accounts.next().unwrap().unwrap();
+        drop(plain_accounts);
+        provider_rw.tx_ref().delete::<tables::PlainAccountState>(missing, None).unwrap();
+        provider_rw.commit().unwrap();
+
+        let report = verify(&factory, &[VerifyCheck::HashedState], false).unwrap();
+        assert_eq!(report.checks[0].issue_count, 1);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw.save_stage_checkpoint_progress(StageId::SnapSync, vec![1]).unwrap();
+        provider_rw.commit().unwrap();
+
+        let report = verify(&factory, &[], false).unwrap();
+        assert!(report.is_consistent(), "{report:?}");
+
+        // mismatches with the plain state are still found, but the hashed state is not rebuilt
+        let provider_rw = factory.provider_rw().unwrap();
+        account.nonce += 1;
+        provider_rw.tx_ref().put::<tables::HashedAccounts>(keccak256(address), account).unwrap();
+        provider_rw.commit().unwrap();
+
+        let report = verify(&factory, &[VerifyCheck::HashedState], true).unwrap();
+        assert_eq!(report.checks[0].issue_count, 1);
+        assert_eq!(report.checks[0].issues[0].key, Some(address.to_string()));
+        assert!(!report.checks[0].repaired);
+        assert!(factory
+            .provider()
+            .unwrap()
+            .tx_ref()
+            .get::<tables::HashedAccounts>(keccak256(missing))
+            .unwrap()
+            .is_some());
+    }
+}
@@ -1,7 +1,7 @@
 use reth_codecs::{add_arbitrary_tests, Compact};
 use serde::Serialize;
 
-use alloy_primitives::{bytes::Buf, Address};
+use alloy_primitives::{bytes::Buf, Address, B256, U256};
 use reth_primitives::Account;
 
 /// Account as it is saved in the database.
@@ -48,3 +48,331 @@ impl Compact for AccountBeforeTx {
         (Self { address, info }, buf)
     }
 }
+
+/// Storage slot of an account as it was before the transaction.
+///
+/// Entry of the storage changesets static files, which unlike the `StorageChangeSets` table
+/// don't key entries by address.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary, serde::Deserialize))]
+#[add_arbitrary_tests(compact)]
+pub struct StorageBeforeTx {
+    /// Address of the account.
+    pub address: Address,
+    /// Storage key.
+    pub key: B256,
+    /// Value of the storage slot before the transaction.
+    pub value: U256,
+}
+
+impl Compact for StorageBeforeTx {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        buf.put_slice(self.address.as_slice());
+        buf.put_slice(self.key.as_slice());
+        self.value.to_compact(buf) + 52
+    }
+
+    fn from_compact(mut buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let address = Address::from_slice(&buf[..20]);
+        let key = B256::from_slice(&buf[20..52]);
+        buf.advance(52);
+
+        let (value, buf) = U256::from_compact(buf, len - 52);
+        (Self { address, key, value }, buf)
+    }
+}
+
+/// All account changes of a block, sorted by address like in the `AccountChangeSets` table.
+///
+/// Row of the account changesets static files. Entries are encoded after a table of their end
+/// offsets, so a single entry can be looked up in the encoded row with a binary search, without
+/// decoding the rest of the changeset.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+pub struct AccountChangeSet(pub Vec<AccountBeforeTx>);
+
+impl AccountChangeSet {
+    /// Returns the change of the address in an encoded changeset.
+    pub fn find_encoded(buf: &[u8], address: Address) -> Option<AccountBeforeTx> {
+        let entries = EncodedEntries::new(buf);
+        let index = entries.partition_point(|entry| entry[..20] < *address.as_slice());
+        (index < entries.len())
+            .then(|| entries.get(index))
+            .filter(|entry| entry[..20] == *address.as_slice())
+            .map(|entry| AccountBeforeTx::from_compact(entry, entry.len()).0)
+    }
+
+    /// Decodes the changes of an encoded changeset for which `filter` returns `true`.
+    ///
+    /// Entries of other addresses are skipped without being decoded.
+    pub fn decode_encoded_filtered(
+        buf: &[u8],
+        mut filter: impl FnMut(&Address) -> bool,
+    ) -> Vec<AccountBeforeTx> {
+        EncodedEntries::new(buf)
+            .iter()
+            .filter(|entry| filter(&Address::from_slice(&entry[..20])))
+            .map(|entry| AccountBeforeTx::from_compact(entry, entry.len()).0)
+            .collect()
+    }
+}
+
+impl Compact for AccountChangeSet {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        debug_assert!(self.0.is_sorted_by_key(|change| change.address));
+        EncodedEntries::encode(&self.0, buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let changes = EncodedEntries::new(&buf[..len])
+            .iter()
+            .map(|entry| AccountBeforeTx::from_compact(entry, entry.len()).0)
+            .collect();
+        (Self(changes), &buf[len..])
+    }
+}
+
+/// All storage changes of a block, sorted by address and storage key like in the
+/// `StorageChangeSets` table.
+///
+/// Row of the storage changesets static files, encoded like an [`AccountChangeSet`].
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+pub struct StorageChangeSet(pub Vec<StorageBeforeTx>);
+
+impl StorageChangeSet {
+    /// Returns the change of the storage slot in an encoded changeset.
+    pub fn find_encoded(buf: &[u8], address: Address, key: B256) -> Option<StorageBeforeTx> {
+        let entries = EncodedEntries::new(buf);
+        let slot = [address.as_slice(), key.as_slice()].concat();
+        let index = entries.partition_point(|entry| entry[..52] < *slot);
+        (index < entries.len())
+            .then(|| entries.get(index))
+            .filter(|entry| entry[..52] == *slot)
+            .map(|entry| StorageBeforeTx::from_compact(entry, entry.len()).0)
+    }
+
+    /// Returns all storage changes of the address in an encoded changeset.
+    pub fn account_encoded(buf: &[u8], address: Address) -> Vec<StorageBeforeTx> {
+        let entries = EncodedEntries::new(buf);
+        let start = entries.partition_point(|entry| entry[..20] < *address.as_slice());
+        let end = entries.partition_point(|entry| entry[..20] <= *address.as_slice());
+        (start..end)
+            .map(|index| {
+                let entry = entries.get(index);
+                StorageBeforeTx::from_compact(entry, entry.len()).0
+            })
+            .collect()
+    }
+
+    /// Decodes the changes of an encoded changeset for which `filter` returns `true`.
+    ///
+    /// Entries of other storage slots are skipped without being decoded.
+    pub fn decode_encoded_filtered(
+        buf: &[u8],
+        mut filter: impl FnMut(&Address, &B256) -> bool,
+    ) -> Vec<StorageBeforeTx> {
+        EncodedEntries::new(buf)
+            .iter()
+            .filter(|entry| {
+                filter(&Address::from_slice(&entry[..20]), &B256::from_slice(&entry[20..52]))
+            })
+            .map(|entry| StorageBeforeTx::from_compact(entry, entry.len()).0)
+            .collect()
+    }
+}
+
+impl Compact for StorageChangeSet {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        debug_assert!(self.0.is_sorted_by_key(|change| (change.address, change.key)));
+        EncodedEntries::encode(&self.0, buf)
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let changes = EncodedEntries::new(&buf[..len])
+            .iter()
+            .map(|entry| StorageBeforeTx::from_compact(entry, entry.len()).0)
+            .collect();
+        (Self(changes), &buf[len..])
+    }
+}
+
+/// Entries of an encoded changeset.
+///
+/// The encoding is the number of entries, followed by the end offset of every entry and the
+/// entries themselves. Numbers and offsets are big endian `u32`s, and offsets are relative to the
+/// first entry.
+struct EncodedEntries<'a> {
+    /// End offsets of the entries.
+    ends: &'a [u8],
+    /// The encoded entries.
+    data: &'a [u8],
+}
+
+impl<'a> EncodedEntries<'a> {
+    /// Splits an encoded changeset into its offsets and entries.
+    fn new(buf: &'a [u8]) -> Self {
+        if buf.is_empty() {
+            return Self { ends: &[], data: &[] }
+        }
+        let len = u32::from_be_bytes(buf[..4].try_into().expect("4 bytes")) as usize;
+        let (ends, data) = buf[4..].split_at(len * 4);
+        Self { ends, data }
+    }
+
+    /// Encodes the entries into the buffer and returns the number of written bytes.
+    fn encode<T: Compact, B: bytes::BufMut>(entries: &[T], buf: &mut B) -> usize {
+        let mut data = Vec::new();
+        let ends = entries
+            .iter()
+            .map(|entry| {
+                entry.to_compact(&mut data);
+                data.len() as u32
+            })
+            .collect::<Vec<_>>();
+
+        buf.put_u32(entries.len() as u32);
+        for end in &ends {
+            buf.put_u32(*end);
+        }
+        buf.put_slice(&data);
+        4 + ends.len() * 4 + data.len()
+    }
+
+    /// Returns the number of entries.
+    const fn len(&self) -> usize {
+        self.ends.len() / 4
+    }
+
+    /// Returns the end offset of the entry at the index.
+    fn end(&self, index: usize) -> usize {
+        u32::from_be_bytes(self.ends[index * 4..index * 4 + 4].try_into().expect("4 bytes"))
+            as usize
+    }
+
+    /// Returns the encoded entry at the index.
+    fn get(&self, index: usize) -> &'a [u8] {
+        let start = if index == 0 { 0 } else { self.end(index - 1) };
+        &self.data[start..self.end(index)]
+    }
+
+    /// Returns an iterator over the encoded entries.
+    fn iter(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+
+    /// Returns the index of the first entry for which `pred` returns `false`, see
+    /// [`slice::partition_point`].
+    fn partition_point(&self, pred: impl Fn(&[u8]) -> bool) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if pred(self.get(mid)) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn change_sets_roundtrip() {
+        let accounts = AccountChangeSet(vec![
+            AccountBeforeTx { address: Address::with_last_byte(1), info: None },
+            AccountBeforeTx {
+                address: Address::with_last_byte(2),
+                info: Some(Account { nonce: 1, ..Default::default() }),
+            },
+        ]);
+        let mut buf = Vec::new();
+        let len = accounts.to_compact(&mut buf);
+        assert_eq!(AccountChangeSet::from_compact(&buf, len).0, accounts);
+
+        let storages = StorageChangeSet(vec![
+            StorageBeforeTx {
+                address: Address::with_last_byte(1),
+                key: B256::with_last_byte(1),
+                value: U256::ZERO,
+            },
+            StorageBeforeTx {
+                address: Address::with_last_byte(1),
+                key: B256::with_last_byte(2),
+                value: U256::MAX,
+            },
+        ]);
+        let mut buf = Vec::new();
+        let len = storages.to_compact(&mut buf);
+        assert_eq!(StorageChangeSet::from_compact(&buf, len).0, storages);
+    }
+
+    #[test]
+    fn change_sets_lookup() {
+        let accounts = AccountChangeSet(
+            (1..=10)
+                .map(|byte| AccountBeforeTx {
+                    address: Address::with_last_byte(byte * 2),
+                    info: (byte % 2 == 0)
+                        .then(|| Account { nonce: u64::from(byte), ..Default::default() }),
+                })
+                .collect(),
+        );
+        let mut buf = Vec::new();
+        accounts.to_compact(&mut buf);
+        for change in &accounts.0 {
+            assert_eq!(AccountChangeSet::find_encoded(&buf, change.address).as_ref(), Some(change));
+        }
+        assert_eq!(AccountChangeSet::find_encoded(&buf, Address::with_last_byte(3)), None);
+        assert_eq!(AccountChangeSet::find_encoded(&buf, Address::with_last_byte(21)), None);
+        assert_eq!(
+            AccountChangeSet::decode_encoded_filtered(&buf, |address| address.0[19] > 16),
+            accounts.0[8..]
+        );
+        assert_eq!(AccountChangeSet::find_encoded(&[], Address::ZERO), None);
+
+        let storages = StorageChangeSet(
+            (1..=3)
+                .flat_map(|address| {
+                    (1..=3).map(move |key| StorageBeforeTx {
+                        address: Address::with_last_byte(address),
+                        key: B256::with_last_byte(key),
+                        value: U256::from(address * key),
+                    })
+                })
+                .collect(),
+        );
+        let mut buf = Vec::new();
+        storages.to_compact(&mut buf);
+        for change in &storages.0 {
+            assert_eq!(
+                StorageChangeSet::find_encoded(&buf, change.address, change.key).as_ref(),
+                Some(change)
+            );
+        }
+        assert_eq!(
+            StorageChangeSet::find_encoded(&buf, Address::with_last_byte(2), B256::ZERO),
+            None
+        );
+        assert_eq!(
+            StorageChangeSet::account_encoded(&buf, Address::with_last_byte(2)),
+            storages.0[3..6]
+        );
+        assert!(StorageChangeSet::account_encoded(&buf, Address::with_last_byte(4)).is_empty());
+        assert_eq!(
+            StorageChangeSet::decode_encoded_filtered(&buf, |_, key| key.0[31] == 1),
+            vec![storages.0[0], storages.0[3], storages.0[6]]
+        );
+    }
+}
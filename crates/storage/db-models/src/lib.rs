@@ -2,7 +2,7 @@
 
 /// Accounts
 pub mod accounts;
-pub use accounts::AccountBeforeTx;
+pub use accounts::{AccountBeforeTx, AccountChangeSet, StorageBeforeTx, StorageChangeSet};
 
 /// Blocks
 pub mod blocks;
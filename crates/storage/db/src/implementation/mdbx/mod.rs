@@ -189,6 +189,10 @@ impl Database for DatabaseEnv {
         )
         .map_err(|e| DatabaseError::InitTx(e.into()))
     }
+
+    fn copy_to(&self, path: &Path, compact: bool) -> Result<(), DatabaseError> {
+        self.inner.copy(path, compact).map_err(|e| DatabaseError::Copy(e.into()))
+    }
 }
 
 impl DatabaseMetrics for DatabaseEnv {
@@ -325,6 +325,15 @@ impl<K: TransactionKind> DbTx for Tx<K> {
             .entries())
     }
 
+    fn table_size<T: Table>(&self) -> Result<u64, DatabaseError> {
+        let stats = self
+            .inner
+            .db_stat_with_dbi(self.get_dbi::<T>()?)
+            .map_err(|e| DatabaseError::Stats(e.into()))?;
+        let pages = stats.branch_pages() + stats.leaf_pages() + stats.overflow_pages();
+        Ok(stats.page_size() as u64 * pages as u64)
+    }
+
     /// Disables long-lived read transaction safety guarantees, such as backtrace recording and
     /// timeout.
     fn disable_long_read_transaction_safety(&mut self) {
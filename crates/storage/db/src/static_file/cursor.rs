@@ -23,13 +23,17 @@ impl<'a> StaticFileCursor<'a> {
         ))
     }
 
-    /// Returns the current `BlockNumber` or `TxNumber` of the cursor depending on the kind of
-    /// static file segment.
+    /// Returns the `BlockNumber` or `TxNumber` of the row last read by the cursor depending on the
+    /// kind of static file segment.
     pub fn number(&self) -> Option<u64> {
-        self.jar().user_header().start().map(|start| self.row_index() + start)
+        let row = self.row_index().checked_sub(1)?;
+        self.jar().user_header().start().map(|start| row + start)
     }
 
     /// Gets a row of values.
+    ///
+    /// A row looked up by key is the first row with the fingerprint of the key, use
+    /// [`Self::get_one_by_key`] to compare the candidate rows with the key.
     pub fn get(
         &mut self,
         key_or_num: KeyOrNumber<'_>,
@@ -40,7 +44,7 @@ impl<'a> StaticFileCursor<'a> {
         }
 
         let row = match key_or_num {
-            KeyOrNumber::Key(_) => unimplemented!(),
+            KeyOrNumber::Key(key) => self.row_by_key_with_cols(key, mask, |_| true),
             KeyOrNumber::Number(n) => match self.jar().user_header().start() {
                 Some(offset) => {
                     if offset > n {
@@ -69,6 +73,31 @@ impl<'a> StaticFileCursor<'a> {
         }
     }
 
+    /// Gets one column value from the first row with the given key whose value matches.
+    ///
+    /// Rows are looked up by the fingerprint of the key, so the value of every candidate row is
+    /// passed to `is_match` to compare it with the key.
+    pub fn get_one_by_key<M: ColumnSelectorOne>(
+        &mut self,
+        key: &[u8],
+        mut is_match: impl FnMut(&M::FIRST) -> bool,
+    ) -> ColumnResult<M::FIRST> {
+        if self.jar().rows() == 0 {
+            return Ok(None)
+        }
+
+        let row = self
+            .row_by_key_with_cols(key, M::MASK, |row| {
+                M::FIRST::decompress(row[0]).is_ok_and(|value| is_match(&value))
+            })
+            .map_err(|err| ProviderError::NippyJar(err.to_string()))?;
+
+        match row {
+            Some(row) => Ok(Some(M::FIRST::decompress(row[0])?)),
+            None => Ok(None),
+        }
+    }
+
     /// Gets two column values from a row.
     pub fn get_two<M: ColumnSelectorTwo>(
         &mut self,
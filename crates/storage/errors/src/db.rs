@@ -42,6 +42,9 @@ pub enum DatabaseError {
     /// Failed to get database stats.
     #[display("failed to get stats: {_0}")]
     Stats(DatabaseErrorInfo),
+    /// Failed to copy the database.
+    #[display("failed to copy the database: {_0}")]
+    Copy(DatabaseErrorInfo),
     /// Failed to use the specified log level, as it's not available.
     #[display("log level {_0:?} is not available")]
     LogLevelUnavailable(LogLevel),
@@ -154,6 +154,22 @@ impl Environment {
         mdbx_result(unsafe { ffi::mdbx_env_sync_ex(self.env_ptr(), force, false) })
     }
 
+    /// Copies the environment into a new database file at `path`.
+    ///
+    /// The copy is a consistent snapshot taken inside a read-only transaction, so it can be made
+    /// while the environment is written to. The file must not exist, but its parent directory
+    /// must. If `compact` is set, free pages are omitted and all pages are renumbered
+    /// sequentially in the copy.
+    ///
+    /// Note: the read-only transaction is kept open for the duration of the copy, which can lead
+    /// to significant file size growth if the environment is written to in parallel.
+    pub fn copy(&self, path: &Path, compact: bool) -> Result<()> {
+        let path = path_to_cstring(path)?;
+        let flags = if compact { ffi::MDBX_CP_COMPACT } else { ffi::MDBX_CP_DEFAULTS };
+        mdbx_result(unsafe { ffi::mdbx_env_copy(self.env_ptr(), path.as_ptr(), flags) })?;
+        Ok(())
+    }
+
     /// Retrieves statistics about this environment.
     pub fn stat(&self) -> Result<Stat> {
         unsafe {
@@ -690,24 +706,7 @@ impl EnvironmentBuilder {
                     ))?;
                 }
 
-                #[cfg(unix)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    use std::os::unix::ffi::OsStrExt;
-                    path.as_ref().as_os_str().as_bytes().to_vec()
-                }
-
-                #[cfg(windows)]
-                fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
-                    // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
-                    // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
-                    // really help.
-                    path.as_ref().to_string_lossy().to_string().into_bytes()
-                }
-
-                let path = match CString::new(path_to_bytes(path)) {
-                    Ok(path) => path,
-                    Err(_) => return Err(Error::Invalid),
-                };
+                let path = path_to_cstring(path)?;
                 mdbx_result(ffi::mdbx_env_open(
                     env,
                     path.as_ptr(),
@@ -919,6 +918,27 @@ fn convert_hsr_fn(callback: Option<HandleSlowReadersCallback>) -> ffi::MDBX_hsr_
     unsafe { std::mem::transmute(callback) }
 }
 
+/// Converts a path into a C string that can be passed to MDBX.
+///
+/// The path may not contain the null character.
+fn path_to_cstring(path: &Path) -> Result<CString> {
+    #[cfg(unix)]
+    fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_ref().as_os_str().as_bytes().to_vec()
+    }
+
+    #[cfg(windows)]
+    fn path_to_bytes<P: AsRef<Path>>(path: P) -> Vec<u8> {
+        // On Windows, could use std::os::windows::ffi::OsStrExt to encode_wide(),
+        // but we end up with a Vec<u16> instead of a Vec<u8>, so that doesn't
+        // really help.
+        path.as_ref().to_string_lossy().to_string().into_bytes()
+    }
+
+    CString::new(path_to_bytes(path)).map_err(|_| Error::Invalid)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Environment, Error, Geometry, HandleSlowReadersReturnCode, PageSize, WriteFlags};
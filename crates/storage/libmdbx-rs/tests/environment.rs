@@ -82,6 +82,28 @@ fn test_sync() {
     }
 }
 
+#[test]
+fn test_copy() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    let tx = env.begin_rw_txn().unwrap();
+    tx.put(tx.open_db(None).unwrap().dbi(), b"key", b"value", WriteFlags::default()).unwrap();
+    tx.commit().unwrap();
+
+    let copy_dir = tempdir().unwrap();
+    let copy_path = copy_dir.path().join("mdbx.dat");
+    env.copy(&copy_path, true).unwrap();
+
+    // the destination file must not exist yet
+    env.copy(&copy_path, true).unwrap_err();
+
+    let copy = Environment::builder().set_flags(Mode::ReadOnly.into()).open(copy_dir.path()).unwrap();
+    let tx = copy.begin_ro_txn().unwrap();
+    let value: Option<Vec<u8>> = tx.get(tx.open_db(None).unwrap().dbi(), b"key").unwrap();
+    assert_eq!(value.as_deref(), Some(&b"value"[..]));
+}
+
 #[test]
 fn test_stat() {
     let dir = tempdir().unwrap();
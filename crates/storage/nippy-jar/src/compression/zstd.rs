@@ -70,8 +70,8 @@ impl Zstd {
         Ok(vec![])
     }
 
-    /// If using dictionaries, creates a list of [`Compressor`].
-    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'_>>>, NippyJarError> {
+    /// If using dictionaries, creates a list of [`Compressor`], one per column.
+    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'static>>>, NippyJarError> {
         match self.state {
             ZstdState::PendingDictionary => Err(NippyJarError::CompressorNotReady),
             ZstdState::Ready => {
@@ -81,7 +81,7 @@ impl Zstd {
 
                 if let Some(dictionaries) = &self.dictionaries {
                     debug!(target: "nippy-jar", count=?dictionaries.len(), "Generating ZSTD compressor dictionaries.");
-                    return Ok(Some(dictionaries.compressors()?))
+                    return Ok(Some(dictionaries.compressors(self.level)?))
                 }
                 Ok(None)
             }
@@ -189,7 +189,6 @@ impl Compression for Zstd {
         matches!(self.state, ZstdState::Ready)
     }
 
-    #[cfg(test)]
     /// If using it with dictionaries, prepares a dictionary for each column.
     fn prepare_compression(
         &mut self,
@@ -261,7 +260,7 @@ mod dictionaries_serde {
         D: Deserializer<'de>,
     {
         let dictionaries: Option<Vec<RawDictionary>> = Option::deserialize(deserializer)?;
-        Ok(dictionaries.map(|dicts| Arc::new(ZstdDictionaries::load(dicts))))
+        Ok(dictionaries.map(|dicts| Arc::new(ZstdDictionaries::new(dicts))))
     }
 }
 
@@ -277,69 +276,53 @@ impl std::fmt::Debug for ZstdDictionaries<'_> {
 }
 
 impl ZstdDictionaries<'_> {
-    #[cfg(test)]
-    /// Creates [`ZstdDictionaries`].
+    /// Creates [`ZstdDictionaries`] from a list of [`RawDictionary`], one per column.
     pub(crate) fn new(raw: Vec<RawDictionary>) -> Self {
-        Self(raw.into_iter().map(ZstdDictionary::Raw).collect())
+        Self(raw.into_iter().map(ZstdDictionary::new).collect())
     }
 
-    /// Loads a list [`RawDictionary`] into a list of [`ZstdDictionary::Loaded`].
-    pub(crate) fn load(raw: Vec<RawDictionary>) -> Self {
-        Self(
-            raw.into_iter()
-                .map(|dict| ZstdDictionary::Loaded(DecoderDictionary::copy(&dict)))
-                .collect(),
-        )
-    }
-
-    /// Creates a list of decompressors from a list of [`ZstdDictionary::Loaded`].
+    /// Creates a list of decompressors, one per column.
     pub(crate) fn decompressors(&self) -> Result<Vec<Decompressor<'_>>, NippyJarError> {
         Ok(self
             .iter()
-            .flat_map(|dict| {
-                dict.loaded()
-                    .ok_or(NippyJarError::DictionaryNotLoaded)
-                    .map(Decompressor::with_prepared_dictionary)
-            })
+            .map(|dict| Decompressor::with_prepared_dictionary(dict.loaded()))
             .collect::<Result<Vec<_>, _>>()?)
     }
 
-    /// Creates a list of compressors from a list of [`ZstdDictionary::Raw`].
-    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'_>>, NippyJarError> {
+    /// Creates a list of compressors with the given compression level, one per column.
+    pub(crate) fn compressors(
+        &self,
+        level: i32,
+    ) -> Result<Vec<Compressor<'static>>, NippyJarError> {
         Ok(self
             .iter()
-            .flat_map(|dict| {
-                dict.raw()
-                    .ok_or(NippyJarError::CompressorNotAllowed)
-                    .map(|dict| Compressor::with_dictionary(0, dict))
-            })
+            .map(|dict| Compressor::with_dictionary(level, dict.raw()))
             .collect::<Result<Vec<_>, _>>()?)
     }
 }
 
-/// A Zstd dictionary. It's created and serialized with [`ZstdDictionary::Raw`], and deserialized as
-/// [`ZstdDictionary::Loaded`].
-pub(crate) enum ZstdDictionary<'a> {
-    #[allow(dead_code)]
-    Raw(RawDictionary),
-    Loaded(DecoderDictionary<'a>),
+/// A Zstd dictionary. It keeps the [`RawDictionary`] it's serialized as, which compressors are
+/// created from, alongside the [`DecoderDictionary`] that is loaded from it for decompression.
+pub(crate) struct ZstdDictionary<'a> {
+    raw: RawDictionary,
+    loaded: DecoderDictionary<'a>,
 }
 
 impl ZstdDictionary<'_> {
-    /// Returns a reference to the expected `RawDictionary`
-    pub(crate) const fn raw(&self) -> Option<&RawDictionary> {
-        match self {
-            ZstdDictionary::Raw(dict) => Some(dict),
-            ZstdDictionary::Loaded(_) => None,
-        }
+    /// Creates a [`ZstdDictionary`] and loads its [`DecoderDictionary`].
+    pub(crate) fn new(raw: RawDictionary) -> Self {
+        let loaded = DecoderDictionary::copy(&raw);
+        Self { raw, loaded }
     }
 
-    /// Returns a reference to the expected `DecoderDictionary`
-    pub(crate) const fn loaded(&self) -> Option<&DecoderDictionary<'_>> {
-        match self {
-            ZstdDictionary::Raw(_) => None,
-            ZstdDictionary::Loaded(dict) => Some(dict),
-        }
+    /// Returns a reference to the `RawDictionary`
+    pub(crate) const fn raw(&self) -> &RawDictionary {
+        &self.raw
+    }
+
+    /// Returns a reference to the loaded `DecoderDictionary`
+    pub(crate) const fn loaded(&self) -> &DecoderDictionary<'_> {
+        &self.loaded
     }
 }
 
@@ -348,8 +331,7 @@ impl<'de> Deserialize<'de> for ZstdDictionary<'_> {
     where
         D: Deserializer<'de>,
     {
-        let dict = RawDictionary::deserialize(deserializer)?;
-        Ok(Self::Loaded(DecoderDictionary::copy(&dict)))
+        Ok(Self::new(RawDictionary::deserialize(deserializer)?))
     }
 }
 
@@ -358,19 +340,13 @@ impl Serialize for ZstdDictionary<'_> {
     where
         S: Serializer,
     {
-        match self {
-            ZstdDictionary::Raw(r) => r.serialize(serializer),
-            ZstdDictionary::Loaded(_) => unreachable!(),
-        }
+        self.raw.serialize(serializer)
     }
 }
 
 #[cfg(test)]
 impl PartialEq for ZstdDictionary<'_> {
     fn eq(&self, other: &Self) -> bool {
-        if let (Self::Raw(a), Self::Raw(b)) = (self, &other) {
-            return a == b
-        }
-        unimplemented!("`DecoderDictionary` can't be compared. So comparison should be done after decompressing a value.");
+        self.raw == other.raw
     }
 }
@@ -1,6 +1,6 @@
 use crate::{
     compression::{Compression, Compressors, Zstd},
-    DataReader, NippyJar, NippyJarError, NippyJarHeader, RefRow,
+    DataReader, NippyJar, NippyJarError, NippyJarHeader, NippyJarIndex, RefRow,
 };
 use std::{ops::Range, sync::Arc};
 use zstd::bulk::Decompressor;
@@ -112,6 +112,29 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
         self.next_row_with_cols(mask)
     }
 
+    /// Returns a row by its key by using a `mask` to only read certain columns from the row.
+    ///
+    /// The rows are looked up by the fingerprint of the key in the jar index, so every candidate
+    /// row is passed to `is_match` to compare its stored value with the key, and the first
+    /// matching one is returned. Returns `None` if the jar has no index.
+    pub fn row_by_key_with_cols(
+        &mut self,
+        key: &[u8],
+        mask: usize,
+        mut is_match: impl FnMut(&[&[u8]]) -> bool,
+    ) -> Result<Option<RefRow<'_>>, NippyJarError> {
+        let Some(index) = self.reader.index() else { return Ok(None) };
+        let candidates = index.rows(NippyJarIndex::fingerprint(key)).collect::<Vec<_>>();
+
+        for row in candidates {
+            if self.row_by_number_with_cols(row as usize, mask)?.is_some_and(|row| is_match(&row)) {
+                return self.row_by_number_with_cols(row as usize, mask)
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Returns the current value and advances the row.
     ///
     /// Uses a `mask` to only read certain columns from the row.
@@ -168,10 +191,8 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
                     // If we are here, then for sure we have the necessary dictionaries and they're
                     // loaded (happens during deserialization). Otherwise, there's an issue
                     // somewhere else and we can't recover here anyway.
-                    let dictionaries = z.dictionaries.as_ref().expect("dictionaries to exist")
-                        [column]
-                        .loaded()
-                        .expect("dictionary to be loaded");
+                    let dictionaries =
+                        z.dictionaries.as_ref().expect("dictionaries to exist")[column].loaded();
                     let mut decompressor = Decompressor::with_prepared_dictionary(dictionaries)?;
                     Zstd::decompress_with_dictionary(
                         self.reader.data(column_offset_range),
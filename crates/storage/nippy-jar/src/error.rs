@@ -89,6 +89,10 @@ pub enum NippyJarError {
     #[error("File is in an inconsistent state.")]
     InconsistentState,
 
+    /// The index file is malformed.
+    #[error("index file is malformed.")]
+    InvalidIndex,
+
     /// A specified file is missing.
     #[error("Missing file: {0}.")]
     MissingFile(PathBuf),
@@ -0,0 +1,139 @@
+//! Fingerprint index over the rows of a [`NippyJar`](crate::NippyJar).
+//!
+//! The index file holds a fan-out table of 256 little endian `u64` cumulative entry counts, one
+//! per leading byte of the fingerprint, followed by the `(fingerprint, row)` entries sorted by
+//! fingerprint, each as two little endian `u64`s.
+
+use crate::NippyJarError;
+use memmap2::Mmap;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Number of fan-out buckets, one per leading fingerprint byte.
+const FANOUT_LEN: usize = 256;
+/// Size of the fan-out table in bytes.
+const FANOUT_SIZE: usize = FANOUT_LEN * 8;
+/// Size of one `(fingerprint, row)` entry in bytes.
+const ENTRY_SIZE: usize = 16;
+
+/// Read-only index that maps key fingerprints to the rows holding them.
+///
+/// Fingerprints are not unique, so a lookup returns candidate rows whose value needs to be
+/// compared with the requested key.
+#[derive(Debug)]
+pub struct NippyJarIndex {
+    /// Index file descriptor. Needs to be kept alive as long as `mmap` handle.
+    #[allow(dead_code)]
+    file: File,
+    /// Mmap handle for the index.
+    mmap: Mmap,
+}
+
+impl NippyJarIndex {
+    /// Returns the fingerprint of a key, which are its first 8 bytes interpreted as a big endian
+    /// `u64`. Shorter keys are right padded with zeroes.
+    pub fn fingerprint(key: &[u8]) -> u64 {
+        let mut buf = [0u8; 8];
+        let len = key.len().min(8);
+        buf[..len].copy_from_slice(&key[..len]);
+        u64::from_be_bytes(buf)
+    }
+
+    /// Writes an index of the given `(fingerprint, row)` entries to `path`.
+    pub fn write(path: &Path, mut entries: Vec<(u64, u64)>) -> Result<(), NippyJarError> {
+        entries.sort_unstable();
+
+        let mut fanout = [0u64; FANOUT_LEN];
+        for (fingerprint, _) in &entries {
+            fanout[(fingerprint >> 56) as usize] += 1;
+        }
+        for bucket in 1..FANOUT_LEN {
+            fanout[bucket] += fanout[bucket - 1];
+        }
+
+        Ok(reth_fs_util::atomic_write_file(path, |file| {
+            let mut writer = BufWriter::new(file);
+            for count in fanout {
+                writer.write_all(&count.to_le_bytes())?;
+            }
+            for (fingerprint, row) in entries {
+                writer.write_all(&fingerprint.to_le_bytes())?;
+                writer.write_all(&row.to_le_bytes())?;
+            }
+            writer.flush()
+        })?)
+    }
+
+    /// Opens the index at `path`.
+    pub fn open(path: &Path) -> Result<Self, NippyJarError> {
+        let file = File::open(path)?;
+        // SAFETY: File is read-only and its descriptor is kept alive as long as the mmap handle.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let index = Self { file, mmap };
+        if index.mmap.len() < FANOUT_SIZE ||
+            index.len().checked_mul(ENTRY_SIZE).and_then(|len| len.checked_add(FANOUT_SIZE)) !=
+                Some(index.mmap.len()) ||
+            (1..FANOUT_LEN).any(|bucket| index.fanout(bucket - 1) > index.fanout(bucket))
+        {
+            return Err(NippyJarError::InvalidIndex)
+        }
+
+        Ok(index)
+    }
+
+    /// Returns the number of entries in the index.
+    pub fn len(&self) -> usize {
+        if self.mmap.len() < FANOUT_SIZE {
+            return 0
+        }
+        self.fanout(FANOUT_LEN - 1) as usize
+    }
+
+    /// Returns `true` if the index has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the rows of all entries with the given fingerprint, in ascending order.
+    pub fn rows(&self, fingerprint: u64) -> impl Iterator<Item = u64> + '_ {
+        let bucket = (fingerprint >> 56) as usize;
+        let start = if bucket == 0 { 0 } else { self.fanout(bucket - 1) as usize };
+        let end = self.fanout(bucket) as usize;
+
+        // first entry in the bucket with a fingerprint that is not lower than the requested one
+        let (mut low, mut high) = (start, end);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.entry(mid).0 < fingerprint {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        (low..end)
+            .map(|position| self.entry(position))
+            .map_while(move |(other, row)| (other == fingerprint).then_some(row))
+    }
+
+    /// Reads the cumulative entry count of the fan-out bucket.
+    fn fanout(&self, bucket: usize) -> u64 {
+        self.read_u64(bucket * 8)
+    }
+
+    /// Reads the `(fingerprint, row)` entry at the position.
+    fn entry(&self, position: usize) -> (u64, u64) {
+        let offset = FANOUT_SIZE + position * ENTRY_SIZE;
+        (self.read_u64(offset), self.read_u64(offset + 8))
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&self.mmap[offset..offset + 8]);
+        u64::from_le_bytes(buf)
+    }
+}
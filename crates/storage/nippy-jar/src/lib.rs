@@ -29,9 +29,7 @@ use tracing::*;
 
 /// Compression algorithms supported by `NippyJar`.
 pub mod compression;
-#[cfg(test)]
-use compression::Compression;
-use compression::Compressors;
+use compression::{Compression, Compressors};
 
 /// empty enum for backwards compatibility
 #[derive(Debug, Serialize, Deserialize)]
@@ -55,12 +53,15 @@ pub use writer::NippyJarWriter;
 mod consistency;
 pub use consistency::NippyJarChecker;
 
+mod index;
+pub use index::NippyJarIndex;
+
 /// The version number of the Nippy Jar format.
 const NIPPY_JAR_VERSION: usize = 1;
 /// The file extension used for index files.
-const INDEX_FILE_EXTENSION: &str = "idx";
+pub const INDEX_FILE_EXTENSION: &str = "idx";
 /// The file extension used for offsets files.
-const OFFSETS_FILE_EXTENSION: &str = "off";
+pub const OFFSETS_FILE_EXTENSION: &str = "off";
 /// The file extension used for configuration files.
 pub const CONFIG_FILE_EXTENSION: &str = "conf";
 
@@ -251,16 +252,19 @@ impl<H: NippyJarHeader> NippyJar<H> {
         DataReader::new(self.data_path())
     }
 
-    /// Writes all necessary configuration to file.
-    fn freeze_config(&self) -> Result<(), NippyJarError> {
-        Ok(reth_fs_util::atomic_write_file(&self.config_path(), |file| {
-            bincode::serialize_into(file, &self)
-        })?)
+    /// Writes a [`NippyJarIndex`] of the given `(fingerprint, row)` entries to the index file.
+    ///
+    /// Data readers opened afterwards resolve keys with
+    /// [`NippyJarCursor::row_by_key_with_cols`].
+    pub fn write_index(&self, entries: Vec<(u64, u64)>) -> Result<(), NippyJarError> {
+        NippyJarIndex::write(&self.index_path(), entries)
+    }
+
+    /// Returns `true` if the jar has an index file.
+    pub fn has_index(&self) -> bool {
+        self.index_path().exists()
     }
-}
 
-#[cfg(test)]
-impl<H: NippyJarHeader> NippyJar<H> {
     /// If required, prepares any compression algorithm to an early pass of the data.
     pub fn prepare_compression(
         &mut self,
@@ -274,6 +278,16 @@ impl<H: NippyJarHeader> NippyJar<H> {
         Ok(())
     }
 
+    /// Writes all necessary configuration to file.
+    fn freeze_config(&self) -> Result<(), NippyJarError> {
+        Ok(reth_fs_util::atomic_write_file(&self.config_path(), |file| {
+            bincode::serialize_into(file, &self)
+        })?)
+    }
+}
+
+#[cfg(test)]
+impl<H: NippyJarHeader> NippyJar<H> {
     /// Writes all data and configuration to a file and the offset index to another.
     pub fn freeze(
         self,
@@ -319,7 +333,8 @@ impl<H: NippyJarHeader> NippyJar<H> {
 
 /// Manages the reading of static file data using memory-mapped files.
 ///
-/// Holds file and mmap descriptors of the data and offsets files of a `static_file`.
+/// Holds file and mmap descriptors of the data and offsets files of a `static_file`, and of its
+/// index file if there is one.
 #[derive(Debug)]
 pub struct DataReader {
     /// Data file descriptor. Needs to be kept alive as long as `data_mmap` handle.
@@ -334,6 +349,8 @@ pub struct DataReader {
     offset_mmap: Mmap,
     /// Number of bytes that represent one offset.
     offset_size: u8,
+    /// Optional index of key fingerprints to rows.
+    index: Option<NippyJarIndex>,
 }
 
 impl DataReader {
@@ -357,7 +374,15 @@ impl DataReader {
             return Err(NippyJarError::OffsetSizeTooSmall { offset_size })
         }
 
-        Ok(Self { data_file, data_mmap, offset_file, offset_size, offset_mmap })
+        let index_path = path.as_ref().with_extension(INDEX_FILE_EXTENSION);
+        let index = index_path.exists().then(|| NippyJarIndex::open(&index_path)).transpose()?;
+
+        Ok(Self { data_file, data_mmap, offset_file, offset_size, offset_mmap, index })
+    }
+
+    /// Returns the index of key fingerprints to rows, if the jar has one.
+    pub const fn index(&self) -> Option<&NippyJarIndex> {
+        self.index.as_ref()
     }
 
     /// Returns the offset for the requested data index
@@ -551,6 +576,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_zstd_with_dictionaries_append() {
+        let (col1, col2) = test_data(None);
+        let half = col1.len() / 2;
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let mut nippy =
+            NippyJar::new_without_header(num_columns, file_path.path()).with_zstd(true, 5000);
+        nippy.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+        nippy
+            .freeze(
+                vec![
+                    clone_with_result(&col1[..half].to_vec()),
+                    clone_with_result(&col2[..half].to_vec()),
+                ],
+                half as u64,
+            )
+            .unwrap();
+
+        // Dictionaries of a loaded jar can be used to append more rows.
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut writer = NippyJarWriter::new(loaded_nippy).unwrap();
+        writer
+            .append_rows(
+                vec![
+                    clone_with_result(&col1[half..].to_vec()),
+                    clone_with_result(&col2[half..].to_vec()),
+                ],
+                (col1.len() - half) as u64,
+            )
+            .unwrap();
+        writer.commit().unwrap();
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows(), col1.len());
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        let mut row_index = 0usize;
+        while let Some(row) = cursor.next_row().unwrap() {
+            assert_eq!((row[0], row[1]), (col1[row_index].as_slice(), col2[row_index].as_slice()));
+            row_index += 1;
+        }
+        assert_eq!(row_index, col1.len());
+    }
+
     #[test]
     fn test_lz4() {
         let (col1, col2) = test_data(None);
@@ -769,6 +839,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_index() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let nippy = NippyJar::new_without_header(num_columns, file_path.path())
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        assert!(!nippy.has_index());
+
+        // Without an index, keys can't be resolved
+        {
+            let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+            assert!(cursor.row_by_key_with_cols(&col1[0], 0b11, |_| true).unwrap().is_none());
+        }
+
+        // Index the first column, and add a colliding fingerprint of the second row for the first
+        let mut entries = col1
+            .iter()
+            .enumerate()
+            .map(|(row, key)| (NippyJarIndex::fingerprint(key), row as u64))
+            .collect::<Vec<_>>();
+        let colliding = NippyJarIndex::fingerprint(&col1[1]);
+        entries.push((colliding, 0));
+        nippy.write_index(entries).unwrap();
+        assert!(nippy.has_index());
+
+        {
+            let reader = nippy.open_data_reader().unwrap();
+            let index = reader.index().unwrap();
+            assert_eq!(index.len(), col1.len() + 1);
+            assert_eq!(index.rows(colliding).collect::<Vec<_>>(), vec![0, 1]);
+
+            let mut cursor = NippyJarCursor::new(&nippy).unwrap();
+            for (row_num, (v0, v1)) in col1.iter().zip(col2.iter()).enumerate() {
+                let row =
+                    cursor.row_by_key_with_cols(v0, 0b11, |row| row[0] == v0).unwrap().unwrap();
+                assert_eq!((&row[0].to_vec(), &row[1].to_vec()), (v0, v1));
+                assert_eq!(cursor.row_index(), row_num as u64 + 1);
+            }
+
+            // The second colliding key is only found by comparing the candidates with the key
+            let row = cursor.row_by_key_with_cols(&col1[1], 0b01, |_| true).unwrap().unwrap();
+            assert_eq!(row[0], col1[0].as_slice());
+            let row = cursor.row_by_key_with_cols(&col1[1], 0b01, |row| row[0] == col1[1]).unwrap();
+            assert_eq!(row.unwrap()[0], col1[1].as_slice());
+            assert_eq!(cursor.row_index(), 2);
+
+            let mut unknown = col1[0].clone();
+            unknown[0] = unknown[0].wrapping_add(1);
+            assert!(cursor
+                .row_by_key_with_cols(&unknown, 0b01, |row| row[0] == unknown)
+                .unwrap()
+                .is_none());
+        }
+
+        // Pruning rows invalidates the index
+        let mut writer = NippyJarWriter::new(nippy).unwrap();
+        writer.prune_rows(1).unwrap();
+        let nippy = writer.into_jar();
+        assert!(!nippy.has_index());
+        assert!(nippy.open_data_reader().unwrap().index().is_none());
+    }
+
     #[test]
     fn test_writer() {
         let (col1, col2) = test_data(None);
@@ -1,12 +1,14 @@
 use crate::{
-    compression::Compression, ColumnResult, NippyJar, NippyJarChecker, NippyJarError,
-    NippyJarHeader,
+    compression::{Compression, Compressors},
+    ColumnResult, NippyJar, NippyJarChecker, NippyJarError, NippyJarHeader,
 };
 use std::{
+    fmt,
     fs::{File, OpenOptions},
     io::{BufWriter, Read, Seek, SeekFrom, Write},
     path::Path,
 };
+use zstd::bulk::Compressor;
 
 /// Size of one offset in bytes.
 pub(crate) const OFFSET_SIZE_BYTES: u8 = 8;
@@ -43,6 +45,8 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     column: usize,
     /// Whether the writer has changed data that needs to be committed.
     dirty: bool,
+    /// Column compressors of a jar that is compressed with zstd dictionaries.
+    dictionary_compressors: Option<DictionaryCompressors>,
 }
 
 impl<H: NippyJarHeader> NippyJarWriter<H> {
@@ -50,6 +54,13 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     ///
     /// If will  **always** attempt to heal any inconsistent state when called.
     pub fn new(jar: NippyJar<H>) -> Result<Self, NippyJarError> {
+        let dictionary_compressors = match jar.compressor() {
+            Some(Compressors::Zstd(zstd)) if zstd.use_dict => {
+                zstd.compressors()?.map(DictionaryCompressors)
+            }
+            _ => None,
+        };
+
         let (data_file, offsets_file, is_created) =
             Self::create_or_open_files(jar.data_path(), &jar.offsets_path())?;
 
@@ -79,6 +90,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             offsets: Vec::with_capacity(1_000_000),
             column: 0,
             dirty: false,
+            dictionary_compressors,
         };
 
         if !is_created {
@@ -227,7 +239,14 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
     fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
         self.uncompressed_row_size += value.len();
-        let len = if let Some(compression) = &self.jar.compressor {
+        let len = if let Some(compressors) = &mut self.dictionary_compressors {
+            // Compressors write to the start of the buffer.
+            self.tmp_buf.clear();
+            self.tmp_buf.reserve(zstd::zstd_safe::compress_bound(value.len()));
+            let len = compressors.0[self.column].compress_to_buffer(value, &mut self.tmp_buf)?;
+            self.data_file.write_all(&self.tmp_buf[..len])?;
+            len
+        } else if let Some(compression) = &self.jar.compressor {
             let before = self.tmp_buf.len();
             let len = compression.compress_to(value, &mut self.tmp_buf)?;
             self.data_file.write_all(&self.tmp_buf[before..before + len])?;
@@ -326,6 +345,12 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         }
         self.jar.freeze_config()?;
 
+        // The index may point to pruned rows, so it has to be rebuilt.
+        let index_path = self.jar.index_path();
+        if index_path.exists() {
+            reth_fs_util::remove_file(index_path)?;
+        }
+
         Ok(())
     }
 
@@ -464,3 +489,12 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         &self.jar
     }
 }
+
+/// Zstd compressors with the dictionary of each column.
+struct DictionaryCompressors(Vec<Compressor<'static>>);
+
+impl fmt::Debug for DictionaryCompressors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DictionaryCompressors").field(&self.0.len()).finish()
+    }
+}
@@ -29,10 +29,11 @@ use reth_primitives::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_storage_api::{DBProvider, StorageChangeSetReader};
+use reth_storage_api::{DBProvider, HistoryIndexReader, StorageChangeSetReader};
 use reth_storage_errors::provider::ProviderResult;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
+    collections::BTreeSet,
     ops::{Add, RangeBounds, RangeInclusive, Sub},
     sync::Arc,
     time::Instant,
@@ -728,6 +729,16 @@ impl<N: ProviderNodeTypes> ChangeSetReader for BlockchainProvider2<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> HistoryIndexReader for BlockchainProvider2<N> {
+    fn address_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        self.consistent_provider()?.address_history_blocks(address, range)
+    }
+}
+
 impl<N: ProviderNodeTypes> AccountReader for BlockchainProvider2<N> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
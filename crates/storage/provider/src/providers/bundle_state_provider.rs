@@ -6,10 +6,12 @@ use alloy_primitives::{
     Address, BlockNumber, Bytes, B256,
 };
 use reth_primitives::{Account, Bytecode};
-use reth_storage_api::{StateProofProvider, StorageRootProvider};
+use reth_storage_api::{StateProofProvider, StateRangeProvider, StorageRootProvider};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
+    range::{HashedAccountRange, HashedStorageRange},
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
 };
 
 /// A state provider that resolves to data from either a wrapped [`crate::ExecutionOutcome`]
@@ -175,6 +177,63 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProofProvider
     }
 }
 
+impl<SP: StateProvider, EDP: ExecutionDataProvider> StateRangeProvider
+    for BundleStateProvider<SP, EDP>
+{
+    fn account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        let bundle_state = self.block_execution_data_provider.execution_outcome().state();
+        let mut state = HashedPostState::from_bundle_state(&bundle_state.state);
+        state.extend(hashed_state);
+        self.state_provider.account_range(state, start, limit, with_storage)
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        let mut storage = self.get_hashed_storage(address);
+        storage.extend(&hashed_storage);
+        self.state_provider.storage_range(address, storage, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        let bundle_state = self.block_execution_data_provider.execution_outcome().state();
+        let mut storage = HashedPostState::from_bundle_state(&bundle_state.state)
+            .storages
+            .remove(&hashed_address)
+            .unwrap_or_default();
+        storage.extend(&hashed_storage);
+        self.state_provider.hashed_storage_range(hashed_address, storage, start, limit)
+    }
+
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        let bundle_state = self.block_execution_data_provider.execution_outcome().state();
+        let mut state = HashedPostState::from_bundle_state(&bundle_state.state);
+        state.extend(hashed_state);
+        self.state_provider.hashed_account_range(state, start, limit)
+    }
+}
+
 impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProvider for BundleStateProvider<SP, EDP> {
     fn storage(
         &self,
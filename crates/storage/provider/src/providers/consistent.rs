@@ -21,14 +21,16 @@ use reth_primitives::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_storage_api::{DatabaseProviderFactory, StateProvider, StorageChangeSetReader};
+use reth_storage_api::{
+    DatabaseProviderFactory, HistoryIndexReader, StateProvider, StorageChangeSetReader,
+};
 use reth_storage_errors::provider::ProviderResult;
 use revm::{
     db::states::PlainStorageRevert,
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
-    collections::{hash_map, HashMap},
+    collections::{hash_map, BTreeSet, HashMap},
     ops::{Add, Bound, RangeBounds, RangeInclusive, Sub},
     sync::Arc,
 };
@@ -1407,14 +1409,9 @@ impl<N: ProviderNodeTypes> StorageChangeSetReader for ConsistentProvider<N> {
             let storage_history_exists = self
                 .storage_provider
                 .get_prune_checkpoint(PruneSegment::StorageHistory)?
-                .and_then(|checkpoint| {
-                    // return true if the block number is ahead of the prune checkpoint.
-                    //
-                    // The checkpoint stores the highest pruned block number, so we should make
-                    // sure the block_number is strictly greater.
-                    checkpoint.block_number.map(|checkpoint| block_number > checkpoint)
-                })
-                .unwrap_or(true);
+                // The checkpoint stores the highest pruned block number, apart from the archive
+                // window of its prune mode.
+                .map_or(true, |checkpoint| !checkpoint.is_pruned(block_number));
 
             if !storage_history_exists {
                 return Err(ProviderError::StateAtBlockPruned(block_number))
@@ -1453,14 +1450,9 @@ impl<N: ProviderNodeTypes> ChangeSetReader for ConsistentProvider<N> {
             let account_history_exists = self
                 .storage_provider
                 .get_prune_checkpoint(PruneSegment::AccountHistory)?
-                .and_then(|checkpoint| {
-                    // return true if the block number is ahead of the prune checkpoint.
-                    //
-                    // The checkpoint stores the highest pruned block number, so we should make
-                    // sure the block_number is strictly greater.
-                    checkpoint.block_number.map(|checkpoint| block_number > checkpoint)
-                })
-                .unwrap_or(true);
+                // The checkpoint stores the highest pruned block number, apart from the archive
+                // window of its prune mode.
+                .map_or(true, |checkpoint| !checkpoint.is_pruned(block_number));
 
             if !account_history_exists {
                 return Err(ProviderError::StateAtBlockPruned(block_number))
@@ -1471,6 +1463,30 @@ impl<N: ProviderNodeTypes> ChangeSetReader for ConsistentProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> HistoryIndexReader for ConsistentProvider<N> {
+    fn address_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        let mut blocks = self.storage_provider.address_history_blocks(address, range.clone())?;
+
+        // In-memory blocks are not indexed yet, so their bundle states are inspected instead.
+        if let Some(head_block) = &self.head_block {
+            for state in head_block.chain() {
+                let block_number = state.number();
+                if range.contains(&block_number) &&
+                    state.block_ref().execution_output.bundle.account(&address).is_some()
+                {
+                    blocks.insert(block_number);
+                }
+            }
+        }
+
+        Ok(blocks)
+    }
+}
+
 impl<N: ProviderNodeTypes> AccountReader for ConsistentProvider<N> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
@@ -2,7 +2,7 @@ use crate::{BlockNumReader, DatabaseProviderFactory, HeaderProvider};
 use alloy_primitives::B256;
 use reth_errors::ProviderError;
 use reth_primitives::GotExpected;
-use reth_storage_api::{BlockReader, DBProvider};
+use reth_storage_api::{BlockReader, ChangeSetReader, StorageChangeSetReader};
 use reth_storage_errors::provider::ProviderResult;
 
 use reth_trie::HashedPostState;
@@ -49,7 +49,10 @@ where
     }
 
     /// Retrieve revert hashed state down to the given block hash.
-    pub fn revert_state(&self, block_hash: B256) -> ProviderResult<HashedPostState> {
+    pub fn revert_state(&self, block_hash: B256) -> ProviderResult<HashedPostState>
+    where
+        Factory::Provider: ChangeSetReader + StorageChangeSetReader,
+    {
         let provider = self.provider_ro()?;
         let block_number = provider
             .block_number(block_hash)?
@@ -59,7 +62,8 @@ where
         {
             Ok(HashedPostState::default())
         } else {
-            Ok(HashedPostState::from_reverts(provider.tx_ref(), block_number + 1)?)
+            let tip = provider.last_block_number()?;
+            HashedPostState::from_reverts(&provider, block_number + 1..=tip)
         }
     }
 
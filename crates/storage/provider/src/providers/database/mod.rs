@@ -1,11 +1,11 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{is_snap_synced, state::latest::LatestStateProvider, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
     BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, ProviderError,
-    PruneCheckpointReader, StageCheckpointReader, StateProviderBox, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoryIndexReader,
+    ProviderError, PruneCheckpointReader, StageCheckpointReader, StateProviderBox,
+    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
@@ -27,6 +27,7 @@ use reth_storage_api::TryIntoHistoricalStateProvider;
 use reth_storage_errors::provider::ProviderResult;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
+    collections::BTreeSet,
     ops::{RangeBounds, RangeInclusive},
     path::Path,
     sync::Arc,
@@ -157,7 +158,11 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
     #[track_caller]
     pub fn latest(&self) -> ProviderResult<StateProviderBox> {
         trace!(target: "providers::db", "Returning latest state provider");
-        Ok(Box::new(LatestStateProvider::new(self.db.tx()?, self.static_file_provider())))
+        let tx = self.db.tx()?;
+        let snap_synced = is_snap_synced(&tx)?;
+        Ok(Box::new(
+            LatestStateProvider::new(tx, self.static_file_provider()).with_snap_synced(snap_synced),
+        ))
     }
 
     /// Storage provider for state at that given block
@@ -605,6 +610,16 @@ impl<N: ProviderNodeTypes> PruneCheckpointReader for ProviderFactory<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> HistoryIndexReader for ProviderFactory<N> {
+    fn address_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        self.provider()?.address_history_blocks(address, range)
+    }
+}
+
 impl<N: NodeTypesWithDB> Clone for ProviderFactory<N> {
     fn clone(&self) -> Self {
         Self {
@@ -800,4 +815,103 @@ mod tests {
         assert_eq!(gap.local_head, head);
         assert_eq!(gap.target.tip(), consensus_tip.into());
     }
+
+    #[test]
+    fn address_history_blocks() {
+        use alloy_primitives::{address, b256};
+        use reth_db::BlockNumberList;
+        use reth_db_api::{
+            models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+            transaction::DbTxMut,
+        };
+
+        let address = address!("0000000000000000000000000000000000000001");
+        let other = address!("0000000000000000000000000000000000000002");
+        let slot = b256!("0000000000000000000000000000000000000000000000000000000000000001");
+        let sharded_slot =
+            b256!("0000000000000000000000000000000000000000000000000000000000000002");
+
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(address, 7),
+            BlockNumberList::new([1, 3, 7]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(address, u64::MAX),
+            BlockNumberList::new([10, 15]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(other, u64::MAX),
+            BlockNumberList::new([4]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(address, slot, u64::MAX),
+            BlockNumberList::new([5, 12]).unwrap(),
+        )
+        .unwrap();
+        for (highest_block_number, blocks) in
+            [(6, vec![2, 6]), (20, vec![9, 20]), (u64::MAX, vec![25])]
+        {
+            tx.put::<tables::StoragesHistory>(
+                StorageShardedKey::new(address, sharded_slot, highest_block_number),
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            provider.address_history_blocks(address, 0..=u64::MAX).unwrap(),
+            BTreeSet::from([1, 2, 3, 5, 6, 7, 9, 10, 12, 15, 20, 25])
+        );
+        assert_eq!(
+            provider.address_history_blocks(address, 4..=12).unwrap(),
+            BTreeSet::from([5, 6, 7, 9, 10, 12])
+        );
+        assert_eq!(
+            provider.address_history_blocks(address, 21..=30).unwrap(),
+            BTreeSet::from([25])
+        );
+        assert_eq!(provider.address_history_blocks(other, 5..=10).unwrap(), BTreeSet::new());
+    }
+
+    #[test]
+    fn snap_synced_latest_state() {
+        use crate::{AccountReader, StageCheckpointWriter, StateProvider};
+        use alloy_primitives::keccak256;
+        use reth_db_api::transaction::DbTxMut;
+        use reth_primitives::{Account, StorageEntry};
+
+        let factory = create_test_provider_factory();
+        let address = Address::random();
+        let storage_key = B256::random();
+        let account = Account { nonce: 1, ..Default::default() };
+
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::HashedAccounts>(keccak256(address), account).unwrap();
+        tx.put::<tables::HashedStorages>(
+            keccak256(address),
+            StorageEntry { key: keccak256(storage_key), value: U256::from(1) },
+        )
+        .unwrap();
+        provider.commit().unwrap();
+
+        // The hashed state is not read before the state was snap synced
+        let state = factory.latest().unwrap();
+        assert_eq!(state.basic_account(address).unwrap(), None);
+        assert_eq!(state.storage(address, storage_key).unwrap(), None);
+
+        let provider = factory.provider_rw().unwrap();
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, vec![1]).unwrap();
+        provider.commit().unwrap();
+
+        let state = factory.latest().unwrap();
+        assert_eq!(state.basic_account(address).unwrap(), Some(account));
+        assert_eq!(state.storage(address, storage_key).unwrap(), Some(U256::from(1)));
+    }
 }
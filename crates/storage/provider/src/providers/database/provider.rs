@@ -1,6 +1,8 @@
 use crate::{
     bundle_state::StorageRevertsIter,
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, state::is_snap_synced, static_file::StaticFileWriter, StaticFileProvider,
+    },
     to_range,
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
@@ -9,11 +11,12 @@ use crate::{
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
     BundleStateInit, ChainStateBlockReader, ChainStateBlockWriter, DBProvider, EvmEnvProvider,
     HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider,
-    HistoricalStateProviderRef, HistoryWriter, LatestStateProvider, LatestStateProviderRef,
-    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter, RevertsInit,
-    StageCheckpointReader, StateChangeWriter, StateProviderBox, StateReader, StateWriter,
-    StaticFileProviderFactory, StatsReader, StorageReader, StorageTrieWriter, TransactionVariant,
-    TransactionsProvider, TransactionsProviderExt, TrieWriter, WithdrawalsProvider,
+    HistoricalStateProviderRef, HistoryIndexReader, HistoryWriter, LatestStateProvider,
+    LatestStateProviderRef, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter, RevertsInit, StageCheckpointReader, StateChangeWriter, StateProviderBox,
+    StateReader, StateWriter, StaticFileProviderFactory, StatsReader, StorageReader,
+    StorageTrieWriter, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
+    TrieWriter, WithdrawalsProvider,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{keccak256, Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
@@ -21,7 +24,9 @@ use itertools::{izip, Itertools};
 use rayon::slice::ParallelSliceMut;
 use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec, EthereumHardforks};
 use reth_db::{
-    cursor::DbDupCursorRW, tables, BlockNumberList, PlainAccountState, PlainStorageState,
+    cursor::DbDupCursorRW,
+    static_file::{AccountChangeSetMask, StorageChangeSetMask},
+    tables, BlockNumberList, PlainAccountState, PlainStorageState,
 };
 use reth_db_api::{
     common::KeyValue,
@@ -149,7 +154,10 @@ impl<TX: DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
     /// State provider for latest block
     pub fn latest<'a>(&'a self) -> ProviderResult<Box<dyn StateProvider + 'a>> {
         trace!(target: "providers::db", "Returning latest state provider");
-        Ok(Box::new(LatestStateProviderRef::new(&self.tx, self.static_file_provider.clone())))
+        Ok(Box::new(
+            LatestStateProviderRef::new(&self.tx, self.static_file_provider.clone())
+                .with_snap_synced(is_snap_synced(&self.tx)?),
+        ))
     }
 
     /// Storage provider for state at that given block hash
@@ -162,10 +170,10 @@ impl<TX: DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
         if block_number == self.best_block_number().unwrap_or_default() &&
             block_number == self.last_block_number().unwrap_or_default()
         {
-            return Ok(Box::new(LatestStateProviderRef::new(
-                &self.tx,
-                self.static_file_provider.clone(),
-            )))
+            return Ok(Box::new(
+                LatestStateProviderRef::new(&self.tx, self.static_file_provider.clone())
+                    .with_snap_synced(is_snap_synced(&self.tx)?),
+            ))
         }
 
         // +1 as the changeset that we want is the one that was applied after this block.
@@ -180,7 +188,8 @@ impl<TX: DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
             &self.tx,
             block_number,
             self.static_file_provider.clone(),
-        );
+        )
+        .with_snap_synced(is_snap_synced(&self.tx)?);
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
@@ -198,6 +207,16 @@ impl<TX: DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
                 prune_checkpoint_block_number + 1,
             );
         }
+        if let Some(window) = account_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.prune_mode.archive_window())
+        {
+            state_provider = state_provider.with_account_history_window(window);
+        }
+        if let Some(window) = storage_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.prune_mode.archive_window())
+        {
+            state_provider = state_provider.with_storage_history_window(window);
+        }
 
         Ok(Box::new(state_provider))
     }
@@ -246,7 +265,11 @@ impl<TX: DbTx + 'static, N: NodeTypes> TryIntoHistoricalStateProvider for Databa
         if block_number == self.best_block_number().unwrap_or_default() &&
             block_number == self.last_block_number().unwrap_or_default()
         {
-            return Ok(Box::new(LatestStateProvider::new(self.tx, self.static_file_provider)))
+            let snap_synced = is_snap_synced(&self.tx)?;
+            return Ok(Box::new(
+                LatestStateProvider::new(self.tx, self.static_file_provider)
+                    .with_snap_synced(snap_synced),
+            ))
         }
 
         // +1 as the changeset that we want is the one that was applied after this block.
@@ -257,8 +280,10 @@ impl<TX: DbTx + 'static, N: NodeTypes> TryIntoHistoricalStateProvider for Databa
         let storage_history_prune_checkpoint =
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
 
+        let snap_synced = is_snap_synced(&self.tx)?;
         let mut state_provider =
-            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider);
+            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider)
+                .with_snap_synced(snap_synced);
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
@@ -276,6 +301,16 @@ impl<TX: DbTx + 'static, N: NodeTypes> TryIntoHistoricalStateProvider for Databa
                 prune_checkpoint_block_number + 1,
             );
         }
+        if let Some(window) = account_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.prune_mode.archive_window())
+        {
+            state_provider = state_provider.with_account_history_window(window);
+        }
+        if let Some(window) = storage_history_prune_checkpoint
+            .and_then(|checkpoint| checkpoint.prune_mode.archive_window())
+        {
+            state_provider = state_provider.with_storage_history_window(window);
+        }
 
         Ok(Box::new(state_provider))
     }
@@ -426,6 +461,98 @@ impl<TX: DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
         self.tx.cursor_read::<T>()?.walk_range(range)?.collect::<Result<Vec<_>, _>>()
     }
 
+    /// Returns the account changesets of the block range.
+    ///
+    /// Blocks that were moved to [`StaticFileSegment::AccountChangeSets`] are read from static
+    /// files, the rest from the [`AccountChangeSets`](tables::AccountChangeSets) table.
+    pub fn account_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumber, AccountBeforeTx)>> {
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::AccountChangeSets,
+            to_range(range),
+            |static_file, range, _| {
+                Ok(static_file
+                    .fetch_range_with_predicate(
+                        StaticFileSegment::AccountChangeSets,
+                        range,
+                        |cursor, number| {
+                            Ok(cursor
+                                .get_one::<AccountChangeSetMask<AccountChangeSet>>(number.into())?
+                                .map(|change_set| (number, change_set)))
+                        },
+                        |_| true,
+                    )?
+                    .into_iter()
+                    .flat_map(|(number, change_set)| {
+                        change_set.0.into_iter().map(move |account| (number, account))
+                    })
+                    .collect())
+            },
+            |range, _| Ok(self.get::<tables::AccountChangeSets>(range)?),
+            |_| true,
+        )
+    }
+
+    /// Returns the storage changesets of the key range.
+    ///
+    /// Blocks that were moved to [`StaticFileSegment::StorageChangeSets`] are read from static
+    /// files, the rest from the [`StorageChangeSets`](tables::StorageChangeSets) table.
+    pub fn storage_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumberAddress>,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        // Blocks that hold any key of the range. Bounds on the address are applied afterwards.
+        let block_range = to_range((
+            range.start_bound().map(|key| key.block_number()),
+            match range.end_bound() {
+                Bound::Included(key) | Bound::Excluded(key) => Bound::Included(key.block_number()),
+                Bound::Unbounded => Bound::Unbounded,
+            },
+        ));
+
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::StorageChangeSets,
+            block_range,
+            |static_file, block_range, _| {
+                Ok(static_file
+                    .fetch_range_with_predicate(
+                        StaticFileSegment::StorageChangeSets,
+                        block_range,
+                        |cursor, number| {
+                            Ok(cursor
+                                .get_one::<StorageChangeSetMask<StorageChangeSet>>(number.into())?
+                                .map(|change_set| (number, change_set)))
+                        },
+                        |_| true,
+                    )?
+                    .into_iter()
+                    .flat_map(|(number, change_set)| {
+                        change_set.0.into_iter().map(move |storage| {
+                            (
+                                BlockNumberAddress((number, storage.address)),
+                                StorageEntry { key: storage.key, value: storage.value },
+                            )
+                        })
+                    })
+                    .filter(|(key, _)| range.contains(key))
+                    .collect())
+            },
+            |block_range, _| {
+                Ok(self
+                    .get::<tables::StorageChangeSets>((
+                        Bound::Included(BlockNumberAddress((block_range.start, Address::ZERO))),
+                        range.end_bound().cloned(),
+                    ))?
+                    .into_iter()
+                    .filter(|(key, _)| range.contains(key))
+                    .collect())
+            },
+            |_| true,
+        )
+    }
+
     /// Iterates over read only values in the given table and collects them into a vector.
     ///
     /// Early-returns if the range is empty, without opening a cursor transaction.
@@ -725,10 +852,9 @@ impl<TX: DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
             return Ok(None)
         };
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.get::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.get::<tables::AccountChangeSets>(range)?;
+        let storage_changeset =
+            self.storage_changesets_range(BlockNumberAddress::range(range.clone()))?;
+        let account_changeset = self.account_changesets_range(range)?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -890,6 +1016,42 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> DatabaseProvider<TX, N> {
         Ok(items)
     }
 
+    /// Takes the account and storage changesets of the block range.
+    ///
+    /// Changesets are removed from the database tables, and the ones of blocks moved to the
+    /// changeset static file segments are read and pruned from static files. Since static files
+    /// can only be pruned from their tip, all static file rows from the start of the range on are
+    /// pruned. The prunes are committed together with the other static file writers.
+    fn take_change_sets(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<(
+        Vec<(BlockNumber, AccountBeforeTx)>,
+        Vec<(BlockNumberAddress, StorageEntry)>,
+    )> {
+        let storage_range = BlockNumberAddress::range(range.clone());
+        let account_changeset = self.account_changesets_range(range.clone())?;
+        let storage_changeset = self.storage_changesets_range(storage_range.clone())?;
+
+        self.remove::<tables::AccountChangeSets>(range.clone())?;
+        self.remove::<tables::StorageChangeSets>(storage_range)?;
+
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            if let Some(highest_block) = self
+                .static_file_provider
+                .get_highest_static_file_block(segment)
+                .filter(|highest_block| highest_block >= range.start())
+            {
+                self.static_file_provider
+                    .latest_writer(segment)?
+                    .prune_change_sets(highest_block - range.start() + 1)?;
+            }
+        }
+
+        Ok((account_changeset, storage_changeset))
+    }
+
     /// Remove requested block transactions, without returning them.
     ///
     /// This will remove block data for the given range from the following tables:
@@ -1228,13 +1390,11 @@ impl<TX: DbTx, N: NodeTypes> AccountExtReader for DatabaseProvider<TX, N> {
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<BTreeSet<Address>> {
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(_, account_before)| account_before.address).map_err(Into::into)
-            })
-            .collect()
+        Ok(self
+            .account_changesets_range(range)?
+            .into_iter()
+            .map(|(_, account_before)| account_before.address)
+            .collect())
     }
 
     fn basic_accounts(
@@ -1252,16 +1412,13 @@ impl<TX: DbTx, N: NodeTypes> AccountExtReader for DatabaseProvider<TX, N> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::AccountChangeSets>()?;
-
-        let account_transitions = changeset_cursor.walk_range(range)?.try_fold(
+        let account_transitions = self.account_changesets_range(range)?.into_iter().fold(
             BTreeMap::new(),
-            |mut accounts: BTreeMap<Address, Vec<u64>>, entry| -> ProviderResult<_> {
-                let (index, account) = entry?;
+            |mut accounts: BTreeMap<Address, Vec<u64>>, (index, account)| {
                 accounts.entry(account.address).or_default().push(index);
-                Ok(accounts)
+                accounts
             },
-        )?;
+        );
 
         Ok(account_transitions)
     }
@@ -1272,13 +1429,22 @@ impl<TX: DbTx, N: NodeTypes> StorageChangeSetReader for DatabaseProvider<TX, N>
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
-        let range = block_number..=block_number;
-        let storage_range = BlockNumberAddress::range(range);
-        self.tx
-            .cursor_dup_read::<tables::StorageChangeSets>()?
-            .walk_range(storage_range)?
-            .map(|result| -> ProviderResult<_> { Ok(result?) })
-            .collect()
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::StorageChangeSets,
+                block_number,
+                |static_file| static_file.storage_changeset(block_number).map(Some),
+                || {
+                    let storage_range = BlockNumberAddress::range(block_number..=block_number);
+                    self.tx
+                        .cursor_dup_read::<tables::StorageChangeSets>()?
+                        .walk_range(storage_range)?
+                        .map(|result| -> ProviderResult<_> { Ok(result?) })
+                        .collect::<ProviderResult<_>>()
+                        .map(Some)
+                },
+            )
+            .map(Option::unwrap_or_default)
     }
 }
 
@@ -1287,15 +1453,81 @@ impl<TX: DbTx, N: NodeTypes> ChangeSetReader for DatabaseProvider<TX, N> {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
-        let range = block_number..=block_number;
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|result| -> ProviderResult<_> {
-                let (_, account_before) = result?;
-                Ok(account_before)
-            })
-            .collect()
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::AccountChangeSets,
+                block_number,
+                |static_file| static_file.account_block_changeset(block_number).map(Some),
+                || {
+                    let range = block_number..=block_number;
+                    self.tx
+                        .cursor_read::<tables::AccountChangeSets>()?
+                        .walk_range(range)?
+                        .map(|result| -> ProviderResult<_> {
+                            let (_, account_before) = result?;
+                            Ok(account_before)
+                        })
+                        .collect::<ProviderResult<_>>()
+                        .map(Some)
+                },
+            )
+            .map(Option::unwrap_or_default)
+    }
+}
+
+impl<TX: DbTx, N: NodeTypes> HistoryIndexReader for DatabaseProvider<TX, N> {
+    fn address_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        let mut blocks = BTreeSet::new();
+
+        // Account shards are keyed by the highest block number they contain, so the first shard
+        // that can contain blocks from the range is the one at or above its start.
+        let mut account_cursor = self.tx.cursor_read::<tables::AccountsHistory>()?;
+        for entry in account_cursor.walk(Some(ShardedKey::new(address, *range.start())))? {
+            let (key, list) = entry?;
+            if key.key != address {
+                break
+            }
+            blocks.extend(list.iter().filter(|block| range.contains(block)));
+            if key.highest_block_number > *range.end() {
+                break
+            }
+        }
+
+        // Storage shards are ordered by slot first. For every slot of the address, only the shards
+        // overlapping the range are visited, starting at the first shard at or above its start.
+        let mut storage_cursor = self.tx.cursor_read::<tables::StoragesHistory>()?;
+        let mut entry =
+            storage_cursor.seek(StorageShardedKey::new(address, B256::ZERO, *range.start()))?;
+        while let Some((key, list)) = entry {
+            if key.address != address {
+                break
+            }
+
+            let slot = key.sharded_key.key;
+            if key.sharded_key.highest_block_number < *range.start() {
+                entry =
+                    storage_cursor.seek(StorageShardedKey::new(address, slot, *range.start()))?;
+                continue
+            }
+
+            blocks.extend(list.iter().filter(|block| range.contains(block)));
+
+            entry = if key.sharded_key.highest_block_number < *range.end() {
+                storage_cursor.next()?
+            } else {
+                // Skip the remaining shards of the slot, the last one is keyed by `u64::MAX`.
+                match storage_cursor.seek(StorageShardedKey::last(address, slot))? {
+                    Some((key, _)) if key.sharded_key.key == slot => storage_cursor.next()?,
+                    next => next,
+                }
+            };
+        }
+
+        Ok(blocks)
     }
 }
 
@@ -1750,7 +1982,13 @@ impl<TX: DbTx, N: NodeTypes<ChainSpec: EthereumHardforks>> TransactionsProvider
     for DatabaseProvider<TX, N>
 {
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
-        Ok(self.tx.get::<tables::TransactionHashNumbers>(tx_hash)?)
+        if let Some(id) = self.tx.get::<tables::TransactionHashNumbers>(tx_hash)? {
+            return Ok(Some(id))
+        }
+
+        // The lookup table may be pruned, in which case transactions in static files can still be
+        // found through their transaction hash index.
+        self.static_file_provider.transaction_id(tx_hash)
     }
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
@@ -2138,36 +2376,36 @@ impl<TX: DbTx, N: NodeTypes> StorageReader for DatabaseProvider<TX, N> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
-        self.tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(BlockNumberAddress::range(range))?
+        Ok(self
+            .storage_changesets_range(BlockNumberAddress::range(range))?
+            .into_iter()
             // fold all storages and save its old state so we can remove it from HashedStorage
             // it is needed as it is dup table.
-            .try_fold(BTreeMap::new(), |mut accounts: BTreeMap<Address, BTreeSet<B256>>, entry| {
-                let (BlockNumberAddress((_, address)), storage_entry) = entry?;
-                accounts.entry(address).or_default().insert(storage_entry.key);
-                Ok(accounts)
-            })
+            .fold(
+                BTreeMap::new(),
+                |mut accounts: BTreeMap<Address, BTreeSet<B256>>,
+                 (BlockNumberAddress((_, address)), storage_entry)| {
+                    accounts.entry(address).or_default().insert(storage_entry.key);
+                    accounts
+                },
+            ))
     }
 
     fn changed_storages_and_blocks_with_range(
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<(Address, B256), Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::StorageChangeSets>()?;
-
         let storage_changeset_lists =
-            changeset_cursor.walk_range(BlockNumberAddress::range(range))?.try_fold(
+            self.storage_changesets_range(BlockNumberAddress::range(range))?.into_iter().fold(
                 BTreeMap::new(),
-                |mut storages: BTreeMap<(Address, B256), Vec<u64>>, entry| -> ProviderResult<_> {
-                    let (index, storage) = entry?;
+                |mut storages: BTreeMap<(Address, B256), Vec<u64>>, (index, storage)| {
                     storages
                         .entry((index.address(), storage.key))
                         .or_default()
                         .push(index.block_number());
-                    Ok(storages)
+                    storages
                 },
-            )?;
+            );
 
         Ok(storage_changeset_lists)
     }
@@ -2249,6 +2487,13 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> StateChangeWriter for DatabaseProvider<TX
         changes.storage.par_sort_by_key(|a| a.address);
         changes.contracts.par_sort_by_key(|a| a.0);
 
+        // The plain state of a snap synced database falls back to the hashed state, so deletions
+        // are applied to the hashed state as well, ahead of the hashing stages.
+        let snap_synced = is_snap_synced(self.tx_ref())?;
+        let mut hashed_accounts_cursor = self.tx_ref().cursor_write::<tables::HashedAccounts>()?;
+        let mut hashed_storages_cursor =
+            self.tx_ref().cursor_dup_write::<tables::HashedStorages>()?;
+
         // Write new account state
         tracing::trace!(len = changes.accounts.len(), "Writing new account state");
         let mut accounts_cursor = self.tx_ref().cursor_write::<tables::PlainAccountState>()?;
@@ -2257,9 +2502,14 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> StateChangeWriter for DatabaseProvider<TX
             if let Some(account) = account {
                 tracing::trace!(?address, "Updating plain state account");
                 accounts_cursor.upsert(address, account.into())?;
-            } else if accounts_cursor.seek_exact(address)?.is_some() {
-                tracing::trace!(?address, "Deleting plain state account");
-                accounts_cursor.delete_current()?;
+            } else {
+                if accounts_cursor.seek_exact(address)?.is_some() {
+                    tracing::trace!(?address, "Deleting plain state account");
+                    accounts_cursor.delete_current()?;
+                }
+                if snap_synced && hashed_accounts_cursor.seek_exact(keccak256(address))?.is_some() {
+                    hashed_accounts_cursor.delete_current()?;
+                }
             }
         }
 
@@ -2278,6 +2528,13 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> StateChangeWriter for DatabaseProvider<TX
             if wipe_storage && storages_cursor.seek_exact(address)?.is_some() {
                 storages_cursor.delete_current_duplicates()?;
             }
+            let hashed_address = keccak256(address);
+            if snap_synced &&
+                wipe_storage &&
+                hashed_storages_cursor.seek_exact(hashed_address)?.is_some()
+            {
+                hashed_storages_cursor.delete_current_duplicates()?;
+            }
             // cast storages to B256.
             let mut storage = storage
                 .into_iter()
@@ -2296,6 +2553,14 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> StateChangeWriter for DatabaseProvider<TX
 
                 if !entry.value.is_zero() {
                     storages_cursor.upsert(address, entry)?;
+                } else if snap_synced {
+                    let hashed_key = keccak256(entry.key);
+                    if hashed_storages_cursor
+                        .seek_by_key_subkey(hashed_address, hashed_key)?
+                        .is_some_and(|entry| entry.key == hashed_key)
+                    {
+                        hashed_storages_cursor.delete_current()?;
+                    }
                 }
             }
         }
@@ -2377,10 +2642,7 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> StateChangeWriter for DatabaseProvider<TX
         let to_transaction_num =
             block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.take::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.take::<tables::AccountChangeSets>(range)?;
+        let (account_changeset, storage_changeset) = self.take_change_sets(range)?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -2470,10 +2732,7 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> StateChangeWriter for DatabaseProvider<TX
         let to_transaction_num =
             block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.take::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.take::<tables::AccountChangeSets>(range)?;
+        let (account_changeset, storage_changeset) = self.take_change_sets(range)?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -2670,11 +2929,7 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> HashingWriter for DatabaseProvider<TX, N>
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<BTreeMap<B256, Option<Account>>> {
-        let changesets = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changesets = self.account_changesets_range(range)?;
         self.unwind_account_hashing(changesets.iter())
     }
 
@@ -2734,11 +2989,7 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> HashingWriter for DatabaseProvider<TX, N>
         &self,
         range: impl RangeBounds<BlockNumberAddress>,
     ) -> ProviderResult<HashMap<B256, BTreeSet<B256>>> {
-        let changesets = self
-            .tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(range)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changesets = self.storage_changesets_range(range)?;
         self.unwind_storage_hashing(changesets.into_iter())
     }
 
@@ -2901,11 +3152,7 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> HistoryWriter for DatabaseProvider<TX, N>
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<usize> {
-        let changesets = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changesets = self.account_changesets_range(range)?;
         self.unwind_account_history_indices(changesets.iter())
     }
 
@@ -2959,11 +3206,7 @@ impl<TX: DbTxMut + DbTx, N: NodeTypes> HistoryWriter for DatabaseProvider<TX, N>
         &self,
         range: impl RangeBounds<BlockNumberAddress>,
     ) -> ProviderResult<usize> {
-        let changesets = self
-            .tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(range)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changesets = self.storage_changesets_range(range)?;
         self.unwind_storage_history_indices(changesets.into_iter())
     }
 
@@ -3009,11 +3252,7 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes<ChainSpec: EthereumHardforks> +
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Chain> {
-        let changed_accounts = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range.clone())?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changed_accounts = self.account_changesets_range(range.clone())?;
 
         // Unwind account hashes. Add changed accounts to account prefix set.
         let hashed_addresses = self.unwind_account_hashing(changed_accounts.iter())?;
@@ -3030,11 +3269,7 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes<ChainSpec: EthereumHardforks> +
         self.unwind_account_history_indices(changed_accounts.iter())?;
         let storage_range = BlockNumberAddress::range(range.clone());
 
-        let changed_storages = self
-            .tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(storage_range)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changed_storages = self.storage_changesets_range(storage_range)?;
 
         // Unwind storage hashes. Add changed account and storage keys to corresponding prefix
         // sets.
@@ -3108,11 +3343,7 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes<ChainSpec: EthereumHardforks> +
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()> {
-        let changed_accounts = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range.clone())?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changed_accounts = self.account_changesets_range(range.clone())?;
 
         // Unwind account hashes. Add changed accounts to account prefix set.
         let hashed_addresses = self.unwind_account_hashing(changed_accounts.iter())?;
@@ -3129,11 +3360,7 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes<ChainSpec: EthereumHardforks> +
         self.unwind_account_history_indices(changed_accounts.iter())?;
 
         let storage_range = BlockNumberAddress::range(range.clone());
-        let changed_storages = self
-            .tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(storage_range)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let changed_storages = self.storage_changesets_range(storage_range)?;
 
         // Unwind storage hashes. Add changed account and storage keys to corresponding prefix
         // sets.
@@ -2,10 +2,11 @@ use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     BlockSource, BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
     CanonStateSubscriptions, ChainSpecProvider, ChainStateBlockReader, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, FullExecutionDataProvider, HeaderProvider,
-    ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider, TreeViewer, WithdrawalsProvider,
+    DatabaseProviderFactory, EvmEnvProvider, ExecutionDataProvider, FullExecutionDataProvider,
+    HeaderProvider, HistoryIndexReader, ProviderError, PruneCheckpointReader, ReceiptProvider,
+    ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox, StateProviderFactory,
+    StaticFileProviderFactory, StorageChangeSetReader, TransactionVariant, TransactionsProvider,
+    TreeViewer, WithdrawalsProvider,
 };
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag};
 use alloy_primitives::{Address, BlockHash, BlockNumber, Sealable, TxHash, TxNumber, B256, U256};
@@ -16,20 +17,20 @@ use reth_blockchain_tree_api::{
 };
 use reth_chain_state::{ChainInfoTracker, ForkChoiceNotifications, ForkChoiceSubscriptions};
 use reth_chainspec::{ChainInfo, EthereumHardforks};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_api::models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_node_types::NodeTypesWithDB;
 use reth_primitives::{
     Account, Block, BlockWithSenders, Header, Receipt, SealedBlock, SealedBlockWithSenders,
-    SealedHeader, TransactionMeta, TransactionSigned, TransactionSignedNoHash, Withdrawal,
-    Withdrawals,
+    SealedHeader, StorageEntry, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
+    Withdrawal, Withdrawals,
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::ProviderResult;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
     time::Instant,
@@ -41,13 +42,15 @@ pub use database::*;
 
 mod static_file;
 pub use static_file::{
-    StaticFileAccess, StaticFileJarProvider, StaticFileProvider, StaticFileProviderRW,
-    StaticFileProviderRWRefMut, StaticFileWriter,
+    RecompressedStaticFile, StaticFileAccess, StaticFileJarProvider, StaticFileProvider,
+    StaticFileProviderRW, StaticFileProviderRWRefMut, StaticFileWriter, StaticFileWritersLock,
+    DEFAULT_MAX_DICTIONARY_SIZE,
 };
 
 mod state;
 pub use state::{
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
+    is_snap_synced,
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
 
@@ -931,6 +934,45 @@ impl<N: ProviderNodeTypes> ChangeSetReader for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> StorageChangeSetReader for BlockchainProvider<N> {
+    fn storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        self.database.provider()?.storage_changeset(block_number)
+    }
+}
+
+impl<N: ProviderNodeTypes> HistoryIndexReader for BlockchainProvider<N> {
+    fn address_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        let mut blocks =
+            self.database.provider()?.address_history_blocks(address, range.clone())?;
+
+        // Canonical blocks are persisted by the tree, only the pending chain is kept in memory.
+        // It is not indexed, so the reverts of its blocks are inspected instead.
+        if let Some(pending) = self.tree.pending_block_num_hash() {
+            if let Some(state) = self.tree.find_pending_state_provider(pending.hash) {
+                let outcome = state.execution_outcome();
+                for (block_number, reverts) in
+                    (outcome.first_block()..).zip(outcome.bundle.reverts.iter())
+                {
+                    if range.contains(&block_number) &&
+                        reverts.iter().any(|(changed, _)| *changed == address)
+                    {
+                        blocks.insert(block_number);
+                    }
+                }
+            }
+        }
+
+        Ok(blocks)
+    }
+}
+
 impl<N: ProviderNodeTypes> AccountReader for BlockchainProvider<N> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
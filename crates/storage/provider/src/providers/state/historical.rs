@@ -1,33 +1,45 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{macros::delegate_provider_impls, snap_synced_account, snap_synced_storage},
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
 use alloy_eips::merge::EPOCH_SLOTS;
 use alloy_primitives::{
+    keccak256,
     map::{HashMap, HashSet},
-    Address, BlockNumber, Bytes, StorageKey, StorageValue, B256,
+    Address, BlockNumber, Bytes, StorageKey, StorageValue, B256, U256,
 };
 use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, AccountChangeSet,
+        BlockNumberAddress, ShardedKey, StorageBeforeTx, StorageChangeSet,
+    },
     table::Table,
     transaction::DbTx,
 };
-use reth_primitives::{Account, Bytecode, StaticFileSegment};
-use reth_storage_api::{StateProofProvider, StorageRootProvider};
+use reth_primitives::{Account, Bytecode, StaticFileSegment, StorageEntry};
+use reth_prune_types::ArchiveWindow;
+use reth_storage_api::{
+    ChangeSetReader, StateProofProvider, StateRangeProvider, StorageChangeSetReader,
+    StorageRootProvider,
+};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
     proof::{Proof, StorageProof},
+    range::{HashedAccountRange, HashedStorageRange, StateRange},
     updates::TrieUpdates,
     witness::TrieWitness,
     AccountProof, HashedPostState, HashedStorage, MultiProof, StateRoot, StorageRoot, TrieInput,
 };
 use reth_trie_db::{
-    DatabaseHashedPostState, DatabaseHashedStorage, DatabaseProof, DatabaseStateRoot,
-    DatabaseStorageProof, DatabaseStorageRoot, DatabaseTrieWitness,
+    DatabaseHashedPostState, DatabaseHashedStorage, DatabaseProof, DatabaseStateRange,
+    DatabaseStateRoot, DatabaseStorageProof, DatabaseStorageRoot, DatabaseTrieWitness,
 };
-use std::fmt::Debug;
+use std::{collections::BTreeSet, fmt::Debug};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -40,6 +52,9 @@ use std::fmt::Debug;
 /// - [`tables::StoragesHistory`]
 /// - [`tables::AccountChangeSets`]
 /// - [`tables::StorageChangeSets`]
+///
+/// Changesets of blocks that were moved to the [`StaticFileSegment::AccountChangeSets`] and
+/// [`StaticFileSegment::StorageChangeSets`] segments are read from static files instead.
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -50,6 +65,8 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the state was snap synced, see [`is_snap_synced`](super::is_snap_synced).
+    snap_synced: bool,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -67,7 +84,13 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            snap_synced: false,
+        }
     }
 
     /// Create new `StateProvider` for historical block number and lowest block numbers at which
@@ -78,7 +101,14 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self { tx, block_number, lowest_available_blocks, static_file_provider, snap_synced: false }
+    }
+
+    /// Set whether the state was snap synced, so accounts and storage slots that are missing in
+    /// the plain state are read from the hashed state.
+    pub const fn with_snap_synced(mut self, snap_synced: bool) -> Self {
+        self.snap_synced = snap_synced;
+        self
     }
 
     /// Lookup an account in the `AccountsHistory` table
@@ -117,17 +147,19 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
 
     /// Checks and returns `true` if distance to historical block exceeds the provided limit.
     fn check_distance_against_limit(&self, limit: u64) -> ProviderResult<bool> {
-        let tip = self
-            .tx
+        Ok(self.tip()?.saturating_sub(self.block_number) > limit)
+    }
+
+    /// Returns the highest canonical block, either from the database or from static files.
+    fn tip(&self) -> ProviderResult<BlockNumber> {
+        self.tx
             .cursor_read::<tables::CanonicalHeaders>()?
             .last()?
             .map(|(tip, _)| tip)
             .or_else(|| {
                 self.static_file_provider.get_highest_static_file_block(StaticFileSegment::Headers)
             })
-            .ok_or(ProviderError::BestBlockNotFound)?;
-
-        Ok(tip.saturating_sub(self.block_number) > limit)
+            .ok_or(ProviderError::BestBlockNotFound)
     }
 
     /// Retrieve revert hashed state for this history provider.
@@ -146,7 +178,81 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedPostState::from_reverts(self.tx, self.block_number)?)
+        let account_highest_block =
+            self.highest_static_file_changeset_block(StaticFileSegment::AccountChangeSets);
+        let storage_highest_block =
+            self.highest_static_file_changeset_block(StaticFileSegment::StorageChangeSets);
+        if account_highest_block.is_none() && storage_highest_block.is_none() {
+            return HashedPostState::from_reverts(self, self.block_number..=self.tip()?)
+        }
+
+        // Changesets of blocks in static files might still be in the database, so read the
+        // database from the first block that is not in both segments. Static file entries belong
+        // to earlier blocks and take precedence.
+        let database_from = match (account_highest_block, storage_highest_block) {
+            (Some(account_block), Some(storage_block)) => account_block.min(storage_block) + 1,
+            _ => self.block_number,
+        };
+        let mut revert_state = HashedPostState::from_reverts(self, database_from..=self.tip()?)?;
+
+        // Only the first change of every account and storage slot is decoded.
+        let mut accounts = HashMap::<Address, Option<Account>>::default();
+        if let Some(highest_block) = account_highest_block {
+            for block_number in self.block_number..=highest_block {
+                let Some(change_set) =
+                    self.static_file_provider.encoded_account_changeset(block_number)?
+                else {
+                    continue
+                };
+                for AccountBeforeTx { address, info } in
+                    AccountChangeSet::decode_encoded_filtered(change_set.raw_value(), |address| {
+                        !accounts.contains_key(address)
+                    })
+                {
+                    accounts.insert(address, info);
+                }
+            }
+        }
+        let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
+        if let Some(highest_block) = storage_highest_block {
+            for block_number in self.block_number..=highest_block {
+                let Some(change_set) =
+                    self.static_file_provider.encoded_storage_changeset(block_number)?
+                else {
+                    continue
+                };
+                for StorageBeforeTx { address, key, value } in
+                    StorageChangeSet::decode_encoded_filtered(
+                        change_set.raw_value(),
+                        |address, key| {
+                            storages.get(address).is_none_or(|storage| !storage.contains_key(key))
+                        },
+                    )
+                {
+                    storages.entry(address).or_default().insert(key, value);
+                }
+            }
+        }
+        revert_state.extend(HashedPostState {
+            accounts: accounts
+                .into_iter()
+                .map(|(address, info)| (keccak256(address), info))
+                .collect(),
+            storages: storages
+                .into_iter()
+                .map(|(address, storage)| {
+                    (
+                        keccak256(address),
+                        HashedStorage::from_iter(
+                            false,
+                            storage.into_iter().map(|(slot, value)| (keccak256(slot), value)),
+                        ),
+                    )
+                })
+                .collect(),
+        });
+
+        Ok(revert_state)
     }
 
     /// Retrieve revert hashed storage for this history provider and target address.
@@ -163,7 +269,91 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedStorage::from_reverts(self.tx, address, self.block_number)?)
+        let Some(highest_block) =
+            self.highest_static_file_changeset_block(StaticFileSegment::StorageChangeSets)
+        else {
+            return HashedStorage::from_reverts(self, address, self.block_number..=self.tip()?)
+        };
+
+        // Static file entries belong to earlier blocks and take precedence.
+        let mut revert_storage =
+            HashedStorage::from_reverts(self, address, highest_block + 1..=self.tip()?)?;
+        let mut storage = HashMap::<B256, U256>::default();
+        for block_number in self.storage_history_blocks(address, highest_block)? {
+            let Some(change_set) =
+                self.static_file_provider.encoded_storage_changeset(block_number)?
+            else {
+                continue
+            };
+            for entry in StorageChangeSet::account_encoded(change_set.raw_value(), address) {
+                storage.entry(keccak256(entry.key)).or_insert(entry.value);
+            }
+        }
+        revert_storage.extend(&HashedStorage::from_iter(false, storage));
+
+        Ok(revert_storage)
+    }
+
+    /// Returns the blocks from this provider's block up to `to_block` in which any storage slot
+    /// of the address was changed, according to the [`tables::StoragesHistory`] shards.
+    fn storage_history_blocks(
+        &self,
+        address: Address,
+        to_block: BlockNumber,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        let mut blocks = BTreeSet::new();
+
+        // Shards are keyed by the highest block number they contain, so the first shard of a slot
+        // that can contain blocks of the range is the one at or above this provider's block.
+        let mut cursor = self.tx.cursor_read::<tables::StoragesHistory>()?;
+        let mut entry =
+            cursor.seek(StorageShardedKey::new(address, B256::ZERO, self.block_number))?;
+        while let Some((key, list)) = entry {
+            if key.address != address {
+                break
+            }
+
+            let slot = key.sharded_key.key;
+            if key.sharded_key.highest_block_number < self.block_number {
+                // first shard of a slot that was skipped to, seek to the shard of the range
+                entry = cursor.seek(StorageShardedKey::new(address, slot, self.block_number))?;
+                continue
+            }
+
+            blocks.extend(
+                list.iter()
+                    .skip_while(|block| *block < self.block_number)
+                    .take_while(|block| *block <= to_block),
+            );
+
+            entry = if key.sharded_key.highest_block_number < to_block {
+                cursor.next()?
+            } else {
+                // remaining shards of the slot are above the range, skip to the next slot
+                let next_slot = U256::from_be_bytes(slot.0).checked_add(U256::from(1));
+                match next_slot {
+                    Some(next_slot) => cursor.seek(StorageShardedKey::new(
+                        address,
+                        next_slot.to_be_bytes::<32>().into(),
+                        self.block_number,
+                    ))?,
+                    None => None,
+                }
+            };
+        }
+
+        Ok(blocks)
+    }
+
+    /// Returns the highest block of the changeset static file segment, if this provider's block
+    /// is in it.
+    fn highest_static_file_changeset_block(
+        &self,
+        segment: StaticFileSegment,
+    ) -> Option<BlockNumber> {
+        self.static_file_provider
+            .get_highest_static_file_block(segment)
+            .filter(|highest_block| *highest_block >= self.block_number)
     }
 
     fn history_info<T, K>(
@@ -246,6 +436,20 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         self.lowest_available_blocks.storage_history_block_number = Some(block_number);
         self
     }
+
+    /// Set the archive window of the account history, which is available even if it's lower than
+    /// the lowest available block number.
+    pub const fn with_account_history_window(mut self, window: ArchiveWindow) -> Self {
+        self.lowest_available_blocks.account_history_window = Some(window);
+        self
+    }
+
+    /// Set the archive window of the storage history, which is available even if it's lower than
+    /// the lowest available block number.
+    pub const fn with_storage_history_window(mut self, window: ArchiveWindow) -> Self {
+        self.lowest_available_blocks.storage_history_window = Some(window);
+        self
+    }
 }
 
 impl<TX: DbTx> AccountReader for HistoricalStateProviderRef<'_, TX> {
@@ -254,22 +458,90 @@ impl<TX: DbTx> AccountReader for HistoricalStateProviderRef<'_, TX> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
+                .static_file_provider
+                .get_with_static_file_or_database(
+                    StaticFileSegment::AccountChangeSets,
+                    changeset_block_number,
+                    |static_file| {
+                        Ok(static_file.encoded_account_changeset(changeset_block_number)?.and_then(
+                            |change_set| {
+                                AccountChangeSet::find_encoded(change_set.raw_value(), address)
+                            },
+                        ))
+                    },
+                    || {
+                        Ok(self
+                            .tx
+                            .cursor_dup_read::<tables::AccountChangeSets>()?
+                            .seek_by_key_subkey(changeset_block_number, address)?
+                            .filter(|acc| acc.address == address))
+                    },
+                )?
                 .ok_or(ProviderError::AccountChangesetNotFound {
                     block_number: changeset_block_number,
                     address,
                 })?
                 .info),
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
-                Ok(self.tx.get::<tables::PlainAccountState>(address)?)
+                match self.tx.get::<tables::PlainAccountState>(address)? {
+                    Some(account) => Ok(Some(account)),
+                    None if self.snap_synced => snap_synced_account(self.tx, address),
+                    None => Ok(None),
+                }
             }
         }
     }
 }
 
+impl<TX: DbTx> ChangeSetReader for HistoricalStateProviderRef<'_, TX> {
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::AccountChangeSets,
+                block_number,
+                |static_file| static_file.account_block_changeset(block_number).map(Some),
+                || {
+                    self.tx
+                        .cursor_read::<tables::AccountChangeSets>()?
+                        .walk_range(block_number..=block_number)?
+                        .map(|result| -> ProviderResult<_> {
+                            let (_, account_before) = result?;
+                            Ok(account_before)
+                        })
+                        .collect::<ProviderResult<_>>()
+                        .map(Some)
+                },
+            )
+            .map(Option::unwrap_or_default)
+    }
+}
+
+impl<TX: DbTx> StorageChangeSetReader for HistoricalStateProviderRef<'_, TX> {
+    fn storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        self.static_file_provider
+            .get_with_static_file_or_database(
+                StaticFileSegment::StorageChangeSets,
+                block_number,
+                |static_file| static_file.storage_changeset(block_number).map(Some),
+                || {
+                    self.tx
+                        .cursor_dup_read::<tables::StorageChangeSets>()?
+                        .walk_range(BlockNumberAddress::range(block_number..=block_number))?
+                        .map(|result| -> ProviderResult<_> { Ok(result?) })
+                        .collect::<ProviderResult<_>>()
+                        .map(Some)
+                },
+            )
+            .map(Option::unwrap_or_default)
+    }
+}
+
 impl<TX: DbTx> BlockHashReader for HistoricalStateProviderRef<'_, TX> {
     /// Get block hash by number.
     fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
@@ -397,6 +669,65 @@ impl<TX: DbTx> StateProofProvider for HistoricalStateProviderRef<'_, TX> {
     }
 }
 
+impl<TX: DbTx> StateRangeProvider for HistoricalStateProviderRef<'_, TX> {
+    fn account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        let mut revert_state = self.revert_state()?;
+        revert_state.extend(hashed_state);
+        StateRange::overlay_account_range(self.tx, revert_state, start, limit, with_storage)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        let mut revert_storage = self.revert_storage(address)?;
+        revert_storage.extend(&hashed_storage);
+        Ok(StateRange::overlay_storage_range(self.tx, address, revert_storage, start, limit)?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        // Changesets are keyed by address, so the reverts of all accounts need to be hashed.
+        let mut revert_storage =
+            self.revert_state()?.storages.remove(&hashed_address).unwrap_or_default();
+        revert_storage.extend(&hashed_storage);
+        Ok(StateRange::overlay_hashed_storage_range(
+            self.tx,
+            hashed_address,
+            revert_storage,
+            start,
+            limit,
+        )?)
+    }
+
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        let mut revert_state = self.revert_state()?;
+        revert_state.extend(hashed_state);
+        StateRange::overlay_hashed_account_range(self.tx, revert_state, start, limit)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+}
+
 impl<TX: DbTx> StateProvider for HistoricalStateProviderRef<'_, TX> {
     /// Get storage.
     fn storage(
@@ -407,10 +738,33 @@ impl<TX: DbTx> StateProvider for HistoricalStateProviderRef<'_, TX> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
-                    .cursor_dup_read::<tables::StorageChangeSets>()?
-                    .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
-                    .filter(|entry| entry.key == storage_key)
+                self.static_file_provider
+                    .get_with_static_file_or_database(
+                        StaticFileSegment::StorageChangeSets,
+                        changeset_block_number,
+                        |static_file| {
+                            Ok(static_file
+                                .encoded_storage_changeset(changeset_block_number)?
+                                .and_then(|change_set| {
+                                    StorageChangeSet::find_encoded(
+                                        change_set.raw_value(),
+                                        address,
+                                        storage_key,
+                                    )
+                                })
+                                .map(|entry| StorageEntry { key: entry.key, value: entry.value }))
+                        },
+                        || {
+                            Ok(self
+                                .tx
+                                .cursor_dup_read::<tables::StorageChangeSets>()?
+                                .seek_by_key_subkey(
+                                    (changeset_block_number, address).into(),
+                                    storage_key,
+                                )?
+                                .filter(|entry| entry.key == storage_key))
+                        },
+                    )?
                     .ok_or_else(|| ProviderError::StorageChangesetNotFound {
                         block_number: changeset_block_number,
                         address,
@@ -418,13 +772,19 @@ impl<TX: DbTx> StateProvider for HistoricalStateProviderRef<'_, TX> {
                     })?
                     .value,
             )),
-            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
-                .tx
-                .cursor_dup_read::<tables::PlainStorageState>()?
-                .seek_by_key_subkey(address, storage_key)?
-                .filter(|entry| entry.key == storage_key)
-                .map(|entry| entry.value)
-                .or(Some(StorageValue::ZERO))),
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
+                let value = match self
+                    .tx
+                    .cursor_dup_read::<tables::PlainStorageState>()?
+                    .seek_by_key_subkey(address, storage_key)?
+                    .filter(|entry| entry.key == storage_key)
+                {
+                    Some(entry) => Some(entry.value),
+                    None if self.snap_synced => snap_synced_storage(self.tx, address, storage_key)?,
+                    None => None,
+                };
+                Ok(value.or(Some(StorageValue::ZERO)))
+            }
         }
     }
 
@@ -446,6 +806,8 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the state was snap synced, see [`is_snap_synced`](super::is_snap_synced).
+    snap_synced: bool,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
@@ -455,7 +817,20 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            snap_synced: false,
+        }
+    }
+
+    /// Set whether the state was snap synced, so accounts and storage slots that are missing in
+    /// the plain state are read from the hashed state.
+    pub const fn with_snap_synced(mut self, snap_synced: bool) -> Self {
+        self.snap_synced = snap_synced;
+        self
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -476,6 +851,20 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         self
     }
 
+    /// Set the archive window of the account history, which is available even if it's lower than
+    /// the lowest available block number.
+    pub const fn with_account_history_window(mut self, window: ArchiveWindow) -> Self {
+        self.lowest_available_blocks.account_history_window = Some(window);
+        self
+    }
+
+    /// Set the archive window of the storage history, which is available even if it's lower than
+    /// the lowest available block number.
+    pub const fn with_storage_history_window(mut self, window: ArchiveWindow) -> Self {
+        self.lowest_available_blocks.storage_history_window = Some(window);
+        self
+    }
+
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
@@ -485,6 +874,7 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
             self.lowest_available_blocks,
             self.static_file_provider.clone(),
         )
+        .with_snap_synced(self.snap_synced)
     }
 }
 
@@ -503,22 +893,40 @@ pub struct LowestAvailableBlocks {
     /// [`reth_prune_types::PruneSegment::StorageHistory`] was pruned.
     /// [`Option::None`] means all history is available.
     pub storage_history_block_number: Option<BlockNumber>,
+    /// Archive window of the account history that was retained by
+    /// [`reth_prune_types::PruneMode::Window`].
+    pub account_history_window: Option<ArchiveWindow>,
+    /// Archive window of the storage history that was retained by
+    /// [`reth_prune_types::PruneMode::Window`].
+    pub storage_history_window: Option<ArchiveWindow>,
 }
 
 impl LowestAvailableBlocks {
     /// Check if account history is available at the provided block number, i.e. lowest available
-    /// block number for account history is less than or equal to the provided block number.
+    /// block number for account history is less than or equal to the provided block number, or
+    /// the block number is in its archive window.
     pub fn is_account_history_available(&self, at: BlockNumber) -> bool {
-        self.account_history_block_number.map(|block_number| block_number <= at).unwrap_or(true)
+        self.account_history_block_number.map(|block_number| block_number <= at).unwrap_or(true) ||
+            self.account_history_window.is_some_and(|window| is_in_window(window, at))
     }
 
     /// Check if storage history is available at the provided block number, i.e. lowest available
-    /// block number for storage history is less than or equal to the provided block number.
+    /// block number for storage history is less than or equal to the provided block number, or
+    /// the block number is in its archive window.
     pub fn is_storage_history_available(&self, at: BlockNumber) -> bool {
-        self.storage_history_block_number.map(|block_number| block_number <= at).unwrap_or(true)
+        self.storage_history_block_number.map(|block_number| block_number <= at).unwrap_or(true) ||
+            self.storage_history_window.is_some_and(|window| is_in_window(window, at))
     }
 }
 
+/// Returns true if the history at the block number is available in the archive window.
+///
+/// The block after the window is available as well, since the lowest changeset after the window
+/// is retained for every account and storage slot.
+const fn is_in_window(window: ArchiveWindow, at: BlockNumber) -> bool {
+    window.start <= at && at <= window.end.saturating_add(1)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -534,6 +942,7 @@ mod tests {
         transaction::{DbTx, DbTxMut},
     };
     use reth_primitives::{Account, StorageEntry};
+    use reth_prune_types::ArchiveWindow;
     use reth_storage_errors::provider::ProviderError;
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
@@ -791,6 +1200,7 @@ mod tests {
             LowestAvailableBlocks {
                 account_history_block_number: Some(3),
                 storage_history_block_number: Some(3),
+                ..Default::default()
             },
             static_file_provider.clone(),
         );
@@ -811,6 +1221,7 @@ mod tests {
             LowestAvailableBlocks {
                 account_history_block_number: Some(2),
                 storage_history_block_number: Some(2),
+                ..Default::default()
             },
             static_file_provider.clone(),
         );
@@ -828,13 +1239,56 @@ mod tests {
             LowestAvailableBlocks {
                 account_history_block_number: Some(1),
                 storage_history_block_number: Some(1),
+                ..Default::default()
             },
-            static_file_provider,
+            static_file_provider.clone(),
+        );
+        assert_eq!(provider.account_history_lookup(ADDRESS), Ok(HistoryInfo::MaybeInPlainState));
+        assert_eq!(
+            provider.storage_history_lookup(ADDRESS, STORAGE),
+            Ok(HistoryInfo::MaybeInPlainState)
+        );
+
+        // provider block_number < lowest available block number, but in the archive window,
+        // i.e. state at provider block is available
+        let window = ArchiveWindow { start: 2, end: 3, distance: 0 };
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            &tx,
+            4,
+            LowestAvailableBlocks {
+                account_history_block_number: Some(10),
+                storage_history_block_number: Some(10),
+                account_history_window: Some(window),
+                storage_history_window: Some(window),
+            },
+            static_file_provider.clone(),
         );
         assert_eq!(provider.account_history_lookup(ADDRESS), Ok(HistoryInfo::MaybeInPlainState));
         assert_eq!(
             provider.storage_history_lookup(ADDRESS, STORAGE),
             Ok(HistoryInfo::MaybeInPlainState)
         );
+
+        // provider block_number < lowest available block number, and after the archive window,
+        // i.e. state at provider block is pruned
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            &tx,
+            5,
+            LowestAvailableBlocks {
+                account_history_block_number: Some(10),
+                storage_history_block_number: Some(10),
+                account_history_window: Some(window),
+                storage_history_window: Some(window),
+            },
+            static_file_provider,
+        );
+        assert_eq!(
+            provider.account_history_lookup(ADDRESS),
+            Err(ProviderError::StateAtBlockPruned(provider.block_number))
+        );
+        assert_eq!(
+            provider.storage_history_lookup(ADDRESS, STORAGE),
+            Err(ProviderError::StateAtBlockPruned(provider.block_number))
+        );
     }
 }
@@ -1,5 +1,8 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{macros::delegate_provider_impls, snap_synced_account, snap_synced_storage},
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, StateProvider, StateRootProvider,
 };
 use alloy_primitives::{
@@ -12,17 +15,18 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_primitives::{Account, Bytecode, StaticFileSegment};
-use reth_storage_api::{StateProofProvider, StorageRootProvider};
+use reth_storage_api::{StateProofProvider, StateRangeProvider, StorageRootProvider};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie::{
     proof::{Proof, StorageProof},
+    range::{HashedAccountRange, HashedStorageRange, StateRange},
     updates::TrieUpdates,
     witness::TrieWitness,
     AccountProof, HashedPostState, HashedStorage, MultiProof, StateRoot, StorageRoot, TrieInput,
 };
 use reth_trie_db::{
-    DatabaseProof, DatabaseStateRoot, DatabaseStorageProof, DatabaseStorageRoot,
-    DatabaseTrieWitness,
+    DatabaseProof, DatabaseStateRange, DatabaseStateRoot, DatabaseStorageProof,
+    DatabaseStorageRoot, DatabaseTrieWitness,
 };
 
 /// State provider over latest state that takes tx reference.
@@ -32,19 +36,32 @@ pub struct LatestStateProviderRef<'b, TX: DbTx> {
     tx: &'b TX,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the state was snap synced, see [`is_snap_synced`](super::is_snap_synced).
+    snap_synced: bool,
 }
 
 impl<'b, TX: DbTx> LatestStateProviderRef<'b, TX> {
     /// Create new state provider
     pub const fn new(tx: &'b TX, static_file_provider: StaticFileProvider) -> Self {
-        Self { tx, static_file_provider }
+        Self { tx, static_file_provider, snap_synced: false }
+    }
+
+    /// Set whether the state was snap synced, so accounts and storage slots that are missing in
+    /// the plain state are read from the hashed state.
+    pub const fn with_snap_synced(mut self, snap_synced: bool) -> Self {
+        self.snap_synced = snap_synced;
+        self
     }
 }
 
 impl<TX: DbTx> AccountReader for LatestStateProviderRef<'_, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
-        self.tx.get::<tables::PlainAccountState>(address).map_err(Into::into)
+        match self.tx.get::<tables::PlainAccountState>(address)? {
+            Some(account) => Ok(Some(account)),
+            None if self.snap_synced => snap_synced_account(self.tx, address),
+            None => Ok(None),
+        }
     }
 }
 
@@ -161,6 +178,55 @@ impl<TX: DbTx> StateProofProvider for LatestStateProviderRef<'_, TX> {
     }
 }
 
+impl<TX: DbTx> StateRangeProvider for LatestStateProviderRef<'_, TX> {
+    fn account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        StateRange::overlay_account_range(self.tx, hashed_state, start, limit, with_storage)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(StateRange::overlay_storage_range(self.tx, address, hashed_storage, start, limit)?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(StateRange::overlay_hashed_storage_range(
+            self.tx,
+            hashed_address,
+            hashed_storage,
+            start,
+            limit,
+        )?)
+    }
+
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        StateRange::overlay_hashed_account_range(self.tx, hashed_state, start, limit)
+            .map_err(|err| ProviderError::Database(err.into()))
+    }
+}
+
 impl<TX: DbTx> StateProvider for LatestStateProviderRef<'_, TX> {
     /// Get storage.
     fn storage(
@@ -174,6 +240,9 @@ impl<TX: DbTx> StateProvider for LatestStateProviderRef<'_, TX> {
                 return Ok(Some(entry.value))
             }
         }
+        if self.snap_synced {
+            return snap_synced_storage(self.tx, account, storage_key)
+        }
         Ok(None)
     }
 
@@ -190,18 +259,28 @@ pub struct LatestStateProvider<TX: DbTx> {
     db: TX,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the state was snap synced, see [`is_snap_synced`](super::is_snap_synced).
+    snap_synced: bool,
 }
 
 impl<TX: DbTx> LatestStateProvider<TX> {
     /// Create new state provider
     pub const fn new(db: TX, static_file_provider: StaticFileProvider) -> Self {
-        Self { db, static_file_provider }
+        Self { db, static_file_provider, snap_synced: false }
+    }
+
+    /// Set whether the state was snap synced, so accounts and storage slots that are missing in
+    /// the plain state are read from the hashed state.
+    pub const fn with_snap_synced(mut self, snap_synced: bool) -> Self {
+        self.snap_synced = snap_synced;
+        self
     }
 
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> LatestStateProviderRef<'_, TX> {
         LatestStateProviderRef::new(&self.db, self.static_file_provider.clone())
+            .with_snap_synced(self.snap_synced)
     }
 }
 
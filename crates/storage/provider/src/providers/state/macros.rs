@@ -51,6 +51,12 @@ macro_rules! delegate_provider_impls {
                 fn storage_root(&self, address: alloy_primitives::Address, storage: reth_trie::HashedStorage) -> reth_storage_errors::provider::ProviderResult<alloy_primitives::B256>;
                 fn storage_proof(&self, address: alloy_primitives::Address, slot: alloy_primitives::B256, storage: reth_trie::HashedStorage) -> reth_storage_errors::provider::ProviderResult<reth_trie::StorageProof>;
             }
+            StateRangeProvider $(where [$($generics)*])? {
+                fn account_range(&self, hashed_state: reth_trie::HashedPostState, start: alloy_primitives::B256, limit: usize, with_storage: bool) -> reth_storage_errors::provider::ProviderResult<reth_trie::range::HashedAccountRange>;
+                fn storage_range(&self, address: alloy_primitives::Address, hashed_storage: reth_trie::HashedStorage, start: alloy_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<reth_trie::range::HashedStorageRange>;
+                fn hashed_storage_range(&self, hashed_address: alloy_primitives::B256, hashed_storage: reth_trie::HashedStorage, start: alloy_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<reth_trie::range::HashedStorageRange>;
+                fn hashed_account_range(&self, hashed_state: reth_trie::HashedPostState, start: alloy_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<reth_trie::range::HashedAccountRange>;
+            }
             StateProofProvider $(where [$($generics)*])? {
                 fn proof(&self, input: reth_trie::TrieInput, address: alloy_primitives::Address, slots: &[alloy_primitives::B256]) -> reth_storage_errors::provider::ProviderResult<reth_trie::AccountProof>;
                 fn multiproof(&self, input: reth_trie::TrieInput, targets: alloy_primitives::map::HashMap<alloy_primitives::B256, alloy_primitives::map::HashSet<alloy_primitives::B256>>) -> reth_storage_errors::provider::ProviderResult<reth_trie::MultiProof>;
@@ -2,3 +2,46 @@
 pub(crate) mod historical;
 pub(crate) mod latest;
 pub(crate) mod macros;
+
+use alloy_primitives::{keccak256, Address, StorageKey, StorageValue};
+use reth_db::tables;
+use reth_db_api::{cursor::DbDupCursorRO, transaction::DbTx};
+use reth_primitives::Account;
+use reth_stages_types::StageId;
+use reth_storage_errors::provider::ProviderResult;
+
+/// Returns `true` if the state of the database was downloaded at a pivot block by the
+/// [`StageId::SnapSync`] stage.
+///
+/// The plain state of such a database only contains the accounts and storage slots that changed
+/// after the pivot, the rest of the state is only available in the hashed tables. State providers
+/// of such a database are built with the flag set, to fall back to the hashed state.
+pub fn is_snap_synced<TX: DbTx>(tx: &TX) -> ProviderResult<bool> {
+    Ok(tx
+        .get::<tables::StageCheckpointProgresses>(StageId::SnapSync.to_string())?
+        .is_some_and(|progress| !progress.is_empty()))
+}
+
+/// Reads an account that is missing in the plain state of a snap synced database from the
+/// hashed state.
+pub(crate) fn snap_synced_account<TX: DbTx>(
+    tx: &TX,
+    address: Address,
+) -> ProviderResult<Option<Account>> {
+    Ok(tx.get::<tables::HashedAccounts>(keccak256(address))?)
+}
+
+/// Reads a storage slot that is missing in the plain state of a snap synced database from the
+/// hashed state.
+pub(crate) fn snap_synced_storage<TX: DbTx>(
+    tx: &TX,
+    address: Address,
+    storage_key: StorageKey,
+) -> ProviderResult<Option<StorageValue>> {
+    let hashed_key = keccak256(storage_key);
+    Ok(tx
+        .cursor_dup_read::<tables::HashedStorages>()?
+        .seek_by_key_subkey(keccak256(address), hashed_key)?
+        .filter(|entry| entry.key == hashed_key)
+        .map(|entry| entry.value))
+}
@@ -3,16 +3,25 @@ use super::{
     LoadedJarRef,
 };
 use crate::{
-    to_range, BlockHashReader, BlockNumReader, HeaderProvider, ReceiptProvider,
-    TransactionsProvider,
+    to_range, BlockHashReader, BlockNumReader, ChangeSetReader, HeaderProvider, ReceiptProvider,
+    StorageChangeSetReader, TransactionsProvider,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
 use reth_chainspec::ChainInfo;
-use reth_db::static_file::{HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask};
-use reth_db_api::models::CompactU256;
+use reth_db::{
+    static_file::{
+        AccountChangeSetMask, HeaderMask, ReceiptMask, StaticFileCursor, StorageChangeSetMask,
+        TransactionMask,
+    },
+    RawValue,
+};
+use reth_db_api::models::{
+    AccountBeforeTx, AccountChangeSet, BlockNumberAddress, CompactU256, StorageChangeSet,
+};
 use reth_primitives::{
-    Header, Receipt, SealedHeader, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
+    Header, Receipt, SealedHeader, StorageEntry, TransactionMeta, TransactionSigned,
+    TransactionSignedNoHash,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use std::{
@@ -73,6 +82,26 @@ impl<'a> StaticFileJarProvider<'a> {
         self.metrics = Some(metrics);
         self
     }
+
+    /// Returns the encoded account changeset of the block.
+    pub fn encoded_account_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<RawValue<AccountChangeSet>>> {
+        Ok(self
+            .cursor()?
+            .get_one::<AccountChangeSetMask<RawValue<AccountChangeSet>>>(block_number.into())?)
+    }
+
+    /// Returns the encoded storage changeset of the block.
+    pub fn encoded_storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<RawValue<StorageChangeSet>>> {
+        Ok(self
+            .cursor()?
+            .get_one::<StorageChangeSetMask<RawValue<StorageChangeSet>>>(block_number.into())?)
+    }
 }
 
 impl HeaderProvider for StaticFileJarProvider<'_> {
@@ -199,8 +228,10 @@ impl TransactionsProvider for StaticFileJarProvider<'_> {
         let mut cursor = self.cursor()?;
 
         Ok(cursor
-            .get_one::<TransactionMask<TransactionSignedNoHash>>((&hash).into())?
-            .and_then(|res| (res.hash() == hash).then(|| cursor.number()).flatten()))
+            .get_one_by_key::<TransactionMask<TransactionSignedNoHash>>(hash.as_slice(), |tx| {
+                tx.hash() == hash
+            })?
+            .and_then(|_| cursor.number()))
     }
 
     fn transaction_by_id(&self, num: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
@@ -220,7 +251,9 @@ impl TransactionsProvider for StaticFileJarProvider<'_> {
     fn transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<TransactionSigned>> {
         Ok(self
             .cursor()?
-            .get_one::<TransactionMask<TransactionSignedNoHash>>((&hash).into())?
+            .get_one_by_key::<TransactionMask<TransactionSignedNoHash>>(hash.as_slice(), |tx| {
+                tx.hash() == hash
+            })?
             .map(|tx| tx.with_hash()))
     }
 
@@ -326,3 +359,40 @@ impl ReceiptProvider for StaticFileJarProvider<'_> {
         Ok(receipts)
     }
 }
+
+impl ChangeSetReader for StaticFileJarProvider<'_> {
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        Ok(self
+            .cursor()?
+            .get_one::<AccountChangeSetMask<AccountChangeSet>>(block_number.into())?
+            .map(|change_set| change_set.0)
+            .unwrap_or_default())
+    }
+}
+
+impl StorageChangeSetReader for StaticFileJarProvider<'_> {
+    fn storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        Ok(self
+            .cursor()?
+            .get_one::<StorageChangeSetMask<StorageChangeSet>>(block_number.into())?
+            .map(|change_set| {
+                change_set
+                    .0
+                    .into_iter()
+                    .map(|entry| {
+                        (
+                            BlockNumberAddress((block_number, entry.address)),
+                            StorageEntry { key: entry.key, value: entry.value },
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
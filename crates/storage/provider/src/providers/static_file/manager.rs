@@ -1,11 +1,12 @@
 use super::{
-    metrics::StaticFileProviderMetrics, writer::StaticFileWriters, LoadedJar,
-    StaticFileJarProvider, StaticFileProviderRW, StaticFileProviderRWRefMut,
+    metrics::StaticFileProviderMetrics, recompress, writer::StaticFileWriters, LoadedJar,
+    RecompressedStaticFile, StaticFileJarProvider, StaticFileProviderRW,
+    StaticFileProviderRWRefMut, StaticFileWritersLock,
 };
 use crate::{
-    to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, HeaderProvider,
-    ReceiptProvider, StageCheckpointReader, StatsReader, TransactionVariant, TransactionsProvider,
-    TransactionsProviderExt, WithdrawalsProvider,
+    to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, ChangeSetReader,
+    HeaderProvider, ReceiptProvider, StageCheckpointReader, StatsReader, StorageChangeSetReader,
+    TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use alloy_eips::BlockHashOrNumber;
 use alloy_primitives::{keccak256, Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256};
@@ -16,23 +17,26 @@ use reth_chainspec::{ChainInfo, ChainSpecProvider};
 use reth_db::{
     lockfile::StorageLock,
     static_file::{iter_static_files, HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask},
-    tables,
+    tables, RawValue,
 };
 use reth_db_api::{
     cursor::DbCursorRO,
-    models::{CompactU256, StoredBlockBodyIndices},
+    models::{
+        AccountBeforeTx, AccountChangeSet, BlockNumberAddress, CompactU256, StorageChangeSet,
+        StoredBlockBodyIndices,
+    },
     table::Table,
     transaction::DbTx,
 };
-use reth_nippy_jar::{NippyJar, NippyJarChecker, CONFIG_FILE_EXTENSION};
+use reth_nippy_jar::{NippyJar, NippyJarChecker, NippyJarIndex, CONFIG_FILE_EXTENSION};
 use reth_primitives::{
     static_file::{
-        find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive,
-        DEFAULT_BLOCKS_PER_STATIC_FILE,
+        find_fixed_range, Compression, HighestStaticFiles, SegmentConfig, SegmentHeader,
+        SegmentRangeInclusive, DEFAULT_BLOCKS_PER_STATIC_FILE,
     },
     Block, BlockWithSenders, Header, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
-    StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash, Withdrawal,
-    Withdrawals,
+    StaticFileSegment, StorageEntry, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
+    Withdrawal, Withdrawals,
 };
 use reth_stages_types::{PipelineTarget, StageId};
 use reth_storage_api::DBProvider;
@@ -44,7 +48,7 @@ use std::{
     sync::{mpsc, Arc},
 };
 use strum::IntoEnumIterator;
-use tracing::{info, trace, warn};
+use tracing::{debug, info, trace, warn};
 
 /// Alias type for a map that can be queried for block ranges from a transaction
 /// segment respectively. It uses `TxNumber` to represent the transaction end of a static file
@@ -80,7 +84,13 @@ pub struct StaticFileProvider(pub(crate) Arc<StaticFileProviderInner>);
 impl StaticFileProvider {
     /// Creates a new [`StaticFileProvider`].
     fn new(path: impl AsRef<Path>, access: StaticFileAccess) -> ProviderResult<Self> {
+        let is_read_write = access.is_read_write();
         let provider = Self(Arc::new(StaticFileProviderInner::new(path, access)?));
+        if is_read_write {
+            // Finishes recompressions that were interrupted after the rewritten jars were complete.
+            recompress::finish_pending_recompressions(&provider.path)
+                .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        }
         provider.initialize_index()?;
         Ok(provider)
     }
@@ -215,6 +225,8 @@ pub struct StaticFileProviderInner {
     access: StaticFileAccess,
     /// Number of blocks per file.
     blocks_per_file: u64,
+    /// Configuration of new static files, per segment.
+    segment_configs: HashMap<StaticFileSegment, SegmentConfig>,
     /// Write lock for when access is [`StaticFileAccess::RW`].
     _lock_file: Option<StorageLock>,
 }
@@ -237,6 +249,7 @@ impl StaticFileProviderInner {
             metrics: None,
             access,
             blocks_per_file: DEFAULT_BLOCKS_PER_STATIC_FILE,
+            segment_configs: Default::default(),
             _lock_file,
         };
 
@@ -252,6 +265,11 @@ impl StaticFileProviderInner {
     pub const fn find_fixed_range(&self, block: BlockNumber) -> SegmentRangeInclusive {
         find_fixed_range(block, self.blocks_per_file)
     }
+
+    /// Returns the configuration that new static files of the segment are created with.
+    pub fn segment_config(&self, segment: StaticFileSegment) -> SegmentConfig {
+        self.segment_configs.get(&segment).copied().unwrap_or_else(|| segment.config())
+    }
 }
 
 impl StaticFileProvider {
@@ -264,6 +282,19 @@ impl StaticFileProvider {
         Self(Arc::new(provider))
     }
 
+    /// Sets the configuration that new static files of each segment are created with.
+    ///
+    /// Segments without a configuration use [`StaticFileSegment::config`].
+    pub fn with_segment_configs(
+        self,
+        segment_configs: impl IntoIterator<Item = (StaticFileSegment, SegmentConfig)>,
+    ) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.segment_configs.extend(segment_configs);
+        Self(Arc::new(provider))
+    }
+
     /// Enables metrics on the [`StaticFileProvider`].
     pub fn with_metrics(self) -> Self {
         let mut provider =
@@ -416,6 +447,36 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Given a segment and block, it rewrites the jar of the respective block range with
+    /// `compression`, replacing its files on disk.
+    ///
+    /// With [`Compression::ZstdWithDictionary`], dictionaries of at most `max_dict_size` bytes are
+    /// trained on rows of the jar.
+    ///
+    /// CAUTION: the segment should not have an open writer, and cached providers of the jar should
+    /// be dropped before calling this or IT WILL deadlock.
+    pub fn recompress_jar(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+        compression: Compression,
+        max_dict_size: usize,
+    ) -> ProviderResult<RecompressedStaticFile> {
+        if self.access.is_read_only() {
+            return Err(ProviderError::ReadOnlyStaticFileAccess)
+        }
+
+        let fixed_block_range = self.find_fixed_range(block);
+        self.map.remove(&(fixed_block_range.end(), segment));
+
+        recompress::recompress_jar(
+            &self.path.join(segment.filename(&fixed_block_range)),
+            compression,
+            max_dict_size,
+        )
+        .map_err(|e| ProviderError::NippyJar(e.to_string()))
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -672,6 +733,11 @@ impl StaticFileProvider {
                 continue
             }
 
+            if segment.is_change_sets() && self.get_highest_static_file_block(segment).is_none() {
+                // Changesets are only moved to static files if their history is not pruned.
+                continue
+            }
+
             let initial_highest_block = self.get_highest_static_file_block(segment);
 
             //  File consistency is broken if:
@@ -747,6 +813,7 @@ impl StaticFileProvider {
                     segment,
                     highest_block,
                     highest_block,
+                    |number| number,
                 )?,
                 StaticFileSegment::Transactions => self
                     .ensure_invariants::<_, tables::Transactions>(
@@ -754,13 +821,31 @@ impl StaticFileProvider {
                         segment,
                         highest_tx,
                         highest_block,
+                        |tx_number| tx_number,
                     )?,
                 StaticFileSegment::Receipts => self.ensure_invariants::<_, tables::Receipts>(
                     provider,
                     segment,
                     highest_tx,
                     highest_block,
+                    |tx_number| tx_number,
                 )?,
+                StaticFileSegment::AccountChangeSets => self
+                    .ensure_invariants::<_, tables::AccountChangeSets>(
+                        provider,
+                        segment,
+                        highest_block,
+                        highest_block,
+                        |number| number,
+                    )?,
+                StaticFileSegment::StorageChangeSets => self
+                    .ensure_invariants::<_, tables::StorageChangeSets>(
+                        provider,
+                        segment,
+                        highest_block,
+                        highest_block,
+                        |key| key.block_number(),
+                    )?,
             } {
                 update_unwind_target(unwind);
             }
@@ -800,12 +885,16 @@ impl StaticFileProvider {
     ///
     /// * If the database tables overlap with static files and have contiguous keys, or the
     ///   checkpoint block matches the highest static files block, then [`None`] will be returned.
-    fn ensure_invariants<Provider, T: Table<Key = u64>>(
+    ///
+    /// `entry_of` maps a database table key to the static file entry ([`TxNumber`] or
+    /// [`BlockNumber`]) it belongs to.
+    fn ensure_invariants<Provider, T: Table>(
         &self,
         provider: &Provider,
         segment: StaticFileSegment,
         highest_static_file_entry: Option<u64>,
         highest_static_file_block: Option<BlockNumber>,
+        entry_of: impl Fn(T::Key) -> u64,
     ) -> ProviderResult<Option<BlockNumber>>
     where
         Provider: DBProvider + BlockReader + StageCheckpointReader,
@@ -814,7 +903,7 @@ impl StaticFileProvider {
         let highest_static_file_block = highest_static_file_block.unwrap_or_default();
         let mut db_cursor = provider.tx_ref().cursor_read::<T>()?;
 
-        if let Some((db_first_entry, _)) = db_cursor.first()? {
+        if let Some(db_first_entry) = db_cursor.first()?.map(|(key, _)| entry_of(key)) {
             // If there is a gap between the entry found in static file and
             // database, then we have most likely lost static file data and need to unwind so we can
             // load it again
@@ -832,7 +921,7 @@ impl StaticFileProvider {
                 return Ok(Some(highest_static_file_block))
             }
 
-            if let Some((db_last_entry, _)) = db_cursor.last()? {
+            if let Some(db_last_entry) = db_cursor.last()?.map(|(key, _)| entry_of(key)) {
                 if db_last_entry > highest_static_file_entry {
                     return Ok(None)
                 }
@@ -845,13 +934,18 @@ impl StaticFileProvider {
             .get_stage_checkpoint(match segment {
                 StaticFileSegment::Headers => StageId::Headers,
                 StaticFileSegment::Transactions => StageId::Bodies,
-                StaticFileSegment::Receipts => StageId::Execution,
+                StaticFileSegment::Receipts |
+                StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets => StageId::Execution,
             })?
             .unwrap_or_default()
             .block_number;
 
         // If the checkpoint is ahead, then we lost static file data. May be data corruption.
-        if checkpoint_block_number > highest_static_file_block {
+        //
+        // Changesets are only moved to static files once indexed, so they are expected to lag
+        // behind the execution checkpoint.
+        if checkpoint_block_number > highest_static_file_block && !segment.is_change_sets() {
             info!(
                 target: "reth::providers::static_file",
                 checkpoint_block_number,
@@ -876,6 +970,8 @@ impl StaticFileProvider {
             let mut writer = self.latest_writer(segment)?;
             if segment.is_headers() {
                 writer.prune_headers(highest_static_file_block - checkpoint_block_number)?;
+            } else if segment.is_change_sets() {
+                writer.prune_change_sets(highest_static_file_block - checkpoint_block_number)?;
             } else if let Some(block) = provider.block_body_indices(checkpoint_block_number)? {
                 let number = highest_static_file_entry - block.last_tx_num();
                 if segment.is_receipts() {
@@ -913,6 +1009,10 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            account_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            storage_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
         }
     }
 
@@ -939,6 +1039,137 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Builds the transaction hash index of the [`StaticFileSegment::Transactions`] static file
+    /// that contains the given block.
+    ///
+    /// The index allows [`TransactionsProvider::transaction_id`] and
+    /// [`TransactionsProvider::transaction_by_hash`] to find transactions of the static file
+    /// without the `TransactionHashNumbers` table. It is only kept for complete static files, since
+    /// pruning rows of a static file deletes its index.
+    pub fn build_transaction_hash_index(&self, block: BlockNumber) -> ProviderResult<()> {
+        let segment = StaticFileSegment::Transactions;
+        let fixed_block_range = self.find_fixed_range(block);
+
+        let tx_range = self
+            .get_or_create_jar_provider(segment, &fixed_block_range)?
+            .user_header()
+            .tx_range()
+            .copied();
+        let entries = match tx_range {
+            Some(tx_range) => self
+                .transaction_hashes_by_range(tx_range.start()..tx_range.end() + 1)?
+                .into_iter()
+                .map(|(hash, tx_number)| {
+                    (NippyJarIndex::fingerprint(hash.as_slice()), tx_number - tx_range.start())
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        self.get_or_create_jar_provider(segment, &fixed_block_range)?
+            .write_index(entries)
+            .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+        // Reload the jar with its index on next access.
+        self.remove_cached_provider(segment, fixed_block_range.end());
+
+        Ok(())
+    }
+
+    /// Builds the missing transaction hash indices of complete
+    /// [`StaticFileSegment::Transactions`] static files in ascending order, stopping after `limit`
+    /// indices if set.
+    ///
+    /// Returns the number of built indices.
+    pub fn build_missing_transaction_hash_indices(
+        &self,
+        limit: Option<usize>,
+    ) -> ProviderResult<usize> {
+        let segment = StaticFileSegment::Transactions;
+        let Some(highest_block) = self.get_highest_static_file_block(segment) else { return Ok(0) };
+
+        let mut built = 0;
+        let mut range = self.find_fixed_range(0);
+        while range.end() <= highest_block && limit.is_none_or(|limit| built < limit) {
+            if !self.get_or_create_jar_provider(segment, &range)?.has_index() {
+                debug!(target: "provider::static_file", ?range, "Building transaction hash index");
+                self.build_transaction_hash_index(range.start())?;
+                built += 1;
+            }
+            range = self.find_fixed_range(range.end() + 1);
+        }
+
+        Ok(built)
+    }
+
+    /// Returns the highest block up to which all [`StaticFileSegment::Transactions`] static files
+    /// have a transaction hash index, or `None` if the first static file has none.
+    pub fn transaction_hash_indexed_block(&self) -> ProviderResult<Option<BlockNumber>> {
+        let segment = StaticFileSegment::Transactions;
+        let Some(highest_block) = self.get_highest_static_file_block(segment) else {
+            return Ok(None)
+        };
+
+        let mut indexed_block = None;
+        let mut range = self.find_fixed_range(0);
+        while range.end() <= highest_block &&
+            self.get_or_create_jar_provider(segment, &range)?.has_index()
+        {
+            indexed_block = Some(range.end());
+            range = self.find_fixed_range(range.end() + 1);
+        }
+
+        Ok(indexed_block)
+    }
+
+    /// Returns the encoded account changeset of the block, or `None` if the block is not in static
+    /// files.
+    ///
+    /// Single changes can be looked up with [`AccountChangeSet::find_encoded`], without decoding
+    /// the whole changeset.
+    pub fn encoded_account_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<RawValue<AccountChangeSet>>> {
+        self.get_segment_provider_from_block(
+            StaticFileSegment::AccountChangeSets,
+            block_number,
+            None,
+        )
+        .and_then(|provider| provider.encoded_account_changeset(block_number))
+        .or_else(|err| {
+            if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                Ok(None)
+            } else {
+                Err(err)
+            }
+        })
+    }
+
+    /// Returns the encoded storage changeset of the block, or `None` if the block is not in static
+    /// files.
+    ///
+    /// Single changes can be looked up with [`StorageChangeSet::find_encoded`], without decoding
+    /// the whole changeset.
+    pub fn encoded_storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<RawValue<StorageChangeSet>>> {
+        self.get_segment_provider_from_block(
+            StaticFileSegment::StorageChangeSets,
+            block_number,
+            None,
+        )
+        .and_then(|provider| provider.encoded_storage_changeset(block_number))
+        .or_else(|err| {
+            if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                Ok(None)
+            } else {
+                Err(err)
+            }
+        })
+    }
+
     /// Fetches data within a specified range across multiple static files.
     ///
     /// This function iteratively retrieves data using `get_fn` for each item in the given range.
@@ -956,7 +1187,9 @@ impl StaticFileProvider {
         P: FnMut(&T) -> bool,
     {
         let get_provider = |start: u64| match segment {
-            StaticFileSegment::Headers => {
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
             StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
@@ -994,7 +1227,7 @@ impl StaticFileProvider {
                                 "Could not find block or tx number on a range request"
                             );
 
-                            let err = if segment.is_headers() {
+                            let err = if segment.is_block_based() {
                                 ProviderError::MissingStaticFileBlock(segment, number)
                             } else {
                                 ProviderError::MissingStaticFileTx(segment, number)
@@ -1032,7 +1265,9 @@ impl StaticFileProvider {
         T: std::fmt::Debug,
     {
         let get_provider = move |start: u64| match segment {
-            StaticFileSegment::Headers => {
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
             StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
@@ -1062,6 +1297,15 @@ impl StaticFileProvider {
         &self.path
     }
 
+    /// Blocks all appends, prunes and commits to the static files until the returned lock is
+    /// dropped, so that the files can be copied while the node is running.
+    ///
+    /// Rows that were appended but not committed before the lock was taken may still be in the
+    /// data files. They are removed once a copy is opened for writing, like after a crash.
+    pub fn lock_writers(&self) -> StaticFileWritersLock<'_> {
+        self.writers.lock_all()
+    }
+
     /// Retrieves data from the database or static file, wherever it's available.
     ///
     /// # Arguments
@@ -1084,7 +1328,9 @@ impl StaticFileProvider {
     {
         // If there is, check the maximum block or transaction number of the segment.
         let static_file_upper_bound = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => self.get_highest_static_file_block(segment),
             StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
                 self.get_highest_static_file_tx(segment)
             }
@@ -1126,7 +1372,9 @@ impl StaticFileProvider {
 
         // If there is, check the maximum block or transaction number of the segment.
         if let Some(static_file_upper_bound) = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
+            StaticFileSegment::Headers |
+            StaticFileSegment::AccountChangeSets |
+            StaticFileSegment::StorageChangeSets => self.get_highest_static_file_block(segment),
             StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
                 self.get_highest_static_file_tx(segment)
             }
@@ -1419,8 +1667,10 @@ impl TransactionsProvider for StaticFileProvider {
         self.find_static_file(StaticFileSegment::Transactions, |jar_provider| {
             let mut cursor = jar_provider.cursor()?;
             if cursor
-                .get_one::<TransactionMask<TransactionSignedNoHash>>((&tx_hash).into())?
-                .and_then(|tx| (tx.hash() == tx_hash).then_some(tx))
+                .get_one_by_key::<TransactionMask<TransactionSignedNoHash>>(
+                    tx_hash.as_slice(),
+                    |tx| tx.hash() == tx_hash,
+                )?
                 .is_some()
             {
                 Ok(cursor.number())
@@ -1461,9 +1711,11 @@ impl TransactionsProvider for StaticFileProvider {
         self.find_static_file(StaticFileSegment::Transactions, |jar_provider| {
             Ok(jar_provider
                 .cursor()?
-                .get_one::<TransactionMask<TransactionSignedNoHash>>((&hash).into())?
-                .map(|tx| tx.with_hash())
-                .and_then(|tx| (tx.hash_ref() == &hash).then_some(tx)))
+                .get_one_by_key::<TransactionMask<TransactionSignedNoHash>>(
+                    hash.as_slice(),
+                    |tx| tx.hash() == hash,
+                )?
+                .map(|tx| tx.with_hash()))
         })
     }
 
@@ -1626,6 +1878,48 @@ impl BlockReader for StaticFileProvider {
     }
 }
 
+impl ChangeSetReader for StaticFileProvider {
+    fn account_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<AccountBeforeTx>> {
+        self.get_segment_provider_from_block(
+            StaticFileSegment::AccountChangeSets,
+            block_number,
+            None,
+        )
+        .and_then(|provider| provider.account_block_changeset(block_number))
+        .or_else(|err| {
+            if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                Ok(Vec::new())
+            } else {
+                Err(err)
+            }
+        })
+    }
+}
+
+impl StorageChangeSetReader for StaticFileProvider {
+    fn storage_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        self.get_segment_provider_from_block(
+            StaticFileSegment::StorageChangeSets,
+            block_number,
+            None,
+        )
+        .and_then(|provider| provider.storage_changeset(block_number))
+        .or_else(|err| {
+            if let ProviderError::MissingStaticFileBlock(_, _) = err {
+                Ok(Vec::new())
+            } else {
+                Err(err)
+            }
+        })
+    }
+}
+
 impl WithdrawalsProvider for StaticFileProvider {
     fn withdrawals_by_block(
         &self,
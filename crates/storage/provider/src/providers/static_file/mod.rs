@@ -5,10 +5,13 @@ mod jar;
 pub use jar::StaticFileJarProvider;
 
 mod writer;
-pub use writer::{StaticFileProviderRW, StaticFileProviderRWRefMut};
+pub use writer::{StaticFileProviderRW, StaticFileProviderRWRefMut, StaticFileWritersLock};
 
 mod metrics;
 
+mod recompress;
+pub use recompress::{RecompressedStaticFile, DEFAULT_MAX_DICTIONARY_SIZE};
+
 use reth_nippy_jar::NippyJar;
 use reth_primitives::{static_file::SegmentHeader, StaticFileSegment};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -65,7 +68,9 @@ mod tests {
     };
     use reth_db_api::transaction::DbTxMut;
     use reth_primitives::{
-        static_file::{find_fixed_range, SegmentRangeInclusive, DEFAULT_BLOCKS_PER_STATIC_FILE},
+        static_file::{
+            find_fixed_range, Compression, SegmentRangeInclusive, DEFAULT_BLOCKS_PER_STATIC_FILE,
+        },
         Header, Receipt, TransactionSignedNoHash,
     };
     use reth_storage_api::{ReceiptProvider, TransactionsProvider};
@@ -547,4 +552,99 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_transaction_hash_index() {
+        let segment = StaticFileSegment::Transactions;
+        let blocks_per_file = 10;
+
+        let (static_dir, _) = create_test_static_files_dir();
+        let sf_rw = StaticFileProvider::read_write(&static_dir)
+            .expect("Failed to create static file provider")
+            .with_custom_blocks_per_file(blocks_per_file);
+
+        setup_tx_based_scenario(&sf_rw, segment, blocks_per_file);
+
+        let highest_tx = sf_rw.get_highest_static_file_tx(segment).unwrap();
+        let transactions = (0..=highest_tx)
+            .map(|id| sf_rw.transaction_by_id(id).unwrap().unwrap())
+            .collect::<Vec<_>>();
+
+        // Without an index, transactions can't be found by hash
+        assert_eq!(sf_rw.transaction_hash_indexed_block().unwrap(), None);
+        assert_eq!(sf_rw.transaction_id(transactions[0].hash()).unwrap(), None);
+
+        // Indices are built in ascending order
+        assert_eq!(sf_rw.build_missing_transaction_hash_indices(Some(1)).unwrap(), 1);
+        assert_eq!(sf_rw.transaction_hash_indexed_block().unwrap(), Some(blocks_per_file - 1));
+        assert_eq!(sf_rw.build_missing_transaction_hash_indices(None).unwrap(), 2);
+        assert_eq!(sf_rw.build_missing_transaction_hash_indices(None).unwrap(), 0);
+        assert_eq!(sf_rw.transaction_hash_indexed_block().unwrap(), Some(blocks_per_file * 3 - 1));
+
+        for (id, transaction) in transactions.iter().enumerate() {
+            assert_eq!(sf_rw.transaction_id(transaction.hash()).unwrap(), Some(id as TxNumber));
+            assert_eq!(
+                sf_rw.transaction_by_hash(transaction.hash()).unwrap().as_ref(),
+                Some(transaction)
+            );
+        }
+        assert_eq!(sf_rw.transaction_id(B256::random()).unwrap(), None);
+
+        // Pruning rows of the last static file removes its index
+        let mut writer = sf_rw.latest_writer(segment).unwrap();
+        writer.prune_transactions(1, blocks_per_file * 2).unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+
+        assert_eq!(sf_rw.transaction_hash_indexed_block().unwrap(), Some(blocks_per_file * 2 - 1));
+        assert_eq!(sf_rw.transaction_id(transactions[highest_tx as usize].hash()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_recompress_jar() {
+        let segment = StaticFileSegment::Headers;
+        let blocks_per_file = 10;
+        let tip = blocks_per_file * 3 - 1;
+
+        let (static_dir, _) = create_test_static_files_dir();
+        let sf_rw = StaticFileProvider::read_write(&static_dir)
+            .expect("Failed to create static file provider")
+            .with_custom_blocks_per_file(blocks_per_file);
+
+        let mut headers = Vec::new();
+        {
+            let mut header_writer = sf_rw.latest_writer(segment).unwrap();
+            for number in 0..=tip {
+                let header = Header { number, gas_limit: number * 1000, ..Default::default() };
+                header_writer
+                    .append_header(&header, U256::from(number), &BlockHash::random())
+                    .unwrap();
+                headers.push(header);
+            }
+            header_writer.commit().unwrap();
+        }
+
+        for compression in [Compression::Zstd, Compression::Uncompressed, Compression::Lz4] {
+            for block in (0..=tip).step_by(blocks_per_file as usize) {
+                let recompressed = sf_rw.recompress_jar(segment, block, compression, 0).unwrap();
+                assert_eq!(recompressed.rows, blocks_per_file as usize);
+
+                let jar_provider = sf_rw.get_segment_provider_from_block(segment, block, None);
+                assert_eq!(
+                    jar_provider.unwrap().compressor().is_some(),
+                    compression != Compression::Uncompressed
+                );
+            }
+
+            for header in &headers {
+                assert_eq!(sf_rw.header_by_number(header.number).unwrap().as_ref(), Some(header));
+            }
+        }
+
+        // Nothing is left to finish on the next read-write open
+        drop(sf_rw);
+        let sf_rw = StaticFileProvider::read_write(&static_dir).unwrap();
+        assert!(!static_dir.as_ref().join("recompress").exists());
+        assert_eq!(sf_rw.get_highest_static_file_block(segment), Some(tip));
+    }
 }
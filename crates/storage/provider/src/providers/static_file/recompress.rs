@@ -0,0 +1,199 @@
+//! Rewriting of existing static files with a different compression.
+//!
+//! A jar is recompressed into [`RECOMPRESS_DIR`] first. Once all of its files are written, a
+//! marker file is created next to them, and only then are they moved over the original ones.
+//! Jars with a marker are moved into place on the next read-write open of the static files,
+//! while jars without one are discarded.
+
+use reth_nippy_jar::{
+    NippyJar, NippyJarCursor, NippyJarError, NippyJarWriter, CONFIG_FILE_EXTENSION,
+    INDEX_FILE_EXTENSION, OFFSETS_FILE_EXTENSION,
+};
+use reth_primitives::static_file::{Compression, SegmentHeader};
+use std::path::{Path, PathBuf};
+
+/// Name of the directory inside the static files directory that recompressed jars are written
+/// to, before they replace the original ones.
+const RECOMPRESS_DIR: &str = "recompress";
+
+/// Extension of the marker file that is written once a recompressed jar is complete.
+const COMMITTED_EXTENSION: &str = "committed";
+
+/// Default maximum size of a trained zstd dictionary, in bytes.
+pub const DEFAULT_MAX_DICTIONARY_SIZE: usize = 64 * 1024;
+
+/// Maximum number of rows that a zstd dictionary is trained on.
+const MAX_DICTIONARY_SAMPLE_ROWS: usize = 100_000;
+
+/// Summary of a recompressed static file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecompressedStaticFile {
+    /// Number of rows in the static file.
+    pub rows: usize,
+    /// Size of all static file files before recompression, in bytes.
+    pub size_before: u64,
+    /// Size of all static file files after recompression, in bytes.
+    pub size_after: u64,
+}
+
+/// Rewrites the jar at `path` with `compression`, and replaces the original files with the
+/// rewritten ones.
+///
+/// With [`Compression::ZstdWithDictionary`], one dictionary of at most `max_dict_size` bytes is
+/// trained per column, on rows sampled evenly over the jar.
+pub(crate) fn recompress_jar(
+    path: &Path,
+    compression: Compression,
+    max_dict_size: usize,
+) -> Result<RecompressedStaticFile, NippyJarError> {
+    let jar = NippyJar::<SegmentHeader>::load(path)?;
+    let size_before = jar_size(&jar)?;
+
+    let tmp_dir = recompress_dir(path);
+    reth_fs_util::create_dir_all(&tmp_dir)?;
+    let tmp_path = tmp_dir.join(path.file_name().expect("static file path has a file name"));
+    remove_jar_files(&tmp_path)?;
+
+    // Dictionaries can't be trained without any rows.
+    let use_dict = compression == Compression::ZstdWithDictionary && jar.rows() > 0;
+
+    let tmp_jar = NippyJar::new(jar.columns(), &tmp_path, jar.user_header().clone());
+    let mut tmp_jar = match compression {
+        Compression::Lz4 => tmp_jar.with_lz4(),
+        Compression::Zstd | Compression::ZstdWithDictionary => {
+            tmp_jar.with_zstd(use_dict, max_dict_size)
+        }
+        Compression::Uncompressed => tmp_jar,
+    };
+
+    let mut cursor = NippyJarCursor::new(&jar)?;
+    if use_dict {
+        tmp_jar.prepare_compression(sample_columns(&jar, &mut cursor)?)?;
+    }
+
+    let mut writer = NippyJarWriter::new(tmp_jar)?;
+    let mut row = 0;
+    while let Some(values) = cursor.row_by_number(row)? {
+        for value in values {
+            writer.append_column(Some(Ok(value)))?;
+        }
+        row += 1;
+    }
+    writer.commit()?;
+    drop(writer);
+
+    // The index maps keys to rows, which are unchanged.
+    if jar.has_index() {
+        std::fs::copy(jar.index_path(), tmp_path.with_extension(INDEX_FILE_EXTENSION))?;
+    }
+    drop(cursor);
+
+    reth_fs_util::write(tmp_path.with_extension(COMMITTED_EXTENSION), [])?;
+    commit_jar(&tmp_path, path)?;
+
+    let size_after = jar_size(&NippyJar::<SegmentHeader>::load(path)?)?;
+    Ok(RecompressedStaticFile { rows: jar.rows(), size_before, size_after })
+}
+
+/// Moves every committed jar left in [`RECOMPRESS_DIR`] of `static_files_dir` into place, and
+/// removes the directory with any incomplete jar.
+pub(crate) fn finish_pending_recompressions(static_files_dir: &Path) -> Result<(), NippyJarError> {
+    let tmp_dir = static_files_dir.join(RECOMPRESS_DIR);
+    if !tmp_dir.exists() {
+        return Ok(())
+    }
+
+    for entry in reth_fs_util::read_dir(&tmp_dir)? {
+        let marker = entry.map_err(|err| NippyJarError::Custom(err.to_string()))?.path();
+        if marker.extension().is_some_and(|extension| extension == COMMITTED_EXTENSION) {
+            let tmp_path = marker.with_extension("");
+            let path = static_files_dir.join(tmp_path.file_name().expect("marker has a file name"));
+            commit_jar(&tmp_path, &path)?;
+        }
+    }
+
+    reth_fs_util::remove_dir_all(&tmp_dir)?;
+    Ok(())
+}
+
+/// Moves the files of the committed jar at `tmp_path` over the ones at `path`, and removes its
+/// marker.
+///
+/// Files that were moved before an interruption are skipped, so this can be repeated until it
+/// succeeds. The configuration is moved last, since it describes how the other files are read.
+fn commit_jar(tmp_path: &Path, path: &Path) -> Result<(), NippyJarError> {
+    for (from, to) in [
+        (
+            tmp_path.with_extension(OFFSETS_FILE_EXTENSION),
+            path.with_extension(OFFSETS_FILE_EXTENSION),
+        ),
+        (tmp_path.with_extension(INDEX_FILE_EXTENSION), path.with_extension(INDEX_FILE_EXTENSION)),
+        (tmp_path.to_path_buf(), path.to_path_buf()),
+        (
+            tmp_path.with_extension(CONFIG_FILE_EXTENSION),
+            path.with_extension(CONFIG_FILE_EXTENSION),
+        ),
+    ] {
+        if from.exists() {
+            reth_fs_util::rename(from, to)?;
+        }
+    }
+
+    reth_fs_util::remove_file(tmp_path.with_extension(COMMITTED_EXTENSION))?;
+    Ok(())
+}
+
+/// Reads up to [`MAX_DICTIONARY_SAMPLE_ROWS`] rows, evenly spaced over the jar, and returns their
+/// values per column.
+fn sample_columns(
+    jar: &NippyJar<SegmentHeader>,
+    cursor: &mut NippyJarCursor<'_, SegmentHeader>,
+) -> Result<Vec<Vec<Vec<u8>>>, NippyJarError> {
+    let step = jar.rows().div_ceil(MAX_DICTIONARY_SAMPLE_ROWS).max(1);
+
+    let mut columns = vec![Vec::new(); jar.columns()];
+    for row in (0..jar.rows()).step_by(step) {
+        let values = cursor.row_by_number(row)?.ok_or(NippyJarError::Custom(format!(
+            "row {row} of {} not found",
+            jar.data_path().display()
+        )))?;
+        for (column, value) in values.into_iter().enumerate() {
+            columns[column].push(value.to_vec());
+        }
+    }
+
+    Ok(columns)
+}
+
+/// Returns the total size of the files of a jar, in bytes.
+fn jar_size(jar: &NippyJar<SegmentHeader>) -> Result<u64, NippyJarError> {
+    let mut size = 0;
+    for path in [jar.data_path().to_path_buf(), jar.offsets_path(), jar.config_path()]
+        .into_iter()
+        .chain(jar.has_index().then(|| jar.index_path()))
+    {
+        size += reth_fs_util::metadata(path)?.len();
+    }
+    Ok(size)
+}
+
+/// Removes the files of a jar at `path`, if any.
+fn remove_jar_files(path: &Path) -> Result<(), NippyJarError> {
+    for path in [
+        path.to_path_buf(),
+        path.with_extension(OFFSETS_FILE_EXTENSION),
+        path.with_extension(INDEX_FILE_EXTENSION),
+        path.with_extension(CONFIG_FILE_EXTENSION),
+        path.with_extension(COMMITTED_EXTENSION),
+    ] {
+        if path.exists() {
+            reth_fs_util::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the [`RECOMPRESS_DIR`] of the static files directory that holds the jar at `path`.
+fn recompress_dir(path: &Path) -> PathBuf {
+    path.parent().unwrap_or(path).join(RECOMPRESS_DIR)
+}
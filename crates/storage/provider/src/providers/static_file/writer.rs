@@ -5,10 +5,10 @@ use crate::providers::static_file::metrics::StaticFileProviderOperation;
 use alloy_primitives::{BlockHash, BlockNumber, TxNumber, U256};
 use parking_lot::{lock_api::RwLockWriteGuard, RawRwLock, RwLock};
 use reth_codecs::Compact;
-use reth_db_api::models::CompactU256;
+use reth_db_api::models::{AccountChangeSet, CompactU256, StorageChangeSet};
 use reth_nippy_jar::{NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
-    static_file::{SegmentHeader, SegmentRangeInclusive},
+    static_file::{Compression, SegmentHeader, SegmentRangeInclusive},
     Header, Receipt, StaticFileSegment, TransactionSignedNoHash,
 };
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -29,6 +29,8 @@ pub(crate) struct StaticFileWriters {
     headers: RwLock<Option<StaticFileProviderRW>>,
     transactions: RwLock<Option<StaticFileProviderRW>>,
     receipts: RwLock<Option<StaticFileProviderRW>>,
+    account_changesets: RwLock<Option<StaticFileProviderRW>>,
+    storage_changesets: RwLock<Option<StaticFileProviderRW>>,
 }
 
 impl StaticFileWriters {
@@ -41,6 +43,8 @@ impl StaticFileWriters {
             StaticFileSegment::Headers => self.headers.write(),
             StaticFileSegment::Transactions => self.transactions.write(),
             StaticFileSegment::Receipts => self.receipts.write(),
+            StaticFileSegment::AccountChangeSets => self.account_changesets.write(),
+            StaticFileSegment::StorageChangeSets => self.storage_changesets.write(),
         };
 
         if write_guard.is_none() {
@@ -51,7 +55,13 @@ impl StaticFileWriters {
     }
 
     pub(crate) fn commit(&self) -> ProviderResult<()> {
-        for writer_lock in [&self.headers, &self.transactions, &self.receipts] {
+        for writer_lock in [
+            &self.headers,
+            &self.transactions,
+            &self.receipts,
+            &self.account_changesets,
+            &self.storage_changesets,
+        ] {
             let mut writer = writer_lock.write();
             if let Some(writer) = writer.as_mut() {
                 writer.commit()?;
@@ -59,6 +69,27 @@ impl StaticFileWriters {
         }
         Ok(())
     }
+
+    /// Locks the writers of all segments, in the same order as [`Self::commit`].
+    pub(crate) fn lock_all(&self) -> StaticFileWritersLock<'_> {
+        StaticFileWritersLock {
+            _guards: [
+                self.headers.write(),
+                self.transactions.write(),
+                self.receipts.write(),
+                self.account_changesets.write(),
+                self.storage_changesets.write(),
+            ],
+        }
+    }
+}
+
+/// Write locks on the [`StaticFileProviderRW`] of all segments, which are released on drop.
+///
+/// See [`StaticFileProvider::lock_writers`].
+#[derive(Debug)]
+pub struct StaticFileWritersLock<'a> {
+    _guards: [RwLockWriteGuard<'a, RawRwLock, Option<StaticFileProviderRW>>; 5],
 }
 
 /// Mutable reference to a [`StaticFileProviderRW`] behind a [`RwLockWriteGuard`].
@@ -152,7 +183,8 @@ impl StaticFileProviderRW {
             ),
             Err(ProviderError::MissingStaticFileBlock(_, _)) => {
                 let path = static_file_provider.directory().join(segment.filename(&block_range));
-                (create_jar(segment, &path, block_range), path)
+                let compression = static_file_provider.segment_config(segment).compression;
+                (create_jar(segment, &path, block_range, compression), path)
             }
             Err(err) => return Err(err),
         };
@@ -187,7 +219,7 @@ impl StaticFileProviderRW {
     /// [`NippyJarWriter`] for more on healing.
     fn ensure_end_range_consistency(&mut self) -> ProviderResult<()> {
         // If we have lost rows (in this run or previous), we need to update the [SegmentHeader].
-        let expected_rows = if self.user_header().segment().is_headers() {
+        let expected_rows = if self.user_header().segment().is_block_based() {
             self.user_header().block_len().unwrap_or_default()
         } else {
             self.user_header().tx_len().unwrap_or_default()
@@ -217,6 +249,9 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Receipts => {
                     self.prune_receipt_data(to_delete, last_block_number.expect("should exist"))?
                 }
+                StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                    self.prune_change_set_data(to_delete)?
+                }
             }
         }
 
@@ -383,13 +418,10 @@ impl StaticFileProviderRW {
         let mut remaining_rows = num_rows;
         let segment = self.writer.user_header().segment();
         while remaining_rows > 0 {
-            let len = match segment {
-                StaticFileSegment::Headers => {
-                    self.writer.user_header().block_len().unwrap_or_default()
-                }
-                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                    self.writer.user_header().tx_len().unwrap_or_default()
-                }
+            let len = if segment.is_block_based() {
+                self.writer.user_header().block_len().unwrap_or_default()
+            } else {
+                self.writer.user_header().tx_len().unwrap_or_default()
             };
 
             if remaining_rows >= len {
@@ -398,12 +430,12 @@ impl StaticFileProviderRW {
                 let block_start = self.writer.user_header().expected_block_start();
 
                 // We only delete the file if it's NOT the first static file AND:
-                // * it's a Header segment  OR
+                // * it's a block-based segment  OR
                 // * it's a tx-based segment AND `last_block` is lower than the first block of this
                 //   file's block range. Otherwise, having no rows simply means that this block
                 //   range has no transactions, but the file should remain.
                 if block_start != 0 &&
-                    (segment.is_headers() || last_block.is_some_and(|b| b < block_start))
+                    (segment.is_block_based() || last_block.is_some_and(|b| b < block_start))
                 {
                     self.delete_current_and_open_previous()?;
                 } else {
@@ -631,6 +663,59 @@ impl StaticFileProviderRW {
         Ok(Some(tx_number))
     }
 
+    /// Appends the account changes of a block to static file.
+    ///
+    /// It **CALLS** `increment_block()` since there is one changeset row per block, even if the
+    /// block has no changes.
+    ///
+    /// Returns the current [`BlockNumber`] as seen in the static file.
+    pub fn append_account_changeset(
+        &mut self,
+        block_number: BlockNumber,
+        changeset: &AccountChangeSet,
+    ) -> ProviderResult<BlockNumber> {
+        debug_assert!(self.writer.user_header().segment() == StaticFileSegment::AccountChangeSets);
+        self.append_change_set(block_number, changeset)
+    }
+
+    /// Appends the storage changes of a block to static file.
+    ///
+    /// It **CALLS** `increment_block()` since there is one changeset row per block, even if the
+    /// block has no changes.
+    ///
+    /// Returns the current [`BlockNumber`] as seen in the static file.
+    pub fn append_storage_changeset(
+        &mut self,
+        block_number: BlockNumber,
+        changeset: &StorageChangeSet,
+    ) -> ProviderResult<BlockNumber> {
+        debug_assert!(self.writer.user_header().segment() == StaticFileSegment::StorageChangeSets);
+        self.append_change_set(block_number, changeset)
+    }
+
+    /// Appends a changeset row for the block.
+    fn append_change_set<V: Compact>(
+        &mut self,
+        block_number: BlockNumber,
+        changeset: V,
+    ) -> ProviderResult<BlockNumber> {
+        let start = Instant::now();
+        self.ensure_no_queued_prune()?;
+
+        let block_number = self.increment_block(block_number)?;
+        self.append_column(changeset)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                self.writer.user_header().segment(),
+                StaticFileProviderOperation::Append,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(block_number)
+    }
+
     /// Adds an instruction to prune `to_delete`transactions during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at.
@@ -661,6 +746,12 @@ impl StaticFileProviderRW {
         self.queue_prune(to_delete, None)
     }
 
+    /// Adds an instruction to prune the changesets of the last `to_delete` blocks during commit.
+    pub fn prune_change_sets(&mut self, to_delete: u64) -> ProviderResult<()> {
+        debug_assert!(self.writer.user_header().segment().is_change_sets());
+        self.queue_prune(to_delete, None)
+    }
+
     /// Adds an instruction to prune `to_delete` elements during commit.
     ///
     /// Note: `last_block` refers to the block the unwinds ends at if dealing with transaction-based
@@ -750,6 +841,26 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Prunes the changesets of the last `to_delete` blocks from the data file.
+    fn prune_change_set_data(&mut self, to_delete: u64) -> ProviderResult<()> {
+        let start = Instant::now();
+
+        let segment = self.writer.user_header().segment();
+        debug_assert!(segment.is_change_sets());
+
+        self.truncate(to_delete, None)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                segment,
+                StaticFileProviderOperation::Prune,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(())
+    }
+
     fn reader(&self) -> StaticFileProvider {
         Self::upgrade_provider_to_strong_reference(&self.reader)
     }
@@ -795,18 +906,19 @@ fn create_jar(
     segment: StaticFileSegment,
     path: &Path,
     expected_block_range: SegmentRangeInclusive,
+    compression: Compression,
 ) -> NippyJar<SegmentHeader> {
-    let mut jar = NippyJar::new(
+    let jar = NippyJar::new(
         segment.columns(),
         path,
         SegmentHeader::new(expected_block_range, None, None, segment),
     );
 
-    // Transaction and Receipt already have the compression scheme used natively in its encoding.
-    // (zstd-dictionary)
-    if segment.is_headers() {
-        jar = jar.with_lz4();
+    match compression {
+        Compression::Lz4 => jar.with_lz4(),
+        // Dictionaries are trained on the rows of a static file, so new ones use plain zstd until
+        // they're recompressed.
+        Compression::Zstd | Compression::ZstdWithDictionary => jar.with_zstd(false, 0),
+        Compression::Uncompressed => jar,
     }
-
-    jar
 }
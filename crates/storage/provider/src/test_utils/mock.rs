@@ -2,8 +2,9 @@ use crate::{
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
     ChainSpecProvider, ChangeSetReader, DatabaseProvider, EvmEnvProvider, HeaderProvider,
-    ReceiptProviderIdExt, StateProvider, StateProviderBox, StateProviderFactory, StateReader,
-    StateRootProvider, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    PruneCheckpointReader, ReceiptProviderIdExt, StateProvider, StateProviderBox,
+    StateProviderFactory, StateReader, StateRootProvider, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 use alloy_consensus::constants::EMPTY_ROOT_HASH;
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
@@ -16,28 +17,31 @@ use alloy_primitives::{
 use parking_lot::Mutex;
 use reth_chainspec::{ChainInfo, ChainSpec};
 use reth_db::mock::{DatabaseMock, TxMock};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_api::models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_execution_types::ExecutionOutcome;
 use reth_node_types::NodeTypes;
 use reth_primitives::{
     Account, Block, BlockWithSenders, Bytecode, GotExpected, Header, Receipt, SealedBlock,
-    SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
+    SealedBlockWithSenders, SealedHeader, StorageEntry, TransactionMeta, TransactionSigned,
     TransactionSignedNoHash, Withdrawal, Withdrawals,
 };
+use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_api::{
-    DatabaseProviderFactory, StageCheckpointReader, StateProofProvider, StorageRootProvider,
+    DatabaseProviderFactory, HistoryIndexReader, StageCheckpointReader, StateProofProvider,
+    StateRangeProvider, StorageChangeSetReader, StorageRootProvider,
 };
 use reth_storage_errors::provider::{ConsistentViewError, ProviderError, ProviderResult};
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof,
-    TrieInput,
+    range::{HashedAccountRange, HashedStorageRange},
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof, TrieInput,
 };
 use reth_trie_db::MerklePatriciaTrie;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -55,6 +59,8 @@ pub struct MockEthProvider {
     pub chain_spec: Arc<ChainSpec>,
     /// Local state roots
     pub state_roots: Arc<Mutex<Vec<B256>>>,
+    /// Local receipt store, by block hash
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<Receipt>>>>,
 }
 
 impl Default for MockEthProvider {
@@ -65,6 +71,7 @@ impl Default for MockEthProvider {
             accounts: Default::default(),
             chain_spec: Arc::new(reth_chainspec::ChainSpecBuilder::mainnet().build()),
             state_roots: Default::default(),
+            receipts: Default::default(),
         }
     }
 }
@@ -149,6 +156,11 @@ impl MockEthProvider {
     pub fn add_state_root(&self, state_root: B256) {
         self.state_roots.lock().push(state_root);
     }
+
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, hash: B256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
 }
 
 /// Mock node.
@@ -393,8 +405,12 @@ impl ReceiptProvider for MockEthProvider {
         Ok(None)
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
+        let hash = match block {
+            BlockHashOrNumber::Hash(hash) => Some(hash),
+            BlockHashOrNumber::Number(number) => self.block_hash(number)?,
+        };
+        Ok(hash.and_then(|hash| self.receipts.lock().get(&hash).cloned()))
     }
 
     fn receipts_by_tx_range(
@@ -515,18 +531,29 @@ impl BlockReader for MockEthProvider {
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn sealed_block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<SealedBlockWithSenders>> {
-        Ok(None)
+        let lock = self.blocks.lock();
+        let block = match id {
+            BlockHashOrNumber::Hash(hash) => lock.get(&hash).map(|block| (hash, block)),
+            BlockHashOrNumber::Number(num) => lock
+                .iter()
+                .find(|(_, block)| block.number == num)
+                .map(|(hash, block)| (*hash, block)),
+        };
+        Ok(block.and_then(|(hash, block)| {
+            SealedBlock::new(SealedHeader::new(block.header.clone(), hash), block.body.clone())
+                .seal_with_senders()
+        }))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -594,6 +621,19 @@ impl AccountReader for MockEthProvider {
     }
 }
 
+impl PruneCheckpointReader for MockEthProvider {
+    fn get_prune_checkpoint(
+        &self,
+        _segment: PruneSegment,
+    ) -> ProviderResult<Option<PruneCheckpoint>> {
+        Ok(None)
+    }
+
+    fn get_prune_checkpoints(&self) -> ProviderResult<Vec<(PruneSegment, PruneCheckpoint)>> {
+        Ok(Vec::new())
+    }
+}
+
 impl StageCheckpointReader for MockEthProvider {
     fn get_stage_checkpoint(&self, _id: StageId) -> ProviderResult<Option<StageCheckpoint>> {
         Ok(None)
@@ -680,6 +720,47 @@ impl StateProofProvider for MockEthProvider {
     }
 }
 
+impl StateRangeProvider for MockEthProvider {
+    fn account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+        _with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        Ok(HashedAccountRange::default())
+    }
+
+    fn storage_range(
+        &self,
+        _address: Address,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(HashedStorageRange::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(HashedStorageRange::default())
+    }
+
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        Ok(HashedAccountRange::default())
+    }
+}
+
 impl StateProvider for MockEthProvider {
     fn storage(
         &self,
@@ -829,6 +910,25 @@ impl ChangeSetReader for MockEthProvider {
     }
 }
 
+impl StorageChangeSetReader for MockEthProvider {
+    fn storage_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        Ok(Vec::default())
+    }
+}
+
+impl HistoryIndexReader for MockEthProvider {
+    fn address_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        Ok(BTreeSet::default())
+    }
+}
+
 impl StateReader for MockEthProvider {
     fn get_state(&self, _block: BlockNumber) -> ProviderResult<Option<ExecutionOutcome>> {
         Ok(None)
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     ops::{RangeBounds, RangeInclusive},
     path::PathBuf,
     sync::Arc,
@@ -14,20 +15,25 @@ use reth_chain_state::{
     ForkChoiceSubscriptions,
 };
 use reth_chainspec::{ChainInfo, ChainSpec, MAINNET};
-use reth_db_api::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db_api::models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices};
 use reth_errors::ProviderError;
 use reth_evm::ConfigureEvmEnv;
 use reth_primitives::{
     Account, Block, BlockWithSenders, Bytecode, Header, Receipt, SealedBlock,
-    SealedBlockWithSenders, SealedHeader, TransactionMeta, TransactionSigned,
+    SealedBlockWithSenders, SealedHeader, StorageEntry, TransactionMeta, TransactionSigned,
     TransactionSignedNoHash, Withdrawal, Withdrawals,
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_storage_api::{StateProofProvider, StorageRootProvider};
+use reth_storage_api::{
+    HistoryIndexReader, StateProofProvider, StateRangeProvider, StorageChangeSetReader,
+    StorageRootProvider,
+};
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
+    range::{HashedAccountRange, HashedStorageRange},
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, TrieInput,
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use tokio::sync::{broadcast, watch};
@@ -324,6 +330,25 @@ impl ChangeSetReader for NoopProvider {
     }
 }
 
+impl StorageChangeSetReader for NoopProvider {
+    fn storage_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        Ok(Vec::default())
+    }
+}
+
+impl HistoryIndexReader for NoopProvider {
+    fn address_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>> {
+        Ok(BTreeSet::default())
+    }
+}
+
 impl StateRootProvider for NoopProvider {
     fn state_root(&self, _state: HashedPostState) -> ProviderResult<B256> {
         Ok(B256::default())
@@ -394,6 +419,47 @@ impl StateProofProvider for NoopProvider {
     }
 }
 
+impl StateRangeProvider for NoopProvider {
+    fn account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+        _with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange> {
+        Ok(HashedAccountRange::default())
+    }
+
+    fn storage_range(
+        &self,
+        _address: Address,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(HashedStorageRange::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_address: B256,
+        _hashed_storage: HashedStorage,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedStorageRange> {
+        Ok(HashedStorageRange::default())
+    }
+
+    fn hashed_account_range(
+        &self,
+        _hashed_state: HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedAccountRange> {
+        Ok(HashedAccountRange::default())
+    }
+}
+
 impl StateProvider for NoopProvider {
     fn storage(
         &self,
@@ -2,8 +2,9 @@
 
 use crate::{
     AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, StageCheckpointReader, StateProviderFactory,
-    StaticFileProviderFactory, TransactionsProvider,
+    EvmEnvProvider, HeaderProvider, HistoryIndexReader, PruneCheckpointReader,
+    StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory, StorageChangeSetReader,
+    TransactionsProvider,
 };
 use reth_chain_state::{CanonStateSubscriptions, ForkChoiceSubscriptions};
 use reth_chainspec::EthereumHardforks;
@@ -19,6 +20,9 @@ pub trait FullProvider<N: NodeTypesWithDB>:
     + EvmEnvProvider
     + ChainSpecProvider<ChainSpec = N::ChainSpec>
     + ChangeSetReader
+    + StorageChangeSetReader
+    + HistoryIndexReader
+    + PruneCheckpointReader
     + CanonStateSubscriptions
     + ForkChoiceSubscriptions
     + StageCheckpointReader
@@ -37,6 +41,9 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
         + EvmEnvProvider
         + ChainSpecProvider<ChainSpec = N::ChainSpec>
         + ChangeSetReader
+        + StorageChangeSetReader
+        + HistoryIndexReader
+        + PruneCheckpointReader
         + CanonStateSubscriptions
         + ForkChoiceSubscriptions
         + StageCheckpointReader
@@ -56,6 +63,10 @@ pub trait FullRpcProvider:
     + HeaderProvider
     + TransactionsProvider
     + StageCheckpointReader
+    + ChangeSetReader
+    + StorageChangeSetReader
+    + HistoryIndexReader
+    + PruneCheckpointReader
     + Clone
     + Unpin
     + 'static
@@ -70,6 +81,10 @@ impl<T> FullRpcProvider for T where
         + HeaderProvider
         + TransactionsProvider
         + StageCheckpointReader
+        + ChangeSetReader
+        + StorageChangeSetReader
+        + HistoryIndexReader
+        + PruneCheckpointReader
         + Clone
         + Unpin
         + 'static
@@ -289,10 +289,30 @@ where
         let total_txs = tx_range.end().saturating_sub(*tx_range.start());
 
         // IMPORTANT: we use `block_number+1` to make sure we remove only what is ABOVE the block
+        //
+        // Removing the execution range also reads the changesets of the removed blocks with a
+        // static file fallback, and prunes the changeset static files above `block_number`.
         debug!(target: "provider::storage_writer", ?block_number, "Removing blocks from database above block_number");
-        self.database().remove_block_and_execution_range(
-            block_number + 1..=self.database().last_block_number()?,
-        )?;
+        let last_block_number = self.database().last_block_number()?;
+        self.database().remove_block_and_execution_range(block_number + 1..=last_block_number)?;
+
+        // If there was nothing to remove from the database, changeset static files can still be
+        // ahead of it, e.g. after an interrupted unwind.
+        if last_block_number <= block_number {
+            for segment in
+                [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+            {
+                if let Some(highest_block) = self
+                    .static_file()
+                    .get_highest_static_file_block(segment)
+                    .filter(|highest_block| *highest_block > block_number)
+                {
+                    self.static_file()
+                        .latest_writer(segment)?
+                        .prune_change_sets(highest_block - block_number)?;
+                }
+            }
+        }
 
         // IMPORTANT: we use `highest_static_file_block.saturating_sub(block_number)` to make sure
         // we remove only what is ABOVE the block.
@@ -0,0 +1,19 @@
+use alloy_primitives::{Address, BlockNumber};
+use auto_impl::auto_impl;
+use reth_storage_errors::provider::ProviderResult;
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+/// History index reader
+#[auto_impl(&, Arc, Box)]
+pub trait HistoryIndexReader: Send + Sync {
+    /// Returns the block numbers in the given range at which the account or any of its storage
+    /// slots were changed, according to the `AccountsHistory` and `StoragesHistory` indices.
+    ///
+    /// NOTE: Blocks below the prune checkpoints of the history segments are not reported, it is
+    /// up to the caller to check the checkpoints.
+    fn address_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<BlockNumber>>;
+}
@@ -4,8 +4,9 @@ use alloy_primitives::{
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
-    updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof,
-    TrieInput,
+    range::{HashedAccountRange, HashedStorageRange},
+    updates::TrieUpdates,
+    AccountProof, HashedPostState, HashedStorage, MultiProof, StorageProof, TrieInput,
 };
 
 /// A type that can compute the state root of a given post state.
@@ -58,6 +59,58 @@ pub trait StorageRootProvider: Send + Sync {
     ) -> ProviderResult<StorageProof>;
 }
 
+/// A type that can paginate over the state in order of hashed keys.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait StateRangeProvider: Send + Sync {
+    /// Returns up to `limit` accounts of the `HashedPostState` on top of the current state,
+    /// starting at the given hashed address.
+    ///
+    /// If `with_storage` is set, all storage slots of each account are returned as well. The
+    /// preimages of the accounts and slots are looked up on a best effort basis, since this
+    /// requires a walk over the plain state.
+    fn account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> ProviderResult<HashedAccountRange>;
+
+    /// Returns up to `limit` storage slots of the `HashedStorage` for target address on top of the
+    /// current state, starting at the given hashed slot.
+    fn storage_range(
+        &self,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange>;
+
+    /// Returns up to `limit` storage slots of the `HashedStorage` for target hashed address on top
+    /// of the current state, starting at the given hashed slot.
+    ///
+    /// Unlike [`Self::storage_range`], the preimages of the returned slots are not looked up.
+    fn hashed_storage_range(
+        &self,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedStorageRange>;
+
+    /// Returns up to `limit` accounts of the `HashedPostState` on top of the current state,
+    /// starting at the given hashed address.
+    ///
+    /// Unlike [`Self::account_range`], the preimages of the returned accounts are not looked up
+    /// and no storage slots are returned.
+    fn hashed_account_range(
+        &self,
+        hashed_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedAccountRange>;
+}
+
 /// A type that can generate state proof on top of a given post state.
 #[auto_impl::auto_impl(&, Box, Arc)]
 pub trait StateProofProvider: Send + Sync {
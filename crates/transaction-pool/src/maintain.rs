@@ -44,11 +44,16 @@ pub struct MaintainPoolConfig {
     ///
     /// Default: 100
     pub max_reload_accounts: usize,
+    /// Whether transactions of reorged blocks that are not part of the new chain are re-injected
+    /// into the pool.
+    ///
+    /// Default: true
+    pub reinject_reorged_transactions: bool,
 }
 
 impl Default for MaintainPoolConfig {
     fn default() -> Self {
-        Self { max_update_depth: 64, max_reload_accounts: 100 }
+        Self { max_update_depth: 64, max_reload_accounts: 100, reinject_reorged_transactions: true }
     }
 }
 
@@ -102,7 +107,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     Tasks: TaskSpawner + 'static,
 {
     let metrics = MaintainPoolMetrics::default();
-    let MaintainPoolConfig { max_update_depth, max_reload_accounts, .. } = config;
+    let MaintainPoolConfig { max_update_depth, max_reload_accounts, reinject_reorged_transactions } =
+        config;
     // ensure the pool points to latest state
     if let Ok(Some(latest)) = client.header_by_number_or_tag(BlockNumberOrTag::Latest) {
         let sealed = latest.seal_slow();
@@ -362,8 +368,10 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 // Note: we no longer know if the tx was local or external
                 // Because the transactions are not finalized, the corresponding blobs are still in
                 // blob store (if we previously received them from the network)
-                metrics.inc_reinserted_transactions(pruned_old_transactions.len());
-                let _ = pool.add_external_transactions(pruned_old_transactions).await;
+                if reinject_reorged_transactions {
+                    metrics.inc_reinserted_transactions(pruned_old_transactions.len());
+                    let _ = pool.add_external_transactions(pruned_old_transactions).await;
+                }
 
                 // keep track of new mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&new_blocks);
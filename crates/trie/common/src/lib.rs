@@ -31,6 +31,9 @@ mod proofs;
 pub use proofs::triehash;
 pub use proofs::*;
 
+mod range_proof;
+pub use range_proof::verify_range_proof;
+
 pub mod root;
 
 pub use alloy_trie::{nodes::*, proof, BranchNodeCompact, HashBuilder, TrieMask, EMPTY_ROOT_HASH};
@@ -0,0 +1,340 @@
+//! Verification of trie range proofs.
+
+use crate::{HashBuilder, Nibbles};
+use alloy_primitives::{keccak256, map::HashMap, Bytes, B256};
+use alloy_rlp::Decodable;
+use alloy_trie::{
+    nodes::{RlpNode, TrieNode, CHILD_INDEX_RANGE},
+    proof::ProofVerificationError,
+};
+use std::cmp::Ordering;
+
+/// Verifies that the given leaves are all the leaves of the trie with the given root, from the
+/// `start` key up to the last leaf.
+///
+/// The proof has to contain the nodes on the paths to `start` and to the last leaf, like the
+/// proofs of the range responses of the `snap` protocol. If the proof is empty, the leaves have to
+/// be the whole trie.
+///
+/// Returns whether the trie has more leaves after the last one.
+pub fn verify_range_proof<'a>(
+    root: B256,
+    start: B256,
+    leaves: &[(B256, Vec<u8>)],
+    proof: impl IntoIterator<Item = &'a Bytes>,
+) -> Result<bool, ProofVerificationError> {
+    let mut previous = None;
+    for (key, value) in leaves {
+        if *key < start || previous.is_some_and(|previous| key <= previous) {
+            return Err(ProofVerificationError::ValueMismatch {
+                path: Nibbles::unpack(key),
+                got: Some(Bytes::copy_from_slice(value)),
+                expected: None,
+            })
+        }
+        previous = Some(key);
+    }
+
+    let nodes: HashMap<_, _> = proof.into_iter().map(|node| (keccak256(node), node)).collect();
+    let mut walker = RangeProofWalker {
+        nodes,
+        start: Nibbles::unpack(start),
+        end: Nibbles::unpack(leaves.last().map_or(B256::repeat_byte(0xff), |(key, _)| *key)),
+        left: Vec::new(),
+        right: Vec::new(),
+    };
+    if !walker.nodes.is_empty() {
+        walker.walk(Nibbles::default(), &RlpNode::word_rlp(&root))?;
+    }
+
+    // the leaves outside of the range are only known by the hashes of their subtries
+    let mut hash_builder = HashBuilder::default();
+    let leaves =
+        leaves.iter().map(|(key, value)| (Nibbles::unpack(key), RangeElement::Leaf(value)));
+    for (path, element) in walker.left.iter().map(RangeElement::as_ref).chain(leaves) {
+        element.add_to(&mut hash_builder, path);
+    }
+    for (path, element) in walker.right.iter().map(RangeElement::as_ref) {
+        element.add_to(&mut hash_builder, path);
+    }
+
+    let got = hash_builder.root();
+    if got != root {
+        return Err(ProofVerificationError::RootMismatch { got, expected: root })
+    }
+    Ok(!walker.right.is_empty())
+}
+
+/// The position of a subtrie relative to the proven range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// All keys of the subtrie are before the range.
+    Left,
+    /// All keys of the subtrie are after the range.
+    Right,
+    /// All keys of the subtrie are inside of the range.
+    Inside,
+    /// The subtrie contains the start or the end of the range.
+    Boundary,
+}
+
+/// An element of the trie outside of the proven range.
+#[derive(Debug)]
+enum RangeElement<T> {
+    /// A subtrie, known by its hash.
+    Hash(B256),
+    /// A leaf value.
+    Leaf(T),
+}
+
+impl RangeElement<Vec<u8>> {
+    fn as_ref((path, element): &(Nibbles, Self)) -> (Nibbles, RangeElement<&Vec<u8>>) {
+        let element = match element {
+            Self::Hash(hash) => RangeElement::Hash(*hash),
+            Self::Leaf(value) => RangeElement::Leaf(value),
+        };
+        (path.clone(), element)
+    }
+}
+
+impl RangeElement<&Vec<u8>> {
+    fn add_to(self, hash_builder: &mut HashBuilder, path: Nibbles) {
+        match self {
+            Self::Hash(hash) => hash_builder.add_branch(path, hash, false),
+            Self::Leaf(value) => hash_builder.add_leaf(path, value),
+        }
+    }
+}
+
+/// Walks the proof along the boundaries of the range, collecting the elements of the trie on
+/// either side of it.
+#[derive(Debug)]
+struct RangeProofWalker<'a> {
+    /// The proof nodes by their hash.
+    nodes: HashMap<B256, &'a Bytes>,
+    /// The first key of the range.
+    start: Nibbles,
+    /// The last key of the range.
+    end: Nibbles,
+    /// The elements before the range, in order.
+    left: Vec<(Nibbles, RangeElement<Vec<u8>>)>,
+    /// The elements after the range, in order.
+    right: Vec<(Nibbles, RangeElement<Vec<u8>>)>,
+}
+
+impl RangeProofWalker<'_> {
+    /// Returns the position of the subtrie at the given path.
+    fn position(&self, path: &Nibbles) -> Position {
+        let len = path.len();
+        match (path[..].cmp(&self.start[..len]), path[..].cmp(&self.end[..len])) {
+            (Ordering::Less, _) => Position::Left,
+            (_, Ordering::Greater) => Position::Right,
+            (Ordering::Greater, Ordering::Less) => Position::Inside,
+            _ => Position::Boundary,
+        }
+    }
+
+    /// Resolves the node referenced by the pointer, either from the proof or in place.
+    fn resolve(
+        &self,
+        path: &Nibbles,
+        pointer: &RlpNode,
+    ) -> Result<TrieNode, ProofVerificationError> {
+        let Some(hash) = pointer.as_hash() else {
+            return Ok(TrieNode::decode(&mut pointer.as_slice())?)
+        };
+        let node = self.nodes.get(&hash).ok_or_else(|| ProofVerificationError::ValueMismatch {
+            path: path.clone(),
+            got: None,
+            expected: Some(Bytes::copy_from_slice(pointer.as_slice())),
+        })?;
+        Ok(TrieNode::decode(&mut &node[..])?)
+    }
+
+    /// Walks the subtrie at the given path, which contains a boundary of the range.
+    fn walk(&mut self, path: Nibbles, pointer: &RlpNode) -> Result<(), ProofVerificationError> {
+        match self.resolve(&path, pointer)? {
+            TrieNode::EmptyRoot if path.is_empty() => {}
+            TrieNode::EmptyRoot => return Err(ProofVerificationError::UnexpectedEmptyRoot),
+            TrieNode::Branch(branch) => {
+                let children =
+                    CHILD_INDEX_RANGE.filter(|index| branch.state_mask.is_bit_set(*index));
+                for (index, child) in children.zip(&branch.stack) {
+                    let mut child_path = path.clone();
+                    child_path.push(index);
+                    self.visit(child_path, child)?;
+                }
+            }
+            TrieNode::Extension(extension) => {
+                self.visit(path.join(&extension.key), &extension.child)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                let key = path.join(&leaf.key);
+                match self.position(&key) {
+                    Position::Left => self.left.push((key, RangeElement::Leaf(leaf.value))),
+                    Position::Right => self.right.push((key, RangeElement::Leaf(leaf.value))),
+                    // must be one of the leaves of the range
+                    Position::Inside | Position::Boundary => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Visits the child at the given path.
+    fn visit(&mut self, path: Nibbles, pointer: &RlpNode) -> Result<(), ProofVerificationError> {
+        match self.position(&path) {
+            Position::Left => Self::collect(&mut self.left, path, pointer),
+            Position::Right => Self::collect(&mut self.right, path, pointer),
+            Position::Inside => Ok(()),
+            Position::Boundary => self.walk(path, pointer),
+        }
+    }
+
+    /// Collects the subtrie at the given path, which is outside of the range.
+    ///
+    /// Nodes that are encoded in place are expanded, since the hash builder only accepts hashes.
+    fn collect(
+        elements: &mut Vec<(Nibbles, RangeElement<Vec<u8>>)>,
+        path: Nibbles,
+        pointer: &RlpNode,
+    ) -> Result<(), ProofVerificationError> {
+        if let Some(hash) = pointer.as_hash() {
+            elements.push((path, RangeElement::Hash(hash)));
+            return Ok(())
+        }
+
+        match TrieNode::decode(&mut pointer.as_slice())? {
+            TrieNode::EmptyRoot => return Err(ProofVerificationError::UnexpectedEmptyRoot),
+            TrieNode::Branch(branch) => {
+                let children =
+                    CHILD_INDEX_RANGE.filter(|index| branch.state_mask.is_bit_set(*index));
+                for (index, child) in children.zip(&branch.stack) {
+                    let mut child_path = path.clone();
+                    child_path.push(index);
+                    Self::collect(elements, child_path, child)?;
+                }
+            }
+            TrieNode::Extension(extension) => {
+                Self::collect(elements, path.join(&extension.key), &extension.child)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                elements.push((path.join(&leaf.key), RangeElement::Leaf(leaf.value)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use alloy_trie::proof::ProofRetainer;
+
+    fn trie_leaves(keys: impl IntoIterator<Item = B256>) -> Vec<(B256, Vec<u8>)> {
+        let mut leaves: Vec<_> = keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| (key, alloy_rlp::encode(U256::from(i + 1))))
+            .collect();
+        leaves.sort_unstable_by_key(|(key, _)| *key);
+        leaves
+    }
+
+    /// Returns the root of the trie and the proof of the given keys.
+    fn root_and_proof(leaves: &[(B256, Vec<u8>)], targets: &[B256]) -> (B256, Vec<Bytes>) {
+        let retainer = ProofRetainer::from_iter(targets.iter().map(Nibbles::unpack));
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+        for (key, value) in leaves {
+            hash_builder.add_leaf(Nibbles::unpack(key), value);
+        }
+        let root = hash_builder.root();
+        let proof = hash_builder
+            .take_proof_nodes()
+            .into_nodes_sorted()
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+        (root, proof)
+    }
+
+    #[test]
+    fn verify_ranges() {
+        let leaves = trie_leaves((0..100u64).map(|i| keccak256(i.to_be_bytes())));
+
+        for (first, last) in [(0, 99), (0, 10), (10, 20), (50, 99), (42, 42)] {
+            let start = leaves[first].0;
+            let range = &leaves[first..=last];
+            let (root, proof) = root_and_proof(&leaves, &[start, range.last().unwrap().0]);
+
+            assert_eq!(verify_range_proof(root, start, range, &proof), Ok(last < 99));
+        }
+    }
+
+    #[test]
+    fn verify_range_from_missing_key() {
+        let leaves = trie_leaves((0..100u64).map(|i| keccak256(i.to_be_bytes())));
+        let start = B256::from(U256::from_be_bytes(leaves[10].0 .0) + U256::from(1));
+        let range = &leaves[11..30];
+        let (root, proof) = root_and_proof(&leaves, &[start, range.last().unwrap().0]);
+
+        assert_eq!(verify_range_proof(root, start, range, &proof), Ok(true));
+    }
+
+    #[test]
+    fn verify_empty_range() {
+        let leaves = trie_leaves((0..100u64).map(|i| keccak256(i.to_be_bytes())));
+        let start = B256::from(U256::from_be_bytes(leaves[99].0 .0) + U256::from(1));
+        let (root, proof) = root_and_proof(&leaves, &[start]);
+
+        assert_eq!(verify_range_proof(root, start, &[], &proof), Ok(false));
+
+        // there are leaves after the start of the range
+        let start = leaves[98].0;
+        let (root, proof) = root_and_proof(&leaves, &[start]);
+        assert!(verify_range_proof(root, start, &[], &proof).is_err());
+    }
+
+    #[test]
+    fn verify_whole_trie_without_proof() {
+        let leaves = trie_leaves((0..100u64).map(|i| keccak256(i.to_be_bytes())));
+        let (root, _) = root_and_proof(&leaves, &[]);
+
+        assert_eq!(verify_range_proof(root, B256::ZERO, &leaves, []), Ok(false));
+        assert!(verify_range_proof(root, B256::ZERO, &leaves[1..], []).is_err());
+    }
+
+    #[test]
+    fn verify_range_with_inline_nodes() {
+        // keys that only differ in their last nibbles produce nodes that are encoded in place
+        let leaves = trie_leaves((0..40u8).map(|i| B256::with_last_byte(i * 3)));
+
+        for (first, last) in [(0, 39), (5, 20), (17, 17), (30, 39)] {
+            let start = leaves[first].0;
+            let range = &leaves[first..=last];
+            let (root, proof) = root_and_proof(&leaves, &[start, range.last().unwrap().0]);
+
+            assert_eq!(verify_range_proof(root, start, range, &proof), Ok(last < 39));
+        }
+    }
+
+    #[test]
+    fn reject_incomplete_range() {
+        let leaves = trie_leaves((0..100u64).map(|i| keccak256(i.to_be_bytes())));
+        let start = leaves[10].0;
+        let (root, proof) = root_and_proof(&leaves, &[start, leaves[20].0]);
+
+        let mut range = leaves[10..=20].to_vec();
+        range.remove(5);
+        assert!(verify_range_proof(root, start, &range, &proof).is_err());
+
+        let mut range = leaves[10..=20].to_vec();
+        range[5].1 = alloy_rlp::encode(U256::MAX);
+        assert!(verify_range_proof(root, start, &range, &proof).is_err());
+
+        let mut range = leaves[10..=20].to_vec();
+        range.swap(3, 4);
+        assert!(verify_range_proof(root, start, &range, &proof).is_err());
+    }
+}
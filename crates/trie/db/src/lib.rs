@@ -4,6 +4,7 @@ mod commitment;
 mod hashed_cursor;
 mod prefix_set;
 mod proof;
+mod range;
 mod state;
 mod storage;
 mod trie_cursor;
@@ -15,6 +16,7 @@ pub use hashed_cursor::{
 };
 pub use prefix_set::PrefixSetLoader;
 pub use proof::{DatabaseProof, DatabaseStorageProof};
+pub use range::{DatabaseStateRange, MAX_PREIMAGE_LOOKUPS};
 pub use state::{DatabaseHashedPostState, DatabaseStateRoot};
 pub use storage::{DatabaseHashedStorage, DatabaseStorageRoot};
 pub use trie_cursor::{
@@ -5,9 +5,10 @@ use reth_db_api::{
     cursor::DbCursorRO,
     models::{AccountBeforeTx, BlockNumberAddress},
     transaction::DbTx,
-    DatabaseError,
 };
 use reth_primitives::StorageEntry;
+use reth_storage_api::{ChangeSetReader, DBProvider, StorageChangeSetReader};
+use reth_storage_errors::provider::ProviderResult;
 use reth_trie::prefix_set::{PrefixSetMut, TriePrefixSets};
 use reth_trie_common::Nibbles;
 use std::{
@@ -15,50 +16,58 @@ use std::{
     ops::RangeInclusive,
 };
 
-/// A wrapper around a database transaction that loads prefix sets within a given block range.
+/// A wrapper around a database provider that loads prefix sets within a given block range.
+///
+/// Changesets are read through the provider, so blocks whose changesets were moved to static
+/// files are included.
 #[derive(Deref, Debug)]
-pub struct PrefixSetLoader<'a, TX>(&'a TX);
+pub struct PrefixSetLoader<'a, Provider>(&'a Provider);
 
-impl<'a, TX> PrefixSetLoader<'a, TX> {
+impl<'a, Provider> PrefixSetLoader<'a, Provider> {
     /// Create a new loader.
-    pub const fn new(tx: &'a TX) -> Self {
-        Self(tx)
+    pub const fn new(provider: &'a Provider) -> Self {
+        Self(provider)
     }
 }
 
-impl<TX: DbTx> PrefixSetLoader<'_, TX> {
+impl<Provider> PrefixSetLoader<'_, Provider>
+where
+    Provider: DBProvider + ChangeSetReader + StorageChangeSetReader,
+{
     /// Load all account and storage changes for the given block range.
-    pub fn load(self, range: RangeInclusive<BlockNumber>) -> Result<TriePrefixSets, DatabaseError> {
+    pub fn load(self, range: RangeInclusive<BlockNumber>) -> ProviderResult<TriePrefixSets> {
         // Initialize prefix sets.
         let mut account_prefix_set = PrefixSetMut::default();
         let mut storage_prefix_sets = HashMap::<B256, PrefixSetMut>::default();
         let mut destroyed_accounts = HashSet::default();
 
         // Walk account changeset and insert account prefixes.
-        let mut account_changeset_cursor = self.cursor_read::<tables::AccountChangeSets>()?;
-        let mut account_plain_state_cursor = self.cursor_read::<tables::PlainAccountState>()?;
-        for account_entry in account_changeset_cursor.walk_range(range.clone())? {
-            let (_, AccountBeforeTx { address, .. }) = account_entry?;
-            let hashed_address = keccak256(address);
-            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+        let mut account_plain_state_cursor =
+            self.tx_ref().cursor_read::<tables::PlainAccountState>()?;
+        for block_number in range.clone() {
+            for AccountBeforeTx { address, .. } in self.account_block_changeset(block_number)? {
+                let hashed_address = keccak256(address);
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
 
-            if account_plain_state_cursor.seek_exact(address)?.is_none() {
-                destroyed_accounts.insert(hashed_address);
+                if account_plain_state_cursor.seek_exact(address)?.is_none() {
+                    destroyed_accounts.insert(hashed_address);
+                }
             }
         }
 
         // Walk storage changeset and insert storage prefixes as well as account prefixes if missing
         // from the account prefix set.
-        let mut storage_cursor = self.cursor_dup_read::<tables::StorageChangeSets>()?;
-        let storage_range = BlockNumberAddress::range(range);
-        for storage_entry in storage_cursor.walk_range(storage_range)? {
-            let (BlockNumberAddress((_, address)), StorageEntry { key, .. }) = storage_entry?;
-            let hashed_address = keccak256(address);
-            account_prefix_set.insert(Nibbles::unpack(hashed_address));
-            storage_prefix_sets
-                .entry(hashed_address)
-                .or_default()
-                .insert(Nibbles::unpack(keccak256(key)));
+        for block_number in range {
+            for (BlockNumberAddress((_, address)), StorageEntry { key, .. }) in
+                self.storage_changeset(block_number)?
+            {
+                let hashed_address = keccak256(address);
+                account_prefix_set.insert(Nibbles::unpack(hashed_address));
+                storage_prefix_sets
+                    .entry(hashed_address)
+                    .or_default()
+                    .insert(Nibbles::unpack(keccak256(key)));
+            }
         }
 
         Ok(TriePrefixSets {
@@ -0,0 +1,229 @@
+use crate::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
+use alloy_primitives::{keccak256, map::HashMap, Address, B256};
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    transaction::DbTx,
+};
+use reth_execution_errors::StorageRootError;
+use reth_primitives::StorageEntry;
+use reth_storage_errors::db::DatabaseError;
+use reth_trie::{
+    hashed_cursor::HashedPostStateCursorFactory,
+    range::{
+        HashedAccountEntry, HashedAccountRange, HashedStorageEntry, HashedStorageRange, StateRange,
+    },
+    HashedPostState, HashedStorage,
+};
+
+/// Maximum number of plain state entries that are walked to look up the preimages of a range.
+///
+/// The plain state is keyed by the preimages, so finding the preimage of a hashed key requires a
+/// walk over the plain state. Keys whose preimage is not found within this many entries are
+/// returned without one.
+pub const MAX_PREIMAGE_LOOKUPS: usize = 100_000;
+
+/// Extends [`StateRange`] with operations specific for working with a database transaction.
+pub trait DatabaseStateRange<'a, TX> {
+    /// Create a new [`StateRange`] from database transaction.
+    fn from_tx(tx: &'a TX) -> Self;
+
+    /// Returns up to `limit` accounts of the [`HashedPostState`] on top of the current state,
+    /// starting at the given hashed address.
+    ///
+    /// The preimages of the returned accounts and their storage slots are looked up in up to
+    /// [`MAX_PREIMAGE_LOOKUPS`] entries of the plain state.
+    fn overlay_account_range(
+        tx: &'a TX,
+        post_state: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> Result<HashedAccountRange, StorageRootError>;
+
+    /// Returns up to `limit` storage slots of the [`HashedStorage`] for target address on top of
+    /// the current state, starting at the given hashed slot.
+    ///
+    /// The preimages of the returned slots are looked up in up to [`MAX_PREIMAGE_LOOKUPS`] entries
+    /// of the plain storage state of the account.
+    fn overlay_storage_range(
+        tx: &'a TX,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedStorageRange, DatabaseError>;
+
+    /// Returns up to `limit` accounts of the [`HashedPostState`] on top of the current state,
+    /// starting at the given hashed address, without their preimages and storage slots.
+    fn overlay_hashed_account_range(
+        tx: &'a TX,
+        post_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedAccountRange, StorageRootError>;
+
+    /// Returns up to `limit` storage slots of the [`HashedStorage`] for target hashed address on
+    /// top of the current state, starting at the given hashed slot, without their preimages.
+    fn overlay_hashed_storage_range(
+        tx: &'a TX,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedStorageRange, DatabaseError>;
+}
+
+impl<'a, TX: DbTx> DatabaseStateRange<'a, TX>
+    for StateRange<DatabaseTrieCursorFactory<'a, TX>, DatabaseHashedCursorFactory<'a, TX>>
+{
+    fn from_tx(tx: &'a TX) -> Self {
+        Self::new(DatabaseTrieCursorFactory::new(tx), DatabaseHashedCursorFactory::new(tx))
+    }
+
+    fn overlay_account_range(
+        tx: &'a TX,
+        post_state: HashedPostState,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> Result<HashedAccountRange, StorageRootError> {
+        let prefix_sets = post_state.construct_prefix_sets();
+        let state_sorted = post_state.into_sorted();
+        let mut range = Self::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(tx),
+                &state_sorted,
+            ))
+            .with_prefix_sets_mut(prefix_sets)
+            .account_range(start, limit, with_storage)?;
+
+        lookup_account_preimages(tx, &mut range.accounts)?;
+        for entry in &mut range.accounts {
+            if let Some(address) = entry.address {
+                lookup_storage_preimages(tx, address, &mut entry.storage)?;
+            }
+        }
+
+        Ok(range)
+    }
+
+    fn overlay_hashed_account_range(
+        tx: &'a TX,
+        post_state: HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedAccountRange, StorageRootError> {
+        let prefix_sets = post_state.construct_prefix_sets();
+        let state_sorted = post_state.into_sorted();
+        Self::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(tx),
+                &state_sorted,
+            ))
+            .with_prefix_sets_mut(prefix_sets)
+            .account_range(start, limit, false)
+    }
+
+    fn overlay_storage_range(
+        tx: &'a TX,
+        address: Address,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedStorageRange, DatabaseError> {
+        let hashed_address = keccak256(address);
+        let state_sorted =
+            HashedPostState::from_hashed_storage(hashed_address, hashed_storage).into_sorted();
+        let mut range = Self::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(tx),
+                &state_sorted,
+            ))
+            .storage_range(hashed_address, start, limit)?;
+
+        lookup_storage_preimages(tx, address, &mut range.slots)?;
+
+        Ok(range)
+    }
+
+    fn overlay_hashed_storage_range(
+        tx: &'a TX,
+        hashed_address: B256,
+        hashed_storage: HashedStorage,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedStorageRange, DatabaseError> {
+        let state_sorted =
+            HashedPostState::from_hashed_storage(hashed_address, hashed_storage).into_sorted();
+        Self::from_tx(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(
+                DatabaseHashedCursorFactory::new(tx),
+                &state_sorted,
+            ))
+            .storage_range(hashed_address, start, limit)
+    }
+}
+
+/// Looks up the addresses of the given accounts in the plain account state.
+///
+/// The walk stops as soon as all preimages are found or after [`MAX_PREIMAGE_LOOKUPS`] entries,
+/// accounts that only exist in the overlay or are not reached are left without one.
+fn lookup_account_preimages<TX: DbTx>(
+    tx: &TX,
+    accounts: &mut [HashedAccountEntry],
+) -> Result<(), DatabaseError> {
+    let mut missing = accounts
+        .iter_mut()
+        .filter(|entry| entry.address.is_none())
+        .map(|entry| (entry.hashed_address, &mut entry.address))
+        .collect::<HashMap<_, _>>();
+    if missing.is_empty() {
+        return Ok(())
+    }
+
+    let mut cursor = tx.cursor_read::<tables::PlainAccountState>()?;
+    for entry in cursor.walk(None)?.take(MAX_PREIMAGE_LOOKUPS) {
+        let (address, _) = entry?;
+        if let Some(preimage) = missing.remove(&keccak256(address)) {
+            *preimage = Some(address);
+            if missing.is_empty() {
+                break
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Looks up the keys of the given storage slots of the account in the plain storage state.
+///
+/// Only the storage of the account is walked, and the walk stops as soon as all preimages are
+/// found or after [`MAX_PREIMAGE_LOOKUPS`] entries.
+fn lookup_storage_preimages<TX: DbTx>(
+    tx: &TX,
+    address: Address,
+    slots: &mut [HashedStorageEntry],
+) -> Result<(), DatabaseError> {
+    let mut missing = slots
+        .iter_mut()
+        .filter(|slot| slot.key.is_none())
+        .map(|slot| (slot.hashed_key, &mut slot.key))
+        .collect::<HashMap<_, _>>();
+    if missing.is_empty() {
+        return Ok(())
+    }
+
+    let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+    for entry in cursor.walk_dup(Some(address), None)?.take(MAX_PREIMAGE_LOOKUPS) {
+        let (_, StorageEntry { key, .. }) = entry?;
+        if let Some(preimage) = missing.remove(&keccak256(key)) {
+            *preimage = Some(key);
+            if missing.is_empty() {
+                break
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,14 +1,13 @@
 use crate::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory, PrefixSetLoader};
 use alloy_primitives::{keccak256, Address, BlockNumber, B256, U256};
-use reth_db::tables;
 use reth_db_api::{
-    cursor::DbCursorRO,
     models::{AccountBeforeTx, BlockNumberAddress},
     transaction::DbTx,
 };
 use reth_execution_errors::StateRootError;
 use reth_primitives::Account;
-use reth_storage_errors::db::DatabaseError;
+use reth_storage_api::{ChangeSetReader, DBProvider, StorageChangeSetReader};
+use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
     hashed_cursor::HashedPostStateCursorFactory, trie_cursor::InMemoryTrieCursorFactory,
     updates::TrieUpdates, HashedPostState, HashedStorage, StateRoot, StateRootProgress, TrieInput,
@@ -30,10 +29,12 @@ pub trait DatabaseStateRoot<'a, TX>: Sized {
     /// # Returns
     ///
     /// An instance of state root calculator with account and storage prefixes loaded.
-    fn incremental_root_calculator(
-        tx: &'a TX,
+    fn incremental_root_calculator<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<Self, StateRootError>;
+    ) -> Result<Self, StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader;
 
     /// Computes the state root of the trie with the changed account and storage prefixes and
     /// existing trie nodes.
@@ -41,10 +42,12 @@ pub trait DatabaseStateRoot<'a, TX>: Sized {
     /// # Returns
     ///
     /// The updated state root.
-    fn incremental_root(
-        tx: &'a TX,
+    fn incremental_root<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<B256, StateRootError>;
+    ) -> Result<B256, StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader;
 
     /// Computes the state root of the trie with the changed account and storage prefixes and
     /// existing trie nodes collecting updates in the process.
@@ -54,10 +57,12 @@ pub trait DatabaseStateRoot<'a, TX>: Sized {
     /// # Returns
     ///
     /// The updated state root and the trie updates.
-    fn incremental_root_with_updates(
-        tx: &'a TX,
+    fn incremental_root_with_updates<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<(B256, TrieUpdates), StateRootError>;
+    ) -> Result<(B256, TrieUpdates), StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader;
 
     /// Computes the state root of the trie with the changed account and storage prefixes and
     /// existing trie nodes collecting updates in the process.
@@ -65,10 +70,12 @@ pub trait DatabaseStateRoot<'a, TX>: Sized {
     /// # Returns
     ///
     /// The intermediate progress of state root computation.
-    fn incremental_root_with_progress(
-        tx: &'a TX,
+    fn incremental_root_with_progress<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<StateRootProgress, StateRootError>;
+    ) -> Result<StateRootProgress, StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader;
 
     /// Calculate the state root for this [`HashedPostState`].
     /// Internally, this method retrieves prefixsets and uses them
@@ -122,11 +129,19 @@ pub trait DatabaseStateRoot<'a, TX>: Sized {
     ) -> Result<(B256, TrieUpdates), StateRootError>;
 }
 
-/// Extends [`HashedPostState`] with operations specific for working with a database transaction.
-pub trait DatabaseHashedPostState<TX>: Sized {
-    /// Initializes [`HashedPostState`] from reverts. Iterates over state reverts from the specified
-    /// block up to the current tip and aggregates them into hashed state in reverse.
-    fn from_reverts(tx: &TX, from: BlockNumber) -> Result<Self, DatabaseError>;
+/// Extends [`HashedPostState`] with operations specific for working with a database provider.
+pub trait DatabaseHashedPostState: Sized {
+    /// Initializes [`HashedPostState`] from reverts. Iterates over the state reverts of the block
+    /// range and aggregates them into hashed state in reverse.
+    ///
+    /// Changesets are read through the provider, so blocks whose changesets were moved to static
+    /// files are included.
+    fn from_reverts<Provider>(
+        provider: &Provider,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Self>
+    where
+        Provider: ChangeSetReader + StorageChangeSetReader;
 }
 
 impl<'a, TX: DbTx> DatabaseStateRoot<'a, TX>
@@ -136,36 +151,48 @@ impl<'a, TX: DbTx> DatabaseStateRoot<'a, TX>
         Self::new(DatabaseTrieCursorFactory::new(tx), DatabaseHashedCursorFactory::new(tx))
     }
 
-    fn incremental_root_calculator(
-        tx: &'a TX,
+    fn incremental_root_calculator<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<Self, StateRootError> {
-        let loaded_prefix_sets = PrefixSetLoader::new(tx).load(range)?;
-        Ok(Self::from_tx(tx).with_prefix_sets(loaded_prefix_sets))
+    ) -> Result<Self, StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader,
+    {
+        let loaded_prefix_sets = PrefixSetLoader::new(provider).load(range)?;
+        Ok(Self::from_tx(provider.tx_ref()).with_prefix_sets(loaded_prefix_sets))
     }
 
-    fn incremental_root(
-        tx: &'a TX,
+    fn incremental_root<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<B256, StateRootError> {
+    ) -> Result<B256, StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader,
+    {
         debug!(target: "trie::loader", ?range, "incremental state root");
-        Self::incremental_root_calculator(tx, range)?.root()
+        Self::incremental_root_calculator(provider, range)?.root()
     }
 
-    fn incremental_root_with_updates(
-        tx: &'a TX,
+    fn incremental_root_with_updates<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<(B256, TrieUpdates), StateRootError> {
+    ) -> Result<(B256, TrieUpdates), StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader,
+    {
         debug!(target: "trie::loader", ?range, "incremental state root");
-        Self::incremental_root_calculator(tx, range)?.root_with_updates()
+        Self::incremental_root_calculator(provider, range)?.root_with_updates()
     }
 
-    fn incremental_root_with_progress(
-        tx: &'a TX,
+    fn incremental_root_with_progress<Provider>(
+        provider: &'a Provider,
         range: RangeInclusive<BlockNumber>,
-    ) -> Result<StateRootProgress, StateRootError> {
+    ) -> Result<StateRootProgress, StateRootError>
+    where
+        Provider: DBProvider<Tx = TX> + ChangeSetReader + StorageChangeSetReader,
+    {
         debug!(target: "trie::loader", ?range, "incremental state root with progress");
-        Self::incremental_root_calculator(tx, range)?.root_with_progress()
+        Self::incremental_root_calculator(provider, range)?.root_with_progress()
     }
 
     fn overlay_root(tx: &'a TX, post_state: HashedPostState) -> Result<B256, StateRootError> {
@@ -219,28 +246,36 @@ impl<'a, TX: DbTx> DatabaseStateRoot<'a, TX>
     }
 }
 
-impl<TX: DbTx> DatabaseHashedPostState<TX> for HashedPostState {
-    fn from_reverts(tx: &TX, from: BlockNumber) -> Result<Self, DatabaseError> {
+impl DatabaseHashedPostState for HashedPostState {
+    fn from_reverts<Provider>(
+        provider: &Provider,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Self>
+    where
+        Provider: ChangeSetReader + StorageChangeSetReader,
+    {
         // Iterate over account changesets and record value before first occurring account change.
         let mut accounts = HashMap::<Address, Option<Account>>::default();
-        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
-        for entry in account_changesets_cursor.walk_range(from..)? {
-            let (_, AccountBeforeTx { address, info }) = entry?;
-            if let hash_map::Entry::Vacant(entry) = accounts.entry(address) {
-                entry.insert(info);
+        for block_number in range.clone() {
+            for AccountBeforeTx { address, info } in
+                provider.account_block_changeset(block_number)?
+            {
+                if let hash_map::Entry::Vacant(entry) = accounts.entry(address) {
+                    entry.insert(info);
+                }
             }
         }
 
         // Iterate over storage changesets and record value before first occurring storage change.
         let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
-        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
-        for entry in
-            storage_changesets_cursor.walk_range(BlockNumberAddress((from, Address::ZERO))..)?
-        {
-            let (BlockNumberAddress((_, address)), storage) = entry?;
-            let account_storage = storages.entry(address).or_default();
-            if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
-                entry.insert(storage.value);
+        for block_number in range {
+            for (BlockNumberAddress((_, address)), storage) in
+                provider.storage_changeset(block_number)?
+            {
+                let account_storage = storages.entry(address).or_default();
+                if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
+                    entry.insert(storage.value);
+                }
             }
         }
 
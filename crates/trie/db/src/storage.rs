@@ -1,10 +1,11 @@
-use std::collections::hash_map;
+use std::{collections::hash_map, ops::RangeInclusive};
 
 use crate::{DatabaseHashedCursorFactory, DatabaseTrieCursorFactory};
 use alloy_primitives::{keccak256, Address, BlockNumber, B256};
-use reth_db::{cursor::DbCursorRO, models::BlockNumberAddress, tables, DatabaseError};
 use reth_db_api::transaction::DbTx;
 use reth_execution_errors::StorageRootError;
+use reth_storage_api::StorageChangeSetReader;
+use reth_storage_errors::provider::ProviderResult;
 use reth_trie::{
     hashed_cursor::HashedPostStateCursorFactory, HashedPostState, HashedStorage, StorageRoot,
 };
@@ -28,11 +29,18 @@ pub trait DatabaseStorageRoot<'a, TX> {
     ) -> Result<B256, StorageRootError>;
 }
 
-/// Extends [`HashedStorage`] with operations specific for working with a database transaction.
-pub trait DatabaseHashedStorage<TX>: Sized {
-    /// Initializes [`HashedStorage`] from reverts. Iterates over storage reverts from the specified
-    /// block up to the current tip and aggregates them into hashed storage in reverse.
-    fn from_reverts(tx: &TX, address: Address, from: BlockNumber) -> Result<Self, DatabaseError>;
+/// Extends [`HashedStorage`] with operations specific for working with a database provider.
+pub trait DatabaseHashedStorage: Sized {
+    /// Initializes [`HashedStorage`] from reverts. Iterates over the storage reverts of the block
+    /// range and aggregates them into hashed storage in reverse.
+    ///
+    /// Changesets are read through the provider, so blocks whose changesets were moved to static
+    /// files are included.
+    fn from_reverts<Provider: StorageChangeSetReader>(
+        provider: &Provider,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Self>;
 }
 
 impl<'a, TX: DbTx> DatabaseStorageRoot<'a, TX>
@@ -78,16 +86,20 @@ impl<'a, TX: DbTx> DatabaseStorageRoot<'a, TX>
     }
 }
 
-impl<TX: DbTx> DatabaseHashedStorage<TX> for HashedStorage {
-    fn from_reverts(tx: &TX, address: Address, from: BlockNumber) -> Result<Self, DatabaseError> {
+impl DatabaseHashedStorage for HashedStorage {
+    fn from_reverts<Provider: StorageChangeSetReader>(
+        provider: &Provider,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Self> {
         let mut storage = Self::new(false);
-        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
-        for entry in storage_changesets_cursor.walk_range(BlockNumberAddress((from, address))..)? {
-            let (BlockNumberAddress((_, storage_address)), storage_change) = entry?;
-            if storage_address == address {
-                let hashed_slot = keccak256(storage_change.key);
-                if let hash_map::Entry::Vacant(entry) = storage.storage.entry(hashed_slot) {
-                    entry.insert(storage_change.value);
+        for block_number in range {
+            for (key, storage_change) in provider.storage_changeset(block_number)? {
+                if key.address() == address {
+                    let hashed_slot = keccak256(storage_change.key);
+                    if let hash_map::Entry::Vacant(entry) = storage.storage.entry(hashed_slot) {
+                        entry.insert(storage_change.value);
+                    }
                 }
             }
         }
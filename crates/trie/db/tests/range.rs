@@ -0,0 +1,207 @@
+#![allow(missing_docs)]
+
+use alloy_consensus::EMPTY_ROOT_HASH;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use reth_db::tables;
+use reth_db_api::transaction::DbTxMut;
+use reth_primitives::{Account, StorageEntry};
+use reth_provider::test_utils::create_test_provider_factory;
+use reth_trie::{range::StateRange, test_utils::storage_root, HashedPostState, HashedStorage};
+use reth_trie_db::DatabaseStateRange;
+use std::collections::BTreeMap;
+
+fn insert_account(
+    tx: &impl DbTxMut,
+    address: Address,
+    account: Account,
+    storage: &BTreeMap<B256, U256>,
+) {
+    let hashed_address = keccak256(address);
+    tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+    tx.put::<tables::PlainAccountState>(address, account).unwrap();
+    for (k, v) in storage {
+        tx.put::<tables::HashedStorages>(
+            hashed_address,
+            StorageEntry { key: keccak256(k), value: *v },
+        )
+        .unwrap();
+        tx.put::<tables::PlainStorageState>(address, StorageEntry { key: *k, value: *v }).unwrap();
+    }
+}
+
+#[test]
+fn storage_range_pagination() {
+    let factory = create_test_provider_factory();
+    let tx = factory.provider_rw().unwrap();
+
+    let address = Address::random();
+    let storage = (1..=5u8)
+        .map(|slot| (B256::with_last_byte(slot), U256::from(slot)))
+        .collect::<BTreeMap<_, _>>();
+    insert_account(tx.tx_ref(), address, Account::default(), &storage);
+
+    let mut expected = storage.iter().map(|(k, v)| (keccak256(k), *k, *v)).collect::<Vec<_>>();
+    expected.sort_unstable();
+
+    // walk the storage in pages of two slots
+    let mut slots = Vec::new();
+    let mut start = B256::ZERO;
+    loop {
+        let range = StateRange::overlay_storage_range(
+            tx.tx_ref(),
+            address,
+            HashedStorage::default(),
+            start,
+            2,
+        )
+        .unwrap();
+        assert!(range.slots.len() <= 2);
+        slots.extend(range.slots.into_iter().map(|slot| (slot.hashed_key, slot.key, slot.value)));
+        match range.next_key {
+            Some(next_key) => start = next_key,
+            None => break,
+        }
+    }
+
+    assert_eq!(
+        slots,
+        expected
+            .into_iter()
+            .map(|(hashed, key, value)| (hashed, Some(key), value))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn storage_range_with_overlay() {
+    let factory = create_test_provider_factory();
+    let tx = factory.provider_rw().unwrap();
+
+    let address = Address::random();
+    let storage = BTreeMap::from([
+        (B256::with_last_byte(1), U256::from(1)),
+        (B256::with_last_byte(2), U256::from(2)),
+    ]);
+    insert_account(tx.tx_ref(), address, Account::default(), &storage);
+
+    // delete the first slot and add a new one that is not in the plain state
+    let new_slot = B256::with_last_byte(3);
+    let mut hashed_storage = HashedStorage::new(false);
+    hashed_storage.storage.insert(keccak256(B256::with_last_byte(1)), U256::ZERO);
+    hashed_storage.storage.insert(keccak256(new_slot), U256::from(3));
+
+    // the hashed range is the same, without the preimages
+    let hashed_range = StateRange::overlay_hashed_storage_range(
+        tx.tx_ref(),
+        keccak256(address),
+        hashed_storage.clone(),
+        B256::ZERO,
+        10,
+    )
+    .unwrap();
+    assert!(hashed_range.slots.iter().all(|slot| slot.key.is_none()));
+
+    let range =
+        StateRange::overlay_storage_range(tx.tx_ref(), address, hashed_storage, B256::ZERO, 10)
+            .unwrap();
+    assert_eq!(range.next_key, None);
+    assert_eq!(
+        range.slots.iter().map(|slot| (slot.hashed_key, slot.value)).collect::<Vec<_>>(),
+        hashed_range.slots.iter().map(|slot| (slot.hashed_key, slot.value)).collect::<Vec<_>>()
+    );
+
+    let slots = range
+        .slots
+        .into_iter()
+        .map(|slot| (slot.hashed_key, (slot.key, slot.value)))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(
+        slots,
+        BTreeMap::from([
+            (keccak256(B256::with_last_byte(2)), (Some(B256::with_last_byte(2)), U256::from(2))),
+            (keccak256(new_slot), (None, U256::from(3))),
+        ])
+    );
+}
+
+#[test]
+fn account_range_with_storage() {
+    let factory = create_test_provider_factory();
+    let tx = factory.provider_rw().unwrap();
+
+    let accounts = (1..=3u8)
+        .map(|i| {
+            let storage = BTreeMap::from([(B256::with_last_byte(i), U256::from(i))]);
+            (Address::with_last_byte(i), Account { nonce: i as u64, ..Default::default() }, storage)
+        })
+        .collect::<Vec<_>>();
+    for (address, account, storage) in &accounts {
+        insert_account(tx.tx_ref(), *address, *account, storage);
+    }
+
+    // destroy one of the accounts in the overlay
+    let destroyed = keccak256(Address::with_last_byte(1));
+    let mut post_state = HashedPostState::default();
+    post_state.accounts.insert(destroyed, None);
+    post_state.storages.insert(destroyed, HashedStorage::new(true));
+
+    let range =
+        StateRange::overlay_account_range(tx.tx_ref(), post_state, B256::ZERO, 1, true).unwrap();
+    assert_eq!(range.accounts.len(), 1);
+    assert!(range.next_key.is_some());
+
+    let remaining = StateRange::overlay_account_range(
+        tx.tx_ref(),
+        HashedPostState::default(),
+        range.next_key.unwrap(),
+        10,
+        false,
+    )
+    .unwrap();
+    assert_eq!(remaining.next_key, None);
+
+    // the first page does not see the destroyed account, the second page is not overlaid
+    let first = &range.accounts[0];
+    assert_ne!(first.hashed_address, destroyed);
+    let (address, account, storage) =
+        accounts.iter().find(|(address, ..)| keccak256(address) == first.hashed_address).unwrap();
+    assert_eq!(first.address, Some(*address));
+    assert_eq!(first.account, *account);
+    assert_eq!(first.storage_root, storage_root(storage.clone().into_iter()));
+    assert_eq!(
+        first.storage.iter().map(|slot| (slot.key, slot.value)).collect::<Vec<_>>(),
+        storage.iter().map(|(key, value)| (Some(*key), *value)).collect::<Vec<_>>()
+    );
+
+    for entry in &remaining.accounts {
+        assert!(entry.storage.is_empty());
+        assert_ne!(entry.storage_root, EMPTY_ROOT_HASH);
+        assert_eq!(entry.address.map(keccak256), Some(entry.hashed_address));
+    }
+}
+
+#[test]
+fn account_range_without_preimage() {
+    let factory = create_test_provider_factory();
+    let tx = factory.provider_rw().unwrap();
+
+    let address = Address::with_last_byte(1);
+    insert_account(tx.tx_ref(), address, Account::default(), &BTreeMap::new());
+
+    // an account that only exists in the overlay has no known address
+    let hashed_address = B256::with_last_byte(2);
+    let mut post_state = HashedPostState::default();
+    post_state.accounts.insert(hashed_address, Some(Account { nonce: 1, ..Default::default() }));
+
+    let range =
+        StateRange::overlay_account_range(tx.tx_ref(), post_state, B256::ZERO, 10, false).unwrap();
+    let addresses = range
+        .accounts
+        .into_iter()
+        .map(|entry| (entry.hashed_address, entry.address))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(
+        addresses,
+        BTreeMap::from([(keccak256(address), Some(address)), (hashed_address, None)])
+    );
+}
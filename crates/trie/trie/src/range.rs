@@ -0,0 +1,184 @@
+use crate::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory},
+    prefix_set::TriePrefixSetsMut,
+    trie_cursor::TrieCursorFactory,
+    StorageRoot,
+};
+use alloy_primitives::{Address, B256, U256};
+use reth_execution_errors::StorageRootError;
+use reth_primitives::Account;
+use reth_storage_errors::db::DatabaseError;
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{TrieRootMetrics, TrieType};
+
+/// A page of hashed accounts, ordered by hashed address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashedAccountRange {
+    /// The accounts in the range.
+    pub accounts: Vec<HashedAccountEntry>,
+    /// The hashed address of the first account after the range, if any.
+    pub next_key: Option<B256>,
+}
+
+/// An account of a [`HashedAccountRange`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashedAccountEntry {
+    /// The hashed address of the account.
+    pub hashed_address: B256,
+    /// The address of the account, if its preimage is known.
+    pub address: Option<Address>,
+    /// The account info.
+    pub account: Account,
+    /// The storage root of the account.
+    pub storage_root: B256,
+    /// The storage slots of the account, empty unless requested.
+    pub storage: Vec<HashedStorageEntry>,
+}
+
+/// A page of the storage slots of an account, ordered by hashed slot.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HashedStorageRange {
+    /// The storage slots in the range.
+    pub slots: Vec<HashedStorageEntry>,
+    /// The hashed key of the first slot after the range, if any.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot of a [`HashedStorageRange`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HashedStorageEntry {
+    /// The hashed key of the slot.
+    pub hashed_key: B256,
+    /// The key of the slot, if its preimage is known.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: U256,
+}
+
+/// A struct for paginating over the hashed state.
+///
+/// Accounts and storage slots are returned in order of their hashed keys, which is the order of
+/// the leaves in the state and storage tries.
+#[derive(Debug)]
+pub struct StateRange<T, H> {
+    /// The factory for traversing trie nodes.
+    trie_cursor_factory: T,
+    /// The factory for hashed cursors.
+    hashed_cursor_factory: H,
+    /// A set of prefix sets that have changes.
+    prefix_sets: TriePrefixSetsMut,
+}
+
+impl<T, H> StateRange<T, H> {
+    /// Create a new [`StateRange`] instance.
+    pub fn new(t: T, h: H) -> Self {
+        Self {
+            trie_cursor_factory: t,
+            hashed_cursor_factory: h,
+            prefix_sets: TriePrefixSetsMut::default(),
+        }
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> StateRange<T, HF> {
+        StateRange {
+            trie_cursor_factory: self.trie_cursor_factory,
+            hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
+    }
+
+    /// Set the prefix sets of the storage tries that have changes. They are required to compute
+    /// the storage roots of the accounts on top of a post state.
+    pub fn with_prefix_sets_mut(mut self, prefix_sets: TriePrefixSetsMut) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
+    }
+}
+
+impl<T, H> StateRange<T, H>
+where
+    T: TrieCursorFactory + Clone,
+    H: HashedCursorFactory + Clone,
+{
+    /// Returns up to `limit` accounts starting at the given hashed address.
+    ///
+    /// If `with_storage` is set, all storage slots of each account are returned as well.
+    pub fn account_range(
+        &self,
+        start: B256,
+        limit: usize,
+        with_storage: bool,
+    ) -> Result<HashedAccountRange, StorageRootError> {
+        let mut cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let mut accounts = Vec::new();
+
+        let mut entry = cursor.seek(start)?;
+        while let Some((hashed_address, account)) = entry {
+            if accounts.len() >= limit {
+                return Ok(HashedAccountRange { accounts, next_key: Some(hashed_address) })
+            }
+
+            let storage = if with_storage {
+                self.storage_range(hashed_address, B256::ZERO, usize::MAX)?.slots
+            } else {
+                Vec::new()
+            };
+            accounts.push(HashedAccountEntry {
+                hashed_address,
+                address: None,
+                account,
+                storage_root: self.storage_root(hashed_address)?,
+                storage,
+            });
+
+            entry = cursor.next()?;
+        }
+
+        Ok(HashedAccountRange { accounts, next_key: None })
+    }
+
+    /// Returns up to `limit` storage slots of the given account starting at the given hashed
+    /// slot.
+    pub fn storage_range(
+        &self,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedStorageRange, DatabaseError> {
+        let mut cursor = self.hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+        let mut slots = Vec::new();
+
+        let mut entry = cursor.seek(start)?;
+        while let Some((hashed_key, value)) = entry {
+            if slots.len() >= limit {
+                return Ok(HashedStorageRange { slots, next_key: Some(hashed_key) })
+            }
+            slots.push(HashedStorageEntry { hashed_key, key: None, value });
+            entry = cursor.next()?;
+        }
+
+        Ok(HashedStorageRange { slots, next_key: None })
+    }
+
+    /// Computes the storage root of the given account.
+    fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
+        let prefix_set = self
+            .prefix_sets
+            .storage_prefix_sets
+            .get(&hashed_address)
+            .cloned()
+            .unwrap_or_default()
+            .freeze();
+        StorageRoot::new_hashed(
+            self.trie_cursor_factory.clone(),
+            self.hashed_cursor_factory.clone(),
+            hashed_address,
+            #[cfg(feature = "metrics")]
+            TrieRootMetrics::new(TrieType::Storage),
+        )
+        .with_prefix_set(prefix_set)
+        .root()
+    }
+}